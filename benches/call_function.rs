@@ -0,0 +1,16 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use lavendeux_parser::Lavendeux;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    // Dominated by function calls; fib(18) makes over 8,000 of them
+    let mut lav = Lavendeux::new();
+    let (profile, bytecode) = lav
+        .compile("fib(n) = n < 2 ? n : fib(n - 1) + fib(n - 2)\nfib(18)")
+        .unwrap();
+    c.bench_function("Recursive calls", |b| {
+        b.iter(|| lav.execute(black_box(bytecode.clone()), Some(profile.clone())))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
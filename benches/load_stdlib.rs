@@ -0,0 +1,24 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use lavendeux_parser::{traits::SerializeToBytes, value::StdFunctionSet, Lavendeux};
+
+const STDLIB: &[u8] = include_bytes!("../stdlib/stdlib.lbc");
+
+fn criterion_benchmark(c: &mut Criterion) {
+    c.bench_function("Decode stdlib", |b| {
+        b.iter(|| StdFunctionSet::from_bytes(black_box(STDLIB)))
+    });
+
+    c.bench_function("Startup", |b| b.iter(Lavendeux::new));
+
+    // Decodes an operand for most instructions it runs
+    let mut lav = Lavendeux::new();
+    let (profile, bytecode) = lav
+        .compile("s = 0\nfor i in 0..10000 { s = s + i * 2 }")
+        .unwrap();
+    c.bench_function("Run loop", |b| {
+        b.iter(|| lav.execute(black_box(bytecode.clone()), Some(profile.clone())))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use lavendeux_parser::{compiler::CompilerOptions, Lavendeux};
+
+/// Dominated by operator expressions, which the register backend compiles differently
+const ARITHMETIC: &str = "\
+    f(x) = (x * x + 3 * x - 7) % 11 + -(x & 5) * (x | 3) - (x << 2) / (x + 1)\n\
+    for i in 0..2000 { f(i) }";
+
+/// Compare the stack and register backends on the same script
+fn compare_backends(c: &mut Criterion, name: &str, source: &str) {
+    let mut group = c.benchmark_group(name);
+    for (backend, registers) in [("stack", false), ("registers", true)] {
+        let mut lav = Lavendeux::with_options(CompilerOptions {
+            registers,
+            ..Default::default()
+        });
+        let (profile, bytecode) = lav.compile(source).unwrap();
+        group.bench_function(backend, |b| {
+            b.iter(|| {
+                lav.execute(black_box(bytecode.clone()), Some(profile.clone()))
+                    .ok()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    compare_backends(c, "Arithmetic", ARITHMETIC);
+
+    // Every example script that runs to completion
+    let mut scripts = std::fs::read_dir("example_scripts")
+        .unwrap()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lav"))
+        .collect::<Vec<_>>();
+    scripts.sort();
+
+    for script in scripts {
+        let source = std::fs::read_to_string(&script).unwrap();
+        if Lavendeux::new().run(&source).is_err() {
+            continue;
+        }
+
+        let name = script.file_name().unwrap().to_string_lossy();
+        compare_backends(c, &name, &source);
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
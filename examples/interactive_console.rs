@@ -1,9 +1,14 @@
-use lavendeux_parser::{compiler::CompilerOptions, Lavendeux};
+use lavendeux_parser::{compiler::CompilerOptions, lexer::SourceMap, Lavendeux, Repl, ReplResult};
 
 const MIN_STACK_SIZE: usize = 32 * 1024 * 1024;
 
 fn main() {
-    stacker::grow(MIN_STACK_SIZE, || interactive_compiler())
+    std::thread::Builder::new()
+        .stack_size(MIN_STACK_SIZE)
+        .spawn(interactive_compiler)
+        .expect("Could not start the console")
+        .join()
+        .expect("The console panicked");
 }
 
 fn interactive_compiler() {
@@ -15,49 +20,48 @@ fn interactive_compiler() {
         stack.insert(0, "exit".to_string());
     }
 
-    let mut lavendeux = Lavendeux::with_options(CompilerOptions {
+    let mut repl = Repl::with_lavendeux(Lavendeux::with_options(CompilerOptions {
         allow_syscalld: true,
         ..Default::default()
-    });
+    }));
     loop {
         // Make sure we have a command ready
         if stack.is_empty() {
-            stack.push(next_command());
+            stack.push(next_command(repl.prompt()));
         }
         let cmd = stack.pop().unwrap();
 
-        if cmd.is_empty() {
-            continue;
-        } else if ["exit", "quit"].contains(&cmd.as_str()) {
+        if ["exit", "quit"].contains(&cmd.trim()) {
             break;
-        } else {
-            // Process the next command
-            let input = cmd.as_str();
-            match lavendeux.run(input) {
-                Ok(value) => {
-                    println!("{}\n", value);
-                }
-                Err(e) => {
-                    eprintln!("{}\n", e);
+        }
+
+        // Process the next line; entries left open continue on the next one
+        match repl.feed(&cmd) {
+            ReplResult::Incomplete => {}
+            ReplResult::Complete(outputs) => {
+                for output in outputs {
+                    println!("{}", output);
                 }
             }
+            ReplResult::Failed { source, error } => {
+                eprintln!("{}\n", error.render(&SourceMap::new(&source)));
+            }
         }
     }
 }
 
-fn next_command() -> String {
+fn next_command(prompt: &str) -> String {
     let mut input = String::new();
-    print!("> ");
+    print!("{prompt}");
     let _ = std::io::Write::flush(&mut std::io::stdout());
 
-    loop {
-        std::io::stdin()
-            .read_line(&mut input)
-            .expect("error: unable to read user input");
-        if !input.trim().ends_with('\\') || input.trim().ends_with("\\\\") {
-            break;
-        }
-    }
+    let read = std::io::stdin()
+        .read_line(&mut input)
+        .expect("error: unable to read user input");
 
-    return input.trim().to_string();
+    // End of input
+    if read == 0 {
+        return "exit".to_string();
+    }
+    input.trim_end_matches(['\r', '\n']).to_string()
 }
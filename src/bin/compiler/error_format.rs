@@ -0,0 +1,69 @@
+use lavendeux_parser::{diagnostic::Diagnostic, lexer::SourceMap, Error};
+use serde_json::{json, Value};
+
+use crate::options::{CliOptions, ErrorFormat};
+
+/// Format an error for output, according to the selected error format
+pub fn format_error(options: &CliOptions, error: Error) -> String {
+    let source = SourceMap::new(options.src());
+    match options.error_format() {
+        ErrorFormat::Human => error.render(&source),
+        ErrorFormat::Json => {
+            let diagnostic = error.diagnostic();
+            let mut value = diagnostic_json(options, &source, "error", &diagnostic);
+            value["context"] = diagnostic
+                .context
+                .iter()
+                .map(|d| diagnostic_json(options, &source, "note", d))
+                .collect();
+            value.to_string()
+        }
+    }
+}
+
+/// Convert a diagnostic into a JSON object
+fn diagnostic_json(
+    options: &CliOptions,
+    source: &SourceMap<'_>,
+    severity: &str,
+    diagnostic: &Diagnostic,
+) -> Value {
+    let (file, span) = match &diagnostic.token {
+        Some(token) => {
+            let own_map;
+            let map = if token.filename().is_none() && token.input() == source.input() {
+                source
+            } else {
+                own_map = token.source_map();
+                &own_map
+            };
+
+            let span = token.span();
+            let (line, column) = map.line_col(span.start);
+            let (end_line, end_column) = map.line_col(span.end);
+            let file = token.filename().or(options.filename());
+            (
+                json!(file),
+                json!({
+                    "start": span.start,
+                    "end": span.end,
+                    "line": line,
+                    "column": column,
+                    "end_line": end_line,
+                    "end_column": end_column,
+                }),
+            )
+        }
+
+        None => (json!(options.filename()), Value::Null),
+    };
+
+    json!({
+        "severity": severity,
+        "code": diagnostic.code,
+        "message": diagnostic.message,
+        "notes": diagnostic.notes,
+        "file": file,
+        "span": span,
+    })
+}
@@ -1,78 +1,138 @@
 use lavendeux_parser::{
-    compiler::{asm_transcoder::ASMTranscoder, Compiler, CompilerOptions},
+    compiler::{
+        asm_transcoder::ASMTranscoder, assembler, Artifact, Compiler, CompilerOptions, DebugProfile,
+    },
     lexer::{Lexer, Stack, Token},
-    parser::{self, Node},
+    parser::{self, Node, ParserOptions},
     traits::SerializeToBytes,
-    value::StdFunctionSet,
-    vm::VirtualMachine,
+    value::{CollisionPolicy, StdFunctionSet, Value},
+    vm::{ReplayLog, WriteTracer},
+    Error, Lavendeux,
 };
 
 mod options;
 use options::{CliOptions, CompilerMode};
 
+mod error_format;
+
 fn main() {
     let options = CliOptions::new(std::env::args().skip(1).collect());
+    if options.watch {
+        watch(options);
+    } else if !exec_timed(&options) {
+        std::process::exit(1);
+    }
+}
+
+/// Run the selected mode, printing the time taken or the error
+/// Returns false if an error occurred
+fn exec_timed(options: &CliOptions) -> bool {
     let t = std::time::Instant::now();
     match exec_mode(options) {
         Ok(_) => {
             println!("Finished in {:?}", t.elapsed());
+            true
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            false
+        }
+    }
+}
+
+/// Run the selected mode every time the input file changes
+fn watch(mut options: CliOptions) -> ! {
+    let filename = options.filename().unwrap_or_default().to_string();
+    let modified = |filename: &str| std::fs::metadata(filename).and_then(|m| m.modified()).ok();
+
+    let mut last_modified = modified(&filename);
+    exec_timed(&options);
+    println!("Watching {filename} for changes...");
+
+    loop {
+        std::thread::sleep(std::time::Duration::from_millis(250));
+        let m = modified(&filename);
+        if m == last_modified {
+            continue;
         }
-        Err(e) => eprintln!("{}", e),
+        last_modified = m;
+
+        println!();
+        match options.reload() {
+            Ok(_) => {
+                exec_timed(&options);
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+        println!("Watching {filename} for changes...");
     }
 }
 
-fn exec_mode(options: CliOptions) -> Result<(), String> {
-    let tokens = Lexer::new(options.src())
-        .all_tokens()
-        .or_else(|e| Err(e.to_string()))?;
+fn exec_mode(options: &CliOptions) -> Result<(), String> {
+    if options.lasm {
+        return exec_lasm(options);
+    }
+
+    // Compiled input has no source to lex
+    let tokens = match options.mode().reads_bytecode() {
+        true => vec![],
+        false => Lexer::new(options.src())
+            .all_tokens()
+            .map_err(|e| render_err(options, e))?,
+    };
+
     match options.mode() {
         CompilerMode::Lexer => {
             let tokens = tokens
                 .into_iter()
                 .map(|t| format!("{t:?}\n"))
                 .collect::<String>();
-            output_str(&options, &tokens)?;
+            output_str(options, &tokens)?;
         }
         CompilerMode::ASTDump => {
-            let ast = build_ast(tokens)?;
-            output_str(&options, &format!("{:#?}", ast))?;
+            let ast = build_ast(options, tokens)?;
+            output_str(options, &format!("{:#?}", ast))?;
         }
         CompilerMode::Compiler => {
-            let (profile, bytecode) = compile_bytecode(&options, tokens)?.decompose();
-            output_bin(&options, bytecode)?;
+            let (profile, bytecode) = compile_bytecode(options, tokens)?.decompose();
+            if options.embed_source {
+                let artifact = Artifact::new(bytecode.clone(), profile.clone());
+                output_bin(options, artifact.serialize_into_bytes())?;
+            } else if !options.run || options.output().is_some() {
+                output_bin(options, bytecode.clone())?;
+            }
 
             match options.debug_path() {
                 Some(debug_path) if !debug_path.is_empty() => {
-                    let profile = profile.serialize_into_bytes();
+                    let profile = profile.clone().serialize_into_bytes();
                     write_bin(debug_path, profile)?;
                 }
 
                 _ => {}
             }
+
+            if options.run {
+                run_bytecode(options, bytecode, Some(profile))?;
+            }
         }
 
         CompilerMode::Assembly => {
-            let asm = transcode_asm(&options, tokens)?;
-            output_str(&options, &asm)?;
+            let asm = transcode_asm(options, tokens)?;
+            output_str(options, &asm)?;
         }
 
         CompilerMode::Functions => {
-            let (profile, bytecode) = compile_bytecode(&options, tokens)?.decompose();
+            let (profile, bytecode) = compile_bytecode(options, tokens)?.decompose();
             let profile = match options.debug_path() {
                 Some(_) => Some(profile),
                 None => None,
             };
 
             // Run the bytecode to gather the functions into memory
-            let mut context = VirtualMachine::new();
-            if let Err(e) = context.run(bytecode, profile.clone()) {
-                return Err(format!("{}", e));
-            }
-
-            let mem = context.destroy();
-            let functions = StdFunctionSet::from_mem(&mem);
+            let functions = StdFunctionSet::from_bytecode(bytecode, profile.clone())
+                .map_err(|e| render_err(options, e))?;
             let bytes = functions.serialize_into_bytes();
-            output_bin(&options, bytes)?;
+            output_bin(options, bytes)?;
 
             match options.debug_path() {
                 Some(debug_path) if !debug_path.is_empty() => {
@@ -83,6 +143,18 @@ fn exec_mode(options: CliOptions) -> Result<(), String> {
                 _ => {}
             }
         }
+
+        CompilerMode::Disassemble => disassemble(options)?,
+        CompilerMode::FunctionsDump => dump_functions(options)?,
+        CompilerMode::Precedence => dump_precedence(options)?,
+        CompilerMode::Grammar => {
+            let grammar = parser::grammar_ebnf().map_err(|e| e.to_string())?;
+            output_str(options, &grammar)?;
+        }
+        CompilerMode::Railroad => {
+            let html = parser::grammar_railroad_html().map_err(|e| e.to_string())?;
+            output_str(options, &html)?;
+        }
     }
 
     Ok(())
@@ -129,24 +201,197 @@ fn write_out(filename: &str, contents: &str) -> Result<(), String> {
     write_bin(filename, contents.as_bytes().to_vec())
 }
 
-fn build_ast(tokens: Vec<Token>) -> Result<Node, String> {
+fn render_err(options: &CliOptions, e: impl Into<Error>) -> String {
+    error_format::format_error(options, e.into())
+}
+
+/// Assemble the input, then run the selected mode on the bytecode
+fn exec_lasm(options: &CliOptions) -> Result<(), String> {
+    let bytecode = assembler::assemble(options.src()).map_err(|e| e.to_string())?;
+    match options.mode() {
+        CompilerMode::Compiler => {
+            if !options.run || options.output().is_some() {
+                output_bin(options, bytecode.clone())?;
+            }
+
+            if options.run {
+                run_bytecode(options, bytecode, None)?;
+            }
+
+            Ok(())
+        }
+
+        CompilerMode::Assembly => {
+            let transcoder = ASMTranscoder::new(&bytecode, None);
+            output_str(options, &transcoder.disassemble_as_string())
+        }
+
+        mode => Err(format!("{mode:?} mode is not supported for assembly input")),
+    }
+}
+
+fn disassemble(options: &CliOptions) -> Result<(), String> {
+    // Artifacts carry their own debug profile
+    if Artifact::is_artifact(options.bytecode()) {
+        let artifact = Artifact::from_bytes(options.bytecode())
+            .map_err(|e| format!("Invalid artifact: {}", e))?;
+        let transcoder = ASMTranscoder::new(&artifact.bytecode, Some(artifact.debug));
+        return output_str(options, &transcoder.disassemble_as_string());
+    }
+
+    let profile = match options.debug_path() {
+        Some(path) if !path.is_empty() => {
+            let bytes = std::fs::read(path).map_err(|e| format!("Error reading file: {}", e))?;
+            let profile = DebugProfile::from_bytes(&bytes)
+                .map_err(|e| format!("Invalid debug profile: {}", e))?;
+            Some(profile)
+        }
+        _ => None,
+    };
+
+    let transcoder = ASMTranscoder::new(options.bytecode(), profile);
+    output_str(options, &transcoder.disassemble_as_string())
+}
+
+fn dump_functions(options: &CliOptions) -> Result<(), String> {
+    let functions = StdFunctionSet::from_bytes(options.bytecode())
+        .map_err(|e| format!("Invalid function set: {}", e))?;
+
+    let mut functions = functions.functions().iter().collect::<Vec<_>>();
+    functions
+        .sort_by(|a, b| (&a.docs.category, &a.docs.name).cmp(&(&b.docs.category, &b.docs.name)));
+
+    let mut output = String::new();
+    let mut category = None;
+    for function in functions {
+        if category != Some(&function.docs.category) {
+            category = Some(&function.docs.category);
+            output.push_str(&format!("# {}\n", function.docs.category));
+        }
+
+        output.push_str(&format!(
+            "{} [{} bytes]\n",
+            function.docs.signature,
+            function.body.len()
+        ));
+        if let Some(short) = &function.docs.short {
+            output.push_str(&format!("    {}\n", short));
+        }
+    }
+
+    output_str(options, &output)
+}
+
+fn dump_precedence(options: &CliOptions) -> Result<(), String> {
+    let mut output = String::new();
+    for group in parser::precedence_table().chunk_by(|a, b| a.level == b.level) {
+        let symbols = group.iter().map(|op| op.symbol()).collect::<Vec<_>>();
+        output.push_str(&format!(
+            "{:>2} {:<8} {}\n",
+            group[0].level,
+            group[0].associativity,
+            symbols.join(" ")
+        ));
+    }
+
+    output_str(options, &output)
+}
+
+fn run_bytecode(
+    options: &CliOptions,
+    bytecode: Vec<u8>,
+    profile: Option<DebugProfile>,
+) -> Result<(), String> {
+    let mut lav = Lavendeux::new();
+    lav.set_file_access(options.allow_file_access);
+    for path in options.libraries() {
+        lav.load_library(path, CollisionPolicy::Error)
+            .map_err(|e| format!("{path}: {e}"))?;
+    }
+
+    if let Some(path) = options.replay_path() {
+        let bytes = std::fs::read(path).map_err(|e| format!("Error reading replay log: {e}"))?;
+        let log =
+            ReplayLog::from_bytes(&bytes).map_err(|e| format!("Error reading replay log: {e}"))?;
+        lav.replay(log);
+    } else if options.record_path().is_some() {
+        lav.record_replay();
+    }
+
+    match options.trace_path() {
+        Some("-") => {
+            lav.set_tracer(Some(Box::new(WriteTracer(std::io::stderr()))));
+        }
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .map_err(|e| format!("Error creating trace file: {e}"))?;
+            lav.set_tracer(Some(Box::new(WriteTracer(std::io::BufWriter::new(file)))));
+        }
+        None => {}
+    }
+
+    let value = lav.execute(bytecode, profile);
+
+    // Keep the log even if the program failed, since that is when it is most useful
+    if let (Some(path), Some(log)) = (options.record_path(), lav.take_replay_log()) {
+        std::fs::write(path, log.serialize_into_bytes())
+            .map_err(|e| format!("Error writing replay log: {e}"))?;
+    }
+    let value = value.map_err(|e| render_err(options, e))?;
+
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                println!("{}", value);
+            }
+        }
+        value => println!("{}", value),
+    }
+
+    Ok(())
+}
+
+fn build_ast<'source>(
+    options: &CliOptions,
+    tokens: Vec<Token<'source>>,
+) -> Result<Node<'source>, String> {
     let stack = Stack::new(tokens);
-    parser::build_ast(stack).or_else(|e| Err(e.to_string()))
+    parser::build_ast(stack, ParserOptions::default()).map_err(|e| render_err(options, e))
 }
 
 fn compile_bytecode<'source>(
     options: &'source CliOptions,
     tokens: Vec<Token<'source>>,
 ) -> Result<Compiler, String> {
-    let ast = build_ast(tokens)?;
+    let ast = build_ast(options, tokens)?;
     let mut compiler = Compiler::new(
         options.src(),
         CompilerOptions {
             allow_syscalld: options.allow_syscalld,
-            debug: options.debug_path().is_some(),
+            debug: options.debug_path().is_some() || options.run || options.embed_source,
+            optimize: options.optimize,
+            typecheck: options.typecheck,
+            registers: options.registers,
+            namespace: options.namespace(),
+            ..Default::default()
         },
     );
-    ast.compile(&mut compiler).or_else(|e| Err(e.to_string()))?;
+    ast.compile(&mut compiler)
+        .map_err(|e| render_err(options, e))?;
+    for warning in compiler.warnings() {
+        eprintln!("warning: {warning}");
+    }
+
+    // Function bodies are optimized as they are compiled, before the rest of the script
+    let before = compiler.len() + compiler.peephole_stats().bytes_removed;
+    compiler.optimize();
+
+    if options.opt_stats {
+        let stats = compiler.peephole_stats();
+        let after = compiler.len();
+        eprintln!("Optimizer: {before} -> {after} bytes; {stats}");
+    }
+
     Ok(compiler)
 }
 
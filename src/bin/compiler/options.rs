@@ -1,3 +1,5 @@
+use std::io::Read;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompilerMode {
     Lexer,
@@ -5,16 +7,51 @@ pub enum CompilerMode {
     Compiler,
     Assembly,
     Functions,
+    Disassemble,
+    FunctionsDump,
+    Precedence,
+    Grammar,
+    Railroad,
+}
+
+impl CompilerMode {
+    /// Returns true if the mode reads compiled bytecode instead of source code
+    pub fn reads_bytecode(&self) -> bool {
+        matches!(self, Self::Disassemble | Self::FunctionsDump)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    Human,
+    Json,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CliOptions {
     mode: CompilerMode,
     src: String,
+    bytecode: Vec<u8>,
+    filename: Option<String>,
+    error_format: ErrorFormat,
     output: Option<String>,
     debug_path: Option<String>,
+    record_path: Option<String>,
+    replay_path: Option<String>,
+    trace_path: Option<String>,
+    libraries: Vec<String>,
+    namespace: Option<String>,
 
     pub allow_syscalld: bool,
+    pub allow_file_access: bool,
+    pub watch: bool,
+    pub run: bool,
+    pub lasm: bool,
+    pub optimize: bool,
+    pub opt_stats: bool,
+    pub typecheck: bool,
+    pub registers: bool,
+    pub embed_source: bool,
 }
 
 impl CliOptions {
@@ -26,6 +63,18 @@ impl CliOptions {
         &self.src
     }
 
+    pub fn bytecode(&self) -> &[u8] {
+        &self.bytecode
+    }
+
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
+
+    pub fn error_format(&self) -> ErrorFormat {
+        self.error_format
+    }
+
     pub fn debug_path(&self) -> Option<&str> {
         self.debug_path.as_deref()
     }
@@ -34,15 +83,95 @@ impl CliOptions {
         self.output.as_deref()
     }
 
+    pub fn record_path(&self) -> Option<&str> {
+        self.record_path.as_deref()
+    }
+
+    pub fn replay_path(&self) -> Option<&str> {
+        self.replay_path.as_deref()
+    }
+
+    pub fn trace_path(&self) -> Option<&str> {
+        self.trace_path.as_deref()
+    }
+
+    pub fn libraries(&self) -> &[String] {
+        &self.libraries
+    }
+
+    /// The namespace functions are defined in; set with --namespace, or named after the
+    /// input file when compiling a library
+    pub fn namespace(&self) -> Option<String> {
+        match (&self.namespace, self.mode, self.filename()) {
+            (Some(namespace), _, _) => Some(namespace.clone()),
+            (None, CompilerMode::Functions, Some(filename)) if filename != "-" => {
+                let stem = std::path::Path::new(filename).file_stem()?;
+                Some(stem.to_string_lossy().to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Re-read the source from the input file, if there is one
+    pub fn reload(&mut self) -> Result<(), String> {
+        match self.filename.clone() {
+            Some(filename) => self.read_input(&filename),
+            None => Ok(()),
+        }
+    }
+
+    /// Read the input from a file, or from stdin if the filename is `-`
+    fn read_input(&mut self, filename: &str) -> Result<(), String> {
+        let mut bytes = vec![];
+        match filename {
+            "-" => std::io::stdin()
+                .read_to_end(&mut bytes)
+                .map_err(|e| format!("Error reading from stdin: {}", e))?,
+            _ => {
+                bytes =
+                    std::fs::read(filename).map_err(|e| format!("Error reading file: {}", e))?;
+                bytes.len()
+            }
+        };
+
+        if self.mode.reads_bytecode() {
+            self.bytecode = bytes;
+        } else {
+            self.src = String::from_utf8(bytes).map_err(|_| {
+                "Input is not valid UTF-8; use --disassemble for compiled files".to_string()
+            })?;
+        }
+
+        Ok(())
+    }
+
     pub fn new(args: Vec<String>) -> Self {
         let mut options = CliOptions {
             mode: CompilerMode::Compiler,
             src: String::new(),
+            bytecode: vec![],
+            filename: None,
+            error_format: ErrorFormat::Human,
             output: None,
             debug_path: None,
+            record_path: None,
+            replay_path: None,
+            trace_path: None,
+            libraries: vec![],
+            namespace: None,
 
             allow_syscalld: false,
+            allow_file_access: false,
+            watch: false,
+            run: false,
+            lasm: false,
+            optimize: true,
+            opt_stats: false,
+            typecheck: false,
+            registers: false,
+            embed_source: false,
         };
+        let mut input = None;
         let mut iter = args.into_iter();
         loop {
             let arg = match iter.next() {
@@ -55,7 +184,12 @@ impl CliOptions {
                 "-c" | "--compiler" => options.mode = CompilerMode::Compiler,
                 "-a" | "--asm" => options.mode = CompilerMode::Assembly,
                 "-A" | "--ast-dump" => options.mode = CompilerMode::ASTDump,
-                "-F" | "--functions" => options.mode = CompilerMode::Functions,
+                "-F" | "--functions" | "--lib" => options.mode = CompilerMode::Functions,
+                "-X" | "--disassemble" => options.mode = CompilerMode::Disassemble,
+                "--functions-dump" => options.mode = CompilerMode::FunctionsDump,
+                "--precedence" => options.mode = CompilerMode::Precedence,
+                "--grammar" => options.mode = CompilerMode::Grammar,
+                "--railroad" => options.mode = CompilerMode::Railroad,
 
                 "-f" | "--file" => {
                     let filename = match iter.next() {
@@ -66,17 +200,11 @@ impl CliOptions {
                         }
                     };
 
-                    let src = match std::fs::read_to_string(&filename) {
-                        Ok(src) => src,
-                        Err(err) => {
-                            println!("Error reading file: {}", err);
-                            std::process::exit(1);
-                        }
-                    };
-
-                    options.src = src;
+                    input = Some(filename);
                 }
 
+                "-" => input = Some("-".to_string()),
+
                 "-i" | "--input" => {
                     options.src = match iter.next() {
                         Some(src) => src,
@@ -107,11 +235,84 @@ impl CliOptions {
                     }
                 }
 
+                "--record" => {
+                    options.record_path = match iter.next() {
+                        Some(path) => Some(path),
+                        None => {
+                            println!("Expected replay log path following --record");
+                            std::process::exit(1);
+                        }
+                    }
+                }
+
+                "--replay" => {
+                    options.replay_path = match iter.next() {
+                        Some(path) => Some(path),
+                        None => {
+                            println!("Expected replay log path following --replay");
+                            std::process::exit(1);
+                        }
+                    }
+                }
+
+                "--trace" => {
+                    options.trace_path = match iter.next() {
+                        Some(path) => Some(path),
+                        None => {
+                            println!("Expected trace output path following --trace");
+                            std::process::exit(1);
+                        }
+                    }
+                }
+
+                "-L" | "--library" => match iter.next() {
+                    Some(path) => options.libraries.push(path),
+                    None => {
+                        println!("Expected library path following -L/--library");
+                        std::process::exit(1);
+                    }
+                },
+
+                "--namespace" => {
+                    options.namespace = match iter.next() {
+                        Some(namespace) => Some(namespace),
+                        None => {
+                            println!("Expected namespace following --namespace");
+                            std::process::exit(1);
+                        }
+                    }
+                }
+
                 "-D" | "--debug-functions" => {
                     options.debug_path = Some(String::new());
                 }
 
                 "--allow-syscalld" => options.allow_syscalld = true,
+                "--allow-file-access" => options.allow_file_access = true,
+                "-w" | "--watch" => options.watch = true,
+                "-r" | "--run" => options.run = true,
+                "--lasm" => options.lasm = true,
+                "--no-optimize" => options.optimize = false,
+                "--opt-stats" => options.opt_stats = true,
+                "--typecheck" => options.typecheck = true,
+                "--registers" => options.registers = true,
+                "--embed-source" => options.embed_source = true,
+
+                _ if arg == "--error-format" || arg.starts_with("--error-format=") => {
+                    let format = match arg.split_once('=') {
+                        Some((_, format)) => Some(format.to_string()),
+                        None => iter.next(),
+                    };
+
+                    options.error_format = match format.as_deref() {
+                        Some("human") => ErrorFormat::Human,
+                        Some("json") => ErrorFormat::Json,
+                        _ => {
+                            println!("Expected `human` or `json` following --error-format");
+                            std::process::exit(1);
+                        }
+                    }
+                }
 
                 "-h" | "--help" => {
                     println!(
@@ -130,17 +331,47 @@ Operational Modes (mutually exclusive):
   -c, --compiler: Run the compiler
   -a, --asm: Run the assembly transcoder
   -A, --ast-dump: Run the AST dumper
-  -F, --functions: Compile and dump functions
+  -F, --functions, --lib: Compile and dump functions, as a library that can be loaded with -L
+  -X, --disassemble: Disassemble compiled bytecode, such as a .lbc file written by -c
+      Artifacts written with --embed-source are shown with their source
+  --functions-dump: List the functions in a compiled function set, such as a .lbc file written by -F
+  --precedence: List the operators, from lowest to highest precedence
+  --grammar: Write the grammar documented by the parser, as EBNF
+  --railroad: Write the grammar documented by the parser, as an HTML page of railroad diagrams
 
 Input/Output Options:
   -f, --file <filename>: Read input from file
+      Files ending in .lasm are read as assembly, see --lasm
+  -, -f -: Read input from stdin
   -i, --input <input>: Read input from command line
   -o, --output <output>: Set output filename
-  -d, --debug <path>: Set debug symbol output path
+  -d, --debug <path>: Set debug symbol output path (or input path, with --disassemble)
   -D, --debug-functions: Enable debug symbols, but don't output them (warning; only useful with -F)
+  --record <path>: With -r, write the random numbers and times the program used to a replay log
+  --replay <path>: With -r, give the program the random numbers and times from a replay log
+  --trace <path>: With -r, write each instruction executed, and the source it came from, to a file
+      Use - to write to stderr
+  -L, --library <path>: With -r, load the functions in a library written by --lib before running
+      Can be repeated; a function that is already defined by another namespace is an error
+  --namespace <name>: Set the namespace functions are defined in
+      Defaults to the name of the input file with --lib, and to the global namespace otherwise
+
+Error Options:
+  --error-format <human|json>: Set the format of error messages
+      json prints one diagnostic object per line, for use by build tools and editors
 
 Flags:
-  --allow-syscalld: Enables calls to __syscalld() in the compiler\
+  --allow-syscalld: Enables calls to __syscalld() in the compiler
+  --allow-file-access: Lets the program read files with read_file() when run with -r
+  -w, --watch: Recompile whenever the input file changes
+  -r, --run: Run the program after compiling it, and print the result
+  --lasm: Read the input as assembly, in the format written by -a
+      Only -c (with -r) and -a are supported for assembly input
+  --no-optimize: Disable the peephole optimizer and constant pool
+  --opt-stats: Print the bytecode size before and after the peephole optimizer
+  --typecheck: Report type errors that can be found before running, such as indexing into an integer
+  --registers: Compile operator expressions to register instructions instead of the stack (experimental)
+  --embed-source: With -c, write a single artifact holding the bytecode, debug symbols and source\
 "
                     );
                     std::process::exit(0);
@@ -153,6 +384,25 @@ Flags:
             }
         }
 
+        if let Some(filename) = input {
+            if filename.ends_with(".lasm") {
+                options.lasm = true;
+            }
+
+            if let Err(e) = options.read_input(&filename) {
+                println!("{}", e);
+                std::process::exit(1);
+            }
+            if filename != "-" {
+                options.filename = Some(filename);
+            }
+        }
+
+        if options.watch && options.filename.is_none() {
+            println!("--watch requires an input file, set with -f/--file");
+            std::process::exit(1);
+        }
+
         options
     }
 }
@@ -1,8 +1,12 @@
-use std::io::Read;
+use std::io::{IsTerminal, Read, Write};
 
-use lavendeux_parser::{value::Value, Lavendeux};
+use lavendeux_parser::{lexer::SourceMap, Lavendeux, Repl, ReplResult};
 
 fn main() {
+    if std::io::stdin().is_terminal() {
+        interactive();
+    }
+
     let mut lav = Lavendeux::new();
 
     // Read stdin until EOF
@@ -13,19 +17,46 @@ fn main() {
 
     // Parse the input
     println!("");
-    match lav.run(&buffer) {
-        Ok(v) => {
-            if let Value::Array(v) = v {
-                for v in v {
-                    println!("{}", v);
-                }
-            } else {
-                println!("{}", v);
+    match lav.run_output(&buffer) {
+        Ok(outputs) => {
+            for output in outputs {
+                println!("{}", output);
             }
         }
         Err(e) => {
-            eprintln!("{}", e);
+            eprintln!("{}", e.render(&SourceMap::new(buffer.as_str())));
             std::process::exit(1);
         }
     }
 }
+
+/// Read and run one entry at a time, until the end of input (Ctrl+D)
+fn interactive() -> ! {
+    let mut repl = Repl::new();
+    let mut line = String::new();
+    loop {
+        print!("{}", repl.prompt());
+        std::io::stdout().flush().ok();
+
+        line.clear();
+        match std::io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => {
+                println!();
+                std::process::exit(0);
+            }
+            Ok(_) => {}
+        }
+
+        match repl.feed(line.trim_end_matches(['\r', '\n'])) {
+            ReplResult::Incomplete => {}
+            ReplResult::Complete(outputs) => {
+                for output in outputs {
+                    println!("{}", output);
+                }
+            }
+            ReplResult::Failed { source, error } => {
+                eprintln!("{}", error.render(&SourceMap::new(source.as_str())));
+            }
+        }
+    }
+}
@@ -6,13 +6,41 @@
 mod compiler;
 pub use compiler::*;
 
+mod name_hash;
+pub use name_hash::{qualified_name, HashString};
+
 mod debug_profile;
 pub use debug_profile::DebugProfile;
 
 mod error;
-pub use error::CompilerError;
+pub use error::{CompilerError, CompilerWarning};
+
+mod cse;
+pub use cse::MAX_CSE_NODES;
+
+mod inlining;
+
+mod registers;
+
+mod peephole;
+pub use peephole::PeepholeStats;
+
+mod constant_pool;
+
+mod typecheck;
+
+mod lint;
+
+mod scopes;
 
 mod function_docs;
 pub use function_docs::FunctionDocs;
 
+mod artifact;
+pub use artifact::Artifact;
+
+mod builder;
+pub use builder::{BuilderError, BytecodeBuilder, JumpTarget};
+
 pub mod asm_transcoder;
+pub mod assembler;
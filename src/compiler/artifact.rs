@@ -0,0 +1,113 @@
+//! Artifacts, which bundle a compiled program with its debug profile and source code
+//! Errors and disassembly of an artifact can always show the original code, even when it is
+//! distributed on its own, without a separate debug file
+//!
+//! ```rust
+//! # use lavendeux_parser::{prelude::*, compiler::Artifact, traits::SerializeToBytes};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut lav = Lavendeux::new();
+//! let (profile, bytecode) = lav.compile("x = 5\nx * 2")?;
+//! let bytes = Artifact::new(bytecode, profile).serialize_into_bytes();
+//!
+//! let artifact = Artifact::from_bytes(&bytes)?;
+//! assert_eq!(artifact.debug.source(), "x = 5\nx * 2");
+//!
+//! let (profile, bytecode) = artifact.decompose();
+//! assert_eq!(lav.execute(bytecode, Some(profile))?.to_string(), "[5, 10]");
+//! # Ok(())
+//! # }
+//! ```
+use super::DebugProfile;
+use crate::{
+    traits::{ByteDecodeError, ByteReader, SerializeToBytes},
+    vm::OPCODE_SET_VERSION,
+};
+
+/// Magic bytes at the start of a serialized artifact
+const ARTIFACT_MAGIC: [u8; 4] = *b"LVA\0";
+
+/// A compiled program, bundled with its debug profile, which holds the source it was compiled from
+#[derive(Debug, Clone)]
+pub struct Artifact {
+    /// The compiled program
+    pub bytecode: Vec<u8>,
+
+    /// Maps the bytecode back to the source, and holds a copy of the source itself
+    pub debug: DebugProfile,
+}
+
+impl Artifact {
+    /// Bundle a compiled program with its debug profile
+    pub fn new(bytecode: Vec<u8>, debug: DebugProfile) -> Self {
+        Self { bytecode, debug }
+    }
+
+    /// Returns true if the bytes start like a serialized artifact, instead of plain bytecode
+    pub fn is_artifact(bytes: &[u8]) -> bool {
+        bytes.starts_with(&ARTIFACT_MAGIC)
+    }
+
+    /// Split the artifact into its components, in the order returned by `Lavendeux::compile`
+    pub fn decompose(self) -> (DebugProfile, Vec<u8>) {
+        (self.debug, self.bytecode)
+    }
+}
+
+/// Serialized as a header (magic bytes, opcode set version), followed by the bytecode and profile
+/// Artifacts compiled against a different opcode set are refused, and must be recompiled
+impl SerializeToBytes for Artifact {
+    fn serialize_into_bytes(self) -> Vec<u8> {
+        let mut bytes = ARTIFACT_MAGIC.to_vec();
+        bytes.extend(OPCODE_SET_VERSION.serialize_into_bytes());
+        bytes.extend(self.bytecode.serialize_into_bytes());
+        bytes.extend(self.debug.serialize_into_bytes());
+        bytes
+    }
+
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
+        let magic = bytes.read_slice(ARTIFACT_MAGIC.len(), "Artifact").ok();
+        if magic != Some(&ARTIFACT_MAGIC[..]) {
+            return Err(ByteDecodeError::MalformedData(
+                "Artifact".to_string(),
+                "Missing artifact header".to_string(),
+            ));
+        }
+
+        let version = u16::deserialize_from_bytes(bytes)?;
+        if version != OPCODE_SET_VERSION {
+            return Err(ByteDecodeError::MalformedData(
+                "Artifact".to_string(),
+                format!("Artifact uses opcode set v{version}, but this version expects v{OPCODE_SET_VERSION}; it must be recompiled"),
+            ));
+        }
+
+        Ok(Self {
+            bytecode: Vec::<u8>::deserialize_from_bytes(bytes)?,
+            debug: DebugProfile::deserialize_from_bytes(bytes)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_artifact() {
+        let (profile, bytecode) = Lavendeux::new().compile("x = 5\nx / 0").unwrap();
+        let bytes = Artifact::new(bytecode.clone(), profile).serialize_into_bytes();
+        assert!(Artifact::is_artifact(&bytes));
+        assert!(!Artifact::is_artifact(&bytecode));
+
+        // Errors from a loaded artifact still show the source it was compiled from
+        let (profile, bytecode) = Artifact::from_bytes(&bytes).unwrap().decompose();
+        assert_eq!(profile.source(), "x = 5\nx / 0");
+        let error = Lavendeux::new()
+            .execute(bytecode, Some(profile))
+            .unwrap_err();
+        assert!(error.to_string().contains("x / 0"), "{error}");
+
+        assert!(Artifact::from_bytes(&bytes[1..]).is_err());
+    }
+}
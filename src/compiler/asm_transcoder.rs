@@ -1,12 +1,13 @@
 //! Transcodes a bytecode buffer into a human-readable assembly-like format
 //! This is useful for debugging and testing purposes
 //!
-//! You can't actually reassemble the assembly back into bytecode yet
+//! The output can be reassembled into bytecode with the `assembler` module,
+//! as long as it does not define functions
 //! The compiler's `--asm` flag makes use of this module
 use crate::lexer::Token;
-use crate::traits::{IntoOwned, SerializeToBytes};
-use crate::value::{Function, Primitive, Value, ValueType};
-use crate::vm::OpCode;
+use crate::traits::{ByteReader, IntoOwned, SerializeToBytes};
+use crate::value::{Function, Primitive, StructType, Value, ValueType};
+use crate::vm::{jump_target, OpCode, CONSTANT_INDEX_SIZE, JUMP_SIZE};
 
 use super::DebugProfile;
 
@@ -32,14 +33,44 @@ pub enum Instruction {
     /// An instruction that pushes a value onto the stack
     Push(Value),
 
+    /// A superinstruction that pushes a value onto the stack, then operates on it
+    PushOp(OpCode, Value),
+
+    /// The constant pool at the start of the bytecode
+    Pool(Vec<Value>),
+
+    /// An instruction that pushes a value from the constant pool, with its index
+    PushConstant(u32, Value),
+
     /// An instruction that reads or writes to memory
     Mem(OpCode, u64),
 
+    /// An instruction that loads a value into a register
+    RegLoad(u8, Value),
+
+    /// An instruction that loads a variable into a register
+    RegVar(u8, u64),
+
+    /// An instruction that moves a value between a register and the stack
+    Reg(OpCode, u8),
+
+    /// A binary operation on registers, with the result and operand registers
+    RegBinary(OpCode, u8, u8, u8),
+
+    /// A unary operation on registers, with the result and operand registers
+    RegUnary(OpCode, u8, u8),
+
     /// An instruction that jumps to a position
     Jump(OpCode, u64),
 
+    /// A jump table, with the smallest key, default position, and positions for each key
+    JumpTable(i128, u64, Vec<u64>),
+
     /// An instruction that casts the top value on the stack to a different type
-    Cast(ValueType),
+    Cast(OpCode, ValueType),
+
+    /// An instruction that checks the top value on the stack against a structured type
+    Struct(OpCode, StructType),
 
     /// An instruction that accepts an N value (used for arrays and objects)
     AcceptsN(OpCode, u64),
@@ -55,6 +86,15 @@ pub enum Instruction {
     /// An instruction that calls a function
     FnCall(u64, u64),
 
+    /// An instruction that calls a method by name
+    MethodCall(String, u64),
+
+    /// An instruction that calls a function with an array of arguments
+    FnCallSpread(u64),
+
+    /// An instruction that calls a method by name with an array of arguments
+    MethodCallSpread(String),
+
     //
     // Meta instructions
     //
@@ -73,14 +113,15 @@ pub enum Instruction {
 
 /// A disassembler for bytecode buffers
 /// This struct is used to transcode a bytecode buffer into a human-readable assembly-like format
-/// This is a one-way operation; you can't reassemble the assembly back into bytecode
+/// See the `assembler` module to turn the output back into bytecode
 #[derive(Clone, Debug)]
 pub struct ASMTranscoder<'src> {
     instructions: Vec<(Instruction, usize)>,
     labels: LabelGun,
-    buffer: std::iter::Copied<std::slice::Iter<'src, u8>>,
+    buffer: ByteReader<'src>,
     debug_profile: Option<DebugProfile>,
     hashref: std::collections::HashMap<u64, String>,
+    constants: Vec<Value>,
 
     label_jumps: bool,
 }
@@ -91,9 +132,10 @@ impl<'src> ASMTranscoder<'src> {
         Self {
             instructions: Vec::new(),
             labels: LabelGun::new(),
-            buffer: buffer.iter().copied(),
+            buffer: ByteReader::new(buffer),
             debug_profile,
             hashref: std::collections::HashMap::new(),
+            constants: Vec::new(),
             label_jumps: true,
         }
     }
@@ -120,6 +162,21 @@ impl<'src> ASMTranscoder<'src> {
         self.instructions.iter().map(|(i, _)| i.clone()).collect()
     }
 
+    /// Decode the bytecode into instructions, with the length in bytes of each
+    /// Unlike `disassemble_as_vec`, no comments, labels or function bodies are added,
+    /// and jumps keep their offsets
+    pub fn decode(mut self) -> Result<Vec<(Instruction, usize)>, Error> {
+        self.all_instructions();
+        match self.instructions.pop() {
+            Some((Instruction::Error(e), _)) => Err(e),
+            Some(last) => {
+                self.instructions.push(last);
+                Ok(self.instructions)
+            }
+            None => Ok(vec![]),
+        }
+    }
+
     /// Perform the disassembly operation
     /// Returns a string containing the disassembled bytecode
     /// If an error occurs, it will appear in the output
@@ -137,20 +194,60 @@ impl<'src> ASMTranscoder<'src> {
             match instruction {
                 Instruction::Simple(opcode) => output.push_str(&format!("  {opcode:?}\n")),
                 Instruction::Push(value) => output.push_str(&format!("  PUSH {value:?}\n")),
+                Instruction::PushOp(opcode, value) => {
+                    output.push_str(&format!("  {opcode:?} {value:?}\n"))
+                }
+                Instruction::Pool(constants) => {
+                    output.push_str(&format!("  POOL {:08X}\n", constants.len()))
+                }
+                Instruction::PushConstant(_, value) => {
+                    output.push_str(&format!("  PSHC {value:?}\n"))
+                }
                 Instruction::Mem(opcode, hash) => {
-                    let label = if self.hashref.contains_key(hash) {
-                        self.hashref.get(hash).unwrap().clone()
-                    } else {
-                        let label = format!("VAR_{}", self.labels.next());
-                        self.hashref.insert(*hash, label.clone());
-                        label
-                    };
+                    let label = Self::var_label(
+                        &mut self.hashref,
+                        &mut self.labels,
+                        self.debug_profile.as_ref(),
+                        *hash,
+                    );
                     output.push_str(&format!("  {opcode:?} {label}\n"))
                 }
+                Instruction::RegLoad(reg, value) => {
+                    output.push_str(&format!("  RLDC r{reg} {value:?}\n"))
+                }
+                Instruction::RegVar(reg, hash) => {
+                    let label = Self::var_label(
+                        &mut self.hashref,
+                        &mut self.labels,
+                        self.debug_profile.as_ref(),
+                        *hash,
+                    );
+                    output.push_str(&format!("  RLDV r{reg} {label}\n"))
+                }
+                Instruction::Reg(opcode, reg) => output.push_str(&format!("  {opcode:?} r{reg}\n")),
+                Instruction::RegBinary(opcode, dst, a, b) => {
+                    output.push_str(&format!("  RBOP {opcode:?} r{dst} r{a} r{b}\n"))
+                }
+                Instruction::RegUnary(opcode, dst, a) => {
+                    output.push_str(&format!("  RUOP {opcode:?} r{dst} r{a}\n"))
+                }
                 Instruction::Jump(opcode, pos) => {
                     output.push_str(&format!("  {opcode:?} {pos:08X}\n"))
                 }
-                Instruction::Cast(type_name) => output.push_str(&format!("  CAST {type_name:?}\n")),
+                Instruction::JumpTable(min, default, table) => {
+                    let table = table
+                        .iter()
+                        .map(|pos| format!("{pos:08X}"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    output.push_str(&format!("  JTBL {min} {default:08X} [{table}]\n"))
+                }
+                Instruction::Cast(opcode, type_name) => {
+                    output.push_str(&format!("  {opcode:?} {type_name:?}\n"))
+                }
+                Instruction::Struct(opcode, struct_type) => {
+                    output.push_str(&format!("  {opcode:?} {struct_type}\n"))
+                }
                 Instruction::AcceptsN(opcode, n) => {
                     output.push_str(&format!("  {opcode:?} {n:08X}\n"))
                 }
@@ -163,9 +260,19 @@ impl<'src> ASMTranscoder<'src> {
                 Instruction::FnArg(idx) => output.push_str(&format!("  FDFT {idx:02X}\n")),
 
                 Instruction::FnCall(name_hash, n) => {
-                    output.push_str(&format!("  CALL {name_hash:08X} {n}\n"))
+                    output.push_str(&format!("  CALL #{name_hash:08X} {n}\n"))
                 }
 
+                Instruction::MethodCall(name, n) => {
+                    output.push_str(&format!("  CALM {name} {n}\n"))
+                }
+
+                Instruction::FnCallSpread(name_hash) => {
+                    output.push_str(&format!("  CALA #{name_hash:08X}\n"))
+                }
+
+                Instruction::MethodCallSpread(name) => output.push_str(&format!("  CLMA {name}\n")),
+
                 Instruction::Label(label) => output.push_str(&format!("{label}:\n")),
                 Instruction::JumpTo(opcode, label, pos) => {
                     if self.label_jumps {
@@ -192,6 +299,26 @@ impl<'src> ASMTranscoder<'src> {
         output
     }
 
+    /// Get the label for a variable hash, creating one the first time it is seen
+    /// The name is used if the debug profile knows it
+    fn var_label(
+        hashref: &mut std::collections::HashMap<u64, String>,
+        labels: &mut LabelGun,
+        profile: Option<&DebugProfile>,
+        hash: u64,
+    ) -> String {
+        if let Some(label) = hashref.get(&hash) {
+            return label.clone();
+        }
+
+        let label = match profile.and_then(|p| p.name(hash)) {
+            Some(name) => name.to_string(),
+            None => format!("VAR_{}", labels.next()),
+        };
+        hashref.insert(hash, label.clone());
+        label
+    }
+
     /// Break up the bytecode buffer into individual instructions
     fn all_instructions(&mut self) {
         let mut offset = 0;
@@ -299,6 +426,13 @@ impl<'src> ASMTranscoder<'src> {
     }
 
     /// Get the next opcode from the buffer
+    /// Read a relative jump, and resolve it to the absolute position it targets
+    fn next_jump(&mut self) -> Option<u64> {
+        let at = self.buffer.offset();
+        let offset = i32::deserialize_from_bytes(&mut self.buffer).ok()?;
+        jump_target(at, offset).map(|pos| pos as u64)
+    }
+
     fn next_opcode(&mut self) -> Option<(Instruction, usize)> {
         let opcode = self.buffer.next()?;
         let opcode = OpCode::from_u8(opcode)?;
@@ -313,28 +447,123 @@ impl<'src> ASMTranscoder<'src> {
                 Some((instruction, 1 + len))
             }
 
-            OpCode::JMP | OpCode::JMPT | OpCode::JMPF | OpCode::JMPE | OpCode::JMPNE => {
-                let pos = u64::deserialize_from_bytes(&mut self.buffer).ok()?;
+            OpCode::PADD | OpCode::PSUB => {
+                let len = self.buffer.len();
+                let value = Primitive::deserialize_from_bytes(&mut self.buffer).ok()?;
+                let value = Value::Primitive(value);
+                let len = len - self.buffer.len();
+                let instruction = Instruction::PushOp(opcode, value);
+                Some((instruction, 1 + len))
+            }
+
+            OpCode::POOL => {
+                let len = self.buffer.len();
+                let count = u64::deserialize_from_bytes(&mut self.buffer).ok()?;
+                let _size = u64::deserialize_from_bytes(&mut self.buffer).ok()?;
+                self.constants = (0..count)
+                    .map(|_| Primitive::deserialize_from_bytes(&mut self.buffer).ok())
+                    .map(|value| value.map(Value::Primitive))
+                    .collect::<Option<Vec<_>>>()?;
+                let len = len - self.buffer.len();
+                let instruction = Instruction::Pool(self.constants.clone());
+                Some((instruction, 1 + len))
+            }
+
+            OpCode::PSHC => {
+                let index = u32::deserialize_from_bytes(&mut self.buffer).ok()?;
+                let value = self.constants.get(index as usize)?.clone();
+                let instruction = Instruction::PushConstant(index, value);
+                Some((instruction, 1 + CONSTANT_INDEX_SIZE))
+            }
+
+            OpCode::JMP
+            | OpCode::JMPT
+            | OpCode::JMPF
+            | OpCode::JMPE
+            | OpCode::JMPNE
+            | OpCode::JMPNN
+            | OpCode::DJNN
+            | OpCode::DJNE => {
+                let pos = self.next_jump()?;
                 let instruction = Instruction::Jump(opcode, pos);
-                Some((instruction, 1 + 4))
+                Some((instruction, 1 + JUMP_SIZE))
             }
 
-            OpCode::REF => {
+            OpCode::JTBL => {
+                let min = i128::deserialize_from_bytes(&mut self.buffer).ok()?;
+                let count = u64::deserialize_from_bytes(&mut self.buffer).ok()?;
+                let default = self.next_jump()?;
+                let table = (0..count)
+                    .map(|_| self.next_jump())
+                    .collect::<Option<Vec<_>>>()?;
+                let instruction = Instruction::JumpTable(min, default, table);
+                Some((instruction, 1 + 16 + 8 + JUMP_SIZE * (1 + count as usize)))
+            }
+
+            OpCode::REF | OpCode::RIDX | OpCode::WRGL | OpCode::WRCN | OpCode::WRLC => {
                 let hash = u64::deserialize_from_bytes(&mut self.buffer).ok()?;
                 let instruction = Instruction::Mem(opcode, hash);
                 Some((instruction, 1 + 8))
             }
 
-            OpCode::CAST => {
+            OpCode::RLDC => {
+                let reg = self.buffer.next()?;
+                let len = self.buffer.len();
+                let value = Primitive::deserialize_from_bytes(&mut self.buffer).ok()?;
+                let value = Value::Primitive(value);
+                let len = len - self.buffer.len();
+                let instruction = Instruction::RegLoad(reg, value);
+                Some((instruction, 1 + 1 + len))
+            }
+
+            OpCode::RLDV => {
+                let reg = self.buffer.next()?;
+                let hash = u64::deserialize_from_bytes(&mut self.buffer).ok()?;
+                let instruction = Instruction::RegVar(reg, hash);
+                Some((instruction, 1 + 1 + 8))
+            }
+
+            OpCode::RPOP | OpCode::RPSH => {
+                let reg = self.buffer.next()?;
+                let instruction = Instruction::Reg(opcode, reg);
+                Some((instruction, 1 + 1))
+            }
+
+            OpCode::RBOP => {
+                let op = OpCode::from_u8(self.buffer.next()?)?;
+                let dst = self.buffer.next()?;
+                let a = self.buffer.next()?;
+                let b = self.buffer.next()?;
+                let instruction = Instruction::RegBinary(op, dst, a, b);
+                Some((instruction, 1 + 4))
+            }
+
+            OpCode::RUOP => {
+                let op = OpCode::from_u8(self.buffer.next()?)?;
+                let dst = self.buffer.next()?;
+                let a = self.buffer.next()?;
+                let instruction = Instruction::RegUnary(op, dst, a);
+                Some((instruction, 1 + 3))
+            }
+
+            OpCode::CAST | OpCode::ISA => {
                 let type_name = ValueType::from_u8(self.buffer.next()?)?;
-                let instruction = Instruction::Cast(type_name);
+                let instruction = Instruction::Cast(opcode, type_name);
                 Some((instruction, 1 + 1))
             }
 
-            OpCode::MKAR | OpCode::MKOB | OpCode::READF => {
+            OpCode::ISST | OpCode::CSST => {
+                let len = self.buffer.len();
+                let struct_type = StructType::deserialize_from_bytes(&mut self.buffer).ok()?;
+                let len = len - self.buffer.len();
+                let instruction = Instruction::Struct(opcode, struct_type);
+                Some((instruction, 1 + len))
+            }
+
+            OpCode::MKAR | OpCode::MKOB | OpCode::CNCT => {
                 let n = u64::deserialize_from_bytes(&mut self.buffer).ok()?;
                 let instruction = Instruction::AcceptsN(opcode, n);
-                Some((instruction, 1 + 4))
+                Some((instruction, 1 + 8))
             }
 
             OpCode::MKFN => {
@@ -342,7 +571,15 @@ impl<'src> ASMTranscoder<'src> {
                 let _version = self.buffer.next()?;
                 let function = Function::deserialize_from_bytes(&mut self.buffer).ok()?;
                 let len = len - self.buffer.len();
-                self.hashref.insert(function.name_hash, self.labels.next());
+                let label = match self
+                    .debug_profile
+                    .as_ref()
+                    .and_then(|p| p.name(function.name_hash))
+                {
+                    Some(name) => name.to_string(),
+                    None => self.labels.next(),
+                };
+                self.hashref.insert(function.name_hash, label);
                 let instruction = Instruction::MkFn(function);
                 Some((instruction, 1 + len))
             }
@@ -360,6 +597,29 @@ impl<'src> ASMTranscoder<'src> {
                 Some((instruction, 1 + 8 + 8))
             }
 
+            OpCode::CALM => {
+                let len = self.buffer.len();
+                let name = String::deserialize_from_bytes(&mut self.buffer).ok()?;
+                let n = u64::deserialize_from_bytes(&mut self.buffer).ok()?;
+                let len = len - self.buffer.len();
+                let instruction = Instruction::MethodCall(name, n);
+                Some((instruction, 1 + len))
+            }
+
+            OpCode::CALA => {
+                let name_hash = u64::deserialize_from_bytes(&mut self.buffer).ok()?;
+                let instruction = Instruction::FnCallSpread(name_hash);
+                Some((instruction, 1 + 8))
+            }
+
+            OpCode::CLMA => {
+                let len = self.buffer.len();
+                let name = String::deserialize_from_bytes(&mut self.buffer).ok()?;
+                let len = len - self.buffer.len();
+                let instruction = Instruction::MethodCallSpread(name);
+                Some((instruction, 1 + len))
+            }
+
             OpCode::POP
             | OpCode::SORT
             | OpCode::TYPE
@@ -380,6 +640,7 @@ impl<'src> ASMTranscoder<'src> {
             | OpCode::PSAR
             | OpCode::PSOB
             | OpCode::IDEX
+            | OpCode::NIDX
             | OpCode::ADD
             | OpCode::SUB
             | OpCode::MUL
@@ -396,6 +657,7 @@ impl<'src> ASMTranscoder<'src> {
             | OpCode::EQ
             | OpCode::NE
             | OpCode::SEQ
+            | OpCode::IEQ
             | OpCode::SNE
             | OpCode::LT
             | OpCode::LE
@@ -410,8 +672,32 @@ impl<'src> ASMTranscoder<'src> {
             | OpCode::EDWT
             | OpCode::WRFN
             | OpCode::FSIG
+            | OpCode::EXFN
             | OpCode::RET
             | OpCode::PRNT
+            | OpCode::WARN
+            | OpCode::RAND
+            | OpCode::TIME
+            | OpCode::FDATE
+            | OpCode::FDUR
+            | OpCode::PDUR
+            | OpCode::IPINT
+            | OpCode::INTIP
+            | OpCode::CIDR
+            | OpCode::CSVR
+            | OpCode::CSVW
+            | OpCode::DFMT
+            | OpCode::LSORT
+            | OpCode::SRTF
+            | OpCode::AMIN
+            | OpCode::AMAX
+            | OpCode::ASUM
+            | OpCode::APROD
+            | OpCode::AMEAN
+            | OpCode::SCNTN
+            | OpCode::SRMV
+            | OpCode::SREV
+            | OpCode::READF
             | OpCode::LSTFN
             | OpCode::LEN
             | OpCode::SSPLT
@@ -429,6 +715,11 @@ impl<'src> ASMTranscoder<'src> {
             | OpCode::LOG
             | OpCode::ILOG
             | OpCode::ROOT
+            | OpCode::ANGL
+            | OpCode::GAMMA
+            | OpCode::ERF
+            | OpCode::PRIME
+            | OpCode::RNDM
             | OpCode::NOP => Some((Instruction::Simple(opcode), 1)),
         }
     }
@@ -437,7 +728,7 @@ impl<'src> ASMTranscoder<'src> {
 /// A label generator for the disassembler
 /// Creates human-readable labels for jump instructions and variables
 /// Examples of output:
-/// ```
+/// ```text
 /// salamander_noodle
 /// kangaroo
 /// arbitrary_cabbage_marmalade
@@ -461,11 +752,13 @@ impl LabelGun {
     /// Get the next label
     pub fn next(&mut self) -> String {
         let label = Self::to_basen(self.0);
+
+        // Prefix with a dict entry chosen from the counter, so disassembly is reproducible
+        // 7 is coprime to the dict size, so consecutive labels get different prefixes
+        let prefix = Self::DICT[(self.0 * 7) % Self::DICT.len()];
         self.0 += 1;
 
-        // Prefix with a random dict entry
-        let rand = rand::random::<usize>() % Self::DICT.len();
-        Self::DICT[rand].to_owned() + "_" + &label
+        prefix.to_owned() + "_" + &label
     }
 
     /// Convert a number to a human-readable label
@@ -0,0 +1,362 @@
+//! Assembles the textual format produced by the `asm_transcoder` back into bytecode
+//! This is useful for testing individual opcodes, without going through the parser
+//!
+//! The format is one instruction per line:
+//! ```text
+//! ; Comments start with a semicolon
+//!   PUSH 1
+//!   REF my_variable      ; Names are hashed, or use #<hex> for a raw hash
+//!   JMPT done            ; Jumps can target labels, or hex offsets
+//!   CAST int             ; Types can be written as `int` or `Integer`
+//!   ISST Point{x: int}   ; Structured types are written as a name, then fields and their types
+//! done:
+//!   CALL sqrt 1          ; Function name (or #<hex> hash), then argument count
+//!   CALM area 1          ; Method name, then argument count
+//!   RBOP ADD r0 r0 r1    ; Register ops take an opcode, then registers from r0 to r255
+//!   PSHC `hello`         ; Constants are written by value; the pool is built from them
+//! ```
+//!
+//! Numeric operands are hexadecimal, as in the transcoder's output, except for the
+//! `JTBL` minimum, the `CALL` and `CALM` argument counts, and register numbers, which are decimal.
+//! Function definitions (`MKFN`) cannot be assembled; define functions in source instead.
+use std::{collections::HashMap, str::FromStr};
+
+use super::{
+    builder::{BuilderError, BytecodeBuilder, JumpTarget},
+    HashString,
+};
+use crate::{
+    literals,
+    traits::SerializeToBytes,
+    value::{Number, Primitive, StructType, ValueType},
+    vm::OpCode,
+};
+
+/// An error that can occur during assembly
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    /// A line could not be parsed
+    #[error("Line {0}: {1}")]
+    Syntax(usize, String),
+
+    /// A jump to a label that is never defined
+    #[error("Line {0}: Undefined label `{1}`")]
+    UndefinedLabel(usize, String),
+
+    /// A label defined more than once
+    #[error("Line {0}: Label `{1}` is already defined")]
+    DuplicateLabel(usize, String),
+
+    /// An opcode that cannot be written in assembly
+    #[error("Line {0}: {1:?} cannot be assembled; define functions in source instead")]
+    Unsupported(usize, OpCode),
+
+    /// The instructions could not be built into bytecode, such as a jump that is too far away
+    #[error(transparent)]
+    Build(BuilderError),
+}
+
+/// Assemble a source string into bytecode
+pub fn assemble(source: &str) -> Result<Vec<u8>, Error> {
+    let mut builder = BytecodeBuilder::new();
+
+    // The first line each label is used on, for errors about undefined labels
+    let mut uses = HashMap::new();
+
+    for (i, line) in source.lines().enumerate() {
+        let line_no = i + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        // Labels
+        if let Some(label) = line.strip_suffix(':') {
+            if !label.contains(char::is_whitespace) {
+                if builder.has_label(label) {
+                    return Err(Error::DuplicateLabel(line_no, label.to_string()));
+                }
+                builder.label(label);
+                continue;
+            }
+        }
+
+        parse_instruction(line_no, line, &mut builder, &mut uses)?;
+    }
+
+    builder.build().map_err(|e| match e {
+        BuilderError::UndefinedLabel(label) => Error::UndefinedLabel(uses[&label], label),
+        e => Error::Build(e),
+    })
+}
+
+/// Parse a single instruction
+/// Labels that are jumped to are added to `uses`, with the line they are first used on
+fn parse_instruction(
+    line_no: usize,
+    line: &str,
+    builder: &mut BytecodeBuilder,
+    uses: &mut HashMap<String, usize>,
+) -> Result<(), Error> {
+    let err = |msg: &str| Error::Syntax(line_no, msg.to_string());
+
+    // Strip trailing comments, unless the line contains a string
+    let line = match line.contains('`') {
+        true => line,
+        false => line.split(';').next().unwrap_or_default().trim(),
+    };
+
+    let (mnemonic, operands) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let operands = operands.trim();
+    let opcode =
+        OpCode::from_str(mnemonic).map_err(|_| err(&format!("Unknown opcode `{mnemonic}`")))?;
+
+    let mut args = operands.split_whitespace();
+    let mut next_arg = |name: &str| {
+        args.next()
+            .ok_or_else(|| err(&format!("{opcode:?} expects {name}")))
+    };
+
+    let mut bytes = vec![opcode as u8];
+    match opcode {
+        OpCode::PUSH | OpCode::PADD | OpCode::PSUB => {
+            let value = parse_primitive(operands)
+                .ok_or_else(|| err(&format!("Invalid value `{operands}`")))?;
+            bytes.extend(value.serialize_into_bytes());
+        }
+
+        // The pool is rebuilt from the values given to PSHC
+        OpCode::POOL => return Ok(()),
+        OpCode::PSHC => {
+            let value = parse_primitive(operands)
+                .ok_or_else(|| err(&format!("Invalid value `{operands}`")))?;
+            builder.constant(value);
+            return Ok(());
+        }
+
+        OpCode::JMP
+        | OpCode::JMPT
+        | OpCode::JMPF
+        | OpCode::JMPE
+        | OpCode::JMPNE
+        | OpCode::JMPNN
+        | OpCode::DJNN
+        | OpCode::DJNE => {
+            let target = parse_target(line_no, next_arg("a label or offset")?, uses)?;
+            builder.jump(opcode, target);
+            return Ok(());
+        }
+
+        OpCode::JTBL => {
+            let min = next_arg("a minimum key")?;
+            let min = i128::from_str(min).map_err(|_| err(&format!("Invalid key `{min}`")))?;
+            let default = parse_target(line_no, next_arg("a default target")?, uses)?;
+
+            let table = operands
+                .split_once('[')
+                .and_then(|(_, t)| t.strip_suffix(']'))
+                .ok_or_else(|| err("JTBL expects a table of targets, like [a, b]"))?;
+            let table = table
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(|t| parse_target(line_no, t, uses))
+                .collect::<Result<Vec<_>, _>>()?;
+            builder.jump_table(min, default, table);
+            return Ok(());
+        }
+
+        OpCode::REF | OpCode::RIDX | OpCode::WRGL | OpCode::WRCN | OpCode::WRLC => {
+            let hash = parse_hash(next_arg("a name")?);
+            bytes.extend(hash.serialize_into_bytes());
+        }
+
+        OpCode::RLDC => {
+            let (reg, value) = operands
+                .split_once(char::is_whitespace)
+                .unwrap_or((operands, ""));
+            let value = value.trim();
+            bytes.push(
+                parse_register(reg).ok_or_else(|| err(&format!("Invalid register `{reg}`")))?,
+            );
+            let value =
+                parse_primitive(value).ok_or_else(|| err(&format!("Invalid value `{value}`")))?;
+            bytes.extend(value.serialize_into_bytes());
+        }
+
+        OpCode::RLDV | OpCode::RPOP | OpCode::RPSH => {
+            let reg = next_arg("a register")?;
+            bytes.push(
+                parse_register(reg).ok_or_else(|| err(&format!("Invalid register `{reg}`")))?,
+            );
+            if opcode == OpCode::RLDV {
+                bytes.extend(parse_hash(next_arg("a name")?).serialize_into_bytes());
+            }
+        }
+
+        OpCode::RBOP | OpCode::RUOP => {
+            let op = next_arg("an opcode")?;
+            let op = OpCode::from_str(op).map_err(|_| err(&format!("Unknown opcode `{op}`")))?;
+            bytes.push(op as u8);
+
+            let n = if opcode == OpCode::RBOP { 3 } else { 2 };
+            for _ in 0..n {
+                let reg = next_arg("a register")?;
+                bytes.push(
+                    parse_register(reg).ok_or_else(|| err(&format!("Invalid register `{reg}`")))?,
+                );
+            }
+        }
+
+        OpCode::CAST | OpCode::ISA => {
+            let name = next_arg("a type")?;
+            let ty = parse_type(name).ok_or_else(|| err(&format!("Unknown type `{name}`")))?;
+            bytes.push(ty as u8);
+        }
+
+        OpCode::ISST | OpCode::CSST => {
+            let struct_type = parse_struct(operands)
+                .ok_or_else(|| err(&format!("Invalid structured type `{operands}`")))?;
+            bytes.extend(struct_type.serialize_into_bytes());
+        }
+
+        OpCode::MKAR | OpCode::MKOB | OpCode::CNCT => {
+            let n = next_arg("a count")?;
+            let n = parse_hex(n).ok_or_else(|| err(&format!("Invalid count `{n}`")))?;
+            bytes.extend(n.serialize_into_bytes());
+        }
+
+        OpCode::FDFT => {
+            let idx = next_arg("an argument index")?;
+            let idx = parse_hex(idx)
+                .and_then(|i| u16::try_from(i).ok())
+                .ok_or_else(|| err(&format!("Invalid argument index `{idx}`")))?;
+            bytes.extend(idx.serialize_into_bytes());
+        }
+
+        OpCode::CALL => {
+            let hash = parse_hash(next_arg("a function name")?);
+            let n = next_arg("an argument count")?;
+            let n = u64::from_str(n).map_err(|_| err(&format!("Invalid argument count `{n}`")))?;
+            bytes.extend(hash.serialize_into_bytes());
+            bytes.extend(n.serialize_into_bytes());
+        }
+
+        OpCode::CALM => {
+            let name = next_arg("a method name")?;
+            let n = next_arg("an argument count")?;
+            let n = u64::from_str(n).map_err(|_| err(&format!("Invalid argument count `{n}`")))?;
+            bytes.extend(name.to_string().serialize_into_bytes());
+            bytes.extend(n.serialize_into_bytes());
+        }
+
+        OpCode::CALA => {
+            let hash = parse_hash(next_arg("a function name")?);
+            bytes.extend(hash.serialize_into_bytes());
+        }
+
+        OpCode::CLMA => {
+            let name = next_arg("a method name")?;
+            bytes.extend(name.to_string().serialize_into_bytes());
+        }
+
+        OpCode::MKFN => return Err(Error::Unsupported(line_no, opcode)),
+
+        _ => {
+            if !operands.is_empty() {
+                return Err(err(&format!("{opcode:?} does not take any operands")));
+            }
+        }
+    }
+
+    builder.bytes(&bytes);
+    Ok(())
+}
+
+/// Parse a hex number, with or without a `0x` prefix
+fn parse_hex(s: &str) -> Option<u64> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    u64::from_str_radix(s, 16).ok()
+}
+
+/// Parse a jump target; a label, or a hex offset with optional parentheses
+/// Labels are added to `uses` if this is the first line they are used on
+fn parse_target(
+    line_no: usize,
+    s: &str,
+    uses: &mut HashMap<String, usize>,
+) -> Result<JumpTarget, Error> {
+    let s = s.trim();
+    let offset = s
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .unwrap_or(s);
+
+    match s.starts_with(|c: char| c.is_ascii_digit() || c == '(') {
+        true => parse_hex(offset)
+            .map(|offset| JumpTarget::Offset(offset as usize))
+            .ok_or_else(|| Error::Syntax(line_no, format!("Invalid offset `{s}`"))),
+        false => {
+            uses.entry(s.to_string()).or_insert(line_no);
+            Ok(JumpTarget::Label(s.to_string()))
+        }
+    }
+}
+
+/// Parse a register, written as `r0` to `r255`
+fn parse_register(s: &str) -> Option<u8> {
+    s.strip_prefix('r')?.parse().ok()
+}
+
+/// Parse a name to hash, or a raw `#<hex>` hash
+fn parse_hash(s: &str) -> u64 {
+    match s.strip_prefix('#').and_then(parse_hex) {
+        Some(hash) => hash,
+        None => s.hash_str(),
+    }
+}
+
+/// Parse a type by its name in the language (`int`), or in the transcoder's output (`Integer`)
+fn parse_type(s: &str) -> Option<ValueType> {
+    ValueType::from_str(s).or_else(|| {
+        (0..=u8::MAX)
+            .filter_map(ValueType::from_u8)
+            .find(|ty| format!("{ty:?}") == s)
+    })
+}
+
+/// Parse a structured type, in the format used by the transcoder: `Name{field: type, ...}`
+fn parse_struct(s: &str) -> Option<StructType> {
+    let (name, fields) = s.split_once('{')?;
+    let fields = fields.strip_suffix('}')?;
+    let fields = fields
+        .split(',')
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .map(|f| {
+            let (field, ty) = f.split_once(':')?;
+            Some((field.trim().to_string(), parse_type(ty.trim())?))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(StructType {
+        name: name.trim().to_string(),
+        fields,
+    })
+}
+
+/// Parse a primitive, in the format used by the transcoder
+fn parse_primitive(s: &str) -> Option<Primitive> {
+    Some(match s {
+        "nil" => Primitive::Nil,
+        "true" => Primitive::Boolean(true),
+        "false" => Primitive::Boolean(false),
+        _ if s.len() >= 2 && s.starts_with('`') && s.ends_with('`') => {
+            Primitive::String(s[1..s.len() - 1].to_string())
+        }
+        _ if s.contains('.') => {
+            Primitive::Decimal(Number::new(literals::decimal(s).ok()?, None, None))
+        }
+        _ => Primitive::Integer(literals::int(s).ok()?),
+    })
+}
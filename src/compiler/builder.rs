@@ -0,0 +1,306 @@
+//! Builds bytecode programmatically, without going through the parser
+//! Useful for hosts and DSLs that want to target the VM directly
+//!
+//! Instructions are written as an opcode, followed by its operands; see `OpCode` for the
+//! operands each one expects. Jumps can target labels that are defined later:
+//! ```rust
+//! # use lavendeux_parser::{prelude::*, compiler::BytecodeBuilder, value::Primitive, vm::OpCode};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut builder = BytecodeBuilder::new();
+//! builder
+//!     .push(Primitive::Boolean(false))
+//!     .jump(OpCode::JMPF, "else")
+//!     .push(Primitive::String("yes".to_string()))
+//!     .jump(OpCode::JMP, "end")
+//!     .label("else")
+//!     .push(Primitive::String("no".to_string()))
+//!     .label("end");
+//!
+//! let mut lav = Lavendeux::new();
+//! assert_eq!(lav.execute(builder.build()?, None)?, Value::string("no".to_string()));
+//! # Ok(())
+//! # }
+//! ```
+use std::collections::HashMap;
+
+use super::HashString;
+use crate::{
+    traits::SerializeToBytes,
+    value::{Primitive, StructType, ValueType},
+    vm::{write_constant_pool, OpCode, JUMP_SIZE},
+};
+
+/// An error that can occur while building bytecode
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum BuilderError {
+    /// A jump to a label that is never defined
+    #[error("Undefined label `{0}`")]
+    UndefinedLabel(String),
+
+    /// A label defined more than once
+    #[error("Label `{0}` is already defined")]
+    DuplicateLabel(String),
+
+    /// A jump whose target is too far away to encode
+    #[error("Jump at offset {0} cannot reach its target")]
+    JumpOutOfRange(usize),
+}
+
+/// The target of a jump; a label, or an absolute offset in the bytecode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JumpTarget {
+    /// A label, defined before or after the jump
+    Label(String),
+
+    /// An offset from the start of the bytecode
+    Offset(usize),
+}
+
+impl From<&str> for JumpTarget {
+    fn from(label: &str) -> Self {
+        JumpTarget::Label(label.to_string())
+    }
+}
+
+impl From<String> for JumpTarget {
+    fn from(label: String) -> Self {
+        JumpTarget::Label(label)
+    }
+}
+
+impl From<usize> for JumpTarget {
+    fn from(offset: usize) -> Self {
+        JumpTarget::Offset(offset)
+    }
+}
+
+/// Builds a bytecode program one instruction at a time
+/// Jump offsets are filled in by `build`, once every label is known
+///
+/// Constants pushed with `constant` are written to a pool in front of the instructions by `build`
+/// Positions and labels do not include the pool, but offset jump targets are positions in the
+/// finished bytecode, as shown by the transcoder
+#[derive(Debug, Clone, Default)]
+pub struct BytecodeBuilder {
+    bytecode: Vec<u8>,
+    labels: HashMap<String, usize>,
+    fixups: Vec<(usize, JumpTarget)>,
+    duplicate: Option<String>,
+    constants: Vec<Vec<u8>>,
+    constant_indices: HashMap<Vec<u8>, u32>,
+}
+
+impl BytecodeBuilder {
+    /// Create an empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The offset the next instruction will be written at
+    pub fn position(&self) -> usize {
+        self.bytecode.len()
+    }
+
+    /// Returns true if a label has been defined
+    pub fn has_label(&self, name: &str) -> bool {
+        self.labels.contains_key(name)
+    }
+
+    /// Define a label at the current position
+    /// Defining the same label twice is reported by `build`
+    pub fn label(&mut self, name: &str) -> &mut Self {
+        let position = self.position();
+        if self.labels.insert(name.to_string(), position).is_some() {
+            self.duplicate.get_or_insert_with(|| name.to_string());
+        }
+        self
+    }
+
+    /// Write an opcode
+    pub fn op(&mut self, opcode: OpCode) -> &mut Self {
+        self.bytecode.push(opcode as u8);
+        self
+    }
+
+    /// Write a single byte, such as a register number
+    pub fn u8(&mut self, value: u8) -> &mut Self {
+        self.bytecode.push(value);
+        self
+    }
+
+    /// Write a 16bit integer, such as an argument index
+    pub fn u16(&mut self, value: u16) -> &mut Self {
+        self.bytes(&value.serialize_into_bytes())
+    }
+
+    /// Write a 64bit integer, such as a count of values
+    pub fn u64(&mut self, value: u64) -> &mut Self {
+        self.bytes(&value.serialize_into_bytes())
+    }
+
+    /// Write the hash of a name, for instructions that access memory or call functions
+    pub fn hash(&mut self, name: &str) -> &mut Self {
+        self.u64(name.hash_str())
+    }
+
+    /// Write a string, such as a method name
+    pub fn string(&mut self, value: &str) -> &mut Self {
+        self.bytes(&value.to_string().serialize_into_bytes())
+    }
+
+    /// Write a type, for `CAST` and `ISA`
+    pub fn value_type(&mut self, value: ValueType) -> &mut Self {
+        self.u8(value as u8)
+    }
+
+    /// Write a structured type, for `ISST` and `CSST`
+    pub fn struct_type(&mut self, value: StructType) -> &mut Self {
+        self.bytes(&value.serialize_into_bytes())
+    }
+
+    /// Write a constant value, such as the operand of `PUSH`
+    pub fn primitive(&mut self, value: Primitive) -> &mut Self {
+        self.bytes(&value.serialize_into_bytes())
+    }
+
+    /// Write raw bytes
+    pub fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.bytecode.extend_from_slice(bytes);
+        self
+    }
+
+    /// Write a `PUSH` of a constant value
+    pub fn push(&mut self, value: Primitive) -> &mut Self {
+        self.op(OpCode::PUSH).primitive(value)
+    }
+
+    /// Write a `PSHC` of a constant value, adding it to the constant pool if it is not already there
+    pub fn constant(&mut self, value: Primitive) -> &mut Self {
+        let bytes = value.serialize_into_bytes();
+        let index = match self.constant_indices.get(&bytes) {
+            Some(index) => *index,
+            None => {
+                let index = self.constants.len() as u32;
+                self.constants.push(bytes.clone());
+                self.constant_indices.insert(bytes, index);
+                index
+            }
+        };
+        self.op(OpCode::PSHC).bytes(&index.serialize_into_bytes())
+    }
+
+    /// Write a call to a function by name, with the given number of arguments on the stack
+    pub fn call(&mut self, name: &str, argc: u64) -> &mut Self {
+        self.op(OpCode::CALL).hash(name).u64(argc)
+    }
+
+    /// Write a jump instruction, such as `JMP` or `JMPF`, to a label or offset
+    pub fn jump(&mut self, opcode: OpCode, target: impl Into<JumpTarget>) -> &mut Self {
+        self.op(opcode).jump_offset(target.into())
+    }
+
+    /// Write a `JTBL`, which jumps to `table[n - min]` for an integer `n` on the stack,
+    /// or to `default` if it is not in the table
+    pub fn jump_table<T: Into<JumpTarget>>(
+        &mut self,
+        min: i128,
+        default: impl Into<JumpTarget>,
+        table: impl IntoIterator<Item = T>,
+    ) -> &mut Self {
+        let table = table.into_iter().map(Into::into).collect::<Vec<_>>();
+        self.op(OpCode::JTBL)
+            .bytes(&min.serialize_into_bytes())
+            .u64(table.len() as u64)
+            .jump_offset(default.into());
+        for target in table {
+            self.jump_offset(target);
+        }
+        self
+    }
+
+    /// Reserve space for a jump offset, to be filled in by `build`
+    fn jump_offset(&mut self, target: JumpTarget) -> &mut Self {
+        self.fixups.push((self.position(), target));
+        self.bytes(&[0; JUMP_SIZE])
+    }
+
+    /// Resolve every jump, and return the finished bytecode, with the constant pool in front
+    pub fn build(mut self) -> Result<Vec<u8>, BuilderError> {
+        if let Some(label) = self.duplicate {
+            return Err(BuilderError::DuplicateLabel(label));
+        }
+
+        let mut output = match self.constants.is_empty() {
+            true => vec![],
+            false => write_constant_pool(&self.constants),
+        };
+        let base = output.len();
+
+        for (at, target) in self.fixups {
+            let target = match target {
+                JumpTarget::Offset(offset) => offset,
+                JumpTarget::Label(label) => match self.labels.get(&label) {
+                    Some(position) => base + *position,
+                    None => return Err(BuilderError::UndefinedLabel(label)),
+                },
+            };
+
+            // Jumps are relative to the position of their offset
+            let offset = i32::try_from(target as i64 - (base + at) as i64)
+                .map_err(|_| BuilderError::JumpOutOfRange(base + at))?;
+            self.bytecode[at..at + JUMP_SIZE].copy_from_slice(&offset.serialize_into_bytes());
+        }
+
+        output.extend(self.bytecode);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_bytecode_builder() {
+        // abs(n - 5) for n in 0..3, looping until the jump table falls through to `done`
+        // MKAR takes the top of the stack first, so the results come out in reverse
+        let mut builder = BytecodeBuilder::new();
+        builder.push(Primitive::Integer(0));
+        builder.label("loop");
+        builder
+            .op(OpCode::DUP)
+            .jump_table(0, "done", ["body", "body", "body"])
+            .label("body")
+            .op(OpCode::DUP)
+            .push(Primitive::Integer(5))
+            .op(OpCode::SUB)
+            .call("abs", 1)
+            .op(OpCode::SWP)
+            .push(Primitive::Integer(1))
+            .op(OpCode::ADD)
+            .jump(OpCode::JMP, "loop")
+            .label("done")
+            .op(OpCode::POP)
+            .op(OpCode::MKAR)
+            .u64(3);
+
+        let mut lav = Lavendeux::new();
+        let result = lav.execute(builder.build().unwrap(), None).unwrap();
+        assert_eq!(result.to_string(), "[3, 4, 5]");
+
+        let mut builder = BytecodeBuilder::new();
+        builder.jump(OpCode::JMP, "nowhere");
+        assert!(matches!(
+            builder.build(),
+            Err(BuilderError::UndefinedLabel(label)) if label == "nowhere"
+        ));
+
+        let mut builder = BytecodeBuilder::new();
+        builder.label("a").op(OpCode::NOP).label("a");
+        assert!(matches!(
+            builder.build(),
+            Err(BuilderError::DuplicateLabel(_))
+        ));
+    }
+}
@@ -1,6 +1,28 @@
-use super::debug_profile::DebugProfile;
-use crate::{lexer::Token, traits::SerializeToBytes, value::ValueType, vm::OpCode};
-use std::ops::Range;
+use super::{
+    constant_pool,
+    cse::CseState,
+    debug_profile::DebugProfile,
+    error::{CompilerError, CompilerWarning},
+    inlining::InlineState,
+    name_hash::{qualified_name, HashString},
+    peephole::{self, PeepholeStats},
+    registers::RegisterState,
+};
+use crate::{
+    lexer::Token,
+    parser::{core::VarScope, Node, VisitorMut},
+    traits::{IntoOwned, SerializeToBytes},
+    value::{Primitive, StructType, ValueType},
+    vm::{jump_offset, OpCode},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
+
+/// How deeply aliases can be expanded inside one another
+/// Prevents infinite expansion of recursive aliases
+pub const MAX_ALIAS_DEPTH: usize = 64;
 
 /// Options for the compiler
 #[derive(Debug, Clone)]
@@ -10,23 +32,122 @@ pub struct CompilerOptions {
 
     /// Whether to allow syscalld calls
     pub allow_syscalld: bool,
+
+    /// Largest function body, in bytes of bytecode, for which calls are inlined
+    /// Set to 0 to disable inlining
+    pub inline_threshold: usize,
+
+    /// Whether to remove redundant instructions with the peephole optimizer,
+    /// and move literals into a constant pool
+    pub optimize: bool,
+
+    /// Whether to compute repeated pure subexpressions only once
+    pub common_subexpressions: bool,
+
+    /// Whether to check for type errors before compiling
+    /// See the `typecheck` module for what is reported
+    pub typecheck: bool,
+
+    /// Whether to warn about expressions whose order of operations may be surprising
+    /// See the `lint` module for what is reported
+    pub lint_precedence: bool,
+
+    /// Whether to warn about variables that shadow others, or are used after the loop that discarded them
+    /// See the `scopes` module for what is reported
+    pub lint_scopes: bool,
+
+    /// Whether to compile operator expressions to register instructions, instead of the stack
+    /// Experimental; see the `registers` module
+    pub registers: bool,
+
+    /// The namespace functions are defined in, such as the name of a library
+    /// Functions defined in an included file are in a namespace named after the file instead
+    pub namespace: Option<String>,
 }
 impl Default for CompilerOptions {
     fn default() -> Self {
         Self {
             debug: true,
             allow_syscalld: false,
+            inline_threshold: 64,
+            optimize: true,
+            common_subexpressions: true,
+            typecheck: false,
+            lint_precedence: true,
+            lint_scopes: true,
+            registers: false,
+            namespace: None,
         }
     }
 }
 
+/// A loop that is currently being compiled
+#[derive(Debug, Clone)]
+struct LoopContext {
+    label: Option<String>,
+    start: usize,
+    depth: usize,                    // Scope depth of the loop body
+    breaks: Vec<Range<usize>>,       // Breaks without a value
+    value_breaks: Vec<Range<usize>>, // Breaks with a value on the stack
+}
+
+/// A compile-time alias, defined with `alias name(args) => expression`
+#[derive(Debug, Clone)]
+pub struct Alias {
+    /// Names of the arguments
+    pub args: Vec<String>,
+
+    /// The expression the alias expands to
+    pub body: Node<'static>,
+}
+
+impl Alias {
+    /// Expand the alias, replacing each argument in the body with the given expressions
+    pub fn expand<'source>(&self, args: Vec<Node<'source>>) -> Node<'source> {
+        struct Substitute<'a, 'source>(HashMap<&'a str, Node<'source>>);
+        impl<'source> VisitorMut<'source> for Substitute<'_, 'source> {
+            fn enter(&mut self, node: &mut Node<'source>) -> bool {
+                if let Node::LiteralIdent(ident) = node {
+                    if let Some(arg) = self.0.get(ident.token.slice()) {
+                        *node = arg.clone();
+                        return false;
+                    }
+                }
+                true
+            }
+        }
+
+        let mut body: Node<'source> = self.body.clone();
+        let args = self.args.iter().map(String::as_str).zip(args).collect();
+        body.walk_mut(&mut Substitute(args));
+        body
+    }
+}
+
 /// Compiles source code into bytecode
 /// You don't need to use this directly, use the `compile` function on a `Node` instead
 #[derive(Debug, Clone)]
 pub struct Compiler {
     bytecode: Vec<u8>,
-    loop_stack: Vec<(usize, Vec<Range<usize>>)>, // (start, break targets)
+    loop_stack: Vec<LoopContext>,
+    scope_depth: usize,
+    aliases: HashMap<String, Alias>,
+    struct_types: HashMap<String, StructType>,
+    enums: HashMap<String, Vec<(String, i128)>>,
+    constants: HashSet<String>,
+    pub(super) variables: HashSet<String>,
+    globals: HashSet<String>,
+    declarations: HashMap<String, VarScope>,
+    functions: HashMap<String, (String, Token<'static>)>,
+    names: HashMap<u64, String>,
+    name_collision: Option<(String, String)>,
+    alias_depth: usize,
+    pub(super) inlining: InlineState,
+    peephole: PeepholeStats,
+    pub(super) cse: CseState,
+    pub(super) registers: RegisterState,
     debug: DebugProfile,
+    warnings: Vec<CompilerWarning>,
     options: CompilerOptions,
 }
 
@@ -36,7 +157,24 @@ impl Compiler {
         Self {
             bytecode: Vec::new(),
             loop_stack: Vec::new(),
+            scope_depth: 0,
+            aliases: HashMap::new(),
+            struct_types: HashMap::new(),
+            enums: HashMap::new(),
+            constants: HashSet::new(),
+            variables: HashSet::new(),
+            globals: HashSet::new(),
+            declarations: HashMap::new(),
+            functions: HashMap::new(),
+            names: HashMap::new(),
+            name_collision: None,
+            alias_depth: 0,
+            inlining: InlineState::default(),
+            peephole: PeepholeStats::default(),
+            cse: CseState::default(),
+            registers: RegisterState::default(),
             debug: DebugProfile::new(input),
+            warnings: Vec::new(),
             options,
         }
     }
@@ -46,6 +184,190 @@ impl Compiler {
         &self.options
     }
 
+    /// Define an alias, replacing any existing alias with the same name
+    pub fn define_alias(&mut self, name: String, alias: Alias) {
+        self.aliases.insert(name, alias);
+    }
+
+    /// Get an alias by name
+    pub fn alias(&self, name: &str) -> Option<&Alias> {
+        self.aliases.get(name)
+    }
+
+    /// Declare a structured type, replacing any existing type with the same name
+    pub fn define_struct_type(&mut self, struct_type: StructType) {
+        self.struct_types
+            .insert(struct_type.name.clone(), struct_type);
+    }
+
+    /// Get a structured type by name
+    pub fn struct_type(&self, name: &str) -> Option<&StructType> {
+        self.struct_types.get(name)
+    }
+
+    /// Make the structured types, enums, constants, globals and names declared in another compiler available to this one
+    /// Used to compile function bodies, which are compiled separately
+    pub fn inherit_types(&mut self, other: &Compiler) {
+        self.struct_types.extend(other.struct_types.clone());
+        self.enums.extend(other.enums.clone());
+        self.constants.extend(other.constants.clone());
+        self.globals.extend(other.globals.clone());
+        self.names.extend(other.names.clone());
+    }
+
+    /// Add the names used by a separately compiled block, such as a function body,
+    /// along with any globals, hash collision and warnings found in it
+    pub fn add_names(&mut self, other: &Compiler) {
+        self.globals.extend(other.globals.iter().cloned());
+        for warning in &other.warnings {
+            self.warn(warning.clone());
+        }
+        for name in other.names.values() {
+            self.intern_name(name);
+        }
+        if let Some(collision) = &other.name_collision {
+            self.name_collision.get_or_insert_with(|| collision.clone());
+        }
+    }
+
+    /// Hash a name, recording it so that two names with the same hash are caught
+    /// Only the first collision is kept; see `take_name_collision`
+    pub fn intern_name(&mut self, name: &str) -> u64 {
+        let hash = name.hash_str();
+        match self.names.get(&hash) {
+            Some(existing) if existing != name => {
+                let collision = (existing.clone(), name.to_string());
+                self.name_collision.get_or_insert(collision);
+            }
+            Some(_) => {}
+            None => {
+                self.names.insert(hash, name.to_string());
+            }
+        }
+
+        if self.options.debug {
+            self.debug.insert_name(hash, name);
+        }
+        hash
+    }
+
+    /// Take the first pair of names found to have the same hash, if any
+    /// They would share a slot in memory, so the program cannot be compiled
+    pub fn take_name_collision(&mut self) -> Option<(String, String)> {
+        self.name_collision.take()
+    }
+
+    /// Declare a constant by name, so that assignments to it are rejected
+    pub fn define_constant(&mut self, name: String) {
+        self.constants.insert(name);
+    }
+
+    /// Declare a variable that exists before the script runs, such as one assigned by an earlier run
+    /// Loops in the script then overwrite it, instead of discarding it when they end
+    pub fn define_variable(&mut self, name: String) {
+        self.variables.insert(name);
+    }
+
+    /// Declare a global that exists before the script runs, such as one declared by an earlier run
+    /// Plain assignments to it then write to the global scope
+    pub fn define_global(&mut self, name: String) {
+        self.globals.insert(name);
+    }
+
+    /// Declare a name `global` or `local` in the function, or top level of the script, being compiled
+    /// Fails with the existing declaration if the name was already declared with the other scope
+    pub fn declare_scope(&mut self, name: &str, scope: VarScope) -> Result<(), VarScope> {
+        match self.declarations.get(name) {
+            Some(existing) if *existing != scope => return Err(*existing),
+            _ => {}
+        }
+
+        self.declarations.insert(name.to_string(), scope);
+        if scope == VarScope::Global {
+            self.globals.insert(name.to_string());
+        }
+        Ok(())
+    }
+
+    /// Returns true if plain assignments to a name write to the global scope;
+    /// it was declared `global`, and not declared `local` in the function being compiled
+    pub fn is_global(&self, name: &str) -> bool {
+        self.globals.contains(name) && self.declarations.get(name) != Some(&VarScope::Local)
+    }
+
+    /// Get the names of all declared globals
+    pub fn globals(&self) -> &HashSet<String> {
+        &self.globals
+    }
+
+    /// Returns true if a constant with the given name has been declared
+    pub fn is_constant(&self, name: &str) -> bool {
+        self.constants.contains(name)
+    }
+
+    /// Get the names of all declared constants
+    pub fn constants(&self) -> &HashSet<String> {
+        &self.constants
+    }
+
+    /// The namespace a token is compiled in; named after the included file it is from, if any,
+    /// or set by the options. Empty for the global namespace
+    pub fn namespace_of(&self, token: &Token<'_>) -> String {
+        let file_stem = token.filename().and_then(|filename| {
+            let stem = std::path::Path::new(filename).file_stem()?;
+            Some(stem.to_string_lossy().to_string())
+        });
+
+        file_stem
+            .or_else(|| self.options.namespace.clone())
+            .unwrap_or_default()
+    }
+
+    /// Record the definition of a function, at the given token
+    /// Redefining a function in the same namespace replaces it, but defining it in another
+    /// namespace is an error, naming both definitions
+    pub fn define_function(&mut self, name: &str, token: Token<'_>) -> Result<(), CompilerError> {
+        let namespace = self.namespace_of(&token);
+        if let Some((existing, site)) = self.functions.get(name) {
+            if *existing != namespace {
+                return Err(CompilerError::FunctionCollision(
+                    token.into_owned(),
+                    qualified_name(&namespace, name),
+                    Box::new((qualified_name(existing, name), site.clone())),
+                ));
+            }
+        }
+
+        self.functions
+            .insert(name.to_string(), (namespace, token.into_owned()));
+        Ok(())
+    }
+
+    /// Declare an enum, with its members in declaration order
+    pub fn define_enum(&mut self, name: String, members: Vec<(String, i128)>) {
+        self.enums.insert(name, members);
+    }
+
+    /// Get the members of an enum by name, in declaration order
+    pub fn enum_members(&self, name: &str) -> Option<&[(String, i128)]> {
+        self.enums.get(name).map(Vec::as_slice)
+    }
+
+    /// Start expanding an alias
+    /// Returns false if aliases are nested more than `MAX_ALIAS_DEPTH` deep
+    pub fn enter_alias(&mut self) -> bool {
+        if self.alias_depth >= MAX_ALIAS_DEPTH {
+            return false;
+        }
+        self.alias_depth += 1;
+        true
+    }
+
+    /// Finish expanding an alias
+    pub fn exit_alias(&mut self) {
+        self.alias_depth -= 1;
+    }
+
     /// Push a token to the debug profile
     /// This is used to map bytecode instructions to source code
     /// during error reporting
@@ -95,7 +417,7 @@ impl Compiler {
     /// Used for memory access
     /// Returns the index of the value
     pub fn push_strhash(&mut self, input: &str) -> Range<usize> {
-        let hash = input.hash_str();
+        let hash = self.intern_name(input);
         self.push_u64(hash)
     }
 
@@ -117,6 +439,58 @@ impl Compiler {
         self.bytecode.splice(range, value);
     }
 
+    /// Push the offset for a jump to a position that is already known, such as the start of a loop
+    /// Returns the index of the offset
+    pub fn push_jump_to(&mut self, target: usize) -> Range<usize> {
+        self.push_i32(jump_offset(self.bytecode.len(), target))
+    }
+
+    /// Push a placeholder for the offset of a forward jump, to be filled in by `patch_jump`
+    /// Returns the index of the offset
+    pub fn push_jump_placeholder(&mut self) -> Range<usize> {
+        self.push_i32(0)
+    }
+
+    /// Point the jump offset at `range` to a position
+    pub fn patch_jump(&mut self, range: Range<usize>, target: usize) {
+        let offset = jump_offset(range.start, target);
+        self.replace(range, offset.serialize_into_bytes());
+    }
+
+    /// Run the peephole optimizer over the bytecode, then move its literals into a constant pool,
+    /// if enabled in the options
+    /// Call this once compilation is finished, since it moves instructions
+    pub fn optimize(&mut self) {
+        if self.options.optimize {
+            peephole::optimize(&mut self.bytecode, &mut self.debug, &mut self.peephole);
+            constant_pool::pool_constants(&mut self.bytecode, &mut self.debug);
+        }
+    }
+
+    /// Get the instructions removed by the peephole optimizer so far
+    pub fn peephole_stats(&self) -> &PeepholeStats {
+        &self.peephole
+    }
+
+    /// Record a problem that does not stop the program from compiling
+    /// Code compiled more than once, such as an inlined function body, is only warned about once
+    pub fn warn(&mut self, warning: CompilerWarning) {
+        let text = warning.to_string();
+        if !self.warnings.iter().any(|w| w.to_string() == text) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Get the warnings found so far, in the order they were found
+    pub fn warnings(&self) -> &[CompilerWarning] {
+        &self.warnings
+    }
+
+    /// Add the instructions removed from a separately compiled block, such as a function body
+    pub fn add_peephole_stats(&mut self, stats: &PeepholeStats) {
+        self.peephole.add(stats);
+    }
+
     /// Get a reference to the bytecode
     pub fn bytecode(&self) -> &[u8] {
         &self.bytecode
@@ -135,58 +509,185 @@ impl Compiler {
 
 /// Extensions for the compiler to handle loops properly
 pub trait LoopCompilationExt {
-    /// Start a new loop
-    fn start_loop(&mut self);
+    /// Enter a new scope, tracking the depth so that loops can be exited
+    fn push_scope_in(&mut self);
+
+    /// Exit the current scope
+    fn push_scope_out(&mut self);
+
+    /// Start a new loop, with an optional label
+    /// The current position is used as the target for `continue`
+    /// The loop body is expected to be compiled in a new scope
+    fn start_loop(&mut self, label: Option<String>);
 
     /// End the current loop
-    fn end_loop(&mut self);
+    /// Returns the jump targets to patch, for breaks with and without a value:
+    /// `(breaks, value_breaks)`
+    fn end_loop(&mut self) -> (Vec<Range<usize>>, Vec<Range<usize>>);
 
-    /// Push a break instruction
-    fn push_break(&mut self);
+    /// Push a break instruction, exiting all scopes up to the loop's body
+    /// If `with_value` is set, the top of the stack is kept as the value for the iteration
+    /// Returns false if there is no matching loop
+    fn push_break(&mut self, label: Option<&str>, with_value: bool) -> bool;
 
-    /// Push a continue instruction
-    fn push_continue(&mut self);
+    /// Push a continue instruction, exiting all scopes up to the loop's body
+    /// Returns false if there is no matching loop
+    fn push_continue(&mut self, label: Option<&str>) -> bool;
 }
 
-impl LoopCompilationExt for Compiler {
-    fn start_loop(&mut self) {
-        self.loop_stack.push((self.bytecode.len(), Vec::new()));
+impl Compiler {
+    /// Find a loop by label, or the innermost loop if no label is given
+    fn find_loop(&self, label: Option<&str>) -> Option<usize> {
+        match label {
+            Some(label) => self
+                .loop_stack
+                .iter()
+                .rposition(|l| l.label.as_deref() == Some(label)),
+            None => self.loop_stack.len().checked_sub(1),
+        }
     }
 
-    fn end_loop(&mut self) {
-        let (_, breaks) = self.loop_stack.pop().unwrap();
-        for target in breaks {
-            let pos = self.bytecode.len();
-            self.replace(target, pos.serialize_into_bytes());
+    /// Exit all scopes down to, and including, the body of a loop
+    /// The top of the stack survives each scope exit
+    fn push_loop_exit(&mut self, index: usize) {
+        let depth = self.loop_stack[index].depth;
+        for _ in depth..=self.scope_depth {
+            self.push(OpCode::SCO);
         }
     }
+}
+
+impl LoopCompilationExt for Compiler {
+    fn push_scope_in(&mut self) {
+        self.push(OpCode::SCI);
+        self.scope_depth += 1;
+    }
+
+    fn push_scope_out(&mut self) {
+        self.push(OpCode::SCO);
+        self.scope_depth -= 1;
+    }
+
+    fn start_loop(&mut self, label: Option<String>) {
+        self.loop_stack.push(LoopContext {
+            label,
+            start: self.bytecode.len(),
+            depth: self.scope_depth + 1,
+            breaks: Vec::new(),
+            value_breaks: Vec::new(),
+        });
+    }
+
+    fn end_loop(&mut self) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+        let context = self.loop_stack.pop().unwrap();
+        (context.breaks, context.value_breaks)
+    }
+
+    fn push_break(&mut self, label: Option<&str>, with_value: bool) -> bool {
+        let index = match self.find_loop(label) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        // Make sure there is exactly one value left after the scopes are gone
+        if !with_value {
+            self.push(OpCode::PUSH);
+            self.extend(Primitive::Nil.serialize_into_bytes());
+        }
+        self.push_loop_exit(index);
 
-    fn push_break(&mut self) {
         self.push(OpCode::JMP);
-        let target = self.push_u64(0);
+        let target = self.push_jump_placeholder();
 
-        let (_, breaks) = self.loop_stack.last_mut().unwrap();
-        breaks.push(target);
+        let context = &mut self.loop_stack[index];
+        if with_value {
+            context.value_breaks.push(target);
+        } else {
+            context.breaks.push(target);
+        }
+        true
     }
 
-    fn push_continue(&mut self) {
+    fn push_continue(&mut self, label: Option<&str>) -> bool {
+        let index = match self.find_loop(label) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        // Exit the scopes, then discard the placeholder value
+        self.push(OpCode::PUSH);
+        self.extend(Primitive::Nil.serialize_into_bytes());
+        self.push_loop_exit(index);
+        self.push(OpCode::POP);
+
         self.push(OpCode::JMP);
-        let start = self.loop_stack.last().unwrap().0;
-        self.push_u64(start as u64);
+        let start = self.loop_stack[index].start;
+        self.push_jump_to(start);
+        true
     }
 }
 
-/// Hash a string to a u64
-pub trait HashString {
-    /// Hash a string to a u64
-    fn hash_str(&self) -> u64;
-}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        value::{CollisionPolicy, LibraryError},
+        Error, Lavendeux,
+    };
+
+    #[test]
+    fn test_function_namespaces() {
+        // Two included files defining the same function collide, naming both files
+        let dir = std::env::temp_dir();
+        let id = std::process::id();
+        let a = dir.join(format!("lav_ns_a_{id}.lav"));
+        let b = dir.join(format!("lav_ns_b_{id}.lav"));
+        std::fs::write(&a, "format(x) = 'a'").unwrap();
+        std::fs::write(&b, "format(x) = 'b'").unwrap();
+
+        let source = format!("include({:?})\ninclude({:?})", a, b);
+        let error = Lavendeux::new().compile(&source).unwrap_err();
+        std::fs::remove_file(&a).ok();
+        std::fs::remove_file(&b).ok();
+        assert!(matches!(
+            &error,
+            Error::Compiler(CompilerError::FunctionCollision(_, name, existing))
+                if *name == format!("lav_ns_b_{id}::format")
+                && existing.0 == format!("lav_ns_a_{id}::format")
+        ));
+        assert_eq!(error.diagnostic().context.len(), 1);
+
+        // Redefining a function in the same namespace is fine
+        assert!(Lavendeux::new().compile("f(x) = 1\nf(x) = 2").is_ok());
+
+        // Loading the same library twice is not a collision, but another namespace is
+        let library = |namespace: &str| {
+            let functions = Lavendeux::with_options(CompilerOptions {
+                namespace: Some(namespace.to_string()),
+                ..Default::default()
+            })
+            .compile_functions("render(x) = x")
+            .unwrap();
+            assert_eq!(
+                functions.functions()[0].qualified_name(),
+                format!("{namespace}::render")
+            );
+
+            let path = dir.join(format!("lav_ns_{namespace}_{id}.lbc"));
+            std::fs::write(&path, functions.serialize_into_bytes()).unwrap();
+            path
+        };
+        let (json, text) = (library("json"), library("text"));
 
-impl HashString for str {
-    fn hash_str(&self) -> u64 {
-        use std::hash::Hasher;
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        hasher.write(self.as_bytes());
-        hasher.finish()
+        let mut lav = Lavendeux::new();
+        lav.load_library(&json, CollisionPolicy::Error).unwrap();
+        lav.load_library(&json, CollisionPolicy::Error).unwrap();
+        let error = lav.load_library(&text, CollisionPolicy::Error).unwrap_err();
+        assert!(matches!(
+            error,
+            LibraryError::Collision(name, existing) if name == "text::render" && existing == "json::render"
+        ));
+        std::fs::remove_file(&json).ok();
+        std::fs::remove_file(&text).ok();
     }
 }
@@ -0,0 +1,141 @@
+//! Moves the literals of a block of bytecode into a constant pool
+//! Each `PUSH` of a literal larger than an index is replaced with a `PSHC` of its index in the
+//! pool, and the pool is written as a `POOL` instruction at the start of the block
+//!
+//! Literals are deduplicated by their serialized bytes, so `1.5` and `1.50` are kept apart
+//! Jumps and the debug profile are updated to the new positions of their instructions
+use super::{
+    asm_transcoder::{ASMTranscoder, Instruction},
+    DebugProfile,
+};
+use crate::{
+    traits::SerializeToBytes,
+    value::Value,
+    vm::{jump_offset, write_constant_pool, OpCode, CONSTANT_INDEX_SIZE},
+};
+use std::collections::HashMap;
+
+/// Move the literals of a block of bytecode into a constant pool, updating the debug profile to match
+/// Bytecode that cannot be decoded, or that already has a pool, is left unchanged
+pub(super) fn pool_constants(bytecode: &mut Vec<u8>, debug: &mut DebugProfile) {
+    if bytecode.first() == Some(&(OpCode::POOL as u8)) {
+        return;
+    }
+
+    let instructions = match ASMTranscoder::new(bytecode, None).decode() {
+        Ok(instructions) => instructions,
+        Err(_) => return,
+    };
+
+    // Index in the pool of each pushed literal worth pooling
+    let mut constants = vec![];
+    let mut indices = HashMap::new();
+    let mut pooled = vec![None; instructions.len()];
+    for (i, (instruction, len)) in instructions.iter().enumerate() {
+        if let Instruction::Push(Value::Primitive(value)) = instruction {
+            if *len > 1 + CONSTANT_INDEX_SIZE {
+                let bytes = value.clone().serialize_into_bytes();
+                let index = *indices.entry(bytes.clone()).or_insert_with(|| {
+                    constants.push(bytes);
+                    constants.len() - 1
+                });
+                pooled[i] = Some(index as u32);
+            }
+        }
+    }
+
+    if constants.is_empty() {
+        return;
+    }
+
+    // Old and new offset of each instruction, after the pool
+    let header = write_constant_pool(&constants);
+    let mut offsets = Vec::with_capacity(instructions.len() + 1);
+    let mut new_offsets = Vec::with_capacity(instructions.len() + 1);
+    let (mut offset, mut new_offset) = (0, header.len());
+    for ((_, len), index) in instructions.iter().zip(&pooled) {
+        offsets.push(offset);
+        new_offsets.push(new_offset);
+        offset += len;
+        new_offset += match index {
+            Some(_) => 1 + CONSTANT_INDEX_SIZE,
+            None => *len,
+        };
+    }
+    offsets.push(offset);
+    new_offsets.push(new_offset);
+
+    let remap = |pos: usize| new_offsets[offsets.partition_point(|o| *o < pos)];
+
+    // Jumps are relative to their own position in the output
+    let push_jump = |output: &mut Vec<u8>, pos: u64| {
+        let offset = jump_offset(output.len(), remap(pos as usize));
+        output.extend(offset.serialize_into_bytes());
+    };
+
+    // Rebuild the bytecode, moving jump targets
+    let mut output = header;
+    output.reserve(new_offset);
+    for (i, (instruction, len)) in instructions.iter().enumerate() {
+        match (instruction, pooled[i]) {
+            (_, Some(index)) => {
+                output.push(OpCode::PSHC as u8);
+                output.extend(index.serialize_into_bytes());
+            }
+            (Instruction::Jump(opcode, pos), _) => {
+                output.push(*opcode as u8);
+                push_jump(&mut output, *pos);
+            }
+            (Instruction::JumpTable(min, default, table), _) => {
+                output.push(OpCode::JTBL as u8);
+                output.extend(min.serialize_into_bytes());
+                output.extend((table.len() as u64).serialize_into_bytes());
+                push_jump(&mut output, *default);
+                for pos in table {
+                    push_jump(&mut output, *pos);
+                }
+            }
+            _ => output.extend_from_slice(&bytecode[offsets[i]..offsets[i] + len]),
+        }
+    }
+
+    debug.remap(remap);
+    *bytecode = output;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{compiler::CompilerOptions, vm::read_constant_pool, Lavendeux};
+
+    #[test]
+    fn test_constant_pool() {
+        let source = "s = 'a long repeated string'\n\
+            f(x) = x + 'a long repeated string' + 12345\n\
+            [f(s), f('a long repeated string'), 12345, true]";
+
+        let mut lav = Lavendeux::new();
+        let (profile, bytecode) = lav.compile(source).unwrap();
+        assert_eq!(bytecode[0], OpCode::POOL as u8);
+
+        // Each literal is pooled once; booleans are smaller inline than as an index
+        let pool = read_constant_pool(&bytecode).unwrap();
+        assert_eq!(pool.len(), 2);
+        let instructions = ASMTranscoder::new(&bytecode, None).disassemble_as_vec();
+        assert!(instructions
+            .iter()
+            .any(|i| matches!(i, Instruction::PushConstant(..))));
+        assert!(instructions
+            .iter()
+            .any(|i| matches!(i, Instruction::Push(_))));
+
+        let result = lav.execute(bytecode, Some(profile)).unwrap();
+        let unpooled = Lavendeux::with_options(CompilerOptions {
+            optimize: false,
+            ..Default::default()
+        })
+        .run(source)
+        .unwrap();
+        assert_eq!(result, unpooled);
+    }
+}
@@ -0,0 +1,258 @@
+//! Common subexpression elimination
+//! Within a pure expression - one with no calls, assignments, or jumps - a subexpression
+//! that appears more than once is only computed the first time.
+//! The result is kept in a hidden variable, which is read by the later copies,
+//! and deleted once the whole expression is done
+//!
+//! Subexpressions are compared by structure, so `(a+b)*(a+b)` is simplified, but `(a+b)*(b+a)` is not
+use super::{Compiler, CompilerError};
+use crate::{parser::Node, vm::OpCode};
+use std::collections::{HashMap, HashSet};
+
+/// Largest expression, in nodes, that is searched for common subexpressions
+/// Keeps the cost of comparing subexpressions bounded for very large expressions
+pub const MAX_CSE_NODES: usize = 256;
+
+/// An expression currently being compiled with common subexpressions eliminated
+#[derive(Debug, Clone, Default)]
+struct Region {
+    // Subexpressions that repeat, and the hidden variable used for each once it is computed
+    repeated: HashSet<String>,
+    computed: HashMap<String, String>,
+}
+
+/// State of the common subexpression elimination pass
+#[derive(Debug, Clone, Default)]
+pub(super) struct CseState {
+    region: Option<Region>,
+
+    // Used to give each hidden variable a unique name
+    counter: usize,
+}
+
+impl Compiler {
+    /// Builds a key describing the structure of a pure expression
+    /// Returns None if the expression is not pure, or is larger than `MAX_CSE_NODES` nodes
+    fn subexpression_key(&self, node: &Node<'_>) -> Option<String> {
+        let mut budget = MAX_CSE_NODES;
+        self.key_within_budget(node, &mut budget)
+    }
+
+    fn key_within_budget(&self, node: &Node<'_>, budget: &mut usize) -> Option<String> {
+        if *budget == 0 {
+            return None;
+        }
+        *budget -= 1;
+
+        let mut key = |name: &str, children: &[&Node<'_>]| -> Option<String> {
+            let mut out = format!("{name}(");
+            for child in children {
+                out.push_str(&self.key_within_budget(child, budget)?);
+                out.push(',');
+            }
+            out.push(')');
+            Some(out)
+        };
+
+        match node {
+            Node::LiteralString(n) => Some(format!("{:?}", n.value)),
+            Node::LiteralInt(n) => Some(format!("{:?}", n.value)),
+            Node::LiteralFloat(n) => Some(format!("{:?}", n.value)),
+            Node::LiteralBool(n) => Some(format!("{:?}", n.value)),
+            Node::LiteralIdent(n) => Some(format!("${}", self.resolve_ident(n.name()))),
+
+            Node::ArithmeticInfixExpr(n) => key(&format!("{:?}", n.op), &[&n.lhs, &n.rhs]),
+            Node::ArithmeticPrefixExpr(n) => key("Neg", &[&n.rhs]),
+            Node::BitwiseInfixExpr(n) => key(&format!("{:?}", n.op), &[&n.lhs, &n.rhs]),
+            Node::BitwiseNot(n) => key("BitNot", &[&n.rhs]),
+            Node::LogicalExpr(n) => key(&format!("{:?}", n.op), &[&n.lhs, &n.rhs]),
+            Node::LogicalNot(n) => key("Not", &[&n.rhs]),
+            Node::ComparisonExpr(n) if n.chain.is_empty() => {
+                key(&format!("{:?}", n.op), &[&n.lhs, &n.rhs])
+            }
+            Node::MatchExpr(n) => key(&format!("{:?}", n.op), &[&n.lhs, &n.rhs]),
+            Node::CastExpr(n) => key(&format!("Cast{:?}", n.type_name), &[&n.expr]),
+            Node::IsExpr(n) => key(&format!("Is{:?}", n.type_name), &[&n.expr]),
+            Node::RangeExpr(n) => key("Range", &[&n.start, &n.end]),
+
+            Node::IndexingExpr(n) => {
+                let mut children = vec![&n.base];
+                let mut name = "Index".to_string();
+                for (index, nil_safe) in &n.path {
+                    children.push(index);
+                    name.push(if *nil_safe { '?' } else { '.' });
+                }
+                key(&name, &children)
+            }
+
+            Node::Array(n) => key("Array", &n.elements.iter().collect::<Vec<_>>()),
+            Node::Object(n) => key(
+                "Object",
+                &n.elements
+                    .iter()
+                    .flat_map(|(k, v)| [k, v])
+                    .collect::<Vec<_>>(),
+            ),
+
+            _ => None,
+        }
+    }
+
+    /// Returns true if the result of the node can be kept in a variable and reused
+    /// Literals and identifiers are cheaper to repeat, and indexing results in a reference
+    fn is_reusable(node: &Node<'_>) -> bool {
+        matches!(
+            node,
+            Node::ArithmeticInfixExpr(_)
+                | Node::ArithmeticPrefixExpr(_)
+                | Node::BitwiseInfixExpr(_)
+                | Node::BitwiseNot(_)
+                | Node::LogicalExpr(_)
+                | Node::LogicalNot(_)
+                | Node::ComparisonExpr(_)
+                | Node::MatchExpr(_)
+                | Node::CastExpr(_)
+                | Node::IsExpr(_)
+                | Node::RangeExpr(_)
+        )
+    }
+
+    /// Find the reusable subexpressions that are repeated, in the order they are compiled
+    /// Copies of a repeated subexpression are not searched, since they will not be compiled
+    fn find_repeated(
+        &self,
+        node: &Node<'_>,
+        seen: &mut HashSet<String>,
+        repeated: &mut HashSet<String>,
+    ) {
+        if Self::is_reusable(node) {
+            if let Some(key) = self.subexpression_key(node) {
+                if seen.contains(&key) {
+                    repeated.insert(key);
+                    return;
+                }
+                seen.insert(key);
+            }
+        }
+
+        for child in node.children() {
+            self.find_repeated(child, seen, repeated);
+        }
+    }
+
+    /// Reserve a unique name for a hidden variable, which cannot clash with any user variable
+    pub fn hidden_variable(&mut self, kind: &str) -> String {
+        self.cse.counter += 1;
+        format!("${kind}:{}", self.cse.counter)
+    }
+
+    /// Compile a node, reusing the results of repeated subexpressions
+    /// Returns the node back if it should be compiled as normal
+    pub fn eliminate_subexpressions<'source>(
+        &mut self,
+        node: Node<'source>,
+    ) -> Result<Option<Node<'source>>, CompilerError> {
+        if !self.options().common_subexpressions || node.children().is_empty() {
+            return Ok(Some(node));
+        }
+
+        // Inside a pure expression; reuse the result if it was already computed
+        if let Some(region) = &self.cse.region {
+            if region.repeated.is_empty() || !Self::is_reusable(&node) {
+                return Ok(Some(node));
+            }
+
+            let key = match self.subexpression_key(&node) {
+                Some(key) if region.repeated.contains(&key) => key,
+                _ => return Ok(Some(node)),
+            };
+
+            if let Some(name) = region.computed.get(&key) {
+                let name = name.clone();
+                self.push(OpCode::REF);
+                self.push_strhash(&name);
+                self.push(OpCode::RREF);
+                return Ok(None);
+            }
+
+            let name = self.hidden_variable("cse");
+            node.compile_node(self)?;
+            self.push(OpCode::REF);
+            self.push_strhash(&name);
+            self.push(OpCode::WREF);
+
+            if let Some(region) = &mut self.cse.region {
+                region.computed.insert(key, name);
+            }
+            return Ok(None);
+        }
+
+        // Start of a pure expression; look for repeated subexpressions
+        if self.subexpression_key(&node).is_none() {
+            return Ok(Some(node));
+        }
+        let mut repeated = HashSet::new();
+        self.find_repeated(&node, &mut HashSet::new(), &mut repeated);
+
+        self.cse.region = Some(Region {
+            repeated,
+            computed: HashMap::new(),
+        });
+        let result = node.compile_node(self);
+        let region = self.cse.region.take().unwrap_or_default();
+        result?;
+
+        // Clean up the hidden variables
+        for name in region.computed.values() {
+            self.push(OpCode::REF);
+            self.push_strhash(name);
+            self.push(OpCode::DREF);
+            self.push(OpCode::POP);
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        compiler::{asm_transcoder::ASMTranscoder, CompilerOptions},
+        Lavendeux,
+    };
+
+    /// Compile a script, returning the number of `ADD` instructions in it, and its value
+    fn count_adds(source: &str) -> (usize, String) {
+        let mut lav = Lavendeux::new();
+        let (profile, bytecode) = lav.compile(source).unwrap();
+        let asm = ASMTranscoder::new(&bytecode, Some(profile.clone())).disassemble_as_string();
+        let adds = asm.lines().filter(|line| line.trim() == "ADD").count();
+
+        let value = lav.execute(bytecode, Some(profile)).unwrap();
+        let mut lav = Lavendeux::with_options(CompilerOptions {
+            common_subexpressions: false,
+            ..Default::default()
+        });
+        assert_eq!(value, lav.run(source).unwrap(), "{source}");
+
+        let lines = value.cast_array().unwrap();
+        (adds, lines.last().unwrap().to_string())
+    }
+
+    #[test]
+    fn test_common_subexpressions() {
+        // A repeated pure subexpression is computed once
+        let (adds, value) = count_adds("a = 2; b = 3\nx = (a + b) * (a + b) - (a + b)");
+        assert_eq!((adds, value.as_str()), (1, "20"));
+
+        let (adds, value) = count_adds("a = 2; b = 3\n[(a + b) * 2, -(a + b), (b + a)]");
+        assert_eq!((adds, value.as_str()), (2, "[10, -5, 5]"));
+
+        // An assignment or call between the uses could change the result,
+        // so both copies are computed, as well as the outer addition
+        let (adds, value) = count_adds("a = 2; b = 3\nx = (a + b) * (a = 10) + (a + b)");
+        assert_eq!((adds, value.as_str()), (3, "63"));
+
+        let (adds, value) = count_adds("a = 2; b = 3\nf() = 1\nx = (a + b) * f() + (a + b)");
+        assert_eq!((adds, value.as_str()), (3, "10"));
+    }
+}
@@ -1,13 +1,16 @@
 use crate::{
     lexer::{SerializedToken, Token},
-    traits::{SafeVecAlloc, SerializeToBytes},
+    traits::{ByteReader, SafeVecAlloc, SerializeToBytes},
 };
+use std::collections::BTreeMap;
 
 /// Maps ranges in bytecode to source code locations.
+/// Also holds the names behind the hashes in the bytecode, for disassembly and error messages
 #[derive(Debug, Clone)]
 pub struct DebugProfile {
     sources: Vec<(String, String)>,
     map: Vec<(usize, SerializedToken)>,
+    names: BTreeMap<u64, String>,
 }
 
 impl DebugProfile {
@@ -16,6 +19,7 @@ impl DebugProfile {
         Self {
             sources: vec![(String::new(), input.to_string())],
             map: Vec::new(),
+            names: BTreeMap::new(),
         }
     }
 
@@ -35,6 +39,11 @@ impl DebugProfile {
         }
     }
 
+    /// Get the source code the profile was created from
+    pub fn source(&self) -> &str {
+        &self.sources[0].1
+    }
+
     fn unpack(&self, token: &SerializedToken) -> Token<'_> {
         let source = self.get_source(token.filename.as_deref()).unwrap();
         SerializedToken::unpack(token, source)
@@ -73,6 +82,30 @@ impl DebugProfile {
         }
     }
 
+    /// Move every token to a new position in the bytecode
+    /// Used when instructions are removed after compilation.
+    /// The mapping must preserve the order of positions.
+    pub fn remap(&mut self, f: impl Fn(usize) -> usize) {
+        for (start, _) in &mut self.map {
+            *start = f(*start);
+        }
+    }
+
+    /// Record the name behind a hash in the bytecode
+    pub fn insert_name(&mut self, hash: u64, name: &str) {
+        self.names.entry(hash).or_insert_with(|| name.to_string());
+    }
+
+    /// Get the name behind a hash in the bytecode, if it is known
+    pub fn name(&self, hash: u64) -> Option<&str> {
+        self.names.get(&hash).map(String::as_str)
+    }
+
+    /// Iterate over every known hash, and the name behind it
+    pub fn names(&self) -> impl Iterator<Item = (u64, &str)> {
+        self.names.iter().map(|(hash, name)| (*hash, name.as_str()))
+    }
+
     /// Get the token at the given index.
     pub fn current_token(&self, index: usize) -> Option<Token<'_>> {
         // Search the map, returning the last token that starts before the index.
@@ -124,11 +157,18 @@ impl SerializeToBytes for DebugProfile {
             bytes.extend_from_slice(&token.serialize_into_bytes());
         }
 
+        // Serialize name table, in order of hash
+        bytes.extend_from_slice(&self.names.len().serialize_into_bytes());
+        for (hash, name) in self.names {
+            bytes.extend_from_slice(&hash.serialize_into_bytes());
+            bytes.extend_from_slice(&name.serialize_into_bytes());
+        }
+
         bytes
     }
 
     fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
+        bytes: &mut ByteReader<'_>,
     ) -> Result<Self, crate::traits::ByteDecodeError> {
         let nsources = usize::deserialize_from_bytes(bytes)?;
         let mut sources = Vec::safe_alloc(nsources)?;
@@ -146,6 +186,18 @@ impl SerializeToBytes for DebugProfile {
             map.push((start, token));
         }
 
-        Ok(Self { sources, map })
+        let nnames = usize::deserialize_from_bytes(bytes)?;
+        let mut names = BTreeMap::new();
+        for _ in 0..nnames {
+            let hash = u64::deserialize_from_bytes(bytes)?;
+            let name = String::deserialize_from_bytes(bytes)?;
+            names.insert(hash, name);
+        }
+
+        Ok(Self {
+            sources,
+            map,
+            names,
+        })
     }
 }
@@ -1,7 +1,7 @@
-use crate::lexer::Token;
+use crate::{lexer::Token, value::ValueType};
 
 /// Error during compilation.
-#[derive(thiserror::Error, Debug, Clone)]
+#[derive(thiserror::Error, Debug, Clone, strum_macros::IntoStaticStr)]
 pub enum CompilerError {
     /// Error node found in the AST
     #[error("{0}")]
@@ -26,4 +26,146 @@ pub enum CompilerError {
     /// Error compiling a special function call
     #[error("{0}\n= {1}() expects {2} arguments, found {3}")]
     InvalidArgumentCount(Token<'static>, String, usize, usize),
+
+    /// break or continue used outside of a loop
+    #[error("{0}\n= `break` and `continue` can only be used inside a loop")]
+    NotInLoop(Token<'static>),
+
+    /// break or continue referring to a label that does not exist
+    #[error("{0}\n= No enclosing loop is labelled `@{1}`")]
+    UnknownLoopLabel(Token<'static>, String),
+
+    /// An alias that expands into itself
+    #[error("{0}\n= Alias `{1}` is recursive, or nested too deeply")]
+    AliasRecursion(Token<'static>, String),
+
+    /// A spread argument passed to an alias, which needs its arguments at compile-time
+    #[error("{0}\n= Alias `{1}` cannot be called with spread arguments")]
+    SpreadIntoAlias(Token<'static>, String),
+
+    /// An assignment to a constant, or to an index into one
+    #[error("{0}\n= `{1}` is a constant, and cannot be reassigned")]
+    ConstantAssignment(Token<'static>, String),
+
+    /// A type name that is neither built in, nor declared with `type`
+    #[error("{0}\n= No type named `{1}` has been declared")]
+    UnknownType(Token<'static>, String),
+
+    /// Indexing into a value that can never be indexed
+    #[error("{0}\n= Cannot index into a value of type {1}")]
+    NotIndexable(Token<'static>, ValueType),
+
+    /// Calling a name that holds a value other than a function
+    #[error("{0}\n= `{1}` is {2}, not a function")]
+    NotAFunction(Token<'static>, String, ValueType),
+
+    /// Calling a function with an argument of the wrong type
+    #[error("{0}\n= Argument {2} of {1}() expects {3}, found {4}")]
+    InvalidArgumentType(Token<'static>, String, usize, ValueType, ValueType),
+
+    /// A function defined in more than one namespace, such as by two included files
+    /// Holds the qualified name and location of the existing definition
+    #[error("{0}\n= `{1}` collides with `{}`, defined at {}", .2 .0, .2 .1)]
+    FunctionCollision(Token<'static>, String, Box<(String, Token<'static>)>),
+
+    /// Two names with the same hash, which would share a slot in memory
+    #[error("{0}\n= `{}` and `{}` have the same hash, and cannot be used in the same program\n= Rename one of them", .1 .0, .1 .1)]
+    NameCollision(Token<'static>, Box<(String, String)>),
+
+    /// A name declared both `global` and `local` in the same function, or at the top level of the same script
+    #[error("{0}\n= `{1}` is declared both `global` and `local` here\n= Rename one of them")]
+    ConflictingDeclaration(Token<'static>, String),
+}
+
+/// A problem found during compilation that does not stop the program from compiling
+#[derive(thiserror::Error, Debug, Clone, strum_macros::IntoStaticStr)]
+pub enum CompilerWarning {
+    /// `-` used on a string, which removes every copy of the right operand from the left one
+    #[error("{0}\n= `-` on a string removes every copy of the right operand\n= Use `remove(s, substring)` to make this clear")]
+    StringSubtraction(Token<'static>),
+
+    /// Unary `-` used on a string, which reverses it
+    #[error("{0}\n= `-` on a string reverses it\n= Use `reverse(s)` to make this clear")]
+    StringNegation(Token<'static>),
+
+    /// An expression whose order of operations may be surprising, such as `a & b == c`
+    #[error("{0}\n= This is evaluated as `{1}`\n= Add parentheses to make the order clear")]
+    AmbiguousPrecedence(Token<'static>, String),
+
+    /// A loop variable with the same name as an existing variable, which the loop overwrites
+    #[error("{0}\n= The loop variable `{}` overwrites the existing variable of the same name:\n{1}\n= Rename the loop variable if the original value is still needed", .0.slice())]
+    LoopVariableShadows(Token<'static>, Token<'static>),
+
+    /// A function argument with the same name as a variable outside the function
+    #[error("{0}\n= The argument `{}` hides the variable of the same name:\n{1}\n= Functions can only see their arguments; rename one of them to make this clear", .0.slice())]
+    ArgumentShadows(Token<'static>, Token<'static>),
+
+    /// A variable used after the loop it was first assigned in, which discarded it
+    #[error("{0}\n= `{}` was first assigned inside a loop, and discarded when the loop ended:\n{1}\n= Assign it before the loop to keep its value", .0.slice())]
+    DiscardedVariable(Token<'static>, Token<'static>),
+}
+
+impl CompilerWarning {
+    /// Get the token that caused the warning.
+    pub fn token(&self) -> &Token<'static> {
+        match self {
+            CompilerWarning::StringSubtraction(token) => token,
+            CompilerWarning::StringNegation(token) => token,
+            CompilerWarning::AmbiguousPrecedence(token, _) => token,
+            CompilerWarning::LoopVariableShadows(token, _) => token,
+            CompilerWarning::ArgumentShadows(token, _) => token,
+            CompilerWarning::DiscardedVariable(token, _) => token,
+        }
+    }
+}
+
+impl CompilerError {
+    /// Get the token that caused the error.
+    pub fn token(&self) -> &Token<'static> {
+        match self {
+            CompilerError::Parser(e) => e.token(),
+            CompilerError::InvalidSyscallOpcode(token, _) => token,
+            CompilerError::InvalidInclude(token) => token,
+            CompilerError::FileNotFound(token, _) => token,
+            CompilerError::IncludeError(token, _) => token,
+            CompilerError::InvalidArgumentCount(token, _, _, _) => token,
+            CompilerError::NotInLoop(token) => token,
+            CompilerError::UnknownLoopLabel(token, _) => token,
+            CompilerError::AliasRecursion(token, _) => token,
+            CompilerError::SpreadIntoAlias(token, _) => token,
+            CompilerError::ConstantAssignment(token, _) => token,
+            CompilerError::UnknownType(token, _) => token,
+            CompilerError::NotIndexable(token, _) => token,
+            CompilerError::NotAFunction(token, _, _) => token,
+            CompilerError::InvalidArgumentType(token, _, _, _, _) => token,
+            CompilerError::FunctionCollision(token, _, _) => token,
+            CompilerError::NameCollision(token, _) => token,
+            CompilerError::ConflictingDeclaration(token, _) => token,
+        }
+    }
+
+    /// Get the stable code identifying the kind of error, such as `E0205` for `break` outside of a loop
+    /// Compiler errors use codes from `E0200` to `E0299`; parser errors keep their own codes
+    pub fn code(&self) -> &'static str {
+        match self {
+            CompilerError::Parser(e) => e.code(),
+            CompilerError::InvalidSyscallOpcode(_, _) => "E0200",
+            CompilerError::InvalidInclude(_) => "E0201",
+            CompilerError::FileNotFound(_, _) => "E0202",
+            CompilerError::IncludeError(_, _) => "E0203",
+            CompilerError::InvalidArgumentCount(_, _, _, _) => "E0204",
+            CompilerError::NotInLoop(_) => "E0205",
+            CompilerError::UnknownLoopLabel(_, _) => "E0206",
+            CompilerError::AliasRecursion(_, _) => "E0207",
+            CompilerError::SpreadIntoAlias(_, _) => "E0208",
+            CompilerError::ConstantAssignment(_, _) => "E0209",
+            CompilerError::UnknownType(_, _) => "E0210",
+            CompilerError::NotIndexable(_, _) => "E0211",
+            CompilerError::NotAFunction(_, _, _) => "E0212",
+            CompilerError::InvalidArgumentType(_, _, _, _, _) => "E0213",
+            CompilerError::FunctionCollision(_, _, _) => "E0214",
+            CompilerError::NameCollision(_, _) => "E0215",
+            CompilerError::ConflictingDeclaration(_, _) => "E0216",
+        }
+    }
 }
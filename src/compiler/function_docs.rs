@@ -1,5 +1,5 @@
 use crate::{
-    traits::{ByteDecodeError, SerializeToBytes},
+    traits::{ByteDecodeError, ByteReader, SerializeToBytes},
     value::{Primitive, Value},
 };
 
@@ -167,9 +167,7 @@ impl SerializeToBytes for FunctionDocs {
         bytes
     }
 
-    fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
-    ) -> Result<Self, ByteDecodeError> {
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
         let name = String::deserialize_from_bytes(bytes)?;
         let args = Vec::<String>::deserialize_from_bytes(bytes)?;
         let category = String::deserialize_from_bytes(bytes)?;
@@ -0,0 +1,319 @@
+//! Inlining of small user functions
+//! Calls to a function are replaced with its body when the function:
+//! - Is defined once, at the top level of the script, and never used as a value
+//! - Has no argument types, defaults or references, and no return type
+//! - Has a body that only refers to its own arguments, with no `return`, `break` or `continue`
+//! - Compiles to no more than `CompilerOptions::inline_threshold` bytes
+//!
+//! Arguments are evaluated once, into hidden variables in a new scope, so the
+//! inlined body behaves the same as a call, without the cost of a new context
+use super::{Compiler, CompilerError, LoopCompilationExt};
+use crate::{
+    parser::{functions::FnAssignNode, Node, Visitor},
+    traits::IntoOwned,
+    vm::OpCode,
+};
+use std::collections::{HashMap, HashSet};
+
+/// A function that can be inlined
+#[derive(Debug, Clone)]
+struct InlineFunction {
+    args: Vec<String>,
+    body: Node<'static>,
+}
+
+/// State of the inlining pass
+#[derive(Debug, Clone, Default)]
+pub(super) struct InlineState {
+    // Functions that can be inlined, by name
+    functions: HashMap<String, InlineFunction>,
+
+    // Functions that must never be inlined, because they are redefined or used as values
+    blocked: HashSet<String>,
+
+    // Set if the script includes other files, which could redefine anything
+    disabled: bool,
+
+    // Functions currently being inlined, with the hidden names of their arguments
+    stack: Vec<(String, HashMap<String, String>)>,
+
+    // Used to give each inlined call's arguments unique names
+    counter: usize,
+}
+
+/// Finds the names of functions that are unsafe to inline
+#[derive(Default)]
+struct Scanner {
+    definitions: HashMap<String, usize>,
+    identifiers: HashSet<String>,
+    has_include: bool,
+}
+
+impl<'source> Visitor<'source> for Scanner {
+    fn enter(&mut self, node: &Node<'source>) -> bool {
+        match node {
+            Node::FnAssign(f) => {
+                let name = f.token.input()[f.name_span.clone()].to_string();
+                *self.definitions.entry(name).or_default() += 1;
+            }
+            Node::LiteralIdent(ident) => {
+                self.identifiers.insert(ident.name().to_string());
+            }
+            Node::FnCall(call) if &call.token.input()[call.name_span.clone()] == "include" => {
+                self.has_include = true;
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Checks that a function body is safe to inline
+struct BodyCheck<'a> {
+    name: &'a str,
+    args: &'a [String],
+    ok: bool,
+}
+
+impl<'source> Visitor<'source> for BodyCheck<'_> {
+    fn enter(&mut self, node: &Node<'source>) -> bool {
+        match node {
+            Node::Return(_)
+            | Node::Break(_)
+            | Node::Continue(_)
+            | Node::FnAssign(_)
+            | Node::Alias(_)
+            | Node::VarDef(_)
+            | Node::Let(_)
+            | Node::Error(_) => self.ok = false,
+
+            Node::LiteralIdent(ident) if !self.args.iter().any(|a| a == ident.name()) => {
+                self.ok = false
+            }
+
+            Node::FnCall(call) if &call.token.input()[call.name_span.clone()] == self.name => {
+                self.ok = false
+            }
+
+            // Calling an argument means it holds a function, which an inlined body cannot see
+            Node::FnCall(call)
+                if self
+                    .args
+                    .iter()
+                    .any(|a| *a == call.token.input()[call.name_span.clone()]) =>
+            {
+                self.ok = false
+            }
+
+            _ => {}
+        }
+        self.ok
+    }
+}
+
+impl Compiler {
+    /// Scan a script before it is compiled, to find functions that cannot be inlined
+    pub fn scan_for_inlining(&mut self, lines: &[Node<'_>]) {
+        let mut scanner = Scanner::default();
+        for line in lines {
+            line.walk(&mut scanner);
+        }
+
+        // Only functions defined at the top level are always defined when called
+        let top_level = lines
+            .iter()
+            .filter_map(|line| match line {
+                Node::FnAssign(f) => Some(f.token.input()[f.name_span.clone()].to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (name, count) in scanner.definitions {
+            if count > 1 || !top_level.contains(&name) || scanner.identifiers.contains(&name) {
+                self.inlining.blocked.insert(name);
+            }
+        }
+
+        if scanner.has_include {
+            self.inlining.disabled = true;
+        }
+    }
+
+    /// Register a function as a candidate for inlining, if it is small and simple enough
+    pub fn consider_inlining(&mut self, function: &FnAssignNode<'_>) {
+        let input = function.token.input();
+        let name = &input[function.name_span.clone()];
+        let decorated = function.token.span().start < function.name_span.start;
+        if self.options().inline_threshold == 0
+            || self.inlining.disabled
+            || self.inlining.blocked.contains(name)
+            || decorated
+            || function.returns.is_some()
+        {
+            return;
+        }
+
+        let mut args = vec![];
+        for (arg, ty, default, by_ref) in &function.args {
+            if ty.is_some() || default.is_some() || *by_ref {
+                return;
+            }
+            args.push(input[arg.clone()].to_string());
+        }
+
+        let mut check = BodyCheck {
+            name,
+            args: &args,
+            ok: true,
+        };
+        function.body.walk(&mut check);
+        if !check.ok {
+            return;
+        }
+
+        // Measure the size of the body
+        let mut options = self.options().clone();
+        options.debug = false;
+        options.inline_threshold = 0;
+        let mut scratch = Compiler::new("", options);
+        if function.body.clone().compile(&mut scratch).is_err()
+            || scratch.len() > self.options().inline_threshold
+        {
+            return;
+        }
+
+        self.inlining.functions.insert(
+            name.to_string(),
+            InlineFunction {
+                args,
+                body: function.body.clone().into_owned(),
+            },
+        );
+    }
+
+    /// Returns true if a call to the function can be inlined
+    pub fn can_inline(&self, name: &str, n_args: usize) -> bool {
+        match self.inlining.functions.get(name) {
+            Some(f) => {
+                f.args.len() == n_args && !self.inlining.stack.iter().any(|(n, _)| n == name)
+            }
+            None => false,
+        }
+    }
+
+    /// Compile a call to a function by inlining its body
+    /// Check `can_inline` first
+    pub fn compile_inline(&mut self, name: &str, args: Vec<Node<'_>>) -> Result<(), CompilerError> {
+        let function = self.inlining.functions[name].clone();
+
+        // Arguments are evaluated in the caller's scope, like a normal call
+        for value in args {
+            value.compile(self)?;
+        }
+
+        // Then written to hidden variables, in a new scope
+        self.push_scope_in();
+        let mut names = HashMap::new();
+        for arg in function.args.into_iter().rev() {
+            self.inlining.counter += 1;
+            let hidden = format!("${}:{arg}", self.inlining.counter);
+
+            self.push(OpCode::REF);
+            self.push_strhash(&hidden);
+            self.push(OpCode::WREF);
+            self.push(OpCode::POP);
+
+            names.insert(arg, hidden);
+        }
+
+        self.inlining.stack.push((name.to_string(), names));
+        let result = function.body.compile(self);
+        self.inlining.stack.pop();
+        result?;
+
+        self.push(OpCode::RREF);
+        self.push_scope_out();
+        Ok(())
+    }
+
+    /// Get the name to use for an identifier
+    /// Inside an inlined function, arguments are renamed to their hidden variables
+    pub fn resolve_ident<'a>(&'a self, name: &'a str) -> &'a str {
+        match self.inlining.stack.last() {
+            Some((_, names)) => names.get(name).map(String::as_str).unwrap_or(name),
+            None => name,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        compiler::{asm_transcoder::ASMTranscoder, CompilerOptions},
+        Lavendeux,
+    };
+
+    /// Disassemble the top level of a script, leaving out the bodies of its functions
+    fn disassemble(lav: &mut Lavendeux, source: &str) -> String {
+        let (profile, bytecode) = lav.compile(source).unwrap();
+        let asm = ASMTranscoder::new(&bytecode, Some(profile)).disassemble_as_string();
+        match asm.split_once("; fn ") {
+            Some((script, _)) => script.to_string(),
+            None => asm,
+        }
+    }
+
+    #[test]
+    fn test_inlining() {
+        // The call is replaced by the body, in its own scope
+        let source = "sq(x) = x * x\nsq(3)";
+        let inlined = disassemble(&mut Lavendeux::new(), source);
+        assert!(
+            !inlined.contains("CALL") && inlined.contains("SCI"),
+            "{inlined}"
+        );
+
+        let mut lav = Lavendeux::with_options(CompilerOptions {
+            inline_threshold: 0,
+            ..Default::default()
+        });
+        let called = disassemble(&mut lav, source);
+        assert!(
+            called.contains("CALL") && !called.contains("SCI"),
+            "{called}"
+        );
+        assert_eq!(
+            Lavendeux::new().run(source).unwrap(),
+            lav.run(source).unwrap()
+        );
+
+        for source in [
+            "f(n) = n < 2 ? n : f(n - 1)\nf(3)",
+            "f(ref x) = x + 1\ny = 1\nf(y)",
+            "f(x: int) = x + 1\nf(1)",
+            "f(x = 2) = x + 1\nf(1)",
+            "f(x): int = x + 1\nf(1)",
+            "f(x) = x + 1\ng = f\nf(1)",
+            "f(x) = x + y\ny = 1\nf(1)",
+        ] {
+            let asm = disassemble(&mut Lavendeux::new(), source);
+            assert!(asm.contains("CALL"), "{source}\n{asm}");
+        }
+    }
+
+    #[test]
+    fn test_inlined_arguments() {
+        // Arguments are renamed, so they neither read nor overwrite the caller's variables
+        let mut lav = Lavendeux::new();
+        let source = "a = 10; b = 1; x = 100\n\
+            f(a, b) = a - b\n\
+            g(x) = x + 1\n\
+            h(x) = g(x * 2)\n\
+            [f(b, a), h(3), a, b, x]";
+        let asm = disassemble(&mut lav, source);
+        assert!(!asm.contains("CALL"), "{asm}");
+
+        let lines = lav.run(source).unwrap().cast_array().unwrap();
+        assert_eq!(lines.last().unwrap().to_string(), "[-9, 7, 10, 1, 100]");
+    }
+}
@@ -0,0 +1,182 @@
+//! Operator precedence lint
+//! Warns about expressions where the order of operations commonly surprises users,
+//! unless the inner expression is already wrapped in parentheses:
+//! - Bitwise `&`, `|` and `^` combined with a comparison, as in `a & b == c`, which is `a & (b == c)`
+//! - Negation raised to a power, as in `-2 ** 2`, which is `(-2) ** 2`
+//! - Shifts combined with arithmetic, as in `1 << n - 1`, which is `1 << (n - 1)`
+//!
+//! Parentheses are not kept in the AST, so they are found in the source around each expression
+use super::{Compiler, CompilerWarning};
+use crate::{
+    lexer::TokenSpan,
+    parser::{arithmetic::ArithmeticOp, bitwise::BitwiseOp, Node, Visitor},
+    traits::IntoOwned,
+};
+
+/// Collects a warning for each ambiguous expression
+#[derive(Default)]
+struct PrecedenceLint {
+    warnings: Vec<CompilerWarning>,
+}
+
+impl PrecedenceLint {
+    /// Warn if `inner`, an operand of `outer`, is not in parentheses
+    fn check(&mut self, outer: &Node<'_>, inner: &Node<'_>) {
+        let input = outer.token().input();
+        let inner_span = balanced(input, inner.token().span());
+        if is_parenthesized(input, &inner_span) {
+            return;
+        }
+
+        // Suggest the same expression, with the inner one in parentheses
+        let outer_span = balanced(input, outer.token().span());
+        let suggestion = format!(
+            "{}({}){}",
+            &input[outer_span.start..inner_span.start],
+            &input[inner_span.clone()],
+            &input[inner_span.end..outer_span.end]
+        );
+
+        self.warnings.push(CompilerWarning::AmbiguousPrecedence(
+            outer.token().clone().into_owned(),
+            suggestion,
+        ));
+    }
+}
+
+impl<'source> Visitor<'source> for PrecedenceLint {
+    fn enter(&mut self, node: &Node<'source>) -> bool {
+        match node {
+            Node::BitwiseInfixExpr(n) => {
+                let surprising: fn(&Node<'_>) -> bool = match n.op {
+                    BitwiseOp::And | BitwiseOp::Or | BitwiseOp::Xor => {
+                        |n| matches!(n, Node::ComparisonExpr(_))
+                    }
+                    BitwiseOp::ShiftLeft | BitwiseOp::ShiftRight => {
+                        |n| matches!(n, Node::ArithmeticInfixExpr(_))
+                    }
+                };
+
+                for operand in [&n.lhs, &n.rhs] {
+                    if surprising(operand) {
+                        self.check(node, operand);
+                    }
+                }
+            }
+
+            Node::ArithmeticInfixExpr(n)
+                if matches!(n.op, ArithmeticOp::Pow)
+                    && matches!(n.lhs, Node::ArithmeticPrefixExpr(_)) =>
+            {
+                self.check(node, &n.lhs);
+            }
+
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Extend a span to include the other half of any parentheses it only partly contains,
+/// such as the `(` before `a) + b`
+fn balanced(input: &str, span: TokenSpan) -> TokenSpan {
+    let bytes = input.as_bytes();
+    let (mut depth, mut lowest) = (0isize, 0isize);
+    for byte in &bytes[span.clone()] {
+        match byte {
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            _ => continue,
+        }
+        lowest = lowest.min(depth);
+    }
+
+    let (mut start, mut open) = (span.start, -lowest);
+    while open > 0 && start > 0 {
+        start -= 1;
+        match bytes[start] {
+            b'(' => open -= 1,
+            b')' => open += 1,
+            _ => {}
+        }
+    }
+
+    let (mut end, mut close) = (span.end, depth - lowest);
+    while close > 0 && end < bytes.len() {
+        match bytes[end] {
+            b')' => close -= 1,
+            b'(' => close += 1,
+            _ => {}
+        }
+        end += 1;
+    }
+
+    start..end
+}
+
+/// Check if a span is directly wrapped in parentheses
+fn is_parenthesized(input: &str, span: &TokenSpan) -> bool {
+    let before = input[..span.start].trim_end();
+    let after = input[span.end..].trim_start();
+    before.ends_with('(') && after.starts_with(')')
+}
+
+impl Compiler {
+    /// Warn about expressions whose order of operations may be surprising
+    /// Only runs if `CompilerOptions::lint_precedence` is set
+    pub fn lint_precedence(&mut self, lines: &[Node<'_>]) {
+        if !self.options().lint_precedence {
+            return;
+        }
+
+        let mut lint = PrecedenceLint::default();
+        for line in lines {
+            line.walk(&mut lint);
+        }
+        for warning in lint.warnings {
+            self.warn(warning);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{compiler::CompilerOptions, Lavendeux};
+
+    #[test]
+    fn test_precedence_lint() {
+        let mut lav = Lavendeux::new();
+        lav.run("a = 6\nb = 2\nc = 1").unwrap();
+        for (source, suggestion) in [
+            ("a & b == c", "a & (b == c)"),
+            ("a == b | c", "(a == b) | c"),
+            ("x = (a) ^ b != c", "(a) ^ (b != c)"),
+            ("-2**2", "(-2)**2"),
+            ("abs(-a ** 2)", "(-a) ** 2"),
+            ("1 << a - 1", "1 << (a - 1)"),
+            ("a * 2 >> 1", "(a * 2) >> 1"),
+        ] {
+            let report = lav.run_report(source).unwrap();
+            assert_eq!(report.warnings.len(), 1, "{source}");
+            assert!(report.warnings[0].contains(suggestion), "{source}");
+        }
+
+        for source in [
+            "a & (b == c)",
+            "(a & b) == c",
+            "(-2)**2",
+            "-(2**2)",
+            "1 << (a - 1)",
+            "a + b * c",
+        ] {
+            let report = lav.run_report(source).unwrap();
+            assert!(report.warnings.is_empty(), "{source}");
+        }
+
+        let mut lav = Lavendeux::with_options(CompilerOptions {
+            lint_precedence: false,
+            ..Default::default()
+        });
+        assert!(lav.run_report("-2**2").unwrap().warnings.is_empty());
+    }
+}
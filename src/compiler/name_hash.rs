@@ -0,0 +1,110 @@
+//! Hashing of variable and function names
+//! The VM addresses names by a 64bit hash, written into the bytecode by the compiler, so the
+//! hash must never change; compiled modules and snapshots would silently stop matching
+//!
+//! Names are hashed with SipHash-1-3, keyed with zeros. This is what `DefaultHasher` currently
+//! uses, but its algorithm is unspecified and may change between Rust releases, so it is
+//! implemented here instead
+//!
+//! The compiler checks that no two names in a program share a hash; see `Compiler::push_strhash`
+
+/// Qualify a name with its namespace, as `namespace::name`
+/// Names in the global namespace are left as they are
+pub fn qualified_name(namespace: &str, name: &str) -> String {
+    match namespace {
+        "" => name.to_string(),
+        _ => format!("{namespace}::{name}"),
+    }
+}
+
+/// Hash a string to a u64
+pub trait HashString {
+    /// Hash a string to a u64
+    fn hash_str(&self) -> u64;
+
+    /// Hash a name qualified by a namespace
+    /// The same as `hash_str` in the global namespace
+    fn hash_in(&self, namespace: &str) -> u64;
+}
+
+impl HashString for str {
+    fn hash_str(&self) -> u64 {
+        sip13(self.as_bytes())
+    }
+
+    fn hash_in(&self, namespace: &str) -> u64 {
+        qualified_name(namespace, self).hash_str()
+    }
+}
+
+/// SipHash-1-3 of a message, with a key of zeros
+fn sip13(bytes: &[u8]) -> u64 {
+    let mut v = [
+        0x736f6d6570736575u64,
+        0x646f72616e646f6du64,
+        0x6c7967656e657261u64,
+        0x7465646279746573u64,
+    ];
+
+    let mut words = bytes.chunks_exact(8);
+    for word in &mut words {
+        let m = u64::from_le_bytes(word.try_into().unwrap_or_default());
+        v[3] ^= m;
+        sip_round(&mut v);
+        v[0] ^= m;
+    }
+
+    // The last word holds the remaining bytes, and the length of the message in its top byte
+    let mut last = (bytes.len() as u64 & 0xff) << 56;
+    for (i, byte) in words.remainder().iter().enumerate() {
+        last |= (*byte as u64) << (8 * i);
+    }
+    v[3] ^= last;
+    sip_round(&mut v);
+    v[0] ^= last;
+
+    v[2] ^= 0xff;
+    for _ in 0..3 {
+        sip_round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13) ^ v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16) ^ v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21) ^ v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17) ^ v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{compiler::asm_transcoder::ASMTranscoder, Lavendeux};
+
+    #[test]
+    fn test_name_hashing() {
+        // Hashes are part of compiled bytecode, so the algorithm must never change
+        assert_eq!("x".hash_str(), 0xd141bba7fdc215a3);
+        assert_eq!("".hash_in("math"), "math::".hash_str());
+
+        // The debug profile names the hashes, for disassembly and errors
+        let mut lav = Lavendeux::new();
+        let (profile, bytecode) = lav.compile("total = 1\ntotal + missing").unwrap();
+        assert_eq!(profile.name("missing".hash_str()), Some("missing"));
+        let asm = ASMTranscoder::new(&bytecode, Some(profile.clone())).disassemble_as_string();
+        assert!(asm.contains("REF total"), "{asm}");
+
+        let error = lav.execute(bytecode, Some(profile)).unwrap_err();
+        assert!(
+            error.to_string().contains("Variable `missing` not defined"),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! Peephole optimization of compiled bytecode
+//! Removes instruction sequences that have no effect:
+//! - `PUSH` followed by `POP`
+//! - `DUP` followed by `POP`
+//! - `CAST` followed by a `CAST` to the same type (the second is removed)
+//!
+//! Then replaces common pairs of instructions with a superinstruction, saving a dispatch;
+//! see `OpCode::fuse` for the pairs
+//!
+//! A sequence is never changed if a jump lands inside it; jumps and the debug profile
+//! are updated to the new positions of their instructions
+use super::{
+    asm_transcoder::{ASMTranscoder, Instruction},
+    DebugProfile,
+};
+use crate::{
+    traits::SerializeToBytes,
+    vm::{jump_offset, OpCode},
+};
+use std::collections::BTreeSet;
+
+/// Counts of the instructions removed by the peephole optimizer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeepholeStats {
+    /// `PUSH`/`POP` pairs removed
+    pub push_pop: usize,
+
+    /// `DUP`/`POP` pairs removed
+    pub dup_pop: usize,
+
+    /// Repeated `CAST` instructions removed
+    pub double_cast: usize,
+
+    /// Pairs of instructions replaced with a superinstruction
+    pub fused: usize,
+
+    /// Total size of the removed instructions, in bytes
+    pub bytes_removed: usize,
+}
+
+impl PeepholeStats {
+    /// Add the counts from another set of stats, such as those for a function body
+    pub fn add(&mut self, other: &PeepholeStats) {
+        self.push_pop += other.push_pop;
+        self.dup_pop += other.dup_pop;
+        self.double_cast += other.double_cast;
+        self.fused += other.fused;
+        self.bytes_removed += other.bytes_removed;
+    }
+}
+
+impl std::fmt::Display for PeepholeStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} PUSH/POP, {} DUP/POP, {} repeated CAST removed, {} pairs fused ({} bytes)",
+            self.push_pop, self.dup_pop, self.double_cast, self.fused, self.bytes_removed
+        )
+    }
+}
+
+/// A change to make to a pair of adjacent instructions
+enum Rewrite {
+    /// Remove both instructions
+    Both,
+
+    /// Remove the second instruction
+    Second,
+
+    /// Replace both instructions with a superinstruction
+    Fuse(OpCode),
+}
+
+/// Get the opcode of an instruction that can be part of a superinstruction
+fn fusable_opcode(instruction: &Instruction) -> Option<OpCode> {
+    match instruction {
+        Instruction::Push(_) => Some(OpCode::PUSH),
+        Instruction::Simple(opcode)
+        | Instruction::Mem(opcode, _)
+        | Instruction::Jump(opcode, _) => Some(*opcode),
+        _ => None,
+    }
+}
+
+/// Find a rewrite for a pair of adjacent instructions
+fn rewrite(
+    first: &Instruction,
+    second: &Instruction,
+    stats: &mut PeepholeStats,
+) -> Option<Rewrite> {
+    match (first, second) {
+        (Instruction::Push(_), Instruction::Simple(OpCode::POP)) => {
+            stats.push_pop += 1;
+            Some(Rewrite::Both)
+        }
+
+        (Instruction::Simple(OpCode::DUP), Instruction::Simple(OpCode::POP)) => {
+            stats.dup_pop += 1;
+            Some(Rewrite::Both)
+        }
+
+        (Instruction::Cast(OpCode::CAST, a), Instruction::Cast(OpCode::CAST, b)) if a == b => {
+            stats.double_cast += 1;
+            Some(Rewrite::Second)
+        }
+
+        _ => {
+            let fused = OpCode::fuse(fusable_opcode(first)?, fusable_opcode(second)?)?;
+            stats.fused += 1;
+            Some(Rewrite::Fuse(fused))
+        }
+    }
+}
+
+/// Run the peephole optimizer over a block of bytecode, updating the debug profile to match
+/// Bytecode that cannot be decoded is left unchanged
+pub(super) fn optimize(
+    bytecode: &mut Vec<u8>,
+    debug: &mut DebugProfile,
+    stats: &mut PeepholeStats,
+) {
+    let instructions = match ASMTranscoder::new(bytecode, None).decode() {
+        Ok(instructions) => instructions,
+        Err(_) => return,
+    };
+
+    // Offset of each instruction
+    let mut offsets = Vec::with_capacity(instructions.len());
+    let mut offset = 0;
+    for (_, len) in &instructions {
+        offsets.push(offset);
+        offset += len;
+    }
+
+    // Positions that are the target of a jump
+    let mut targets = BTreeSet::new();
+    for (instruction, _) in &instructions {
+        match instruction {
+            Instruction::Jump(_, pos) => {
+                targets.insert(*pos as usize);
+            }
+            Instruction::JumpTable(_, default, table) => {
+                targets.insert(*default as usize);
+                targets.extend(table.iter().map(|pos| *pos as usize));
+            }
+            _ => {}
+        }
+    }
+
+    // Indices of the instructions that are kept, with the superinstruction and second
+    // instruction of any fused pair
+    // Removing a pair can make the instructions around it adjacent, so compare against the last kept
+    let mut kept: Vec<(usize, Option<(OpCode, usize)>)> = Vec::with_capacity(instructions.len());
+    for i in 0..instructions.len() {
+        if let Some(&(prev, None)) = kept.last() {
+            let jumped_into = targets
+                .range(offsets[prev] + 1..=offsets[i])
+                .next()
+                .is_some();
+            if !jumped_into {
+                match rewrite(&instructions[prev].0, &instructions[i].0, stats) {
+                    Some(Rewrite::Both) => {
+                        kept.pop();
+                        continue;
+                    }
+                    Some(Rewrite::Second) => continue,
+                    Some(Rewrite::Fuse(opcode)) => {
+                        kept.last_mut().unwrap().1 = Some((opcode, i));
+                        continue;
+                    }
+                    None => {}
+                }
+            }
+        }
+        kept.push((i, None));
+    }
+
+    if kept.len() == instructions.len() {
+        return;
+    }
+
+    // Size of each instruction in the output
+    // The second of a fused pair loses its opcode; removed instructions have no size
+    let mut sizes = vec![0; instructions.len()];
+    for (i, fused) in &kept {
+        sizes[*i] = instructions[*i].1;
+        if let Some((_, j)) = fused {
+            sizes[*j] = instructions[*j].1 - 1;
+        }
+    }
+
+    // New offset of each instruction; removed instructions map to the next kept instruction
+    let mut new_offsets = vec![0; instructions.len() + 1];
+    let mut new_offset = 0;
+    for (i, size) in sizes.iter().enumerate() {
+        new_offsets[i] = new_offset;
+        new_offset += size;
+    }
+    new_offsets[instructions.len()] = new_offset;
+
+    let remap = |pos: usize| new_offsets[offsets.partition_point(|o| *o < pos)];
+
+    // Jumps are relative to their own position in the output
+    let push_jump = |output: &mut Vec<u8>, pos: u64| {
+        let offset = jump_offset(output.len(), remap(pos as usize));
+        output.extend(offset.serialize_into_bytes());
+    };
+
+    // Rebuild the bytecode, moving jump targets
+    let mut output = Vec::with_capacity(new_offset);
+    for (i, fused) in kept {
+        let (instruction, len) = &instructions[i];
+        if let Some((opcode, j)) = fused {
+            // The operands of both instructions follow the superinstruction
+            output.push(opcode as u8);
+            output.extend_from_slice(&bytecode[offsets[i] + 1..offsets[i] + len]);
+            match &instructions[j] {
+                (Instruction::Jump(_, pos), _) => push_jump(&mut output, *pos),
+                (_, len) => output.extend_from_slice(&bytecode[offsets[j] + 1..offsets[j] + len]),
+            }
+            continue;
+        }
+
+        match instruction {
+            Instruction::Jump(opcode, pos) => {
+                output.push(*opcode as u8);
+                push_jump(&mut output, *pos);
+            }
+            Instruction::JumpTable(min, default, table) => {
+                output.push(OpCode::JTBL as u8);
+                output.extend(min.serialize_into_bytes());
+                output.extend((table.len() as u64).serialize_into_bytes());
+                push_jump(&mut output, *default);
+                for pos in table {
+                    push_jump(&mut output, *pos);
+                }
+            }
+            _ => output.extend_from_slice(&bytecode[offsets[i]..offsets[i] + len]),
+        }
+    }
+
+    stats.bytes_removed += bytecode.len() - output.len();
+    debug.remap(remap);
+    *bytecode = output;
+}
+
+#[cfg(test)]
+mod test {
+    use super::{optimize, PeepholeStats};
+    use crate::{
+        compiler::{
+            asm_transcoder::ASMTranscoder, assembler::assemble, CompilerOptions, DebugProfile,
+        },
+        vm::OpCode,
+        Lavendeux,
+    };
+
+    /// Reads one of the counts from a set of stats
+    type Count = fn(&PeepholeStats) -> usize;
+
+    /// Assemble a sequence and run the peephole optimizer over it
+    fn optimized(asm: &str) -> (Vec<u8>, PeepholeStats) {
+        let mut bytecode = assemble(asm).unwrap();
+        let mut stats = PeepholeStats::default();
+        optimize(&mut bytecode, &mut DebugProfile::new(asm), &mut stats);
+        (bytecode, stats)
+    }
+
+    #[test]
+    fn test_rewrites() {
+        // Each rewrite on its own, with the count it adds to
+        let cases: [(&str, &str, Count); 8] = [
+            ("PUSH 1\nPUSH 2\nPOP", "PUSH 1", |s| s.push_pop),
+            ("PUSH 1\nDUP\nPOP", "PUSH 1", |s| s.dup_pop),
+            ("PUSH 1\nCAST int\nCAST int", "PUSH 1\nCAST int", |s| {
+                s.double_cast
+            }),
+            ("PUSH 1\nPUSH 2\nADD", "PUSH 1\nPADD 2", |s| s.fused),
+            ("PUSH 3\nPUSH 2\nSUB", "PUSH 3\nPSUB 2", |s| s.fused),
+            ("REF a\nIDEX", "RIDX a", |s| s.fused),
+            (
+                "PUSH nil\nDUP\nJMPNN end\nend:",
+                "PUSH nil\nDJNN end\nend:",
+                |s| s.fused,
+            ),
+            (
+                "PUSH ``\nDUP\nJMPNE end\nend:",
+                "PUSH ``\nDJNE end\nend:",
+                |s| s.fused,
+            ),
+        ];
+        for (asm, expected, count) in cases {
+            let (bytecode, stats) = optimized(asm);
+            assert_eq!(bytecode, assemble(expected).unwrap(), "{asm}");
+            assert_eq!(count(&stats), 1, "{asm}");
+        }
+
+        // Removing a pair makes the instructions around it adjacent
+        let (bytecode, stats) = optimized("PUSH 1\nPUSH 2\nDUP\nPOP\nPOP");
+        assert_eq!(bytecode, assemble("PUSH 1").unwrap());
+        assert_eq!((stats.push_pop, stats.dup_pop), (1, 1));
+        assert_eq!(
+            stats.bytes_removed,
+            assemble("PUSH 2\nDUP\nPOP\nPOP").unwrap().len()
+        );
+
+        // Different casts are both kept
+        let (bytecode, stats) = optimized("PUSH 1\nCAST int\nCAST float");
+        assert_eq!(bytecode, assemble("PUSH 1\nCAST int\nCAST float").unwrap());
+        assert_eq!(stats, PeepholeStats::default());
+    }
+
+    #[test]
+    fn test_rewrite_jumps() {
+        // Jumps over and back across removed instructions still land on the same instruction
+        let (bytecode, _) =
+            optimized("top:\nPUSH true\nJMPF end\nPUSH 1\nPOP\nPUSH 2\nJMP top\nend:\nPUSH 3");
+        let expected = "top:\nPUSH true\nJMPF end\nPUSH 2\nJMP top\nend:\nPUSH 3";
+        assert_eq!(bytecode, assemble(expected).unwrap());
+
+        let (bytecode, _) = optimized(
+            "PUSH 1\nJTBL 0 other [zero]\nPUSH 5\nPOP\nzero:\nPUSH 6\nPOP\nother:\nPUSH 7",
+        );
+        let expected = "PUSH 1\nJTBL 0 other [zero]\nzero:\nother:\nPUSH 7";
+        assert_eq!(bytecode, assemble(expected).unwrap());
+
+        // A pair is left alone if a jump lands between its instructions
+        let asm = "PUSH 1\nJMP mid\nPUSH 2\nmid:\nPOP";
+        let (bytecode, stats) = optimized(asm);
+        assert_eq!(bytecode, assemble(asm).unwrap());
+        assert_eq!(stats, PeepholeStats::default());
+
+        // A fused jump keeps its target
+        let (bytecode, _) =
+            optimized("PUSH nil\nDUP\nJMPNN end\nPUSH 1\nPOP\nPUSH 2\nend:\nPUSH 3");
+        let expected = "PUSH nil\nDJNN end\nPUSH 2\nend:\nPUSH 3";
+        assert_eq!(bytecode, assemble(expected).unwrap());
+    }
+
+    #[test]
+    fn test_superinstructions() {
+        let source = "fib(n) = n < 2 ? n : fib(n - 1) + fib(n - 2)\n\
+            a = [10, 20, 30]; i = 1\n\
+            [fib(12), a[i] + 1, nil ?? 3, for x in 0..3 { x - 1 }]";
+
+        let (profile, bytecode) = Lavendeux::new().compile(source).unwrap();
+        let opcodes = ASMTranscoder::new(&bytecode, Some(profile.clone())).disassemble_as_string();
+        for opcode in [OpCode::PADD, OpCode::RIDX, OpCode::DJNN, OpCode::DJNE] {
+            assert!(
+                opcodes.contains(&format!("{opcode:?}")),
+                "{opcode:?}\n{opcodes}"
+            );
+        }
+
+        let mut lav = Lavendeux::new();
+        let optimized = lav.execute(bytecode, Some(profile)).unwrap();
+        let mut lav = Lavendeux::with_options(CompilerOptions {
+            optimize: false,
+            ..Default::default()
+        });
+        let unoptimized = lav.run(source).unwrap();
+        assert_eq!(optimized, unoptimized);
+        assert_eq!(
+            optimized.to_string(),
+            "[[10, 20, 30], 1, [144, 21, 3, [-1, 0, 1]]]"
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Experimental register backend
+//! Trees of arithmetic, bitwise, logical and comparison operators are compiled to register
+//! instructions, which read their operands from a file of registers in the current function's
+//! context, instead of shuffling them on the stack. `(a + 1) * -b` becomes:
+//! ```text
+//!   RLDV r0 a
+//!   RLDC r1 1
+//!   RBOP ADD r0 r0 r1
+//!   RLDV r1 b
+//!   RUOP NEG r1 r1
+//!   RBOP MUL r0 r0 r1
+//!   RPSH r0
+//! ```
+//!
+//! Any other node in the tree is compiled to the stack as normal, then moved into a register,
+//! and the result of the whole tree is moved onto the stack, so the rest of the program is unchanged
+//!
+//! Enabled with `CompilerOptions::registers`; the stack backend remains the default
+use super::{Compiler, CompilerError};
+use crate::{lexer::Token, parser::Node, traits::SerializeToBytes, vm::OpCode};
+
+/// State of the register backend
+#[derive(Debug, Clone, Default)]
+pub(super) struct RegisterState {
+    // The lowest register that is free to use; the ones below hold operands still needed
+    next: u8,
+}
+
+/// An operator node, broken up for the register backend
+struct Operator<'source> {
+    opcode: OpCode,
+    token: Token<'source>,
+    lhs: Option<Node<'source>>,
+    rhs: Node<'source>,
+}
+
+impl Compiler {
+    /// Returns true if the node is an operator that can be compiled to register instructions
+    fn is_register_op(node: &Node<'_>) -> bool {
+        match node {
+            Node::ArithmeticInfixExpr(_)
+            | Node::ArithmeticPrefixExpr(_)
+            | Node::BitwiseInfixExpr(_)
+            | Node::BitwiseNot(_)
+            | Node::LogicalExpr(_)
+            | Node::LogicalNot(_) => true,
+            Node::ComparisonExpr(n) => n.chain.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Break up an operator node into its opcode and operands
+    fn into_operator(node: Node<'_>) -> Option<Operator<'_>> {
+        let (opcode, token, lhs, rhs) = match node {
+            Node::ArithmeticInfixExpr(n) => (n.op.opcode(), n.token, Some(n.lhs), n.rhs),
+            Node::ArithmeticPrefixExpr(n) => (OpCode::NEG, n.token, None, n.rhs),
+            Node::BitwiseInfixExpr(n) => (n.op.opcode(), n.token, Some(n.lhs), n.rhs),
+            Node::BitwiseNot(n) => (OpCode::NOT, n.token, None, n.rhs),
+            Node::LogicalExpr(n) => (n.op.opcode(), n.token, Some(n.lhs), n.rhs),
+            Node::LogicalNot(n) => (OpCode::LNOT, n.token, None, n.rhs),
+            Node::ComparisonExpr(n) if n.chain.is_empty() => {
+                (n.op.opcode(), n.token, Some(n.lhs), n.rhs)
+            }
+            _ => return None,
+        };
+
+        Some(Operator {
+            opcode,
+            token,
+            lhs,
+            rhs,
+        })
+    }
+
+    /// Compile a tree of operators to register instructions, leaving the result on the stack
+    /// Returns the node back if it should be compiled to the stack as normal
+    pub fn allocate_registers<'source>(
+        &mut self,
+        node: Node<'source>,
+    ) -> Result<Option<Node<'source>>, CompilerError> {
+        if !self.options().registers
+            || self.registers.next == u8::MAX
+            || !Self::is_register_op(&node)
+        {
+            return Ok(Some(node));
+        }
+
+        let base = self.registers.next;
+        self.compile_into_register(node, base)?;
+        self.push(OpCode::RPSH);
+        self.push_u8(base);
+        Ok(None)
+    }
+
+    /// Compile an operand into a register, reusing the result if it is a common subexpression
+    /// A subexpression computed for the first time is a new tree, which must start above `dst`
+    fn compile_operand(&mut self, node: Node<'_>, dst: u8) -> Result<(), CompilerError> {
+        let next = std::mem::replace(&mut self.registers.next, dst);
+        let node = self.eliminate_subexpressions(node);
+        self.registers.next = next;
+
+        match node? {
+            Some(node) => self.compile_into_register(node, dst),
+            None => {
+                self.push(OpCode::RPOP);
+                self.push_u8(dst);
+                Ok(())
+            }
+        }
+    }
+
+    /// Compile a node into a register, leaving the registers below it untouched
+    fn compile_into_register(&mut self, node: Node<'_>, dst: u8) -> Result<(), CompilerError> {
+        match node {
+            Node::LiteralInt(n) => self.load_constant(n.token, dst, n.value.serialize_into_bytes()),
+            Node::LiteralFloat(n) => {
+                self.load_constant(n.token, dst, n.value.serialize_into_bytes())
+            }
+            Node::LiteralString(n) => {
+                self.load_constant(n.token, dst, n.value.serialize_into_bytes())
+            }
+            Node::LiteralBool(n) => {
+                self.load_constant(n.token, dst, n.value.serialize_into_bytes())
+            }
+
+            Node::LiteralIdent(n) => {
+                let name = self.resolve_ident(n.token.slice()).to_string();
+                self.push_token(n.token);
+                self.push(OpCode::RLDV);
+                self.push_u8(dst);
+                self.push_strhash(&name);
+            }
+
+            // Binary operators need a second register for their right operand
+            node if Self::is_register_op(&node) && dst < u8::MAX => {
+                let Some(op) = Self::into_operator(node) else {
+                    unreachable!("Not a register operator")
+                };
+                self.push_token(op.token);
+
+                match op.lhs {
+                    Some(lhs) => {
+                        self.compile_operand(lhs, dst)?;
+                        self.compile_operand(op.rhs, dst + 1)?;
+                        self.push(OpCode::RBOP);
+                        self.push_u8(op.opcode as u8);
+                        self.push_u8(dst);
+                        self.push_u8(dst);
+                        self.push_u8(dst + 1);
+                    }
+                    None => {
+                        self.compile_operand(op.rhs, dst)?;
+                        self.push(OpCode::RUOP);
+                        self.push_u8(op.opcode as u8);
+                        self.push_u8(dst);
+                        self.push_u8(dst);
+                    }
+                }
+            }
+
+            // Anything else is compiled to the stack, with any operators inside it using the free registers
+            node => {
+                let next = std::mem::replace(&mut self.registers.next, dst);
+                let result = node.compile_node(self);
+                self.registers.next = next;
+                result?;
+
+                self.push(OpCode::RPOP);
+                self.push_u8(dst);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Load a serialized constant into a register
+    fn load_constant(&mut self, token: Token<'_>, dst: u8, value: Vec<u8>) {
+        self.push_token(token);
+        self.push(OpCode::RLDC);
+        self.push_u8(dst);
+        self.extend(value);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        compiler::{asm_transcoder::ASMTranscoder, CompilerOptions},
+        vm::OpCode,
+        Lavendeux,
+    };
+
+    #[test]
+    fn test_register_backend() {
+        // Right-nested operators need one register per level
+        let nested = format!("{}1{}", "1 + (".repeat(40), ")".repeat(40));
+        let source = format!(
+            "a = 3; b = 4; s = 'x'\n\
+            f(n) = n < 2 ? n : f(n - 1) + f(n - 2)\n\
+            [(a + 1) * -b, (a + b) * (a + b) - sqrt(a*a + b*b), !(a >= b) || a == b]\n\
+            [0xFF & ~a | 1 << 4, s + a, f(a + b), a + (a = 10), {nested}]"
+        );
+
+        let registers = CompilerOptions {
+            registers: true,
+            ..Default::default()
+        };
+        let mut lav = Lavendeux::with_options(registers.clone());
+        let (profile, bytecode) = lav.compile(&source).unwrap();
+        let opcodes = ASMTranscoder::new(&bytecode, Some(profile.clone())).disassemble_as_string();
+        for opcode in [
+            OpCode::RLDC,
+            OpCode::RLDV,
+            OpCode::RBOP,
+            OpCode::RUOP,
+            OpCode::RPSH,
+        ] {
+            assert!(
+                opcodes.contains(&format!("{opcode:?}")),
+                "{opcode:?}\n{opcodes}"
+            );
+        }
+        let result = lav.execute(bytecode, Some(profile)).unwrap();
+
+        let stack = Lavendeux::new().run(&source).unwrap();
+        assert_eq!(result, stack);
+        assert_eq!(
+            result.to_string(),
+            "[3, 4, `x`, [-16, 44, true], [245, `x10`, 377, 20, 41]]"
+        );
+
+        let mut lav = Lavendeux::with_options(registers);
+        let script = include_str!("../../example_scripts/zarban_storydata.lav");
+        let result = lav.run(script).unwrap();
+        assert_eq!(result, Lavendeux::new().run(script).unwrap());
+    }
+}
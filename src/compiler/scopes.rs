@@ -0,0 +1,338 @@
+//! Shadowing and scope lint
+//! Warns about names whose scope commonly surprises users, pointing at both places the name is used:
+//! - A loop variable with the same name as an existing variable, which the loop overwrites
+//! - A function argument with the same name as a variable outside the function,
+//!   which the function cannot see anyway
+//! - A variable first assigned inside a loop, then used after it; the loop's scope,
+//!   and everything first assigned in it, is discarded when the loop ends
+//!
+//! Variables assigned by earlier runs are known through `Compiler::define_variable`,
+//! but have no location to point at, so they are never reported as being shadowed
+//!
+//! Names declared `global` are shared by every scope, so they are never discarded
+use super::{Compiler, CompilerWarning};
+use crate::{
+    lexer::{Rule, Token},
+    parser::{core::VarScope, Node},
+    traits::IntoOwned,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Names assigned in one scope, and where they were first assigned
+#[derive(Clone, Default)]
+struct Scope {
+    vars: HashMap<String, Option<Token<'static>>>,
+
+    // Variables first assigned in loops that have ended, which are no longer defined here
+    discarded: HashMap<String, Token<'static>>,
+
+    // Functions cannot see past their own scope
+    frame: bool,
+}
+
+/// Follows the scopes of a script, collecting a warning for each surprising name
+struct ScopeLint {
+    scopes: Vec<Scope>,
+    globals: HashSet<String>,
+    warnings: Vec<CompilerWarning>,
+}
+
+impl ScopeLint {
+    /// The scopes visible from the current one, innermost first
+    fn visible(&self) -> impl Iterator<Item = &Scope> {
+        let frame = self.scopes.iter().rposition(|s| s.frame).unwrap_or(0);
+        self.scopes[frame..].iter().rev()
+    }
+
+    /// Find a visible variable, and where it was first assigned, if that is known
+    fn find(&self, name: &str) -> Option<&Option<Token<'static>>> {
+        self.visible().find_map(|s| s.vars.get(name))
+    }
+
+    /// Record an assignment, in the current scope if the variable is not already visible
+    fn assign(&mut self, name: &str, token: &Token<'_>) {
+        if self.find(name).is_some() || self.globals.contains(name) {
+            return;
+        }
+
+        let scope = self.scopes.last_mut().expect("scope stack is never empty");
+        scope.discarded.remove(name);
+        scope
+            .vars
+            .insert(name.to_string(), Some(token.clone().into_owned()));
+    }
+
+    /// Record a use of a variable, warning if a loop discarded it
+    fn read(&mut self, name: &str, token: &Token<'_>) {
+        if self.find(name).is_some() || self.globals.contains(name) {
+            return;
+        }
+
+        let frame = self.scopes.iter().rposition(|s| s.frame).unwrap_or(0);
+        for scope in self.scopes[frame..].iter_mut().rev() {
+            if let Some(assigned) = scope.discarded.remove(name) {
+                self.warnings.push(CompilerWarning::DiscardedVariable(
+                    token.clone().into_owned(),
+                    assigned,
+                ));
+                return;
+            }
+        }
+    }
+
+    /// Enter a new scope, warning about names in it that shadow a visible variable
+    fn scope_in(&mut self, names: Vec<Token<'_>>, frame: bool) {
+        let mut scope = Scope {
+            frame,
+            ..Default::default()
+        };
+
+        for token in names {
+            let name = token.slice().to_string();
+            if let Some(Some(outer)) = self.find(&name).cloned() {
+                let token = token.clone().into_owned();
+                self.warnings.push(match frame {
+                    true => CompilerWarning::ArgumentShadows(token, outer),
+                    false => CompilerWarning::LoopVariableShadows(token, outer),
+                });
+            }
+
+            // A loop variable that already exists is overwritten, not declared in the loop
+            if frame || self.find(&name).is_none() {
+                scope.vars.insert(name, Some(token.into_owned()));
+            }
+        }
+
+        self.scopes.push(scope);
+    }
+
+    /// Leave the current scope
+    /// Everything first assigned in a loop's scope is discarded
+    fn scope_out(&mut self) {
+        let scope = self.scopes.pop().expect("scope stack is never empty");
+        if scope.frame {
+            return;
+        }
+
+        let parent = self.scopes.last_mut().expect("scope stack is never empty");
+        for (name, token) in scope.vars {
+            if let Some(token) = token {
+                parent.discarded.insert(name, token);
+            }
+        }
+        parent.discarded.extend(scope.discarded);
+    }
+
+    /// Visit branches of which only one runs, each starting from the current scopes
+    /// Afterwards, anything assigned in any of them is known
+    fn visit_branches<'a, 'source: 'a>(
+        &mut self,
+        branches: impl IntoIterator<Item = &'a Node<'source>>,
+    ) {
+        let before = self.scopes.clone();
+        let mut merged: Option<Vec<Scope>> = None;
+        for branch in branches {
+            self.scopes = before.clone();
+            self.visit(branch);
+
+            let after = std::mem::take(&mut self.scopes);
+            merged = Some(match merged {
+                None => after,
+                Some(mut merged) => {
+                    for (scope, other) in merged.iter_mut().zip(after) {
+                        for (name, token) in other.vars {
+                            scope.vars.entry(name).or_insert(token);
+                        }
+                        for (name, token) in other.discarded {
+                            scope.discarded.entry(name).or_insert(token);
+                        }
+                    }
+                    merged
+                }
+            });
+        }
+        self.scopes = merged.unwrap_or(before);
+    }
+
+    fn visit(&mut self, node: &Node<'_>) {
+        match node {
+            Node::AssignExpr(n) => match &n.target {
+                Node::LiteralIdent(ident) => {
+                    self.visit(&n.value);
+                    self.assign(ident.name(), &ident.token);
+                }
+                target => {
+                    self.visit(target);
+                    self.visit(&n.value);
+                }
+            },
+
+            Node::LiteralIdent(ident) => self.read(ident.name(), &ident.token),
+
+            Node::VarDef(n) => {
+                self.visit(&n.expr);
+
+                let name = n.token.input()[n.name_span.clone()].to_string();
+                match n.scope {
+                    VarScope::Global => {
+                        self.globals.insert(name);
+                    }
+                    VarScope::Local => {
+                        let token = n.token.child(Rule::LiteralIdent, n.name_span.clone());
+                        let scope = self.scopes.last_mut().expect("scope stack is never empty");
+                        scope.discarded.remove(&name);
+                        scope.vars.insert(name, Some(token.into_owned()));
+                    }
+                }
+            }
+
+            Node::For(n) => {
+                self.visit(&n.expr);
+
+                let names = n
+                    .name_span
+                    .iter()
+                    .map(|span| n.token.child(Rule::LiteralIdent, span.clone()))
+                    .collect();
+                self.scope_in(names, false);
+                if let Some(condition) = &n.condition {
+                    self.visit(condition);
+                }
+                self.visit(&n.block);
+                self.scope_out();
+            }
+
+            Node::FnAssign(n) => {
+                for (_, _, default, _) in &n.args {
+                    if let Some(default) = default {
+                        self.visit(default);
+                    }
+                }
+
+                let names = n
+                    .args
+                    .iter()
+                    .map(|(span, _, _, _)| n.token.child(Rule::LiteralIdent, span.clone()))
+                    .collect();
+                self.scope_in(names, true);
+                self.visit(&n.body);
+                self.scope_out();
+            }
+
+            Node::If(n) => {
+                self.visit(&n.condition);
+                self.visit_branches(std::iter::once(&n.then_block).chain(&n.else_block));
+            }
+
+            Node::Switch(n) => {
+                self.visit(&n.expr);
+                for (values, _) in &n.cases {
+                    for (_, value) in values {
+                        self.visit(value);
+                    }
+                }
+                let bodies = n.cases.iter().map(|(_, body)| body);
+                self.visit_branches(bodies.chain(std::iter::once(&n.default)));
+            }
+
+            // Let variables hide others on purpose, and are gone once the expression is done
+            Node::Let(n) => {
+                self.scopes.push(Scope::default());
+                for (span, value) in &n.bindings {
+                    self.visit(value);
+
+                    let token = n.token.child(Rule::LiteralIdent, span.clone());
+                    let scope = self.scopes.last_mut().expect("scope stack is never empty");
+                    scope
+                        .vars
+                        .insert(token.slice().to_string(), Some(token.into_owned()));
+                }
+                self.visit(&n.expr);
+                self.scopes.pop();
+            }
+
+            // Alias bodies are checked where they are expanded
+            Node::Alias(_) => {}
+
+            _ => {
+                for child in node.children() {
+                    self.visit(child);
+                }
+            }
+        }
+    }
+}
+
+impl Compiler {
+    /// Warn about loop variables and arguments that shadow other variables,
+    /// and variables used after the loop that discarded them
+    /// Only runs if `CompilerOptions::lint_scopes` is set
+    pub fn lint_scopes(&mut self, lines: &[Node<'_>]) {
+        if !self.options().lint_scopes {
+            return;
+        }
+
+        let globals = Scope {
+            vars: self.variables.iter().map(|n| (n.clone(), None)).collect(),
+            frame: true,
+            ..Default::default()
+        };
+        let mut lint = ScopeLint {
+            scopes: vec![globals],
+            globals: self.globals().clone(),
+            warnings: vec![],
+        };
+
+        for line in lines {
+            lint.visit(line);
+        }
+        for warning in lint.warnings {
+            self.warn(warning);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_scope_lint() {
+        let mut lav = Lavendeux::new();
+        let warnings = |lav: &mut Lavendeux, source: &str| lav.run_report(source).unwrap().warnings;
+
+        let w = warnings(&mut lav, "i = 10\nfor i in 0..3 { i }");
+        assert_eq!(w.len(), 1);
+        assert!(w[0].contains("loop variable `i` overwrites"));
+        assert!(w[0].contains("Line 1") && w[0].contains("Line 2"));
+
+        let w = warnings(&mut lav, "x = 1\nf(x) = x * 2");
+        assert_eq!(w.len(), 1);
+        assert!(w[0].contains("argument `x` hides"));
+
+        // Either branch may have assigned it
+        let source = "if true then { v = 1 } else nil\nfor v in 0..2 { v }";
+        let w = warnings(&mut lav, source);
+        assert_eq!(w.len(), 1);
+
+        // Never read, since it would fail at runtime
+        let w = warnings(&mut lav, "for n in 0..3 { y = n }\nif false then y else 0");
+        assert_eq!(w.len(), 1);
+        assert!(w[0].contains("`y` was first assigned inside a loop"));
+
+        for source in [
+            "total = 0\nfor n in 0..3 { total += n }\ntotal",
+            "for n in 0..3 { z = n }\nz = 5\nz",
+            "g(a) = { b = a; b }\ng(1)",
+            "for a in 0..2 { for b in 0..2 { a + b } }",
+            "switch 1 { 1 => { q = 0 }, _ => for q in 0..2 do q }",
+        ] {
+            assert!(warnings(&mut lav, source).is_empty(), "{source}");
+        }
+
+        // Variables from earlier runs are overwritten by loops, not discarded
+        lav.run("last_seen = 0").unwrap();
+        let source = "for n in 0..3 { last_seen = n }\nlast_seen";
+        assert!(warnings(&mut lav, source).is_empty());
+    }
+}
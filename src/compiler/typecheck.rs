@@ -0,0 +1,556 @@
+//! Compile-time type checking
+//! Infers the types of expressions from literals, casts, and the declared argument and return types
+//! of functions, and reports operations that would always fail at runtime:
+//! - Indexing into a value that cannot be indexed, such as an integer
+//! - Calling a name that holds a value other than a function
+//! - Calling a function with the wrong number of arguments, or an argument of the wrong type
+//!
+//! Anything that cannot be known for certain is given the type `any`, and never reported,
+//! so a script that passes the check can still fail at runtime
+use super::{Compiler, CompilerError};
+use crate::{
+    parser::{core::VarScope, Node},
+    traits::IntoOwned,
+    value::ValueType,
+};
+use std::collections::HashMap;
+
+/// Argument and return types of a user function
+#[derive(Debug, Clone)]
+struct Signature {
+    // Type of each argument, and whether it has a default value
+    args: Vec<(ValueType, bool)>,
+    returns: ValueType,
+}
+
+/// Types known at one point in a script
+#[derive(Debug, Clone, Default)]
+struct Checker {
+    vars: HashMap<String, ValueType>,
+    functions: HashMap<String, Signature>,
+    aliases: Vec<String>,
+}
+
+impl Checker {
+    /// Forget everything assigned anywhere within a node
+    /// Used before code that may run any number of times, such as a loop or a branch
+    fn invalidate(&mut self, node: &Node<'_>) {
+        match node {
+            Node::AssignExpr(n) => self.invalidate_target(&n.target),
+            Node::AssignArithmeticExpr(n) => self.invalidate_target(&n.target),
+            Node::AssignBitwiseExpr(n) => self.invalidate_target(&n.target),
+            Node::DeleteExpr(n) => self.invalidate_target(&n.target),
+            Node::VarDef(n) => {
+                self.vars.remove(&n.token.input()[n.name_span.clone()]);
+            }
+            Node::FnAssign(n) => {
+                let name = &n.token.input()[n.name_span.clone()];
+                self.functions.remove(name);
+                self.vars.remove(name);
+            }
+            Node::For(n) => {
+                if let Some(span) = &n.name_span {
+                    self.vars.remove(&n.token.input()[span.clone()]);
+                }
+            }
+            Node::FnCall(n) => {
+                for arg in &n.args {
+                    self.invalidate_target(arg);
+                }
+            }
+            Node::CustomInfixExpr(n) => {
+                self.invalidate_target(&n.lhs);
+                self.invalidate_target(&n.rhs);
+            }
+            Node::Alias(_) => return,
+            _ => {}
+        }
+
+        for child in node.children() {
+            self.invalidate(child);
+        }
+    }
+
+    /// Forget the type of a variable that may be written to
+    fn invalidate_target(&mut self, target: &Node<'_>) {
+        match target {
+            Node::LiteralIdent(ident) => {
+                self.vars.remove(ident.name());
+                self.functions.remove(ident.name());
+            }
+            Node::Array(n) => n.elements.iter().for_each(|e| self.invalidate_target(e)),
+            _ => {}
+        }
+    }
+
+    /// Check code that may run any number of times, or not at all
+    fn check_branch(&mut self, node: &Node<'_>) -> Result<ValueType, CompilerError> {
+        self.invalidate(node);
+        self.clone().check(node)
+    }
+
+    /// Parse a type annotation, as the compiler does
+    fn annotation(input: &str, span: &Option<std::ops::Range<usize>>) -> ValueType {
+        span.as_ref()
+            .map(|span| ValueType::from_str(&input[span.clone()]).unwrap_or(ValueType::All))
+            .unwrap_or(ValueType::All)
+    }
+
+    /// Infer the type of a node, reporting any definite type errors within it
+    fn check(&mut self, node: &Node<'_>) -> Result<ValueType, CompilerError> {
+        let ty = match node {
+            Node::LiteralString(n) => n.value.type_of(),
+            Node::LiteralInt(n) => n.value.type_of(),
+            Node::LiteralFloat(n) => n.value.type_of(),
+            Node::LiteralBool(n) => n.value.type_of(),
+            Node::LiteralIdent(n) => self.vars.get(n.name()).copied().unwrap_or(ValueType::All),
+
+            Node::Script(n) => self.check_lines(&n.lines)?,
+            Node::Block(n) => self.check_lines(&n.lines)?,
+
+            Node::CastExpr(n) => {
+                self.check(&n.expr)?;
+                n.type_name.value_type()
+            }
+
+            Node::IsExpr(n) => {
+                self.check(&n.expr)?;
+                ValueType::Boolean
+            }
+
+            Node::ComparisonExpr(n) => {
+                self.check(&n.lhs)?;
+                self.check(&n.rhs)?;
+                for (_, operand) in &n.chain {
+                    self.check(operand)?;
+                }
+                ValueType::Boolean
+            }
+
+            Node::MatchExpr(n) => {
+                self.check(&n.lhs)?;
+                self.check(&n.rhs)?;
+                ValueType::Boolean
+            }
+
+            Node::LogicalExpr(n) => {
+                self.check(&n.lhs)?;
+                self.check_branch(&n.rhs)?;
+                ValueType::Boolean
+            }
+
+            Node::LogicalNot(n) => {
+                self.check(&n.rhs)?;
+                ValueType::Boolean
+            }
+
+            Node::Array(n) => {
+                for element in &n.elements {
+                    self.check(element)?;
+                }
+                ValueType::Array
+            }
+
+            Node::Object(n) => {
+                for (key, value) in &n.elements {
+                    self.check(key)?;
+                    self.check(value)?;
+                }
+                ValueType::Object
+            }
+
+            Node::RangeExpr(n) => {
+                self.check(&n.start)?;
+                self.check(&n.end)?;
+                ValueType::Collection
+            }
+
+            Node::IndexingExpr(n) => {
+                let mut ty = self.check(&n.base)?;
+                for (index, nil_safe) in &n.path {
+                    self.check(index)?;
+                    let indexable = ty.overlaps(ValueType::Collection)
+                        || (*nil_safe && ty.overlaps(ValueType::Nil));
+                    if !indexable {
+                        return Err(CompilerError::NotIndexable(
+                            n.token.clone().into_owned(),
+                            ty,
+                        ));
+                    }
+                    ty = ValueType::All;
+                }
+                ty
+            }
+
+            Node::AssignExpr(n) => {
+                let ty = self.check(&n.value)?;
+                match &n.target {
+                    Node::LiteralIdent(ident) => {
+                        self.functions.remove(ident.name());
+                        self.vars.insert(ident.name().to_string(), ty);
+                    }
+                    target => {
+                        self.check_target(target)?;
+                        self.invalidate_target(target);
+                    }
+                }
+                ty
+            }
+
+            Node::AssignArithmeticExpr(n) => {
+                self.check(&n.value)?;
+                self.check_target(&n.target)?;
+                self.invalidate_target(&n.target);
+                ValueType::All
+            }
+
+            Node::AssignBitwiseExpr(n) => {
+                self.check(&n.value)?;
+                self.check_target(&n.target)?;
+                self.invalidate_target(&n.target);
+                ValueType::All
+            }
+
+            Node::DeleteExpr(n) => {
+                self.check_target(&n.target)?;
+                self.invalidate_target(&n.target);
+                ValueType::All
+            }
+
+            Node::FnAssign(n) => {
+                let input = n.token.input();
+                let name = input[n.name_span.clone()].to_string();
+
+                // The body runs in its own scope, where only the arguments are known
+                let mut body = Checker {
+                    aliases: self.aliases.clone(),
+                    ..Default::default()
+                };
+                let mut args = vec![];
+                for (arg, ty, default, _) in &n.args {
+                    if let Some(default) = default {
+                        self.check_branch(default)?;
+                    }
+
+                    let ty = Self::annotation(input, ty);
+                    body.vars.insert(input[arg.clone()].to_string(), ty);
+                    args.push((ty, default.is_some()));
+                }
+                body.invalidate(&n.body);
+                body.check(&n.body)?;
+
+                let signature = Signature {
+                    args,
+                    returns: Self::annotation(input, &n.returns),
+                };
+                self.vars.insert(name.clone(), ValueType::Function);
+                self.functions.insert(name, signature);
+                ValueType::Function
+            }
+
+            Node::Alias(n) => {
+                self.aliases
+                    .push(n.token.input()[n.name_span.clone()].to_string());
+                ValueType::Nil
+            }
+
+            Node::FnCall(n) => self.check_call(n)?,
+
+            Node::If(n) => {
+                self.check(&n.condition)?;
+                let then_ty = self.check_branch(&n.then_block)?;
+                let else_ty = match &n.else_block {
+                    Some(block) => self.check_branch(block)?,
+                    None => ValueType::Nil,
+                };
+                if then_ty == else_ty {
+                    then_ty
+                } else {
+                    ValueType::All
+                }
+            }
+
+            Node::Switch(n) => {
+                self.check(&n.expr)?;
+                for (values, body) in &n.cases {
+                    for (_, value) in values {
+                        self.check_branch(value)?;
+                    }
+                    self.check_branch(body)?;
+                }
+                self.check_branch(&n.default)?;
+                ValueType::All
+            }
+
+            Node::For(n) => {
+                self.check(&n.expr)?;
+                self.invalidate(&n.block);
+                if let Some(condition) = &n.condition {
+                    self.invalidate(condition);
+                }
+
+                let mut body = self.clone();
+                if let Some(span) = &n.name_span {
+                    body.vars.remove(&n.token.input()[span.clone()]);
+                }
+                if let Some(condition) = &n.condition {
+                    body.check(condition)?;
+                }
+                body.check(&n.block)?;
+
+                if n.discard {
+                    ValueType::Nil
+                } else {
+                    ValueType::Array
+                }
+            }
+
+            Node::NilCoalesceExpr(n) => {
+                self.check(&n.value)?;
+                self.check_branch(&n.default)?;
+                ValueType::All
+            }
+
+            // Let variables hide others only until the expression is done
+            Node::Let(n) => {
+                let mut hidden = vec![];
+                for (span, value) in &n.bindings {
+                    let ty = self.check(value)?;
+                    let name = n.token.input()[span.clone()].to_string();
+                    hidden.push((name.clone(), self.vars.insert(name, ty)));
+                }
+
+                let ty = self.check(&n.expr)?;
+                for (name, outer) in hidden.into_iter().rev() {
+                    match outer {
+                        Some(outer) => self.vars.insert(name, outer),
+                        None => self.vars.remove(&name),
+                    };
+                }
+                ty
+            }
+
+            // A global may be hidden by a variable of the same name, so only locals keep their type
+            Node::VarDef(n) => {
+                let ty = self.check(&n.expr)?;
+                let name = n.token.input()[n.name_span.clone()].to_string();
+                match n.scope {
+                    VarScope::Local => self.vars.insert(name, ty),
+                    VarScope::Global => self.vars.remove(&name),
+                };
+                ty
+            }
+
+            _ => {
+                for child in node.children() {
+                    self.check(child)?;
+                }
+                ValueType::All
+            }
+        };
+
+        Ok(ty)
+    }
+
+    /// Check a sequence of lines, returning the type of the last
+    fn check_lines(&mut self, lines: &[Node<'_>]) -> Result<ValueType, CompilerError> {
+        let mut ty = ValueType::Nil;
+        for line in lines {
+            ty = self.check(line)?;
+        }
+        Ok(ty)
+    }
+
+    /// Check the target of an assignment; only the indexing within it is checked
+    fn check_target(&mut self, target: &Node<'_>) -> Result<(), CompilerError> {
+        match target {
+            Node::LiteralIdent(_) => Ok(()),
+            Node::Array(n) => n.elements.iter().try_for_each(|e| self.check_target(e)),
+            target => self.check(target).map(|_| ()),
+        }
+    }
+
+    /// Check a function call against the signature of the function, if it is known
+    fn check_call(
+        &mut self,
+        call: &crate::parser::functions::FnCallNode<'_>,
+    ) -> Result<ValueType, CompilerError> {
+        let name = call.token.input()[call.name_span.clone()].to_string();
+
+        let mut arg_types = vec![];
+        for arg in &call.args {
+            arg_types.push(self.check(arg)?);
+        }
+
+        if name == "include" {
+            // The included file could define or redefine anything
+            *self = Checker {
+                aliases: self.aliases.clone(),
+                ..Default::default()
+            };
+            return Ok(ValueType::All);
+        }
+
+        if self.aliases.contains(&name) {
+            for arg in &call.args {
+                self.invalidate_target(arg);
+            }
+            return Ok(ValueType::All);
+        }
+
+        if call.method && arg_types[0].overlaps(ValueType::Object) {
+            // The object may hold its own function under this name
+            for arg in &call.args {
+                self.invalidate_target(arg);
+            }
+            return Ok(ValueType::All);
+        }
+
+        if let Some(ty) = self.vars.get(&name) {
+            if !ty.overlaps(ValueType::Function) {
+                return Err(CompilerError::NotAFunction(
+                    call.token.clone().into_owned(),
+                    name,
+                    *ty,
+                ));
+            }
+        }
+
+        let spread = call.args.iter().any(|a| matches!(a, Node::Spread(_)));
+        let signature = match self.functions.get(&name) {
+            Some(signature) if !spread => signature.clone(),
+            _ => {
+                // Unknown functions could take any argument by reference
+                for arg in &call.args {
+                    self.invalidate_target(arg);
+                }
+                return Ok(ValueType::All);
+            }
+        };
+
+        let required = signature
+            .args
+            .iter()
+            .filter(|(_, default)| !default)
+            .count();
+        if arg_types.len() > signature.args.len() || arg_types.len() < required {
+            let expected = if arg_types.len() > signature.args.len() {
+                signature.args.len()
+            } else {
+                required
+            };
+            return Err(CompilerError::InvalidArgumentCount(
+                call.token.clone().into_owned(),
+                name,
+                expected,
+                arg_types.len(),
+            ));
+        }
+
+        for (i, (provided, (expected, _))) in arg_types.iter().zip(&signature.args).enumerate() {
+            if !provided.overlaps(*expected) {
+                return Err(CompilerError::InvalidArgumentType(
+                    call.args[i].token().clone().into_owned(),
+                    name,
+                    i + 1,
+                    *expected,
+                    *provided,
+                ));
+            }
+        }
+
+        for arg in &call.args {
+            self.invalidate_target(arg);
+        }
+        Ok(signature.returns)
+    }
+}
+
+impl Compiler {
+    /// Check a script for operations that would always fail with a type error at runtime
+    /// Only runs if `CompilerOptions::typecheck` is set
+    pub fn typecheck(&self, lines: &[Node<'_>]) -> Result<(), CompilerError> {
+        if !self.options().typecheck {
+            return Ok(());
+        }
+
+        let mut checker = Checker::default();
+        for line in lines {
+            checker.check(line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        compiler::{CompilerError, CompilerOptions},
+        value::ValueType,
+        Error, Lavendeux,
+    };
+
+    fn typechecked() -> Lavendeux {
+        Lavendeux::with_options(CompilerOptions {
+            typecheck: true,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_argument_types() {
+        let mut lav = typechecked();
+        let error = lav
+            .compile("f(a: int, b: string) = a\nf(1, 2)")
+            .unwrap_err();
+        assert!(
+            matches!(
+                &error,
+                Error::Compiler(CompilerError::InvalidArgumentType(
+                    _,
+                    name,
+                    2,
+                    ValueType::String,
+                    ValueType::Integer
+                )) if name == "f"
+            ),
+            "{error:?}"
+        );
+
+        // Types that may match are left for the runtime to check
+        assert!(lav.compile("f(a: int) = a\nf(1)").is_ok());
+        assert!(lav.compile("f(a: int) = a\ng(x) = f(x)").is_ok());
+
+        // Without the check, the call fails when it runs
+        let mut lav = Lavendeux::new();
+        assert!(lav.compile("f(a: int, b: string) = a\nf(1, 2)").is_ok());
+        assert!(lav.run("f(a: int, b: string) = a\nf(1, 2)").is_err());
+    }
+
+    #[test]
+    fn test_assignment_types() {
+        // An assigned value's type is tracked, so later misuse of the variable is reported
+        let mut lav = typechecked();
+        let error = lav.compile("x = 5\nx[0] = 1").unwrap_err();
+        assert!(
+            matches!(
+                &error,
+                Error::Compiler(CompilerError::NotIndexable(_, ValueType::Integer))
+            ),
+            "{error:?}"
+        );
+
+        let error = lav.compile("g = 'text'\ng(1)").unwrap_err();
+        assert!(
+            matches!(
+                &error,
+                Error::Compiler(CompilerError::NotAFunction(_, name, ValueType::String))
+                    if name == "g"
+            ),
+            "{error:?}"
+        );
+
+        // Reassigning the variable replaces its type
+        assert!(lav.compile("x = 5\nx = [1]\nx[0] = 2").is_ok());
+    }
+}
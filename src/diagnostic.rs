@@ -0,0 +1,142 @@
+//! Structured diagnostics for errors
+//! `Diagnostic` breaks an error down into a message, notes and a location,
+//! so it can be rendered or consumed by tools without parsing error strings.
+//! With the `cli` feature, `Renderer` produces rustc-style terminal output.
+use crate::{
+    compiler::CompilerError,
+    lexer::{LexerError, Token},
+    vm::error::{RuntimeError, RuntimeErrorType},
+    Error,
+};
+
+#[cfg(feature = "cli")]
+mod render;
+#[cfg(feature = "cli")]
+pub use render::Renderer;
+
+/// A structured description of an error
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// Name of the kind of error, such as `Syntax` or `HashNotFound`
+    pub code: &'static str,
+
+    /// Short description of the problem
+    pub message: String,
+
+    /// Additional information, such as hints or the expected input
+    pub notes: Vec<String>,
+
+    /// The token the error occurred at, if known
+    pub token: Option<Token<'static>>,
+
+    /// Where the error was reached from, innermost first
+    /// For example the function calls or includes leading to the error
+    pub context: Vec<Diagnostic>,
+}
+
+impl Diagnostic {
+    /// Create a diagnostic from an error message
+    /// The first line of the text is the message, and each following line is a note
+    pub fn new(code: &'static str, token: Option<Token<'static>>, text: &str) -> Self {
+        let mut lines = text
+            .trim_start_matches('\n')
+            .lines()
+            .map(|l| l.strip_prefix("= ").unwrap_or(l).to_string());
+
+        Self {
+            code,
+            message: lines.next().unwrap_or_default(),
+            notes: lines.collect(),
+            token,
+            context: vec![],
+        }
+    }
+
+    /// Create a diagnostic from an error message that starts with the token's context
+    /// As produced by the `Display` implementations of the error types
+    fn with_token_prefix(code: &'static str, token: &Token<'static>, text: &str) -> Self {
+        let text = text.strip_prefix(&token.to_string()).unwrap_or(text);
+        Self::new(code, Some(token.clone()), text)
+    }
+}
+
+impl From<&LexerError> for Diagnostic {
+    fn from(error: &LexerError) -> Self {
+        match error {
+            LexerError::UnrecognizedToken(token) => {
+                Self::new(error.into(), Some(token.clone()), "Unrecognized token")
+            }
+        }
+    }
+}
+
+impl From<&CompilerError> for Diagnostic {
+    fn from(error: &CompilerError) -> Self {
+        match error {
+            CompilerError::IncludeError(token, inner) => {
+                let mut diagnostic = inner.diagnostic();
+                diagnostic
+                    .context
+                    .push(Self::new(error.into(), Some(token.clone()), "In include"));
+                diagnostic
+            }
+
+            CompilerError::FunctionCollision(token, name, existing) => {
+                let (existing, site) = existing.as_ref();
+                let message = format!("`{name}` collides with `{existing}`");
+                let mut diagnostic = Self::new(error.into(), Some(token.clone()), &message);
+                let message = format!("`{existing}` is defined here");
+                diagnostic
+                    .context
+                    .push(Self::new(error.into(), Some(site.clone()), &message));
+                diagnostic
+            }
+
+            CompilerError::Parser(e) => {
+                Self::with_token_prefix(e.into(), e.token(), &e.to_string())
+            }
+            _ => Self::with_token_prefix(error.into(), error.token(), &error.to_string()),
+        }
+    }
+}
+
+impl From<&RuntimeError> for Diagnostic {
+    fn from(error: &RuntimeError) -> Self {
+        let mut diagnostic = Self::new(
+            runtime_code(&error.error),
+            error.token.clone(),
+            &error.error.to_string(),
+        );
+
+        let mut parent = error.parent.as_deref();
+        while let Some(e) = parent {
+            diagnostic.context.push(Self::new(
+                runtime_code(&e.error),
+                e.token.clone(),
+                &e.error.to_string(),
+            ));
+            parent = e.parent.as_deref();
+        }
+
+        diagnostic
+    }
+}
+
+/// Value errors are reported by the kind of value error, rather than as `Value`
+fn runtime_code(error: &RuntimeErrorType) -> &'static str {
+    match error {
+        RuntimeErrorType::Value(e) => e.into(),
+        _ => error.into(),
+    }
+}
+
+impl From<&Error> for Diagnostic {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::Lexer(e) => e.into(),
+            Error::Parser(e) => Self::with_token_prefix(e.into(), e.token(), &e.to_string()),
+            Error::Compiler(e) => e.into(),
+            Error::Runtime(e) => e.into(),
+        }
+    }
+}
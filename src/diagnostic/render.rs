@@ -0,0 +1,115 @@
+use super::Diagnostic;
+use crate::lexer::SourceMap;
+use std::io::IsTerminal;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+
+/// Renders diagnostics in the style of rustc, with gutters, underlines and notes
+/// Colors are used if enabled, see `Renderer::new`
+#[derive(Debug, Clone, Copy)]
+pub struct Renderer {
+    color: bool,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Renderer {
+    /// Create a renderer that uses colors if stderr is a terminal
+    /// and the `NO_COLOR` environment variable is not set
+    pub fn new() -> Self {
+        let color = std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        Self { color }
+    }
+
+    /// Create a renderer with colors explicitly enabled or disabled
+    pub fn with_color(color: bool) -> Self {
+        Self { color }
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{style}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Render a diagnostic
+    /// `source` is used to locate tokens from the main input; tokens from
+    /// other files, such as includes, are located using their own input
+    pub fn render(&self, diagnostic: &Diagnostic, source: &SourceMap<'_>) -> String {
+        let mut out = self.render_one("error", RED, diagnostic, source);
+        for context in &diagnostic.context {
+            out.push_str("\n\n");
+            out.push_str(&self.render_one("note", CYAN, context, source));
+        }
+        out
+    }
+
+    fn render_one(
+        &self,
+        level: &str,
+        level_style: &str,
+        diagnostic: &Diagnostic,
+        source: &SourceMap<'_>,
+    ) -> String {
+        let mut lines = vec![format!(
+            "{}{}",
+            self.paint(level_style, &format!("{level}:")),
+            self.paint(BOLD, &format!(" {}", diagnostic.message))
+        )];
+
+        let mut gutter_width = 1;
+        if let Some(token) = &diagnostic.token {
+            let own_map;
+            let map = if token.filename().is_none() && token.input() == source.input() {
+                source
+            } else {
+                own_map = token.source_map();
+                &own_map
+            };
+
+            let span = token.span();
+            let (line, col) = map.line_col(span.start);
+            let line_span = map.line_span(line).unwrap_or(span.start..span.start);
+            let text = &map.input()[line_span.clone()];
+
+            // Underline the token, up to the end of its first line
+            let end = span.end.clamp(span.start, line_span.end);
+            let len = map.input()[span.start.min(end)..end].chars().count().max(1);
+
+            let line_no = line.to_string();
+            gutter_width = line_no.len();
+            let pad = " ".repeat(gutter_width);
+            let bar = self.paint(BLUE, "|");
+
+            lines.push(format!(
+                "{pad}{} {}:{line}:{col}",
+                self.paint(BLUE, "-->"),
+                token.filename().unwrap_or("input")
+            ));
+            lines.push(format!("{pad} {bar}"));
+            lines.push(format!("{} {bar} {text}", self.paint(BLUE, &line_no)));
+            lines.push(format!(
+                "{pad} {bar} {}{}",
+                " ".repeat(col - 1),
+                self.paint(level_style, &"^".repeat(len))
+            ));
+        }
+
+        let pad = " ".repeat(gutter_width);
+        for note in &diagnostic.notes {
+            lines.push(format!("{pad} {} {note}", self.paint(BLUE, "=")));
+        }
+
+        lines.join("\n")
+    }
+}
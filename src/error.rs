@@ -1,3 +1,9 @@
+use crate::{
+    compiler::CompilerError,
+    lexer::{LexerError, Rule, Token, TokenSpan},
+    parser::ParserError,
+};
+
 /// Error type for the language
 /// Encapsulates all possible errors during lexing, parsing, compiling, and running
 #[derive(thiserror::Error, Debug, Clone)]
@@ -18,3 +24,152 @@ pub enum Error {
     #[error("{0}")]
     Runtime(#[from] crate::vm::error::RuntimeError),
 }
+
+impl Error {
+    /// Get the stable code identifying the kind of error, such as `E0100` for syntax errors,
+    /// or `E0402` for undefined variables, so that hosts can handle errors without matching on text
+    /// - `E0001` to `E0099`: tokenizing
+    /// - `E0100` to `E0199`: parsing
+    /// - `E0200` to `E0299`: compiling
+    /// - `E0300` to `E0399`: bugs in the VM
+    /// - `E0400` to `E0499`: running the script
+    /// - `E0500` to `E0599`: operations on values
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::Lavendeux;
+    /// let mut lav = Lavendeux::new();
+    /// assert_eq!(lav.run("x + 1").unwrap_err().code(), "E0402");
+    /// assert_eq!(lav.run("1 +").unwrap_err().code(), "E0100");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Lexer(e) => e.code(),
+            Error::Parser(e) => e.code(),
+            Error::Compiler(e) => e.code(),
+            Error::Runtime(e) => e.code(),
+        }
+    }
+
+    /// Get the token the error occurred at, if known
+    /// Runtime errors only have a token if the program was compiled with a debug profile
+    pub fn token(&self) -> Option<&Token<'static>> {
+        match self {
+            Error::Lexer(LexerError::UnrecognizedToken(token)) => Some(token),
+            Error::Parser(e) => Some(e.token()),
+            Error::Compiler(e) => Some(e.token()),
+            Error::Runtime(e) => e.token.as_ref(),
+        }
+    }
+
+    /// Get the span of the input the error occurred at, if known
+    pub fn span(&self) -> Option<TokenSpan> {
+        self.token().map(Token::span)
+    }
+
+    /// Get the line the error occurred on, if known
+    pub fn line(&self) -> Option<usize> {
+        self.token().map(Token::line)
+    }
+
+    /// Get the column the error occurred at, if known
+    pub fn column(&self) -> Option<usize> {
+        self.token().map(Token::column)
+    }
+
+    /// Get the input surrounding the error, with a line beneath highlighting it, if known
+    pub fn context(&self) -> Option<String> {
+        self.token().map(Token::context_slice)
+    }
+
+    /// Check if the error was caused by the input ending too early, such as an open bracket,
+    /// block, string or comment, so that more input could complete it
+    /// REPLs use this to decide whether to prompt for another line
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::Lavendeux;
+    /// let mut lav = Lavendeux::new();
+    /// assert!(lav.run("x = [1, 2").unwrap_err().is_recoverable());
+    /// assert!(lav.run("s = 'unfinished").unwrap_err().is_recoverable());
+    /// assert!(!lav.run("x = [1, 2)").unwrap_err().is_recoverable());
+    /// ```
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            // Strings and block comments can span several lines
+            Error::Lexer(LexerError::UnrecognizedToken(token)) => {
+                let slice = token.slice();
+                token.span().end == token.input().len()
+                    && ["'", "\"", "/*"].iter().any(|open| slice.starts_with(open))
+            }
+
+            Error::Parser(ParserError::Syntax { found, .. })
+            | Error::Compiler(CompilerError::Parser(ParserError::Syntax { found, .. })) => {
+                found.rule() == Rule::EOI
+            }
+
+            _ => false,
+        }
+    }
+
+    /// Break the error down into a structured diagnostic
+    pub fn diagnostic(&self) -> crate::diagnostic::Diagnostic {
+        self.into()
+    }
+
+    /// Render the error in the style of rustc, using colors if stderr is a terminal
+    /// `source` should be the source map for the input that caused the error
+    #[cfg(feature = "cli")]
+    pub fn render(&self, source: &crate::lexer::SourceMap<'_>) -> String {
+        crate::diagnostic::Renderer::new().render(&self.diagnostic(), source)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_error_codes() {
+        let mut lav = Lavendeux::new();
+        let code = |lav: &mut Lavendeux, script: &str| lav.run(script).unwrap_err().code();
+        assert_eq!(code(&mut lav, "1 +"), "E0100");
+        assert_eq!(code(&mut lav, "break"), "E0205");
+        assert_eq!(code(&mut lav, "undefined_name"), "E0402");
+        assert_eq!(code(&mut lav, "1 / 0"), "E0505");
+        assert_eq!(code(&mut lav, "a = [1]\na[5]"), "E0509");
+        assert_eq!(code(&mut lav, "throw('oops')"), "E0431");
+
+        // Errors inside functions have the code of the error itself
+        assert_eq!(code(&mut lav, "f(x) = x / 0\nf(1)"), "E0505");
+    }
+
+    #[test]
+    fn test_error_locations() {
+        let mut lav = Lavendeux::new();
+
+        let error = lav.run("x = 1\ny = 2 +* 3").unwrap_err();
+        assert_eq!(error.line(), Some(2));
+        assert_eq!(error.column(), Some(8));
+        assert_eq!(error.span(), Some(13..14));
+        assert!(error.context().unwrap().contains("y = 2 +*"));
+        assert!(!error.is_recoverable());
+
+        // Input ending early can be completed by more lines
+        for source in [
+            "[1, 2",
+            "f(x) = {",
+            "1 +",
+            "if true then",
+            "'abc",
+            "/* comment",
+        ] {
+            let error = lav.run(source).unwrap_err();
+            assert!(error.is_recoverable(), "{source}");
+        }
+        for source in ["[1, 2)", "`abc", "undefined_name"] {
+            let error = lav.run(source).unwrap_err();
+            assert!(!error.is_recoverable(), "{source}");
+        }
+    }
+}
@@ -2,9 +2,21 @@ use crate::{
     compiler::{CompilerOptions, DebugProfile},
     error::Error,
     lexer::Stack,
-    parser::{core::ScriptNode, ParserNode},
-    value::Value,
-    vm::{memory_manager::MemoryManager, VirtualMachine},
+    parser::{build_ast, ParserOptions},
+    run_output::{RunOutput, RunReport},
+    value::{
+        CollisionPolicy, DivisionByZero, IntegerOverflow, LibraryError, Primitive, StdFunctionSet,
+        Value,
+    },
+    vm::{
+        memory_manager::MemoryManager, AngleMode, CapturedOutput, Clock, CurrencyConverter,
+        OutputSink, ReplayLog, Tracer, VirtualMachine,
+    },
+};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
 };
 
 /// Main structure for interacting with the Lavendeux parser
@@ -12,9 +24,15 @@ use crate::{
 pub struct Lavendeux {
     vm: VirtualMachine,
     options: CompilerOptions,
+    parser_options: ParserOptions,
+    constants: HashSet<String>,
+    globals: HashSet<String>,
 }
 
 impl Lavendeux {
+    /// The name a context object is bound to by `run_with_context`
+    pub const CONTEXT: &'static str = "ctx";
+
     /// Create a new Lavendeux instance
     /// Allocates a new memory manager for the instance
     pub fn new() -> Self {
@@ -30,9 +48,370 @@ impl Lavendeux {
         Self {
             vm: VirtualMachine::with_mem(mem),
             options,
+            parser_options: ParserOptions::default(),
+            constants: HashSet::new(),
+            globals: HashSet::new(),
         }
     }
 
+    /// Set the limits used when parsing source code
+    /// Useful when compiling untrusted input
+    pub fn set_parser_options(&mut self, options: ParserOptions) {
+        self.parser_options = options;
+    }
+
+    /// Set the unit used for angles by the trigonometric functions
+    /// Scripts can also change this with `set_angle_mode`
+    pub fn set_angle_mode(&mut self, mode: AngleMode) {
+        self.vm.set_angle_mode(mode);
+    }
+
+    /// Get the unit currently used for angles by the trigonometric functions
+    pub fn angle_mode(&self) -> AngleMode {
+        self.vm.angle_mode()
+    }
+
+    /// Set whether `-` can be used on strings; enabled by default
+    /// `a - b` removes every copy of `b` from `a`, and `-a` reverses `a`
+    /// When disabled these are errors, and scripts use `remove` and `reverse` instead
+    /// Either way, using `-` on a string literal causes a warning when compiling
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{value::Value, Error, Lavendeux};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// lav.set_creative_string_ops(false);
+    /// assert_eq!(lav.run("remove('abc', 'b')")?, Value::string("ac".to_string()));
+    /// assert!(lav.run("x = 'abc'; x - 'b'").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_creative_string_ops(&mut self, enabled: bool) {
+        self.vm.set_creative_string_ops(enabled);
+    }
+
+    /// Set whether arithmetic on booleans, such as `true + true`, is an error; disabled by default
+    /// When disabled, `+` and `-` act as XOR on booleans, and `*`, `/`, `%` and `**` act as AND
+    /// Booleans mixed with numbers are still treated as 0 or 1
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{value::Value, Error, Lavendeux};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// assert_eq!(lav.run("true + true")?, Value::boolean(false));
+    ///
+    /// lav.set_strict_booleans(true);
+    /// assert_eq!(lav.run("(true as int) + (true as int)")?, Value::integer(2));
+    /// assert!(lav.run("true + true").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_strict_booleans(&mut self, strict: bool) {
+        self.vm.set_strict_booleans(strict);
+    }
+
+    /// Set whether scripts can read files from the host, with `read_file`; disabled by default
+    /// Only enable this for scripts that are trusted to see the host's files
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{Error, Lavendeux};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// assert!(lav.run("read_file('Cargo.toml')").is_err());
+    ///
+    /// lav.set_file_access(true);
+    /// assert!(lav.run("read_file('Cargo.toml')")?.to_string().contains("[package]"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_file_access(&mut self, enabled: bool) {
+        self.vm.set_file_access(enabled);
+    }
+
+    /// Set what dividing by zero, with `/` or `%`, results in; an error by default
+    /// Decimals cannot represent infinity, so `DivisionByZero::Infinity` gives the largest decimal instead
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{value::{DivisionByZero, Value}, Error, Lavendeux};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// lav.run("x = 0")?;
+    /// lav.set_division_by_zero(DivisionByZero::Nil);
+    /// assert_eq!(lav.run("5 / x")?, Value::nil());
+    ///
+    /// lav.set_division_by_zero(DivisionByZero::Error);
+    /// assert!(lav.run("5 % x").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_division_by_zero(&mut self, policy: DivisionByZero) {
+        self.vm.set_division_by_zero(policy);
+    }
+
+    /// Set what integer arithmetic that overflows results in; an error by default
+    /// Integers are 128 bits wide, and can wrap around or saturate at those limits instead
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{value::{IntegerOverflow, Value}, Error, Lavendeux};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// lav.run("max_int = 170141183460469231731687303715884105727")?;
+    /// assert!(lav.run("max_int + 1").is_err());
+    ///
+    /// lav.set_integer_overflow(IntegerOverflow::Saturate);
+    /// assert_eq!(lav.run("max_int + 1 == max_int")?, Value::boolean(true));
+    ///
+    /// lav.set_integer_overflow(IntegerOverflow::Wrap);
+    /// assert_eq!(lav.run("max_int + 1 == -max_int - 1")?, Value::boolean(true));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_integer_overflow(&mut self, policy: IntegerOverflow) {
+        self.vm.set_integer_overflow(policy);
+    }
+
+    /// Set the maximum number of nested function calls; the default is 10,000
+    /// Deeper calls, such as runaway recursion, fail with an error naming the functions involved
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.vm.set_max_call_depth(depth);
+    }
+
+    /// Set the largest collection a program may build, in bytes; the default is 512MiB
+    /// Larger arrays and objects, such as from `0..1000000000 as array`, fail with an error
+    pub fn set_memory_budget(&mut self, bytes: usize) {
+        self.vm.set_memory_budget(bytes);
+    }
+
+    /// Set where values printed by `echo`, warnings and thrown errors are sent
+    /// The default writes to stdout; closures taking a `&str` receive printed values
+    /// Returns the previous sink
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{vm::WriteSink, Error, Lavendeux};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// lav.set_stdout(Box::new(|text: &str| assert_eq!(text, "hello")));
+    /// lav.run("echo('hello')")?;
+    ///
+    /// lav.set_stdout(Box::new(WriteSink(std::io::sink())));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_stdout(&mut self, sink: Box<dyn OutputSink>) -> Box<dyn OutputSink> {
+        self.vm.set_stdout(sink)
+    }
+
+    /// Set a tracer to receive every instruction executed, with the source it was compiled from
+    /// Pass None to stop tracing; returns the previous tracer
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{vm::{OpCode, TraceEntry}, Error, Lavendeux};
+    /// # use std::{cell::RefCell, rc::Rc};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// let entries = Rc::new(RefCell::new(vec![]));
+    /// let log = entries.clone();
+    /// lav.set_tracer(Some(Box::new(move |entry: &TraceEntry| {
+    ///     log.borrow_mut().push(entry.clone())
+    /// })));
+    /// lav.run("x = 2; x * 3")?;
+    ///
+    /// let entries = entries.borrow();
+    /// let mul = entries.iter().find(|e| e.opcode == OpCode::MUL).unwrap();
+    /// assert!(mul.token.is_some());
+    ///
+    /// lav.set_tracer(None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_tracer(&mut self, tracer: Option<Box<dyn Tracer>>) -> Option<Box<dyn Tracer>> {
+        self.vm.set_tracer(tracer)
+    }
+
+    /// Set a converter used when arithmetic mixes two currencies, such as `$1 + 1€`
+    /// The right operand is converted into the currency of the left one
+    /// Pass None to make mixing currencies an error again; returns the previous converter
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{value::{CheckedArithmetic, Number, NumberSymbol}, Error, Lavendeux};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// assert!(lav.run("$1 + 1€").is_err());
+    ///
+    /// lav.set_currency_converter(Some(Box::new(|amount: &Number, _: &NumberSymbol| {
+    ///     amount.clone().checked_mul(Number::from_f64(2.0).ok()?).ok()
+    /// })));
+    /// assert_eq!(lav.run("$1 + 1€")?.to_string(), "$3");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_currency_converter(
+        &mut self,
+        converter: Option<Box<dyn CurrencyConverter>>,
+    ) -> Option<Box<dyn CurrencyConverter>> {
+        self.vm.set_currency_converter(converter)
+    }
+
+    /// Set the clock used by `now()` and `timestamp()`, such as a `FixedClock` for reproducible runs
+    /// Returns the previous clock
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{prelude::*, vm::FixedClock};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// lav.set_clock(Box::new(FixedClock(86400)));
+    /// assert_eq!(lav.run("timestamp()")?, Value::integer(86400));
+    /// assert_eq!(lav.run("format_date(timestamp())")?.to_string(), "1970-01-02 00:00:00");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) -> Box<dyn Clock> {
+        self.vm.set_clock(clock)
+    }
+
+    /// Start recording the nondeterministic inputs of every run, such as `rand()` and `now()`
+    /// Collect the log with `take_replay_log`, and pass it to `replay` to reproduce the runs
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{Error, Lavendeux};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// lav.record_replay();
+    /// let first = lav.run("rand_int(0, 1000)")?;
+    /// let log = lav.take_replay_log().unwrap();
+    ///
+    /// lav.replay(log);
+    /// assert_eq!(lav.run("rand_int(0, 1000)")?, first);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn record_replay(&mut self) {
+        self.vm.record_replay();
+    }
+
+    /// Replay a recorded log, so runs receive the same nondeterministic inputs as when it was recorded
+    pub fn replay(&mut self, log: ReplayLog) {
+        self.vm.replay(log);
+    }
+
+    /// Stop recording or replaying, and return the log
+    /// Returns None if neither was started
+    pub fn take_replay_log(&mut self) -> Option<ReplayLog> {
+        self.vm.take_replay_log()
+    }
+
+    /// Add a set of compiled functions, available in every run
+    /// Replaces any function or variable with the same name
+    ///
+    /// Modules loaded with `StdFunctionSet::from_shared_bytes` or `StdFunctionSet::map_file`
+    /// keep function bodies in the module until each function is first called
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{traits::SerializeToBytes, value::{StdFunctionSet, Value}, Error, Lavendeux};
+    /// # use std::sync::Arc;
+    /// # fn main() -> Result<(), Error> {
+    /// // Compile a module, as the compiler's `-F` flag does
+    /// let module = Lavendeux::new().compile_functions("triple(x) = x * 3")?.serialize_into_bytes();
+    ///
+    /// let mut lav = Lavendeux::new();
+    /// lav.load_functions(StdFunctionSet::from_shared_bytes(Arc::new(module)).unwrap());
+    /// assert_eq!(lav.run("triple(14)")?, Value::integer(42));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_functions(&mut self, functions: StdFunctionSet) {
+        self.vm.load_functions(functions);
+    }
+
+    /// Load a library of functions from a file, such as one written by the compiler's `--lib` flag
+    /// Functions with the same name as an existing global are handled according to the policy
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{traits::SerializeToBytes, value::{CollisionPolicy, Value}, Lavendeux};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let library = Lavendeux::new().compile_functions("abs(x) = 'mine'\ntriple(x) = x * 3")?;
+    /// let path = std::env::temp_dir().join(format!("lav_doc_{}.lbc", std::process::id()));
+    /// std::fs::write(&path, library.serialize_into_bytes())?;
+    ///
+    /// // `abs` is already defined by the stdlib
+    /// let mut lav = Lavendeux::new();
+    /// assert!(lav.load_library(&path, CollisionPolicy::Error).is_err());
+    /// lav.load_library(&path, CollisionPolicy::Skip)?;
+    /// assert_eq!(lav.run("[abs(-1), triple(2)]")?.to_string(), "[1, 6]");
+    /// # std::fs::remove_file(&path).ok();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_library(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+        policy: CollisionPolicy,
+    ) -> Result<(), LibraryError> {
+        let bytes = std::fs::read(path)?;
+        let functions = StdFunctionSet::from_shared_bytes(std::sync::Arc::new(bytes))?;
+        self.vm.load_functions_with(functions, policy)
+    }
+
+    /// Compile a source string into a set of functions, which can be saved and loaded later
+    /// The source is run once in an empty VM, without the stdlib, to define its functions
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::prelude::*;
+    /// # fn main() -> Result<(), Error> {
+    /// let functions = Lavendeux::new().compile_functions("double(x) = x * 2")?;
+    /// assert_eq!(functions.functions()[0].docs.name, "double");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compile_functions(&mut self, source: &str) -> Result<StdFunctionSet, Error> {
+        let (profile, bytecode) = self.compile(source)?;
+        StdFunctionSet::from_bytecode(bytecode, Some(profile)).map_err(Error::Runtime)
+    }
+
+    /// Define a constant, available in every run
+    /// Constants are read like variables, but scripts cannot reassign or delete them
+    /// Replaces any existing constant with the same name
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{value::Value, Error, Lavendeux};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// lav.define_constant("answer", Value::integer(42));
+    /// assert_eq!(lav.run("answer")?, Value::integer(42));
+    /// assert!(lav.run("answer = 1").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn define_constant(&mut self, name: &str, value: Value) {
+        self.vm.define_constant(name, value);
+        self.constants.insert(name.to_string());
+    }
+
+    /// Get the value of a constant by name
+    /// Includes constants declared by scripts with `const`
+    pub fn constant(&self, name: &str) -> Option<Value> {
+        self.vm.constant(name)
+    }
+
+    /// Get the names of all constants, including those declared by scripts with `const`
+    pub fn constant_names(&self) -> impl Iterator<Item = &str> {
+        self.constants.iter().map(String::as_str)
+    }
+
     /// Compile a source string into a debug profile and bytecode.
     /// Returns an error if the source string is invalid.
     ///
@@ -41,25 +420,66 @@ impl Lavendeux {
     ///
     /// Example:
     /// ```rust
-    /// # use lavendeux::{Lavendeux, Error};
+    /// # use lavendeux_parser::{prelude::*, vm::OpCode};
     /// # fn main() -> Result<(), Error> {
     /// let mut lav = Lavendeux::new();
     /// let (profile, bytecode) = lav.compile("!true")?;
-    /// assert_eq!(bytecode, vec![0x00, 0x01, 0x31]); // [PUSH true; LNOT]
+    ///
+    /// // [PUSH true; LNOT]
+    /// assert_eq!(bytecode[0], OpCode::PUSH as u8);
+    /// assert_eq!(bytecode.last(), Some(&(OpCode::LNOT as u8)));
     /// # Ok(())
     /// # }
+    /// ```
     pub fn compile<'source>(
         &mut self,
         source: &'source str,
     ) -> Result<(DebugProfile, Vec<u8>), Error> {
-        let lexer = crate::lexer::Lexer::new(source);
-        let tokens = lexer.all_tokens()?;
+        trace_span!("compile", len = source.len());
+
+        let tokens = {
+            trace_span!("lex");
+            crate::lexer::Lexer::new(source)
+                .with_custom_operators(&self.parser_options.custom_operators)
+                .all_tokens()?
+        };
 
-        let mut stack = Stack::new(tokens);
-        let ast = ScriptNode::parse(&mut stack).ok_or_else(|| stack.emit_err())?;
+        let ast = {
+            trace_span!("parse", tokens = tokens.len());
+            build_ast(Stack::new(tokens), self.parser_options.clone())?
+        };
 
         let mut compiler = crate::compiler::Compiler::new(source, self.options.clone());
-        ast.compile(&mut compiler)?;
+        for name in &self.constants {
+            compiler.define_constant(name.clone());
+        }
+        for name in &self.globals {
+            compiler.define_global(name.clone());
+        }
+
+        // Variables left by earlier runs are overwritten by loops, not discarded
+        for name in self.vm.variable_names() {
+            compiler.define_variable(name.to_string());
+        }
+        {
+            trace_span!("codegen");
+            ast.compile(&mut compiler)?;
+        }
+        {
+            trace_span!("optimize");
+            compiler.optimize();
+        }
+
+        // Constants declared by the script stay protected in later runs
+        self.constants.extend(compiler.constants().iter().cloned());
+
+        // So do globals, which later plain assignments keep writing to
+        self.globals.extend(compiler.globals().iter().cloned());
+
+        // Warnings go to the same place as those raised by `warn`
+        for warning in compiler.warnings() {
+            self.vm.warn(&warning.to_string());
+        }
 
         Ok(compiler.decompose())
     }
@@ -69,15 +489,20 @@ impl Lavendeux {
     ///
     /// Example:
     /// ```rust
-    /// # use lavendeux::{Lavendeux, Error};
+    /// # use lavendeux_parser::prelude::*;
     /// # fn main() -> Result<(), Error> {
     /// let mut lav = Lavendeux::new();
-    ///
+    /// let (profile, bytecode) = lav.compile("1 + 2")?;
+    /// assert_eq!(lav.execute(bytecode, Some(profile))?, Value::integer(3));
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn execute<'source>(
         &mut self,
         bytecode: Vec<u8>,
         profile: Option<DebugProfile>,
     ) -> Result<Value, Error> {
+        trace_span!("execute", len = bytecode.len());
         self.vm.run(bytecode, profile).map_err(Error::Runtime)
     }
 
@@ -87,15 +512,147 @@ impl Lavendeux {
     ///
     /// Example:
     /// ```rust
-    /// # use lavendeux::{Lavendeux, Error, Value};
+    /// # use lavendeux_parser::prelude::*;
     /// # fn main() -> Result<(), Error> {
     /// let mut lav = Lavendeux::new();
     /// let result = lav.run("1 + 2")?;
-    /// assert_eq!(result, Value::Integer(3));
+    /// assert_eq!(result, Value::integer(3));
     /// # Ok(())
     /// # }
+    /// ```
     pub fn run<'source>(&mut self, source: &'source str) -> Result<Value, Error> {
         let (profile, bytecode) = self.compile(source)?;
         self.execute(bytecode, Some(profile))
     }
+
+    /// Run a source string with a context object from the host, such as request-scoped data
+    /// The object is bound to the read-only name `ctx` for this run only, and is removed afterwards
+    /// A constant of the same name defined by the host is restored once the run is over
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{prelude::*, value::Primitive};
+    /// # use std::collections::HashMap;
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// let mut context = HashMap::new();
+    /// context.insert(Primitive::String("user".to_string()), Value::string("ada".to_string()));
+    ///
+    /// let result = lav.run_with_context("ctx.user", context)?;
+    /// assert_eq!(result, Value::string("ada".to_string()));
+    /// assert!(lav.run("ctx").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run_with_context(
+        &mut self,
+        source: &str,
+        context: HashMap<Primitive, Value>,
+    ) -> Result<Value, Error> {
+        let name = Self::CONTEXT;
+        let previous = self.vm.constant(name);
+        let declared = self.constants.insert(name.to_string());
+        self.vm.define_constant(name, Value::Object(context));
+
+        let result = self.run(source);
+
+        self.vm.remove_constant(name);
+        if let Some(value) = previous {
+            self.vm.define_constant(name, value);
+        }
+        if declared {
+            self.constants.remove(name);
+        }
+
+        result
+    }
+
+    /// Run a source string, separating out results a front-end can present specially
+    /// Returns one output per line, with plot data from the `plot` function as `RunOutput::Plot`
+    /// and values passed to a decorator, such as `255 @hex`, as `RunOutput::Decorated`
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{Error, Lavendeux, RunOutput};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// let output = lav.run_output("plot(tabulate('x ** 2', 0..2, 2))")?;
+    /// match &output[0] {
+    ///     RunOutput::Plot(plot) => assert_eq!(plot.points[2], (2.0, 4.0)),
+    ///     _ => panic!("Expected plot data"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run_output(&mut self, source: &str) -> Result<Vec<RunOutput>, Error> {
+        self.run(source).map(RunOutput::from_result)
+    }
+
+    /// Run a source string, collecting everything it produces into a report
+    /// Printed values and warnings are kept in the report instead of being written to the console
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{Error, Lavendeux};
+    /// # fn main() -> Result<(), Error> {
+    /// let mut lav = Lavendeux::new();
+    /// let report = lav.run_report("echo('hello')\nwarn('careful')\ntable([[1, 2]])")?;
+    /// assert_eq!(report.printed, vec!["hello".to_string()]);
+    /// assert_eq!(report.warnings, vec!["careful".to_string()]);
+    /// assert_eq!(report.artifacts().count(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run_report(&mut self, source: &str) -> Result<RunReport, Error> {
+        let captured = Rc::new(RefCell::new(CapturedOutput::default()));
+        let previous = self.vm.set_stdout(Box::new(captured.clone()));
+
+        let start = std::time::Instant::now();
+        let compiled = self.compile(source);
+        let compile_time = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let result = compiled.and_then(|(profile, bytecode)| self.execute(bytecode, Some(profile)));
+        let run_time = start.elapsed();
+
+        // Stop capturing even if the program failed
+        self.vm.set_stdout(previous);
+        let captured = captured.take();
+        Ok(RunReport {
+            outputs: RunOutput::from_result(result?),
+            printed: captured.printed,
+            warnings: captured.warnings,
+            compile_time,
+            run_time,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_context_object() {
+        let mut lav = Lavendeux::new();
+        let context = HashMap::from([(Primitive::String("id".to_string()), Value::integer(7))]);
+
+        assert_eq!(
+            lav.run_with_context("ctx.id * 2", context.clone()).unwrap(),
+            Value::integer(14)
+        );
+
+        // Read-only, and gone once the run is over
+        assert!(lav.run_with_context("ctx.id = 1", context.clone()).is_err());
+        assert!(lav.run_with_context("ctx = 1", context.clone()).is_err());
+        assert!(lav.run("ctx").is_err());
+        assert!(lav.run("ctx = 1").is_ok());
+
+        // A host constant with the same name comes back afterwards
+        let mut lav = Lavendeux::new();
+        lav.define_constant("ctx", Value::integer(1));
+        lav.run_with_context("ctx.id", context).unwrap();
+        assert_eq!(lav.run("ctx").unwrap(), Value::integer(1));
+        assert!(lav.run("ctx = 2").is_err());
+    }
 }
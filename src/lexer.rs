@@ -4,7 +4,8 @@
 //! `Token` is the main token type.
 //! `Rule` is the set of rules for the lexer.
 //! `Stack` is a token queue with rewind used by the parser.
-use crate::traits::IntoOwned;
+//! `SourceMap` maps positions in the input to lines and columns.
+use crate::{parser::CustomOperator, traits::IntoOwned};
 use logos::Logos;
 use std::borrow::Cow;
 
@@ -12,7 +13,7 @@ mod error;
 pub use error::LexerError;
 
 mod stack;
-pub use stack::Stack;
+pub use stack::{Checkpoint, Stack};
 
 mod rule;
 pub use rule::Rule;
@@ -23,11 +24,16 @@ pub use token::{SerializedToken, Token, TokenSpan};
 mod category;
 pub use category::Category;
 
+mod source_map;
+pub use source_map::SourceMap;
+
 /// A lexer for the language
 /// Splits the input into tokens
 pub struct Lexer<'source> {
     source: logos::Lexer<'source, Rule>,
+    source_map: SourceMap<'source>,
     filename: Option<String>,
+    custom_operators: Vec<String>,
 }
 impl<'source> Lexer<'source> {
     /// Creates a new lexer from the input
@@ -38,18 +44,38 @@ impl<'source> Lexer<'source> {
     /// Creates a new lexer from the input with a filename
     pub fn with_filename(input: &'source str, filename: Option<String>) -> Self {
         Self {
-            source: Rule::lexer_with_extras(input, 1),
+            source: Rule::lexer(input),
+            source_map: SourceMap::new(input),
             filename: filename,
+            custom_operators: vec![],
         }
     }
 
+    /// Recognize the symbols of custom infix operators, as `Rule::CustomOperator`
+    /// The longest matching symbol is used, and symbols are matched before the built-in rules
+    pub fn with_custom_operators(mut self, operators: &[CustomOperator]) -> Self {
+        self.custom_operators = operators
+            .iter()
+            .filter(|op| !op.symbol.is_empty())
+            .map(|op| op.symbol.clone())
+            .collect();
+        self.custom_operators
+            .sort_by_key(|s| std::cmp::Reverse(s.len()));
+        self
+    }
+
     /// Consumes and returns the next token
     pub fn consume_next(&mut self) -> Token<'source> {
+        if let Some(t) = self.next_custom_operator() {
+            return t;
+        }
+
         let token = self.source.next().unwrap_or_else(|| Ok(Rule::EOI));
         let input = self.source.source();
+        let span = self.source.span();
         let mut t = Token::new(
-            self.source.extras,
-            self.source.span(),
+            self.source_map.line(span.start),
+            span,
             token.unwrap_or_else(|_| Rule::Error),
             Cow::Borrowed(input),
         );
@@ -59,6 +85,44 @@ impl<'source> Lexer<'source> {
         t
     }
 
+    /// Consumes a custom operator, if one is next in the input
+    fn next_custom_operator(&mut self) -> Option<Token<'source>> {
+        if self.custom_operators.is_empty() {
+            return None;
+        }
+
+        // Whitespace is skipped the same way as the built-in rules skip it
+        let remainder = self.source.remainder();
+        let rest = remainder.trim_start_matches([' ', '\t', '\r', '\x0C']);
+        let symbol = self.custom_operators.iter().find(|symbol| {
+            // Symbols ending in a letter must not split an identifier
+            let word_char = |c: char| c.is_alphanumeric() || c == '_';
+            rest.starts_with(symbol.as_str())
+                && !(symbol.ends_with(word_char) && rest[symbol.len()..].starts_with(word_char))
+        })?;
+
+        let start = self.source.span().end + remainder.len() - rest.len();
+        let span = start..start + symbol.len();
+        self.source
+            .bump(remainder.len() - rest.len() + symbol.len());
+
+        let mut t = Token::new(
+            self.source_map.line(span.start),
+            span,
+            Rule::CustomOperator,
+            Cow::Borrowed(self.source.source()),
+        );
+        if let Some(filename) = &self.filename {
+            t = t.add_filename(filename.clone());
+        }
+        Some(t)
+    }
+
+    /// Get the source map for the input
+    pub fn source_map(&self) -> &SourceMap<'source> {
+        &self.source_map
+    }
+
     /// Consumes this iterator, returning all tokens in the input
     pub fn all_tokens(mut self) -> Result<Vec<Token<'source>>, LexerError> {
         let mut tokens = vec![];
@@ -116,10 +180,67 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_line_numbers() {
+        let input = "a\n/* block\ncomment */ b\n\"multi\nline\" c\nd";
+        let tokens = Lexer::new(input).all_tokens().unwrap();
+        let lines = tokens
+            .iter()
+            .filter(|t| t.rule() != Rule::EOL)
+            .map(|t| (t.slice(), t.line()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                ("a", 1),
+                ("b", 3),
+                ("\"multi\nline\"", 4),
+                ("c", 5),
+                ("d", 6),
+                ("", 6)
+            ]
+        );
+
+        let map = SourceMap::new(input);
+        assert_eq!(map.line_col(input.rfind('c').unwrap()), (5, 7));
+        assert_eq!(map.line_text(3), Some("comment */ b"));
+    }
+
     #[test]
     fn test_const_indents_keywords() {
         assert_tokens!("returned", vec![Rule::LiteralIdent, Rule::EOI]);
         assert_tokens!("pies", vec![Rule::LiteralIdent, Rule::EOI]);
         assert_tokens!("applepi", vec![Rule::LiteralIdent, Rule::EOI]);
     }
+
+    #[test]
+    fn test_unicode() {
+        assert_tokens!(
+            "2 × π ÷ 3",
+            vec![
+                Rule::LiteralInt,
+                Rule::Mul,
+                Rule::LiteralConstPi,
+                Rule::Div,
+                Rule::LiteralInt,
+                Rule::EOI
+            ]
+        );
+        assert_tokens!(
+            "a ≤ b ≥ c ≠ d",
+            vec![
+                Rule::LiteralIdent,
+                Rule::Le,
+                Rule::LiteralIdent,
+                Rule::Ge,
+                Rule::LiteralIdent,
+                Rule::Ne,
+                Rule::LiteralIdent,
+                Rule::EOI
+            ]
+        );
+        assert_tokens!("größe", vec![Rule::LiteralIdent, Rule::EOI]);
+        assert_tokens!("πr", vec![Rule::LiteralIdent, Rule::EOI]);
+        assert_tokens!("_變數1", vec![Rule::LiteralIdent, Rule::EOI]);
+    }
 }
@@ -64,7 +64,9 @@ impl Category {
             | Rule::Comma
             | Rule::Colon
             | Rule::Range
+            | Rule::Spread
             | Rule::Dot
+            | Rule::OptionalDot
             | Rule::Question
             | Rule::Decorator => Category::Symbol(vec![rule]),
 
@@ -97,13 +99,16 @@ impl Category {
             | Rule::LogicalNot
             | Rule::SEq
             | Rule::SNe
+            | Rule::IEq
             | Rule::Eq
             | Rule::Ne
             | Rule::Le
             | Rule::Ge
             | Rule::Lt
             | Rule::Gt
-            | Rule::FatArrow => Category::Operator(vec![rule]),
+            | Rule::NilCoalesce
+            | Rule::FatArrow
+            | Rule::CustomOperator => Category::Operator(vec![rule]),
 
             Rule::If
             | Rule::Then
@@ -112,16 +117,19 @@ impl Category {
             | Rule::In
             | Rule::Do
             | Rule::Where
+            | Rule::Discard
             | Rule::Switch
             | Rule::Return
             | Rule::Continue
             | Rule::Break
             | Rule::Delete
             | Rule::As
+            | Rule::Is
             | Rule::Contains
             | Rule::Matches
             | Rule::StartsWith
-            | Rule::EndsWith => Category::Keyword(vec![rule]),
+            | Rule::EndsWith
+            | Rule::Alias => Category::Keyword(vec![rule]),
 
             Rule::LiteralIdent => Category::Identifier,
 
@@ -136,7 +144,8 @@ impl Category {
             | Rule::LiteralSuffixedCurrency
             | Rule::LiteralFloat
             | Rule::LiteralRegex
-            | Rule::LiteralString => Category::Literal,
+            | Rule::LiteralString
+            | Rule::LiteralBytes => Category::Literal,
 
             Rule::DocBlockComment => Category::DocBlock,
 
@@ -151,10 +160,11 @@ impl Category {
             .filter_map(|r| Category::from_rule(*r))
             .collect::<Vec<_>>();
 
-        let mut symbols = std::collections::HashSet::new();
-        let mut keywords = std::collections::HashSet::new();
-        let mut operators = std::collections::HashSet::new();
-        let mut set = std::collections::HashSet::new();
+        // Ordered sets, so that error messages list the categories in a stable order
+        let mut symbols = std::collections::BTreeSet::new();
+        let mut keywords = std::collections::BTreeSet::new();
+        let mut operators = std::collections::BTreeSet::new();
+        let mut set = std::collections::BTreeSet::new();
 
         for c in categories.into_iter() {
             match c {
@@ -1,9 +1,18 @@
 use super::Token;
 
 /// Error during lexing.
-#[derive(thiserror::Error, Debug, Clone)]
+#[derive(thiserror::Error, Debug, Clone, strum_macros::IntoStaticStr)]
 pub enum LexerError {
     /// Encountered an unexpected token.
     #[error("| {}\n= Unrecognized token", .0.slice())]
     UnrecognizedToken(Token<'static>),
 }
+
+impl LexerError {
+    /// Get the stable code identifying the kind of error, such as `E0001`
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexerError::UnrecognizedToken(_) => "E0001",
+        }
+    }
+}
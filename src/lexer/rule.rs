@@ -1,7 +1,7 @@
 use logos::{Logos, Skip};
 use serde::{Deserialize, Serialize};
 
-use crate::traits::SerializeToBytes;
+use crate::traits::{ByteReader, SerializeToBytes};
 
 /// Main lexer rule set for the language
 #[allow(missing_docs)]
@@ -10,21 +10,14 @@ use crate::traits::SerializeToBytes;
 )]
 #[logos(skip r"[ \t\r\f]+")]
 #[logos(subpattern currency_symbol = r"[$¢£¤¥֏؋߾߿৲৳৻૱௹฿៛₠₡₢₣₤₥₦₧₨₩₪₫€₭₮₯₰₱₲₳₴₵₶₷₸₹₺₻₼₽₾₿꠸﷼﹩＄￠￡￥￦]")]
-#[logos(extras = usize)]
 #[repr(u16)]
 #[derive(strum_macros::Display)]
 pub enum Rule {
     #[strum(to_string = "end of input")]
     EOI,
 
-    #[token("\\\n", callback = |lex| {
-        lex.extras += 1;
-        Skip
-    })]
-    #[token("\\\r\n", callback = |lex| {
-        lex.extras += 1;
-        Skip
-    })]
+    #[token("\\\n", |_| Skip)]
+    #[token("\\\r\n", |_| Skip)]
     SkippedEOL,
 
     Script,
@@ -33,6 +26,9 @@ pub enum Rule {
     #[strum(to_string = "[expression] as [type]")]
     CastExpr,
 
+    #[strum(to_string = "[expression] is [type]")]
+    IsExpr,
+
     #[strum(to_string = "[expression] @[decorator]")]
     DecoratorExpr,
 
@@ -61,9 +57,11 @@ pub enum Rule {
 
     Array,
     Object,
+    SpreadExpr,
 
     IfExpr,
     TernaryExpr,
+    NilCoalesceExpr,
     SwitchExpr,
     ForExpr,
     ReturnExpr,
@@ -72,12 +70,18 @@ pub enum Rule {
 
     FnCallExpr,
     FnAssignExpr,
+    AliasExpr,
+    TypeDefExpr,
+    EnumDefExpr,
+    ConstDefExpr,
+    VarDefExpr,
+    LetExpr,
 
     //
     // Symbols
     //
     #[strum(to_string = "end of line")]
-    #[regex("\n", |l| {l.extras += 1})]
+    #[regex("\n")]
     #[regex(";")]
     EOL,
 
@@ -115,6 +119,12 @@ pub enum Rule {
     #[strum(to_string = "?")]
     #[token("?")]
     Question,
+    #[strum(to_string = "?.")]
+    #[token("?.")]
+    OptionalDot,
+    #[strum(to_string = "??")]
+    #[token("??")]
+    NilCoalesce,
 
     #[strum(to_string = "=>")]
     #[token("=>")]
@@ -122,6 +132,9 @@ pub enum Rule {
     #[strum(to_string = "..")]
     #[token("..")]
     Range,
+    #[strum(to_string = "...")]
+    #[token("...")]
+    Spread,
 
     #[strum(to_string = "@")]
     #[token("@")]
@@ -172,17 +185,7 @@ pub enum Rule {
     DocBlockComment,
     #[regex(r"//[^\n]*", |_| Skip)]
     LineComment,
-    #[regex(r"/\*([^*]|\*[^/])*\*/", |lex| {
-        // Count the number of newlines in the block comment
-        let mut count = 0;
-        for c in lex.slice().chars() {
-            if c == '\n' {
-                count += 1;
-            }
-        }
-        lex.extras += count;
-        Skip
-    })]
+    #[regex(r"/\*([^*]|\*[^/])*\*/", |_| Skip)]
     BlockComment,
 
     // Arithmetic operators
@@ -197,9 +200,11 @@ pub enum Rule {
     Pow,
     #[strum(to_string = "*")]
     #[token("*")]
+    #[token("×")]
     Mul,
     #[strum(to_string = "/")]
     #[token("/")]
+    #[token("÷")]
     Div,
     #[strum(to_string = "%")]
     #[token("%")]
@@ -243,17 +248,23 @@ pub enum Rule {
     #[strum(to_string = "!==")]
     #[token("!==")]
     SNe,
+    #[strum(to_string = "~=")]
+    #[token("~=")]
+    IEq,
     #[strum(to_string = "==")]
     #[token("==")]
     Eq,
     #[strum(to_string = "!=")]
     #[token("!=")]
+    #[token("≠")]
     Ne,
     #[strum(to_string = "<=")]
     #[token("<=")]
+    #[token("≤")]
     Le,
     #[strum(to_string = ">=")]
     #[token(">=")]
+    #[token("≥")]
     Ge,
     #[strum(to_string = "<")]
     #[token("<")]
@@ -287,6 +298,9 @@ pub enum Rule {
     #[strum(to_string = "where")]
     #[token("where")]
     Where,
+    #[strum(to_string = "discard")]
+    #[token("discard")]
+    Discard,
 
     #[strum(to_string = "switch")]
     #[token("switch")]
@@ -308,6 +322,9 @@ pub enum Rule {
     #[strum(to_string = "as")]
     #[token("as")]
     As,
+    #[strum(to_string = "is")]
+    #[token("is")]
+    Is,
 
     #[strum(to_string = "contains")]
     #[token("contains")]
@@ -326,10 +343,15 @@ pub enum Rule {
     #[token("ref")]
     Reference,
 
+    #[strum(to_string = "alias")]
+    #[token("alias")]
+    Alias,
+
     //
     // Value Literals
     //
     #[token("pi", priority = 2)]
+    #[token("π", priority = 2)]
     LiteralConstPi,
     #[token("e", priority = 2)]
     LiteralConstE,
@@ -342,7 +364,8 @@ pub enum Rule {
     #[token("false", priority = 2)]
     LiteralConstFalse,
 
-    #[regex(r"[a-zA-Z_][0-9A-Za-z_]*", priority = 1)]
+    // Unicode identifiers, following the XID rules
+    #[regex(r"[\p{XID_Start}_]\p{XID_Continue}*", priority = 1)]
     LiteralIdent,
 
     #[regex(r"0[a-zA-Z][a-zA-Z0-9]+")]
@@ -377,6 +400,14 @@ pub enum Rule {
     #[regex(r#"'([^'\\]+|\\.)*'"#)] // " string literal '
     LiteralString,
 
+    // Infix operators registered by the host; matched by the lexer itself, rather than a pattern
+    #[strum(to_string = "operator")]
+    CustomOperator,
+
+    #[regex(r#"0x"[^"]*""#, priority = 3)] // hex bytes literal "
+    #[regex(r#"b"[^"]*""#, priority = 3)] // base64 bytes literal "
+    LiteralBytes,
+
     Error,
 }
 
@@ -388,7 +419,7 @@ impl SerializeToBytes for Rule {
     }
 
     fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
+        bytes: &mut ByteReader<'_>,
     ) -> Result<Self, crate::traits::ByteDecodeError> {
         let rule = u16::deserialize_from_bytes(bytes)?;
         if rule > Rule::Error as u16 {
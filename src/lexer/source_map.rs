@@ -0,0 +1,105 @@
+use super::TokenSpan;
+use std::borrow::Cow;
+
+/// Maps byte offsets in a source string to line and column numbers
+/// Line starts are computed once, so lookups are a binary search
+#[derive(Clone, Debug)]
+pub struct SourceMap<'source> {
+    input: Cow<'source, str>,
+    line_starts: Vec<usize>,
+}
+
+impl<'source> SourceMap<'source> {
+    /// The maximum number of characters shown in a snippet
+    pub const CONTEXT_LEN: usize = 50;
+
+    /// Create a new source map for the given input
+    pub fn new(input: impl Into<Cow<'source, str>>) -> Self {
+        let input = input.into();
+        let mut line_starts = vec![0];
+        line_starts.extend(input.match_indices('\n').map(|(i, _)| i + 1));
+        Self { input, line_starts }
+    }
+
+    /// Get the input string
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Get the number of lines in the input
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Get the 1-based line number containing the given byte offset
+    pub fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|start| *start <= offset)
+    }
+
+    /// Get the 1-based (line, column) pair for the given byte offset
+    /// Columns are counted in characters, not bytes
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.input.len());
+        let line = self.line(offset);
+        let start = self.line_starts[line - 1];
+        let column = match self.input.get(start..offset) {
+            Some(s) => s.chars().count(),
+            None => offset - start,
+        };
+
+        (line, column + 1)
+    }
+
+    /// Get the byte span of the given 1-based line, excluding the line ending
+    pub fn line_span(&self, line: usize) -> Option<TokenSpan> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = match self.line_starts.get(line) {
+            Some(next) => next - 1,
+            None => self.input.len(),
+        };
+        let end = match self.input[..end].ends_with('\r') {
+            true => end - 1,
+            false => end,
+        };
+
+        Some(start..end)
+    }
+
+    /// Get the text of the given 1-based line, excluding the line ending
+    pub fn line_text(&self, line: usize) -> Option<&str> {
+        Some(&self.input[self.line_span(line)?])
+    }
+
+    /// Returns a slice of the input surrounding and ending after the span, with a maximum of 50 characters
+    /// Will include a line beneath highlighting the span
+    pub fn snippet(&self, span: TokenSpan) -> String {
+        let end = span.end.min(self.input.len());
+        let span_start = span.start.min(end);
+        let start = self.line_starts[self.line(span_start) - 1];
+
+        let mut line = &self.input[start..end];
+        let mut highlight_start = span_start - start;
+        let mut highlight_len = line.len() - highlight_start;
+
+        if highlight_len > Self::CONTEXT_LEN {
+            line = &line[highlight_start..highlight_start + Self::CONTEXT_LEN];
+            highlight_len = Self::CONTEXT_LEN;
+            highlight_start = 0;
+        } else if line.len() > Self::CONTEXT_LEN {
+            line = &line[line.len() - Self::CONTEXT_LEN..];
+            highlight_start = Self::CONTEXT_LEN - highlight_len;
+        }
+
+        let line = line
+            .split('\n')
+            .map(|l| format!("| {}", l))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!(
+            "{}\n| {}{}",
+            line,
+            " ".repeat(highlight_start),
+            "^".repeat(highlight_len)
+        )
+    }
+}
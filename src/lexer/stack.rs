@@ -1,10 +1,40 @@
 use crate::{
-    lexer::{Category, Rule, Token},
-    parser::ParserError,
+    lexer::{Category, Rule, Token, TokenSpan},
+    parser::{ParserError, ParserOptions},
     traits::IntoOwned,
 };
 
+/// A saved position in a `Stack`
+/// Created by `Stack::checkpoint`, and restored with `Stack::rollback`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    depth: usize,
+    len: usize,
+}
+
 /// Token queue with rewind
+///
+/// Besides the transactions used by the parser, the stack offers a cursor API
+/// for external tools such as formatters, linters or syntax highlighters:
+///
+/// ```rust
+/// # use lavendeux_parser::lexer::{Lexer, Rule, Stack};
+/// let tokens = Lexer::new("a = 1 + 2").all_tokens().unwrap();
+/// let mut stack = Stack::new(tokens);
+///
+/// // Look ahead without consuming anything
+/// assert_eq!(stack.peek_n(1).map(|t| t.rule()), Some(Rule::Assign));
+///
+/// // Consume some tokens, then find the span they covered
+/// let start = stack.checkpoint();
+/// stack.pop();
+/// stack.pop();
+/// assert_eq!(stack.span_since(start), Some(0..3));
+///
+/// // Go back to where we were
+/// stack.rollback(start);
+/// assert_eq!(stack.peek().map(|t| t.slice()), Some("a"));
+/// ```
 #[derive(Clone, Debug)]
 pub struct Stack<'source> {
     // All tokens in the stack
@@ -18,6 +48,15 @@ pub struct Stack<'source> {
 
     // The set of rules we tried to match at error_pos
     could_expect: Vec<Rule>,
+
+    // Limits for the parser
+    options: ParserOptions,
+
+    // Nesting depth of the non-terminals being parsed
+    nt_depth: usize,
+
+    // Set once one of the limits was exceeded
+    limit_error: Option<ParserError>,
 }
 impl<'source> Stack<'source> {
     /// Creates a new stack
@@ -29,7 +68,106 @@ impl<'source> Stack<'source> {
             cur_len: vec![len],
             error_pos: len - 1,
             could_expect: vec![],
+            options: ParserOptions::default(),
+            nt_depth: 0,
+            limit_error: None,
+        }
+    }
+
+    /// Sets the limits for the parser
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        if self.tokens.len() > options.max_tokens {
+            let token = self.tokens[self.tokens.len() - 1 - options.max_tokens].clone();
+            self.limit_error = Some(ParserError::TooManyTokens(
+                token.into_owned(),
+                options.max_tokens,
+            ));
+        }
+
+        self.options = options;
+        self
+    }
+
+    /// Get the options used by the parser
+    pub fn options(&self) -> &ParserOptions {
+        &self.options
+    }
+
+    /// Returns the error for the first limit that was exceeded, if any
+    pub fn limit_error(&self) -> Option<&ParserError> {
+        self.limit_error.as_ref()
+    }
+
+    /// Record that a limit was exceeded at the current position
+    fn exceed_limit(&mut self, error: impl FnOnce(Token<'static>) -> ParserError) {
+        if self.limit_error.is_none() {
+            let token = match self.tokens.get(self.len().saturating_sub(1)) {
+                Some(token) => token.clone().into_owned(),
+                None => self.error_token().clone().into_owned(),
+            };
+            self.limit_error = Some(error(token));
+        }
+    }
+
+    /// Enter a non-terminal, checking the depth limit
+    /// Returns false if a limit was exceeded, in which case parsing should stop
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub fn enter_nt(&mut self, name: &str) -> bool {
+        trace_event!(
+            target: "lavendeux::parser",
+            depth = self.nt_depth,
+            next = ?self.peek(),
+            "parsing {name}"
+        );
+
+        if self.limit_error.is_some() {
+            return false;
+        }
+
+        if self.nt_depth >= self.options.max_depth {
+            self.exceed_limit(ParserError::TooDeep);
+            return false;
         }
+
+        self.nt_depth += 1;
+        true
+    }
+
+    /// Check that an expression with `n` terms would not exceed the term limit
+    /// Returns false if a limit was exceeded, in which case parsing should stop
+    pub fn check_expr_terms(&mut self, n: usize) -> bool {
+        let max = self.options.max_expr_terms;
+        if n > max {
+            self.exceed_limit(|token| ParserError::TooManyTerms(token, max));
+        }
+
+        self.limit_error.is_none()
+    }
+
+    /// Check that an operator has not been disabled in the parser options
+    /// Returns false if it was, in which case parsing should stop
+    pub fn check_operator(&mut self, token: &Token<'source>) -> bool {
+        let rule = token.rule();
+        if self.limit_error.is_none() && self.options.disabled_operators.contains(&rule) {
+            let symbol = crate::parser::precedence_of(rule)
+                .map_or_else(|| rule.to_string(), |op| op.symbol());
+            self.limit_error = Some(ParserError::DisabledOperator(
+                token.clone().into_owned(),
+                symbol,
+            ));
+        }
+
+        self.limit_error.is_none()
+    }
+
+    /// Exit a non-terminal entered with `enter_nt`
+    /// `parsed` is true if the non-terminal was matched
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub fn exit_nt(&mut self, name: &str, parsed: bool) {
+        if parsed {
+            trace_event!(target: "lavendeux::parser", "parsed {name}");
+        }
+        self.nt_depth -= 1;
     }
 
     /// Get the current length of the stack
@@ -55,6 +193,10 @@ impl<'source> Stack<'source> {
 
     /// Emit an error with the current state
     pub fn emit_err(&self) -> ParserError {
+        if let Some(error) = &self.limit_error {
+            return error.clone();
+        }
+
         ParserError::Syntax {
             expected: self.could_expect.clone(),
             found: self.error_token().clone().into_owned(),
@@ -119,13 +261,61 @@ impl<'source> Stack<'source> {
 
     /// Peek at the next token on the stack
     pub fn peek(&self) -> Option<&Token<'source>> {
+        self.peek_n(0)
+    }
+
+    /// Peek `n` tokens ahead of the next token on the stack
+    /// `peek_n(0)` is the same as `peek()`
+    pub fn peek_n(&self, n: usize) -> Option<&Token<'source>> {
         let len = self.len();
-        match len {
-            0 => None,
-            _ => self.tokens.get(len - 1),
+        match len.checked_sub(n + 1) {
+            Some(i) => self.tokens.get(i),
+            None => None,
         }
     }
 
+    /// Returns true if there are no tokens left on the stack
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Save the current position in the stack
+    /// The position can be restored with `rollback`, as long as the transaction
+    /// that was active when the checkpoint was created is still open
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            depth: self.depth(),
+            len: self.len(),
+        }
+    }
+
+    /// Restore the stack to a position saved with `checkpoint`
+    /// Any transactions started after the checkpoint are discarded
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        if checkpoint.depth <= self.depth() {
+            self.cur_len.truncate(checkpoint.depth + 1);
+            *self.cur_len.last_mut().unwrap() = checkpoint.len;
+        }
+    }
+
+    /// Get the tokens consumed since a checkpoint, in order
+    pub fn tokens_since(
+        &self,
+        checkpoint: Checkpoint,
+    ) -> impl DoubleEndedIterator<Item = &Token<'source>> {
+        let end = checkpoint.len.min(self.tokens.len());
+        let start = self.len().min(end);
+        self.tokens[start..end].iter().rev()
+    }
+
+    /// Get the span covering all tokens consumed since a checkpoint
+    /// Returns None if no tokens were consumed
+    pub fn span_since(&self, checkpoint: Checkpoint) -> Option<TokenSpan> {
+        self.tokens_since(checkpoint)
+            .map(|t| t.span())
+            .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+    }
+
     /// Return a token, or error
     pub fn try_pop_a(&mut self, rules: &[Rule]) -> Option<Token<'source>> {
         self.try_update_error_pos(rules);
@@ -167,3 +357,27 @@ impl<'source> Stack<'source> {
         self.borrow();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        lexer::Lexer,
+        parser::{core::ScriptNode, ParserError, ParserNode},
+    };
+
+    #[test]
+    fn test_deep_nesting() {
+        let input = format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000));
+        let tokens = Lexer::new(&input).all_tokens().expect("Could not lex");
+        let mut tokens = Stack::new(tokens);
+        assert!(ScriptNode::parse(&mut tokens).is_none());
+        assert!(matches!(tokens.emit_err(), ParserError::TooDeep(_)));
+
+        // Long flat expressions are limited by the term count, not the depth
+        let input = vec!["1"; 300].join("+");
+        let tokens = Lexer::new(&input).all_tokens().expect("Could not lex");
+        let mut tokens = Stack::new(tokens);
+        assert!(ScriptNode::parse(&mut tokens).is_some());
+    }
+}
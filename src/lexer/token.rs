@@ -1,5 +1,5 @@
-use super::Rule;
-use crate::traits::{IntoOwned, SerializeToBytes};
+use super::{Rule, SourceMap};
+use crate::traits::{ByteReader, IntoOwned, SerializeToBytes};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
@@ -57,7 +57,7 @@ impl SerializeToBytes for SerializedToken {
     }
 
     fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
+        bytes: &mut ByteReader<'_>,
     ) -> Result<Self, crate::traits::ByteDecodeError> {
         let filename = Option::<String>::deserialize_from_bytes(bytes)?;
         let line = usize::deserialize_from_bytes(bytes)?;
@@ -133,6 +133,16 @@ impl<'source> Token<'source> {
         }
     }
 
+    /// Create a token covering part of this one, such as a single escape in a string
+    /// The span is relative to the start of this token, and the line accounts for any line breaks before it
+    pub fn subtoken(&self, span: TokenSpan) -> Self {
+        let start = self.span.start + span.start;
+        let end = (self.span.start + span.end).min(self.span.end);
+        let mut token = self.child(self.rule, start..end);
+        token.line += self.input()[self.span.start..start].matches('\n').count();
+        token
+    }
+
     /// Set the rule of the token
     pub fn set_rule(&mut self, rule: Rule) {
         self.rule = rule;
@@ -149,52 +159,20 @@ impl<'source> Token<'source> {
         self.line
     }
 
-    /// Get a slice of the input at the given line
-    fn line_slice(&self) -> (&str, usize) {
-        let start = self.input[..self.span.start]
-            .rfind('\n')
-            .map_or(0, |pos| pos + 1);
-        if self.span.end >= self.input.len() {
-            return (&self.input[start..], self.span.start - start);
-        }
-        let mut end = self.input[self.span.end..]
-            .find('\n')
-            .map_or(self.input.len(), |pos| pos + self.span.end);
-
-        if end > self.span.end {
-            end = self.span.end;
-        }
+    /// Get the column number of the token
+    pub fn column(&self) -> usize {
+        self.source_map().line_col(self.span.start).1
+    }
 
-        (&self.input[start..end], self.span.start - start)
+    /// Get a source map for the token's input
+    pub fn source_map(&self) -> SourceMap<'_> {
+        SourceMap::new(self.input())
     }
 
     /// Returns a slice of the input surrounding and ending after the token, with a maximum of 50 characters
     /// Will include a line beneath highlighting the token
     pub fn context_slice(&self) -> String {
-        const CONTEXT_LEN: usize = 50;
-        let (mut line, mut highlight_start) = self.line_slice();
-        let mut highlight_len = line.len() - highlight_start;
-
-        if highlight_len > CONTEXT_LEN {
-            line = &line[highlight_start..highlight_start + CONTEXT_LEN];
-            highlight_len = CONTEXT_LEN;
-            highlight_start = 0;
-        } else if line.len() > CONTEXT_LEN {
-            line = &line[line.len() - CONTEXT_LEN..];
-            highlight_start = CONTEXT_LEN - highlight_len;
-        }
-
-        let line = line
-            .split('\n')
-            .map(|l| format!("| {}", l))
-            .collect::<Vec<_>>()
-            .join("\n");
-        format!(
-            "{}\n| {}{}",
-            line,
-            " ".repeat(highlight_start),
-            "^".repeat(highlight_len)
-        )
+        self.source_map().snippet(self.span())
     }
 
     /// Get the span of the token
@@ -1,8 +1,12 @@
-//!
+//! A parser, compiler and virtual machine for the Lavendeux language
+//! Start with `Lavendeux`, or glob-import `prelude` for the types needed to embed it
 #![warn(missing_docs)]
 #![deny(elided_lifetimes_in_paths)]
 #![allow(unused_assignments)]
 
+#[macro_use]
+mod telemetry;
+
 pub mod traits;
 
 mod lavendeux;
@@ -11,10 +15,20 @@ pub use lavendeux::Lavendeux;
 mod error;
 pub use error::Error;
 
+mod run_output;
+pub use run_output::{
+    DecoratedData, PlotData, RunOutput, RunReport, TableData, DECORATED_KEY, PLOT_KEY, TABLE_KEY,
+};
+
+mod repl;
+pub use repl::{Repl, ReplResult};
+
 pub mod compiler;
+pub mod diagnostic;
 pub mod lexer;
 pub mod literals;
 pub mod parser;
+pub mod prelude;
 pub mod value;
 pub mod vm;
 
@@ -22,8 +22,23 @@ pub enum LiteralError {
     InvalidCurrencyLiteral,
 
     /// Invalid escape sequence in a string.
-    #[error("Invalid escape sequence `\\{0}`")]
-    InvalidEscapeSequence(char),
+    /// Holds the escape as written, and its span within the literal
+    #[error("Invalid escape sequence `{0}`")]
+    InvalidEscapeSequence(String, std::ops::Range<usize>),
+
+    /// Invalid hex or base64 bytes literal.
+    #[error("Invalid {0} in bytes literal")]
+    InvalidBytesLiteral(&'static str),
+}
+
+impl LiteralError {
+    /// The span within the literal that the error applies to, if it is not the whole literal
+    pub fn span(&self) -> Option<std::ops::Range<usize>> {
+        match self {
+            LiteralError::InvalidEscapeSequence(_, span) => Some(span.clone()),
+            _ => None,
+        }
+    }
 }
 
 /// Parse a string as an integer in the given base
@@ -56,49 +71,258 @@ pub fn decimal(slice: &str) -> Result<Decimal, LiteralError> {
         .map_err(|_| LiteralError::InvalidFloatLiteral)
 }
 
-/// Parse a string as a currency
+/// Parse a quoted string literal, resolving escape sequences
+///
+/// Supported escapes are:
+/// - `\'`, `\"` and `\\` for quotes and backslashes
+/// - `\n`, `\r`, `\t` and `\0` for newlines, carriage returns, tabs and nul
+/// - `\xNN` for a character from `00` to `FF`
+/// - `\u{N}` for a unicode codepoint of 1 to 6 hex digits, such as `\u{1F600}`
+/// - `\uNNNN` for a unicode codepoint of exactly 4 hex digits
+///
+/// Invalid escapes are reported with their span within the slice
 pub fn string(slice: &str) -> Result<String, LiteralError> {
-    let mut slice = slice[1..slice.len() - 1].chars();
+    let body = &slice[1..slice.len() - 1];
+    let mut chars = body.char_indices().peekable();
     let mut output = String::new();
 
-    loop {
-        let c = match slice.next() {
-            Some(c) => c,
-            None => break,
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+
+        let escaped = match chars.next() {
+            Some((_, c)) => c,
+            None => return Err(invalid_escape(slice, start + 1..slice.len() - 1)),
         };
 
-        match c {
-            '\\' => match slice.next() {
-                Some(c) => match c {
-                    '\'' => output.push('\''),
-                    '"' => output.push('"'),
-                    '\\' => output.push('\\'),
-                    'n' => output.push('\n'),
-                    'r' => output.push('\r'),
-                    't' => output.push('\t'),
-                    'u' => {
-                        let mut code = String::new();
-                        for _ in 0..4 {
-                            match slice.next() {
-                                Some(c) => code.push(c),
-                                None => return Err(LiteralError::InvalidEscapeSequence('\\')),
-                            }
-                        }
-                        let code = u32::from_str_radix(&code, 16)
-                            .map_err(|_| LiteralError::InvalidEscapeSequence('\\'))?;
-                        output.push(
-                            std::char::from_u32(code)
-                                .ok_or_else(|| LiteralError::InvalidEscapeSequence('\\'))?,
-                        );
-                    }
-                    _ => return Err(LiteralError::InvalidEscapeSequence(c)),
-                },
-                None => return Err(LiteralError::InvalidEscapeSequence('\\')),
-            },
-
-            _ => output.push(c),
+        let c = match escaped {
+            '\'' | '"' | '\\' => escaped,
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+
+            'x' => {
+                let digits = take_hex_digits(&mut chars, 2);
+                let end = start + 2 + digits.len();
+                match digits.len() {
+                    2 => u8::from_str_radix(&digits, 16).unwrap() as char,
+                    _ => return Err(invalid_escape(slice, start + 1..end + 1)),
+                }
+            }
+
+            'u' if chars.next_if(|(_, c)| *c == '{').is_some() => {
+                let digits = take_hex_digits(&mut chars, 6);
+                let closed = chars.next_if(|(_, c)| *c == '}').is_some();
+                let end = start + 3 + digits.len() + closed as usize;
+                match u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                {
+                    Some(c) if closed => c,
+                    _ => return Err(invalid_escape(slice, start + 1..end + 1)),
+                }
+            }
+
+            'u' => {
+                let digits = take_hex_digits(&mut chars, 4);
+                let end = start + 2 + digits.len();
+                match u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                {
+                    Some(c) if digits.len() == 4 => c,
+                    _ => return Err(invalid_escape(slice, start + 1..end + 1)),
+                }
+            }
+
+            _ => {
+                let end = start + 1 + escaped.len_utf8();
+                return Err(invalid_escape(slice, start + 1..end + 1));
+            }
+        };
+
+        output.push(c);
+    }
+
+    Ok(output)
+}
+
+/// Consume up to `max` hex digits from an escape sequence
+fn take_hex_digits(
+    chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>,
+    max: usize,
+) -> String {
+    let mut digits = String::new();
+    while digits.len() < max {
+        match chars.next_if(|(_, c)| c.is_ascii_hexdigit()) {
+            Some((_, c)) => digits.push(c),
+            None => break,
+        }
+    }
+    digits
+}
+
+/// Build the error for an escape sequence at `span` in the literal
+fn invalid_escape(slice: &str, span: std::ops::Range<usize>) -> LiteralError {
+    LiteralError::InvalidEscapeSequence(slice[span.clone()].to_string(), span)
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Parse a bytes literal
+/// `0x"..."` holds hex digits, and `b"..."` holds base64
+/// Whitespace and underscores between digits are ignored
+pub fn bytes(slice: &str) -> Result<Vec<u8>, LiteralError> {
+    let (prefix, body) = slice.split_once('"').unwrap_or(("", slice));
+    let body = body.strip_suffix('"').unwrap_or(body);
+    let body = body
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '_')
+        .collect::<String>();
+
+    match prefix {
+        "0x" => from_hex(&body),
+        _ => from_base64(&body),
+    }
+}
+
+/// Decode a string of hex digits into bytes
+pub fn from_hex(digits: &str) -> Result<Vec<u8>, LiteralError> {
+    if !digits.len().is_multiple_of(2) {
+        return Err(LiteralError::InvalidBytesLiteral("hex length"));
+    }
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            digits
+                .get(i..i + 2)
+                .and_then(|d| u8::from_str_radix(d, 16).ok())
+                .ok_or(LiteralError::InvalidBytesLiteral("hex digit"))
+        })
+        .collect()
+}
+
+/// Encode bytes as a string of lowercase hex digits
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decode a standard, padded or unpadded, base64 string into bytes
+pub fn from_base64(digits: &str) -> Result<Vec<u8>, LiteralError> {
+    let digits = digits.trim_end_matches('=');
+    if digits.len() % 4 == 1 {
+        return Err(LiteralError::InvalidBytesLiteral("base64 length"));
+    }
+
+    let mut output = Vec::with_capacity(digits.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in digits.bytes() {
+        let value = BASE64_ALPHABET
+            .iter()
+            .position(|&b| b == c)
+            .ok_or(LiteralError::InvalidBytesLiteral("base64 digit"))?;
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
         }
     }
 
     Ok(output)
 }
+
+/// Encode bytes as a padded base64 string
+pub fn to_base64(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let buffer = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (buffer >> (18 - 6 * i)) & 0x3F;
+                output.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{compiler::CompilerError, parser::ParserError, value::Value, Error, Lavendeux};
+
+    #[test]
+    fn test_bytes() {
+        let mut lav = Lavendeux::new();
+
+        let result = lav.run(r#"0x"dead" + b"vu8=""#).unwrap();
+        assert_eq!(result, Value::bytes(vec![0xde, 0xad, 0xbe, 0xef]));
+
+        // Indexing returns a byte, and slicing returns bytes
+        let result = lav.run(r#"[0x"00ff10"[1], 0x"00ff10"[1..3]]"#).unwrap();
+        let expected = Value::Array(vec![Value::integer(255), Value::bytes(vec![0xff, 0x10])]);
+        assert_eq!(result, expected);
+
+        let result = lav.run("a = 0x\"0000\"\na[0] = 7\na").unwrap();
+        assert_eq!(result.cast_array().unwrap()[2], Value::bytes(vec![7, 0]));
+
+        // Conversions to and from strings and integer arrays
+        let result = lav.run(r#"("hi" as bytes) + ([33] as bytes)"#).unwrap();
+        assert_eq!(result, Value::bytes(b"hi!".to_vec()));
+        let result = lav.run(r#"0x"6869" as string"#).unwrap();
+        assert_eq!(result, Value::string("hi".to_string()));
+        let result = lav.run(r#"0x"0102" as array"#).unwrap();
+        assert_eq!(
+            result,
+            Value::Array(vec![Value::integer(1), Value::integer(2)])
+        );
+        assert!(lav.run("[256] as bytes").is_err());
+
+        assert!(lav.run(r#"0x"abc""#).is_err());
+        assert_eq!(to_base64(&[0xde, 0xad, 0xbe]), "3q2+");
+        assert_eq!(from_base64("3q0=").unwrap(), vec![0xde, 0xad]);
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let value = string(r#""a\tb\n\x41\u{1F600}\u00e9\0\'\"\\""#).unwrap();
+        assert_eq!(value, "a\tb\nA\u{1F600}\u{e9}\0'\"\\");
+
+        for (input, escape) in [
+            (r#""ab\q""#, "\\q"),
+            (r#""\x4""#, "\\x4"),
+            (r#""\u{110000}""#, "\\u{110000}"),
+            (r#""\u{1F600""#, "\\u{1F600"),
+            (r#""\u12""#, "\\u12"),
+        ] {
+            match string(input) {
+                Err(e @ LiteralError::InvalidEscapeSequence(..)) => {
+                    assert_eq!(&input[e.span().unwrap()], escape);
+                }
+                other => panic!("Expected an invalid escape in {input}, got {other:?}"),
+            }
+        }
+
+        // The error points at the escape, on the line it appears on
+        let err = Lavendeux::new().run("x = 1\ny = \"ok\n\\é\"").unwrap_err();
+        match err {
+            Error::Compiler(CompilerError::Parser(ParserError::InvalidLiteral(token, _))) => {
+                assert_eq!((token.line(), token.slice()), (3, "\\é"));
+            }
+            _ => panic!("Expected an invalid literal, got {err:?}"),
+        }
+    }
+}
@@ -11,7 +11,24 @@ mod special_functions;
 mod error;
 pub use error::ParserError;
 
+mod options;
+pub use options::{ParserOptions, DEFAULT_MAX_DEPTH};
+
 mod pratt;
+pub use pratt::{
+    precedence_of, precedence_table, Associativity, CustomOperator, OperatorPrecedence,
+};
+
+mod grammar;
+pub use grammar::{
+    grammar, grammar_ebnf, grammar_railroad_html, GrammarError, GrammarRule, Production,
+};
+
+mod incremental;
+pub use incremental::{IncrementalParser, Statement, TextEdit};
+
+mod visitor;
+pub use visitor::{Visitor, VisitorMut};
 
 mod traits;
 pub use traits::ParserNode;
@@ -20,11 +37,96 @@ mod nodes;
 pub use nodes::*;
 
 /// Parses a stack of tokens into an AST.
+/// Returns an error if the input is invalid, or exceeds the limits in `options`
 pub fn build_ast<'source>(
-    mut tokens: crate::lexer::Stack<'source>,
+    tokens: crate::lexer::Stack<'source>,
+    options: ParserOptions,
 ) -> Result<Node<'source>, crate::parser::ParserError> {
+    let mut tokens = tokens.with_options(options);
+    if let Some(e) = tokens.limit_error() {
+        return Err(e.clone());
+    }
+
     match core::ScriptNode::parse(&mut tokens) {
         Some(ast) => Ok(ast),
         None => Err(tokens.emit_err()),
     }
 }
+
+/// How complete a piece of input is; see `classify`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Completeness {
+    /// The input parses
+    Complete,
+
+    /// The input ends too early, with a bracket, block, string or comment left open
+    /// More input could complete it
+    Incomplete,
+
+    /// The input has a syntax error that more input cannot fix
+    Invalid,
+}
+
+/// Check if input is complete, needs more input, or is invalid, without compiling it
+/// Interactive front-ends use this to decide whether to prompt for another line
+///
+/// Example:
+/// ```rust
+/// # use lavendeux_parser::parser::{classify, Completeness};
+/// assert_eq!(classify("f(x) = x * 2"), Completeness::Complete);
+/// assert_eq!(classify("f(x) = {"), Completeness::Incomplete);
+/// assert_eq!(classify("s = 'unfinished"), Completeness::Incomplete);
+/// assert_eq!(classify("[1, 2)"), Completeness::Invalid);
+/// ```
+pub fn classify(source: &str) -> Completeness {
+    classify_with_options(source, ParserOptions::default())
+}
+
+/// Like `classify`, but with the custom operators and limits in `options`
+pub fn classify_with_options(source: &str, options: ParserOptions) -> Completeness {
+    let tokens = crate::lexer::Lexer::new(source)
+        .with_custom_operators(&options.custom_operators)
+        .all_tokens();
+
+    let error: crate::Error = match tokens {
+        Ok(tokens) => match build_ast(crate::lexer::Stack::new(tokens), options) {
+            Ok(_) => return Completeness::Complete,
+            Err(e) => e.into(),
+        },
+        Err(e) => e.into(),
+    };
+
+    if error.is_recoverable() {
+        Completeness::Incomplete
+    } else {
+        Completeness::Invalid
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        for (source, expected) in [
+            ("", Completeness::Complete),
+            ("x = [1, 2]\ny = x[0]", Completeness::Complete),
+            ("f(x) = {\n    x * 2\n}", Completeness::Complete),
+            ("x = [1, 2", Completeness::Incomplete),
+            ("f(x) = {\n    x * 2", Completeness::Incomplete),
+            ("(1 + 2", Completeness::Incomplete),
+            ("if true then", Completeness::Incomplete),
+            ("s = \"unfinished", Completeness::Incomplete),
+            ("/* comment", Completeness::Incomplete),
+            ("[1, 2)", Completeness::Invalid),
+            ("2 +* 3", Completeness::Invalid),
+            ("x = 1 }", Completeness::Invalid),
+        ] {
+            assert_eq!(classify(source), expected, "{source}");
+        }
+
+        // Parsing does not check that names exist
+        assert_eq!(classify("undefined_name"), Completeness::Complete);
+    }
+}
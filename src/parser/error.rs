@@ -4,7 +4,7 @@ use crate::{
 };
 
 /// Error during parsing.
-#[derive(thiserror::Error, Debug, Clone)]
+#[derive(thiserror::Error, Debug, Clone, strum_macros::IntoStaticStr)]
 pub enum ParserError {
     /// Syntax error.
     #[error(
@@ -20,32 +20,44 @@ pub enum ParserError {
         found: Token<'static>,
     },
 
+    /// Input is nested too deeply to parse safely.
+    #[error("{0}\n= Expression is nested too deeply")]
+    TooDeep(Token<'static>),
+
+    /// Input has too many tokens.
+    #[error("{0}\n= Input is too long; the limit is {1} tokens")]
+    TooManyTokens(Token<'static>, usize),
+
+    /// Expression has too many terms.
+    #[error("{0}\n= Expression is too long; the limit is {1} terms")]
+    TooManyTerms(Token<'static>, usize),
+
+    /// Operator was disabled in the parser options.
+    #[error("{0}\n= The `{1}` operator is not allowed here")]
+    DisabledOperator(Token<'static>, String),
+
     /// Invalid literal value.
     #[error("{0}\n= {1}")]
     InvalidLiteral(Token<'static>, LiteralError),
 
     /// Invalid type.
-    #[error("{0}\n= Not a type; expected one of [bool, int, float, string, array, object, range, function, primitive, numeric, collection, all]")]
+    #[error("{0}\n= Not a type; expected one of [bool, int, float, string, bytes, array, object, range, function, primitive, numeric, collection, all]")]
     InvalidType(Token<'static>),
 
     /// Cannot cast to type.
     #[error(
-        "{0}\n= Cannot cast to type; expected one of [bool, int, float, string, array, object]"
+        "{0}\n= Cannot cast to type; expected one of [bool, int, float, string, bytes, array, object]"
     )]
     CannotCastToType(Token<'static>),
 
     /// Unreachable switch case.
-    #[error("{0}\n= Unreachable statement; Put this case before the default case")]
+    #[error("{0}\n= Unreachable statement; The default case must be the last case")]
     UnreachableSwitchCase(Token<'static>),
 
     /// Missing default case.
     #[error("{0}\n= Switch statements are required to have a default case ( _ => BLOCK )")]
     MissingDefaultCase(Token<'static>),
 
-    /// Missing else block.
-    #[error("{0}\n= Conditionals are required to have an 'else' block.\n= If a value is not needed, use `else nil`")]
-    MissingElse(Token<'static>),
-
     /// Missing function return value.
     #[error("{0}\n= Function must return a value; return a value, or `nil`")]
     MustReturnAValue(Token<'static>),
@@ -74,11 +86,14 @@ impl ParserError {
         match self {
             ParserError::InvalidLiteral(token, _) => token,
             ParserError::Syntax { found, .. } => found,
+            ParserError::TooDeep(token) => token,
+            ParserError::TooManyTokens(token, _) => token,
+            ParserError::TooManyTerms(token, _) => token,
+            ParserError::DisabledOperator(token, _) => token,
             ParserError::InvalidType(token) => token,
             ParserError::CannotCastToType(token) => token,
             ParserError::UnreachableSwitchCase(token) => token,
             ParserError::MissingDefaultCase(token) => token,
-            ParserError::MissingElse(token) => token,
             ParserError::MustReturnAValue(token) => token,
             ParserError::AssignmentToConstant(token) => token,
             ParserError::NotADecorator(token) => token,
@@ -86,4 +101,26 @@ impl ParserError {
             ParserError::TooManyArguments(token) => token,
         }
     }
+
+    /// Get the stable code identifying the kind of error, such as `E0100` for syntax errors
+    /// Parser errors use codes from `E0100` to `E0199`
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParserError::Syntax { .. } => "E0100",
+            ParserError::TooDeep(_) => "E0101",
+            ParserError::TooManyTokens(_, _) => "E0102",
+            ParserError::TooManyTerms(_, _) => "E0103",
+            ParserError::DisabledOperator(_, _) => "E0104",
+            ParserError::InvalidLiteral(_, _) => "E0105",
+            ParserError::InvalidType(_) => "E0106",
+            ParserError::CannotCastToType(_) => "E0107",
+            ParserError::UnreachableSwitchCase(_) => "E0108",
+            ParserError::MissingDefaultCase(_) => "E0109",
+            ParserError::MustReturnAValue(_) => "E0110",
+            ParserError::AssignmentToConstant(_) => "E0111",
+            ParserError::NotADecorator(_) => "E0112",
+            ParserError::DecoratorSignature(_) => "E0113",
+            ParserError::TooManyArguments(_) => "E0114",
+        }
+    }
 }
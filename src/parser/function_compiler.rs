@@ -1,11 +1,11 @@
 use crate::{
-    compiler::{Compiler, CompilerError, DebugProfile, FunctionDocs, HashString},
+    compiler::{Compiler, CompilerError, DebugProfile, FunctionDocs},
     traits::SerializeToBytes,
-    value::{Function, FunctionArgument, Value, ValueType},
+    value::{Function, FunctionArgument, StructType, Value, ValueType},
     vm::OpCode,
 };
 
-use super::Node;
+use super::{core::VarScope, Node};
 
 pub enum FunctionArgumentDefault<'source> {
     None,
@@ -17,6 +17,7 @@ pub struct FunctionArgumentCompiler<'source> {
     pub name: String,
     pub default: FunctionArgumentDefault<'source>,
     pub ty: ValueType,
+    pub struct_type: Option<StructType>,
     pub by_ref: bool,
 }
 
@@ -37,29 +38,35 @@ impl<'source> FunctionCompiler<'source> {
     // 4. Use WRFN to write the function to memory
 
     pub fn compile(self, compiler: &mut Compiler) -> Result<(), CompilerError> {
-        let name_hash = self.name.hash_str();
+        let name_hash = compiler.intern_name(&self.name);
 
         let mut arg_names = vec![];
         let mut arg_defaults = vec![];
         let mut args = vec![];
+        let mut struct_checks = vec![];
 
         for (i, arg) in self.args.into_iter().enumerate() {
+            let arg_hash = compiler.intern_name(&arg.name);
+            if let Some(struct_type) = arg.struct_type {
+                struct_checks.push((arg.name.clone(), struct_type));
+            }
+
             match arg.default {
                 FunctionArgumentDefault::None => args.push(FunctionArgument {
-                    name_hash: arg.name.hash_str(),
+                    name_hash: arg_hash,
                     ty: arg.ty,
                     by_ref: arg.by_ref,
                     default: None,
                 }),
                 FunctionArgumentDefault::Static(value) => args.push(FunctionArgument {
-                    name_hash: arg.name.hash_str(),
+                    name_hash: arg_hash,
                     ty: arg.ty,
                     by_ref: arg.by_ref,
                     default: Some(value),
                 }),
                 FunctionArgumentDefault::Stack(node) => {
                     args.push(FunctionArgument {
-                        name_hash: arg.name.hash_str(),
+                        name_hash: arg_hash,
                         ty: arg.ty,
                         by_ref: arg.by_ref,
                         default: None,
@@ -73,10 +80,30 @@ impl<'source> FunctionCompiler<'source> {
         let function_slice = self.body.token().slice().to_string();
         let offset = self.body.token().span().start;
         let filename = self.body.token().filename().map(|s| s.to_string());
+        let namespace = compiler.namespace_of(self.body.token());
 
         let mut fcompiler = Compiler::new(&function_slice, compiler.options().clone());
+        fcompiler.inherit_types(compiler);
+
+        // Arguments are local, even if a global has the same name
+        for name in &arg_names {
+            fcompiler.declare_scope(name, VarScope::Local).ok();
+        }
+
+        // Check arguments with a structured type before running the body
+        for (name, struct_type) in struct_checks {
+            fcompiler.push(OpCode::REF);
+            fcompiler.push_strhash(&name);
+            fcompiler.push(OpCode::CSST);
+            fcompiler.extend(struct_type.serialize_into_bytes());
+            fcompiler.push(OpCode::POP);
+        }
+
         self.body.compile(&mut fcompiler)?;
+        compiler.add_names(&fcompiler);
         fcompiler.push(OpCode::RET);
+        fcompiler.optimize();
+        compiler.add_peephole_stats(fcompiler.peephole_stats());
         let (mut debug, body) = fcompiler.decompose();
 
         debug.offset(filename, offset);
@@ -87,11 +114,12 @@ impl<'source> FunctionCompiler<'source> {
 
         let function = Function {
             name_hash,
+            namespace,
             returns: self.ty,
             expects: args,
             debug,
             docs: self.doc,
-            body,
+            body: body.into(),
         };
         let function = function.serialize_into_bytes();
 
@@ -0,0 +1,565 @@
+//! The grammar of the language, collected from the doc strings of the parser's nodes
+//!
+//! A node documents its grammar with a doc string wrapped in backticks, such as
+//! `` "`if EXPR then? BLOCK (else BLOCK)?`" ``, written in this notation:
+//! - Words starting with an upper-case letter, like `EXPR`, are other parts of the grammar
+//! - Other words and symbols, like `if` or `=>`, are written as-is in a script
+//! - `(` and `)` group items, and `|` separates alternatives
+//! - A `?`, `*` or `+` directly after an item makes it optional, repeated, or repeated at least once
+//! - Quoted symbols, like `'('` or `'*'`, are written as-is; for symbols used by the notation
+//!
+//! Use `grammar_ebnf` for a machine-readable copy, or `grammar_railroad_html` for diagrams
+use super::Node;
+
+/// Error in a grammar doc string
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid grammar for `{node}`: {message}")]
+pub struct GrammarError {
+    /// The name of the node the grammar documents
+    pub node: &'static str,
+
+    /// What is wrong with the grammar
+    pub message: String,
+}
+
+/// The documented grammar of one kind of node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrammarRule {
+    /// The name of the node, such as `FnCall`
+    pub name: &'static str,
+
+    /// The rest of the node's doc strings
+    pub description: String,
+
+    /// The grammar of the node
+    pub production: Production,
+}
+
+/// A part of a grammar rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Production {
+    /// Text written as-is in a script, such as `if`
+    Terminal(String),
+
+    /// Another part of the grammar, such as `EXPR`
+    NonTerminal(String),
+
+    /// Items that follow one another
+    Sequence(Vec<Production>),
+
+    /// One of several alternatives
+    Choice(Vec<Production>),
+
+    /// An item that may be left out
+    Optional(Box<Production>),
+
+    /// An item repeated any number of times, including none
+    ZeroOrMore(Box<Production>),
+
+    /// An item repeated at least once
+    OneOrMore(Box<Production>),
+}
+
+impl Production {
+    /// Parse a production written in the grammar notation
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut position = 0;
+        let production = parse_choice(&tokens, &mut position)?;
+        match tokens.get(position) {
+            None => Ok(production),
+            Some(token) => Err(format!("Unexpected `{token:?}`")),
+        }
+    }
+
+    /// True if the production must be wrapped in parentheses when it is part of a larger one
+    fn needs_group(&self) -> bool {
+        matches!(self, Self::Sequence(_) | Self::Choice(_))
+    }
+
+    /// Write an item with a suffix, grouping it if needed
+    fn fmt_suffixed(
+        f: &mut std::fmt::Formatter<'_>,
+        item: &Production,
+        suffix: char,
+    ) -> std::fmt::Result {
+        match item.needs_group() {
+            true => write!(f, "( {item} ){suffix}"),
+            false => write!(f, "{item}{suffix}"),
+        }
+    }
+}
+
+/// Formats the production as EBNF
+impl std::fmt::Display for Production {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Terminal(text) if text.contains('\'') => write!(f, "\"{text}\""),
+            Self::Terminal(text) => write!(f, "'{text}'"),
+            Self::NonTerminal(name) => write!(f, "{name}"),
+
+            Self::Sequence(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| match item {
+                        Self::Choice(_) => format!("( {item} )"),
+                        _ => item.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                write!(f, "{}", items.join(" "))
+            }
+
+            Self::Choice(items) => {
+                let items = items.iter().map(ToString::to_string).collect::<Vec<_>>();
+                write!(f, "{}", items.join(" | "))
+            }
+
+            Self::Optional(item) => Self::fmt_suffixed(f, item, '?'),
+            Self::ZeroOrMore(item) => Self::fmt_suffixed(f, item, '*'),
+            Self::OneOrMore(item) => Self::fmt_suffixed(f, item, '+'),
+        }
+    }
+}
+
+/// A token of the grammar notation
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GrammarToken {
+    Open,
+    Close,
+    Or,
+    Suffix(char),
+    Word(String),
+    Quoted(String),
+}
+
+/// Split a production into tokens
+fn tokenize(source: &str) -> Result<Vec<GrammarToken>, String> {
+    let mut tokens = vec![];
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+
+            // Separates items in the pest-style grammars in comments; a sequence either way
+            '~' => {}
+
+            '(' => tokens.push(GrammarToken::Open),
+            ')' => tokens.push(GrammarToken::Close),
+            '|' => tokens.push(GrammarToken::Or),
+            '?' | '*' | '+' => tokens.push(GrammarToken::Suffix(c)),
+
+            '\'' | '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(next) => text.push(next),
+                        None => return Err(format!("Unterminated quote `{c}{text}`")),
+                    }
+                }
+                tokens.push(GrammarToken::Quoted(text));
+            }
+
+            _ => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || "()|?*+'\"".contains(next) {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push(GrammarToken::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// CHOICE := SEQUENCE ( '|' SEQUENCE )*
+fn parse_choice(tokens: &[GrammarToken], position: &mut usize) -> Result<Production, String> {
+    let mut items = vec![parse_sequence(tokens, position)?];
+    while tokens.get(*position) == Some(&GrammarToken::Or) {
+        *position += 1;
+        items.push(parse_sequence(tokens, position)?);
+    }
+
+    match items.len() {
+        1 => Ok(items.pop().unwrap()),
+        _ => Ok(Production::Choice(items)),
+    }
+}
+
+/// SEQUENCE := ( ITEM SUFFIX* )+
+fn parse_sequence(tokens: &[GrammarToken], position: &mut usize) -> Result<Production, String> {
+    let mut items = vec![];
+    loop {
+        let mut item = match tokens.get(*position) {
+            Some(GrammarToken::Open) => {
+                *position += 1;
+                let item = parse_choice(tokens, position)?;
+                match tokens.get(*position) {
+                    Some(GrammarToken::Close) => *position += 1,
+                    _ => return Err("Unclosed `(`".to_string()),
+                }
+                item
+            }
+
+            Some(GrammarToken::Word(word)) if word.starts_with(char::is_uppercase) => {
+                *position += 1;
+                Production::NonTerminal(word.clone())
+            }
+
+            Some(GrammarToken::Word(text)) | Some(GrammarToken::Quoted(text)) => {
+                *position += 1;
+                Production::Terminal(text.clone())
+            }
+
+            Some(GrammarToken::Suffix(c)) => return Err(format!("`{c}` does not follow an item")),
+            _ => break,
+        };
+
+        while let Some(GrammarToken::Suffix(c)) = tokens.get(*position) {
+            *position += 1;
+            item = match c {
+                '?' => Production::Optional(Box::new(item)),
+                '*' => Production::ZeroOrMore(Box::new(item)),
+                _ => Production::OneOrMore(Box::new(item)),
+            };
+        }
+
+        items.push(item);
+    }
+
+    match items.len() {
+        0 => Err("Expected an item".to_string()),
+        1 => Ok(items.pop().unwrap()),
+        _ => Ok(Production::Sequence(items)),
+    }
+}
+
+/// Collect the documented grammar of each kind of node, in declaration order
+/// Nodes without a grammar doc string are left out
+pub fn grammar() -> Result<Vec<GrammarRule>, GrammarError> {
+    let mut rules = vec![];
+    for (name, docs) in Node::docs() {
+        let is_grammar = |doc: &str| {
+            let doc = doc.trim();
+            doc.len() > 1
+                && doc.starts_with('`')
+                && doc.ends_with('`')
+                && doc.matches('`').count() == 2
+        };
+
+        let Some(production) = docs.iter().find(|doc| is_grammar(doc)) else {
+            continue;
+        };
+
+        let production = production.trim().trim_matches('`');
+        let production = Production::parse(production).map_err(|message| GrammarError {
+            node: name,
+            message,
+        })?;
+
+        let description = docs
+            .iter()
+            .filter(|doc| !is_grammar(doc))
+            .map(|doc| doc.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        rules.push(GrammarRule {
+            name,
+            description,
+            production,
+        });
+    }
+
+    Ok(rules)
+}
+
+/// The documented grammar as EBNF, one rule per line
+/// Such as `If ::= 'if' EXPR 'then'? BLOCK ( 'else' BLOCK )?`
+pub fn grammar_ebnf() -> Result<String, GrammarError> {
+    let rules = grammar()?
+        .into_iter()
+        .map(|rule| format!("{} ::= {}\n", rule.name, rule.production))
+        .collect();
+    Ok(rules)
+}
+
+/// The documented grammar as a standalone HTML page, with a railroad diagram for each rule
+pub fn grammar_railroad_html() -> Result<String, GrammarError> {
+    let mut html = String::from(RAILROAD_HEADER);
+    for rule in grammar()? {
+        html.push_str(&format!(
+            "<h2 id=\"{0}\">{0}</h2>\n<p>{1}</p>\n{2}\n<pre>{3}</pre>\n",
+            rule.name,
+            escape_html(&rule.description),
+            railroad::diagram(&rule.production),
+            escape_html(&rule.production.to_string())
+        ));
+    }
+    html.push_str("</body>\n</html>\n");
+    Ok(html)
+}
+
+const RAILROAD_HEADER: &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Lavendeux grammar</title>
+<style>
+body { font-family: sans-serif; margin: 2em; }
+svg path { stroke: #333; stroke-width: 2; fill: none; }
+svg rect { stroke: #333; stroke-width: 2; fill: #f3eefa; }
+svg rect.nonterminal { fill: #fff; }
+svg text { font-family: monospace; font-size: 13px; text-anchor: middle; dominant-baseline: central; }
+pre { color: #555; }
+</style>
+</head>
+<body>
+<h1>Lavendeux grammar</h1>
+";
+
+/// Escape text for use in HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// SVG railroad diagrams of productions
+mod railroad {
+    use super::{escape_html, Production};
+
+    /// Horizontal space between items in a sequence
+    const GAP: usize = 10;
+
+    /// Width of the curves into and out of branches and loops
+    const ARC: usize = 20;
+
+    /// Vertical space between branches
+    const SPACE: usize = 10;
+
+    /// Half the height of a box
+    const HALF_BOX: usize = 11;
+
+    /// Approximate width of a character of monospace text
+    const CHAR_WIDTH: usize = 8;
+
+    /// The space a production takes up; the line runs through it at `up` from the top
+    #[derive(Debug, Clone, Copy)]
+    struct Size {
+        width: usize,
+        up: usize,
+        down: usize,
+    }
+
+    /// Render a production as an SVG railroad diagram
+    pub fn diagram(production: &Production) -> String {
+        let size = measure(production);
+        let (width, height) = (size.width + 2 * ARC, size.up + size.down + 2 * SPACE);
+        let y = size.up + SPACE;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\">\n"
+        );
+        svg.push_str(&format!(
+            "<path d=\"M 0 {0} h {ARC} M {1} {0} h {ARC}\"/>\n",
+            y,
+            ARC + size.width
+        ));
+        render(production, ARC, y, &mut svg);
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Optional items are drawn as a choice between skipping them and the item
+    fn measure(production: &Production) -> Size {
+        match production {
+            Production::Terminal(text) | Production::NonTerminal(text) => Size {
+                width: text.chars().count() * CHAR_WIDTH + 2 * GAP,
+                up: HALF_BOX,
+                down: HALF_BOX,
+            },
+
+            Production::Sequence(items) => {
+                let sizes = items.iter().map(measure).collect::<Vec<_>>();
+                Size {
+                    width: sizes.iter().map(|s| s.width).sum::<usize>() + GAP * (sizes.len() - 1),
+                    up: sizes.iter().map(|s| s.up).max().unwrap_or(0),
+                    down: sizes.iter().map(|s| s.down).max().unwrap_or(0),
+                }
+            }
+
+            Production::Choice(items) => measure_branches(items.iter().map(measure).collect()),
+            Production::Optional(item) => measure_branches(vec![skip(), measure(item)]),
+
+            Production::OneOrMore(item) => {
+                let size = measure(item);
+                Size {
+                    width: size.width + 2 * ARC,
+                    up: size.up,
+                    down: size.down + SPACE,
+                }
+            }
+
+            Production::ZeroOrMore(item) => measure(&Production::Optional(Box::new(
+                Production::OneOrMore(item.clone()),
+            ))),
+        }
+    }
+
+    /// The size of a branch that skips over an item
+    fn skip() -> Size {
+        Size {
+            width: 0,
+            up: 0,
+            down: 0,
+        }
+    }
+
+    /// Branches are stacked below the first one, which is on the line
+    fn measure_branches(sizes: Vec<Size>) -> Size {
+        let below = sizes[1..]
+            .iter()
+            .map(|s| SPACE + s.up + s.down)
+            .sum::<usize>();
+        Size {
+            width: sizes.iter().map(|s| s.width).max().unwrap_or(0) + 2 * ARC,
+            up: sizes[0].up,
+            down: sizes[0].down + below,
+        }
+    }
+
+    /// Draw a production with its line starting at (x, y)
+    fn render(production: &Production, x: usize, y: usize, svg: &mut String) {
+        match production {
+            Production::Terminal(text) | Production::NonTerminal(text) => {
+                let width = measure(production).width;
+                let (class, radius) = match production {
+                    Production::Terminal(_) => ("terminal", HALF_BOX),
+                    _ => ("nonterminal", 0),
+                };
+                svg.push_str(&format!(
+                    "<rect class=\"{class}\" x=\"{x}\" y=\"{}\" width=\"{width}\" height=\"{}\" rx=\"{radius}\"/>\n",
+                    y - HALF_BOX,
+                    2 * HALF_BOX
+                ));
+                svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"{y}\">{}</text>\n",
+                    x + width / 2,
+                    escape_html(text)
+                ));
+            }
+
+            Production::Sequence(items) => {
+                let mut x = x;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        svg.push_str(&format!("<path d=\"M {x} {y} h {GAP}\"/>\n"));
+                        x += GAP;
+                    }
+                    render(item, x, y, svg);
+                    x += measure(item).width;
+                }
+            }
+
+            Production::Choice(items) => {
+                let branches = items.iter().map(Some).collect::<Vec<_>>();
+                render_branches(&branches, x, y, svg);
+            }
+
+            Production::Optional(item) => render_branches(&[None, Some(item)], x, y, svg),
+
+            Production::OneOrMore(item) => {
+                let size = measure(item);
+                let end = x + ARC + size.width;
+                let loop_y = y + size.down + SPACE;
+                svg.push_str(&format!(
+                    "<path d=\"M {x} {y} h {ARC} M {end} {y} h {ARC}\"/>\n"
+                ));
+                svg.push_str(&format!(
+                    "<path d=\"M {end} {y} C {0} {y} {0} {loop_y} {end} {loop_y} H {1} C {x} {loop_y} {x} {y} {1} {y}\"/>\n",
+                    end + ARC,
+                    x + ARC
+                ));
+                render(item, x + ARC, y, svg);
+            }
+
+            Production::ZeroOrMore(item) => {
+                let item = Production::Optional(Box::new(Production::OneOrMore(item.clone())));
+                render(&item, x, y, svg);
+            }
+        }
+    }
+
+    /// Draw a set of branches; None is a branch that skips over the others
+    fn render_branches(branches: &[Option<&Production>], x: usize, y: usize, svg: &mut String) {
+        let sizes = branches
+            .iter()
+            .map(|b| b.map_or(skip(), measure))
+            .collect::<Vec<_>>();
+        let width = measure_branches(sizes.clone()).width;
+        let end = x + width;
+
+        let mut branch_y = y;
+        for (i, (branch, size)) in branches.iter().zip(&sizes).enumerate() {
+            if i > 0 {
+                branch_y += sizes[i - 1].down + SPACE + size.up;
+                svg.push_str(&format!(
+                    "<path d=\"M {x} {y} C {0} {y} {x} {branch_y} {0} {branch_y} M {1} {branch_y} C {end} {branch_y} {1} {y} {end} {y}\"/>\n",
+                    x + ARC,
+                    end - ARC
+                ));
+            } else {
+                svg.push_str(&format!(
+                    "<path d=\"M {x} {y} h {ARC} M {0} {y} h {ARC}\"/>\n",
+                    end - ARC
+                ));
+            }
+
+            // The line runs from the end of the branch to the curve back to the main line
+            let branch_end = x + ARC + size.width;
+            svg.push_str(&format!(
+                "<path d=\"M {branch_end} {branch_y} H {0}\"/>\n",
+                end - ARC
+            ));
+            if let Some(branch) = branch {
+                render(branch, x + ARC, branch_y, svg);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_grammar() {
+        // Every grammar doc string must be well-formed
+        let rules = grammar().unwrap();
+        let rule = rules.iter().find(|r| r.name == "If").unwrap();
+        assert_eq!(
+            rule.production.to_string(),
+            "'if' EXPR 'then'? BLOCK ( 'else' BLOCK )?"
+        );
+        assert!(rule.description.starts_with("Conditional expression"));
+
+        let ebnf = grammar_ebnf().unwrap();
+        assert_eq!(ebnf.lines().count(), rules.len());
+        assert!(ebnf.contains(
+            "Alias ::= 'alias' IDENTIFIER '(' ( IDENTIFIER ',' )* IDENTIFIER? ')' '=>' EXPR"
+        ));
+
+        let html = grammar_railroad_html().unwrap();
+        assert_eq!(html.matches("<svg ").count(), rules.len());
+
+        assert!(Production::parse("(a | b").is_err());
+        assert!(Production::parse("? a").is_err());
+    }
+}
@@ -0,0 +1,347 @@
+//! Incremental re-parsing, for editors that re-parse on every keystroke
+//! The document is cached as a list of top-level statements; after an edit
+//! only the statements touching the edit are lexed and parsed again
+use super::{
+    core::{LineNode, ScriptNode},
+    Node, ParserNode, ParserOptions,
+};
+use crate::{
+    error::Error,
+    lexer::{Lexer, Rule, Stack, Token, TokenSpan},
+    traits::IntoOwned,
+};
+use std::borrow::Cow;
+
+/// A change to the document; the bytes in `range` are replaced with `text`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// The range of bytes to replace, in the document before the edit
+    pub range: TokenSpan,
+
+    /// The replacement text
+    pub text: String,
+}
+
+impl TextEdit {
+    /// Create a new edit
+    pub fn new(range: TokenSpan, text: impl Into<String>) -> Self {
+        Self {
+            range,
+            text: text.into(),
+        }
+    }
+
+    /// Create an edit that inserts text at a position
+    pub fn insert(at: usize, text: impl Into<String>) -> Self {
+        Self::new(at..at, text)
+    }
+
+    /// Create an edit that deletes a range of bytes
+    pub fn delete(range: TokenSpan) -> Self {
+        Self::new(range, "")
+    }
+}
+
+/// A cached top-level statement
+/// Tokens in the AST are relative to the statement, not the document;
+/// `span().start` is the offset of the statement in the document
+#[derive(Clone)]
+pub struct Statement {
+    span: TokenSpan,
+    line: usize,
+    ast: Node<'static>,
+}
+
+impl Statement {
+    /// The span of the statement in the document
+    pub fn span(&self) -> TokenSpan {
+        self.span.clone()
+    }
+
+    /// The line of the document the statement starts on, starting at 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The AST of the statement
+    pub fn ast(&self) -> &Node<'static> {
+        &self.ast
+    }
+}
+
+/// Incremental parser for a document
+/// Keeps the AST of each top-level statement, and re-parses only the statements
+/// affected by an edit, along with their neighbours.
+///
+/// If part of the document cannot be parsed, the statements before it are kept,
+/// and the rest of the document is parsed again on the next edit.
+///
+/// ```rust
+/// # use lavendeux_parser::parser::{IncrementalParser, ParserOptions, TextEdit};
+/// let mut parser = IncrementalParser::new("a = 1\nb = 2\nc = 3", ParserOptions::default());
+/// assert_eq!(parser.statements().len(), 3);
+///
+/// // Only the last statements are parsed again
+/// let reparsed = parser.edit(TextEdit::new(16..17, "4 + 5"));
+/// assert_eq!(reparsed, 1..3);
+/// assert_eq!(parser.source(), "a = 1\nb = 2\nc = 4 + 5");
+/// ```
+pub struct IncrementalParser {
+    source: String,
+    options: ParserOptions,
+    statements: Vec<Statement>,
+
+    // Set if the document could not be parsed past the last statement
+    error: Option<Error>,
+}
+
+impl IncrementalParser {
+    /// Parse a document
+    pub fn new(source: impl Into<String>, options: ParserOptions) -> Self {
+        let mut parser = Self {
+            source: source.into(),
+            options,
+            statements: vec![],
+            error: None,
+        };
+
+        parser.reparse(0, 0, 0..parser.source.len(), 1);
+        parser
+    }
+
+    /// The current text of the document
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The cached statements, in order
+    pub fn statements(&self) -> &[Statement] {
+        &self.statements
+    }
+
+    /// The error preventing the rest of the document from being parsed, if any
+    /// Tokens in the error are relative to the end of the last statement
+    pub fn error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+
+    /// Offset in the document where the valid statements end
+    pub fn parsed_len(&self) -> usize {
+        self.statements.last().map(|s| s.span.end).unwrap_or(0)
+    }
+
+    /// Get an AST for the whole document, suitable for compilation
+    /// Fails if part of the document could not be parsed
+    pub fn ast(&self) -> Result<Node<'static>, Error> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+
+        let lines: Vec<_> = self.statements.iter().map(|s| s.ast.clone()).collect();
+        let token = match lines.first() {
+            Some(line) => line.token().child(Rule::Script, 0..0),
+            None => Token::new(1, 0..0, Rule::Script, Cow::Borrowed("")),
+        };
+
+        Ok(ScriptNode { lines, token }.into_node())
+    }
+
+    /// Apply an edit to the document, and re-parse the affected statements
+    /// Returns the range of indices in `statements` that were replaced
+    ///
+    /// # Panics
+    /// Panics if the edit is out of bounds, or not on a character boundary
+    pub fn edit(&mut self, edit: TextEdit) -> std::ops::Range<usize> {
+        let TextEdit { range, text } = edit;
+        let delta = text.len() as isize - range.len() as isize;
+        let line_delta = text.matches('\n').count() as isize
+            - self.source[range.clone()].matches('\n').count() as isize;
+        self.source.replace_range(range.clone(), &text);
+
+        // Statements touching the edit, plus one on either side, since the edit may
+        // join them together; `if a {} \n else {}` is a single statement
+        let first = self
+            .statements
+            .iter()
+            .position(|s| s.span.end >= range.start)
+            .unwrap_or(self.statements.len())
+            .saturating_sub(1);
+        let last = match self.error {
+            // The end of the document was not parsed, so it all needs to be parsed again
+            Some(_) => self.statements.len(),
+            None => self
+                .statements
+                .iter()
+                .rposition(|s| s.span.start <= range.end)
+                .map(|i| i + 2)
+                .unwrap_or(0)
+                .clamp(first, self.statements.len()),
+        };
+
+        // Move the statements after the edit
+        for statement in &mut self.statements[last..] {
+            statement.span.start = statement.span.start.saturating_add_signed(delta);
+            statement.span.end = statement.span.end.saturating_add_signed(delta);
+            statement.line = statement.line.saturating_add_signed(line_delta);
+        }
+
+        let (start, line) = match first {
+            0 => (0, 1),
+            i => {
+                let previous = &self.statements[i - 1];
+                let lines = self.source[previous.span.clone()].matches('\n').count();
+                (previous.span.end, previous.line + lines)
+            }
+        };
+        let end = match self.statements.get(last) {
+            Some(s) => s.span.start,
+            None => self.source.len(),
+        };
+
+        self.reparse(first, last, start..end, line)
+    }
+
+    /// Parse `range` of the source, which starts on `line`, replacing `statements[first..last]`
+    /// If the range cannot be parsed on its own, the rest of the document is parsed
+    fn reparse(
+        &mut self,
+        first: usize,
+        last: usize,
+        range: TokenSpan,
+        line: usize,
+    ) -> std::ops::Range<usize> {
+        let (statements, error) = match self.parse_range(range.clone(), line) {
+            Ok(statements) => (statements, None),
+            Err(_) if range.end < self.source.len() => {
+                let range = range.start..self.source.len();
+                return self.reparse(first, self.statements.len(), range, line);
+            }
+            Err((statements, e)) => (statements, Some(e)),
+        };
+
+        // An error can only occur at the end of the document, so the error from any
+        // earlier parse is replaced if this parse reached the end
+        if range.end == self.source.len() {
+            self.error = error;
+        }
+
+        let n = statements.len();
+        self.statements.splice(first..last, statements);
+        first..first + n
+    }
+
+    /// Parse the statements in a range of the source
+    /// On failure, returns the statements parsed before the error
+    fn parse_range(
+        &self,
+        range: TokenSpan,
+        line: usize,
+    ) -> Result<Vec<Statement>, (Vec<Statement>, Error)> {
+        let input = &self.source[range.clone()];
+        let tokens = Lexer::new(input)
+            .with_custom_operators(&self.options.custom_operators)
+            .all_tokens()
+            .map_err(|e| (vec![], e.into()))?;
+        let mut tokens = Stack::new(tokens).with_options(self.options.clone());
+
+        // Find the boundaries of each statement
+        let mut spans = vec![];
+        loop {
+            // Leading blank lines are not part of the statement
+            while tokens.peek().is_some_and(|t| t.rule() == Rule::EOL) {
+                tokens.pop();
+            }
+            if tokens.len() <= 1 {
+                break;
+            }
+
+            let checkpoint = tokens.checkpoint();
+            if LineNode::parse(&mut tokens).is_none() {
+                let error = tokens.emit_err();
+                let statements = self.parse_statements(range.start, line, spans);
+                return Err((statements, error.into()));
+            }
+
+            if let Some(span) = tokens.span_since(checkpoint) {
+                spans.push(span);
+            }
+        }
+
+        Ok(self.parse_statements(range.start, line, spans))
+    }
+
+    /// Parse each statement on its own, so that its tokens only refer to its own text
+    fn parse_statements(
+        &self,
+        offset: usize,
+        line: usize,
+        spans: Vec<TokenSpan>,
+    ) -> Vec<Statement> {
+        let mut line = line;
+        let mut line_offset = offset;
+        spans
+            .into_iter()
+            .filter_map(|span| {
+                let span = span.start + offset..span.end + offset;
+                line += self.source[line_offset..span.start].matches('\n').count();
+                line_offset = span.start;
+
+                let input = &self.source[span.clone()];
+                let tokens = Lexer::new(input)
+                    .with_custom_operators(&self.options.custom_operators)
+                    .all_tokens()
+                    .ok()?;
+                let ast = super::build_ast(Stack::new(tokens), self.options.clone()).ok()?;
+
+                let Node::Script(script) = ast else {
+                    return None;
+                };
+                let ast = script.lines.into_iter().next()?.into_owned();
+
+                Some(Statement { span, line, ast })
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for IncrementalParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IncrementalParser")
+            .field("statements", &self.statements.len())
+            .field("parsed_len", &self.parsed_len())
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_incremental_parse() {
+        let mut parser = IncrementalParser::new(
+            "a = 1\n\nif a {\n  2\n}\nelse {\n  3\n}\nb = 4",
+            Default::default(),
+        );
+        assert_eq!(parser.statements().len(), 3);
+        assert_eq!(parser.statements()[2].line(), 9);
+
+        // Edits re-parse the statement and its neighbours
+        assert_eq!(parser.edit(TextEdit::new(4..5, "10")), 0..2);
+        assert_eq!(parser.statements()[2].span().start, 34);
+
+        // Breaking the document keeps the statements before the error
+        parser.edit(TextEdit::insert(10, "("));
+        assert!(parser.error().is_some());
+        assert_eq!(parser.statements().len(), 1);
+        assert!(parser.ast().is_err());
+
+        // Fixing it parses the rest of the document again
+        parser.edit(TextEdit::delete(10..11));
+        assert!(parser.error().is_none());
+        assert_eq!(parser.statements().len(), 3);
+        assert_eq!(parser.statements()[2].line(), 9);
+        assert!(parser.ast().is_ok());
+    }
+}
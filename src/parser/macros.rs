@@ -57,31 +57,12 @@ macro_rules! terminal {
 
 macro_rules! build_nt {
     ($type:ident, $tokens:expr) => {{
-        match $tokens.len() == 0 {
+        match $tokens.len() == 0 || !$tokens.enter_nt(stringify!($type)) {
             true => None,
             _ => {
-                #[cfg(feature = "debug_compiler_internal_parser")]
-                {
-                    println!(
-                        "{}Parsing {}: next={:?}",
-                        " ".repeat($tokens.depth()),
-                        stringify!($type),
-                        $tokens.peek()
-                    );
-                    match $type::parse($tokens) {
-                        Some(t) => {
-                            println!(
-                                "{}Success ({:?})!",
-                                " ".repeat($tokens.depth() + 1),
-                                stringify!($type)
-                            );
-                            Some(t)
-                        }
-                        None => None,
-                    }
-                }
-                #[cfg(not(feature = "debug_compiler_internal_parser"))]
-                $type::parse($tokens)
+                let result = $type::parse($tokens);
+                $tokens.exit_nt(stringify!($type), result.is_some());
+                result
             }
         }
     }};
@@ -191,6 +172,10 @@ macro_rules! define_node {
                 $oblock
             }
         }
+        impl $name<'_> {
+            /// The doc strings of the node, including its grammar
+            pub const DOCS: &'static [&'static str] = &[$($docstr),*];
+        }
         impl<'source> $crate::parser::ParserNode<'source> for $name<'source> {
             fn into_node(self) -> $crate::parser::Node<'source> {
                 let $nselfarg = self;
@@ -230,6 +215,9 @@ macro_rules! pratt_node {
             }
         }
         impl<'source> $name<'source> {
+            /// The doc strings of the node, including its grammar
+            pub const DOCS: &'static [&'static str] = &[$($docstr),*];
+
             pub fn into_node(self) -> $crate::parser::Node<'source> {
                 let $nselfarg = self;
                 $nblock
@@ -303,8 +291,41 @@ macro_rules! define_parser {
                 }
             }
 
-            pub fn compile(self, compiler: &mut $crate::compiler::Compiler) -> Result<(), $crate::compiler::CompilerError> {
+            /// The name of the node's variant, such as `FnCall`
+            pub fn name(&self) -> &'static str {
                 match self {
+                    Self::Error(_) => "Error",
+                    $(
+                        Self::$name(_) => stringify!($name),
+                    )+
+                }
+            }
+
+            /// The name and doc strings of each kind of node, in declaration order
+            /// See `parser::grammar` for the grammar collected from them
+            pub fn docs() -> Vec<(&'static str, &'static [&'static str])> {
+                vec![
+                    $(
+                        (stringify!($name), $src::DOCS),
+                    )+
+                ]
+            }
+
+            pub fn compile(self, compiler: &mut $crate::compiler::Compiler) -> Result<(), $crate::compiler::CompilerError> {
+                match compiler.eliminate_subexpressions(self)? {
+                    Some(node) => node.compile_node(compiler),
+                    None => Ok(()),
+                }
+            }
+
+            /// Compile this node, without checking for common subexpressions
+            pub fn compile_node(self, compiler: &mut $crate::compiler::Compiler) -> Result<(), $crate::compiler::CompilerError> {
+                let node = match compiler.allocate_registers(self)? {
+                    Some(node) => node,
+                    None => return Ok(()),
+                };
+
+                match node {
                     Self::Error(e) => Err(e.into()),
                     $(
                         Self::$name(n) => n.compile(compiler),
@@ -31,6 +31,12 @@ define_parser!(
     Script: ScriptNode,
     Block: BlockNode,
     CastExpr: CastExprNode,
+    IsExpr: IsExprNode,
+    TypeDef: TypeDefNode,
+    EnumDef: EnumDefNode,
+    ConstDef: ConstDefNode,
+    VarDef: VarDefNode,
+    Let: LetNode,
     DecoratorExpr: DecoratorExprNode,
 
     // Assignment nodes
@@ -44,6 +50,7 @@ define_parser!(
     InfixOperator: InfixOperatorNode,
     PostfixIndexingOperator: PostfixIndexingOperatorNode,
     PostfixFnCallOperator: PostfixFnCallOperatorNode,
+    PostfixDecoratorOperator: PostfixDecoratorOperatorNode,
 
     // Arithmetic nodes
     ArithmeticInfixExpr: ArithmeticInfixExprNode,
@@ -59,7 +66,9 @@ define_parser!(
 
     // Function related nodes
     FnCall: FnCallNode,
+    CustomInfixExpr: CustomInfixExprNode,
     FnAssign: FnAssignNode,
+    Alias: AliasNode,
     Return: ReturnNode,
 
     // Iterator related nodes
@@ -71,11 +80,13 @@ define_parser!(
     // Collection related nodes
     Array: ArrayNode,
     Object: ObjectNode,
+    Spread: SpreadNode,
     RangeExpr: RangeExprNode,
     IndexingExpr: IndexingExprNode,
 
     // Conditional related nodes
     If: IfNode,
+    NilCoalesceExpr: NilCoalesceExprNode,
 
     // Literals and constants
     LiteralString: LiteralStringNode,
@@ -1,5 +1,7 @@
 use super::*;
-use crate::{lexer::Rule, traits::IntoOwned, vm::OpCode};
+use crate::{
+    compiler::CompilerWarning, lexer::Rule, traits::IntoOwned, value::Primitive, vm::OpCode,
+};
 
 /// Arithmetic infix expression
 #[allow(missing_docs)]
@@ -25,11 +27,29 @@ impl ArithmeticOp {
             _ => return None,
         })
     }
+
+    /// The opcode that performs this operation
+    pub fn opcode(self) -> OpCode {
+        match self {
+            ArithmeticOp::Add => OpCode::ADD,
+            ArithmeticOp::Sub => OpCode::SUB,
+            ArithmeticOp::Mul => OpCode::MUL,
+            ArithmeticOp::Div => OpCode::DIV,
+            ArithmeticOp::Mod => OpCode::REM,
+            ArithmeticOp::Pow => OpCode::POW,
+        }
+    }
+}
+
+/// Check if a node is a string literal
+/// `-` has surprising effects on strings, so the compiler warns when it is used on one
+pub(super) fn is_string_literal(node: &Node<'_>) -> bool {
+    matches!(node, Node::LiteralString(s) if matches!(s.value, Primitive::String(_)))
 }
 
 pratt_node!(ArithmeticInfixExprNode(lhs: Node<'source>, op: ArithmeticOp, rhs: Node<'source>) {
     "Infix arithmetic expression"
-    "`EXPR (+, -, *, /, %, **) EXPR`"
+    "`EXPR ('+' | '-' | '*' | '/' | '%' | '**') EXPR`"
 
     build(token, lhs, op, rhs) {
         token.set_rule(Rule::ArithmethicInfixExpr);
@@ -38,18 +58,16 @@ pratt_node!(ArithmeticInfixExprNode(lhs: Node<'source>, op: ArithmeticOp, rhs: N
     }
 
     compile(this, compiler) {
+        if matches!(this.op, ArithmeticOp::Sub)
+            && (is_string_literal(&this.lhs) || is_string_literal(&this.rhs))
+        {
+            compiler.warn(CompilerWarning::StringSubtraction(this.token.clone().into_owned()));
+        }
         compiler.push_token(this.token);
 
         this.lhs.compile(compiler)?;
         this.rhs.compile(compiler)?;
-        compiler.push(match this.op {
-            ArithmeticOp::Add => OpCode::ADD,
-            ArithmeticOp::Sub => OpCode::SUB,
-            ArithmeticOp::Mul => OpCode::MUL,
-            ArithmeticOp::Div => OpCode::DIV,
-            ArithmeticOp::Mod => OpCode::REM,
-            ArithmeticOp::Pow => OpCode::POW,
-        });
+        compiler.push(this.op.opcode());
 
         Ok(())
     }
@@ -70,7 +88,7 @@ pratt_node!(ArithmeticInfixExprNode(lhs: Node<'source>, op: ArithmeticOp, rhs: N
 
 pratt_node!(ArithmeticPrefixExprNode(rhs: Node<'source>) {
     "Prefix arithmetic expression"
-    "`(-) EXPR`"
+    "`'-' EXPR`"
 
     build(token, rhs, _op) {
         token.set_rule(Rule::ArithmeticPrefixExpr);
@@ -78,6 +96,9 @@ pratt_node!(ArithmeticPrefixExprNode(rhs: Node<'source>) {
     }
 
     compile(this, compiler) {
+        if is_string_literal(&this.rhs) {
+            compiler.warn(CompilerWarning::StringNegation(this.token.clone().into_owned()));
+        }
         compiler.push_token(this.token);
 
         this.rhs.compile(compiler)?;
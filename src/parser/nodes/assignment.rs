@@ -1,5 +1,10 @@
 use super::*;
-use crate::{lexer::Rule, traits::IntoOwned, vm::OpCode};
+use crate::{
+    compiler::{Compiler, CompilerError, CompilerWarning},
+    lexer::Rule,
+    traits::IntoOwned,
+    vm::OpCode,
+};
 
 /*
 TODO
@@ -9,6 +14,21 @@ a non-resolving reference
 We then adjust normal reference types to resolve IN compilation
 Then the compiler never needs to deal with em? Maybe a value cache eventually */
 
+/// Reject an assignment or deletion whose target is a constant, or an index into one
+fn check_not_constant(compiler: &Compiler, target: &Node<'_>) -> Result<(), CompilerError> {
+    let base = match target {
+        Node::IndexingExpr(n) => &n.base,
+        target => target,
+    };
+
+    match base {
+        Node::LiteralIdent(ident) if compiler.is_constant(ident.name()) => Err(
+            CompilerError::ConstantAssignment(ident.token.clone().into_owned(), ident.name().to_string()),
+        ),
+        _ => Ok(()),
+    }
+}
+
 pratt_node!(AssignExprNode(target: Node<'source>, value: Node<'source>) {
     build(token, lhs, _op, rhs) {
         token.set_rule(Rule::AssignExpr);
@@ -16,6 +36,22 @@ pratt_node!(AssignExprNode(target: Node<'source>, value: Node<'source>) {
     }
 
     compile(this, compiler) {
+        check_not_constant(compiler, &this.target)?;
+
+        // Names declared `global` are written to the global scope
+        if let Node::LiteralIdent(ident) = &this.target {
+            if compiler.is_global(ident.name()) {
+                let name = ident.name().to_string();
+                compiler.push_token(this.token);
+
+                this.value.compile(compiler)?;
+                compiler.push(OpCode::DUP);
+                compiler.push(OpCode::WRGL);
+                compiler.push_strhash(&name);
+                return Ok(());
+            }
+        }
+
         compiler.push_token(this.token);
 
         this.value.compile(compiler)?;
@@ -56,6 +92,10 @@ pratt_node!(AssignArithmeticExprNode(target: Node<'source>, value: Node<'source>
     }
 
     compile(this, compiler) {
+        check_not_constant(compiler, &this.target)?;
+        if matches!(this.op, ArithmeticOp::Sub) && is_string_literal(&this.value) {
+            compiler.warn(CompilerWarning::StringSubtraction(this.token.clone().into_owned()));
+        }
         compiler.push_token(this.token);
 
         this.target.compile(compiler)?;
@@ -107,6 +147,7 @@ pratt_node!(AssignBitwiseExprNode(target: Node<'source>, value: Node<'source>, o
     }
 
     compile(this, compiler) {
+        check_not_constant(compiler, &this.target)?;
         compiler.push_token(this.token);
         compiler.push(OpCode::DUP); // Duplicate target reference, one for assignment and one for arithmetic operation
 
@@ -148,6 +189,7 @@ pratt_node!(DeleteExprNode(target: Node<'source>) {
     }
 
     compile(this, compiler) {
+        check_not_constant(compiler, &this.target)?;
         compiler.push_token(this.token);
         this.target.compile(compiler)?;
         compiler.push(OpCode::DREF);
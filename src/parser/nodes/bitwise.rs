@@ -23,6 +23,17 @@ impl BitwiseOp {
             _ => return None,
         })
     }
+
+    /// The opcode that performs this operation
+    pub fn opcode(self) -> OpCode {
+        match self {
+            BitwiseOp::And => OpCode::AND,
+            BitwiseOp::Or => OpCode::OR,
+            BitwiseOp::Xor => OpCode::XOR,
+            BitwiseOp::ShiftLeft => OpCode::SHL,
+            BitwiseOp::ShiftRight => OpCode::SHR,
+        }
+    }
 }
 
 pratt_node!(BitwiseInfixExprNode(lhs: Node<'source>, op: BitwiseOp, rhs: Node<'source>) {
@@ -37,13 +48,7 @@ pratt_node!(BitwiseInfixExprNode(lhs: Node<'source>, op: BitwiseOp, rhs: Node<'s
 
         this.lhs.compile(compiler)?;
         this.rhs.compile(compiler)?;
-        compiler.push(match this.op {
-            BitwiseOp::And => OpCode::AND,
-            BitwiseOp::Or => OpCode::OR,
-            BitwiseOp::Xor => OpCode::XOR,
-            BitwiseOp::ShiftLeft => OpCode::SHL,
-            BitwiseOp::ShiftRight => OpCode::SHR,
-        });
+        compiler.push(this.op.opcode());
 
         Ok(())
     }
@@ -8,6 +8,15 @@ pub enum LogicalOp {
     And,
     Or,
 }
+impl LogicalOp {
+    /// The opcode that performs this operation
+    pub fn opcode(self) -> OpCode {
+        match self {
+            LogicalOp::And => OpCode::LAND,
+            LogicalOp::Or => OpCode::LOR,
+        }
+    }
+}
 
 pratt_node!(LogicalExprNode(lhs: Node<'source>, op: LogicalOp, rhs: Node<'source>) {
     build(token, lhs, op, rhs) {
@@ -25,10 +34,7 @@ pratt_node!(LogicalExprNode(lhs: Node<'source>, op: LogicalOp, rhs: Node<'source
 
         this.lhs.compile(compiler)?;
         this.rhs.compile(compiler)?;
-        compiler.push(match this.op {
-            LogicalOp::And => OpCode::LAND,
-            LogicalOp::Or => OpCode::LOR,
-        });
+        compiler.push(this.op.opcode());
 
         Ok(())
     }
@@ -59,6 +65,7 @@ pub enum ComparisonOp {
     Lte,
     SEq,
     SNeq,
+    IEq,
 }
 impl ComparisonOp {
     /// Convert a rule to a comparison operator
@@ -72,24 +79,19 @@ impl ComparisonOp {
             Rule::Le => ComparisonOp::Lte,
             Rule::SEq => ComparisonOp::SEq,
             Rule::SNe => ComparisonOp::SNeq,
+            Rule::IEq => ComparisonOp::IEq,
             _ => return None,
         })
     }
-}
 
-pratt_node!(ComparisonExprNode(lhs: Node<'source>, op: ComparisonOp, rhs: Node<'source>) {
-    build(token, lhs, op, rhs) {
-        token.set_rule(Rule::ComparisonExpr);
-        let op = ComparisonOp::from_rule(op.token().rule())?;
-        Some(Self { lhs, op, rhs, token }.into_node())
+    /// Returns true for the ordering operators, which can be chained (`1 < x < 10`)
+    pub fn is_ordering(self) -> bool {
+        matches!(self, Self::Gt | Self::Lt | Self::Gte | Self::Lte)
     }
 
-    compile(this, compiler) {
-        compiler.push_token(this.token);
-
-        this.lhs.compile(compiler)?;
-        this.rhs.compile(compiler)?;
-        compiler.push(match this.op {
+    /// The opcode that performs this comparison
+    pub fn opcode(self) -> OpCode {
+        match self {
             ComparisonOp::Eq => OpCode::EQ,
             ComparisonOp::Neq => OpCode::NE,
             ComparisonOp::Gt => OpCode::GT,
@@ -98,7 +100,101 @@ pratt_node!(ComparisonExprNode(lhs: Node<'source>, op: ComparisonOp, rhs: Node<'
             ComparisonOp::Lte => OpCode::LE,
             ComparisonOp::SEq => OpCode::SEQ,
             ComparisonOp::SNeq => OpCode::SNE,
-        });
+            ComparisonOp::IEq => OpCode::IEQ,
+        }
+    }
+}
+
+pratt_node!(ComparisonExprNode(lhs: Node<'source>, op: ComparisonOp, rhs: Node<'source>, chain: Vec<(ComparisonOp, Node<'source>)>) {
+    "Comparison expression - `a < b`"
+    "Ordering comparisons can be chained, so `1 < x < 10` means `1 < x && x < 10`, with `x` evaluated once"
+
+    build(token, lhs, op, rhs) {
+        token.set_rule(Rule::ComparisonExpr);
+        let op_start = op.token().span().start;
+        let op = ComparisonOp::from_rule(op.token().rule())?;
+
+        // Extend a chain of ordering comparisons, unless the left side is in parentheses
+        // A parenthesized left side leaves an unmatched `)` before the operator
+        if let Node::ComparisonExpr(mut prev) = lhs {
+            let prefix = token.input().get(prev.token.span().start..op_start).unwrap_or_default();
+            let parenthesized = prefix.matches(')').count() > prefix.matches('(').count();
+            let chains = op.is_ordering()
+                && prev.op.is_ordering()
+                && prev.chain.iter().all(|(op, _)| op.is_ordering())
+                && !parenthesized;
+            if chains {
+                prev.token.include_span(rhs.token().span());
+                prev.chain.push((op, rhs));
+                return Some(prev.into_node());
+            }
+
+            return Some(Self { lhs: prev.into_node(), op, rhs, chain: vec![], token }.into_node());
+        }
+
+        Some(Self { lhs, op, rhs, chain: vec![], token }.into_node())
+    }
+
+    compile(this, compiler) {
+        compiler.push_token(this.token);
+
+        this.lhs.compile(compiler)?;
+        if this.chain.is_empty() {
+            this.rhs.compile(compiler)?;
+            compiler.push(this.op.opcode());
+            return Ok(());
+        }
+
+        // Each operand shared by two comparisons is kept in a hidden variable
+        // Identifiers and literals are cheap to read twice, and are compiled again instead
+        let mut links = vec![(this.op, this.rhs)];
+        links.extend(this.chain);
+        let n_links = links.len();
+
+        let mut hidden = vec![];
+        let mut prev: Option<Result<Node<'_>, String>> = None;
+        for (i, (op, operand)) in links.into_iter().enumerate() {
+            match prev.take() {
+                Some(Ok(node)) => node.compile(compiler)?,
+                Some(Err(name)) => {
+                    compiler.push(OpCode::REF);
+                    compiler.push_strhash(&name);
+                    compiler.push(OpCode::RREF);
+                }
+                None => {}
+            }
+
+            let simple = matches!(
+                operand,
+                Node::LiteralIdent(_) | Node::LiteralInt(_) | Node::LiteralFloat(_) | Node::LiteralString(_) | Node::LiteralBool(_)
+            );
+            if i + 1 == n_links {
+                operand.compile(compiler)?;
+            } else if simple {
+                operand.clone().compile(compiler)?;
+                prev = Some(Ok(operand));
+            } else {
+                let name = compiler.hidden_variable("chain");
+                operand.compile(compiler)?;
+                compiler.push(OpCode::REF);
+                compiler.push_strhash(&name);
+                compiler.push(OpCode::WREF);
+                hidden.push(name.clone());
+                prev = Some(Err(name));
+            }
+
+            compiler.push(op.opcode());
+            if i > 0 {
+                compiler.push(OpCode::LAND);
+            }
+        }
+
+        for name in hidden {
+            compiler.push(OpCode::REF);
+            compiler.push_strhash(&name);
+            compiler.push(OpCode::DREF);
+            compiler.push(OpCode::POP);
+        }
 
         Ok(())
     }
@@ -112,6 +208,7 @@ pratt_node!(ComparisonExprNode(lhs: Node<'source>, op: ComparisonOp, rhs: Node<'
             lhs: this.lhs.into_owned(),
             op: this.op,
             rhs: this.rhs.into_owned(),
+            chain: this.chain.into_iter().map(|(op, n)| (op, n.into_owned())).collect(),
             token: this.token.into_owned(),
         }
     }
@@ -201,3 +298,25 @@ pratt_node!(LogicalNotNode(rhs: Node<'source>) {
         }
     }
 });
+
+#[cfg(test)]
+mod test {
+    use crate::{value::Value, Lavendeux};
+
+    #[test]
+    fn test_chained_comparisons() {
+        let mut lav = Lavendeux::new();
+        lav.run("x = 5").unwrap();
+
+        assert_eq!(lav.run("1 < x < 10").unwrap(), Value::boolean(true));
+        assert_eq!(lav.run("1 < x < 4").unwrap(), Value::boolean(false));
+        assert_eq!(lav.run("10 > x >= 5").unwrap(), Value::boolean(true));
+        assert_eq!(lav.run("1 < 2 <= 2 < 3").unwrap(), Value::boolean(true));
+        assert_eq!(lav.run("1 < x < 10 < 3").unwrap(), Value::boolean(false));
+
+        // The middle operand is only evaluated once
+        lav.run("n = 0").unwrap();
+        assert_eq!(lav.run("0 < (n += 1) < 5").unwrap(), Value::boolean(true));
+        assert_eq!(lav.run("n").unwrap(), Value::integer(1));
+    }
+}
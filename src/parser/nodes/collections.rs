@@ -1,5 +1,5 @@
 use super::*;
-use crate::{lexer::Rule, traits::IntoOwned, vm::OpCode};
+use crate::{lexer::Rule, traits::IntoOwned, value::{Primitive, ValueType}, vm::OpCode};
 
 // LBrack ~ RBrack | LBrack ~ ~ EOL* ~ EXPRESSION ~ (EOL* ~ Comma ~ EOL* ~ EXPRESSION)* ~ EOL* ~ RBrack
 define_node!(ArrayNode(elements: Vec<Node<'source>>) {
@@ -17,7 +17,7 @@ define_node!(ArrayNode(elements: Vec<Node<'source>>) {
                 Some(Self { elements: vec![], token }.into_node())
             }
             None => {
-                let mut elements = vec![non_terminal!(ExpressionNode, tokens, skip_eol!(tokens))?];
+                let mut elements = vec![non_terminal!(SpreadNode|ExpressionNode, tokens, skip_eol!(tokens))?];
                 loop {
                     tokens.start_transaction();
 
@@ -25,7 +25,7 @@ define_node!(ArrayNode(elements: Vec<Node<'source>>) {
                         break;
                     }
 
-                    match non_terminal!(ExpressionNode, tokens, skip_eol!(tokens)) {
+                    match non_terminal!(SpreadNode|ExpressionNode, tokens, skip_eol!(tokens)) {
                         Some(e) => elements.push(e),
                         None => break,
                     }
@@ -44,10 +44,38 @@ define_node!(ArrayNode(elements: Vec<Node<'source>>) {
     compile(this, compiler) {
         compiler.push_token(this.token);
 
-        let len = this.elements.len();
-        this.elements.into_iter().rev().map(|e| e.compile(compiler)).collect::<Result<Vec<_>, _>>()?;
+        if !this.elements.iter().any(|e| matches!(e, Node::Spread(_))) {
+            let len = this.elements.len();
+            this.elements.into_iter().rev().map(|e| e.compile(compiler)).collect::<Result<Vec<_>, _>>()?;
+
+            compiler.push(OpCode::MKAR);
+            compiler.push_u64(len as u64);
+            return Ok(());
+        }
+
+        // Split into runs of plain elements and spread arrays, then join them
+        let mut segments: Vec<Vec<Node<'_>>> = vec![];
+        for element in this.elements {
+            match (element, segments.last_mut()) {
+                (Node::Spread(s), _) => segments.push(vec![Node::Spread(s)]),
+                (e, Some(last)) if !matches!(last[0], Node::Spread(_)) => last.push(e),
+                (e, _) => segments.push(vec![e]),
+            }
+        }
 
-        compiler.push(OpCode::MKAR);
+        let len = segments.len();
+        for segment in segments.into_iter().rev() {
+            if let [Node::Spread(_)] = segment.as_slice() {
+                segment.into_iter().next().unwrap().compile(compiler)?;
+            } else {
+                let n = segment.len();
+                segment.into_iter().rev().map(|e| e.compile(compiler)).collect::<Result<Vec<_>, _>>()?;
+                compiler.push(OpCode::MKAR);
+                compiler.push_u64(n as u64);
+            }
+        }
+
+        compiler.push(OpCode::CNCT);
         compiler.push_u64(len as u64);
 
         Ok(())
@@ -65,6 +93,47 @@ define_node!(ArrayNode(elements: Vec<Node<'source>>) {
     }
 });
 
+// Spread ~ EXPRESSION
+define_node!(SpreadNode(expr: Node<'source>) {
+    "Spread expression - splices the elements of a collection into an array or a list of arguments"
+    "`[1, ...rest, 9]` or `f(...args)`"
+
+    build(tokens) {
+        tokens.start_transaction();
+
+        let token = terminal!(Spread, tokens)?;
+        let mut token = token.child(Rule::SpreadExpr, token.span());
+
+        let expr = non_terminal!(ExpressionNode, tokens, skip_eol!(tokens))?;
+        token.include_span(expr.token().span());
+
+        tokens.apply_transaction();
+        Some(Self { expr, token }.into_node())
+    }
+
+    compile(this, compiler) {
+        compiler.push_token(this.token);
+
+        // Spread values are converted to arrays, as with `as array`
+        this.expr.compile(compiler)?;
+        compiler.push(OpCode::CAST);
+        compiler.push_type(ValueType::Array);
+
+        Ok(())
+    }
+
+    into_node(this) {
+        Node::Spread(Box::new(this))
+    }
+
+    into_owned(this) {
+        Self::Owned {
+            expr: this.expr.into_owned(),
+            token: this.token.into_owned()
+        }
+    }
+});
+
 // LBrace ~ RBrace | LBrace ~ ~ EOL* ~ EXPRESSION ~ COLON ~ EXPRESSION ~ (EOL* ~ Comma ~ EOL* ~ EXPRESSION ~ COLON ~ EXPRESSION)* ~ EOL* ~ RBrace
 define_node!(ObjectNode(elements: Vec<(Node<'source>, Node<'source>)>) {
     build(tokens) {
@@ -334,7 +403,7 @@ pratt_node!(RangeExprNode(start: Node<'source>, end: Node<'source>) {
     }
 });
 
-pratt_node!(IndexingExprNode(base: Node<'source>, path: Vec<Node<'source>>) {
+pratt_node!(IndexingExprNode(base: Node<'source>, path: Vec<(Node<'source>, bool)>) {
     build(token, base, op) {
         token.set_rule(Rule::IndexingExpr);
         let path = if let Node::PostfixIndexingOperator(op) = op { op } else {
@@ -346,9 +415,30 @@ pratt_node!(IndexingExprNode(base: Node<'source>, path: Vec<Node<'source>>) {
     compile(this, compiler) {
         compiler.push_token(this.token);
         this.base.compile(compiler)?;
-        for idx in this.path {
+
+        // A nil from `?.` skips the rest of the chain, so `a?.b.c` is nil if `a` is
+        let mut if_nil = vec![];
+        let mut path = this.path.into_iter().peekable();
+        while let Some((idx, nil_safe)) = path.next() {
             idx.compile(compiler)?;
-            compiler.push(OpCode::IDEX);
+            if !nil_safe {
+                compiler.push(OpCode::IDEX);
+                continue;
+            }
+
+            compiler.push(OpCode::NIDX);
+            if path.peek().is_some() {
+                compiler.push(OpCode::DUP);
+                compiler.push(OpCode::JMPNN);
+                let not_nil = compiler.push_jump_placeholder();
+                compiler.push(OpCode::JMP);
+                if_nil.push(compiler.push_jump_placeholder());
+                compiler.patch_jump(not_nil, compiler.len());
+            }
+        }
+
+        for jump in if_nil {
+            compiler.patch_jump(jump, compiler.len());
         }
 
         Ok(())
@@ -361,14 +451,14 @@ pratt_node!(IndexingExprNode(base: Node<'source>, path: Vec<Node<'source>>) {
     into_owned(this) {
         Self::Owned {
             base: this.base.into_owned(),
-            path: this.path.into_iter().map(|e| e.into_owned()).collect(),
+            path: this.path.into_iter().map(|(e, n)| (e.into_owned(), n)).collect(),
             token: this.token.into_owned(),
         }
     }
 });
 
-// (symbol_opensquare ~ EOL* ~ EXPR ~ EOL* ~ symbol_closesquare)+
-define_node!(PostfixIndexingOperatorNode(path: Vec<Node<'source>>) {
+// ((symbol_opensquare ~ EOL* ~ EXPR ~ EOL* ~ symbol_closesquare) | ((symbol_dot | symbol_optionaldot) ~ EOL* ~ identifier ~ !symbol_openparen))+
+define_node!(PostfixIndexingOperatorNode(path: Vec<(Node<'source>, bool)>) {
     build(tokens) {
         tokens.start_transaction();
         let mut path = vec![];
@@ -381,7 +471,33 @@ define_node!(PostfixIndexingOperatorNode(path: Vec<Node<'source>>) {
         loop {
             tokens.start_transaction();
 
-            match terminal!(LBrack, tokens, skip_eol!(tokens)) {
+            match terminal!(LBrack|Dot|OptionalDot, tokens, skip_eol!(tokens)) {
+                Some(b) if b.rule() == Rule::Dot || b.rule() == Rule::OptionalDot => {
+                    // Dot-access sugar - `a.b` is equivalent to `a["b"]`
+                    // But `a.b(...)` is a method call, and is left for PostfixFnCallOperatorNode
+                    // `a?.b` is the nil-safe variant, yielding nil if `a` is nil or has no `b`
+                    let name = match terminal!(LiteralIdent, tokens, skip_eol!(tokens)) {
+                        Some(name) => name,
+                        None => {
+                            break;
+                        }
+                    };
+
+                    if terminal!(&LParen, tokens).is_some() {
+                        tokens.revert_transaction();
+                        break;
+                    }
+
+                    end = b.span();
+                    start = name.span();
+                    path.push((LiteralStringNode {
+                        value: Primitive::String(name.slice().to_string()),
+                        token: name.child(Rule::LiteralString, name.span()),
+                    }.into_node(), b.rule() == Rule::OptionalDot));
+
+                    tokens.apply_transaction();
+                    continue;
+                }
                 Some(b) => end = b.span(),
                 None => {
                     break;
@@ -403,7 +519,7 @@ define_node!(PostfixIndexingOperatorNode(path: Vec<Node<'source>>) {
                 }
             }
 
-            path.push(expr);
+            path.push((expr, false));
             tokens.apply_transaction();
         }
 
@@ -439,8 +555,64 @@ define_node!(PostfixIndexingOperatorNode(path: Vec<Node<'source>>) {
 
     into_owned(this) {
         Self::Owned {
-            path: this.path.into_iter().map(|e| e.into_owned()).collect(),
+            path: this.path.into_iter().map(|(e, n)| (e.into_owned(), n)).collect(),
             token: this.token.into_owned()
         }
     }
 });
+
+#[cfg(test)]
+mod test {
+    use crate::{value::Value, Lavendeux};
+
+    #[test]
+    fn test_nil_safe_indexing() {
+        let mut lav = Lavendeux::new();
+        lav.run("o = {'a': {'b': 1}}; n = nil; l = [1, 2]").unwrap();
+
+        assert_eq!(lav.run("o?.a?.b").unwrap(), Value::integer(1));
+        assert_eq!(lav.run("n?.a").unwrap(), Value::nil());
+        assert_eq!(lav.run("o?.missing").unwrap(), Value::nil());
+        assert_eq!(lav.run("o.a?.missing ?? 7").unwrap(), Value::integer(7));
+
+        // A nil from `?.` skips the rest of the chain
+        assert_eq!(lav.run("n?.a.b").unwrap(), Value::nil());
+        assert_eq!(lav.run("o?.missing.b[0]").unwrap(), Value::nil());
+        assert_eq!(lav.run("o?.a.b").unwrap(), Value::integer(1));
+
+        // Indexing into the wrong type is still an error
+        assert!(lav.run("a = 5; a?.b").is_err());
+        assert!(lav.run("l?.b").is_err());
+        assert!(lav.run("o.missing?.b").is_err());
+    }
+
+    #[test]
+    fn test_spread() {
+        let mut lav = Lavendeux::new();
+        lav.run("rest = [2, 3]; f(a, b, c) = a + b + c; g(a, b) = a - b")
+            .unwrap();
+
+        // Spreading into an array literal
+        assert_eq!(
+            lav.run("[1, ...rest, 4]").unwrap(),
+            lav.run("[1, 2, 3, 4]").unwrap()
+        );
+        assert_eq!(
+            lav.run("[...rest, ...rest]").unwrap(),
+            lav.run("[2, 3, 2, 3]").unwrap()
+        );
+        assert_eq!(
+            lav.run("[...(0..3)]").unwrap(),
+            lav.run("[0, 1, 2]").unwrap()
+        );
+        assert_eq!(lav.run("[...nil]").unwrap(), lav.run("[]").unwrap());
+
+        // Spreading into call arguments
+        assert_eq!(lav.run("f(...[1, 2, 3])").unwrap(), Value::integer(6));
+        assert_eq!(lav.run("g(10, ...[3])").unwrap(), Value::integer(7));
+        assert_eq!(lav.run("f(1, ...rest)").unwrap(), Value::integer(6));
+
+        // The spread arguments must still match the function's signature
+        assert!(lav.run("g(...[1, 2, 3])").is_err());
+    }
+}
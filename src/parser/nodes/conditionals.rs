@@ -2,9 +2,10 @@ use optional_transpose::OptionTranspose;
 
 use super::*;
 use crate::{
-    lexer::Rule,
+    lexer::{Rule, Stack},
     parser::{ParserError, ParserNode},
     traits::{IntoOwned, SerializeToBytes},
+    value::Primitive,
     vm::OpCode,
 };
 
@@ -12,8 +13,12 @@ use crate::{
 define_node!(IfNode(
     condition: Node<'source>,
     then_block: Node<'source>,
-    else_block: Node<'source>
+    else_block: Option<Node<'source>>
 ) {
+    "Conditional expression - evaluates to the value of the branch taken."
+    "If the `else` block is omitted, a false condition evaluates to `nil`."
+    "`if EXPR then? BLOCK (else BLOCK)?`"
+
     build(tokens) {
         tokens.start_transaction();
 
@@ -25,16 +30,21 @@ define_node!(IfNode(
         let then_block = non_terminal!(BlockNode, tokens, skip_eol!(tokens))?;
         token.include_span(then_block.token().span());
 
+        // The newlines before a missing `else` belong to the next line
+        tokens.start_transaction();
         let else_block = match terminal!(Else?, tokens, skip_eol!(tokens)) {
             Some(_) => {
+                tokens.apply_transaction();
                 let block = non_terminal!(BlockNode, tokens, skip_eol!(tokens))?;
                 token.include_span(block.token().span());
-                block
+                Some(block)
+            }
+            None => {
+                tokens.revert_transaction();
+                None
             }
-            None => return error_node!(ParserError::MissingElse(token.into_owned()))
         };
 
-
         tokens.apply_transaction();
         Some(Self { condition, then_block, else_block, token }.into_node())
     }
@@ -45,23 +55,27 @@ define_node!(IfNode(
         // IF <CONDITION>
         this.condition.compile(compiler)?;
         compiler.push(OpCode::JMPF);
-        let if_false = compiler.push_u64(0); // Placeholder for a forward jump
+        let if_false = compiler.push_jump_placeholder();
 
         // THEN <BLOCK>
         this.then_block.compile(compiler)?;
         compiler.push(OpCode::JMP);
-        let after_truth = compiler.push_u64(0); // Placeholder for a forward jump
+        let after_truth = compiler.push_jump_placeholder();
 
         // Fill in the first else-jump
-        let jmp1_value = compiler.len() as u64;
-        compiler.replace(if_false, jmp1_value.serialize_into_bytes());
-
-        // ELSE <BLOCK>
-        this.else_block.compile(compiler)?;
+        compiler.patch_jump(if_false, compiler.len());
+
+        // ELSE <BLOCK>, or nil if there is no else
+        match this.else_block {
+            Some(else_block) => else_block.compile(compiler)?,
+            None => {
+                compiler.push(OpCode::PUSH);
+                compiler.extend(Primitive::Nil.serialize_into_bytes());
+            }
+        }
 
         // Fill in the second jump
-        let jmp2_value = compiler.len() as u64;
-        compiler.replace(after_truth, jmp2_value.serialize_into_bytes());
+        compiler.patch_jump(after_truth, compiler.len());
 
         Ok(())
     }
@@ -74,7 +88,7 @@ define_node!(IfNode(
         Self::Owned {
             condition: this.condition.into_owned(),
             then_block: this.then_block.into_owned(),
-            else_block: this.else_block.into_owned(),
+            else_block: this.else_block.map(|b| b.into_owned()),
             token: this.token.into_owned(),
         }
     }
@@ -88,21 +102,132 @@ pratt_node_silent!(TernaryExprNode {
             then_block: if let Node::InfixOperator(op) = op { op.inner.unwrap() } else {
                 unreachable!("Invalid operator: {:?}", op)
             },
-            else_block: rhs,
+            else_block: Some(rhs),
             token
         }.into_node())
     }
 });
 
+pratt_node!(NilCoalesceExprNode(value: Node<'source>, default: Node<'source>) {
+    build(token, lhs, _op, rhs) {
+        token.set_rule(Rule::NilCoalesceExpr);
+        Some(Self { value: lhs, default: rhs, token }.into_node())
+    }
+
+    compile(this, compiler) {
+        compiler.push_token(this.token);
+
+        // <VALUE>, keeping a copy for the nil check
+        this.value.compile(compiler)?;
+        compiler.push(OpCode::DUP);
+        compiler.push(OpCode::JMPNN);
+        let if_not_nil = compiler.push_jump_placeholder();
+
+        // Value was nil - replace it with <DEFAULT>
+        compiler.push(OpCode::POP);
+        this.default.compile(compiler)?;
+
+        // Fill in the jump
+        compiler.patch_jump(if_not_nil, compiler.len());
+
+        Ok(())
+    }
+
+    into_node(this) {
+        Node::NilCoalesceExpr(Box::new(this))
+    }
+
+    into_owned(this) {
+        Self::Owned {
+            value: this.value.into_owned(),
+            default: this.default.into_owned(),
+            token: this.token.into_owned(),
+        }
+    }
+});
+
+/// The minimum number of integer cases before a switch is compiled to a jump table
+const JUMP_TABLE_MIN_CASES: usize = 4;
+
+/// Parses the values for one arm of a switch statement, and the arrow that ends them
+/// `(CmpOp)? EXPR (, (CmpOp)? EXPR)* =>`
+#[allow(clippy::type_complexity)]
+fn parse_switch_arm_values<'source>(
+    tokens: &mut Stack<'source>,
+) -> Option<Vec<(Option<ComparisonOp>, Node<'source>)>> {
+    let mut values = vec![];
+    loop {
+        let cmp = terminal!(
+            SEq | SNe | Eq | Ne | IEq | Le | Lt | Ge | Gt?,
+            tokens,
+            skip_eol!(tokens)
+        );
+        let cmp = cmp.map(|t| ComparisonOp::from_rule(t.rule())).transpose()?;
+        let value = non_terminal!(ExpressionNode, tokens, skip_eol!(tokens))?;
+        values.push((cmp, value));
+
+        if terminal!(FatArrow?, tokens, skip_eol!(tokens)).is_some() {
+            return Some(values);
+        }
+        terminal!(Comma, tokens, skip_eol!(tokens))?;
+    }
+}
+
+/// Checks if the cases of a switch statement can be compiled to a jump table
+/// This is the case if all cases are equality checks against dense integer literals
+/// Returns the smallest key, and the index of the case for each key in the table
+#[allow(clippy::type_complexity)]
+fn switch_jump_table(
+    cases: &[(Vec<(ComparisonOp, Node<'_>)>, Node<'_>)],
+) -> Option<(i128, Vec<Option<usize>>)> {
+    let mut keys = vec![];
+    for (i, (values, _)) in cases.iter().enumerate() {
+        for (cmp, value) in values {
+            match (cmp, value) {
+                (ComparisonOp::Eq, Node::LiteralInt(node)) => match node.value {
+                    Primitive::Integer(key) => keys.push((key, i)),
+                    _ => return None,
+                },
+                _ => return None,
+            }
+        }
+    }
+
+    if keys.len() < JUMP_TABLE_MIN_CASES {
+        return None;
+    }
+
+    let min = keys.iter().map(|(k, _)| *k).min()?;
+    let max = keys.iter().map(|(k, _)| *k).max()?;
+    let span = usize::try_from(max.checked_sub(min)?)
+        .ok()?
+        .checked_add(1)?;
+    if span > keys.len() * 2 {
+        return None;
+    }
+
+    // Earlier cases take priority, same as the comparison chain
+    let mut table = vec![None; span];
+    for (key, case) in keys {
+        let entry = &mut table[(key - min) as usize];
+        if entry.is_none() {
+            *entry = Some(case);
+        }
+    }
+
+    Some((min, table))
+}
+
 define_node!(SwitchNode(
     expr: Node<'source>,
-    cases: Vec<(ComparisonOp, Node<'source>, Node<'source>)>,
+    cases: Vec<(Vec<(ComparisonOp, Node<'source>)>, Node<'source>)>,
     default: Node<'source>
 ) {
     "Switch statement - branches based on the value of an expression."
     "Can include multiple cases and an optional default case."
-    "Must include a default case."
-    "`switch EXPR { ((CmpOp)? EXPR => BLOCK) (, (CmpOp)? EXPR => BLOCK)* }`"
+    "Each case can match several values, separated by commas."
+    "Must include a default case, so that it always evaluates to a value."
+    "`switch EXPR { CmpOp? EXPR (, CmpOp? EXPR)* => BLOCK (, CmpOp? EXPR (, CmpOp? EXPR)* => BLOCK)* }`"
 
     build(tokens) {
         tokens.start_transaction();
@@ -113,18 +238,14 @@ define_node!(SwitchNode(
         terminal!(LBrace, tokens, skip_eol!(tokens))?;
 
         // First case - make sure we have at least one
-        // ((CmpOp)? EXPR => BLOCK)
-        let cmp = terminal!(SEq|SNe|Eq|Ne|Le|Lt|Ge|Gt ?, tokens, skip_eol!(tokens));
-        let cmp = cmp.map(|t| ComparisonOp::from_rule(t.rule())).transpose()?;
-
-        let value = non_terminal!(ExpressionNode, tokens, skip_eol!(tokens))?;
-        terminal!(FatArrow, tokens, skip_eol!(tokens))?;
+        // ((CmpOp)? EXPR (, (CmpOp)? EXPR)* => BLOCK)
+        let values = parse_switch_arm_values(tokens)?;
         let block = non_terminal!(BlockNode, tokens, skip_eol!(tokens))?;
 
-        let mut raw_cases = vec![(cmp, value, block)];
+        let mut raw_cases = vec![(values, block)];
 
         // Remaining cases
-        // (, (CmpOp)? EXPR => BLOCK)*
+        // (, (CmpOp)? EXPR (, (CmpOp)? EXPR)* => BLOCK)*
         loop {
             tokens.start_transaction();
 
@@ -132,25 +253,19 @@ define_node!(SwitchNode(
                 break;
             }
 
-            // (CmpOp)? EXPR
-            let cmp = terminal!(SEq|SNe|Eq|Ne|Le|Lt|Ge|Gt ?, tokens, skip_eol!(tokens));
-            let cmp = cmp.map(|t| ComparisonOp::from_rule(t.rule())).transpose()?;
-            let value = match non_terminal!(ExpressionNode, tokens, skip_eol!(tokens)) {
+            // (CmpOp)? EXPR (, (CmpOp)? EXPR)* =>
+            let values = match parse_switch_arm_values(tokens) {
                 Some(v) => v,
                 None => break,
             };
 
-            // => BLOCK
-            if terminal!(FatArrow, tokens, skip_eol!(tokens)).is_none() {
-                break;
-            }
-
+            // BLOCK
             let block = match non_terminal!(BlockNode, tokens, skip_eol!(tokens)) {
                 Some(b) => b,
                 None => break,
             };
 
-            raw_cases.push((cmp, value, block));
+            raw_cases.push((values, block));
 
             // Check for default case
             tokens.apply_transaction();
@@ -159,79 +274,131 @@ define_node!(SwitchNode(
         token = token.child(Rule::SwitchExpr, token.span().start .. terminal!(RBrace, tokens, skip_eol!(tokens))?.span().end);
         tokens.apply_transaction();
 
-        let mut iter = raw_cases.into_iter().rev();
-
-        let default = match iter.next() {
-            Some((None, Node::LiteralIdent(ident), block)) if ident.token.slice() == "_" => {
-                block
-            }
-            _ => return error_node!(ParserError::MissingDefaultCase(token.into_owned()))
-        };
+        // Safe to unwrap, we always have at least one case
+        let last = raw_cases.pop().unwrap();
 
+        // A default case anywhere but the end would make the rest unreachable
         let mut cases = vec![];
-        for (cmp, value, block) in iter.rev() {
-            let cmp = match cmp {
-                Some(cmp) => cmp,
-                None => ComparisonOp::Eq
-            };
-
-            let x = match Some(value) {
-                Some(Node::LiteralIdent(ident)) if ident.token.slice() == "_" => {
-                    return error_node!(ParserError::UnreachableSwitchCase(ident.token.into_owned()))
+        for (values, block) in raw_cases {
+            let mut arm = vec![];
+            for (cmp, value) in values {
+                let cmp = match cmp {
+                    Some(cmp) => cmp,
+                    None => ComparisonOp::Eq
+                };
+
+                match value {
+                    Node::LiteralIdent(ident) if ident.token.slice() == "_" => {
+                        return error_node!(ParserError::UnreachableSwitchCase(ident.token.into_owned()))
+                    }
+                    value => arm.push((cmp, value)),
                 }
-                Some(value) => (cmp, value, block),
-                _ => unreachable!()
-            };
+            }
 
-            cases.push(x);
+            cases.push((arm, block));
         }
 
+        // The default case must be last, and on its own
+        let default = match last {
+            (values, block) if values.len() == 1 && matches!(
+                &values[0], (None, Node::LiteralIdent(ident)) if ident.token.slice() == "_"
+            ) => block,
+            _ => return error_node!(ParserError::MissingDefaultCase(token.into_owned()))
+        };
+
         Some(Self { expr, cases, default, token }.into_node())
     }
 
     compile(this, compiler) {
         compiler.push_token(this.token);
+        this.expr.compile(compiler)?;
 
-        // This will effectively take the form of a series of if-else statements
-        // Repeated checks and jumps for each case
+        // Dense integer cases can use a jump table instead of a comparison chain
+        if let Some((min, table)) = switch_jump_table(&this.cases) {
+            // JTBL <min> <count> <default> <targets>*
+            // The table consumes the value, so the blocks don't need to pop it
+            compiler.push(OpCode::JTBL);
+            compiler.extend(min.serialize_into_bytes());
+            compiler.push_u64(table.len() as u64);
+            let default_jmp = compiler.push_jump_placeholder();
+            let table_jmps = table.iter().map(|_| compiler.push_jump_placeholder()).collect::<Vec<_>>();
+
+            // Each case block, followed by a jump to the end
+            let mut case_positions = vec![];
+            let mut end_jmps = vec![];
+            for (_, block) in this.cases {
+                case_positions.push(compiler.len());
+                block.compile(compiler)?;
+
+                compiler.push(OpCode::JMP);
+                end_jmps.push(compiler.push_jump_placeholder());
+            }
 
-        this.expr.compile(compiler)?;
+            // Default case
+            let default_pos = compiler.len();
+            this.default.compile(compiler)?;
+
+            // Fill in the table
+            compiler.patch_jump(default_jmp, default_pos);
+            for (jmp, case) in table_jmps.into_iter().zip(table) {
+                let pos = case.map(|i| case_positions[i]).unwrap_or(default_pos);
+                compiler.patch_jump(jmp, pos);
+            }
 
-        // All cases will be [dup calc-condition, jmpf, calc-block, jmp]
+            // Fill in the end jumps
+            let jmp_value = compiler.len();
+            for jmp in end_jmps {
+                compiler.patch_jump(jmp, jmp_value);
+            }
+
+            return Ok(());
+        }
+
+        // Otherwise this will effectively take the form of a series of if-else statements
+        // Repeated checks and jumps for each case
+
+        // All values will be [dup calc-condition, jmpt]
+        // Followed by a jump to the next case, then [pop, calc-block, jmp]
         // We need to remember to pop the value off the stack we end
 
         let mut end_jmps = vec![];
-        for (cmp, value, block) in this.cases {
-            // Duplicate the value and ready comparison
-            compiler.push(OpCode::DUP);
-            value.compile(compiler)?;
-
-            // Perform the comparison
-            compiler.push(match cmp {
-                ComparisonOp::Eq => OpCode::EQ,
-                ComparisonOp::Neq => OpCode::NE,
-                ComparisonOp::Gt => OpCode::GT,
-                ComparisonOp::Lt => OpCode::LT,
-                ComparisonOp::Gte => OpCode::GE,
-                ComparisonOp::Lte => OpCode::LE,
-                ComparisonOp::SEq => OpCode::SEQ,
-                ComparisonOp::SNeq => OpCode::SNE,
-            });
-
-            // Jump if false to next
-            compiler.push(OpCode::JMPF);
-            let skip_jmp = compiler.push_u64(0);
+        for (values, block) in this.cases {
+            let mut match_jmps = vec![];
+            for (cmp, value) in values {
+                // Duplicate the value and ready comparison
+                compiler.push(OpCode::DUP);
+                value.compile(compiler)?;
+
+                // Perform the comparison
+                compiler.push(cmp.opcode());
+
+                // Jump to the block if true
+                compiler.push(OpCode::JMPT);
+                match_jmps.push(compiler.push_jump_placeholder());
+            }
+
+            // No match - jump to the next case
+            compiler.push(OpCode::JMP);
+            let skip_jmp = compiler.push_jump_placeholder();
+
+            // Fill in the match jumps
+            let match_jmp_value = compiler.len();
+            for jmp in match_jmps {
+                compiler.patch_jump(jmp, match_jmp_value);
+            }
+
+            // Matched - the switch value is no longer needed
+            compiler.push(OpCode::POP);
 
             // The block
             block.compile(compiler)?;
 
             // Jump to end
             compiler.push(OpCode::JMP);
-            end_jmps.push(compiler.push_u64(0));
+            end_jmps.push(compiler.push_jump_placeholder());
 
             // Fill in the skip jump
-            let skip_jmp_value = compiler.len() as u64;
-            compiler.replace(skip_jmp, skip_jmp_value.serialize_into_bytes());
+            compiler.patch_jump(skip_jmp, compiler.len());
         }
 
         // Pop the extra value off the stack
@@ -241,9 +408,9 @@ define_node!(SwitchNode(
         this.default.compile(compiler)?;
 
         // Fill in the end jumps
-        let jmp_value = compiler.len() as u64;
+        let jmp_value = compiler.len();
         for jmp in end_jmps {
-            compiler.replace(jmp, jmp_value.serialize_into_bytes());
+            compiler.patch_jump(jmp, jmp_value);
         }
 
         Ok(())
@@ -256,9 +423,72 @@ define_node!(SwitchNode(
     into_owned(this) {
         Self::Owned {
             expr: this.expr.into_owned(),
-            cases: this.cases.into_iter().map(|(r, c, b)| (r, c.into_owned(), b.into_owned())).collect(),
+            cases: this.cases.into_iter().map(|(v, b)| (
+                v.into_iter().map(|(r, c)| (r, c.into_owned())).collect(),
+                b.into_owned()
+            )).collect(),
             default: this.default.into_owned(),
             token: this.token.into_owned()
         }
     }
 });
+
+#[cfg(test)]
+mod test {
+    use crate::{value::Value, Lavendeux};
+
+    #[test]
+    fn test_if_without_else() {
+        let mut lav = Lavendeux::new();
+        lav.run("x = if false then 3; y = x").unwrap();
+        assert_eq!(lav.run("y").unwrap(), Value::nil());
+
+        lav.run("f(x) = if x > 0 then 1\nz = f(3)").unwrap();
+        assert_eq!(lav.run("z").unwrap(), Value::integer(1));
+        assert_eq!(lav.run("f(-1)").unwrap(), Value::nil());
+
+        // An else on the next line still belongs to the if
+        let result = lav.run("if false then 1\nelse 2").unwrap();
+        assert_eq!(result, Value::integer(2));
+    }
+
+    #[test]
+    fn test_nil_coalesce() {
+        let mut lav = Lavendeux::new();
+        assert_eq!(lav.run("nil ?? 7").unwrap(), Value::integer(7));
+        assert_eq!(lav.run("5 ?? 7").unwrap(), Value::integer(5));
+        assert_eq!(lav.run("false ?? 7").unwrap(), Value::boolean(false));
+        assert_eq!(lav.run("nil ?? nil ?? 3").unwrap(), Value::integer(3));
+
+        // The default is only evaluated when needed
+        lav.run("n = 0; 1 ?? (n = 1)").unwrap();
+        assert_eq!(lav.run("n").unwrap(), Value::integer(0));
+
+        // Errors in the value are not hidden
+        assert!(lav.run("a = 5; a?.b ?? 7").is_err());
+    }
+
+    #[test]
+    fn test_switch_multiple_values() {
+        let mut lav = Lavendeux::new();
+
+        lav.run("f(x) = switch x { 1, 2 => 'low', 3 => 'three', _ => 'other' }")
+            .unwrap();
+        let result = lav.run("[f(1), f(2), f(3), f(4)]").unwrap();
+        assert_eq!(result, lav.run("['low', 'low', 'three', 'other']").unwrap());
+
+        // Each value can have its own comparison
+        lav.run("g(x) = switch x { < 0, > 100 => 'out', 0 => 'zero', _ => 'in' }")
+            .unwrap();
+        let result = lav.run("[g(-1), g(0), g(5), g(101)]").unwrap();
+        assert_eq!(result, lav.run("['out', 'zero', 'in', 'out']").unwrap());
+
+        // Dense integer cases are compiled to a jump table, with the same results
+        lav.run(
+            "h(x) = switch x { 0 => 'a', 1 => 'b', 2, 3 => 'c', 4 => 'd', 5 => 'e', _ => 'z' }",
+        )
+        .unwrap();
+        let result = lav.run("[h(0), h(2), h(3), h(5), h(6), h(-1)]").unwrap();
+        assert_eq!(result, lav.run("['a', 'c', 'c', 'e', 'z', 'z']").unwrap());
+    }
+}
@@ -1,9 +1,11 @@
 use super::*;
 use crate::{
+    compiler::{CompilerError, LoopCompilationExt},
     lexer::{Rule, TokenSpan},
     parser::ParserError,
-    traits::IntoOwned,
-    value::ValueType,
+    run_output::DECORATED_KEY,
+    traits::{IntoOwned, SerializeToBytes},
+    value::{Primitive, StructType, ValueType},
     vm::OpCode,
 };
 
@@ -37,8 +39,17 @@ define_node!(ScriptNode(lines: Vec<Node<'source>>) {
     }
 
     compile(this, compiler) {
+        compiler.typecheck(&this.lines)?;
+        compiler.scan_for_inlining(&this.lines);
+        compiler.lint_precedence(&this.lines);
+        compiler.lint_scopes(&this.lines);
         for line in this.lines {
+            let token = line.token().clone();
             line.compile(compiler)?;
+
+            if let Some(names) = compiler.take_name_collision() {
+                return Err(CompilerError::NameCollision(token.into_owned(), Box::new(names)));
+            }
         }
 
         Ok(())
@@ -62,7 +73,7 @@ node_silent!(LineNode {
         tokens.start_transaction();
 
         terminal!(EOI|EOL*, tokens);
-        let expr = non_terminal!(FnAssignNode|ExpressionNode, tokens)?;
+        let expr = non_terminal!(TypeDefNode|EnumDefNode|ConstDefNode|VarDefNode|AliasNode|FnAssignNode|ExpressionNode, tokens)?;
         terminal!(EOI|EOL+, tokens)?;
 
         tokens.apply_transaction();
@@ -70,7 +81,7 @@ node_silent!(LineNode {
     }
 });
 
-// "{" ~ LINE* ~ EXPR? ~ "}" | EXPR
+// "{" ~ LINE* ~ (VARDEF | EXPR)? ~ "}" | EXPR
 define_node!(BlockNode(lines: Vec<Node<'source>>) {
     build(tokens) {
         tokens.start_transaction();
@@ -78,7 +89,13 @@ define_node!(BlockNode(lines: Vec<Node<'source>>) {
         match terminal!(LBrace?, tokens) {
             Some(token) => {
                 let mut lines = non_terminal!(LineNode*, tokens);
-                if let Some(expr) = non_terminal!(ExpressionNode?, tokens) {
+
+                // A declaration can also end the block, as in `{ local x = 1 }`
+                let last = match non_terminal!(VarDefNode?, tokens) {
+                    Some(def) => Some(def),
+                    None => non_terminal!(ExpressionNode?, tokens),
+                };
+                if let Some(expr) = last {
                     lines.push(expr);
                 }
 
@@ -133,10 +150,43 @@ define_node!(BlockNode(lines: Vec<Node<'source>>) {
     }
 });
 
-pratt_node!(CastExprNode(expr: Node<'source>, type_name: ValueType) {
+/// The type named on the right of `as` or `is`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeName {
+    /// A built-in type, such as `int` or `collection`
+    Builtin(ValueType),
+
+    /// A structured type declared with `type Name = {...}`
+    /// Resolved when compiled
+    Struct(String),
+}
+
+impl TypeName {
+    /// Read a type name from a node
+    /// Any identifier that is not a built-in type is assumed to be a structured type
+    fn from_node(node: &Node<'_>) -> Option<Self> {
+        match ValueType::from_str(node.token().slice()) {
+            Some(ty) => Some(Self::Builtin(ty)),
+            None => match node {
+                Node::LiteralIdent(ident) => Some(Self::Struct(ident.name().to_string())),
+                _ => None,
+            },
+        }
+    }
+
+    /// The built-in type that values of this type have
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Self::Builtin(ty) => *ty,
+            Self::Struct(_) => ValueType::Object,
+        }
+    }
+}
+
+pratt_node!(CastExprNode(expr: Node<'source>, type_name: TypeName) {
     build(token, lhs, _op, rhs) {
         token.set_rule(Rule::CastExpr);
-        let type_name = match ValueType::from_str(rhs.token().slice()) {
+        let type_name = match TypeName::from_node(&rhs) {
             Some(t) => t,
             None => {
                 return error_node!(ParserError::CannotCastToType(rhs.token().clone().into_owned()));
@@ -146,10 +196,22 @@ pratt_node!(CastExprNode(expr: Node<'source>, type_name: ValueType) {
     }
 
     compile(this, compiler) {
+        let token = this.token.clone();
         compiler.push_token(this.token);
         this.expr.compile(compiler)?;
-        compiler.push(OpCode::CAST);
-        compiler.push_type(this.type_name);
+        match this.type_name {
+            TypeName::Builtin(ty) => {
+                compiler.push(OpCode::CAST);
+                compiler.push_type(ty);
+            }
+            TypeName::Struct(name) => {
+                let struct_type = compiler.struct_type(&name).cloned().ok_or_else(|| {
+                    CompilerError::UnknownType(token.into_owned(), name)
+                })?;
+                compiler.push(OpCode::CSST);
+                compiler.extend(struct_type.serialize_into_bytes());
+            }
+        }
 
         Ok(())
     }
@@ -167,16 +229,482 @@ pratt_node!(CastExprNode(expr: Node<'source>, type_name: ValueType) {
     }
 });
 
-pratt_node!(DecoratorExprNode(expr: Node<'source>, name_span: TokenSpan) {
+pratt_node!(IsExprNode(expr: Node<'source>, type_name: TypeName) {
     build(token, lhs, _op, rhs) {
+        token.set_rule(Rule::IsExpr);
+        let type_name = match TypeName::from_node(&rhs) {
+            Some(t) => t,
+            None => {
+                return error_node!(ParserError::CannotCastToType(rhs.token().clone().into_owned()));
+            }
+        };
+        Some(Self { expr: lhs, type_name, token }.into_node())
+    }
+
+    compile(this, compiler) {
+        let token = this.token.clone();
+        compiler.push_token(this.token);
+        this.expr.compile(compiler)?;
+        match this.type_name {
+            TypeName::Builtin(ty) => {
+                compiler.push(OpCode::ISA);
+                compiler.push_type(ty);
+            }
+            TypeName::Struct(name) => {
+                let struct_type = compiler.struct_type(&name).cloned().ok_or_else(|| {
+                    CompilerError::UnknownType(token.into_owned(), name)
+                })?;
+                compiler.push(OpCode::ISST);
+                compiler.extend(struct_type.serialize_into_bytes());
+            }
+        }
+
+        Ok(())
+    }
+
+    into_node(this) {
+        Node::IsExpr(Box::new(this))
+    }
+
+    into_owned(this) {
+        Self::Owned {
+            expr: this.expr.into_owned(),
+            type_name: this.type_name,
+            token: this.token.into_owned()
+        }
+    }
+});
+
+// type ~ Identifier ~ Assign ~ LBrace ~ (Identifier ~ Colon ~ Identifier ~ (Comma ~ Identifier ~ Colon ~ Identifier)*)? ~ RBrace
+define_node!(TypeDefNode(
+    name_span: TokenSpan,
+    fields: Vec<(TokenSpan, ValueType)>,
+) {
+    "Type definition - declares a structured type, usable with `is`, `as` and in argument types."
+    "A value matches the type if it is an object with each listed field, of the listed type."
+    "`type` is only a keyword here, so it can still be used as a variable name."
+    "`type IDENTIFIER = { (IDENTIFIER : IDENTIFIER ,)* (IDENTIFIER : IDENTIFIER)? }`"
+
+    build(tokens) {
+        tokens.start_transaction();
+
+        let token = terminal!(LiteralIdent, tokens)?;
+        if token.slice() != "type" {
+            tokens.revert_transaction();
+            return None;
+        }
+        let name = terminal!(LiteralIdent, tokens)?;
+        let mut token = token.child(Rule::TypeDefExpr, token.span());
+
+        // Assign ~ LBrace
+        terminal!(Assign, tokens)?;
+        terminal!(LBrace, tokens, skip_eol!(tokens))?;
+
+        // (Identifier ~ Colon ~ Identifier ~ Comma?)*
+        let mut fields = vec![];
+        while let Some(field) = terminal!(LiteralIdent?, tokens, skip_eol!(tokens)) {
+            terminal!(Colon, tokens, skip_eol!(tokens))?;
+            let ty = terminal!(LiteralIdent, tokens, skip_eol!(tokens))?;
+
+            fields.push((field.span(), ty));
+            if terminal!(Comma?, tokens, skip_eol!(tokens)).is_none() {
+                break;
+            }
+        }
+
+        let end = terminal!(RBrace, tokens, skip_eol!(tokens))?;
+        token.include_span(end.span());
+
+        let mut typed_fields = vec![];
+        for (field, ty) in fields {
+            match ValueType::from_str(ty.slice()) {
+                Some(t) => typed_fields.push((field, t)),
+                None => return error_node!(ParserError::InvalidType(ty.into_owned())),
+            }
+        }
+        let fields = typed_fields;
+
+        tokens.apply_transaction();
+        Some(Self { name_span: name.span(), fields, token }.into_node())
+    }
+
+    compile(this, compiler) {
+        let input = this.token.input();
+        let struct_type = StructType {
+            name: input[this.name_span.clone()].to_string(),
+            fields: this.fields.iter().map(|(name, ty)| (input[name.clone()].to_string(), *ty)).collect(),
+        };
+
+        // The definition evaluates to its signature, like an alias
+        compiler.push_token(this.token.clone());
+        compiler.push(OpCode::PUSH);
+        compiler.extend(Primitive::String(struct_type.to_string()).serialize_into_bytes());
+
+        compiler.define_struct_type(struct_type);
+        Ok(())
+    }
+
+    into_node(this) {
+        Node::TypeDef(Box::new(this))
+    }
+
+    into_owned(this) {
+        Self::Owned {
+            name_span: this.name_span,
+            fields: this.fields,
+            token: this.token.into_owned(),
+        }
+    }
+});
+
+// enum ~ Identifier ~ LBrace ~ (Identifier ~ (Assign ~ Integer)? ~ (Comma ~ Identifier ~ (Assign ~ Integer)?)*)? ~ RBrace
+define_node!(EnumDefNode(
+    name_span: TokenSpan,
+    members: Vec<(TokenSpan, i128)>,
+) {
+    "Enum definition - declares a set of named integer constants, read as `Name.Member`."
+    "Members count up from 0, or from the last member given a value with `= INTEGER`."
+    "The enum is stored as a global object, like a function, so it can be used inside functions."
+    "`enum` is only a keyword here, so it can still be used as a variable name."
+    "`enum IDENTIFIER { (IDENTIFIER (= INTEGER)? ,)* (IDENTIFIER (= INTEGER)?)? }`"
+
+    build(tokens) {
+        tokens.start_transaction();
+
+        let token = terminal!(LiteralIdent, tokens)?;
+        if token.slice() != "enum" {
+            tokens.revert_transaction();
+            return None;
+        }
+        let name = terminal!(LiteralIdent, tokens)?;
+        let mut token = token.child(Rule::EnumDefExpr, token.span());
+
+        // LBrace ~ (Identifier ~ (Assign ~ Integer)? ~ Comma?)* ~ RBrace
+        terminal!(LBrace, tokens, skip_eol!(tokens))?;
+        let mut members = vec![];
+        let mut next = 0;
+        while let Some(member) = terminal!(LiteralIdent?, tokens, skip_eol!(tokens)) {
+            if terminal!(Assign?, tokens, skip_eol!(tokens)).is_some() {
+                match non_terminal!(LiteralIntNode, tokens, skip_eol!(tokens))? {
+                    Node::LiteralInt(n) => match n.value {
+                        Primitive::Integer(i) => next = i,
+                        _ => unreachable!(),
+                    },
+                    node => return Some(node),
+                }
+            }
+
+            members.push((member.span(), next));
+            next = next.wrapping_add(1);
+            if terminal!(Comma?, tokens, skip_eol!(tokens)).is_none() {
+                break;
+            }
+        }
+
+        let end = terminal!(RBrace, tokens, skip_eol!(tokens))?;
+        token.include_span(end.span());
+
+        tokens.apply_transaction();
+        Some(Self { name_span: name.span(), members, token }.into_node())
+    }
+
+    compile(this, compiler) {
+        let input = this.token.input();
+        let name = input[this.name_span.clone()].to_string();
+        let members = this.members.iter().map(|(member, value)| {
+            (input[member.clone()].to_string(), *value)
+        }).collect::<Vec<_>>();
+
+        compiler.push_token(this.token.clone());
+
+        // Write the members to a global object
+        for (member, value) in &members {
+            compiler.push(OpCode::PUSH);
+            compiler.extend(Primitive::Integer(*value).serialize_into_bytes());
+            compiler.push(OpCode::PUSH);
+            compiler.extend(Primitive::String(member.clone()).serialize_into_bytes());
+        }
+        compiler.push(OpCode::MKOB);
+        compiler.push_u64(members.len() as u64);
+        compiler.push(OpCode::WRGL);
+        compiler.push_strhash(&name);
+
+        // The definition evaluates to its signature, like an alias
+        let signature = members.iter().map(|(member, value)| format!("{member} = {value}")).collect::<Vec<_>>();
+        let signature = format!("{name}{{{}}}", signature.join(", "));
+        compiler.push(OpCode::PUSH);
+        compiler.extend(Primitive::String(signature).serialize_into_bytes());
+
+        compiler.define_enum(name, members);
+        Ok(())
+    }
+
+    into_node(this) {
+        Node::EnumDef(Box::new(this))
+    }
+
+    into_owned(this) {
+        Self::Owned {
+            name_span: this.name_span,
+            members: this.members,
+            token: this.token.into_owned(),
+        }
+    }
+});
+
+// const ~ Identifier ~ Assign ~ EXPR
+define_node!(ConstDefNode(name_span: TokenSpan, expr: Node<'source>) {
+    "Constant definition - like an assignment, but the name cannot be reassigned or deleted."
+    "Constants are stored globally, so they can be used inside functions, and remain defined"
+    "after the file that declares them is included. Declaring a constant again replaces it."
+    "`const` is only a keyword here, so it can still be used as a variable name."
+    "`const IDENTIFIER = EXPR`"
+
+    build(tokens) {
+        tokens.start_transaction();
+
+        let token = terminal!(LiteralIdent, tokens)?;
+        if token.slice() != "const" {
+            tokens.revert_transaction();
+            return None;
+        }
+        let name = terminal!(LiteralIdent, tokens)?;
+        terminal!(Assign, tokens)?;
+        let expr = non_terminal!(ExpressionNode, tokens)?;
+
+        let mut token = token.child(Rule::ConstDefExpr, token.span());
+        token.include_span(expr.token().span());
+
+        tokens.apply_transaction();
+        Some(Self { name_span: name.span(), expr, token }.into_node())
+    }
+
+    compile(this, compiler) {
+        let name = this.token.input()[this.name_span.clone()].to_string();
+        compiler.push_token(this.token);
+
+        // The definition evaluates to its value, like an assignment
+        this.expr.compile(compiler)?;
+        compiler.push(OpCode::DUP);
+        compiler.push(OpCode::WRCN);
+        compiler.push_strhash(&name);
+
+        compiler.define_constant(name);
+        Ok(())
+    }
+
+    into_node(this) {
+        Node::ConstDef(Box::new(this))
+    }
+
+    into_owned(this) {
+        Self::Owned {
+            name_span: this.name_span,
+            expr: this.expr.into_owned(),
+            token: this.token.into_owned(),
+        }
+    }
+});
+
+/// The scope a variable is declared in, with `global` or `local`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarScope {
+    /// Shared by every function and the top level of the script
+    Global,
+
+    /// The innermost scope of the current function, or of the top level of the script
+    Local,
+}
+
+// (global|local) ~ Identifier ~ Assign ~ EXPR
+define_node!(VarDefNode(scope: VarScope, name_span: TokenSpan, expr: Node<'source>) {
+    "Variable declaration - an assignment that chooses the scope the variable is written to."
+    "Names are looked up in the scopes of the current function, innermost first, then in the global scope."
+    "Functions can only see their own arguments and variables, and globals."
+    "A plain assignment writes to a visible variable of the current function if there is one,"
+    "and otherwise creates it in the current scope - so it never changes a global, unless the"
+    "name was declared `global` earlier in the script, and not declared `local` in the current function."
+    "`global` writes to the global scope, making the variable visible everywhere, and later plain assignments to the name write there too."
+    "`local` writes to the innermost scope, hiding any variable of the same name outside it."
+    "A name cannot be declared both `global` and `local` in the same function."
+    "`global` and `local` are only keywords here, so they can still be used as variable names."
+    "`global IDENTIFIER = EXPR` | `local IDENTIFIER = EXPR`"
+
+    build(tokens) {
+        tokens.start_transaction();
+
+        let token = terminal!(LiteralIdent, tokens)?;
+        let scope = match token.slice() {
+            "global" => VarScope::Global,
+            "local" => VarScope::Local,
+            _ => {
+                tokens.revert_transaction();
+                return None;
+            }
+        };
+        let name = terminal!(LiteralIdent, tokens)?;
+        terminal!(Assign, tokens)?;
+        let expr = non_terminal!(ExpressionNode, tokens)?;
+
+        let mut token = token.child(Rule::VarDefExpr, token.span());
+        token.include_span(expr.token().span());
+
+        tokens.apply_transaction();
+        Some(Self { scope, name_span: name.span(), expr, token }.into_node())
+    }
+
+    compile(this, compiler) {
+        let name = this.token.input()[this.name_span.clone()].to_string();
+        if compiler.is_constant(&name) {
+            let token = this.token.child(Rule::LiteralIdent, this.name_span.clone());
+            return Err(CompilerError::ConstantAssignment(token.into_owned(), name));
+        }
+        if compiler.declare_scope(&name, this.scope).is_err() {
+            return Err(CompilerError::ConflictingDeclaration(this.token.into_owned(), name));
+        }
+        compiler.push_token(this.token);
+
+        // The declaration evaluates to its value, like an assignment
+        this.expr.compile(compiler)?;
+        compiler.push(OpCode::DUP);
+        compiler.push(match this.scope {
+            VarScope::Global => OpCode::WRGL,
+            VarScope::Local => OpCode::WRLC,
+        });
+        compiler.push_strhash(&name);
+        Ok(())
+    }
+
+    into_node(this) {
+        Node::VarDef(Box::new(this))
+    }
+
+    into_owned(this) {
+        Self::Owned {
+            scope: this.scope,
+            name_span: this.name_span,
+            expr: this.expr.into_owned(),
+            token: this.token.into_owned(),
+        }
+    }
+});
+
+// let ~ Identifier ~ Assign ~ EXPR ~ ("," ~ Identifier ~ Assign ~ EXPR)* ~ in ~ BLOCK
+define_node!(LetNode(bindings: Vec<(TokenSpan, Node<'source>)>, expr: Node<'source>) {
+    "Let expression - evaluates an expression with some temporary variables, as in `let x = 5, y = 6 in x * y`."
+    "The variables only exist until the expression is done, hiding any others with the same names,"
+    "so nothing outside of it is changed. Each variable can use the ones declared before it."
+    "`let` is only a keyword here, so it can still be used as a variable name."
+    "`let IDENTIFIER = EXPR (, IDENTIFIER = EXPR)* in BLOCK`"
+
+    build(tokens) {
+        tokens.start_transaction();
+
+        let token = terminal!(LiteralIdent, tokens)?;
+        if token.slice() != "let" {
+            tokens.revert_transaction();
+            return None;
+        }
+
+        let mut bindings = vec![];
+        loop {
+            let name = terminal!(LiteralIdent, tokens, skip_eol!(tokens))?;
+            terminal!(Assign, tokens, skip_eol!(tokens))?;
+            let value = non_terminal!(ExpressionNode, tokens, skip_eol!(tokens))?;
+            bindings.push((name.span(), value));
+
+            if terminal!(Comma?, tokens, skip_eol!(tokens)).is_none() {
+                break;
+            }
+        }
+
+        terminal!(In, tokens, skip_eol!(tokens))?;
+        let expr = non_terminal!(BlockNode, tokens, skip_eol!(tokens))?;
+
+        let mut token = token.child(Rule::LetExpr, token.span());
+        token.include_span(expr.token().span());
+
+        tokens.apply_transaction();
+        Some(Self { bindings, expr, token }.into_node())
+    }
+
+    compile(this, compiler) {
+        let input = this.token.input();
+        let mut bindings = vec![];
+        for (span, value) in this.bindings {
+            let name = input[span.clone()].to_string();
+            if compiler.is_constant(&name) {
+                let token = this.token.child(Rule::LiteralIdent, span);
+                return Err(CompilerError::ConstantAssignment(token.into_owned(), name));
+            }
+            bindings.push((name, value));
+        }
+        compiler.push_token(this.token);
+
+        // The variables live in a scope of their own, in order
+        compiler.push_scope_in();
+        for (name, value) in bindings {
+            value.compile(compiler)?;
+            compiler.push(OpCode::WRLC);
+            compiler.push_strhash(&name);
+        }
+
+        // Resolve the result before the variables it may refer to are gone
+        this.expr.compile(compiler)?;
+        compiler.push(OpCode::RREF);
+        compiler.push_scope_out();
+        Ok(())
+    }
+
+    into_node(this) {
+        Node::Let(Box::new(this))
+    }
+
+    into_owned(this) {
+        Self::Owned {
+            bindings: this.bindings.into_iter().map(|(span, value)| (span, value.into_owned())).collect(),
+            expr: this.expr.into_owned(),
+            token: this.token.into_owned(),
+        }
+    }
+});
+
+pratt_node!(DecoratorExprNode(expr: Node<'source>, name_span: TokenSpan) {
+    "Decorator expression (e.g. `255 @hex`)"
+    "Calls the decorator with the value, and keeps both the value and the string it returns"
+    "`EXPR @ IDENTIFIER`"
+
+    build(token, lhs, op) {
         token.set_rule(Rule::DecoratorExpr);
-        Some(Self { expr: lhs, name_span: rhs.token().span(), token }.into_node())
+
+        let op = if let Node::PostfixDecoratorOperator(op) = op { op } else { unreachable!() };
+        Some(Self { expr: lhs, name_span: op.name_span, token }.into_node())
     }
 
-    compile(_this, _compiler) {
-        todo!()
-        // Validate function dignature
-        // Then normal function call
+    compile(this, compiler) {
+        let name = this.token.input()[this.name_span.clone()].to_string();
+        compiler.push_token(this.token);
+
+        // Keep the raw value alongside the decorated string, as an object with the reserved
+        // `__decorated` key, so that hosts get both through `RunOutput::Decorated`
+        this.expr.compile(compiler)?;
+        compiler.push(OpCode::DUP);
+
+        compiler.push(OpCode::CALL);
+        compiler.push_strhash(&name);
+        compiler.push_u64(1);
+        compiler.push(OpCode::DFMT);
+
+        // [value, decorated] => [decorated, "__decorated", value, "value"]
+        compiler.push(OpCode::SWP);
+        compiler.push(OpCode::PUSH);
+        compiler.extend(Primitive::String(DECORATED_KEY.to_string()).serialize_into_bytes());
+        compiler.push(OpCode::SWP);
+        compiler.push(OpCode::PUSH);
+        compiler.extend(Primitive::String("value".to_string()).serialize_into_bytes());
+        compiler.push(OpCode::MKOB);
+        compiler.push_u64(2);
+        Ok(())
     }
 
     into_node(this) {
@@ -191,3 +719,220 @@ pratt_node!(DecoratorExprNode(expr: Node<'source>, name_span: TokenSpan) {
         }
     }
 });
+
+// "@" ~ IDENTIFIER
+define_node!(PostfixDecoratorOperatorNode(name_span: TokenSpan) {
+    build(tokens) {
+        tokens.start_transaction();
+
+        let token = terminal!(Decorator, tokens)?;
+        let name = terminal!(LiteralIdent, tokens)?;
+
+        let mut token = token.child(Rule::DecoratorOperator, token.span());
+        token.include_span(name.span());
+
+        tokens.apply_transaction();
+        Some(Self { name_span: name.span(), token }.into_node())
+    }
+
+    compile(_this, _compiler) {
+        unreachable!("Intermediate node")
+    }
+
+    into_node(this) {
+        Node::PostfixDecoratorOperator(Box::new(this))
+    }
+
+    into_owned(this) {
+        Self::Owned {
+            name_span: this.name_span,
+            token: this.token.into_owned()
+        }
+    }
+});
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        compiler::CompilerError,
+        parser::ParserError,
+        value::{Value, ValueError},
+        vm::error::{RuntimeError, RuntimeErrorType},
+        Error, Lavendeux,
+    };
+
+    #[test]
+    fn test_global_local() {
+        let mut lav = Lavendeux::new();
+
+        // Globals are visible to every function, and plain assignments to them write there
+        lav.run("init() = { global total = 0; 0 }\nadd(n) = { total = total + n; total }")
+            .unwrap();
+        lav.run("init()\nadd(3)\nadd(4)").unwrap();
+        assert_eq!(lav.run("total").unwrap(), Value::integer(7));
+
+        // Local variables and arguments hide globals of the same name
+        lav.run("h() = { local total = 100; total = total + 1; total }")
+            .unwrap();
+        assert_eq!(lav.run("h()").unwrap(), Value::integer(101));
+        assert_eq!(
+            lav.run("f(total) = total * 2\nf(1)").unwrap(),
+            Value::integer(2)
+        );
+        assert_eq!(lav.run("total").unwrap(), Value::integer(7));
+
+        // A local in a loop is discarded with it, leaving the outer variable alone
+        lav.run("x = 1\nfor i in 0..3 { local x = i }").unwrap();
+        assert_eq!(lav.run("x").unwrap(), Value::integer(1));
+
+        // Still ordinary names everywhere else
+        lav.run("global = 3\nlocal = global + 1").unwrap();
+        assert_eq!(lav.run("local").unwrap(), Value::integer(4));
+
+        for source in ["global q = 1\nlocal q = 2", "f(q) = { global q = 1; q }"] {
+            let error = lav.run(source).unwrap_err();
+            assert_eq!(error.code(), "E0216", "{source}");
+        }
+        assert_eq!(
+            lav.run("const k = 1\nglobal k = 2").unwrap_err().code(),
+            "E0209"
+        );
+    }
+
+    #[test]
+    fn test_let() {
+        let mut lav = Lavendeux::new();
+
+        let result = lav.run("let x = 5, y = 6 in x * y").unwrap();
+        assert_eq!(result, Value::integer(30));
+        assert!(lav.run("x").is_err());
+
+        // Later variables can use earlier ones, and nothing outside is changed
+        lav.run("x = 1").unwrap();
+        let result = lav.run("let x = 2, y = x + 1 in { z = x * y; z }").unwrap();
+        assert_eq!(result, Value::integer(6));
+        assert_eq!(lav.run("x").unwrap(), Value::integer(1));
+        assert!(lav.run("z").is_err());
+
+        let result = lav.run("f(n) = let sq = n * n in sq + 1\nf(3)").unwrap();
+        assert_eq!(result, Value::integer(10));
+        let result = lav.run("1 + let x = [1, 2] in x[1]").unwrap();
+        assert_eq!(result, Value::integer(3));
+
+        // Still an ordinary name everywhere else
+        lav.run("let = 5").unwrap();
+        assert_eq!(lav.run("let + 1").unwrap(), Value::integer(6));
+    }
+
+    #[test]
+    fn test_struct_types() {
+        let mut lav = Lavendeux::new();
+        let point = "type Point = {x: int, y: int}\n";
+
+        // Objects with every field, of the right type, match; extra fields are allowed
+        let result = lav
+            .run(&format!(
+                "{point}p = {{'x': 1, 'y': 2}} as Point\n\
+                [p.x + p.y, p is Point, {{'x': 1, 'y': 2, 'z': 3}} is Point, {{'x': 1}} is Point]"
+            ))
+            .unwrap();
+        let lines = result.cast_array().unwrap();
+        assert_eq!(lines[0].to_string(), "Point{x: int, y: int}");
+        assert_eq!(lines[2].to_string(), "[3, true, true, false]");
+
+        // Each field is checked when converting, or when passed as an argument
+        let mut mismatch = |source: &str| match lav.run(&format!("{point}{source}")) {
+            Err(Error::Runtime(RuntimeError {
+                error: RuntimeErrorType::StructMismatch(name, detail),
+                ..
+            })) if name == "Point" => detail,
+            other => panic!("{source}: {other:?}"),
+        };
+        assert_eq!(
+            mismatch("{'x': 'a', 'y': 2} as Point"),
+            "field `x` expected `int`, found `string`"
+        );
+        assert_eq!(mismatch("{'x': 1} as Point"), "missing field `y`");
+        assert_eq!(
+            mismatch("f(p: Point) = p.x\nf({'y': 1})"),
+            "missing field `x`"
+        );
+        assert_eq!(
+            mismatch("[1, 2] as Point"),
+            "expected `object`, found `array`"
+        );
+
+        // Fields that are not in the object cannot be read
+        let error = lav
+            .run(&format!("{point}p = {{'x': 1, 'y': 2}} as Point\np.z"))
+            .unwrap_err();
+        assert!(
+            matches!(
+                &error,
+                Error::Runtime(RuntimeError {
+                    error: RuntimeErrorType::Value(ValueError::KeyNotFound(_)),
+                    ..
+                })
+            ),
+            "{error:?}"
+        );
+
+        // Field types must be built in, and types must be declared before they are used
+        let error = lav.run("type Bad = {x: widget}").unwrap_err();
+        assert!(
+            matches!(
+                &error,
+                Error::Compiler(CompilerError::Parser(ParserError::InvalidType(_)))
+            ),
+            "{error:?}"
+        );
+        let error = lav.run("{'x': 1} as Undeclared").unwrap_err();
+        assert!(
+            matches!(
+                &error,
+                Error::Compiler(CompilerError::UnknownType(_, name)) if name == "Undeclared"
+            ),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn test_enums() {
+        let mut lav = Lavendeux::new();
+        let color = "enum Color { Red, Green, Blue = 5, Violet }\n";
+
+        // Members can be matched in a switch, including inside functions
+        let result = lav
+            .run(&format!(
+                "{color}name(c) = switch c {{\n\
+                    Color.Red => 'red', Color.Green => 'green', Color.Blue => 'blue', _ => 'other'\n\
+                }}\n\
+                [name(Color.Blue), name(Color.Red), name(1), name(Color.Violet), Color.Violet]"
+            ))
+            .unwrap();
+        let lines = result.cast_array().unwrap();
+        assert_eq!(
+            lines[0].to_string(),
+            "Color{Red = 0, Green = 1, Blue = 5, Violet = 6}"
+        );
+        assert_eq!(
+            lines.last().unwrap().to_string(),
+            "[`blue`, `red`, `green`, `other`, 6]"
+        );
+
+        // Members that were not declared are an error
+        for source in ["Color.Purple", "Color['Purple']"] {
+            let error = lav.run(&format!("{color}{source}")).unwrap_err();
+            assert!(
+                matches!(
+                    &error,
+                    Error::Runtime(RuntimeError {
+                        error: RuntimeErrorType::Value(ValueError::KeyNotFound(key)),
+                        ..
+                    }) if key.contains("Purple")
+                ),
+                "{source}: {error:?}"
+            );
+        }
+    }
+}
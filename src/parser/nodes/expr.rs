@@ -1,5 +1,9 @@
 use super::*;
-use crate::{lexer::Rule, traits::IntoOwned};
+use crate::{
+    lexer::{Rule, Stack},
+    parser::CustomOperator,
+    traits::IntoOwned,
+};
 
 // prefix_op? ~ EOL* ~ TERM ~ postfix_operation* ~ ( EOL* ~ infix_op ~ prefix_op? ~ EOL* ~ TERM ~ postfix_operation*)*
 node_silent!(ExpressionNode {
@@ -17,6 +21,7 @@ node_silent!(ExpressionNode {
         expr.extend(non_terminal!(PostfixOperatorNode*, tokens));
 
         // ( EOL* ~ infix_op ~ prefix_op? ~ EOL* ~ TERM ~ postfix_operation*)*
+        let mut terms = 1;
         loop {
             tokens.start_transaction();
             let mut group = vec![];
@@ -37,7 +42,12 @@ node_silent!(ExpressionNode {
             group.extend(non_terminal!(PostfixOperatorNode*, tokens));
 
             tokens.apply_transaction();
-            expr.extend(group.drain(0..))
+            expr.extend(group.drain(0..));
+
+            terms += 1;
+            if !tokens.check_expr_terms(terms) {
+                return None;
+            }
         }
 
         if expr.len() == 1 {
@@ -47,12 +57,13 @@ node_silent!(ExpressionNode {
         }
 
         tokens.apply_transaction();
+
         expr.reverse(); // pratt expects the expression to be in reverse order
-        crate::parser::pratt::fold_expression(&mut expr, 0)
+        crate::parser::pratt::fold_expression(tokens, &mut expr, 0)
     }
 });
 
-// "(" ~ EXPR ~ ")" | Array | Object | SKIP_KEYWORD | BREAK_EXPRESSION | RETURN_EXPRESSION | FOR_LOOP_EXPRESSION | SWITCH_EXPRESSION | IF_EXPRESSION | Literal
+// "(" ~ EXPR ~ ")" | Array | Object | SKIP_KEYWORD | BREAK_EXPRESSION | RETURN_EXPRESSION | FOR_LOOP_EXPRESSION | SWITCH_EXPRESSION | IF_EXPRESSION | LET_EXPRESSION | Literal
 node_silent!(TermNode {
     build(tokens) {
         tokens.start_transaction();
@@ -65,15 +76,7 @@ node_silent!(TermNode {
             tokens.apply_transaction();
             Some(expr)
         } else {
-            let t = non_terminal!(
-                LiteralStringNode|LiteralRegexNode
-                | LiteralIdentNode
-                | LiteralFloatNode|LiteralBoolNode|LiteralConstNode
-                | LiteralIntNode
-                | ArrayNode|ObjectNode
-                | ContinueNode|BreakNode|ReturnNode
-                | ForNode|SwitchNode|IfNode
-            , tokens)?;
+            let t = parse_bare_term(tokens)?;
 
             tokens.apply_transaction();
             Some(t)
@@ -81,23 +84,64 @@ node_silent!(TermNode {
     }
 });
 
-define_node!(InfixOperatorNode(inner: Option<Node<'source>>) {
+/// Parses a term that is not in parentheses
+/// Kept out of `TermNode`, whose stack frame is repeated for every level of nested parentheses
+#[inline(never)]
+fn parse_bare_term<'source>(tokens: &mut Stack<'source>) -> Option<Node<'source>> {
+    non_terminal!(
+        LiteralStringNode
+            | LiteralRegexNode
+            | LetNode
+            | LiteralIdentNode
+            | LiteralFloatNode
+            | LiteralBoolNode
+            | LiteralConstNode
+            | LiteralIntNode
+            | ArrayNode
+            | ObjectNode
+            | ContinueNode
+            | BreakNode
+            | ReturnNode
+            | ForNode
+            | SwitchNode
+            | IfNode,
+        tokens
+    )
+}
+
+define_node!(InfixOperatorNode(inner: Option<Node<'source>>, custom: Option<CustomOperator>) {
     build(tokens) {
         tokens.start_transaction();
 
-        let mut token = terminal!(
-            Contains|Matches|StartsWith|EndsWith
-            | Assign
-            | AssignAdd|AssignSub|AssignMul|AssignDiv|AssignMod|AssignPow
-            | AssignAnd|AssignOr|AssignXor|AssignSL|AssignSR
-            | Decorator
-            | Add|Sub|Mul|Div|Mod|Pow
-            | BitwiseOr|BitwiseAnd|Xor|SL|SR
-            | LogicalAnd|LogicalOr
-            | SEq|SNe | Eq|Ne | Le|Lt | Ge|Gt
-            | As | Range
-            | Question
-        , tokens)?;
+        // Custom operators are only accepted if they were registered with the parser
+        // Otherwise they are left out of the expected tokens in syntax errors
+        let custom_token = match tokens.options().custom_operators.is_empty() {
+            true => None,
+            false => terminal!(CustomOperator?, tokens),
+        };
+
+        let mut token = match custom_token {
+            Some(token) => token,
+            None => terminal!(
+                Contains|Matches|StartsWith|EndsWith
+                | Assign
+                | AssignAdd|AssignSub|AssignMul|AssignDiv|AssignMod|AssignPow
+                | AssignAnd|AssignOr|AssignXor|AssignSL|AssignSR
+                | Add|Sub|Mul|Div|Mod|Pow
+                | BitwiseOr|BitwiseAnd|Xor|SL|SR
+                | LogicalAnd|LogicalOr
+                | SEq|SNe | Eq|Ne | IEq | Le|Lt | Ge|Gt
+                | As | Is | Range
+                | NilCoalesce
+                | Question
+            , tokens)?,
+        };
+
+        let mut custom = None;
+        if token.rule() == Rule::CustomOperator {
+            let symbol = token.slice();
+            custom = Some(tokens.options().custom_operators.iter().find(|op| op.symbol == symbol)?.clone());
+        }
 
         // ternary part... "?" ~ EXPR
         let mut inner = None;
@@ -107,8 +151,12 @@ define_node!(InfixOperatorNode(inner: Option<Node<'source>>) {
             token.include_span(terminal!(Colon, tokens, skip_eol!(tokens))?.span());
         }
 
+        if !tokens.check_operator(&token) {
+            return None;
+        }
+
         tokens.apply_transaction();
-        Some(Self { inner, token }.into_node())
+        Some(Self { inner, custom, token }.into_node())
     }
 
     compile(_this, _compiler) {
@@ -122,6 +170,7 @@ define_node!(InfixOperatorNode(inner: Option<Node<'source>>) {
     into_owned(this) {
         Self::Owned {
             inner: this.inner.map(|n| n.into_owned()),
+            custom: this.custom,
             token: this.token.into_owned()
         }
     }
@@ -137,6 +186,10 @@ define_node!(PrefixOperatorNode() {
             token.set_rule(Rule::PrefixNeg)
         }
 
+        if !tokens.check_operator(&token) {
+            return None;
+        }
+
         tokens.apply_transaction();
         Some(Node::PrefixOperator(Box::new(Self { token })))
     }
@@ -162,9 +215,13 @@ node_silent!(PostfixOperatorNode {
         tokens.start_transaction();
 
         let operator = non_terminal!(
-            PostfixIndexingOperatorNode | PostfixFnCallOperatorNode,
+            PostfixIndexingOperatorNode | PostfixFnCallOperatorNode | PostfixDecoratorOperatorNode,
         tokens)?;
 
+        if !tokens.check_operator(operator.token()) {
+            return None;
+        }
+
         tokens.apply_transaction();
         Some(operator)
     }
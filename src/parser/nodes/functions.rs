@@ -2,13 +2,13 @@ use std::str::FromStr;
 
 use super::*;
 use crate::{
-    compiler::{CompilerError, FunctionDocs},
+    compiler::{Alias, CompilerError, FunctionDocs},
     lexer::{Rule, Token, TokenSpan},
     parser::{
         function_compiler::{FunctionArgumentCompiler, FunctionArgumentDefault, FunctionCompiler},
         special_functions, ParserError,
     },
-    traits::IntoOwned,
+    traits::{IntoOwned, SerializeToBytes},
     value::{Primitive, Value, ValueType},
     vm::OpCode,
 };
@@ -26,9 +26,8 @@ define_node!(FnAssignNode(
     "Can be preceded by a docblock"
     "Args can have optional types and default values, e.g. `foo(a: int, b: int = 0) {}`"
     "`
-        DocBlockComment* ~ At? ~ Identifer ~ EOL* ~ LParen ~ EOL* ~
-            (ref? ~ Identifier ~ (EOL* ~ COLON ~ IDENTIFIER)? ~ (Assign ~ EXPR)? ~ EOL* ~ Comma ~ EOL*)* ~ (ref? ~ Identifier ~ (EOL* ~ COLON ~ IDENTIFIER)? ~ (Assign ~ EXPR)?)? ~ EOL* ~ 
-        RParen ~ (EOL* ~ COLON ~ IDENTIFIER)? ~ EOL* ~ Assign ~ Block
+        DocBlockComment* @? IDENTIFIER '(' (ref? IDENTIFIER (: IDENTIFIER)? (= EXPR)? ,)* (ref? IDENTIFIER (: IDENTIFIER)? (= EXPR)?)?
+        ')' (: IDENTIFIER)? = BLOCK
     `"
 
     build(tokens) {
@@ -203,15 +202,25 @@ define_node!(FnAssignNode(
     }
 
     compile(this, compiler) {
+        compiler.consider_inlining(&this);
+
         let name = this.token.input();
         let name = name[this.name_span.start..this.name_span.end].to_string();
 
         let arguments = this.args.into_iter().map(|(name, ty, default, by_ref)| {
             let name = this.token.input()[name.start..name.end].to_string();
-            let ty = ty.map(|ty| {
-                let ty = &this.token.input()[ty.start..ty.end];
-                ValueType::from_str(ty).unwrap_or(ValueType::All)
-            }).unwrap_or(ValueType::All);
+
+            // Structured types are objects, checked against the type when the function is called
+            let struct_type = ty.as_ref().and_then(|ty| {
+                compiler.struct_type(&this.token.input()[ty.start..ty.end]).cloned()
+            });
+            let ty = match struct_type {
+                Some(_) => ValueType::Object,
+                None => ty.map(|ty| {
+                    let ty = &this.token.input()[ty.start..ty.end];
+                    ValueType::from_str(ty).unwrap_or(ValueType::All)
+                }).unwrap_or(ValueType::All),
+            };
 
             let default = match default {
                 Some(Node::LiteralString(s)) => FunctionArgumentDefault::Static(Value::Primitive(s.value)),
@@ -222,7 +231,7 @@ define_node!(FnAssignNode(
                 None => FunctionArgumentDefault::None
             };
 
-            (name, ty, default, by_ref)
+            (name, ty, struct_type, default, by_ref)
         }).collect::<Vec<_>>();
 
         let returns = this.returns.map(|returns| {
@@ -230,6 +239,7 @@ define_node!(FnAssignNode(
             ValueType::from_str(returns).unwrap_or(ValueType::All)
         }).unwrap_or(ValueType::All);
 
+        compiler.define_function(&name, this.token.clone())?;
         compiler.push_token(this.token);
 
         let arg_names = arguments.iter().map(|(name, ..)| name.as_str()).collect::<Vec<_>>();
@@ -242,10 +252,11 @@ define_node!(FnAssignNode(
             ty: returns,
             dbg: None,
             doc,
-            args: arguments.into_iter().map(|(name, ty, default, by_ref)| {
+            args: arguments.into_iter().map(|(name, ty, struct_type, default, by_ref)| {
                 FunctionArgumentCompiler {
                     name,
                     ty,
+                    struct_type,
                     default,
                     by_ref
                 }
@@ -275,6 +286,73 @@ define_node!(FnAssignNode(
     }
 });
 
+// alias ~ Identifier ~ LParen ~ (Identifier ~ (Comma ~ Identifier)*)? ~ RParen ~ FatArrow ~ EXPR
+define_node!(AliasNode(
+    name_span: TokenSpan,
+    args: Vec<TokenSpan>,
+    body: Node<'source>,
+) {
+    "Alias definition - defines an expression that is expanded in place wherever it is called."
+    "Unlike a function, no call is made at runtime; arguments are substituted into the expression,"
+    "so an argument is evaluated each time it is used. Aliases can be used after they are defined."
+    "`alias IDENTIFIER '(' (IDENTIFIER ,)* IDENTIFIER? ')' => EXPR`"
+
+    build(tokens) {
+        tokens.start_transaction();
+
+        let token = terminal!(Alias, tokens)?;
+        let name = terminal!(LiteralIdent, tokens)?;
+        let mut token = token.child(Rule::AliasExpr, token.span());
+
+        // LParen ~ (Identifier ~ (Comma ~ Identifier)*)? ~ RParen
+        terminal!(LParen, tokens, skip_eol!(tokens))?;
+        let mut args = vec![];
+        while let Some(arg) = terminal!(LiteralIdent?, tokens, skip_eol!(tokens)) {
+            args.push(arg.span());
+            if terminal!(Comma?, tokens, skip_eol!(tokens)).is_none() {
+                break;
+            }
+        }
+        terminal!(RParen, tokens, skip_eol!(tokens))?;
+
+        // FatArrow ~ EXPR
+        terminal!(FatArrow, tokens, skip_eol!(tokens))?;
+        let body = non_terminal!(ExpressionNode, tokens, skip_eol!(tokens))?;
+        token.include_span(body.token().span());
+
+        tokens.apply_transaction();
+        Some(Self { name_span: name.span(), args, body, token }.into_node())
+    }
+
+    compile(this, compiler) {
+        let input = this.token.input();
+        let name = input[this.name_span.start..this.name_span.end].to_string();
+        let args = this.args.iter().map(|a| input[a.start..a.end].to_string()).collect::<Vec<_>>();
+
+        // The alias evaluates to its signature, like a function definition
+        let signature = format!("{name}({})", args.join(", "));
+        compiler.push_token(this.token.clone());
+        compiler.push(OpCode::PUSH);
+        compiler.extend(Primitive::String(signature).serialize_into_bytes());
+
+        compiler.define_alias(name, Alias { args, body: this.body.into_owned() });
+        Ok(())
+    }
+
+    into_node(this) {
+        Node::Alias(Box::new(this))
+    }
+
+    into_owned(this) {
+        Self::Owned {
+            name_span: this.name_span,
+            args: this.args,
+            body: this.body.into_owned(),
+            token: this.token.into_owned(),
+        }
+    }
+});
+
 // return BLOCK?
 define_node!(ReturnNode(value: Node<'source>) {
     "Return statement - returns from the current function."
@@ -319,10 +397,11 @@ define_node!(ReturnNode(value: Node<'source>) {
     }
 });
 
-pratt_node!(FnCallNode(name_span: TokenSpan, args: Vec<Node<'source>>) {
+pratt_node!(FnCallNode(name_span: TokenSpan, args: Vec<Node<'source>>, method: bool) {
     "Function call expression."
     "Can be a normal function call (e.g. `foo(1, 2, 3)`)"
     "Or a method call (e.g. `foo.bar(1, 2, 3)`)"
+    "A method call on an object holding a function named `bar` calls that function instead"
 
     build(token, lhs, op) {
         token.set_rule(Rule::FnCallExpr);
@@ -337,7 +416,8 @@ pratt_node!(FnCallNode(name_span: TokenSpan, args: Vec<Node<'source>>) {
                 let mut args = vec![lhs];
                 args.extend(op.args.into_iter());
                 Some(Self {
-                    name_span, token, args
+                    name_span, token, args,
+                    method: true,
                 }.into_node())
             },
             None => {
@@ -346,6 +426,7 @@ pratt_node!(FnCallNode(name_span: TokenSpan, args: Vec<Node<'source>>) {
                 Some(Self {
                     name_span, token,
                     args: op.args.into_iter().collect(),
+                    method: false,
                 }.into_node())
             }
         }
@@ -383,7 +464,7 @@ pratt_node!(FnCallNode(name_span: TokenSpan, args: Vec<Node<'source>>) {
                 let filename = match this.args.get(0).unwrap().clone() {
                     Node::LiteralString(s) => match s.value {
                         Primitive::String(s) => s,
-                        _ => unreachable!()
+                        _ => return Err(CompilerError::InvalidInclude(_token.into_owned()))
                     },
                     _ => return Err(CompilerError::InvalidInclude(_token.into_owned()))
                 };
@@ -391,6 +472,81 @@ pratt_node!(FnCallNode(name_span: TokenSpan, args: Vec<Node<'source>>) {
                 special_functions::__include(compiler, _token, filename)?;
             }
 
+            //
+            // Spread arguments; the list of arguments is built at runtime
+            _ if this.args.iter().any(|a| matches!(a, Node::Spread(_))) => {
+                if compiler.alias(&name).is_some() {
+                    return Err(CompilerError::SpreadIntoAlias(_token.into_owned(), name));
+                }
+
+                let method = this.method;
+                ArrayNode { elements: this.args, token: _token }.into_node().compile(compiler)?;
+
+                if method {
+                    compiler.push(OpCode::CLMA);
+                    compiler.extend(name.serialize_into_bytes());
+                } else {
+                    compiler.push(OpCode::CALA);
+                    compiler.push_strhash(&name);
+                }
+            }
+
+            //
+            // Members of an enum, in declaration order
+            // Objects are unordered, so these are resolved at compile-time
+            "keys" | "values" if this.args.len() == 1 && compiler.alias(&name).is_none() && matches!(
+                &this.args[0], Node::LiteralIdent(n) if compiler.enum_members(n.name()).is_some()
+            ) => {
+                let members = match &this.args[0] {
+                    Node::LiteralIdent(n) => compiler.enum_members(n.name()).unwrap().to_vec(),
+                    _ => unreachable!()
+                };
+
+                let n_members = members.len();
+                for (member, value) in members.into_iter().rev() {
+                    let value = if name == "keys" { Primitive::String(member) } else { Primitive::Integer(value) };
+                    compiler.push(OpCode::PUSH);
+                    compiler.extend(value.serialize_into_bytes());
+                }
+                compiler.push(OpCode::MKAR);
+                compiler.push_u64(n_members as u64);
+            }
+
+            //
+            // Alias; expanded in place
+            _ if compiler.alias(&name).is_some() => {
+                let alias = compiler.alias(&name).cloned().unwrap();
+                if this.args.len() != alias.args.len() {
+                    return Err(CompilerError::InvalidArgumentCount(_token.into_owned(), name, alias.args.len(), this.args.len()));
+                }
+
+                if !compiler.enter_alias() {
+                    return Err(CompilerError::AliasRecursion(_token.into_owned(), name));
+                }
+                alias.expand(this.args).compile(compiler)?;
+                compiler.exit_alias();
+            }
+
+            //
+            // Small user function; inlined
+            // Method calls are resolved at runtime, since the object may hold its own function
+            _ if !this.method && compiler.can_inline(&name, this.args.len()) => {
+                compiler.compile_inline(&name, this.args)?;
+            }
+
+            //
+            // Method call
+            _ if this.method => {
+                let n_args = this.args.len();
+                for arg in this.args {
+                    arg.compile(compiler)?;
+                }
+
+                compiler.push(OpCode::CALM);
+                compiler.extend(name.serialize_into_bytes());
+                compiler.push_u64(n_args as u64);
+            }
+
             //
             // Normal function call
             _ => {
@@ -416,6 +572,48 @@ pratt_node!(FnCallNode(name_span: TokenSpan, args: Vec<Node<'source>>) {
         Self::Owned {
             name_span: this.name_span,
             args: this.args.into_iter().map(|a| a.into_owned()).collect(),
+            method: this.method,
+            token: this.token.into_owned(),
+        }
+    }
+});
+
+pratt_node!(CustomInfixExprNode(lhs: Node<'source>, rhs: Node<'source>, handler: String) {
+    "Custom infix operator expression."
+    "For an operator registered with `ParserOptions::custom_operators`"
+    "`EXPR SYMBOL EXPR`"
+    "Compiled as a call to the operator's handler, with both operands as arguments"
+
+    build(token, lhs, op, rhs) {
+        token.set_rule(Rule::FnCallExpr);
+        let handler = match op {
+            Node::InfixOperator(op) => op.custom?.handler,
+            _ => unreachable!("Invalid operator: {:?}", op)
+        };
+        Some(Self { lhs, rhs, handler, token }.into_node())
+    }
+
+    compile(this, compiler) {
+        compiler.push_token(this.token);
+
+        this.lhs.compile(compiler)?;
+        this.rhs.compile(compiler)?;
+        compiler.push(OpCode::CALL);
+        compiler.push_strhash(&this.handler);
+        compiler.push_u64(2);
+
+        Ok(())
+    }
+
+    into_node(this) {
+        Node::CustomInfixExpr(Box::new(this))
+    }
+
+    into_owned(this) {
+        Self::Owned {
+            lhs: this.lhs.into_owned(),
+            rhs: this.rhs.into_owned(),
+            handler: this.handler,
             token: this.token.into_owned(),
         }
     }
@@ -475,7 +673,7 @@ define_node!(PostfixFnCallOperatorNode(
         loop {
             tokens.start_transaction();
 
-            let expr = match non_terminal!(ExpressionNode, tokens, skip_eol!(tokens)) {
+            let expr = match non_terminal!(SpreadNode|ExpressionNode, tokens, skip_eol!(tokens)) {
                 Some(expr) => expr,
                 None => {
                     break;
@@ -491,7 +689,8 @@ define_node!(PostfixFnCallOperatorNode(
         }
 
         //  EXPR? ~ EOL* ~ ")"
-        if let Some(arg) = non_terminal!(ExpressionNode?, tokens, skip_eol!(tokens)) {
+        skip_eol!(tokens);
+        if let Some(arg) = non_terminal!(SpreadNode?, tokens).or_else(|| non_terminal!(ExpressionNode?, tokens)) {
             args.push(arg);
         }
 
@@ -517,3 +716,95 @@ define_node!(PostfixFnCallOperatorNode(
         }
     }
 });
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        compiler::HashString,
+        value::Value,
+        vm::error::{RuntimeError, RuntimeErrorType},
+        Error, Lavendeux,
+    };
+
+    // Aliases only live for the script they are defined in, so check the last line's value
+    fn run_last(lav: &mut Lavendeux, source: &str) -> Value {
+        let mut lines = lav.run(source).unwrap().cast_array().unwrap();
+        lines.pop().unwrap()
+    }
+
+    #[test]
+    fn test_alias() {
+        let mut lav = Lavendeux::new();
+
+        assert_eq!(
+            run_last(&mut lav, "alias sq(x) => x * x\nsq(3)"),
+            Value::integer(9)
+        );
+        assert_eq!(
+            run_last(&mut lav, "alias add(a, b) => a + b\nadd(2, 5)"),
+            Value::integer(7)
+        );
+        assert_eq!(
+            run_last(&mut lav, "alias two() => 2\ntwo() + 1"),
+            Value::integer(3)
+        );
+
+        // Arguments are substituted, so they are evaluated each time they are used
+        assert_eq!(
+            run_last(&mut lav, "alias sq(x) => x * x\ni = 0\nsq(i += 1)"),
+            Value::integer(2)
+        );
+
+        // The argument count must match
+        assert!(lav.run("alias sq(x) => x * x\nsq(1, 2)").is_err());
+
+        // Aliases cannot be used before they are defined
+        assert!(lav.run("cube(2)\nalias cube(x) => x * x * x").is_err());
+    }
+
+    #[test]
+    fn test_method_dispatch() {
+        let mut lav = Lavendeux::new();
+
+        // A global function taking a structured type can be called as a method on an instance
+        assert_eq!(
+            run_last(
+                &mut lav,
+                "type Point = {x: int, y: int}\n\
+                total(p: Point) = p.x + p.y\n\
+                p = {'x': 1, 'y': 2}\n\
+                p.total()"
+            ),
+            Value::integer(3)
+        );
+
+        // A function stored in the object shadows the global function with the same name
+        assert_eq!(
+            run_last(
+                &mut lav,
+                "greet(o) = 'global'\n\
+                own(o) = o.name\n\
+                o = {'name': 'own', 'greet': own}\n\
+                [o.greet(), greet(o), {}.greet()]"
+            ),
+            Value::Array(vec![
+                Value::string("own".to_string()),
+                Value::string("global".to_string()),
+                Value::string("global".to_string())
+            ])
+        );
+
+        // With neither, the method is reported as undefined
+        let error = lav.run("o = {'a': 1}\no.nothing()").unwrap_err();
+        assert!(
+            matches!(
+                &error,
+                Error::Runtime(RuntimeError {
+                    error: RuntimeErrorType::HashNotFound(hash, _),
+                    ..
+                }) if *hash == "nothing".hash_str()
+            ),
+            "{error:?}"
+        );
+    }
+}
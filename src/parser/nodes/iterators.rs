@@ -1,26 +1,53 @@
 use super::*;
 use crate::{
-    compiler::LoopCompilationExt,
-    lexer::{Rule, TokenSpan},
+    compiler::{CompilerError, LoopCompilationExt},
+    lexer::{Rule, Stack, TokenSpan},
     traits::{IntoOwned, SerializeToBytes},
+    value::Primitive,
     vm::OpCode,
 };
 
-define_node!(ContinueNode() {
+/// Parses an optional loop label, of the form `@ident`
+/// Returns the span of the label's name
+fn parse_loop_label(tokens: &mut Stack<'_>) -> Option<Option<TokenSpan>> {
+    match terminal!(Decorator?, tokens) {
+        Some(_) => {
+            let ident = non_terminal!(LiteralIdentNode, tokens)?;
+            Some(Some(ident.token().span()))
+        }
+        None => Some(None),
+    }
+}
+
+// continue (@ident)?
+define_node!(ContinueNode(label_span: Option<TokenSpan>) {
     "Continue statement - jumps to the next iteration of the current loop."
-    "`continue`"
+    "A label can be given to continue an outer loop instead."
+    "`continue (@ IDENTIFIER)?`"
 
     build(tokens) {
         tokens.start_transaction();
-        let token = terminal!(Continue, tokens)?;
+        let mut token = terminal!(Continue, tokens)?;
+        let label_span = parse_loop_label(tokens)?;
+        if let Some(span) = &label_span {
+            token.include_span(span.clone());
+        }
 
         tokens.apply_transaction();
-        Some(Self { token: token.child(Rule::ContinueExpr, token.span()) }.into_node())
+        Some(Self { label_span, token: token.child(Rule::ContinueExpr, token.span()) }.into_node())
     }
 
     compile(this, compiler) {
+        let label = this.label_span.map(|s| this.token.borrow_input()[s].to_string());
+        let token = this.token.clone().into_owned();
         compiler.push_token(this.token);
-        compiler.push_continue();
+
+        if !compiler.push_continue(label.as_deref()) {
+            return Err(match label {
+                Some(label) => CompilerError::UnknownLoopLabel(token, label),
+                None => CompilerError::NotInLoop(token),
+            });
+        }
         Ok(())
     }
 
@@ -30,37 +57,53 @@ define_node!(ContinueNode() {
 
     into_owned(this) {
         Self::Owned {
+            label_span: this.label_span,
             token: this.token.into_owned()
         }
     }
 });
 
-// break BLOCK?
-define_node!(BreakNode(value: Option<Node<'source>>) {
+// break (@ident)? BLOCK?
+define_node!(BreakNode(label_span: Option<TokenSpan>, value: Option<Node<'source>>) {
     "Break statement - jumps out of the current loop."
+    "A label can be given to break out of an outer loop instead."
     "Can optionally include a value to return for the current iteration."
-    "`break BLOCK?`"
+    "`break (@ IDENTIFIER)? BLOCK?`"
 
     build(tokens) {
         tokens.start_transaction();
 
         let mut token = terminal!(Break, tokens)?;
+        let label_span = parse_loop_label(tokens)?;
+        if let Some(span) = &label_span {
+            token.include_span(span.clone());
+        }
+
         let value = non_terminal!(BlockNode?, tokens);
         if let Some(value) = &value {
             token.include_span(value.token().span());
         }
 
         tokens.apply_transaction();
-        Some(Self { value, token: token.child(Rule::BreakExpr, token.span()) }.into_node())
+        Some(Self { label_span, value, token: token.child(Rule::BreakExpr, token.span()) }.into_node())
     }
 
     compile(this, compiler) {
+        let label = this.label_span.map(|s| this.token.borrow_input()[s].to_string());
+        let token = this.token.clone().into_owned();
         compiler.push_token(this.token);
+
+        let with_value = this.value.is_some();
         if let Some(value) = this.value {
             value.compile(compiler)?;
         }
 
-        compiler.push_break();
+        if !compiler.push_break(label.as_deref(), with_value) {
+            return Err(match label {
+                Some(label) => CompilerError::UnknownLoopLabel(token, label),
+                None => CompilerError::NotInLoop(token),
+            });
+        }
         Ok(())
     }
 
@@ -70,6 +113,7 @@ define_node!(BreakNode(value: Option<Node<'source>>) {
 
     into_owned(this) {
         Self::Owned {
+            label_span: this.label_span,
             value: this.value.map(|v| v.into_owned()),
             token: this.token.into_owned()
         }
@@ -77,14 +121,18 @@ define_node!(BreakNode(value: Option<Node<'source>>) {
 });
 
 define_node!(ForNode(
+    label_span: Option<TokenSpan>,
     name_span: Option<TokenSpan>,
     expr: Node<'source>,
     block: Node<'source>,
-    condition: Option<Node<'source>>
+    condition: Option<Node<'source>>,
+    discard: bool
 ) {
     "For loop - iterates over a range or collection."
-    "Optional iteration variable name and filter condition."
-    "`for (ident in )? EXPR do? BLOCK (where BLOCK)?`"
+    "Optional label, iteration variable name and filter condition."
+    "Evaluates to an array of the result of each iteration."
+    "If `discard` is given, results are not collected, and the loop evaluates to `nil`."
+    "`for (@ IDENTIFIER)? (IDENTIFIER in)? EXPR do? BLOCK (where BLOCK)? discard?`"
 
     build(tokens) {
         tokens.start_transaction();
@@ -92,6 +140,10 @@ define_node!(ForNode(
         let mut token = terminal!(For, tokens)?;
         token = token.child(Rule::ForExpr, token.span());
 
+        // Optional label, for break and continue
+        // (@ident)?
+        let label_span = parse_loop_label(tokens)?;
+
         // Optional iteration variable name
         // (ident in )?
         tokens.start_transaction();
@@ -130,86 +182,133 @@ define_node!(ForNode(
             None => None,
         };
 
+        // Optionally skip collecting the results
+        // discard?
+        let discard = match terminal!(Discard?, tokens) {
+            Some(t) => {
+                token.include_span(t.span());
+                true
+            },
+            None => false,
+        };
+
         tokens.apply_transaction();
-        Some(Self { name_span, expr, block, condition, token }.into_node())
+        Some(Self { label_span, name_span, expr, block, condition, discard, token }.into_node())
     }
 
     compile(this, compiler) {
-        let name = this.token.borrow_input();
-        let name = this.name_span.map(|s| name[s].to_string());
+        let input = this.token.borrow_input();
+        let name = this.name_span.map(|s| input[s].to_string());
+        let label = this.label_span.map(|s| input[s].to_string());
 
         compiler.push_token(this.token);
 
-        // An array to hold the result
-        compiler.push(OpCode::MKAR);
-        compiler.push_u64(0);
+        // The outer scope holds the iteration variable
+        compiler.push_scope_in();
+
+        // An array to hold the result, or nil if we are discarding them
+        if this.discard {
+            compiler.push(OpCode::PUSH);
+            compiler.extend(Primitive::Nil.serialize_into_bytes());
+        } else {
+            compiler.push(OpCode::MKAR);
+            compiler.push_u64(0);
+        }
 
-        // Compile the iteration variable
+        // Compile the iterable, and swap it under the result
+        // Between iterations, the stack is always [iterable, result]
         this.expr.compile(compiler)?;
+        compiler.push(OpCode::SWP);
 
-        // Enter the loop block
-        compiler.start_loop();
-        compiler.push(OpCode::SCI);
+        // Start of each iteration - continue jumps here
+        let start = compiler.len();
+        compiler.start_loop(label);
 
-        // Stack here is [result, iterable]
-        // End the loop if the iterable is empty
+        // Swap the iterable back to the top of the stack
+        // Then end the loop if the iterable is empty
         // We need to duplicate here, sadly
+        compiler.push(OpCode::SWP);
         compiler.push(OpCode::DUP);
         compiler.push(OpCode::JMPNE);
-        let jump_skp = compiler.push_u64(0);
-        compiler.push_break();
-        let pos = compiler.len() as u64;
-        compiler.replace(jump_skp, pos.serialize_into_bytes());
-
+        let jump_skp = compiler.push_jump_placeholder();
+        compiler.push(OpCode::JMP);
+        let jump_end = compiler.push_jump_placeholder();
+        compiler.patch_jump(jump_skp, compiler.len());
 
         // Stack here is [result, iterable]
         // This turns into [result, iterable, value]
         compiler.push(OpCode::NEXT);
 
         // If the name is provided, set the reference
+        // Otherwise we can just drop the value
         if let Some(name) = name {
             compiler.push(OpCode::REF);
             compiler.push_strhash(&name);
             compiler.push(OpCode::WREF);
-            compiler.push(OpCode::POP);
         }
+        compiler.push(OpCode::POP);
+
+        // Stack here is [result, iterable]
+        // Swap them back, and enter the loop body
+        compiler.push(OpCode::SWP);
+        compiler.push_scope_in();
 
-        // Stack here is [result, iterable, value]
-        // So we can consume the value to check the filter
+        // Filtered out values skip to the next iteration
         if let Some(condition) = this.condition {
             condition.compile(compiler)?;
             compiler.push(OpCode::JMPT);
-            let shortjmp = compiler.push_u64(0);
-            compiler.push_break();
+            let shortjmp = compiler.push_jump_placeholder();
+            compiler.push_continue(None);
 
-            compiler.replace(shortjmp, (compiler.len() as u64).serialize_into_bytes());
+            compiler.patch_jump(shortjmp, compiler.len());
         }
 
-        // Stack here is [result, iterable]
-        // Swap them for the next step
-        compiler.push(OpCode::SWP);
-
-        // Now we have [iterable, result]
         // Compile the loop block
         this.block.compile(compiler)?;
+        compiler.push_scope_out();
 
         // Stack now contains [iterable, result, last_result]
-        // Resolve the last value and add it to the result array
-        // We then swap the iterable back to the top of the stack
-        compiler.push(OpCode::PSAR);
-        compiler.push(OpCode::SWP);
+        // Add the last value to the result array, or drop it
+        if this.discard {
+            compiler.push(OpCode::POP);
+        } else {
+            compiler.push(OpCode::PSAR);
+        }
+
+        // Next iteration
+        compiler.push(OpCode::JMP);
+        compiler.push_jump_to(start);
+
+        // Breaks land here with [iterable, result, value]
+        // The value is only kept if one was given to break
+        let (breaks, value_breaks) = compiler.end_loop();
+        let pos = compiler.len();
+        for target in breaks {
+            compiler.patch_jump(target, pos);
+        }
+        compiler.push(OpCode::POP);
+        compiler.push(OpCode::JMP);
+        let jump_done = compiler.push_jump_placeholder();
 
-        // Continue the loop, remembering to reset the scope
-        compiler.push(OpCode::SCO);
-        compiler.push_continue();
+        let pos = compiler.len();
+        for target in value_breaks {
+            compiler.patch_jump(target, pos);
+        }
+        if this.discard {
+            compiler.push(OpCode::POP);
+        } else {
+            compiler.push(OpCode::PSAR);
+        }
 
-        // End the loop
-        compiler.end_loop();
-        compiler.push(OpCode::SCO);
+        // Stack here is [iterable, result]
+        compiler.patch_jump(jump_done, compiler.len());
+        compiler.push(OpCode::SWP);
 
-        // Stack here is [result, empty]
-        // We need to pop the empty array
+        // Stack here is [result, iterable]
+        // We need to pop the iterable, and leave the outer scope
+        compiler.patch_jump(jump_end, compiler.len());
         compiler.push(OpCode::POP);
+        compiler.push_scope_out();
 
         // Stack here is [result]
         Ok(())
@@ -221,11 +320,98 @@ define_node!(ForNode(
 
     into_owned(this) {
         Self::Owned {
+            label_span: this.label_span,
             name_span: this.name_span,
             expr: this.expr.into_owned(),
             block: this.block.into_owned(),
             condition: this.condition.map(|c| c.into_owned()),
+            discard: this.discard,
             token: this.token.into_owned()
         }
     }
 });
+
+#[cfg(test)]
+mod test {
+    use crate::{value::Value, vm::VirtualMachine, Lavendeux};
+
+    #[test]
+    fn test_where_and_discard() {
+        let mut lav = Lavendeux::new();
+
+        // Filtered out iterations are skipped, not collected as nil
+        let result = lav.run("for i in 0..5 do i where i % 2 == 0").unwrap();
+        assert_eq!(result, lav.run("[0, 2, 4]").unwrap());
+
+        // Discarded results are not collected at all
+        assert_eq!(lav.run("for i in 0..5 do i discard").unwrap(), Value::nil());
+        assert_eq!(
+            lav.run("for i in 0..5 do i where i > 1 discard").unwrap(),
+            Value::nil()
+        );
+
+        // But the body still runs
+        lav.run("n = 0\nfor i in 0..4 do n += i where i != 2 discard")
+            .unwrap();
+        assert_eq!(lav.run("n").unwrap(), Value::integer(4));
+    }
+
+    #[test]
+    fn test_loop_labels() {
+        let mut lav = Lavendeux::new();
+
+        // Breaking out of the outer loop keeps the results collected before it
+        let result = lav
+            .run("for @outer i in 0..3 do { for j in 0..3 do { if i == 1 then break @outer else j } }")
+            .unwrap();
+        assert_eq!(result, lav.run("[[0, 1, 2]]").unwrap());
+
+        // Continuing the outer loop skips the rest of the inner one
+        lav.run("n = 0\nfor @outer i in 0..3 do { for j in 0..3 do { if j == 1 then continue @outer else nil; n += 1 } } discard")
+            .unwrap();
+        assert_eq!(lav.run("n").unwrap(), Value::integer(3));
+
+        // Unlabelled break and continue still apply to the innermost loop
+        let result = lav
+            .run("for @outer i in 0..2 do { for j in 0..3 do { if j == 1 then continue else j } }")
+            .unwrap();
+        assert_eq!(result, lav.run("[[0, 2], [0, 2]]").unwrap());
+
+        // Labels must belong to an enclosing loop
+        assert!(lav.run("for i in 0..3 do { break @nope }").is_err());
+        assert!(lav
+            .run("for @a i in 0..3 do i\nfor j in 0..3 do { continue @a }")
+            .is_err());
+    }
+
+    #[test]
+    fn test_loop_exit_scopes() {
+        // Every scope entered by a loop body is left again when it is exited early
+        let run = |source: &str| {
+            let (profile, bytecode) = Lavendeux::new().compile(source).unwrap();
+            let mut vm = VirtualMachine::new();
+            let result = vm.run(bytecode, Some(profile)).unwrap();
+            (result, vm.destroy().scope_depth())
+        };
+
+        let (result, depth) =
+            run("for @o i in 0..3 do { for j in 0..3 do { if j == 1 then break @o } }");
+        assert_eq!(result, Value::Array(vec![]));
+        assert_eq!(depth, 0);
+
+        let (result, depth) =
+            run("for @o i in 0..3 do { for j in 0..3 do { if i == 1 then continue @o else j } }");
+        assert_eq!(result.to_string(), "[[0, 1, 2], [0, 1, 2]]");
+        assert_eq!(depth, 0);
+
+        // Including a file enters a scope of its own
+        let path = std::env::temp_dir().join(format!("lav_break_{}.lav", std::process::id()));
+        std::fs::write(&path, "break").unwrap();
+        let (result, depth) = run(&format!(
+            "for i in 0..3 do {{ if i == 1 then include({path:?}) else i }}"
+        ));
+        std::fs::remove_file(&path).ok();
+        assert_eq!(result.to_string(), "[0]");
+        assert_eq!(depth, 0);
+    }
+}
@@ -22,16 +22,28 @@ node_silent!(LiteralRegexNode {
 
 define_node!(LiteralStringNode(value: Primitive) {
     "String literal - a sequence of characters enclosed in double or single quotes."
-    "Supports escape sequences for quotes, backslashes, and \\n \\r \\t, plus unicode escapes like \\u{1234}"
+    "Supports escape sequences for quotes, backslashes, \\n \\r \\t \\0, characters like \\x7F, and unicode escapes like \\u{1F600} or \\u00E9"
+    "Bytes literals are written in hex or base64, e.g. `0x\"deadbeef\"` or `b\"3q2+7w==\"`"
 
     build(tokens) {
         tokens.start_transaction();
-        let token = terminal!(LiteralString, tokens)?;
+        let token = terminal!(LiteralString|LiteralBytes, tokens)?;
         tokens.apply_transaction();
 
-        match literals::string(token.slice()) {
-            Ok(value) => Some(Self { token, value: Primitive::String(value) }.into_node()),
-            Err(e) => error_node!(ParserError::InvalidLiteral(token.into_owned(), e)),
+        let value = match token.rule() {
+            Rule::LiteralBytes => literals::bytes(token.slice()).map(Primitive::Bytes),
+            _ => literals::string(token.slice()).map(Primitive::String),
+        };
+        match value {
+            Ok(value) => Some(Self { token, value }.into_node()),
+            Err(e) => {
+                // Point at the part of the literal that was invalid, such as a single escape
+                let token = match e.span() {
+                    Some(span) => token.subtoken(span),
+                    None => token,
+                };
+                error_node!(ParserError::InvalidLiteral(token.into_owned(), e))
+            }
         }
     }
 
@@ -125,10 +137,18 @@ define_node!(LiteralFloatNode(value: Primitive) {
         let token = terminal!(LiteralFloat|LiteralPrefixedCurrency|LiteralSuffixedCurrency, tokens)?;
         tokens.apply_transaction();
 
+        // Currency symbols can be more than one byte long, like €
+        let slice = token.slice();
         let (value, symbol) = match token.rule() {
-            Rule::LiteralFloat => (token.slice(), None),
-            Rule::LiteralPrefixedCurrency => (&token.slice()[1..], token.slice().chars().next().map(|c| NumberSymbol::Prefix(c.to_string()))),
-            Rule::LiteralSuffixedCurrency => (&token.slice()[..token.slice().len()-1], token.slice().chars().last().map(|c| NumberSymbol::Suffix(c.to_string()))),
+            Rule::LiteralFloat => (slice, None),
+            Rule::LiteralPrefixedCurrency => {
+                let c = slice.chars().next().unwrap();
+                (&slice[c.len_utf8()..], Some(NumberSymbol::Prefix(c.to_string())))
+            },
+            Rule::LiteralSuffixedCurrency => {
+                let c = slice.chars().last().unwrap();
+                (&slice[..slice.len() - c.len_utf8()], Some(NumberSymbol::Suffix(c.to_string())))
+            },
             _ => unreachable!("Invalid float rule: {:?}", token.rule())
         };
         let value = match literals::decimal(value) {
@@ -164,17 +184,18 @@ define_node!(LiteralFloatNode(value: Primitive) {
 });
 
 define_node!(LiteralBoolNode(value: Primitive) {
-    "Boolean literal - either `true` or `false`"
+    "Boolean literal - either `true`, `false`, or `nil`"
 
     build(tokens) {
         tokens.start_transaction();
         let token = terminal!(LiteralConstFalse|LiteralConstTrue|LiteralConstNil, tokens)?;
         let value = match token.rule() {
-            Rule::LiteralConstTrue => true,
-            _ => false,
+            Rule::LiteralConstTrue => Primitive::Boolean(true),
+            Rule::LiteralConstNil => Primitive::Nil,
+            _ => Primitive::Boolean(false),
         };
         tokens.apply_transaction();
-        Some(Node::LiteralBool(Box::new(Self { token, value: Primitive::Boolean(value) })))
+        Some(Node::LiteralBool(Box::new(Self { token, value })))
     }
 
     // PUSH BOOL <bytes>
@@ -213,7 +234,7 @@ define_node!(LiteralIdentNode() {
     }
 
     compile(this, compiler) {
-        let name = this.token.slice().to_string();
+        let name = compiler.resolve_ident(this.token.slice()).to_string();
         compiler.push_token(this.token);
         compiler.push(OpCode::REF);
         compiler.push_strhash(&name);
@@ -248,7 +269,8 @@ node_silent!(LiteralConstNode {
             Rule::LiteralConstPi => Some(LiteralFloatNode { token, value: Primitive::Decimal(Number::pi()) }.into_node()),
             Rule::LiteralConstE => Some(LiteralFloatNode { token, value: Primitive::Decimal(Number::e()) }.into_node()),
             Rule::LiteralConstTau => Some(LiteralFloatNode { token, value: Primitive::Decimal(Number::tau()) }.into_node()),
-            Rule::LiteralConstNil|Rule::LiteralConstFalse => Some(LiteralBoolNode { token, value: Primitive::Boolean(false.into()) }.into_node()),
+            Rule::LiteralConstNil => Some(LiteralBoolNode { token, value: Primitive::Nil }.into_node()),
+            Rule::LiteralConstFalse => Some(LiteralBoolNode { token, value: Primitive::Boolean(false.into()) }.into_node()),
             Rule::LiteralConstTrue => Some(LiteralBoolNode { token, value: Primitive::Boolean(true.into()) }.into_node()),
 
             _ => unreachable!("Invalid constant rule: {:?}", token.rule())
@@ -0,0 +1,74 @@
+use super::CustomOperator;
+use crate::lexer::Rule;
+
+/// The default maximum nesting depth for the parser
+/// Deeper input fails with `ParserError::TooDeep` instead of overflowing the call stack
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// Limits applied while parsing
+/// Input exceeding these limits results in an error instead of
+/// exhausting the call stack or memory
+#[derive(Debug, Clone)]
+pub struct ParserOptions {
+    /// Maximum nesting depth of the syntax tree
+    pub max_depth: usize,
+
+    /// Maximum number of tokens in the input
+    pub max_tokens: usize,
+
+    /// Maximum number of terms in a single expression
+    pub max_expr_terms: usize,
+
+    /// Operators the parser rejects, by their rule in `precedence_table`
+    /// Such as `Rule::Assign`, to evaluate expressions without side effects
+    pub disabled_operators: Vec<Rule>,
+
+    /// Infix operators added to the language, each compiled as a call to a function
+    /// The lexer must be given the same operators, with `Lexer::with_custom_operators`
+    pub custom_operators: Vec<CustomOperator>,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_tokens: usize::MAX,
+            max_expr_terms: usize::MAX,
+            disabled_operators: vec![],
+            custom_operators: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        lexer::{Lexer, Stack},
+        parser::{build_ast, ParserError},
+    };
+
+    #[test]
+    fn test_parser_limits() {
+        let options = ParserOptions {
+            max_tokens: 10,
+            ..Default::default()
+        };
+        let tokens = Lexer::new("1+2+3+4+5+6")
+            .all_tokens()
+            .expect("Could not lex");
+        let result = build_ast(Stack::new(tokens), options);
+        assert!(matches!(result, Err(ParserError::TooManyTokens(_, 10))));
+
+        let options = ParserOptions {
+            max_expr_terms: 3,
+            ..Default::default()
+        };
+        let tokens = Lexer::new("1+2+3+4").all_tokens().expect("Could not lex");
+        let result = build_ast(Stack::new(tokens), options.clone());
+        assert!(matches!(result, Err(ParserError::TooManyTerms(_, 3))));
+
+        let tokens = Lexer::new("1+2+3").all_tokens().expect("Could not lex");
+        assert!(build_ast(Stack::new(tokens), options).is_ok());
+    }
+}
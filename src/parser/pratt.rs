@@ -1,14 +1,14 @@
 use crate::{
-    lexer::Rule,
+    lexer::{Rule, Stack},
     parser::{
         arithmetic::*,
         assignment::*,
         bitwise::*,
         boolean::*,
         collections::{IndexingExprNode, RangeExprNode},
-        conditionals::TernaryExprNode,
-        core::{CastExprNode, DecoratorExprNode},
-        functions::FnCallNode,
+        conditionals::{NilCoalesceExprNode, TernaryExprNode},
+        core::{CastExprNode, DecoratorExprNode, IsExprNode},
+        functions::{CustomInfixExprNode, FnCallNode},
         Node,
     },
 };
@@ -19,7 +19,24 @@ use crate::{
 /// Where a higher binding power means a higher precedence
 ///
 /// It will attempt to create an AST from the expression
+/// Each level of recursion counts towards the parser's depth limit
 pub fn fold_expression<'source>(
+    tokens: &mut Stack<'source>,
+    expr: &mut Vec<Node<'source>>,
+    min_bp: u8,
+) -> Option<Node<'source>> {
+    if !tokens.enter_nt("fold_expression") {
+        return None;
+    }
+
+    let result = fold_operands(tokens, expr, min_bp);
+    tokens.exit_nt("fold_expression", result.is_some());
+    result
+}
+
+/// The body of `fold_expression`, run once its depth has been checked
+fn fold_operands<'source>(
+    tokens: &mut Stack<'source>,
     expr: &mut Vec<Node<'source>>,
     min_bp: u8,
 ) -> Option<Node<'source>> {
@@ -31,10 +48,10 @@ pub fn fold_expression<'source>(
     };
 
     // Check if the left hand side is a prefix operator
-    match priority_of(lhs.token().rule()) {
+    match binding_power_of(&lhs) {
         Some((left_bp, 0)) if left_bp > 0 => {
             // Prefix
-            let rhs = fold_expression(expr, left_bp)?;
+            let rhs = fold_expression(tokens, expr, left_bp)?;
             lhs = build_pratt_unary(rhs, lhs)?;
         }
 
@@ -54,7 +71,7 @@ pub fn fold_expression<'source>(
 
         // Get the binding power of the operator
         // Check if it's a postfix operator
-        let (left_bp, right_bp) = priority_of(op_rule)
+        let (left_bp, right_bp) = binding_power_of(op)
             .or_else(|| unreachable!("Unregistered operator: {:?}", op_rule))?;
         match (left_bp, right_bp) {
             (0, right_bp) => {
@@ -79,7 +96,7 @@ pub fn fold_expression<'source>(
 
         // Build the expression
         let op = expr.pop().unwrap();
-        let rhs = fold_expression(expr, right_bp)?;
+        let rhs = fold_expression(tokens, expr, right_bp)?;
         lhs = build_pratt_binary(lhs, op, rhs)?;
     }
 
@@ -102,7 +119,9 @@ pub fn build_pratt_binary<'source>(
         Add | Sub | Mul | Div | Mod | Pow => ArithmeticInfixExprNode::parse(token, lhs, op, rhs),
 
         SL | SR | BitwiseOr | BitwiseAnd | Xor => BitwiseInfixExprNode::parse(token, lhs, op, rhs),
-        Eq | Ne | SEq | SNe | Lt | Gt | Le | Ge => ComparisonExprNode::parse(token, lhs, op, rhs),
+        Eq | Ne | SEq | SNe | IEq | Lt | Gt | Le | Ge => {
+            ComparisonExprNode::parse(token, lhs, op, rhs)
+        }
         LogicalAnd | LogicalOr => LogicalExprNode::parse(token, lhs, op, rhs),
 
         Matches | Contains | StartsWith | EndsWith => MatchExprNode::parse(token, lhs, op, rhs),
@@ -118,9 +137,12 @@ pub fn build_pratt_binary<'source>(
         }
 
         TernaryOperator => TernaryExprNode::parse(token, lhs, op, rhs),
-        DecoratorOperator => DecoratorExprNode::parse(token, lhs, op, rhs),
+        NilCoalesce => NilCoalesceExprNode::parse(token, lhs, op, rhs),
 
         As => CastExprNode::parse(token, lhs, op, rhs),
+        Is => IsExprNode::parse(token, lhs, op, rhs),
+
+        CustomOperator => CustomInfixExprNode::parse(token, lhs, op, rhs),
 
         _ => unreachable!("Unregistered operator: {:?}", op.token().rule()),
     }
@@ -141,99 +163,261 @@ pub fn build_pratt_unary<'source>(term: Node<'source>, op: Node<'source>) -> Opt
 
         FnCallOperator => FnCallNode::parse(token, term, op),
         IndexingOperator => IndexingExprNode::parse(token, term, op),
+        DecoratorOperator => DecoratorExprNode::parse(token, term, op),
 
         _ => unreachable!("Unregistered operator: {:?}", op.token().rule()),
     }
 }
 
-/// Get the binding power of a rule
-/// Higher binding power means higher precedence
-/// Returns None if the rule is not in the map
-/// The tuple is (left, right) binding power
-fn priority_of(rule: Rule) -> Option<(u8, u8)> {
-    PRATT_PRIORITY[rule as usize]
+/// How an operator binds to its operands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// Infix, grouping from the left; `a - b - c` is `(a - b) - c`
+    Left,
+
+    /// Infix, grouping from the right
+    Right,
+
+    /// Unary, before its operand
+    Prefix,
+
+    /// Unary, after its operand
+    Postfix,
 }
 
-fn generate_pratttable() -> Vec<Option<(u8, u8)>> {
-    struct Precedence(u8);
-    impl Precedence {
-        fn lhs(&mut self) -> (u8, u8) {
-            (self.0, self.0 + 1)
-        }
+impl std::fmt::Display for Associativity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Prefix => "prefix",
+            Self::Postfix => "postfix",
+        })
+    }
+}
 
-        fn rhs(&mut self) -> (u8, u8) {
-            (self.0, self.0 + 1)
-        }
+/// An entry in the operator precedence table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperatorPrecedence {
+    /// The rule of the operator, as seen by the parser
+    /// Some operators are renamed by the parser, such as `-` as a prefix, which is `Rule::PrefixNeg`
+    pub rule: Rule,
+
+    /// The precedence level; operators with a higher level bind more tightly
+    pub level: u8,
 
-        fn prefix(&mut self) -> (u8, u8) {
-            (self.0 + 1, 0)
+    /// How the operator binds to its operands
+    pub associativity: Associativity,
+}
+
+impl OperatorPrecedence {
+    /// The operator as it is written in a script
+    pub fn symbol(&self) -> String {
+        match self.rule {
+            Rule::PrefixNeg => "-".to_string(),
+            Rule::TernaryOperator => "? :".to_string(),
+            Rule::DecoratorOperator => "@".to_string(),
+            Rule::FnCallOperator => "()".to_string(),
+            Rule::IndexingOperator => "[]".to_string(),
+            rule => rule.to_string(),
         }
+    }
 
-        fn postfix(&mut self) -> (u8, u8) {
-            (0, self.0 + 1)
+    /// The (left, right) binding power of the operator, as used by `fold_expression`
+    fn binding_power(&self) -> (u8, u8) {
+        match self.associativity {
+            Associativity::Left | Associativity::Right => (self.level, self.level + 1),
+            Associativity::Prefix => (self.level + 1, 0),
+            Associativity::Postfix => (0, self.level + 1),
         }
+    }
+}
 
-        fn advance(&mut self) {
-            self.0 += 1;
+/// An infix operator added to the language by the host, with `ParserOptions::custom_operators`
+/// `a <symbol> b` is compiled as a call to `handler(a, b)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomOperator {
+    /// The operator as it is written in a script, such as `<>`
+    pub symbol: String,
+
+    /// The precedence level, from `precedence_table`
+    /// The operator binds like the built-in operators at that level, and groups from the left
+    pub level: u8,
+
+    /// The name of the function the operator calls
+    pub handler: String,
+}
+
+impl CustomOperator {
+    /// Create a new custom operator
+    pub fn new(symbol: impl Into<String>, level: u8, handler: impl Into<String>) -> Self {
+        Self {
+            symbol: symbol.into(),
+            level,
+            handler: handler.into(),
         }
     }
 
+    /// The (left, right) binding power of the operator, as used by `fold_expression`
+    /// Levels are clamped so that the operator is never mistaken for a prefix or postfix one
+    fn binding_power(&self) -> (u8, u8) {
+        let level = self.level.clamp(1, u8::MAX - 1);
+        (level, level + 1)
+    }
+}
+
+/// Get the operator precedence table, from lowest to highest precedence
+pub fn precedence_table() -> &'static [OperatorPrecedence] {
+    &PRECEDENCE_TABLE
+}
+
+/// Get the precedence of an operator
+/// Returns None if the rule is not an operator
+pub fn precedence_of(rule: Rule) -> Option<OperatorPrecedence> {
+    PRECEDENCE_TABLE.iter().find(|op| op.rule == rule).copied()
+}
+
+/// Get the binding power of an operator node
+/// Custom operators carry their own precedence; the rest are looked up by rule
+fn binding_power_of(node: &Node<'_>) -> Option<(u8, u8)> {
+    match node {
+        Node::InfixOperator(op) => match &op.custom {
+            Some(custom) => Some(custom.binding_power()),
+            None => priority_of(op.token.rule()),
+        },
+        _ => priority_of(node.token().rule()),
+    }
+}
+
+/// Get the binding power of a rule
+/// Higher binding power means higher precedence
+/// Returns None if the rule is not in the map
+/// The tuple is (left, right) binding power
+fn priority_of(rule: Rule) -> Option<(u8, u8)> {
+    PRATT_PRIORITY[rule as usize]
+}
+
+fn generate_precedence_table() -> Vec<OperatorPrecedence> {
     macro_rules! bind {
-        ($table:ident, $precedence:ident :: Right => $($rule:ident)|+) => {
-            $( $table[Rule::$rule as usize] = Some($precedence.rhs()); )+
-            $precedence.advance();
-        };
-        ($table:ident, $precedence:ident :: Left => $($rule:ident)|+) => {
-            $( $table[Rule::$rule as usize] = Some($precedence.lhs()); )+
-            $precedence.advance();
-        };
-        ($table:ident, $precedence:ident :: Prefix => $($rule:ident)|+) => {
-            $( $table[Rule::$rule as usize] = Some($precedence.prefix()); )+
-            $precedence.advance();
-        };
-        ($table:ident, $precedence:ident :: Postfix => $($rule:ident)|+) => {
-            $( $table[Rule::$rule as usize] = Some($precedence.postfix()); )+
-            $precedence.advance();
+        ($table:ident, $associativity:ident => $($rule:ident)|+) => {
+            let level = $table.last().map_or(1, |op: &OperatorPrecedence| op.level + 1);
+            $( $table.push(OperatorPrecedence {
+                rule: Rule::$rule,
+                level,
+                associativity: Associativity::$associativity,
+            }); )+
         };
     }
 
-    let mut precedence = Precedence(1);
-    let mut table = Vec::with_capacity(Rule::Error as usize);
-    table.resize(Rule::Error as usize, None);
+    let mut table = vec![];
 
-    bind!(table, precedence::Right => Assign|AssignAdd|AssignSub|AssignPow|AssignMod|AssignMul|AssignDiv|AssignAnd|AssignOr|AssignXor|AssignSL|AssignSR);
+    bind!(table, Right => Assign|AssignAdd|AssignSub|AssignPow|AssignMod|AssignMul|AssignDiv|AssignAnd|AssignOr|AssignXor|AssignSL|AssignSR);
 
-    bind!(table, precedence::Prefix => Delete);
-    bind!(table, precedence::Right => Range);
-    bind!(table, precedence::Right => TernaryOperator);
-    bind!(table, precedence::Postfix => DecoratorOperator);
+    bind!(table, Prefix => Delete);
+    bind!(table, Right => Range);
+    bind!(table, Right => TernaryOperator);
+    bind!(table, Postfix => DecoratorOperator);
+    bind!(table, Left => NilCoalesce);
 
-    bind!(table, precedence::Left => LogicalOr);
-    bind!(table, precedence::Left => LogicalAnd);
+    bind!(table, Left => LogicalOr);
+    bind!(table, Left => LogicalAnd);
 
-    bind!(table, precedence::Left => Matches|Contains|StartsWith|EndsWith);
+    bind!(table, Left => Matches|Contains|StartsWith|EndsWith|Is);
 
-    bind!(table, precedence::Left => BitwiseOr);
-    bind!(table, precedence::Left => Xor);
-    bind!(table, precedence::Left => BitwiseAnd);
+    bind!(table, Left => BitwiseOr);
+    bind!(table, Left => Xor);
+    bind!(table, Left => BitwiseAnd);
 
-    bind!(table, precedence::Left => Eq|Ne|SEq|SNe);
-    bind!(table, precedence::Left => Lt|Gt|Le|Ge);
+    bind!(table, Left => Eq|Ne|SEq|SNe|IEq);
+    bind!(table, Left => Lt|Gt|Le|Ge);
 
-    bind!(table, precedence::Left => SL|SR);
+    bind!(table, Left => SL|SR);
 
-    bind!(table, precedence::Left => Add|Sub);
-    bind!(table, precedence::Left => Mul|Div|Mod);
-    bind!(table, precedence::Right => Pow);
+    bind!(table, Left => Add|Sub);
+    bind!(table, Left => Mul|Div|Mod);
+    bind!(table, Right => Pow);
 
-    bind!(table, precedence::Prefix => PrefixNeg|BitwiseNot|LogicalNot);
-    bind!(table, precedence::Postfix => FnCallOperator|IndexingOperator);
+    bind!(table, Prefix => PrefixNeg|BitwiseNot|LogicalNot);
+    bind!(table, Postfix => FnCallOperator|IndexingOperator);
 
-    bind!(table, precedence::Right => As);
+    bind!(table, Right => As);
+
+    table
+}
+
+fn generate_pratttable() -> Vec<Option<(u8, u8)>> {
+    let mut table = Vec::with_capacity(Rule::Error as usize);
+    table.resize(Rule::Error as usize, None);
+
+    for op in PRECEDENCE_TABLE.iter() {
+        table[op.rule as usize] = Some(op.binding_power());
+    }
 
     table
 }
 
 lazy_static::lazy_static! {
+    static ref PRECEDENCE_TABLE: Vec<OperatorPrecedence> = generate_precedence_table();
     pub static ref PRATT_PRIORITY: Vec<Option<(u8, u8)>> = generate_pratttable();
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        lexer::Lexer,
+        parser::{build_ast, CustomOperator, ParserError, ParserOptions},
+        value::Value,
+        Lavendeux,
+    };
+
+    #[test]
+    fn test_disabled_operators() {
+        let options = ParserOptions {
+            disabled_operators: vec![Rule::Assign, Rule::PrefixNeg],
+            ..Default::default()
+        };
+        for input in ["a = 1", "1 + -2", "[1, a = 2]"] {
+            let tokens = Lexer::new(input).all_tokens().expect("Could not lex");
+            let result = build_ast(Stack::new(tokens), options.clone());
+            assert!(
+                matches!(result, Err(ParserError::DisabledOperator(..))),
+                "{input}"
+            );
+        }
+
+        let tokens = Lexer::new("a == 1 - 2")
+            .all_tokens()
+            .expect("Could not lex");
+        assert!(build_ast(Stack::new(tokens), options).is_ok());
+
+        let table = precedence_table();
+        assert!(table.windows(2).all(|w| w[0].level <= w[1].level));
+        let mul = precedence_of(Rule::Mul).unwrap();
+        let add = precedence_of(Rule::Add).unwrap();
+        assert!(mul.level > add.level);
+        assert_eq!(add.associativity, Associativity::Left);
+    }
+
+    #[test]
+    fn test_custom_operators() {
+        let mut lav = Lavendeux::new();
+        lav.set_parser_options(ParserOptions {
+            custom_operators: vec![
+                CustomOperator::new("<>", 14, "cmp"),
+                CustomOperator::new("÷", 17, "div"),
+            ],
+            ..Default::default()
+        });
+
+        let result = lav.run("cmp(a, b) = a < b ? -1 : (a > b ? 1 : 0)\n1 + 2 <> 4");
+        assert_eq!(result.unwrap(), Value::integer(-1));
+
+        let result = lav.run("div(a, b) = a / b\n1 + 10 ÷ 2 ÷ 5");
+        assert_eq!(result.unwrap(), Value::integer(2));
+
+        // Unregistered symbols are still lexed as usual
+        let tokens = Lexer::new("a <> b").all_tokens().expect("Could not lex");
+        assert!(tokens.iter().all(|t| t.rule() != Rule::CustomOperator));
+    }
+}
@@ -1,6 +1,6 @@
 use super::{core::ScriptNode, Node, ParserNode};
 use crate::{
-    compiler::{asm_transcoder::ASMTranscoder, Compiler, CompilerError},
+    compiler::{asm_transcoder::ASMTranscoder, Compiler, CompilerError, LoopCompilationExt},
     lexer::{Lexer, Stack, Token},
     traits::{IntoOwned, SerializeToBytes},
     value::Primitive,
@@ -14,7 +14,7 @@ pub fn __syscalld<'source>(
     op: OpCode,
     args: Vec<Node<'source>>,
 ) -> Result<(), CompilerError> {
-    for node in args.into_iter().rev() {
+    for node in args.into_iter() {
         node.compile(compiler)?;
     }
     compiler.push(op);
@@ -74,14 +74,14 @@ pub fn __include<'source>(
         })?;
 
     // Create the locked scope for the included file
-    compiler.push(OpCode::SCI);
+    compiler.push_scope_in();
     compiler.push(OpCode::SCL);
 
     // Compile the file
     ast.compile(compiler)?;
 
     // Pop the locked scope
-    compiler.push(OpCode::SCO);
+    compiler.push_scope_out();
 
     Ok(())
 }
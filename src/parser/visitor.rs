@@ -0,0 +1,273 @@
+//! Traversal of the AST
+//! `Visitor` and `VisitorMut` walk a tree of `Node`s, so that analysis passes,
+//! lints and rewrites do not need to match every variant themselves
+//!
+//! ```rust
+//! use lavendeux_parser::{lexer::{Lexer, Stack}, parser::{build_ast, Node, Visitor}};
+//!
+//! /// Counts the function calls in a script
+//! struct CallCounter(usize);
+//! impl<'source> Visitor<'source> for CallCounter {
+//!     fn enter(&mut self, node: &Node<'source>) -> bool {
+//!         if let Node::FnCall(_) = node {
+//!             self.0 += 1;
+//!         }
+//!         true
+//!     }
+//! }
+//!
+//! let tokens = Lexer::new("sqrt(abs(-4)) + 1").all_tokens().unwrap();
+//! let ast = build_ast(Stack::new(tokens), Default::default()).unwrap();
+//!
+//! let mut counter = CallCounter(0);
+//! ast.walk(&mut counter);
+//! assert_eq!(counter.0, 2);
+//! ```
+use super::Node;
+
+/// Walks an AST by reference
+/// Both methods do nothing by default; implement the ones you need
+pub trait Visitor<'source> {
+    /// Called when a node is reached, before its children
+    /// Return `false` to skip the node's children
+    fn enter(&mut self, node: &Node<'source>) -> bool {
+        let _ = node;
+        true
+    }
+
+    /// Called after the node's children have been visited
+    fn exit(&mut self, node: &Node<'source>) {
+        let _ = node;
+    }
+}
+
+/// Walks an AST by mutable reference, allowing nodes to be rewritten
+/// A node can be replaced outright by assigning to it; replacing a node in `exit`
+/// rewrites the tree from the bottom up, while `enter` rewrites it from the top down
+pub trait VisitorMut<'source> {
+    /// Called when a node is reached, before its children
+    /// Return `false` to skip the node's children
+    fn enter(&mut self, node: &mut Node<'source>) -> bool {
+        let _ = node;
+        true
+    }
+
+    /// Called after the node's children have been visited
+    fn exit(&mut self, node: &mut Node<'source>) {
+        let _ = node;
+    }
+}
+
+/// Collect references to the children of a node, in source order
+/// Pass `mut` to collect mutable references
+macro_rules! children {
+    ($node:expr $(, $mut:tt)?) => {{
+        let mut children = vec![];
+        match $node {
+            Node::Error(_)
+            | Node::PrefixOperator(_)
+            | Node::PostfixDecoratorOperator(_)
+            | Node::Continue(_)
+            | Node::TypeDef(_)
+            | Node::EnumDef(_)
+            | Node::LiteralString(_)
+            | Node::LiteralFloat(_)
+            | Node::LiteralBool(_)
+            | Node::LiteralInt(_)
+            | Node::LiteralIdent(_) => {}
+
+            Node::Script(n) => children.extend(& $($mut)? n.lines),
+            Node::Block(n) => children.extend(& $($mut)? n.lines),
+            Node::CastExpr(n) => children.push(& $($mut)? n.expr),
+            Node::IsExpr(n) => children.push(& $($mut)? n.expr),
+            Node::DecoratorExpr(n) => children.push(& $($mut)? n.expr),
+            Node::ConstDef(n) => children.push(& $($mut)? n.expr),
+            Node::VarDef(n) => children.push(& $($mut)? n.expr),
+            Node::Let(n) => {
+                for (_, value) in & $($mut)? n.bindings {
+                    children.push(value);
+                }
+                children.push(& $($mut)? n.expr);
+            }
+
+            Node::AssignExpr(n) => {
+                children.push(& $($mut)? n.target);
+                children.push(& $($mut)? n.value);
+            }
+            Node::AssignArithmeticExpr(n) => {
+                children.push(& $($mut)? n.target);
+                children.push(& $($mut)? n.value);
+            }
+            Node::AssignBitwiseExpr(n) => {
+                children.push(& $($mut)? n.target);
+                children.push(& $($mut)? n.value);
+            }
+            Node::DeleteExpr(n) => children.push(& $($mut)? n.target),
+
+            Node::InfixOperator(n) => children.extend(& $($mut)? n.inner),
+            Node::PostfixIndexingOperator(n) => {
+                for (index, _) in & $($mut)? n.path {
+                    children.push(index);
+                }
+            }
+            Node::PostfixFnCallOperator(n) => children.extend(& $($mut)? n.args),
+
+            Node::ArithmeticInfixExpr(n) => {
+                children.push(& $($mut)? n.lhs);
+                children.push(& $($mut)? n.rhs);
+            }
+            Node::ArithmeticPrefixExpr(n) => children.push(& $($mut)? n.rhs),
+            Node::BitwiseNot(n) => children.push(& $($mut)? n.rhs),
+            Node::LogicalNot(n) => children.push(& $($mut)? n.rhs),
+            Node::BitwiseInfixExpr(n) => {
+                children.push(& $($mut)? n.lhs);
+                children.push(& $($mut)? n.rhs);
+            }
+            Node::ComparisonExpr(n) => {
+                children.push(& $($mut)? n.lhs);
+                children.push(& $($mut)? n.rhs);
+                for (_, operand) in & $($mut)? n.chain {
+                    children.push(operand);
+                }
+            }
+            Node::LogicalExpr(n) => {
+                children.push(& $($mut)? n.lhs);
+                children.push(& $($mut)? n.rhs);
+            }
+            Node::MatchExpr(n) => {
+                children.push(& $($mut)? n.lhs);
+                children.push(& $($mut)? n.rhs);
+            }
+
+            Node::FnCall(n) => children.extend(& $($mut)? n.args),
+            Node::CustomInfixExpr(n) => {
+                children.push(& $($mut)? n.lhs);
+                children.push(& $($mut)? n.rhs);
+            }
+            Node::FnAssign(n) => {
+                for (_, _, default, _) in & $($mut)? n.args {
+                    children.extend(default);
+                }
+                children.push(& $($mut)? n.body);
+            }
+            Node::Alias(n) => children.push(& $($mut)? n.body),
+            Node::Return(n) => children.push(& $($mut)? n.value),
+            Node::Break(n) => children.extend(& $($mut)? n.value),
+            Node::Switch(n) => {
+                children.push(& $($mut)? n.expr);
+                for (values, body) in & $($mut)? n.cases {
+                    for (_, value) in values {
+                        children.push(value);
+                    }
+                    children.push(body);
+                }
+                children.push(& $($mut)? n.default);
+            }
+            Node::For(n) => {
+                children.push(& $($mut)? n.expr);
+                children.push(& $($mut)? n.block);
+                children.extend(& $($mut)? n.condition);
+            }
+
+            Node::Array(n) => children.extend(& $($mut)? n.elements),
+            Node::Spread(n) => children.push(& $($mut)? n.expr),
+            Node::Object(n) => {
+                for (key, value) in & $($mut)? n.elements {
+                    children.push(key);
+                    children.push(value);
+                }
+            }
+            Node::RangeExpr(n) => {
+                children.push(& $($mut)? n.start);
+                children.push(& $($mut)? n.end);
+            }
+            Node::IndexingExpr(n) => {
+                children.push(& $($mut)? n.base);
+                for (index, _) in & $($mut)? n.path {
+                    children.push(index);
+                }
+            }
+
+            Node::If(n) => {
+                children.push(& $($mut)? n.condition);
+                children.push(& $($mut)? n.then_block);
+                children.extend(& $($mut)? n.else_block);
+            }
+            Node::NilCoalesceExpr(n) => {
+                children.push(& $($mut)? n.value);
+                children.push(& $($mut)? n.default);
+            }
+        }
+        children
+    }};
+}
+
+impl<'source> Node<'source> {
+    /// The direct children of this node, in source order
+    pub fn children(&self) -> Vec<&Node<'source>> {
+        children!(self)
+    }
+
+    /// Mutable references to the direct children of this node, in source order
+    pub fn children_mut(&mut self) -> Vec<&mut Node<'source>> {
+        children!(self, mut)
+    }
+
+    /// Walk this node and all of its descendants, depth-first
+    pub fn walk(&self, visitor: &mut impl Visitor<'source>) {
+        if visitor.enter(self) {
+            for child in self.children() {
+                child.walk(visitor);
+            }
+        }
+        visitor.exit(self);
+    }
+
+    /// Walk this node and all of its descendants, depth-first, allowing them to be rewritten
+    pub fn walk_mut(&mut self, visitor: &mut impl VisitorMut<'source>) {
+        if visitor.enter(self) {
+            for child in self.children_mut() {
+                child.walk_mut(visitor);
+            }
+        }
+        visitor.exit(self);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        compiler::Compiler,
+        lexer::{Lexer, Stack},
+        parser::build_ast,
+        value::{Primitive, Value},
+        Lavendeux,
+    };
+
+    #[test]
+    fn test_visitor_rewrite() {
+        struct DoubleInts;
+        impl<'source> VisitorMut<'source> for DoubleInts {
+            fn exit(&mut self, node: &mut Node<'source>) {
+                if let Node::LiteralInt(n) = node {
+                    if let Primitive::Integer(i) = n.value {
+                        n.value = Primitive::Integer(i * 2);
+                    }
+                }
+            }
+        }
+
+        let source = "1 + 2 * 3";
+        let tokens = Lexer::new(source).all_tokens().expect("Could not lex");
+        let mut ast = build_ast(Stack::new(tokens), Default::default()).unwrap();
+        ast.walk_mut(&mut DoubleInts);
+
+        let mut compiler = Compiler::new(source, Default::default());
+        ast.compile(&mut compiler).expect("Could not compile");
+        let (profile, bytecode) = compiler.decompose();
+
+        let result = Lavendeux::new().execute(bytecode, Some(profile)).unwrap();
+        assert_eq!(result, Value::integer(26));
+    }
+}
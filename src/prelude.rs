@@ -0,0 +1,33 @@
+//! The types needed to embed Lavendeux, for a single glob import
+//!
+//! ```rust
+//! use lavendeux_parser::prelude::*;
+//!
+//! # fn main() -> Result<(), Error> {
+//! let mut lav = Lavendeux::new();
+//! lav.set_angle_mode(AngleMode::Degrees);
+//! match lav.run_output("2 + 2")?.pop() {
+//!     Some(RunOutput::Value(value)) => assert_eq!(value, Value::integer(4)),
+//!     _ => panic!("Expected a value"),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The prelude covers running scripts and loading functions; the other modules expose the
+//! lexer, parser, compiler and VM for tools that work with them directly
+pub use crate::{
+    compiler::{CompilerOptions, DebugProfile},
+    lexer::SourceMap,
+    parser::{CustomOperator, ParserOptions},
+    traits::SerializeToBytes,
+    value::{
+        CheckedArithmetic, DivisionByZero, Function, IntegerOverflow, Number, NumberSymbol,
+        Primitive, StdFunctionSet, Value, ValueError, ValueType,
+    },
+    vm::{
+        AngleMode, CapturedOutput, Clock, CurrencyConverter, OutputSink, ReplayLog, TraceEntry,
+        Tracer, VirtualMachine, WriteSink,
+    },
+    DecoratedData, Error, Lavendeux, PlotData, Repl, ReplResult, RunOutput, RunReport, TableData,
+};
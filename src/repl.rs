@@ -0,0 +1,115 @@
+use crate::{Error, Lavendeux, RunOutput};
+
+/// The result of giving a line of input to a `Repl`
+#[derive(Debug)]
+pub enum ReplResult {
+    /// The entry is unfinished, such as a block or bracket left open
+    /// The next line continues it
+    Incomplete,
+
+    /// The entry ran; there is one output per line of the entry
+    Complete(Vec<RunOutput>),
+
+    /// The entry failed
+    Failed {
+        /// The full text of the entry, for rendering the error against
+        source: String,
+
+        /// The error that occurred
+        error: Error,
+    },
+}
+
+/// An interactive session, for front-ends that read input a line at a time
+/// Variables and functions defined by each entry remain available to the next
+///
+/// Entries may span lines; a line that leaves a block or bracket open is held
+/// until a later line completes it
+pub struct Repl {
+    lav: Lavendeux,
+    pending: String,
+}
+
+impl Repl {
+    /// Start a new session, with a fresh `Lavendeux` instance
+    pub fn new() -> Self {
+        Self::with_lavendeux(Lavendeux::new())
+    }
+
+    /// Start a new session using an existing instance, keeping its settings and state
+    pub fn with_lavendeux(lav: Lavendeux) -> Self {
+        Self {
+            lav,
+            pending: String::new(),
+        }
+    }
+
+    /// The instance entries are run in, for changing its settings between entries
+    pub fn lavendeux(&mut self) -> &mut Lavendeux {
+        &mut self.lav
+    }
+
+    /// The prompt to show before the next line; it differs while an entry is unfinished
+    pub fn prompt(&self) -> &'static str {
+        if self.pending.is_empty() {
+            "> "
+        } else {
+            "| "
+        }
+    }
+
+    /// Discard an unfinished entry, such as when the user presses Ctrl+C
+    pub fn cancel(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Add a line of input, and run the entry if it is complete
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{value::Value, Repl, ReplResult, RunOutput};
+    /// let mut repl = Repl::new();
+    /// assert!(matches!(repl.feed("double(x) = {"), ReplResult::Incomplete));
+    /// assert!(matches!(repl.feed("    x * 2"), ReplResult::Incomplete));
+    /// assert!(matches!(repl.feed("}"), ReplResult::Complete(_)));
+    ///
+    /// match repl.feed("double(21)") {
+    ///     ReplResult::Complete(outputs) => {
+    ///         assert_eq!(outputs, vec![RunOutput::Value(Value::integer(42))]);
+    ///     }
+    ///     _ => panic!("expected a result"),
+    /// }
+    /// ```
+    pub fn feed(&mut self, line: &str) -> ReplResult {
+        if !self.pending.is_empty() {
+            self.pending.push('\n');
+        }
+        self.pending.push_str(line);
+
+        if self.pending.trim().is_empty() {
+            self.pending.clear();
+            return ReplResult::Complete(vec![]);
+        }
+
+        match self.lav.run_output(&self.pending) {
+            Ok(outputs) => {
+                self.pending.clear();
+                ReplResult::Complete(outputs)
+            }
+
+            // Running out of input means the entry continues on the next line
+            Err(error) if error.is_recoverable() => ReplResult::Incomplete,
+
+            Err(error) => ReplResult::Failed {
+                source: std::mem::take(&mut self.pending),
+                error,
+            },
+        }
+    }
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
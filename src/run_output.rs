@@ -0,0 +1,377 @@
+use crate::value::{Primitive, Value};
+use std::time::Duration;
+
+/// Reserved object key marking a value as plot data
+/// Set by the `plot` stdlib function
+pub const PLOT_KEY: &str = "__plot";
+
+/// Reserved object key marking a value as a table
+/// Set by the `table` stdlib function
+pub const TABLE_KEY: &str = "__table";
+
+/// Reserved object key marking a value as the result of a decorator
+/// Set by decorator expressions, such as `255 @hex`, alongside the raw value under `value`
+pub const DECORATED_KEY: &str = "__decorated";
+
+/// Everything produced by running a program, so embedders need not intercept stdout
+/// See `Lavendeux::run_report`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunReport {
+    /// The result of each line
+    pub outputs: Vec<RunOutput>,
+
+    /// Values printed with `echo`, in order
+    pub printed: Vec<String>,
+
+    /// Warnings raised with `warn`, in order
+    pub warnings: Vec<String>,
+
+    /// Time spent lexing, parsing and compiling the source
+    pub compile_time: Duration,
+
+    /// Time spent running the compiled program
+    pub run_time: Duration,
+}
+
+impl RunReport {
+    /// The outputs that are artifacts, such as plots and tables, rather than values
+    pub fn artifacts(&self) -> impl Iterator<Item = &RunOutput> {
+        self.outputs
+            .iter()
+            .filter(|output| !matches!(output, RunOutput::Value(_) | RunOutput::Decorated(_)))
+    }
+}
+
+/// A structured result of running a program, for front-ends
+/// Values that carry data a front-end can present specially are separated out
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum RunOutput {
+    /// An ordinary value
+    Value(Value),
+
+    /// Plot data, produced by the `plot` stdlib function
+    Plot(PlotData),
+
+    /// A table, produced by the `table` stdlib function
+    Table(TableData),
+
+    /// A value formatted by a decorator, such as `255 @hex`
+    Decorated(DecoratedData),
+}
+
+impl RunOutput {
+    /// Split the result of a program into one output per line, as the front-ends print it
+    pub fn from_result(value: Value) -> Vec<Self> {
+        match value {
+            Value::Array(values) => values.into_iter().map(Self::from).collect(),
+            value => vec![Self::from(value)],
+        }
+    }
+}
+
+impl From<Value> for RunOutput {
+    /// Objects with the reserved `__plot`, `__table` or `__decorated` keys are separated out,
+    /// if their data is valid
+    fn from(value: Value) -> Self {
+        if let Some(plot) = PlotData::from_value(&value) {
+            Self::Plot(plot)
+        } else if let Some(table) = TableData::from_value(&value) {
+            Self::Table(table)
+        } else if let Some(decorated) = DecoratedData::from_value(&value) {
+            Self::Decorated(decorated)
+        } else {
+            Self::Value(value)
+        }
+    }
+}
+
+impl std::fmt::Display for RunOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Value(value) => write!(f, "{value}"),
+            Self::Plot(plot) => write!(f, "{plot}"),
+            Self::Table(table) => write!(f, "{table}"),
+            Self::Decorated(decorated) => write!(f, "{decorated}"),
+        }
+    }
+}
+
+/// A series of points to plot, with an optional title
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotData {
+    /// The title of the plot, if one was given
+    pub title: Option<String>,
+
+    /// The [x, y] pairs to plot, in order
+    pub points: Vec<(f64, f64)>,
+}
+
+impl PlotData {
+    /// Read plot data from an object with the reserved `__plot` key
+    /// Returns None if the value is not plot data, or a point is not a pair of numbers
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let object = match value {
+            Value::Object(object) => object,
+            _ => return None,
+        };
+
+        let points = match object.get(&Primitive::String(PLOT_KEY.to_string()))? {
+            Value::Array(points) => points
+                .iter()
+                .map(|point| match point {
+                    Value::Array(pair) if pair.len() == 2 => Some((
+                        pair[0].clone().cast_decimal().ok()?.into_f64(),
+                        pair[1].clone().cast_decimal().ok()?.into_f64(),
+                    )),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?,
+            _ => return None,
+        };
+
+        let title = match object.get(&Primitive::String("title".to_string())) {
+            Some(Value::Primitive(Primitive::String(title))) if !title.is_empty() => {
+                Some(title.clone())
+            }
+            _ => None,
+        };
+
+        Some(Self { title, points })
+    }
+}
+
+impl std::fmt::Display for PlotData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(title) = &self.title {
+            writeln!(f, "{title}")?;
+        }
+        write!(f, "x\ty")?;
+        for (x, y) in &self.points {
+            write!(f, "\n{x}\t{y}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Rows of values, with optional column headers
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableData {
+    /// The name of each column; empty if no headers were given
+    pub headers: Vec<String>,
+
+    /// The values in each row, in order
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl TableData {
+    /// Read a table from an object with the reserved `__table` key
+    /// Returns None if the value is not a table, or a row is not an array or object
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let object = match value {
+            Value::Object(object) => object,
+            _ => return None,
+        };
+
+        let rows = match object.get(&Primitive::String(TABLE_KEY.to_string()))? {
+            Value::Array(rows) => rows,
+            _ => return None,
+        };
+
+        let headers = match object.get(&Primitive::String("headers".to_string())) {
+            Some(Value::Array(headers)) => headers.iter().map(ToString::to_string).collect(),
+            _ => vec![],
+        };
+
+        Self::from_rows(rows, headers)
+    }
+
+    /// Build a table from rows that are either arrays of values, or objects
+    /// Object rows are read in the order of the headers; if there are none, the headers are
+    /// every key in the rows, sorted, and missing keys are left blank
+    /// Returns None if a row is not an array or object
+    pub fn from_rows(rows: &[Value], mut headers: Vec<String>) -> Option<Self> {
+        if headers.is_empty() {
+            headers = rows
+                .iter()
+                .filter_map(|row| match row {
+                    Value::Object(row) => Some(row.keys()),
+                    _ => None,
+                })
+                .flatten()
+                .map(ToString::to_string)
+                .collect();
+            headers.sort();
+            headers.dedup();
+        }
+
+        let rows = rows
+            .iter()
+            .map(|row| match row {
+                Value::Array(row) => Some(row.clone()),
+                Value::Object(row) => Some(
+                    headers
+                        .iter()
+                        .map(|header| {
+                            row.iter()
+                                .find(|(key, _)| key.to_string() == *header)
+                                .map(|(_, value)| value.clone())
+                                .unwrap_or_else(|| Value::string(String::new()))
+                        })
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self { headers, rows })
+    }
+}
+
+/// Drawn as an ASCII table, with the columns aligned, and numbers aligned to the right
+impl std::fmt::Display for TableData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|value| {
+                        let numeric = matches!(
+                            value,
+                            Value::Primitive(Primitive::Integer(_) | Primitive::Decimal(_))
+                        );
+                        (value.to_string(), numeric)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // Rows can be ragged; every column is as wide as its widest cell
+        let columns = rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.headers.len()])
+            .max()
+            .unwrap_or_default();
+        let mut widths = vec![0; columns];
+        for (i, cell) in self.headers.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+        for row in &rows {
+            for (i, (cell, _)) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.chars().count());
+            }
+        }
+
+        let border = widths
+            .iter()
+            .map(|width| "-".repeat(width + 2))
+            .collect::<Vec<_>>()
+            .join("+");
+        let border = format!("+{border}+");
+        let line = |cells: &[(String, bool)]| {
+            let cells = widths
+                .iter()
+                .enumerate()
+                .map(|(i, &width)| match cells.get(i) {
+                    Some((cell, true)) => format!(" {cell:>width$} "),
+                    Some((cell, false)) => format!(" {cell:<width$} "),
+                    None => " ".repeat(width + 2),
+                })
+                .collect::<Vec<_>>();
+            format!("|{}|", cells.join("|"))
+        };
+
+        writeln!(f, "{border}")?;
+        if !self.headers.is_empty() {
+            let headers = self
+                .headers
+                .iter()
+                .map(|header| (header.clone(), false))
+                .collect::<Vec<_>>();
+            writeln!(f, "{}", line(&headers))?;
+            writeln!(f, "{border}")?;
+        }
+        for row in &rows {
+            writeln!(f, "{}", line(row))?;
+        }
+        write!(f, "{border}")
+    }
+}
+
+/// A value, and the string a decorator formatted it as
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecoratedData {
+    /// The value before it was decorated
+    pub value: Value,
+
+    /// The string returned by the decorator
+    pub formatted: String,
+}
+
+impl DecoratedData {
+    /// Read a decorated value from an object with the reserved `__decorated` key
+    /// Returns None if the value is not decorated, or the formatted value is not a string
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let object = match value {
+            Value::Object(object) => object,
+            _ => return None,
+        };
+
+        let formatted = match object.get(&Primitive::String(DECORATED_KEY.to_string()))? {
+            Value::Primitive(Primitive::String(formatted)) => formatted.clone(),
+            _ => return None,
+        };
+
+        let value = object
+            .get(&Primitive::String("value".to_string()))
+            .cloned()
+            .unwrap_or(Value::Primitive(Primitive::Nil));
+
+        Some(Self { value, formatted })
+    }
+}
+
+impl std::fmt::Display for DecoratedData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.formatted)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_decorators() {
+        let mut lav = Lavendeux::new();
+        let outputs = lav.run_output("@double(x) = x * 2\n2 + 3 @double").unwrap();
+        assert_eq!(
+            outputs.last(),
+            Some(&RunOutput::Decorated(DecoratedData {
+                value: Value::integer(5),
+                formatted: "10".to_string(),
+            }))
+        );
+        assert_eq!(outputs.last().unwrap().to_string(), "10");
+
+        // Decorated values are not artifacts
+        let report = lav.run_report("@double(x) = x * 2\n4 @double").unwrap();
+        assert_eq!(report.artifacts().count(), 0);
+
+        // Durations, in seconds or milliseconds, and back again
+        let outputs = lav
+            .run_output("7985 @duration\n7985250 @human_time\nparse_duration('2h 13m 5s')")
+            .unwrap();
+        let outputs = outputs.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(outputs, vec!["2h 13m 5s", "2h 13m 5s 250ms", "7985"]);
+        assert_eq!(
+            lav.run("parse_duration(duration(1.5))")
+                .unwrap()
+                .to_string(),
+            "1.5"
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Helpers for the `tracing` feature
+//! Both macros expand to nothing unless the feature is enabled
+
+/// Enter a tracing span, which lasts until the end of the enclosing scope
+macro_rules! trace_span {
+    ($($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = ::tracing::debug_span!($($args)*).entered();
+    };
+}
+
+/// Emit a tracing event at the trace level
+macro_rules! trace_event {
+    ($($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        ::tracing::trace!($($args)*);
+    };
+}
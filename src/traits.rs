@@ -1,10 +1,14 @@
 //! Traits used for various parts of the compiler
 
 /// Trait for catching memory allocation errors
+#[doc(hidden)]
 pub trait SafeVecAlloc {
     fn safe_alloc(size: usize) -> Result<Self, std::collections::TryReserveError>
     where
         Self: Sized;
+
+    /// The number of bytes each item takes, used to measure allocations against a budget
+    fn item_size() -> usize;
 }
 
 impl<T> SafeVecAlloc for Vec<T> {
@@ -16,6 +20,10 @@ impl<T> SafeVecAlloc for Vec<T> {
         vec.try_reserve(size)?;
         Ok(vec)
     }
+
+    fn item_size() -> usize {
+        std::mem::size_of::<T>()
+    }
 }
 
 impl<K, V> SafeVecAlloc for std::collections::HashMap<K, V>
@@ -30,6 +38,10 @@ where
         map.try_reserve(size)?;
         Ok(map)
     }
+
+    fn item_size() -> usize {
+        std::mem::size_of::<(K, V)>()
+    }
 }
 
 /// Trait for converting a type into an owned version of itself
@@ -41,23 +53,93 @@ pub trait IntoOwned {
     fn into_owned(self) -> Self::Owned;
 }
 
-pub(crate) trait NextN {
-    fn next_n(&mut self, n: usize) -> Option<Vec<u8>>;
+/// A cursor over a byte slice, used to decode types serialized with `SerializeToBytes`
+/// Reads are bounds-checked against the slice, and fixed-size or length-prefixed data
+/// is taken as a sub-slice instead of byte by byte
+///
+/// It is also an iterator over the remaining bytes
+#[derive(Debug, Clone)]
+pub struct ByteReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
 }
 
-impl<T: Iterator<Item = u8>> NextN for T {
-    fn next_n(&mut self, n: usize) -> Option<Vec<u8>> {
-        let mut bytes = Vec::with_capacity(n);
-        for _ in 0..n {
-            match self.next() {
-                Some(byte) => bytes.push(byte),
-                None => return None,
-            }
-        }
-        Some(bytes)
+impl<'a> ByteReader<'a> {
+    /// Create a reader positioned at the start of the given bytes
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    /// The number of bytes read so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The bytes that have not been read yet
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.offset..]
+    }
+
+    /// Read a single byte
+    /// `context` names the type being decoded, for the error if no bytes remain
+    pub fn next_byte(&mut self, context: &str) -> Result<u8, ByteDecodeError> {
+        let byte = *self
+            .bytes
+            .get(self.offset)
+            .ok_or_else(|| ByteDecodeError::UnexpectedEnd(context.to_string()))?;
+        self.offset += 1;
+        Ok(byte)
+    }
+
+    /// Borrow the next `len` bytes
+    /// `context` names the type being decoded, for the error if too few bytes remain
+    pub fn read_slice(&mut self, len: usize, context: &str) -> Result<&'a [u8], ByteDecodeError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| ByteDecodeError::UnexpectedEnd(context.to_string()))?;
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+
+    /// Read the next `N` bytes into an array
+    pub fn read_array<const N: usize>(
+        &mut self,
+        context: &str,
+    ) -> Result<[u8; N], ByteDecodeError> {
+        let mut buf = [0; N];
+        buf.copy_from_slice(self.read_slice(N, context)?);
+        Ok(buf)
+    }
+
+    /// Borrow a run of bytes prefixed by its length, as written for strings and byte vectors
+    pub fn read_prefixed(&mut self, context: &str) -> Result<&'a [u8], ByteDecodeError> {
+        let len = u64::deserialize_from_bytes(self)?;
+        let len = usize::try_from(len)
+            .map_err(|_| ByteDecodeError::UnexpectedEnd(context.to_string()))?;
+        self.read_slice(len, context)
     }
 }
 
+impl Iterator for ByteReader<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.bytes.get(self.offset).copied()?;
+        self.offset += 1;
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bytes.len() - self.offset;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for ByteReader<'_> {}
+
 /// Error found when decoding bytes
 #[derive(thiserror::Error, Debug, Clone, PartialEq)]
 pub enum ByteDecodeError {
@@ -86,130 +168,36 @@ where
     /// Convert the type to bytes
     fn serialize_into_bytes(self) -> Vec<u8>;
 
-    /// Convert bytes to the type
-    fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
-    ) -> Result<Self, ByteDecodeError>;
-}
-
-impl SerializeToBytes for i128 {
-    fn serialize_into_bytes(self) -> Vec<u8> {
-        self.to_be_bytes().to_vec()
-    }
+    /// Convert bytes to the type, advancing the reader past them
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError>;
 
-    fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
-    ) -> Result<Self, ByteDecodeError> {
-        let bytes = bytes
-            .next_n(16)
-            .ok_or_else(|| ByteDecodeError::UnexpectedEnd("i128".to_string()))?;
-        let mut buf = [0; 16];
-        buf.copy_from_slice(&bytes);
-        Ok(i128::from_be_bytes(buf))
+    /// Convert bytes to the type, starting at the beginning of the slice
+    /// Any bytes after the encoded value are ignored
+    fn from_bytes(bytes: &[u8]) -> Result<Self, ByteDecodeError> {
+        Self::deserialize_from_bytes(&mut ByteReader::new(bytes))
     }
 }
 
-impl SerializeToBytes for u8 {
-    fn serialize_into_bytes(self) -> Vec<u8> {
-        self.to_be_bytes().to_vec()
-    }
-
-    fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
-    ) -> Result<Self, ByteDecodeError> {
-        let bytes = bytes
-            .next_n(1)
-            .ok_or_else(|| ByteDecodeError::UnexpectedEnd("u8".to_string()))?;
-        let mut buf = [0; 1];
-        buf.copy_from_slice(&bytes);
-        Ok(u8::from_be_bytes(buf))
-    }
-}
-
-impl SerializeToBytes for i8 {
-    fn serialize_into_bytes(self) -> Vec<u8> {
-        self.to_be_bytes().to_vec()
-    }
-
-    fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
-    ) -> Result<Self, ByteDecodeError> {
-        let bytes = bytes
-            .next_n(1)
-            .ok_or_else(|| ByteDecodeError::UnexpectedEnd("i8".to_string()))?;
-        let mut buf = [0; 1];
-        buf.copy_from_slice(&bytes);
-        Ok(i8::from_be_bytes(buf))
-    }
-}
-
-impl SerializeToBytes for i32 {
-    fn serialize_into_bytes(self) -> Vec<u8> {
-        self.to_be_bytes().to_vec()
-    }
-
-    fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
-    ) -> Result<Self, ByteDecodeError> {
-        let bytes = bytes
-            .next_n(4)
-            .ok_or_else(|| ByteDecodeError::UnexpectedEnd("i32".to_string()))?;
-        let mut buf = [0; 4];
-        buf.copy_from_slice(&bytes);
-        Ok(i32::from_be_bytes(buf))
-    }
-}
-
-impl SerializeToBytes for u64 {
-    fn serialize_into_bytes(self) -> Vec<u8> {
-        self.to_be_bytes().to_vec()
-    }
-
-    fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
-    ) -> Result<Self, ByteDecodeError> {
-        let bytes = bytes
-            .next_n(8)
-            .ok_or_else(|| ByteDecodeError::UnexpectedEnd("u64".to_string()))?;
-        let mut buf = [0; 8];
-        buf.copy_from_slice(&bytes);
-        Ok(u64::from_be_bytes(buf))
-    }
-}
-
-impl SerializeToBytes for usize {
-    fn serialize_into_bytes(self) -> Vec<u8> {
-        self.to_be_bytes().to_vec()
-    }
-
-    fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
-    ) -> Result<Self, ByteDecodeError> {
-        let bytes = bytes
-            .next_n(8)
-            .ok_or_else(|| ByteDecodeError::UnexpectedEnd("usize".to_string()))?;
-        let mut buf = [0; 8];
-        buf.copy_from_slice(&bytes);
-        Ok(usize::from_be_bytes(buf))
-    }
+/// Implement `SerializeToBytes` for integer types, as big-endian bytes
+macro_rules! impl_serialize_int {
+    ($($t:ty),+) => {
+        $(
+            impl SerializeToBytes for $t {
+                fn serialize_into_bytes(self) -> Vec<u8> {
+                    self.to_be_bytes().to_vec()
+                }
+
+                fn deserialize_from_bytes(
+                    bytes: &mut ByteReader<'_>,
+                ) -> Result<Self, ByteDecodeError> {
+                    Ok(<$t>::from_be_bytes(bytes.read_array(stringify!($t))?))
+                }
+            }
+        )+
+    };
 }
 
-impl SerializeToBytes for u16 {
-    fn serialize_into_bytes(self) -> Vec<u8> {
-        self.to_be_bytes().to_vec()
-    }
-
-    fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
-    ) -> Result<Self, ByteDecodeError> {
-        let bytes = bytes
-            .next_n(2)
-            .ok_or_else(|| ByteDecodeError::UnexpectedEnd("u16".to_string()))?;
-        let mut buf = [0; 2];
-        buf.copy_from_slice(&bytes);
-        Ok(u16::from_be_bytes(buf))
-    }
-}
+impl_serialize_int!(i128, u8, i8, i32, u32, u64, usize, u16);
 
 impl SerializeToBytes for String {
     fn serialize_into_bytes(self) -> Vec<u8> {
@@ -219,16 +207,12 @@ impl SerializeToBytes for String {
         bytes
     }
 
-    fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
-    ) -> Result<Self, ByteDecodeError> {
-        let len = u64::deserialize_from_bytes(bytes)?;
-        let bytes = bytes
-            .next_n(len as usize)
-            .ok_or_else(|| ByteDecodeError::UnexpectedEnd("String".to_string()))?;
-        Ok(String::from_utf8(bytes).map_err(|_| {
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
+        let bytes = bytes.read_prefixed("String")?;
+        let string = std::str::from_utf8(bytes).map_err(|_| {
             ByteDecodeError::MalformedData("String".to_string(), "Invalid UTF-8".to_string())
-        })?)
+        })?;
+        Ok(string.to_string())
     }
 }
 
@@ -245,10 +229,14 @@ where
         bytes
     }
 
-    fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
-    ) -> Result<Self, ByteDecodeError> {
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
         let len = u64::deserialize_from_bytes(bytes)?;
+
+        // Every item takes at least one byte, so a longer length can only be malformed
+        if len > bytes.len() as u64 {
+            return Err(ByteDecodeError::UnexpectedEnd("Vec".to_string()));
+        }
+
         let mut items = Vec::safe_alloc(len as usize)?;
         for _ in 0..len {
             items.push(T::deserialize_from_bytes(bytes)?);
@@ -273,12 +261,10 @@ where
         }
     }
 
-    fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
-    ) -> Result<Self, ByteDecodeError> {
-        match bytes.next() {
-            Some(0) => Ok(None),
-            Some(1) => Ok(Some(T::deserialize_from_bytes(bytes)?)),
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
+        match bytes.next_byte("Option")? {
+            0 => Ok(None),
+            1 => Ok(Some(T::deserialize_from_bytes(bytes)?)),
             _ => Err(ByteDecodeError::MalformedData(
                 "Option".to_string(),
                 "Invalid header".to_string(),
@@ -286,3 +272,29 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::value::Value;
+
+    #[test]
+    fn test_truncated_bytes() {
+        let bytes = Value::string("hello world".to_string()).serialize_into_bytes();
+        let mut reader = ByteReader::new(&bytes);
+        assert!(Value::deserialize_from_bytes(&mut reader).is_ok());
+        assert_eq!(reader.offset(), bytes.len());
+
+        // Every prefix of a value fails to decode, instead of panicking
+        for len in 0..bytes.len() {
+            assert!(Value::from_bytes(&bytes[..len]).is_err());
+        }
+
+        // A length longer than the data is refused before anything is allocated
+        let bytes = u64::MAX.serialize_into_bytes();
+        assert_eq!(
+            Vec::<Value>::from_bytes(&bytes),
+            Err(ByteDecodeError::UnexpectedEnd("Vec".to_string()))
+        );
+    }
+}
@@ -1,11 +1,11 @@
 //! # Value
 //! The value type used by the language
 //! Contains the main value type and all subtypes
-use crate::traits::SerializeToBytes;
+use crate::traits::{ByteReader, SerializeToBytes};
 use std::collections::HashMap;
 
 mod error;
-pub use error::ValueError;
+pub use error::{Snippet, ValueError};
 
 mod function;
 pub use function::*;
@@ -13,8 +13,14 @@ pub use function::*;
 mod number;
 pub use number::{Number, NumberSymbol};
 
+mod division;
+pub use division::{division_by_zero, set_division_by_zero, DivisionByZero};
+
+mod overflow;
+pub use overflow::{integer_overflow, set_integer_overflow, IntegerOverflow};
+
 mod primitive;
-pub use primitive::Primitive;
+pub use primitive::{set_strict_booleans, strict_booleans, Primitive};
 
 mod traits;
 pub use traits::*;
@@ -22,6 +28,9 @@ pub use traits::*;
 mod types;
 pub use types::ValueType;
 
+mod struct_type;
+pub use struct_type::StructType;
+
 mod indexing;
 pub use indexing::*;
 
@@ -43,7 +52,8 @@ pub enum Value {
     Range(std::ops::Range<i128>),
 
     /// Represents a function, which can be called
-    Function(Function),
+    /// Boxed, since functions are much larger than the other variants and rarely on the stack
+    Function(Box<Function>),
 }
 
 impl PartialOrd for Value {
@@ -54,27 +64,33 @@ impl PartialOrd for Value {
 
 impl Ord for Value {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.partial_cmp(other) {
-            Some(o) => o,
-            None => {
-                // Fall back to type ordering
-                // Primitive < Array < Object < Range < Function
-                match (self, other) {
-                    (Value::Primitive(_), _) => std::cmp::Ordering::Less,
-                    (_, Value::Primitive(_)) => std::cmp::Ordering::Greater,
+        match (self, other) {
+            (Value::Primitive(a), Value::Primitive(b)) => a.cmp(b),
+            (Value::Array(a), Value::Array(b)) => a.cmp(b),
+            (Value::Object(a), Value::Object(b)) => {
+                // Objects are unordered, so compare their entries sorted by key
+                let mut a = a.iter().collect::<Vec<_>>();
+                let mut b = b.iter().collect::<Vec<_>>();
+                a.sort();
+                b.sort();
+                a.cmp(&b)
+            }
+            (Value::Range(a), Value::Range(b)) => (a.start, a.end).cmp(&(b.start, b.end)),
+            (Value::Function(a), Value::Function(b)) => a.cmp(b),
 
-                    (Value::Array(_), _) => std::cmp::Ordering::Less,
-                    (_, Value::Array(_)) => std::cmp::Ordering::Greater,
+            // Fall back to type ordering
+            // Primitive < Array < Object < Range < Function
+            (Value::Primitive(_), _) => std::cmp::Ordering::Less,
+            (_, Value::Primitive(_)) => std::cmp::Ordering::Greater,
 
-                    (Value::Object(_), _) => std::cmp::Ordering::Less,
-                    (_, Value::Object(_)) => std::cmp::Ordering::Greater,
+            (Value::Array(_), _) => std::cmp::Ordering::Less,
+            (_, Value::Array(_)) => std::cmp::Ordering::Greater,
 
-                    (Value::Range(_), _) => std::cmp::Ordering::Less,
-                    (_, Value::Range(_)) => std::cmp::Ordering::Greater,
+            (Value::Object(_), _) => std::cmp::Ordering::Less,
+            (_, Value::Object(_)) => std::cmp::Ordering::Greater,
 
-                    (Value::Function(_), _) => std::cmp::Ordering::Greater,
-                }
-            }
+            (Value::Range(_), _) => std::cmp::Ordering::Less,
+            (_, Value::Range(_)) => std::cmp::Ordering::Greater,
         }
     }
 }
@@ -87,28 +103,25 @@ impl Value {
             Ok(self)
         } else {
             match typename {
-                ValueType::Boolean => self
-                    .as_boolean()
-                    .ok_or_else(|| ValueError::TypeConversion(own_type, typename)),
-                ValueType::Integer => self
-                    .as_integer()
-                    .ok_or_else(|| ValueError::TypeConversion(own_type, typename)),
-                ValueType::Decimal => self
-                    .as_decimal()
-                    .ok_or_else(|| ValueError::TypeConversion(own_type, typename)),
-                ValueType::String => self
-                    .as_string()
-                    .ok_or_else(|| ValueError::TypeConversion(own_type, typename)),
-                ValueType::Array => self
-                    .as_array()
-                    .ok_or_else(|| ValueError::TypeConversion(own_type, typename)),
-                ValueType::Object => self
-                    .as_object()
-                    .ok_or_else(|| ValueError::TypeConversion(own_type, typename)),
-
-                ValueType::Range => self
-                    .as_range()
-                    .ok_or_else(|| ValueError::TypeConversion(own_type, typename)),
+                ValueType::Boolean => self.as_boolean().ok_or_else(|| {
+                    ValueError::TypeConversion(own_type, typename, Snippet::default())
+                }),
+                ValueType::Integer => self.cast_integer().map(Value::integer),
+                ValueType::Decimal => self.cast_decimal().map(Value::decimal),
+                ValueType::String => self.as_string().ok_or_else(|| {
+                    ValueError::TypeConversion(own_type, typename, Snippet::default())
+                }),
+                ValueType::Bytes => self.as_bytes().ok_or_else(|| {
+                    ValueError::TypeConversion(own_type, typename, Snippet::default())
+                }),
+                ValueType::Array => self.as_array().ok_or_else(|| {
+                    ValueError::TypeConversion(own_type, typename, Snippet::default())
+                }),
+                ValueType::Object => self.as_object().ok_or_else(|| {
+                    ValueError::TypeConversion(own_type, typename, Snippet::default())
+                }),
+
+                ValueType::Range => self.into_range().map(Value::Range),
 
                 ValueType::Primitive => self.cast_primitive().map(Value::Primitive),
                 ValueType::Numeric => self
@@ -118,49 +131,35 @@ impl Value {
 
                 ValueType::All => Ok(self),
 
-                _ => Err(ValueError::TypeConversion(own_type, typename)),
+                _ => Err(self.conversion_error(typename)),
             }
         }
     }
 
+    /// The error for failing to convert this value to another type, showing the value
+    fn conversion_error(&self, typename: ValueType) -> ValueError {
+        ValueError::TypeConversion(self.type_of(), typename, Snippet::of(&[self]))
+    }
+
     /// Returns the length of the value
-    /// For arrays, objects, and strings, this is the number of elements
+    /// For arrays, objects, strings and bytes, this is the number of elements
     /// For ranges, this is the difference between the start and end
-    /// For primitives, this is always 1
+    /// For primitives, this is always 1, except for nil which is 0
     pub fn len(&self) -> i128 {
         match self {
+            Value::Primitive(Primitive::Nil) => 0,
             Value::Array(a) => a.len() as i128,
             Value::Object(o) => o.len() as i128,
             Value::Range(r) => (r.end - r.start) as i128,
             Value::Primitive(Primitive::String(s)) => s.chars().count() as i128,
+            Value::Primitive(Primitive::Bytes(b)) => b.len() as i128,
             _ => 1,
         }
     }
 
     /// Checks if the value is of a certain type
     pub fn is_a(&self, typename: ValueType) -> bool {
-        let a = self.type_of();
-        match (a, typename) {
-            (
-                ValueType::Integer | ValueType::Decimal | ValueType::Boolean | ValueType::String,
-                ValueType::Primitive,
-            ) => true,
-
-            (
-                ValueType::String | ValueType::Array | ValueType::Object | ValueType::Range,
-                ValueType::Collection,
-            ) => true,
-
-            (ValueType::Boolean | ValueType::Integer | ValueType::Decimal, ValueType::Numeric) => {
-                true
-            }
-
-            (_, ValueType::All) => true,
-
-            _ if a == typename => true,
-
-            _ => false,
-        }
+        self.type_of().is_a(typename)
     }
 
     /// Returns the type of the value
@@ -180,16 +179,31 @@ impl Value {
         let (mut ta, mut tb) = (self.type_of(), other.type_of());
         if ta == tb {
             Ok((self, other))
+        } else if ta == ValueType::Nil || tb == ValueType::Nil {
+            // nil never converts implicitly
+            Err(ValueError::TypeConversion(
+                ta,
+                tb,
+                Snippet::of(&[&self, &other]),
+            ))
         } else {
             if matches!(
                 ta,
-                ValueType::Boolean | ValueType::Integer | ValueType::Decimal | ValueType::String
+                ValueType::Boolean
+                    | ValueType::Integer
+                    | ValueType::Decimal
+                    | ValueType::String
+                    | ValueType::Bytes
             ) {
                 ta = ValueType::Primitive;
             }
             if matches!(
                 tb,
-                ValueType::Boolean | ValueType::Integer | ValueType::Decimal | ValueType::String
+                ValueType::Boolean
+                    | ValueType::Integer
+                    | ValueType::Decimal
+                    | ValueType::String
+                    | ValueType::Bytes
             ) {
                 tb = ValueType::Primitive;
             }
@@ -198,9 +212,9 @@ impl Value {
                 (ValueType::Primitive, ValueType::Primitive) => {
                     if let (Value::Primitive(p1), Value::Primitive(p2)) = (self, other) {
                         let (t1, t2) = (p1.type_of(), p2.type_of());
-                        let (p1, p2) = p1
-                            .resolve(p2)
-                            .ok_or_else(|| ValueError::TypeConversion(t1, t2))?;
+                        let (p1, p2) = p1.resolve(p2).ok_or_else(|| {
+                            ValueError::TypeConversion(t1, t2, Snippet::default())
+                        })?;
                         Ok((Value::Primitive(p1), Value::Primitive(p2)))
                     } else {
                         unreachable!("Both values are primitives")
@@ -208,12 +222,12 @@ impl Value {
                 }
                 (ValueType::Primitive, ValueType::Array) => Ok((
                     self.as_array()
-                        .ok_or_else(|| ValueError::TypeConversion(ta, tb))?,
+                        .ok_or_else(|| ValueError::TypeConversion(ta, tb, Snippet::default()))?,
                     other,
                 )),
                 (ValueType::Primitive, ValueType::Object) => Ok((
                     self.as_object()
-                        .ok_or_else(|| ValueError::TypeConversion(ta, tb))?,
+                        .ok_or_else(|| ValueError::TypeConversion(ta, tb, Snippet::default()))?,
                     other,
                 )),
 
@@ -221,47 +235,47 @@ impl Value {
                     self,
                     other
                         .as_array()
-                        .ok_or_else(|| ValueError::TypeConversion(ta, tb))?,
+                        .ok_or_else(|| ValueError::TypeConversion(ta, tb, Snippet::default()))?,
                 )),
                 (ValueType::Object, ValueType::Primitive) => Ok((
                     self,
                     other
                         .as_object()
-                        .ok_or_else(|| ValueError::TypeConversion(ta, tb))?,
+                        .ok_or_else(|| ValueError::TypeConversion(ta, tb, Snippet::default()))?,
                 )),
 
                 (ValueType::Array, ValueType::Object) => Ok((
                     self.as_object()
-                        .ok_or_else(|| ValueError::TypeConversion(ta, tb))?,
+                        .ok_or_else(|| ValueError::TypeConversion(ta, tb, Snippet::default()))?,
                     other,
                 )),
                 (ValueType::Object, ValueType::Array) => Ok((
                     self,
                     other
                         .as_object()
-                        .ok_or_else(|| ValueError::TypeConversion(ta, tb))?,
+                        .ok_or_else(|| ValueError::TypeConversion(ta, tb, Snippet::default()))?,
                 )),
 
                 (ValueType::Array, ValueType::Range) => Ok((
                     self,
                     other
                         .as_array()
-                        .ok_or_else(|| ValueError::TypeConversion(ta, tb))?,
+                        .ok_or_else(|| ValueError::TypeConversion(ta, tb, Snippet::default()))?,
                 )),
                 (ValueType::Object, ValueType::Range) => Ok((
                     self,
                     other
                         .as_object()
-                        .ok_or_else(|| ValueError::TypeConversion(ta, tb))?,
+                        .ok_or_else(|| ValueError::TypeConversion(ta, tb, Snippet::default()))?,
                 )),
                 (ValueType::Range, ValueType::Array) => Ok((
                     self.as_array()
-                        .ok_or_else(|| ValueError::TypeConversion(ta, tb))?,
+                        .ok_or_else(|| ValueError::TypeConversion(ta, tb, Snippet::default()))?,
                     other,
                 )),
                 (ValueType::Range, ValueType::Object) => Ok((
                     self.as_object()
-                        .ok_or_else(|| ValueError::TypeConversion(ta, tb))?,
+                        .ok_or_else(|| ValueError::TypeConversion(ta, tb, Snippet::default()))?,
                     other,
                 )),
 
@@ -269,7 +283,11 @@ impl Value {
                 (ValueType::Object, ValueType::Object) => Ok((self, other)),
                 (ValueType::Range, ValueType::Range) => Ok((self, other)),
 
-                _ => Err(ValueError::TypeConversion(ta, tb)),
+                _ => Err(ValueError::TypeConversion(
+                    ta,
+                    tb,
+                    Snippet::of(&[&self, &other]),
+                )),
             }
         }
     }
@@ -278,10 +296,7 @@ impl Value {
     pub fn cast_primitive(self) -> Result<Primitive, ValueError> {
         match self {
             Value::Primitive(p) => Ok(p),
-            _ => Err(ValueError::TypeConversion(
-                self.type_of(),
-                ValueType::Primitive,
-            )),
+            _ => Err(self.conversion_error(ValueType::Primitive)),
         }
     }
 
@@ -289,11 +304,36 @@ impl Value {
     pub fn cast_decimal(self) -> Result<Number, ValueError> {
         let own_type = self.type_of();
         match self {
-            Value::Primitive(p) => match p.as_decimal() {
+            Value::Primitive(
+                p @ (Primitive::Boolean(_) | Primitive::Integer(_) | Primitive::Decimal(_)),
+            ) => match p.as_decimal() {
                 Some(Primitive::Decimal(n)) => Ok(n),
-                _ => Err(ValueError::TypeConversion(own_type, ValueType::Decimal)),
+                _ => Err(ValueError::TypeConversion(
+                    own_type,
+                    ValueType::Decimal,
+                    Snippet::default(),
+                )),
             },
-            _ => Err(ValueError::TypeConversion(own_type, ValueType::Decimal)),
+            _ => Err(self.conversion_error(ValueType::Decimal)),
+        }
+    }
+
+    /// Creates a new nil value
+    pub fn nil() -> Self {
+        Value::Primitive(Primitive::Nil)
+    }
+
+    /// Returns true if the value is nil
+    pub fn is_nil(&self) -> bool {
+        matches!(self, Value::Primitive(Primitive::Nil))
+    }
+
+    /// Replaces nil with `false`, for use in boolean contexts
+    fn nil_as_false(self) -> Self {
+        if self.is_nil() {
+            Value::boolean(false)
+        } else {
+            self
         }
     }
 
@@ -318,7 +358,11 @@ impl Value {
         let own_type = self.type_of();
         match self.as_boolean() {
             Some(Value::Primitive(Primitive::Boolean(i))) => Ok(i),
-            _ => Err(ValueError::TypeConversion(own_type, ValueType::Boolean)),
+            _ => Err(ValueError::TypeConversion(
+                own_type,
+                ValueType::Boolean,
+                Snippet::default(),
+            )),
         }
     }
 
@@ -336,11 +380,18 @@ impl Value {
     }
 
     /// Turns the value into an integer, if possible
+    /// Decimals that do not fit in an integer are not shown in the error
     pub fn cast_integer(self) -> Result<i128, ValueError> {
         let own_type = self.type_of();
-        match self.as_integer() {
-            Some(Value::Primitive(Primitive::Integer(i))) => Ok(i),
-            _ => Err(ValueError::TypeConversion(own_type, ValueType::Integer)),
+        match self {
+            Value::Primitive(
+                p @ (Primitive::Boolean(_) | Primitive::Integer(_) | Primitive::Decimal(_)),
+            ) => p.into_integer().ok_or(ValueError::TypeConversion(
+                own_type,
+                ValueType::Integer,
+                Snippet::default(),
+            )),
+            _ => Err(self.conversion_error(ValueType::Integer)),
         }
     }
 
@@ -362,6 +413,11 @@ impl Value {
         Value::Primitive(Primitive::String(value))
     }
 
+    /// Creates a new function value
+    pub fn function(value: Function) -> Self {
+        Value::Function(Box::new(value))
+    }
+
     /// Turns the value into a string, if possible
     pub fn as_string(self) -> Option<Self> {
         match self {
@@ -378,13 +434,58 @@ impl Value {
         let own_type = self.type_of();
         match self.as_string() {
             Some(Value::Primitive(Primitive::String(s))) => Ok(s),
-            _ => Err(ValueError::TypeConversion(own_type, ValueType::String)),
+            _ => Err(ValueError::TypeConversion(
+                own_type,
+                ValueType::String,
+                Snippet::default(),
+            )),
+        }
+    }
+
+    /// Creates a new bytes value
+    pub fn bytes(value: Vec<u8>) -> Self {
+        Value::Primitive(Primitive::Bytes(value))
+    }
+
+    /// Turns the value into bytes, if possible
+    /// Strings are encoded as UTF-8, and collections must only contain integers from 0 to 255
+    pub fn as_bytes(self) -> Option<Self> {
+        match self {
+            Value::Primitive(p) => p.as_bytes().map(Value::Primitive),
+            Value::Array(_) | Value::Range(_) => self
+                .cast_array()
+                .ok()?
+                .into_iter()
+                .map(|v| v.cast_integer().ok().and_then(|i| u8::try_from(i).ok()))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::bytes),
+            _ => None,
+        }
+    }
+
+    /// Turns the value into bytes, if possible
+    pub fn cast_bytes(self) -> Result<Vec<u8>, ValueError> {
+        let own_type = self.type_of();
+        match self.as_bytes() {
+            Some(Value::Primitive(Primitive::Bytes(b))) => Ok(b),
+            _ => Err(ValueError::TypeConversion(
+                own_type,
+                ValueType::Bytes,
+                Snippet::default(),
+            )),
         }
     }
 
     /// Turns the value into an array, if possible
     pub fn as_array(self) -> Option<Self> {
         match self.type_of() {
+            ValueType::Nil => Some(Value::Array(vec![])),
+            ValueType::Bytes => match self {
+                Value::Primitive(Primitive::Bytes(b)) => Some(Value::Array(
+                    b.into_iter().map(|b| Value::integer(b as i128)).collect(),
+                )),
+                _ => None,
+            },
             ValueType::Integer
             | ValueType::Decimal
             | ValueType::String
@@ -410,13 +511,18 @@ impl Value {
         let own_type = self.type_of();
         match self.as_array() {
             Some(Value::Array(a)) => Ok(a),
-            _ => Err(ValueError::TypeConversion(own_type, ValueType::Array)),
+            _ => Err(ValueError::TypeConversion(
+                own_type,
+                ValueType::Array,
+                Snippet::default(),
+            )),
         }
     }
 
     /// Turns the value into an object, if possible
     pub fn as_object(self) -> Option<Self> {
         match self {
+            Value::Primitive(Primitive::Nil) => Some(Value::Object(HashMap::new())),
             Value::Primitive(p) => Some(Value::Object(
                 [(Primitive::Integer(0), Value::Primitive(p))]
                     .into_iter()
@@ -448,7 +554,11 @@ impl Value {
         let own_type = self.type_of();
         match self.as_object() {
             Some(Value::Object(o)) => Ok(o),
-            _ => Err(ValueError::TypeConversion(own_type, ValueType::Object)),
+            _ => Err(ValueError::TypeConversion(
+                own_type,
+                ValueType::Object,
+                Snippet::default(),
+            )),
         }
     }
 
@@ -462,9 +572,52 @@ impl Value {
 
     pub fn into_range(self) -> Result<std::ops::Range<i128>, ValueError> {
         let own_type = self.type_of();
-        match self.as_range() {
-            Some(Value::Range(r)) => Ok(r),
-            _ => Err(ValueError::TypeConversion(own_type, ValueType::Range)),
+        match self {
+            Value::Range(r) => Ok(r),
+            Value::Primitive(
+                p @ (Primitive::Boolean(_) | Primitive::Integer(_) | Primitive::Decimal(_)),
+            ) => p
+                .into_integer()
+                .map(|i| i..i + 1)
+                .ok_or(ValueError::TypeConversion(
+                    own_type,
+                    ValueType::Range,
+                    Snippet::default(),
+                )),
+            _ => Err(self.conversion_error(ValueType::Range)),
+        }
+    }
+
+    /// Check if this value contains another, without cloning either
+    /// Arrays are scanned for an equal value, objects are checked by key, and strings by pattern
+    /// Ranges are checked in constant time, and contain whole numbers and the ranges inside them
+    pub fn contains(&self, other: &Value) -> Result<bool, ValueError> {
+        match self {
+            Value::Range(r) => match other {
+                Value::Range(o) => Ok(o.start >= r.start && o.end <= r.end),
+                Value::Primitive(Primitive::Decimal(_)) => {
+                    Ok(other.clone().cast_integer().is_ok_and(|i| r.contains(&i)))
+                }
+                _ => Ok(r.contains(&other.clone().cast_integer()?)),
+            },
+            Value::Array(a) => Ok(a.contains(other)),
+            Value::Object(o) => Ok(o.contains_key(&other.clone().cast_primitive()?)),
+
+            Value::Primitive(Primitive::String(a)) => {
+                let b = other.clone().cast_string()?;
+                Value::checked_regex(a, &b, |s| s)?.cast_boolean()
+            }
+
+            _ => Err(ValueError::InvalidOperationForType(self.type_of())),
+        }
+    }
+
+    /// Check if this value contains another, assuming it is sorted in natural order
+    /// Arrays are binary searched; other values are checked as with `contains`
+    pub fn contains_sorted(&self, other: &Value) -> Result<bool, ValueError> {
+        match self {
+            Value::Array(a) => Ok(a.binary_search(other).is_ok()),
+            _ => self.contains(other),
         }
     }
 
@@ -612,7 +765,7 @@ impl CheckedBitwise for Value {
 
 impl CheckedBoolean for Value {
     fn checked_logical_and(self, other: Self) -> Result<Self, ValueError> {
-        let (a, b) = self.resolve(other)?;
+        let (a, b) = self.nil_as_false().resolve(other.nil_as_false())?;
         let t = a.type_of();
         match (a, b) {
             (Value::Primitive(a), Value::Primitive(b)) => {
@@ -623,7 +776,7 @@ impl CheckedBoolean for Value {
     }
 
     fn checked_logical_or(self, other: Self) -> Result<Self, ValueError> {
-        let (a, b) = self.resolve(other)?;
+        let (a, b) = self.nil_as_false().resolve(other.nil_as_false())?;
         let t = a.type_of();
         match (a, b) {
             (Value::Primitive(a), Value::Primitive(b)) => {
@@ -642,6 +795,10 @@ impl CheckedBoolean for Value {
     }
 
     fn checked_eq(self, other: Self) -> Result<Self, ValueError> {
+        if self.is_nil() || other.is_nil() {
+            return Ok(Value::boolean(self.is_nil() && other.is_nil()));
+        }
+
         let (a, b) = self.resolve(other)?;
         Ok(match (a, b) {
             (Value::Primitive(a), Value::Primitive(b)) => a.checked_eq(b).map(Value::Primitive)?,
@@ -653,6 +810,10 @@ impl CheckedBoolean for Value {
     }
 
     fn checked_ne(self, other: Self) -> Result<Self, ValueError> {
+        if self.is_nil() || other.is_nil() {
+            return Ok(Value::boolean(self.is_nil() != other.is_nil()));
+        }
+
         let (a, b) = self.resolve(other)?;
         Ok(match (a, b) {
             (Value::Primitive(a), Value::Primitive(b)) => a.checked_ne(b).map(Value::Primitive)?,
@@ -755,18 +916,7 @@ impl CheckedMatching for Value {
     }
 
     fn checked_contains(self, other: Self) -> Result<Self, ValueError> {
-        Ok(match self {
-            Value::Range(v) => Value::boolean(v.contains(&other.cast_integer()?)),
-            Value::Array(v) => Value::boolean(v.contains(&other)),
-            Value::Object(v) => Value::boolean(v.contains_key(&other.cast_primitive()?)),
-
-            Value::Primitive(Primitive::String(a)) => {
-                let b = other.cast_string()?;
-                Value::checked_regex(&a, &b, |s| s)?
-            }
-
-            _ => return Err(ValueError::InvalidOperationForType(self.type_of())),
-        })
+        self.contains(&other).map(Value::boolean)
     }
 
     fn checked_starts_with(self, other: Self) -> Result<Self, ValueError> {
@@ -884,6 +1034,8 @@ impl std::fmt::Display for Value {
     }
 }
 
+/// Object entries are written in key order, so equal objects serialize to the same bytes
+/// regardless of the order their keys were inserted in
 impl SerializeToBytes for Value {
     fn serialize_into_bytes(self) -> Vec<u8> {
         let mut bytes = vec![];
@@ -907,7 +1059,10 @@ impl SerializeToBytes for Value {
             Value::Object(o) => {
                 bytes.push(ValueType::Object as u8);
                 bytes.extend(o.len().serialize_into_bytes());
-                for (k, v) in o {
+
+                let mut entries = o.into_iter().collect::<Vec<_>>();
+                entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+                for (k, v) in entries {
                     bytes.extend(k.serialize_into_bytes());
                     bytes.extend(v.serialize_into_bytes());
                 }
@@ -924,7 +1079,7 @@ impl SerializeToBytes for Value {
     }
 
     fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
+        bytes: &mut ByteReader<'_>,
     ) -> Result<Self, crate::traits::ByteDecodeError> {
         let ty = u8::deserialize_from_bytes(bytes)?;
         match ValueType::from_u8(ty) {
@@ -933,7 +1088,7 @@ impl SerializeToBytes for Value {
             }
 
             Some(ValueType::Function) => {
-                Ok(Value::Function(Function::deserialize_from_bytes(bytes)?))
+                Ok(Value::function(Function::deserialize_from_bytes(bytes)?))
             }
             Some(ValueType::Array) => Ok(Value::Array(Vec::deserialize_from_bytes(bytes)?)),
             Some(ValueType::Object) => {
@@ -1026,3 +1181,58 @@ where
 
     Ok(regex.build()?)
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        value::{Number, Value},
+        vm::value_source::ValueSource,
+        Lavendeux,
+    };
+
+    #[test]
+    fn test_nil_semantics() {
+        let mut lav = Lavendeux::new();
+        let checks = [
+            // nil is only equal to itself
+            "nil == nil",
+            "nil === nil",
+            "nil != 0",
+            "nil != false",
+            "nil != ''",
+            // It is false in boolean contexts
+            "!nil",
+            "(nil || true) == true",
+            "(nil && true) == false",
+            "(nil as bool) == false",
+            // It casts to an empty collection, and to the string `nil`
+            "(nil as string) == 'nil'",
+            "(nil as array) == []",
+            "len(nil as object) == 0",
+            "len(nil) == 0",
+            // And it has its own type
+            "nil is nil",
+            "nil is primitive",
+            "!(0 is nil)",
+            "!(false is nil)",
+        ];
+        for check in checks {
+            assert_eq!(lav.run(check).unwrap(), Value::boolean(true), "{check}");
+        }
+
+        // nil is never converted to a number implicitly, so it cannot be ordered or used in arithmetic
+        for error in ["nil < 1", "1 >= nil", "nil > nil", "nil + 1", "nil as int"] {
+            assert!(lav.run(error).is_err(), "{error}");
+        }
+    }
+
+    #[test]
+    fn test_value_size() {
+        use std::mem::size_of;
+
+        // Stack entries should fit in a cache line; large, rare variants are boxed
+        assert!(size_of::<Value>() <= 64);
+        assert!(size_of::<ValueSource>() <= 64);
+        assert!(size_of::<Number>() <= 48);
+    }
+}
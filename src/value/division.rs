@@ -0,0 +1,95 @@
+use std::cell::Cell;
+
+use super::{number::Number, primitive::Primitive, ValueError};
+
+/// What dividing by zero, with `/` or `%`, results in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DivisionByZero {
+    /// Dividing by zero is an error
+    #[default]
+    Error,
+
+    /// Dividing a decimal by zero gives the largest decimal with the sign of the dividend,
+    /// since decimals cannot represent infinity
+    /// Integer division, remainders and `0.0 / 0` are still errors
+    Infinity,
+
+    /// Dividing by zero gives nil
+    Nil,
+}
+
+thread_local! {
+    static DIVISION_BY_ZERO: Cell<DivisionByZero> = const { Cell::new(DivisionByZero::Error) };
+}
+
+/// Set what dividing by zero results in on the current thread
+/// Returns the previous policy
+pub fn set_division_by_zero(policy: DivisionByZero) -> DivisionByZero {
+    DIVISION_BY_ZERO.with(|p| p.replace(policy))
+}
+
+/// Get what dividing by zero results in on the current thread; see `set_division_by_zero`
+pub fn division_by_zero() -> DivisionByZero {
+    DIVISION_BY_ZERO.with(|p| p.get())
+}
+
+/// Check if a resolved divisor is zero
+/// Booleans are not checked, since dividing them is a logical operation
+pub(super) fn is_zero_divisor(divisor: &Primitive) -> bool {
+    match divisor {
+        Primitive::Integer(i) => *i == 0,
+        Primitive::Decimal(d) => d.is_zero(),
+        _ => false,
+    }
+}
+
+/// The result of dividing `dividend` by zero, according to the current policy
+/// `remainder` is true for `%`, which never gives infinity
+pub(super) fn divide_by_zero(
+    dividend: Primitive,
+    remainder: bool,
+) -> Result<Primitive, ValueError> {
+    match (division_by_zero(), dividend) {
+        (DivisionByZero::Nil, _) => Ok(Primitive::Nil),
+        (DivisionByZero::Infinity, Primitive::Decimal(d)) if !remainder && !d.is_zero() => {
+            let (value, symbol, precision) = d.decompose();
+            let limit = if value.is_negative() {
+                fpdec::Decimal::MIN
+            } else {
+                fpdec::Decimal::MAX
+            };
+            Ok(Primitive::Decimal(Number::new(limit, symbol, precision)))
+        }
+        _ => Err(ValueError::DivideByZero),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_division_by_zero() {
+        let mut lav = Lavendeux::new();
+        lav.set_division_by_zero(DivisionByZero::Nil);
+        lav.run("x = 0").unwrap();
+        assert_eq!(lav.run("5 / x").unwrap().to_string(), "nil");
+        assert_eq!(lav.run("5.5 % x").unwrap().to_string(), "nil");
+        assert_eq!(lav.run("x /= 0").unwrap().to_string(), "nil");
+        assert_eq!(lav.run("6 / 3").unwrap().to_string(), "2");
+
+        // Only decimal division saturates
+        lav.set_division_by_zero(DivisionByZero::Infinity);
+        assert_eq!(lav.run("1.5 / 0 > 10 ** 30").unwrap().to_string(), "true");
+        assert_eq!(
+            lav.run("-1.5 / 0 < -(10 ** 30)").unwrap().to_string(),
+            "true"
+        );
+        assert!(lav.run("1 / 0").is_err());
+
+        lav.set_division_by_zero(DivisionByZero::Error);
+        let err = lav.run("1 % 0").unwrap_err();
+        assert!(err.to_string().contains("Division by zero"));
+    }
+}
@@ -1,8 +1,75 @@
-use super::ValueType;
+use super::{NumberSymbol, Value, ValueType};
+
+/// The longest rendering of a value shown in an error, in characters
+const SNIPPET_LEN: usize = 32;
+
+/// A short rendering of the values involved in an error, such as `[1, 2, 3]`
+/// Long values are truncated, and the snippet is empty if the values were not available
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Snippet(String);
+
+impl Snippet {
+    /// Render the given values, truncating each one
+    pub fn of(values: &[&Value]) -> Self {
+        Self(
+            values
+                .iter()
+                .map(|v| v.snippet())
+                .collect::<Vec<_>>()
+                .join(" and "),
+        )
+    }
+}
+
+impl std::fmt::Display for Snippet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.is_empty() {
+            true => Ok(()),
+            false => write!(f, ": {}", self.0),
+        }
+    }
+}
+
+/// Collects at most `SNIPPET_LEN` characters, so that huge values are never fully rendered
+struct SnippetWriter {
+    text: String,
+    len: usize,
+    truncated: bool,
+}
+
+impl std::fmt::Write for SnippetWriter {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for c in s.chars() {
+            if self.len == SNIPPET_LEN {
+                self.truncated = true;
+                return Err(std::fmt::Error);
+            }
+            self.text.push(c);
+            self.len += 1;
+        }
+        Ok(())
+    }
+}
+
+impl Value {
+    /// Render the value as it would appear in a script, truncated for use in error messages
+    pub fn snippet(&self) -> String {
+        let mut writer = SnippetWriter {
+            text: String::new(),
+            len: 0,
+            truncated: false,
+        };
+        let _ = std::fmt::write(&mut writer, format_args!("{self:?}"));
+        match writer.truncated {
+            true => format!("{}...", writer.text),
+            false => writer.text,
+        }
+    }
+}
 
 /// An error that occurs during value operations
 #[rustfmt::skip]
-#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[derive(thiserror::Error, Debug, Clone, PartialEq, strum_macros::IntoStaticStr)]
 pub enum ValueError {
     //
     // Errors during casting and arithmetic operations
@@ -17,8 +84,26 @@ pub enum ValueError {
     InvalidOperationForType(ValueType),
 
     /// Caused by converting a value to a type that is not supported
-    #[error("Cannot resolve values of type {0} and {1}")]
-    TypeConversion(ValueType, ValueType),
+    /// Includes the values involved, when they are known
+    #[error("Cannot resolve values of type {0} and {1}{2}")]
+    TypeConversion(ValueType, ValueType, Snippet),
+
+    /// Caused by arithmetic on amounts in two different currencies, such as `$1 + 1€`
+    /// Hosts can convert between them with a `CurrencyConverter`
+    #[error("Cannot mix currencies `{0}` and `{1}`")]
+    CurrencyMismatch(NumberSymbol, NumberSymbol),
+
+    /// Caused by taking the minimum, maximum or mean of an empty array or range
+    #[error("Cannot find the {0} of an empty collection")]
+    EmptyCollection(&'static str),
+
+    /// Caused by dividing by zero, with `/` or `%`, unless another policy is set
+    #[error("Division by zero")]
+    DivideByZero,
+
+    /// Caused by arithmetic on two booleans, or negating one, while booleans are strict
+    #[error("Cannot use `{0}` on booleans; use a logical operator, or convert them with `as int`")]
+    BooleanArithmetic(&'static str),
 
     /// Caused by attempting to use the bigliest memory
     #[error("{0}")]
@@ -29,12 +114,14 @@ pub enum ValueError {
     //
 
     /// Caused by attempting to access a value index that does not exist
-    #[error("Key not found in object")]
-    KeyNotFound,
+    /// Includes the key that was not found
+    #[error("Key {0} not found in object")]
+    KeyNotFound(String),
 
     /// Caused by attempting to access a value index that does not exist
-    #[error("Index out of bounds")]
-    IndexOutOfBounds,
+    /// Includes the index, or range of indices, and the length of the value
+    #[error("Index {0} is out of bounds for a length of {1}")]
+    IndexOutOfBounds(String, i128),
 
     /// Caused by attempting to index into a value that does not support indexing
     #[error("Cannot index into {0}")]
@@ -62,3 +149,50 @@ pub enum ValueError {
     #[error("Invalid regex literal")]
     RegexError(#[from] regex::Error),
 }
+
+impl ValueError {
+    /// Get the stable code identifying the kind of error, such as `E0505` for division by zero
+    /// Value errors use codes from `E0500` to `E0599`
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValueError::ArithmeticOverflow => "E0500",
+            ValueError::InvalidOperationForType(_) => "E0501",
+            ValueError::TypeConversion(_, _, _) => "E0502",
+            ValueError::CurrencyMismatch(_, _) => "E0503",
+            ValueError::EmptyCollection(_) => "E0504",
+            ValueError::DivideByZero => "E0505",
+            ValueError::BooleanArithmetic(_) => "E0506",
+            ValueError::MemoryAllocation(_) => "E0507",
+            ValueError::KeyNotFound(_) => "E0508",
+            ValueError::IndexOutOfBounds(_, _) => "E0509",
+            ValueError::CannotIndexInto(_) => "E0510",
+            ValueError::CannotIndexUsing(_, _) => "E0511",
+            ValueError::ReadOnlyIndexing => "E0512",
+            ValueError::InvalidRegexFlag(_) => "E0513",
+            ValueError::RegexError(_) => "E0514",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_error_snippets() {
+        let mut lav = Lavendeux::new();
+        let err = |lav: &mut Lavendeux, script: &str| lav.run(script).unwrap_err().to_string();
+        lav.run("a = [1, 2, 3]\no = {'a': 1}").unwrap();
+        assert!(err(&mut lav, "a[5]").contains("Index 5 is out of bounds for a length of 3"));
+        assert!(err(&mut lav, "a[1..9]").contains("Index 1..9 is out of bounds"));
+        assert!(err(&mut lav, "o['b']").contains("Key `b` not found"));
+        assert!(err(&mut lav, "'abc' as int").contains("string and int: `abc`"));
+        assert!(err(&mut lav, "a + nil").contains("array and nil: [1, 2, 3] and nil"));
+
+        // Long values are truncated
+        lav.run("b = (0..1000) as array").unwrap();
+        let message = err(&mut lav, "b as int");
+        assert!(message.contains(": [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 1..."));
+        assert!(!message.contains("999"));
+    }
+}
@@ -1,12 +1,16 @@
 use super::{Value, ValueType};
 use crate::{
-    compiler::{DebugProfile, FunctionDocs},
-    traits::{ByteDecodeError, SerializeToBytes},
+    compiler::{qualified_name, DebugProfile, FunctionDocs, HashString},
+    traits::{ByteDecodeError, ByteReader, SafeVecAlloc, SerializeToBytes},
     vm::{
+        error::RuntimeError,
         memory_manager::{MemoryManager, Slot},
+        read_constant_pool,
         value_source::ValueSource,
+        ConstantPool, VirtualMachine, OPCODE_SET_VERSION,
     },
 };
+use std::sync::{Arc, OnceLock};
 
 /// An argument to a function.
 #[derive(Debug, Clone)]
@@ -30,6 +34,10 @@ pub struct Function {
     /// The name of the function
     pub name_hash: u64,
 
+    /// The namespace the function was defined in; empty for the global namespace
+    /// Functions with the same name from different namespaces collide when loaded together
+    pub namespace: String,
+
     /// The return type of the function
     pub returns: ValueType,
 
@@ -43,7 +51,109 @@ pub struct Function {
     pub docs: FunctionDocs,
 
     /// The bytecode of the function
-    pub body: Vec<u8>,
+    pub body: FunctionBody,
+}
+
+/// The bytes of a compiled module, shared by the functions loaded from it
+/// Such as a `Vec<u8>`, or a memory-mapped file
+pub type ModuleBytes = dyn AsRef<[u8]> + Send + Sync;
+
+/// The bytecode of a function
+/// Dereferences to the bytecode, wherever it is held
+#[derive(Clone)]
+pub enum FunctionBody {
+    /// Bytecode held in memory, shared by clones of the function and the contexts running it
+    Loaded {
+        /// The bytecode
+        code: Arc<[u8]>,
+
+        /// The constant pool decoded from the bytecode
+        constants: ConstantPool,
+    },
+
+    /// Bytecode left in the module the function was loaded from, by `StdFunctionSet::from_shared_bytes`
+    /// It is copied out of the module the first time the function is called, and kept for later calls
+    Mapped {
+        /// The module the function was loaded from
+        module: Arc<ModuleBytes>,
+
+        /// Where the bytecode is in the module
+        range: std::ops::Range<usize>,
+
+        /// The bytecode and its constant pool, once the function has been called;
+        /// shared by clones of the function
+        loaded: Arc<OnceLock<(Arc<[u8]>, ConstantPool)>>,
+    },
+}
+
+impl FunctionBody {
+    /// Whether the bytecode has been copied into memory
+    /// Only false for functions from a shared module that have not been called yet
+    pub fn is_loaded(&self) -> bool {
+        match self {
+            Self::Loaded { .. } => true,
+            Self::Mapped { loaded, .. } => loaded.get().is_some(),
+        }
+    }
+
+    /// Hold bytecode in memory, decoding its constant pool
+    /// Bytecode with a malformed pool is given an empty one, and fails when it reads a constant
+    pub fn new(code: Arc<[u8]>) -> Self {
+        let constants = read_constant_pool(&code).unwrap_or_default();
+        Self::Loaded { code, constants }
+    }
+
+    /// Get shared handles to the bytecode and its constant pool, to be run
+    /// Bytecode left in a module is copied out on the first call, and the copy is reused after that
+    pub fn shared(&self) -> (Arc<[u8]>, ConstantPool) {
+        match self {
+            Self::Loaded { code, constants } => (code.clone(), constants.clone()),
+            Self::Mapped {
+                module,
+                range,
+                loaded,
+            } => loaded
+                .get_or_init(|| {
+                    let code: Arc<[u8]> = Arc::from(&(**module).as_ref()[range.clone()]);
+                    let constants = read_constant_pool(&code).unwrap_or_default();
+                    (code, constants)
+                })
+                .clone(),
+        }
+    }
+}
+
+impl std::ops::Deref for FunctionBody {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Loaded { code, .. } => code,
+            Self::Mapped { module, range, .. } => &(**module).as_ref()[range.clone()],
+        }
+    }
+}
+
+impl From<Vec<u8>> for FunctionBody {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes.into())
+    }
+}
+
+impl std::fmt::Debug for FunctionBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Loaded { code, constants } => f
+                .debug_struct("Loaded")
+                .field("code", code)
+                .field("constants", constants)
+                .finish(),
+            Self::Mapped { range, loaded, .. } => f
+                .debug_struct("Mapped")
+                .field("range", range)
+                .field("loaded", &loaded.get().is_some())
+                .finish(),
+        }
+    }
 }
 
 impl PartialEq for Function {
@@ -65,33 +175,135 @@ impl PartialOrd for Function {
     }
 }
 
+/// What to do when a loaded function has the same name as an existing global
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CollisionPolicy {
+    /// The loaded function replaces the existing global
+    #[default]
+    Replace,
+
+    /// The existing global is kept, and the loaded function is ignored
+    Skip,
+
+    /// Nothing is loaded, and the name of the first colliding function is returned as an error
+    Error,
+}
+
+/// An error that can occur while loading a library of functions
+#[derive(Debug, thiserror::Error)]
+pub enum LibraryError {
+    /// The library file could not be read
+    #[error("Could not read library: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The library file is not a valid function set
+    #[error("Invalid library: {0}")]
+    Decode(#[from] ByteDecodeError),
+
+    /// A function in the library is already defined by another namespace, under `CollisionPolicy::Error`
+    #[error("Library function `{0}` collides with `{1}`")]
+    Collision(String, String),
+}
+
 /// Represents a set of compiled functions.
 /// This is used to store functions in a memory manager.
 /// The stdlib works this way
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct StdFunctionSet {
     functions: Vec<Function>,
 }
 impl StdFunctionSet {
+    /// Create an empty function set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a function to the set, replacing any function with the same name
+    pub fn insert(&mut self, function: Function) {
+        self.functions.retain(|f| f.name_hash != function.name_hash);
+        self.functions.push(function);
+    }
+
+    /// Run a compiled program in an empty VM, and collect the functions it defines
+    /// This is how the compiler's `-F` flag builds modules, such as the stdlib
+    pub fn from_bytecode(
+        bytecode: Vec<u8>,
+        profile: Option<DebugProfile>,
+    ) -> Result<Self, RuntimeError> {
+        let mut vm = VirtualMachine::new();
+        vm.run(bytecode, profile)?;
+        Ok(Self::from_mem(&vm.destroy()))
+    }
+
     /// Populate a memory manager with the functions in this set.
+    #[doc(hidden)]
     pub fn into_mem(self, mem: &mut MemoryManager) {
         for function in self.functions {
             mem.write_global(
                 function.name_hash,
-                ValueSource::Literal(Value::Function(function)),
+                ValueSource::Literal(Value::function(function)),
+                true,
+            );
+        }
+    }
+
+    /// Populate a memory manager with the functions in this set, resolving collisions with
+    /// existing globals according to the policy
+    #[doc(hidden)]
+    pub fn into_mem_with(
+        self,
+        mem: &mut MemoryManager,
+        policy: CollisionPolicy,
+    ) -> Result<(), LibraryError> {
+        // The name of the global a function collides with; the same function loaded again does not
+        let collision = |mem: &MemoryManager, f: &Function| match mem.read(f.name_hash)? {
+            ValueSource::Literal(Value::Function(existing)) => {
+                (existing.identity_hash() != f.identity_hash()).then(|| existing.qualified_name())
+            }
+            _ => Some(f.docs.name.clone()),
+        };
+
+        if policy == CollisionPolicy::Error {
+            for function in &self.functions {
+                if let Some(existing) = collision(mem, function) {
+                    return Err(LibraryError::Collision(function.qualified_name(), existing));
+                }
+            }
+        }
+
+        for function in self.functions {
+            if policy == CollisionPolicy::Skip && collision(mem, &function).is_some() {
+                continue;
+            }
+
+            if let Some(profile) = &function.debug {
+                mem.record_names(profile);
+            }
+
+            mem.write_global(
+                function.name_hash,
+                ValueSource::Literal(Value::function(function)),
                 true,
             );
         }
+
+        Ok(())
+    }
+
+    /// Get the functions in this set.
+    pub fn functions(&self) -> &[Function] {
+        &self.functions
     }
 
     /// Create a new function set from the functions in a memory manager.
+    #[doc(hidden)]
     pub fn from_mem(mem: &MemoryManager) -> Self {
         let mut functions = vec![];
         for slot in mem.all_globals() {
             match slot {
                 Slot::Occupied { value, .. } => {
                     if let ValueSource::Literal(Value::Function(function)) = value {
-                        functions.push(function.clone());
+                        functions.push(function.as_ref().clone());
                     }
                 }
                 _ => {}
@@ -100,6 +312,71 @@ impl StdFunctionSet {
 
         Self { functions }
     }
+
+    /// Load a function set from a compiled module, leaving the function bodies in it
+    /// Each body is copied out of the module the first time its function is called, so functions
+    /// that are never called cost no more memory than their signatures and docs
+    ///
+    /// The module is kept alive by the functions loaded from it
+    pub fn from_shared_bytes(module: Arc<ModuleBytes>) -> Result<Self, ByteDecodeError> {
+        let bytes = &mut ByteReader::new((*module).as_ref());
+        Self::read_header(bytes)?;
+
+        let len = u64::deserialize_from_bytes(bytes)?;
+        if len > bytes.len() as u64 {
+            return Err(ByteDecodeError::UnexpectedEnd("StdFunctionSet".to_string()));
+        }
+
+        let mut functions = Vec::safe_alloc(len as usize)?;
+        for _ in 0..len {
+            functions.push(Function::deserialize_with_body(bytes, |bytes| {
+                let len = bytes.read_prefixed("Function")?.len();
+                Ok(FunctionBody::Mapped {
+                    module: module.clone(),
+                    range: bytes.offset() - len..bytes.offset(),
+                    loaded: Default::default(),
+                })
+            })?);
+        }
+
+        Ok(Self { functions })
+    }
+
+    /// Memory-map a compiled module, such as a .lbc file written by the compiler's `-F` flag,
+    /// and load its functions with `from_shared_bytes`
+    /// The OS reads the file in as it is used, so large modules cost little memory up front
+    ///
+    /// The file must not be modified while any function loaded from it is alive
+    #[cfg(feature = "mmap")]
+    pub fn map_file(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+
+        // Safety: the map is only read, and the file must not be modified while it is mapped
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Self::from_shared_bytes(Arc::new(map))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Check the magic bytes and opcode set version at the start of a module
+    fn read_header(bytes: &mut ByteReader<'_>) -> Result<(), ByteDecodeError> {
+        let magic = bytes.read_slice(MODULE_MAGIC.len(), "StdFunctionSet").ok();
+        if magic != Some(&MODULE_MAGIC[..]) {
+            return Err(ByteDecodeError::MalformedData(
+                "StdFunctionSet".to_string(),
+                "Missing module header; it may have been compiled by an older version, and must be recompiled".to_string(),
+            ));
+        }
+
+        let version = u16::deserialize_from_bytes(bytes)?;
+        if version != OPCODE_SET_VERSION {
+            return Err(ByteDecodeError::MalformedData(
+                "StdFunctionSet".to_string(),
+                format!("Module uses opcode set v{version}, but this version expects v{OPCODE_SET_VERSION}; it must be recompiled"),
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 impl SerializeToBytes for FunctionArgument {
@@ -115,7 +392,7 @@ impl SerializeToBytes for FunctionArgument {
     }
 
     fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
+        bytes: &mut ByteReader<'_>,
     ) -> Result<Self, crate::traits::ByteDecodeError> {
         let name_hash = u64::deserialize_from_bytes(bytes)?;
         let ty = u8::deserialize_from_bytes(bytes)?;
@@ -142,6 +419,7 @@ impl SerializeToBytes for Function {
         let mut bytes = Vec::new();
 
         bytes.extend(self.name_hash.serialize_into_bytes());
+        bytes.extend(self.namespace.serialize_into_bytes());
         bytes.push(self.returns as u8);
         bytes.extend(self.expects.serialize_into_bytes());
         bytes.extend(self.debug.serialize_into_bytes());
@@ -152,9 +430,37 @@ impl SerializeToBytes for Function {
     }
 
     fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
+        bytes: &mut ByteReader<'_>,
     ) -> Result<Self, crate::traits::ByteDecodeError> {
+        Self::deserialize_with_body(bytes, FunctionBody::deserialize_from_bytes)
+    }
+}
+
+/// Serialized like a `Vec<u8>`; always decoded as `FunctionBody::Loaded`
+/// The constant pool is part of the bytecode, so it is not written separately
+impl SerializeToBytes for FunctionBody {
+    fn serialize_into_bytes(self) -> Vec<u8> {
+        let mut bytes = self.len().serialize_into_bytes();
+        bytes.extend_from_slice(&self);
+        bytes
+    }
+
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
+        // Read as one slice, rather than decoding the body byte by byte
+        let code: Arc<[u8]> = bytes.read_prefixed("Function")?.into();
+        let constants = read_constant_pool(&code)?;
+        Ok(Self::Loaded { code, constants })
+    }
+}
+
+impl Function {
+    /// Decode a function, reading its body with `read_body`
+    fn deserialize_with_body(
+        bytes: &mut ByteReader<'_>,
+        read_body: impl FnOnce(&mut ByteReader<'_>) -> Result<FunctionBody, ByteDecodeError>,
+    ) -> Result<Self, ByteDecodeError> {
         let name_hash = u64::deserialize_from_bytes(bytes)?;
+        let namespace = String::deserialize_from_bytes(bytes)?;
         let returns = u8::deserialize_from_bytes(bytes)?;
         let returns = ValueType::from_u8(returns).ok_or_else(|| {
             ByteDecodeError::MalformedData(
@@ -165,10 +471,11 @@ impl SerializeToBytes for Function {
         let expects = Vec::<FunctionArgument>::deserialize_from_bytes(bytes)?;
         let debug = Option::<DebugProfile>::deserialize_from_bytes(bytes)?;
         let docs = FunctionDocs::deserialize_from_bytes(bytes)?;
-        let body = Vec::<u8>::deserialize_from_bytes(bytes)?;
+        let body = read_body(bytes)?;
 
         Ok(Self {
             name_hash,
+            namespace,
             returns,
             expects,
             debug,
@@ -178,22 +485,176 @@ impl SerializeToBytes for Function {
     }
 }
 
+/// Magic bytes at the start of a serialized function set
+const MODULE_MAGIC: [u8; 4] = *b"LBC\0";
+
+/// Serialized as a header (magic bytes, opcode set version), followed by the functions
+/// Sets compiled against a different opcode set are refused, and must be recompiled
+///
+/// Functions are written in order of their name hash, so a set serializes to the same bytes
+/// regardless of the order its functions were defined in
+impl FromIterator<Function> for StdFunctionSet {
+    fn from_iter<I: IntoIterator<Item = Function>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for function in iter {
+            set.insert(function);
+        }
+        set
+    }
+}
+
 impl SerializeToBytes for StdFunctionSet {
-    fn serialize_into_bytes(self) -> Vec<u8> {
-        self.functions.serialize_into_bytes()
+    fn serialize_into_bytes(mut self) -> Vec<u8> {
+        self.functions.sort_by_key(|function| function.name_hash);
+
+        let mut bytes = MODULE_MAGIC.to_vec();
+        bytes.extend(OPCODE_SET_VERSION.serialize_into_bytes());
+        bytes.extend(self.functions.serialize_into_bytes());
+        bytes
     }
 
     fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
+        bytes: &mut ByteReader<'_>,
     ) -> Result<Self, crate::traits::ByteDecodeError> {
+        Self::read_header(bytes)?;
         Ok(Self {
             functions: Vec::<Function>::deserialize_from_bytes(bytes)?,
         })
     }
 }
 
+impl Function {
+    /// The name of the function, qualified by its namespace as `namespace::name`
+    pub fn qualified_name(&self) -> String {
+        qualified_name(&self.namespace, &self.docs.name)
+    }
+
+    /// Identifies the function by its name and namespace
+    /// Functions with the same name from different namespaces have different identities
+    pub fn identity_hash(&self) -> u64 {
+        self.docs.name.hash_in(&self.namespace)
+    }
+}
+
 impl std::fmt::Display for Function {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.docs.signature)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{value::Primitive, Lavendeux};
+
+    #[test]
+    fn test_canonical_serialization() {
+        // Objects with the same entries, inserted in opposite orders
+        let entry = |i: i128| (Primitive::Integer(i), Value::string(i.to_string()));
+        let forward = Value::Object((0..32).map(entry).collect());
+        let reverse = Value::Object((0..32).rev().map(entry).collect());
+        let bytes = forward.clone().serialize_into_bytes();
+        assert_eq!(bytes, reverse.serialize_into_bytes());
+        assert_eq!(Value::from_bytes(&bytes), Ok(forward));
+
+        // Function sets with the same functions, written to memory in opposite orders
+        let (profile, bytecode) = Lavendeux::new()
+            .compile("f(x) = x + 1\ng(x) = x * 2\nh(x) = x - 3")
+            .unwrap();
+        let mut vm = VirtualMachine::new();
+        vm.run(bytecode, Some(profile)).unwrap();
+        let forward = StdFunctionSet::from_mem(&vm.destroy());
+
+        let mut mem = MemoryManager::new();
+        for function in forward.functions().iter().rev() {
+            let value = ValueSource::Literal(Value::function(function.clone()));
+            mem.write_global(function.name_hash, value, true);
+        }
+        let reverse = StdFunctionSet::from_mem(&mem);
+        assert_ne!(forward.functions()[0], reverse.functions()[0]);
+
+        let bytes = forward.serialize_into_bytes();
+        assert_eq!(bytes, reverse.serialize_into_bytes());
+
+        let set = StdFunctionSet::from_bytes(&bytes).unwrap();
+        assert_eq!(set.functions().len(), 3);
+        assert_eq!(set.serialize_into_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_shared_module() {
+        let (profile, bytecode) = Lavendeux::new()
+            .compile("f(x) = x + 1\ng(x) = x * 2")
+            .unwrap();
+        let mut vm = VirtualMachine::new();
+        vm.run(bytecode, Some(profile)).unwrap();
+        let module = StdFunctionSet::from_mem(&vm.destroy()).serialize_into_bytes();
+
+        // Bodies stay in the module until each function is called
+        let set = StdFunctionSet::from_shared_bytes(Arc::new(module.clone())).unwrap();
+        assert!(set.functions().iter().all(|f| !f.body.is_loaded()));
+        assert_eq!(set.clone().serialize_into_bytes(), module);
+
+        let mut vm = VirtualMachine::new();
+        vm.load_functions(set);
+        let (profile, bytecode) = Lavendeux::new().compile("g(21)").unwrap();
+        assert_eq!(vm.run(bytecode, Some(profile)).unwrap(), Value::integer(42));
+
+        let set = StdFunctionSet::from_mem(&vm.destroy());
+        let loaded = set
+            .functions()
+            .iter()
+            .filter(|f| f.body.is_loaded())
+            .map(|f| f.docs.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(loaded, vec!["g"]);
+        assert_eq!(set.serialize_into_bytes(), module);
+
+        // Truncated modules are refused up front, rather than when a function is called
+        let truncated = Arc::new(module[..module.len() - 1].to_vec());
+        assert!(StdFunctionSet::from_shared_bytes(truncated).is_err());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_map_file() {
+        let (profile, bytecode) = Lavendeux::new().compile("triple(x) = x * 3").unwrap();
+        let mut vm = VirtualMachine::new();
+        vm.run(bytecode, Some(profile)).unwrap();
+        let module = StdFunctionSet::from_mem(&vm.destroy()).serialize_into_bytes();
+
+        let path = std::env::temp_dir().join(format!("lav_map_{}.lbc", std::process::id()));
+        std::fs::write(&path, module).unwrap();
+        let set = StdFunctionSet::map_file(&path).unwrap();
+
+        let mut lav = Lavendeux::new();
+        lav.load_functions(set);
+        assert_eq!(lav.run("triple(14)").unwrap(), Value::integer(42));
+
+        // The file cannot change while it is mapped
+        drop(lav);
+        std::fs::write(&path, b"not a module").unwrap();
+        assert!(StdFunctionSet::map_file(&path).is_err());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_function_set_builder() {
+        let mut lav = Lavendeux::new();
+        let first = lav.compile_functions("f(x) = x + 1\ng(x) = x * 2").unwrap();
+        let second = lav.compile_functions("f(x) = x - 1").unwrap();
+
+        // Later functions replace earlier ones with the same name
+        let set = first
+            .functions()
+            .iter()
+            .chain(second.functions())
+            .cloned()
+            .collect::<StdFunctionSet>();
+        assert_eq!(set.functions().len(), 2);
+
+        let mut lav = Lavendeux::new();
+        lav.load_functions(set);
+        assert_eq!(lav.run("f(10) + g(1)").unwrap(), Value::integer(11));
+    }
+}
@@ -1,13 +1,29 @@
 use crate::traits::SafeVecAlloc;
 
-use super::{Primitive, Value, ValueError, ValueType};
+use super::{Primitive, Snippet, Value, ValueError, ValueType};
+
+/// The error for indices that fall outside of a value of the given length
+fn out_of_bounds(indices: &std::ops::Range<i128>, len: i128) -> ValueError {
+    let index = match indices.end - indices.start {
+        1 => indices.start.to_string(),
+        _ => format!("{}..{}", indices.start, indices.end),
+    };
+    ValueError::IndexOutOfBounds(index, len)
+}
 
+/// The result of indexing into a value, borrowing from it where possible
 pub enum ValueIndexResult<'a> {
+    /// A mutable borrow of the indexed value
     Mutable(&'a mut Value),
+
+    /// A borrow of the indexed value
     Immutable(&'a Value),
+
+    /// A new value, for indices that do not point to a single stored value, such as slices
     Owned(Value),
 }
 impl ValueIndexResult<'_> {
+    /// Turns the result into an owned value, cloning it if it was borrowed
     pub fn into_value(self) -> Value {
         match self {
             ValueIndexResult::Mutable(value) => value.clone(),
@@ -16,6 +32,7 @@ impl ValueIndexResult<'_> {
         }
     }
 
+    /// Get a reference to the indexed value
     pub fn value(&self) -> &Value {
         match self {
             ValueIndexResult::Mutable(value) => value,
@@ -25,11 +42,43 @@ impl ValueIndexResult<'_> {
     }
 }
 
+impl<'a> ValueIndexResult<'a> {
+    /// Like `ref_index`, but keeps borrowing from the original value instead of from this result
+    /// Used to follow a chain of indices
+    pub fn into_ref_index(self, index: Value) -> Result<ValueIndexResult<'a>, ValueError> {
+        match self {
+            ValueIndexResult::Mutable(value) => (*value).ref_index(index),
+            ValueIndexResult::Immutable(value) => value.ref_index(index),
+            ValueIndexResult::Owned(value) => value.into_index(index).map(ValueIndexResult::Owned),
+        }
+    }
+
+    /// Like `mut_index`, but keeps borrowing from the original value instead of from this result
+    /// Used to follow a chain of indices
+    pub fn into_mut_index(self, index: Value) -> Result<ValueIndexResult<'a>, ValueError> {
+        match self {
+            ValueIndexResult::Mutable(value) => value.mut_index(index),
+            ValueIndexResult::Immutable(_) => Err(ValueError::ReadOnlyIndexing),
+            ValueIndexResult::Owned(value) => value.into_index(index).map(ValueIndexResult::Owned),
+        }
+    }
+}
+
+/// Indexing operations shared by values and the results of indexing into them
 pub trait IndexingExt {
+    /// Index into the value, borrowing the result
     fn ref_index(&self, index: Value) -> Result<ValueIndexResult<'_>, ValueError>;
+
+    /// Index into the value, mutably borrowing the result
     fn mut_index(&mut self, index: Value) -> Result<ValueIndexResult<'_>, ValueError>;
+
+    /// Index into the value, consuming it
     fn into_index(self, index: Value) -> Result<Value, ValueError>;
+
+    /// Remove the value at an index, returning it
     fn delete_index(&mut self, index: Value) -> Result<Value, ValueError>;
+
+    /// Replace the value at an index
     fn set_index(&mut self, index: Value, value: Value) -> Result<(), ValueError>;
 }
 
@@ -82,21 +131,21 @@ impl IndexingExt for Value {
             Value::Array(a) => {
                 let indices = index.into_range()?;
                 if indices.start < 0 || indices.end > a.len() as i128 {
-                    return Err(ValueError::IndexOutOfBounds);
+                    return Err(out_of_bounds(&indices, a.len() as i128));
                 }
 
-                let indices = (indices.start as usize)..(indices.end as usize);
-                match a.get(indices) {
+                let len = a.len() as i128;
+                match a.get((indices.start as usize)..(indices.end as usize)) {
                     Some(slice) if slice.len() == 1 => Ok(ValueIndexResult::Immutable(&slice[0])),
                     Some(slice) => Ok(ValueIndexResult::Owned(Value::Array(slice.to_vec()))),
-                    None => Err(ValueError::IndexOutOfBounds),
+                    None => Err(out_of_bounds(&indices, len)),
                 }
             }
 
             Value::Object(o) => match index {
                 Value::Primitive(index) => match o.get(&index) {
                     Some(value) => Ok(ValueIndexResult::Immutable(value)),
-                    None => Err(ValueError::KeyNotFound),
+                    None => Err(ValueError::KeyNotFound(Value::Primitive(index).snippet())),
                 },
                 _ => Err(ValueError::CannotIndexUsing(
                     ValueType::Object,
@@ -107,7 +156,7 @@ impl IndexingExt for Value {
             Value::Primitive(Primitive::String(s)) => {
                 let indices = index.into_range()?;
                 if indices.start < 0 || indices.end > s.chars().count() as i128 {
-                    return Err(ValueError::IndexOutOfBounds);
+                    return Err(out_of_bounds(&indices, s.chars().count() as i128));
                 }
 
                 let indices = (indices.start as usize)..(indices.end as usize);
@@ -119,11 +168,25 @@ impl IndexingExt for Value {
                 Ok(ValueIndexResult::Owned(Value::string(s)))
             }
 
+            Value::Primitive(Primitive::Bytes(b)) => {
+                let indices = index.into_range()?;
+                if indices.start < 0 || indices.end > b.len() as i128 {
+                    return Err(out_of_bounds(&indices, b.len() as i128));
+                }
+
+                let len = b.len() as i128;
+                match b.get((indices.start as usize)..(indices.end as usize)) {
+                    Some([byte]) => Ok(ValueIndexResult::Owned(Value::integer(*byte as i128))),
+                    Some(slice) => Ok(ValueIndexResult::Owned(Value::bytes(slice.to_vec()))),
+                    None => Err(out_of_bounds(&indices, len)),
+                }
+            }
+
             Value::Range(r) => {
                 let r_len = r.end - r.start;
                 let indices = index.into_range()?;
                 if indices.start > r_len || indices.end > r_len {
-                    return Err(ValueError::IndexOutOfBounds);
+                    return Err(out_of_bounds(&indices, r_len));
                 }
 
                 let start = r.start + indices.start;
@@ -143,21 +206,21 @@ impl IndexingExt for Value {
             Value::Array(a) => {
                 let indices = index.into_range()?;
                 if indices.start < 0 || indices.end > a.len() as i128 {
-                    return Err(ValueError::IndexOutOfBounds);
+                    return Err(out_of_bounds(&indices, a.len() as i128));
                 }
 
-                let indices = (indices.start as usize)..(indices.end as usize);
-                match a.get_mut(indices) {
+                let len = a.len() as i128;
+                match a.get_mut((indices.start as usize)..(indices.end as usize)) {
                     Some(slice) if slice.len() == 1 => Ok(ValueIndexResult::Mutable(&mut slice[0])),
                     Some(slice) => Ok(ValueIndexResult::Owned(Value::Array(slice.to_vec()))),
-                    None => Err(ValueError::IndexOutOfBounds),
+                    None => Err(out_of_bounds(&indices, len)),
                 }
             }
 
             Value::Object(o) => match index {
                 Value::Primitive(index) => match o.get_mut(&index) {
                     Some(value) => Ok(ValueIndexResult::Mutable(value)),
-                    None => Err(ValueError::KeyNotFound),
+                    None => Err(ValueError::KeyNotFound(Value::Primitive(index).snippet())),
                 },
                 _ => Err(ValueError::CannotIndexUsing(
                     ValueType::Object,
@@ -174,7 +237,7 @@ impl IndexingExt for Value {
             Value::Array(mut a) => {
                 let indices = index.into_range()?;
                 if indices.start < 0 || indices.end > a.len() as i128 {
-                    return Err(ValueError::IndexOutOfBounds);
+                    return Err(out_of_bounds(&indices, a.len() as i128));
                 }
 
                 let indices = (indices.start as usize)..(indices.end as usize);
@@ -188,7 +251,7 @@ impl IndexingExt for Value {
             Value::Object(mut o) => match index {
                 Value::Primitive(index) => match o.remove(&index) {
                     Some(value) => Ok(value),
-                    None => Err(ValueError::KeyNotFound),
+                    None => Err(ValueError::KeyNotFound(Value::Primitive(index).snippet())),
                 },
                 _ => Err(ValueError::CannotIndexUsing(
                     ValueType::Object,
@@ -205,7 +268,7 @@ impl IndexingExt for Value {
             Value::Array(a) => {
                 let indices = index.into_range()?;
                 if indices.start < 0 || indices.end > a.len() as i128 {
-                    return Err(ValueError::IndexOutOfBounds);
+                    return Err(out_of_bounds(&indices, a.len() as i128));
                 }
 
                 let indices = (indices.start as usize)..(indices.end as usize);
@@ -238,7 +301,7 @@ impl IndexingExt for Value {
             Value::Primitive(Primitive::String(s)) => {
                 let indices = index.into_range()?;
                 if indices.start < 0 || indices.end > s.chars().count() as i128 {
-                    return Err(ValueError::IndexOutOfBounds);
+                    return Err(out_of_bounds(&indices, s.chars().count() as i128));
                 }
 
                 let indices = (indices.start as usize)..(indices.end as usize);
@@ -252,6 +315,33 @@ impl IndexingExt for Value {
                 Ok(())
             }
 
+            Value::Primitive(Primitive::Bytes(b)) => {
+                let indices = index.into_range()?;
+                if indices.start < 0 || indices.end > b.len() as i128 {
+                    return Err(out_of_bounds(&indices, b.len() as i128));
+                }
+
+                let indices = (indices.start as usize)..(indices.end as usize);
+                match indices.len() {
+                    1 => {
+                        let own_type = value.type_of();
+                        b[indices.start] = value
+                            .cast_integer()
+                            .ok()
+                            .and_then(|i| u8::try_from(i).ok())
+                            .ok_or(ValueError::TypeConversion(
+                                own_type,
+                                ValueType::Bytes,
+                                Snippet::default(),
+                            ))?;
+                    }
+                    _ => {
+                        b.splice(indices, value.cast_bytes()?);
+                    }
+                }
+                Ok(())
+            }
+
             _ => Err(ValueError::CannotIndexInto(self.type_of())),
         }
     }
@@ -261,7 +351,7 @@ impl IndexingExt for Value {
             Value::Array(a) => {
                 let indices = index.into_range()?;
                 if indices.start < 0 || indices.end > a.len() as i128 {
-                    return Err(ValueError::IndexOutOfBounds);
+                    return Err(out_of_bounds(&indices, a.len() as i128));
                 }
 
                 let indices = (indices.start as usize)..(indices.end as usize);
@@ -275,7 +365,7 @@ impl IndexingExt for Value {
             Value::Object(o) => match index {
                 Value::Primitive(index) => match o.remove(&index) {
                     Some(value) => Ok(value),
-                    None => Err(ValueError::KeyNotFound),
+                    None => Err(ValueError::KeyNotFound(Value::Primitive(index).snippet())),
                 },
                 _ => Err(ValueError::CannotIndexUsing(
                     ValueType::Object,
@@ -286,7 +376,7 @@ impl IndexingExt for Value {
             Value::Primitive(Primitive::String(s)) => {
                 let indices = index.into_range()?;
                 if indices.start < 0 || indices.end > s.chars().count() as i128 {
-                    return Err(ValueError::IndexOutOfBounds);
+                    return Err(out_of_bounds(&indices, s.chars().count() as i128));
                 }
 
                 let indices = (indices.start as usize)..(indices.end as usize);
@@ -299,6 +389,20 @@ impl IndexingExt for Value {
                 Ok(Value::string(result))
             }
 
+            Value::Primitive(Primitive::Bytes(b)) => {
+                let indices = index.into_range()?;
+                if indices.start < 0 || indices.end > b.len() as i128 {
+                    return Err(out_of_bounds(&indices, b.len() as i128));
+                }
+
+                let indices = (indices.start as usize)..(indices.end as usize);
+                let mut result = b.drain(indices).collect::<Vec<_>>();
+                match result.len() {
+                    1 => Ok(Value::integer(result.pop().unwrap() as i128)),
+                    _ => Ok(Value::bytes(result)),
+                }
+            }
+
             Value::Range(r) => {
                 let indices = index.into_range()?;
                 if indices.start == 0 && indices.end < r.end {
@@ -310,7 +414,7 @@ impl IndexingExt for Value {
                     r.end = indices.start;
                     Ok(Value::Range(indices.start..r.end))
                 } else {
-                    Err(ValueError::IndexOutOfBounds)
+                    Err(out_of_bounds(&indices, r.end - r.start))
                 }
             }
 
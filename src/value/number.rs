@@ -1,6 +1,8 @@
-use fpdec::{CheckedAdd, CheckedDiv, CheckedMul, CheckedRem, CheckedSub, Decimal, Round};
+use fpdec::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedRem, CheckedSub, Decimal, Round, MAX_N_FRAC_DIGITS,
+};
 
-use crate::traits::{ByteDecodeError, SerializeToBytes};
+use crate::traits::{ByteDecodeError, ByteReader, SerializeToBytes};
 
 use super::{CheckedArithmetic, ValueError};
 
@@ -14,12 +16,21 @@ pub enum NumberSymbol {
     Suffix(String),
 }
 
+impl std::fmt::Display for NumberSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumberSymbol::Prefix(symbol) | NumberSymbol::Suffix(symbol) => write!(f, "{}", symbol),
+        }
+    }
+}
+
 /// A fixed-point number that can be used in calculations.
 /// The number is represented as a Decimal with a symbol and precision.
+/// The symbol is boxed, since most numbers do not have one and it would double the size of the number
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Number {
     value: fpdec::Decimal,
-    symbol: Option<NumberSymbol>,
+    symbol: Option<Box<NumberSymbol>>,
     precision: Option<i8>,
 }
 
@@ -28,7 +39,7 @@ impl Number {
     pub fn new(value: fpdec::Decimal, symbol: Option<NumberSymbol>, precision: Option<i8>) -> Self {
         let mut i = Self {
             value,
-            symbol,
+            symbol: symbol.map(Box::new),
             precision,
         };
         i.update_precision();
@@ -47,25 +58,41 @@ impl Number {
         self.value.eq_zero()
     }
 
+    /// Get the currency symbol of the number, if it has one
+    pub fn symbol(&self) -> Option<&NumberSymbol> {
+        self.symbol.as_deref()
+    }
+
+    /// Consume the number, returning one with the given symbol
+    /// Symbols are only for display, so the value is unchanged
+    pub fn with_symbol(mut self, symbol: Option<NumberSymbol>) -> Self {
+        self.symbol = symbol.map(Box::new);
+        self
+    }
+
     /// Resolve the precision and symbol of two numbers.
-    pub fn resolve(self, other: Self) -> (Self, Self) {
+    /// A number without a symbol takes on the symbol of the other,
+    /// but two different symbols are an error, since they are different currencies
+    pub fn resolve(self, other: Self) -> Result<(Self, Self), ValueError> {
         let (v1, s1, p1) = self.decompose();
         let (v2, s2, p2) = other.decompose();
 
         let p = p1.max(p2);
         let s = match (s1, s2) {
-            (Some(s1), Some(s2)) if s1 != s2 => None,
+            (Some(s1), Some(s2)) if s1 != s2 => {
+                return Err(ValueError::CurrencyMismatch(s1, s2));
+            }
             (Some(s1), _) => Some(s1),
             (None, Some(s2)) => Some(s2),
             (None, None) => None,
         };
 
-        (Self::new(v1, s.clone(), p), Self::new(v2, s, p))
+        Ok((Self::new(v1, s.clone(), p), Self::new(v2, s, p)))
     }
 
     /// Decompose the number into its components.
     pub fn decompose(self) -> (fpdec::Decimal, Option<NumberSymbol>, Option<i8>) {
-        (self.value, self.symbol, self.precision)
+        (self.value, self.symbol.map(|s| *s), self.precision)
     }
 
     /// Convert the number into a float
@@ -91,7 +118,22 @@ impl Number {
             self.value
                 .checked_round(precision)
                 .ok_or_else(|| ValueError::ArithmeticOverflow)?,
-            self.symbol,
+            self.symbol.map(|s| *s),
+            self.precision,
+        ))
+    }
+
+    /// Consume the value, returning one rounded to the given precision using a specific rounding mode,
+    /// rather than the default of rounding half to even.
+    pub fn round_with(self, precision: i8, mode: fpdec::RoundingMode) -> Result<Self, ValueError> {
+        let previous = fpdec::RoundingMode::default();
+        fpdec::RoundingMode::set_default(mode);
+        let value = self.value.checked_round(precision);
+        fpdec::RoundingMode::set_default(previous);
+
+        Ok(Self::new(
+            value.ok_or_else(|| ValueError::ArithmeticOverflow)?,
+            self.symbol.map(|s| *s),
             self.precision,
         ))
     }
@@ -112,6 +154,33 @@ impl Number {
     }
 }
 
+/// Multiply two decimals, rounding the product if it has more fractional digits than a decimal can hold
+fn checked_mul_rounded(a: Decimal, b: Decimal) -> Option<Decimal> {
+    let n_frac_digits = a.n_frac_digits() + b.n_frac_digits();
+    if n_frac_digits <= MAX_N_FRAC_DIGITS {
+        return a.checked_mul(b);
+    }
+
+    let shift = 10i128.pow((n_frac_digits - MAX_N_FRAC_DIGITS) as u32);
+    match a.coefficient().checked_mul(b.coefficient()) {
+        Some(coeff) => {
+            // Round half away from zero
+            let mut rounded = coeff / shift;
+            if (coeff % shift).abs() * 2 >= shift {
+                rounded += coeff.signum();
+            }
+            Some(Decimal::new_raw(rounded, MAX_N_FRAC_DIGITS))
+        }
+
+        // Too large to multiply exactly, so give up half of each operand's fractional digits
+        None => {
+            let digits = (MAX_N_FRAC_DIGITS / 2) as i8;
+            a.checked_round(digits)?
+                .checked_mul(b.checked_round(digits)?)
+        }
+    }
+}
+
 impl TryInto<i128> for Number {
     type Error = fpdec::TryFromDecimalError;
 
@@ -141,7 +210,11 @@ impl From<i128> for Number {
 
 impl std::fmt::Display for Number {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &self.symbol {
+        match self.symbol.as_deref() {
+            // The sign goes before the symbol, as in -$5
+            Some(NumberSymbol::Prefix(prefix)) if self.value < Decimal::ZERO => {
+                write!(f, "-{}{}", prefix, -self.value)
+            }
             Some(NumberSymbol::Prefix(prefix)) => write!(f, "{}{}", prefix, self.value),
             Some(NumberSymbol::Suffix(suffix)) => write!(f, "{}{}", self.value, suffix),
             None => write!(f, "{}", self.value),
@@ -151,7 +224,7 @@ impl std::fmt::Display for Number {
 
 impl CheckedArithmetic for Number {
     fn checked_add(self, other: Self) -> Result<Self, ValueError> {
-        let (self_, other_) = self.resolve(other);
+        let (self_, other_) = self.resolve(other)?;
         let (v1, _, _) = self_.decompose();
         let (v2, symbol, precision) = other_.decompose();
 
@@ -164,7 +237,7 @@ impl CheckedArithmetic for Number {
     }
 
     fn checked_sub(self, other: Self) -> Result<Self, ValueError> {
-        let (self_, other_) = self.resolve(other);
+        let (self_, other_) = self.resolve(other)?;
         let (v1, _, _) = self_.decompose();
         let (v2, symbol, precision) = other_.decompose();
 
@@ -177,20 +250,30 @@ impl CheckedArithmetic for Number {
     }
 
     fn checked_mul(self, other: Self) -> Result<Self, ValueError> {
-        let (self_, other_) = self.resolve(other);
+        let (self_, other_) = self.resolve(other)?;
         let (v1, _, _) = self_.decompose();
         let (v2, symbol, precision) = other_.decompose();
 
         Ok(Self::new(
-            v1.checked_mul(&v2)
-                .ok_or_else(|| ValueError::ArithmeticOverflow)?,
+            checked_mul_rounded(v1, v2).ok_or_else(|| ValueError::ArithmeticOverflow)?,
             symbol,
             precision,
         ))
     }
 
     fn checked_div(self, other: Self) -> Result<Self, ValueError> {
-        let (self_, other_) = self.resolve(other);
+        // Dividing two amounts of the same currency gives a plain ratio, like $10 / $4 = 2.5
+        if self.symbol.is_some() && self.symbol == other.symbol {
+            let (v1, v2) = (self.value, other.value);
+            return Ok(Self::new(
+                v1.checked_div(&v2)
+                    .ok_or_else(|| ValueError::ArithmeticOverflow)?,
+                None,
+                None,
+            ));
+        }
+
+        let (self_, other_) = self.resolve(other)?;
         let (v1, _, _) = self_.decompose();
         let (v2, symbol, precision) = other_.decompose();
 
@@ -203,7 +286,7 @@ impl CheckedArithmetic for Number {
     }
 
     fn checked_rem(self, other: Self) -> Result<Self, ValueError> {
-        let (self_, other_) = self.resolve(other);
+        let (self_, other_) = self.resolve(other)?;
         let (v1, _, _) = self_.decompose();
         let (v2, symbol, precision) = other_.decompose();
 
@@ -216,7 +299,7 @@ impl CheckedArithmetic for Number {
     }
 
     fn checked_pow(self, other: Self) -> Result<Self, ValueError> {
-        let (self_, other_) = self.resolve(other);
+        let (self_, other_) = self.resolve(other)?;
         let (v1, _, _) = self_.decompose();
         let (v2, symbol, precision) = other_.decompose();
 
@@ -254,7 +337,7 @@ impl SerializeToBytes for Number {
         bytes.push(frac);
 
         // Serialize the symbol
-        match self.symbol {
+        match self.symbol.map(|s| *s) {
             None => {
                 bytes.push(0x00);
             }
@@ -275,7 +358,7 @@ impl SerializeToBytes for Number {
     }
 
     fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
+        bytes: &mut ByteReader<'_>,
     ) -> Result<Self, crate::traits::ByteDecodeError> {
         let coeef = i128::deserialize_from_bytes(bytes)?;
         let frac = u8::deserialize_from_bytes(bytes)?;
@@ -0,0 +1,81 @@
+use std::cell::Cell;
+
+use super::ValueError;
+
+/// What integer arithmetic that overflows a 128-bit integer results in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegerOverflow {
+    /// Overflowing is an error
+    #[default]
+    Error,
+
+    /// The result wraps around, as in two's complement hardware
+    Wrap,
+
+    /// The result is clamped to the largest or smallest integer
+    Saturate,
+}
+
+thread_local! {
+    static INTEGER_OVERFLOW: Cell<IntegerOverflow> = const { Cell::new(IntegerOverflow::Error) };
+}
+
+/// Set what integer overflow results in on the current thread
+/// Returns the previous policy
+pub fn set_integer_overflow(policy: IntegerOverflow) -> IntegerOverflow {
+    INTEGER_OVERFLOW.with(|p| p.replace(policy))
+}
+
+/// Get what integer overflow results in on the current thread; see `set_integer_overflow`
+pub fn integer_overflow() -> IntegerOverflow {
+    INTEGER_OVERFLOW.with(|p| p.get())
+}
+
+/// The result of an integer operation according to the current policy,
+/// given its checked result, and its wrapping and saturating forms for when that overflowed
+pub(super) fn integer_result(
+    checked: Option<i128>,
+    wrapping: impl FnOnce() -> i128,
+    saturating: impl FnOnce() -> i128,
+) -> Result<i128, ValueError> {
+    match (checked, integer_overflow()) {
+        (Some(value), _) => Ok(value),
+        (None, IntegerOverflow::Error) => Err(ValueError::ArithmeticOverflow),
+        (None, IntegerOverflow::Wrap) => Ok(wrapping()),
+        (None, IntegerOverflow::Saturate) => Ok(saturating()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_integer_overflow() {
+        let mut lav = Lavendeux::new();
+        lav.run("big = 2 ** 126").unwrap();
+
+        lav.set_integer_overflow(IntegerOverflow::Wrap);
+        assert_eq!(lav.run("big * 2 < 0").unwrap().to_string(), "true");
+        assert_eq!(
+            lav.run("-(big * 2) == big * 2").unwrap().to_string(),
+            "true"
+        );
+        assert_eq!(lav.run("2 ** 128").unwrap().to_string(), "0");
+
+        lav.set_integer_overflow(IntegerOverflow::Saturate);
+        assert_eq!(lav.run("big * 4 == big * 8").unwrap().to_string(), "true");
+        assert_eq!(lav.run("-big * 4 < 0").unwrap().to_string(), "true");
+        assert_eq!(lav.run("(-big * 2) / -1 > 0").unwrap().to_string(), "true");
+        assert_eq!(
+            lav.run("sum([big, big, big]) > big").unwrap().to_string(),
+            "true"
+        );
+
+        // Only integers are affected; decimals and the default still error
+        assert!(lav.run("(big as decimal) * big").is_err());
+        lav.set_integer_overflow(IntegerOverflow::Error);
+        assert!(lav.run("big * 2").is_err());
+    }
+}
@@ -1,12 +1,42 @@
-use crate::traits::SerializeToBytes;
+use crate::traits::{ByteReader, SerializeToBytes};
 
 use super::{
-    number::Number, types::ValueType, CheckedArithmetic, CheckedBitwise, CheckedBoolean, ValueError,
+    division, number::Number, overflow, types::ValueType, CheckedArithmetic, CheckedBitwise,
+    CheckedBoolean, Snippet, Value, ValueError,
 };
+use std::cell::Cell;
+
+thread_local! {
+    static STRICT_BOOLEANS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Set whether arithmetic on booleans is an error on the current thread
+/// When not strict, which is the default, `+` and `-` act as XOR, and `*`, `/`, `%` and `**` act as AND
+/// Returns the previous setting
+pub fn set_strict_booleans(strict: bool) -> bool {
+    STRICT_BOOLEANS.with(|s| s.replace(strict))
+}
+
+/// Check if arithmetic on booleans is an error on the current thread; see `set_strict_booleans`
+pub fn strict_booleans() -> bool {
+    STRICT_BOOLEANS.with(|s| s.get())
+}
+
+/// The result of an arithmetic operator on booleans, or an error if booleans are strict
+fn boolean_arithmetic(operator: &'static str, result: bool) -> Result<Primitive, ValueError> {
+    if strict_booleans() {
+        Err(ValueError::BooleanArithmetic(operator))
+    } else {
+        Ok(Primitive::Boolean(result))
+    }
+}
 
 /// Represents a primitive value.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Primitive {
+    /// Represents the absence of a value.
+    Nil,
+
     /// Represents a boolean value.
     Boolean(bool),
 
@@ -18,16 +48,21 @@ pub enum Primitive {
 
     /// Represents a string value.
     String(String),
+
+    /// Represents a sequence of raw bytes.
+    Bytes(Vec<u8>),
 }
 
 impl Primitive {
     /// Returns the type of the primitive
     pub fn type_of(&self) -> ValueType {
         match self {
+            Primitive::Nil => ValueType::Nil,
             Primitive::Boolean(_) => ValueType::Boolean,
             Primitive::Integer(_) => ValueType::Integer,
             Primitive::Decimal(_) => ValueType::Decimal,
             Primitive::String(_) => ValueType::String,
+            Primitive::Bytes(_) => ValueType::Bytes,
         }
     }
 
@@ -38,6 +73,11 @@ impl Primitive {
             ValueType::Integer => self.as_integer(),
             ValueType::Decimal => self.as_decimal(),
             ValueType::String => Some(self.as_string()),
+            ValueType::Bytes => self.as_bytes(),
+            ValueType::Nil => match self {
+                Primitive::Nil => Some(self),
+                _ => None,
+            },
 
             ValueType::Numeric => match self {
                 Primitive::Integer(_) | Primitive::Decimal(_) | Primitive::Boolean(_) => Some(self),
@@ -50,13 +90,21 @@ impl Primitive {
         }
     }
 
+    /// Returns true if the primitive is nil
+    pub fn is_nil(&self) -> bool {
+        matches!(self, Primitive::Nil)
+    }
+
     /// Converts the primitive to a boolean
+    /// nil is always false
     pub fn as_boolean(self) -> Self {
         match self {
+            Primitive::Nil => Primitive::Boolean(false),
             Primitive::Boolean(b) => Primitive::Boolean(b),
             Primitive::Integer(i) => Primitive::Boolean(i != 0),
             Primitive::Decimal(d) => Primitive::Boolean(!d.is_zero()),
             Primitive::String(s) => Primitive::Boolean(!s.is_empty()),
+            Primitive::Bytes(b) => Primitive::Boolean(!b.is_empty()),
         }
     }
 
@@ -71,10 +119,11 @@ impl Primitive {
     /// Converts the primitive to an integer
     pub fn as_integer(self) -> Option<Self> {
         match self {
+            Primitive::Nil => None,
             Primitive::Boolean(b) => Some(Primitive::Integer(b as i128)),
             Primitive::Integer(i) => Some(Primitive::Integer(i)),
             Primitive::Decimal(d) => Some(Primitive::Integer(d.try_into().ok()?)),
-            Primitive::String(_) => None,
+            Primitive::String(_) | Primitive::Bytes(_) => None,
         }
     }
 
@@ -89,27 +138,47 @@ impl Primitive {
     /// Converts the primitive to a decimal
     pub fn as_decimal(self) -> Option<Self> {
         match self {
+            Primitive::Nil => None,
             Primitive::Boolean(b) => Some(Primitive::Decimal(Number::from(b as i128))),
             Primitive::Integer(i) => Some(Primitive::Decimal(Number::from(i))),
             Primitive::Decimal(d) => Some(Primitive::Decimal(d)),
-            Primitive::String(_) => None,
+            Primitive::String(_) | Primitive::Bytes(_) => None,
         }
     }
 
     /// Converts the primitive to a string
     pub fn as_string(self) -> Self {
         match self {
+            Primitive::Nil => Primitive::String("nil".to_string()),
             Primitive::Boolean(b) => Primitive::String(b.to_string()),
             Primitive::Integer(i) => Primitive::String(i.to_string()),
             Primitive::Decimal(d) => Primitive::String(d.to_string()),
             Primitive::String(s) => Primitive::String(s),
+            Primitive::Bytes(b) => Primitive::String(String::from_utf8_lossy(&b).into_owned()),
+        }
+    }
+
+    /// Converts the primitive to bytes
+    /// Strings are encoded as UTF-8
+    pub fn as_bytes(self) -> Option<Self> {
+        match self {
+            Primitive::String(s) => Some(Primitive::Bytes(s.into_bytes())),
+            Primitive::Bytes(b) => Some(Primitive::Bytes(b)),
+            _ => None,
         }
     }
 
     /// Resolves the type of two primitives
-    /// Priority: String > Decimal > Integer > Boolean
+    /// Priority: Bytes > String > Decimal > Integer > Boolean
+    /// nil can only be resolved against another nil
     pub fn resolve(self, other: Self) -> Option<(Self, Self)> {
         match (self, other) {
+            (Primitive::Nil, Primitive::Nil) => Some((Primitive::Nil, Primitive::Nil)),
+            (Primitive::Nil, _) | (_, Primitive::Nil) => None,
+
+            (Primitive::Bytes(b), other_) => Some((Primitive::Bytes(b), other_.as_bytes()?)),
+            (self_, Primitive::Bytes(b)) => Some((self_.as_bytes()?, Primitive::Bytes(b))),
+
             (Primitive::String(s), other_) => Some((Primitive::String(s), other_.as_string())),
             (self_, Primitive::String(s)) => Some((self_.as_string(), Primitive::String(s))),
 
@@ -122,45 +191,62 @@ impl Primitive {
             (Primitive::Boolean(b), other_) => Some((Primitive::Boolean(b), other_.as_boolean())),
         }
     }
+
+    /// Resolves the type of two primitives used with an operator
+    /// If either is nil, the error shows both values
+    fn resolve_operands(self, other: Self) -> Result<(Self, Self), ValueError> {
+        let (ta, tb) = (self.type_of(), other.type_of());
+        if ta != tb && (ta == ValueType::Nil || tb == ValueType::Nil) {
+            let snippet = Snippet::of(&[&Value::Primitive(self), &Value::Primitive(other)]);
+            return Err(ValueError::TypeConversion(ta, tb, snippet));
+        }
+
+        self.resolve(other)
+            .ok_or(ValueError::TypeConversion(ta, tb, Snippet::default()))
+    }
 }
 
 impl CheckedArithmetic for Primitive {
     fn checked_add(self, other: Self) -> Result<Self, ValueError> {
-        let (ta, tb) = (self.type_of(), other.type_of());
-        let (a, b) = self
-            .resolve(other)
-            .ok_or_else(|| ValueError::TypeConversion(ta, tb))?;
+        let (a, b) = self.resolve_operands(other)?;
         let t = a.type_of();
         match (a, b) {
-            (Primitive::Boolean(a), Primitive::Boolean(b)) => Ok(Primitive::Boolean(a ^ b)),
-            (Primitive::Integer(a), Primitive::Integer(b)) => Ok(Primitive::Integer(
-                a.checked_add(b)
-                    .ok_or_else(|| ValueError::ArithmeticOverflow)?,
-            )),
+            (Primitive::Boolean(a), Primitive::Boolean(b)) => boolean_arithmetic("+", a ^ b),
+            (Primitive::Integer(a), Primitive::Integer(b)) => {
+                Ok(Primitive::Integer(overflow::integer_result(
+                    a.checked_add(b),
+                    || a.wrapping_add(b),
+                    || a.saturating_add(b),
+                )?))
+            }
             (Primitive::Decimal(a), Primitive::Decimal(b)) => {
                 Ok(Primitive::Decimal(a.checked_add(b)?))
             }
             (Primitive::String(a), Primitive::String(b)) => {
                 Ok(Primitive::String(format!("{}{}", a, b)))
             }
+            (Primitive::Bytes(mut a), Primitive::Bytes(b)) => {
+                a.extend(b);
+                Ok(Primitive::Bytes(a))
+            }
 
             _ => Err(ValueError::InvalidOperationForType(t)),
         }
     }
 
     fn checked_sub(self, other: Self) -> Result<Self, ValueError> {
-        let (ta, tb) = (self.type_of(), other.type_of());
-        let (a, b) = self
-            .resolve(other)
-            .ok_or_else(|| ValueError::TypeConversion(ta, tb))?;
+        let (a, b) = self.resolve_operands(other)?;
         let t = a.type_of();
 
         match (a, b) {
-            (Primitive::Boolean(a), Primitive::Boolean(b)) => Ok(Primitive::Boolean(a ^ b)),
-            (Primitive::Integer(a), Primitive::Integer(b)) => Ok(Primitive::Integer(
-                a.checked_sub(b)
-                    .ok_or_else(|| ValueError::ArithmeticOverflow)?,
-            )),
+            (Primitive::Boolean(a), Primitive::Boolean(b)) => boolean_arithmetic("-", a ^ b),
+            (Primitive::Integer(a), Primitive::Integer(b)) => {
+                Ok(Primitive::Integer(overflow::integer_result(
+                    a.checked_sub(b),
+                    || a.wrapping_sub(b),
+                    || a.saturating_sub(b),
+                )?))
+            }
             (Primitive::Decimal(a), Primitive::Decimal(b)) => {
                 Ok(Primitive::Decimal(a.checked_sub(b)?))
             }
@@ -174,17 +260,17 @@ impl CheckedArithmetic for Primitive {
     }
 
     fn checked_mul(self, other: Self) -> Result<Self, ValueError> {
-        let (ta, tb) = (self.type_of(), other.type_of());
-        let (a, b) = self
-            .resolve(other)
-            .ok_or_else(|| ValueError::TypeConversion(ta, tb))?;
+        let (a, b) = self.resolve_operands(other)?;
         let t = a.type_of();
         match (a, b) {
-            (Primitive::Boolean(a), Primitive::Boolean(b)) => Ok(Primitive::Boolean(a && b)),
-            (Primitive::Integer(a), Primitive::Integer(b)) => Ok(Primitive::Integer(
-                a.checked_mul(b)
-                    .ok_or_else(|| ValueError::ArithmeticOverflow)?,
-            )),
+            (Primitive::Boolean(a), Primitive::Boolean(b)) => boolean_arithmetic("*", a && b),
+            (Primitive::Integer(a), Primitive::Integer(b)) => {
+                Ok(Primitive::Integer(overflow::integer_result(
+                    a.checked_mul(b),
+                    || a.wrapping_mul(b),
+                    || a.saturating_mul(b),
+                )?))
+            }
             (Primitive::Decimal(a), Primitive::Decimal(b)) => {
                 Ok(Primitive::Decimal(a.checked_mul(b)?))
             }
@@ -194,17 +280,20 @@ impl CheckedArithmetic for Primitive {
     }
 
     fn checked_div(self, other: Self) -> Result<Self, ValueError> {
-        let (ta, tb) = (self.type_of(), other.type_of());
-        let (a, b) = self
-            .resolve(other)
-            .ok_or_else(|| ValueError::TypeConversion(ta, tb))?;
+        let (a, b) = self.resolve_operands(other)?;
         let t = a.type_of();
+        if division::is_zero_divisor(&b) {
+            return division::divide_by_zero(a, false);
+        }
         match (a, b) {
-            (Primitive::Boolean(a), Primitive::Boolean(b)) => Ok(Primitive::Boolean(a && b)),
-            (Primitive::Integer(a), Primitive::Integer(b)) => Ok(Primitive::Integer(
-                a.checked_div(b)
-                    .ok_or_else(|| ValueError::ArithmeticOverflow)?,
-            )),
+            (Primitive::Boolean(a), Primitive::Boolean(b)) => boolean_arithmetic("/", a && b),
+            (Primitive::Integer(a), Primitive::Integer(b)) => {
+                Ok(Primitive::Integer(overflow::integer_result(
+                    a.checked_div(b),
+                    || a.wrapping_div(b),
+                    || a.saturating_div(b),
+                )?))
+            }
             (Primitive::Decimal(a), Primitive::Decimal(b)) => {
                 Ok(Primitive::Decimal(a.checked_div(b)?))
             }
@@ -214,17 +303,21 @@ impl CheckedArithmetic for Primitive {
     }
 
     fn checked_rem(self, other: Self) -> Result<Self, ValueError> {
-        let (ta, tb) = (self.type_of(), other.type_of());
-        let (a, b) = self
-            .resolve(other)
-            .ok_or_else(|| ValueError::TypeConversion(ta, tb))?;
+        let (a, b) = self.resolve_operands(other)?;
         let t = a.type_of();
+        if division::is_zero_divisor(&b) {
+            return division::divide_by_zero(a, true);
+        }
         match (a, b) {
-            (Primitive::Boolean(a), Primitive::Boolean(b)) => Ok(Primitive::Boolean(a && b)),
-            (Primitive::Integer(a), Primitive::Integer(b)) => Ok(Primitive::Integer(
-                a.checked_rem(b)
-                    .ok_or_else(|| ValueError::ArithmeticOverflow)?,
-            )),
+            (Primitive::Boolean(a), Primitive::Boolean(b)) => boolean_arithmetic("%", a && b),
+            // Only `i128::MIN % -1` overflows, and its remainder is exactly 0
+            (Primitive::Integer(a), Primitive::Integer(b)) => {
+                Ok(Primitive::Integer(overflow::integer_result(
+                    a.checked_rem(b),
+                    || a.wrapping_rem(b),
+                    || a.wrapping_rem(b),
+                )?))
+            }
             (Primitive::Decimal(a), Primitive::Decimal(b)) => {
                 Ok(Primitive::Decimal(a.checked_rem(b)?))
             }
@@ -234,21 +327,21 @@ impl CheckedArithmetic for Primitive {
     }
 
     fn checked_pow(self, other: Self) -> Result<Self, ValueError> {
-        let (ta, tb) = (self.type_of(), other.type_of());
-        let (a, b) = self
-            .resolve(other)
-            .ok_or_else(|| ValueError::TypeConversion(ta, tb))?;
+        let (a, b) = self.resolve_operands(other)?;
         let t = a.type_of();
         match (a, b) {
-            (Primitive::Boolean(a), Primitive::Boolean(b)) => Ok(Primitive::Boolean(a && b)),
-            (Primitive::Integer(a), Primitive::Integer(b)) => Ok(Primitive::Integer(
-                a.checked_pow(
-                    b.try_into()
-                        .ok()
-                        .ok_or_else(|| ValueError::ArithmeticOverflow)?,
-                )
-                .ok_or_else(|| ValueError::ArithmeticOverflow)?,
-            )),
+            (Primitive::Boolean(a), Primitive::Boolean(b)) => boolean_arithmetic("**", a && b),
+            (Primitive::Integer(a), Primitive::Integer(b)) => {
+                let b: u32 = b
+                    .try_into()
+                    .ok()
+                    .ok_or_else(|| ValueError::ArithmeticOverflow)?;
+                Ok(Primitive::Integer(overflow::integer_result(
+                    a.checked_pow(b),
+                    || a.wrapping_pow(b),
+                    || a.saturating_pow(b),
+                )?))
+            }
             (Primitive::Decimal(a), Primitive::Decimal(b)) => Ok(Primitive::Decimal(
                 a.checked_pow(
                     b.try_into()
@@ -263,20 +356,23 @@ impl CheckedArithmetic for Primitive {
 
     fn checked_neg(self) -> Result<Self, ValueError> {
         match self {
-            Primitive::Boolean(b) => Ok(Primitive::Boolean(!b)),
-            Primitive::Integer(i) => Ok(Primitive::Integer(-i)),
+            Primitive::Nil => Err(ValueError::InvalidOperationForType(ValueType::Nil)),
+            Primitive::Boolean(b) => boolean_arithmetic("-", !b),
+            Primitive::Integer(i) => Ok(Primitive::Integer(overflow::integer_result(
+                i.checked_neg(),
+                || i.wrapping_neg(),
+                || i.saturating_neg(),
+            )?)),
             Primitive::Decimal(d) => Ok(Primitive::Decimal(d.checked_neg()?)),
             Primitive::String(s) => Ok(Primitive::String(s.chars().rev().collect::<String>())),
+            Primitive::Bytes(b) => Ok(Primitive::Bytes(b.into_iter().rev().collect())),
         }
     }
 }
 
 impl CheckedBitwise for Primitive {
     fn checked_and(self, other: Self) -> Result<Self, ValueError> {
-        let (ta, tb) = (self.type_of(), other.type_of());
-        let (a, b) = self
-            .resolve(other)
-            .ok_or_else(|| ValueError::TypeConversion(ta, tb))?;
+        let (a, b) = self.resolve_operands(other)?;
         let t = a.type_of();
         match (a, b) {
             (Primitive::Boolean(a), Primitive::Boolean(b)) => Ok(Primitive::Boolean(a && b)),
@@ -286,10 +382,7 @@ impl CheckedBitwise for Primitive {
     }
 
     fn checked_or(self, other: Self) -> Result<Self, ValueError> {
-        let (ta, tb) = (self.type_of(), other.type_of());
-        let (a, b) = self
-            .resolve(other)
-            .ok_or_else(|| ValueError::TypeConversion(ta, tb))?;
+        let (a, b) = self.resolve_operands(other)?;
         let t = a.type_of();
         match (a, b) {
             (Primitive::Boolean(a), Primitive::Boolean(b)) => Ok(Primitive::Boolean(a || b)),
@@ -299,10 +392,7 @@ impl CheckedBitwise for Primitive {
     }
 
     fn checked_xor(self, other: Self) -> Result<Self, ValueError> {
-        let (ta, tb) = (self.type_of(), other.type_of());
-        let (a, b) = self
-            .resolve(other)
-            .ok_or_else(|| ValueError::TypeConversion(ta, tb))?;
+        let (a, b) = self.resolve_operands(other)?;
         let t = a.type_of();
         match (a, b) {
             (Primitive::Boolean(a), Primitive::Boolean(b)) => Ok(Primitive::Boolean(a ^ b)),
@@ -312,10 +402,7 @@ impl CheckedBitwise for Primitive {
     }
 
     fn checked_shl(self, other: Self) -> Result<Self, ValueError> {
-        let (ta, tb) = (self.type_of(), other.type_of());
-        let (a, b) = self
-            .resolve(other)
-            .ok_or_else(|| ValueError::TypeConversion(ta, tb))?;
+        let (a, b) = self.resolve_operands(other)?;
         let t = a.type_of();
         match (a, b) {
             (Primitive::Integer(a), Primitive::Integer(b)) => Ok(Primitive::Integer(a << b)),
@@ -324,10 +411,7 @@ impl CheckedBitwise for Primitive {
     }
 
     fn checked_shr(self, other: Self) -> Result<Self, ValueError> {
-        let (ta, tb) = (self.type_of(), other.type_of());
-        let (a, b) = self
-            .resolve(other)
-            .ok_or_else(|| ValueError::TypeConversion(ta, tb))?;
+        let (a, b) = self.resolve_operands(other)?;
         let t = a.type_of();
         match (a, b) {
             (Primitive::Integer(a), Primitive::Integer(b)) => Ok(Primitive::Integer(a >> b)),
@@ -362,67 +446,102 @@ impl CheckedBoolean for Primitive {
     }
 
     fn checked_eq(self, other: Self) -> Result<Self, ValueError> {
+        // nil is only ever equal to itself
+        if self.is_nil() || other.is_nil() {
+            return Ok(Primitive::Boolean(self.is_nil() && other.is_nil()));
+        }
+
         let (a, b) = self.resolve(other).unwrap();
         Ok(Primitive::Boolean(match (a, b) {
             (Primitive::Boolean(a), Primitive::Boolean(b)) => a == b,
             (Primitive::Integer(a), Primitive::Integer(b)) => a == b,
             (Primitive::Decimal(a), Primitive::Decimal(b)) => a == b,
             (Primitive::String(a), Primitive::String(b)) => a == b,
+            (Primitive::Bytes(a), Primitive::Bytes(b)) => a == b,
             _ => false,
         }))
     }
 
     fn checked_ne(self, other: Self) -> Result<Self, ValueError> {
+        if self.is_nil() || other.is_nil() {
+            return Ok(Primitive::Boolean(self.is_nil() != other.is_nil()));
+        }
+
         let (a, b) = self.resolve(other).unwrap();
         Ok(Primitive::Boolean(match (a, b) {
             (Primitive::Boolean(a), Primitive::Boolean(b)) => a != b,
             (Primitive::Integer(a), Primitive::Integer(b)) => a != b,
             (Primitive::Decimal(a), Primitive::Decimal(b)) => a != b,
             (Primitive::String(a), Primitive::String(b)) => a != b,
+            (Primitive::Bytes(a), Primitive::Bytes(b)) => a != b,
             _ => false,
         }))
     }
 
     fn checked_ge(self, other: Self) -> Result<Self, ValueError> {
+        // nil has no ordering
+        if self.is_nil() || other.is_nil() {
+            return Err(ValueError::InvalidOperationForType(ValueType::Nil));
+        }
+
         let (a, b) = self.resolve(other).unwrap();
         Ok(Primitive::Boolean(match (a, b) {
             (Primitive::Boolean(a), Primitive::Boolean(b)) => a >= b,
             (Primitive::Integer(a), Primitive::Integer(b)) => a >= b,
             (Primitive::Decimal(a), Primitive::Decimal(b)) => a >= b,
             (Primitive::String(a), Primitive::String(b)) => a >= b,
+            (Primitive::Bytes(a), Primitive::Bytes(b)) => a >= b,
             _ => false,
         }))
     }
 
     fn checked_gt(self, other: Self) -> Result<Self, ValueError> {
+        // nil has no ordering
+        if self.is_nil() || other.is_nil() {
+            return Err(ValueError::InvalidOperationForType(ValueType::Nil));
+        }
+
         let (a, b) = self.resolve(other).unwrap();
         Ok(Primitive::Boolean(match (a, b) {
             (Primitive::Boolean(a), Primitive::Boolean(b)) => a > b,
             (Primitive::Integer(a), Primitive::Integer(b)) => a > b,
             (Primitive::Decimal(a), Primitive::Decimal(b)) => a > b,
             (Primitive::String(a), Primitive::String(b)) => a > b,
+            (Primitive::Bytes(a), Primitive::Bytes(b)) => a > b,
             _ => false,
         }))
     }
 
     fn checked_le(self, other: Self) -> Result<Self, ValueError> {
+        // nil has no ordering
+        if self.is_nil() || other.is_nil() {
+            return Err(ValueError::InvalidOperationForType(ValueType::Nil));
+        }
+
         let (a, b) = self.resolve(other).unwrap();
         Ok(Primitive::Boolean(match (a, b) {
             (Primitive::Boolean(a), Primitive::Boolean(b)) => a <= b,
             (Primitive::Integer(a), Primitive::Integer(b)) => a <= b,
             (Primitive::Decimal(a), Primitive::Decimal(b)) => a <= b,
             (Primitive::String(a), Primitive::String(b)) => a <= b,
+            (Primitive::Bytes(a), Primitive::Bytes(b)) => a <= b,
             _ => false,
         }))
     }
 
     fn checked_lt(self, other: Self) -> Result<Self, ValueError> {
+        // nil has no ordering
+        if self.is_nil() || other.is_nil() {
+            return Err(ValueError::InvalidOperationForType(ValueType::Nil));
+        }
+
         let (a, b) = self.resolve(other).unwrap();
         Ok(Primitive::Boolean(match (a, b) {
             (Primitive::Boolean(a), Primitive::Boolean(b)) => a < b,
             (Primitive::Integer(a), Primitive::Integer(b)) => a < b,
             (Primitive::Decimal(a), Primitive::Decimal(b)) => a < b,
             (Primitive::String(a), Primitive::String(b)) => a < b,
+            (Primitive::Bytes(a), Primitive::Bytes(b)) => a < b,
             _ => false,
         }))
     }
@@ -439,10 +558,12 @@ impl CheckedBoolean for Primitive {
 impl std::fmt::Debug for Primitive {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Primitive::Nil => write!(f, "nil"),
             Primitive::Boolean(b) => write!(f, "{}", b),
             Primitive::Integer(i) => write!(f, "{}", i),
             Primitive::Decimal(d) => write!(f, "{}", d),
             Primitive::String(s) => write!(f, "`{}`", s),
+            Primitive::Bytes(b) => write!(f, "0x\"{}\"", crate::literals::to_hex(b)),
         }
     }
 }
@@ -450,10 +571,12 @@ impl std::fmt::Debug for Primitive {
 impl std::fmt::Display for Primitive {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Primitive::Nil => write!(f, "nil"),
             Primitive::Boolean(b) => write!(f, "{}", b),
             Primitive::Integer(i) => write!(f, "{}", i),
             Primitive::Decimal(d) => write!(f, "{}", d),
             Primitive::String(s) => write!(f, "{}", s),
+            Primitive::Bytes(b) => write!(f, "0x\"{}\"", crate::literals::to_hex(b)),
         }
     }
 }
@@ -463,20 +586,23 @@ impl SerializeToBytes for Primitive {
         let mut bytes = Vec::new();
         bytes.push(self.type_of() as u8);
         match self {
+            Primitive::Nil => {}
             Primitive::Boolean(b) => bytes.push(b as u8),
             Primitive::Integer(i) => bytes.extend(i.serialize_into_bytes()),
             Primitive::Decimal(d) => bytes.extend(d.serialize_into_bytes()),
             Primitive::String(s) => bytes.extend(s.serialize_into_bytes()),
+            Primitive::Bytes(b) => bytes.extend(b.serialize_into_bytes()),
         }
 
         bytes
     }
 
     fn deserialize_from_bytes(
-        bytes: &mut impl Iterator<Item = u8>,
+        bytes: &mut ByteReader<'_>,
     ) -> Result<Self, crate::traits::ByteDecodeError> {
         let ty = u8::deserialize_from_bytes(bytes)?;
         match ValueType::from_u8(ty) {
+            Some(ValueType::Nil) => Ok(Primitive::Nil),
             Some(ValueType::Boolean) => {
                 Ok(Primitive::Boolean(u8::deserialize_from_bytes(bytes)? != 0))
             }
@@ -489,6 +615,7 @@ impl SerializeToBytes for Primitive {
             Some(ValueType::String) => {
                 Ok(Primitive::String(String::deserialize_from_bytes(bytes)?))
             }
+            Some(ValueType::Bytes) => Ok(Primitive::Bytes(Vec::deserialize_from_bytes(bytes)?)),
 
             _ => Err(crate::traits::ByteDecodeError::MalformedData(
                 "Primitive".to_string(),
@@ -497,3 +624,33 @@ impl SerializeToBytes for Primitive {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_strict_booleans() {
+        let mut lav = Lavendeux::new();
+        assert_eq!(lav.run("true + true").unwrap().to_string(), "false");
+        assert_eq!(lav.run("true / false").unwrap().to_string(), "false");
+
+        // Logical and bitwise operators, and mixing with numbers, are unaffected
+        lav.set_strict_booleans(true);
+        assert_eq!(lav.run("true && !false").unwrap().to_string(), "true");
+        assert_eq!(lav.run("true ^ true").unwrap().to_string(), "false");
+        assert_eq!(lav.run("true + 1").unwrap().to_string(), "2");
+        assert_eq!(lav.run("(true as int) * 3").unwrap().to_string(), "3");
+
+        for op in ["+", "-", "*", "/", "%", "**"] {
+            let err = lav.run(&format!("true {op} true")).unwrap_err();
+            assert!(err.to_string().contains("booleans"), "{op}: {err}");
+        }
+        assert!(lav.run("b = true; -b").is_err());
+        assert!(lav.run("b = true; b += false").is_err());
+
+        // The setting only applies while this VM is running
+        assert!(!strict_booleans());
+    }
+}
@@ -0,0 +1,90 @@
+use super::{Primitive, Value, ValueType};
+use crate::traits::{ByteDecodeError, ByteReader, SerializeToBytes};
+
+/// A user-defined structured type, declared with `type Name = {field: type, ...}`
+/// A value matches the type if it is an object with every declared field, each of the declared type
+/// Fields that are not declared are allowed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructType {
+    /// The name of the type
+    pub name: String,
+
+    /// The name and type of each field, in declaration order
+    pub fields: Vec<(String, ValueType)>,
+}
+
+impl StructType {
+    /// Describe each way in which a value does not match this type
+    /// Returns an empty list if the value matches
+    pub fn mismatches(&self, value: &Value) -> Vec<String> {
+        let object = match value {
+            Value::Object(object) => object,
+            _ => return vec![format!("expected `object`, found `{}`", value.type_of())],
+        };
+
+        let mut mismatches = vec![];
+        for (name, ty) in &self.fields {
+            match object.get(&Primitive::String(name.clone())) {
+                None => mismatches.push(format!("missing field `{name}`")),
+                Some(field) if !field.is_a(*ty) => mismatches.push(format!(
+                    "field `{name}` expected `{ty}`, found `{}`",
+                    field.type_of()
+                )),
+                Some(_) => {}
+            }
+        }
+        mismatches
+    }
+
+    /// Returns true if the value matches this type
+    pub fn matches(&self, value: &Value) -> bool {
+        self.mismatches(value).is_empty()
+    }
+}
+
+impl std::fmt::Display for StructType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields = self
+            .fields
+            .iter()
+            .map(|(name, ty)| format!("{name}: {ty}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "{}{{{fields}}}", self.name)
+    }
+}
+
+impl SerializeToBytes for StructType {
+    fn serialize_into_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend(self.name.serialize_into_bytes());
+        bytes.extend((self.fields.len() as u64).serialize_into_bytes());
+        for (name, ty) in self.fields {
+            bytes.extend(name.serialize_into_bytes());
+            bytes.push(ty as u8);
+        }
+
+        bytes
+    }
+
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
+        let name = String::deserialize_from_bytes(bytes)?;
+        let len = u64::deserialize_from_bytes(bytes)?;
+
+        let mut fields = vec![];
+        for _ in 0..len {
+            let field = String::deserialize_from_bytes(bytes)?;
+            let ty = u8::deserialize_from_bytes(bytes)?;
+            let ty = ValueType::from_u8(ty).ok_or_else(|| {
+                ByteDecodeError::MalformedData(
+                    "StructType".to_string(),
+                    "Invalid field type".to_string(),
+                )
+            })?;
+            fields.push((field, ty));
+        }
+
+        Ok(Self { name, fields })
+    }
+}
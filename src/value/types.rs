@@ -1,13 +1,23 @@
 /// Represents the type of a value.
 /// Can be a concrete type, a group of types, or all types.
+///
+/// `nil` is a primitive with the following rules:
+/// - It is falsy, and casts to `false`, `"nil"`, `[]` or `{}`
+/// - It is only equal to itself, and cannot be ordered
+/// - Arithmetic and bitwise operations involving nil are errors
+/// - Indexing into nil is an error, unless the nil-safe `?.` operator is used
+///
+/// `bytes` is a primitive that can also be indexed and sliced like a collection
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(u8)]
 pub enum ValueType {
+    Nil = 0b0001_0000,
     Boolean = 0b0001_0001,
     Integer = 0b0001_0010,
     Decimal = 0b0001_0100,
     String = 0b0001_1000,
+    Bytes = 0b0011_1000,
     Array = 0b010_0001,
     Object = 0b0010_0010,
     Range = 0b0010_0100,
@@ -20,13 +30,69 @@ pub enum ValueType {
 }
 
 impl ValueType {
+    /// All the types a value can have, without the groups
+    pub const CONCRETE: [ValueType; 10] = [
+        ValueType::Nil,
+        ValueType::Boolean,
+        ValueType::Integer,
+        ValueType::Decimal,
+        ValueType::String,
+        ValueType::Bytes,
+        ValueType::Array,
+        ValueType::Object,
+        ValueType::Range,
+        ValueType::Function,
+    ];
+
+    /// Checks if this type is, or belongs to, another type
+    pub fn is_a(self, typename: ValueType) -> bool {
+        match (self, typename) {
+            (
+                ValueType::Nil
+                | ValueType::Integer
+                | ValueType::Decimal
+                | ValueType::Boolean
+                | ValueType::String
+                | ValueType::Bytes,
+                ValueType::Primitive,
+            ) => true,
+
+            (
+                ValueType::String
+                | ValueType::Bytes
+                | ValueType::Array
+                | ValueType::Object
+                | ValueType::Range,
+                ValueType::Collection,
+            ) => true,
+
+            (ValueType::Boolean | ValueType::Integer | ValueType::Decimal, ValueType::Numeric) => {
+                true
+            }
+
+            (_, ValueType::All) => true,
+
+            _ => self == typename,
+        }
+    }
+
+    /// Checks if a value could be of both types
+    /// For example, `numeric` overlaps with `primitive`, but `int` does not overlap with `string`
+    pub fn overlaps(self, other: ValueType) -> bool {
+        Self::CONCRETE
+            .iter()
+            .any(|ty| ty.is_a(self) && ty.is_a(other))
+    }
+
     /// Parses a `ValueType` from a string.
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
+            "nil" => Some(ValueType::Nil),
             "bool" => Some(ValueType::Boolean),
             "int" => Some(ValueType::Integer),
             "float" => Some(ValueType::Decimal),
             "string" => Some(ValueType::String),
+            "bytes" => Some(ValueType::Bytes),
             "array" => Some(ValueType::Array),
             "object" => Some(ValueType::Object),
             "range" => Some(ValueType::Range),
@@ -42,10 +108,12 @@ impl ValueType {
     /// Parses a `ValueType` from a byte.
     pub fn from_u8(value: u8) -> Option<Self> {
         Some(match value {
+            0b0001_0000 => ValueType::Nil,
             0b0001_0001 => ValueType::Boolean,
             0b0001_0010 => ValueType::Integer,
             0b0001_0100 => ValueType::Decimal,
             0b0001_1000 => ValueType::String,
+            0b0011_1000 => ValueType::Bytes,
             0b010_0001 => ValueType::Array,
             0b0010_0010 => ValueType::Object,
             0b0010_0100 => ValueType::Range,
@@ -62,10 +130,12 @@ impl ValueType {
 impl std::fmt::Display for ValueType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            ValueType::Nil => write!(f, "nil"),
             ValueType::Boolean => write!(f, "bool"),
             ValueType::Integer => write!(f, "int"),
             ValueType::Decimal => write!(f, "float"),
             ValueType::String => write!(f, "string"),
+            ValueType::Bytes => write!(f, "bytes"),
             ValueType::Array => write!(f, "array"),
             ValueType::Object => write!(f, "object"),
             ValueType::Range => write!(f, "range"),
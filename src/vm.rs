@@ -2,16 +2,44 @@
 //! This module contains the Lavendeux VM and its components.
 //! The VM is responsible for executing the bytecode generated by the compiler.
 //! It also contains the memory manager which is responsible for storing values and functions
+//!
+//! The memory manager and value sources are internal to the VM; they stay public so that
+//! existing code keeps building, but are hidden from the docs and not part of the stable API
 
+mod angle_mode;
+mod clock;
+mod collation;
+mod currency;
+mod output_sink;
+mod replay;
+mod tracer;
 mod execution_context;
 mod load_stdlib;
 
 pub mod error;
+#[doc(hidden)]
 pub mod memory_manager;
+pub mod ops;
+#[doc(hidden)]
 pub mod value_source;
 
 mod virtual_machine;
 pub use virtual_machine::VirtualMachine;
 
+#[cfg(test)]
+mod test_harness;
+
+pub use angle_mode::AngleMode;
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use currency::CurrencyConverter;
+pub use output_sink::{CapturedOutput, OutputSink, StdoutSink, WriteSink};
+pub use replay::ReplayLog;
+pub use tracer::{TraceEntry, Tracer, WriteTracer};
+
 mod opcodes;
-pub use opcodes::OpCode;
+pub use opcodes::{jump_offset, jump_target, OpCode, JUMP_SIZE, OPCODE_SET_VERSION};
+
+mod constant_pool;
+pub use constant_pool::{
+    read_constant_pool, write_constant_pool, ConstantPool, CONSTANT_INDEX_SIZE,
+};
@@ -0,0 +1,56 @@
+/// The unit used for angles by the trigonometric opcodes
+/// Inputs to `SIN`, `COS` and `TAN`, and the results of `ASIN`, `ACOS`, `ATAN` and `ATAN2`,
+/// are in this unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AngleMode {
+    /// A full turn is 2*pi radians
+    #[default]
+    Radians,
+
+    /// A full turn is 360 degrees
+    Degrees,
+
+    /// A full turn is 400 gradians
+    Gradians,
+}
+
+impl AngleMode {
+    /// Parse an angle mode by name, such as `degrees` or `deg`
+    /// Returns None if the name is not recognized
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "radians" | "radian" | "rad" => Some(Self::Radians),
+            "degrees" | "degree" | "deg" => Some(Self::Degrees),
+            "gradians" | "gradian" | "grad" => Some(Self::Gradians),
+            _ => None,
+        }
+    }
+
+    /// Convert an angle in this unit to radians
+    pub fn to_radians(self, angle: f64) -> f64 {
+        match self {
+            Self::Radians => angle,
+            Self::Degrees => angle.to_radians(),
+            Self::Gradians => angle * std::f64::consts::PI / 200.0,
+        }
+    }
+
+    /// Convert an angle in radians to this unit
+    pub fn from_radians(self, angle: f64) -> f64 {
+        match self {
+            Self::Radians => angle,
+            Self::Degrees => angle.to_degrees(),
+            Self::Gradians => angle * 200.0 / std::f64::consts::PI,
+        }
+    }
+}
+
+impl std::fmt::Display for AngleMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Radians => write!(f, "radians"),
+            Self::Degrees => write!(f, "degrees"),
+            Self::Gradians => write!(f, "gradians"),
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The source of the current time, for `now()` and `timestamp()`
+/// Set with `VirtualMachine::set_clock`; the default is the system clock
+///
+/// Hosts and tests can use a `FixedClock`, so programs that read the time give the same results
+/// Closures returning the time since the unix epoch are clocks
+pub trait Clock {
+    /// Get the current time, as the duration since the unix epoch
+    fn now(&mut self) -> Duration;
+}
+
+impl std::fmt::Debug for dyn Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Clock")
+    }
+}
+
+impl<F: FnMut() -> Duration> Clock for F {
+    fn now(&mut self) -> Duration {
+        self()
+    }
+}
+
+/// The system clock, used by default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+impl Clock for SystemClock {
+    fn now(&mut self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
+
+/// A clock stopped at a fixed time, in seconds since the unix epoch
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedClock(pub u64);
+impl Clock for FixedClock {
+    fn now(&mut self) -> Duration {
+        Duration::from_secs(self.0)
+    }
+}
+
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Units of a duration, largest first, and their length in milliseconds
+const DURATION_UNITS: [(&str, i128); 5] = [
+    ("d", 86_400_000),
+    ("h", 3_600_000),
+    ("m", 60_000),
+    ("s", 1000),
+    ("ms", 1),
+];
+
+/// Format a duration in milliseconds as a human-readable string, such as `2h 13m 5s`
+/// Units that are zero are left out
+pub fn format_duration(milliseconds: i128) -> String {
+    let mut remaining = milliseconds.unsigned_abs();
+    let mut parts = vec![];
+    for (unit, length) in DURATION_UNITS {
+        let count = remaining / length as u128;
+        remaining %= length as u128;
+        if count > 0 {
+            parts.push(format!("{count}{unit}"));
+        }
+    }
+
+    match (parts.is_empty(), milliseconds < 0) {
+        (true, _) => "0s".to_string(),
+        (false, true) => format!("-{}", parts.join(" ")),
+        (false, false) => parts.join(" "),
+    }
+}
+
+/// Parse a human-readable duration, such as `2h 13m 5s` or `1.5d`, into milliseconds
+/// Supports the units `d`, `h`, `m`, `s` and `ms`
+/// Returns None if the string is not a duration
+pub fn parse_duration(input: &str) -> Option<i128> {
+    let input = input.trim();
+    let (sign, mut input) = match input.strip_prefix('-') {
+        Some(rest) => (-1.0, rest.trim_start()),
+        None => (1.0, input),
+    };
+
+    let mut total = 0.0;
+    let mut any = false;
+    while !input.is_empty() {
+        let number_len = input
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(input.len());
+        let count = input[..number_len].parse::<f64>().ok()?;
+        input = &input[number_len..];
+
+        let unit_len = input
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(input.len());
+        let unit = &input[..unit_len];
+        let (_, length) = DURATION_UNITS.iter().find(|(u, _)| *u == unit)?;
+        input = input[unit_len..].trim_start();
+
+        total += count * *length as f64;
+        any = true;
+    }
+
+    any.then(|| (sign * total).round() as i128)
+}
+
+/// Get the year, month and day of a number of days since the unix epoch
+fn civil_from_days(days: i64) -> (i64, usize, i64) {
+    // Days are counted from 0000-03-01, so the leap day is at the end of each year
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month as usize, day)
+}
+
+/// Format a timestamp, in seconds since the unix epoch, as a UTC date
+/// Supports `%Y`, `%m`, `%d`, `%H`, `%M`, `%S`, `%A`, `%a`, `%B`, `%b`, `%s` and `%%`
+/// Returns None if the format contains any other specifier
+pub fn format_date(timestamp: i64, format: &str) -> Option<String> {
+    let days = timestamp.div_euclid(86400);
+    let seconds = timestamp.rem_euclid(86400);
+    let (year, month, day) = civil_from_days(days);
+    let weekday = WEEKDAYS[(days + 3).rem_euclid(7) as usize];
+    let month_name = MONTHS[month - 1];
+
+    let mut output = String::with_capacity(format.len());
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            'Y' => output.push_str(&format!("{year:04}")),
+            'm' => output.push_str(&format!("{month:02}")),
+            'd' => output.push_str(&format!("{day:02}")),
+            'H' => output.push_str(&format!("{:02}", seconds / 3600)),
+            'M' => output.push_str(&format!("{:02}", seconds / 60 % 60)),
+            'S' => output.push_str(&format!("{:02}", seconds % 60)),
+            'A' => output.push_str(weekday),
+            'a' => output.push_str(&weekday[..3]),
+            'B' => output.push_str(month_name),
+            'b' => output.push_str(&month_name[..3]),
+            's' => output.push_str(&timestamp.to_string()),
+            '%' => output.push('%'),
+            _ => return None,
+        }
+    }
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{value::Value, Lavendeux};
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn test_clock() {
+        let mut lav = Lavendeux::new();
+        lav.set_clock(Box::new(FixedClock(1_700_000_000)));
+        assert_eq!(
+            lav.run("timestamp()").unwrap(),
+            Value::integer(1_700_000_000)
+        );
+        assert_eq!(
+            lav.run("format_date(timestamp(), '%A %Y-%m-%dT%H:%M:%SZ')")
+                .unwrap()
+                .to_string(),
+            "Tuesday 2023-11-14T22:13:20Z"
+        );
+
+        // Closures are clocks, and the time is still recorded for replays
+        let ticks = Rc::new(Cell::new(0));
+        let clock = ticks.clone();
+        lav.set_clock(Box::new(move || {
+            clock.set(clock.get() + 1);
+            Duration::from_millis(1500 * clock.get())
+        }));
+        lav.record_replay();
+        assert_eq!(lav.run("now()").unwrap().to_string(), "1.5");
+        assert_eq!(lav.run("timestamp()").unwrap(), Value::integer(3));
+        let log = lav.take_replay_log().unwrap();
+        lav.replay(log);
+        assert_eq!(
+            lav.run("timestamp() + timestamp()").unwrap(),
+            Value::integer(4)
+        );
+        assert_eq!(ticks.get(), 2);
+    }
+}
@@ -0,0 +1,93 @@
+//! Case-insensitive and collation-aware string comparison
+//! Collation follows the usual dictionary order; letters are compared without their accents or case
+//! first, so `éclair` sorts between `eclair` and `ecole`, and accents and then case only break ties
+use std::cmp::Ordering;
+
+/// Accented latin letters, and the letter they are sorted as
+const ACCENTED: [(char, &str); 19] = [
+    ('a', "àáâãäåāăą"),
+    ('c', "çćĉċč"),
+    ('d', "ďđð"),
+    ('e', "èéêëēĕėęě"),
+    ('g', "ĝğġģ"),
+    ('h', "ĥħ"),
+    ('i', "ìíîïĩīĭįı"),
+    ('j', "ĵ"),
+    ('k', "ķ"),
+    ('l', "ĺļľŀł"),
+    ('n', "ñńņňŉ"),
+    ('o', "òóôõöøōŏő"),
+    ('r', "ŕŗř"),
+    ('s', "śŝşš"),
+    ('t', "ţťŧ"),
+    ('u', "ùúûüũūŭůűų"),
+    ('w', "ŵ"),
+    ('y', "ýÿŷ"),
+    ('z', "źżž"),
+];
+
+/// Letters sorted as more than one letter
+const EXPANSIONS: [(char, &str); 4] = [('ß', "ss"), ('æ', "ae"), ('œ', "oe"), ('þ', "th")];
+
+/// Lowercase a string
+fn lowercase(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.chars().flat_map(char::to_lowercase)
+}
+
+/// Lowercase a string, and replace accented letters with the letters they are sorted as
+fn base_letters(s: &str) -> impl Iterator<Item = char> + '_ {
+    lowercase(s).flat_map(
+        |c| match EXPANSIONS.iter().find(|(letter, _)| *letter == c) {
+            Some((_, expansion)) => expansion.chars().collect(),
+            None => {
+                let base = ACCENTED.iter().find(|(_, accented)| accented.contains(c));
+                vec![base.map_or(c, |(base, _)| *base)]
+            }
+        },
+    )
+}
+
+/// Check if two strings are equal, ignoring case
+pub fn eq_ignore_case(a: &str, b: &str) -> bool {
+    lowercase(a).eq(lowercase(b))
+}
+
+/// Compare two strings in dictionary order
+/// Strings are compared by their letters first, then by their accents, then by case,
+/// with lowercase letters first
+pub fn compare(a: &str, b: &str) -> Ordering {
+    base_letters(a)
+        .cmp(base_letters(b))
+        .then_with(|| lowercase(a).cmp(lowercase(b)))
+        .then_with(|| {
+            let case = |s: &str| s.chars().map(char::is_uppercase).collect::<Vec<_>>();
+            case(a).cmp(&case(b))
+        })
+        .then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_ignore_case() {
+        let mut lav = Lavendeux::new();
+        for (source, expected) in [
+            ("'Größe' ~= 'GRÖSSE'", "false"),
+            ("'Größe' ~= 'GRÖßE'", "true"),
+            ("equals_ignore_case('Lavendeux', 'LAVENDEUX')", "true"),
+            (
+                "switch 'No' { ~= 'yes' => 1, ~= 'no', ~= 'n' => 2, _ => 3 }",
+                "2",
+            ),
+            (
+                "sort_by_locale(['zèbre', 'Zoe', 'étage', 'Eagle', 'zebra'])",
+                "[`Eagle`, `étage`, `zebra`, `zèbre`, `Zoe`]",
+            ),
+        ] {
+            let result = lav.run(source).unwrap();
+            assert_eq!(result.to_string(), expected, "{source}");
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Constant pools, which hold the literals of a block of bytecode
+//! The optimizer moves literals into a `POOL` instruction at the start of each block, and
+//! replaces each `PUSH` of one with a `PSHC` of its index, so repeated literals are stored once,
+//! and are decoded once when the block is loaded instead of every time they are pushed
+use super::OpCode;
+use crate::{
+    traits::{ByteDecodeError, ByteReader, SafeVecAlloc, SerializeToBytes},
+    value::{Primitive, Value},
+};
+use std::sync::Arc;
+
+/// The decoded constants of a block of bytecode, shared by every context running it
+pub type ConstantPool = Arc<[Value]>;
+
+/// Size in bytes of the index used by `PSHC`
+pub const CONSTANT_INDEX_SIZE: usize = 4;
+
+/// Write the `POOL` instruction for a set of serialized constants
+pub fn write_constant_pool(constants: &[Vec<u8>]) -> Vec<u8> {
+    let size = constants.iter().map(Vec::len).sum::<usize>();
+
+    let mut bytes = vec![OpCode::POOL as u8];
+    bytes.extend((constants.len() as u64).serialize_into_bytes());
+    bytes.extend((size as u64).serialize_into_bytes());
+    for constant in constants {
+        bytes.extend_from_slice(constant);
+    }
+    bytes
+}
+
+/// Decode the constant pool at the start of a block of bytecode
+/// Blocks that do not start with a `POOL` instruction have an empty pool
+pub fn read_constant_pool(code: &[u8]) -> Result<ConstantPool, ByteDecodeError> {
+    if code.first() != Some(&(OpCode::POOL as u8)) {
+        return Ok(ConstantPool::default());
+    }
+
+    let bytes = &mut ByteReader::new(&code[1..]);
+    let count = u64::deserialize_from_bytes(bytes)?;
+    let size = u64::deserialize_from_bytes(bytes)?;
+    if count > size || size > bytes.remaining().len() as u64 {
+        return Err(ByteDecodeError::UnexpectedEnd("ConstantPool".to_string()));
+    }
+
+    let mut constants = Vec::safe_alloc(count as usize)?;
+    for _ in 0..count {
+        constants.push(Value::Primitive(Primitive::deserialize_from_bytes(bytes)?));
+    }
+    Ok(constants.into())
+}
@@ -0,0 +1,53 @@
+use crate::value::{Number, NumberSymbol};
+
+/// Converts amounts between currencies, for arithmetic that mixes them such as `$1 + 1€`
+/// Set with `VirtualMachine::set_currency_converter`; without one, mixing currencies is an error
+///
+/// The right-hand amount is converted into the currency of the left-hand one
+/// Closures taking the amount and the symbol to convert it to are converters
+pub trait CurrencyConverter {
+    /// Convert an amount into the currency with the given symbol
+    /// Return None if there is no known rate, in which case the operation fails
+    fn convert(&mut self, amount: &Number, to: &NumberSymbol) -> Option<Number>;
+}
+
+impl std::fmt::Debug for dyn CurrencyConverter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CurrencyConverter")
+    }
+}
+
+impl<F: FnMut(&Number, &NumberSymbol) -> Option<Number>> CurrencyConverter for F {
+    fn convert(&mut self, amount: &Number, to: &NumberSymbol) -> Option<Number> {
+        self(amount, to)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{value::CheckedArithmetic, Lavendeux};
+
+    #[test]
+    fn test_currency() {
+        let mut lav = Lavendeux::new();
+        assert_eq!(lav.run("$5 + 2").unwrap().to_string(), "$7");
+        assert_eq!(lav.run("3 * $2 - $1").unwrap().to_string(), "$5");
+        assert_eq!(lav.run("2.5€ + 1€").unwrap().to_string(), "3.5€");
+        assert_eq!(lav.run("-$5").unwrap().to_string(), "-$5");
+
+        // Dividing two amounts of a currency gives a plain ratio
+        assert_eq!(lav.run("$10 / $4").unwrap().to_string(), "2.5");
+
+        let err = lav.run("$1 + 1€").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Cannot mix currencies `$` and `€`"));
+
+        lav.set_currency_converter(Some(Box::new(|amount: &Number, _: &_| {
+            amount.clone().checked_mul(Number::from_f64(2.0).ok()?).ok()
+        })));
+        assert_eq!(lav.run("$1 + 1€").unwrap().to_string(), "$3");
+        assert_eq!(lav.run("1€ - $1").unwrap().to_string(), "-1€");
+    }
+}
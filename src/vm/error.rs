@@ -6,7 +6,7 @@ use crate::{compiler::DebugProfile, lexer::Token, traits::IntoOwned, value::Valu
 
 /// The different types of errors that can occur during execution
 #[rustfmt::skip]
-#[derive(thiserror::Error, Debug, Clone)]
+#[derive(thiserror::Error, Debug, Clone, strum_macros::IntoStaticStr)]
 pub enum RuntimeErrorType {
     //
     // This category of errors deals with issues in the bytecode
@@ -17,6 +17,14 @@ pub enum RuntimeErrorType {
     #[error("An issue occurred with the Lavendeux VM - this is a bug\n= Unexpected end of bytecode while reading {0:?}")]
     UnexpectedEnd(OpCode),
 
+    /// A jump landed before the start of the bytecode
+    #[error("An issue occurred with the Lavendeux VM - this is a bug\n= {0:?} jumped outside of the bytecode")]
+    InvalidJump(OpCode),
+
+    /// A constant was read from outside of the constant pool
+    #[error("An issue occurred with the Lavendeux VM - this is a bug\n= Constant {0} is not in the constant pool")]
+    InvalidConstant(usize),
+
     /// Attempted to pop from an empty stack
     #[error("An issue occurred with the Lavendeux VM - this is a bug\n= {0:?} attempted to pop a value from an empty stack")]
     StackEmpty(OpCode),
@@ -52,14 +60,25 @@ pub enum RuntimeErrorType {
     /// Attempted to allocate too much memory
     #[error("{0}")]
     MemoryAllocation(#[from] std::collections::TryReserveError),
+
+    /// Attempted to build a collection larger than the memory budget allows
+    #[error("Collection too large\n= It would need {requested} bytes, but the memory budget is {budget} bytes")]
+    MemoryBudgetExceeded {
+        /// The number of bytes the collection would need
+        requested: usize,
+
+        /// The largest allocation allowed, in bytes
+        budget: usize,
+    },
     
     //
     // Errors during reference resolution
     //
 
     /// Caused by attempting to resolve a reference that is not in memory
-    #[error("Variable not defined\n= You can assign a value with `name = ...`")]
-    HashNotFound,
+    /// Holds the hash of the name, and the name itself if the debug profile knows it
+    #[error("{}\n= You can assign a value with `name = ...`", undefined_variable(.1))]
+    HashNotFound(u64, Option<String>),
 
     /// Caused by attempting to pull a value from a slot that is no longer valid
     /// This should never happen, I think? It's a bug if it does probably
@@ -116,6 +135,10 @@ pub enum RuntimeErrorType {
     #[error("In function")]
     Function,
 
+    /// An expression that could not be compiled into a function
+    #[error("Invalid expression: {0}")]
+    InvalidExpression(String),
+
     /// Attempted to call a non-function
     #[error("No such function exists")]
     UndefinedFunction,
@@ -140,6 +163,82 @@ pub enum RuntimeErrorType {
         index: usize,
     },
 
+    /// A function returned a value that cannot be converted to its return type
+    #[error("Return type incorrect;\n= Expected `{expected}`, found `{provided}`\n= {signature}")]
+    IncorrectReturnType {
+        /// The signature of the function
+        signature: String,
+
+        /// The declared return type
+        expected: ValueType,
+
+        /// The type of the returned value
+        provided: ValueType,
+    },
+
+    /// Too many nested function calls, usually from recursion without a base case
+    #[error("Maximum call depth of {limit} exceeded\n= {chain}")]
+    CallDepthExceeded {
+        /// The maximum number of nested calls
+        limit: usize,
+
+        /// The functions being called, innermost first
+        chain: String,
+    },
+
+    /// A value that does not match a user-defined structured type
+    #[error("Value does not match type `{0}`;\n= {1}")]
+    StructMismatch(String, String),
+
+    /// An angle mode that is not recognized
+    #[error("Unknown angle mode `{0}`\n= Expected `radians`, `degrees` or `gradians`")]
+    InvalidAngleMode(String),
+
+    /// A rounding mode that is not recognized
+    #[error("Unknown rounding mode `{0}`\n= Expected one of `half_even`, `half_up`, `half_down`, `up`, `down`, `ceiling` or `floor`")]
+    InvalidRoundingMode(String),
+
+    /// A date format containing a specifier that is not recognized
+    #[error("Invalid date format `{0}`\n= Expected specifiers among `%Y`, `%m`, `%d`, `%H`, `%M`, `%S`, `%A`, `%a`, `%B`, `%b`, `%s` or `%%`")]
+    InvalidDateFormat(String),
+
+    /// A string that is not a duration
+    #[error("Invalid duration `{0}`\n= Expected a list of amounts with units, such as `2h 13m 5s`, using `d`, `h`, `m`, `s` or `ms`")]
+    InvalidDuration(String),
+
+    /// A string that is not an IP address, or an integer that cannot be formatted as one
+    #[error("Invalid IP address `{0}`")]
+    InvalidIpAddress(String),
+
+    /// A string that is not a CIDR block
+    #[error("Invalid CIDR block `{0}`\n= Expected an address and a prefix length, such as `10.0.0.0/8`")]
+    InvalidCidr(String),
+
+    /// CSV text that could not be parsed
+    #[error("Invalid CSV; {0}")]
+    InvalidCsv(String),
+
+    /// Rows that cannot be written as a table
+    #[error("Invalid table\n= Expected an array of rows, each an array or an object")]
+    InvalidTable,
+
+    /// `-` used on a string while creative string operators are disabled
+    /// Holds the function that does the same thing
+    #[error("Operator `-` cannot be used on strings\n= Use `{0}` instead, or enable creative string operators")]
+    CreativeStringOperator(&'static str),
+
+    /// A file could not be read
+    #[error("Could not read `{0}`\n= {1}")]
+    ReadFile(String, String),
+
+    /// A file was read while the host has not enabled file access
+    #[error("Reading files is disabled\n= The host must enable file access before scripts can read `{0}`")]
+    FileAccessDisabled(String),
+
+    /// A replayed program asked for more nondeterministic inputs than were recorded
+    #[error("Replay log has no inputs left\n= The program may have changed since the log was recorded")]
+    ReplayExhausted,
+
     /// Call to THRW, or stdlib::throw
     #[error("{0}")]
     Custom(String),
@@ -149,6 +248,61 @@ pub enum RuntimeErrorType {
     Value(crate::value::ValueError),
 }
 
+impl RuntimeErrorType {
+    /// Get the stable code identifying the kind of error, such as `E0402` for undefined variables
+    /// Codes from `E0300` to `E0399` are bugs in the VM, and `E0400` to `E0499` are errors in the script
+    /// Errors in value operations keep their own codes
+    pub fn code(&self) -> &'static str {
+        match self {
+            RuntimeErrorType::UnexpectedEnd(_) => "E0300",
+            RuntimeErrorType::InvalidJump(_) => "E0301",
+            RuntimeErrorType::InvalidConstant(_) => "E0302",
+            RuntimeErrorType::StackEmpty(_) => "E0303",
+            RuntimeErrorType::InvalidOpcode(_) => "E0304",
+            RuntimeErrorType::InvalidType(_) => "E0305",
+            RuntimeErrorType::Decode(_, _) => "E0306",
+            RuntimeErrorType::BadType(_, _) => "E0307",
+            RuntimeErrorType::NestedReference => "E0308",
+            RuntimeErrorType::SlotRefInvalid => "E0309",
+            RuntimeErrorType::ReferenceNotResolved(_) => "E0310",
+            RuntimeErrorType::MemoryAllocation(_) => "E0400",
+            RuntimeErrorType::MemoryBudgetExceeded { .. } => "E0401",
+            RuntimeErrorType::HashNotFound(_, _) => "E0402",
+            RuntimeErrorType::DeleteLiteral => "E0403",
+            RuntimeErrorType::SetLiteral => "E0404",
+            RuntimeErrorType::NotAReference => "E0405",
+            RuntimeErrorType::InvalidValuesForRange(_) => "E0406",
+            RuntimeErrorType::InvalidStringsForRange => "E0407",
+            RuntimeErrorType::IndexingValue => "E0408",
+            RuntimeErrorType::IndexingType => "E0409",
+            RuntimeErrorType::IndexingBaseType => "E0410",
+            RuntimeErrorType::IteratorEmpty => "E0411",
+            RuntimeErrorType::Function => "E0412",
+            RuntimeErrorType::InvalidExpression(_) => "E0413",
+            RuntimeErrorType::UndefinedFunction => "E0414",
+            RuntimeErrorType::IncorrectFunctionArgCount(_) => "E0415",
+            RuntimeErrorType::IncorrectFunctionArg { .. } => "E0416",
+            RuntimeErrorType::IncorrectReturnType { .. } => "E0417",
+            RuntimeErrorType::CallDepthExceeded { .. } => "E0418",
+            RuntimeErrorType::StructMismatch(_, _) => "E0419",
+            RuntimeErrorType::InvalidAngleMode(_) => "E0420",
+            RuntimeErrorType::InvalidRoundingMode(_) => "E0421",
+            RuntimeErrorType::InvalidDateFormat(_) => "E0422",
+            RuntimeErrorType::InvalidDuration(_) => "E0423",
+            RuntimeErrorType::InvalidIpAddress(_) => "E0424",
+            RuntimeErrorType::InvalidCidr(_) => "E0425",
+            RuntimeErrorType::InvalidCsv(_) => "E0426",
+            RuntimeErrorType::InvalidTable => "E0427",
+            RuntimeErrorType::CreativeStringOperator(_) => "E0428",
+            RuntimeErrorType::ReadFile(_, _) => "E0429",
+            RuntimeErrorType::ReplayExhausted => "E0430",
+            RuntimeErrorType::Custom(_) => "E0431",
+            RuntimeErrorType::FileAccessDisabled(_) => "E0432",
+            RuntimeErrorType::Value(e) => e.code(),
+        }
+    }
+}
+
 /// The error wrapper for runtime errors
 #[derive(Debug, Clone)]
 pub struct RuntimeError {
@@ -178,11 +332,36 @@ impl std::fmt::Display for RuntimeError {
 }
 
 impl RuntimeError {
+    /// Get the stable code identifying the kind of error; see `RuntimeErrorType::code`
+    pub fn code(&self) -> &'static str {
+        self.error.code()
+    }
+
     /// Add debug information to the error
     pub fn with_context(self, debug_profile: &DebugProfile) -> Self {
         let token = debug_profile
             .current_token(self.pos)
             .map(|t| t.clone().into_owned());
-        Self { token, ..self }
+
+        // Undefined variables are named, rather than shown as a hash
+        let error = match self.error {
+            RuntimeErrorType::HashNotFound(hash, None) => {
+                RuntimeErrorType::HashNotFound(hash, debug_profile.name(hash).map(str::to_string))
+            }
+            error => error,
+        };
+        Self {
+            error,
+            token,
+            ..self
+        }
+    }
+}
+
+/// Describe an undefined variable, by name if it is known
+fn undefined_variable(name: &Option<String>) -> String {
+    match name {
+        Some(name) => format!("Variable `{name}` not defined"),
+        None => "Variable not defined".to_string(),
     }
 }
@@ -1,26 +1,98 @@
-use crate::{compiler::DebugProfile, value::ValueType};
+use crate::{
+    compiler::DebugProfile,
+    traits::{ByteDecodeError, ByteReader, SerializeToBytes},
+    value::{Value, ValueType},
+};
 
-use super::error::{RuntimeError, RuntimeErrorType};
+use super::{
+    constant_pool::{read_constant_pool, ConstantPool},
+    error::{RuntimeError, RuntimeErrorType},
+    value_source::ValueSource,
+};
+use std::sync::Arc;
 
 /// A contextual layer in the VM
+/// The bytecode and its constants are shared with the function being run, so calls do not copy them
 #[derive(Debug, Clone)]
 pub struct ExecutionContext {
-    code: Vec<u8>,
+    code: Arc<[u8]>,
+    constants: ConstantPool,
     pc: usize,
     debug_profile: Option<DebugProfile>,
     returns: ValueType,
+    signature: Option<String>,
+    registers: Vec<ValueSource>,
 }
 
 impl ExecutionContext {
-    pub fn new(code: Vec<u8>, debug_profile: Option<DebugProfile>, returns: ValueType) -> Self {
+    pub fn new(
+        code: Arc<[u8]>,
+        constants: ConstantPool,
+        debug_profile: Option<DebugProfile>,
+        returns: ValueType,
+        signature: Option<String>,
+    ) -> Self {
         Self {
             code,
+            constants,
             pc: 0,
             debug_profile,
             returns,
+            signature,
+            registers: vec![],
         }
     }
 
+    /// Reuse a finished context for a new call, keeping its allocations
+    /// The signature is copied into the existing buffer instead of allocating a new string
+    pub fn recycle(
+        &mut self,
+        code: Arc<[u8]>,
+        constants: ConstantPool,
+        debug_profile: Option<DebugProfile>,
+        returns: ValueType,
+        signature: Option<&str>,
+    ) {
+        self.code = code;
+        self.constants = constants;
+        self.pc = 0;
+        self.debug_profile = debug_profile;
+        self.returns = returns;
+        self.registers.clear();
+        match (&mut self.signature, signature) {
+            (Some(buffer), Some(signature)) => {
+                buffer.clear();
+                buffer.push_str(signature);
+            }
+            (buffer, signature) => *buffer = signature.map(str::to_string),
+        }
+    }
+
+    /// Release the parts of a finished context that should not outlive it
+    pub fn release(&mut self) {
+        self.debug_profile = None;
+        self.registers.clear();
+    }
+
+    /// Take the value out of a register, leaving it empty
+    /// Registers that were never written hold nil
+    pub fn take_register(&mut self, register: u8) -> ValueSource {
+        match self.registers.get_mut(register as usize) {
+            Some(value) => std::mem::replace(value, ValueSource::Literal(Value::nil())),
+            None => ValueSource::Literal(Value::nil()),
+        }
+    }
+
+    /// Write a value to a register, growing the register file if needed
+    pub fn set_register(&mut self, register: u8, value: ValueSource) {
+        let register = register as usize;
+        if register >= self.registers.len() {
+            self.registers
+                .resize(register + 1, ValueSource::Literal(Value::nil()));
+        }
+        self.registers[register] = value;
+    }
+
     /// Read the current context's bytecode
     pub fn pc(&self) -> usize {
         self.pc
@@ -36,6 +108,11 @@ impl ExecutionContext {
         &self.code
     }
 
+    /// Read a value from the constant pool of the current context's bytecode
+    pub fn constant(&self, index: usize) -> Option<&Value> {
+        self.constants.get(index)
+    }
+
     /// Read the current context's debug profile
     pub fn debug_profile(&self) -> Option<&DebugProfile> {
         self.debug_profile.as_ref()
@@ -47,6 +124,12 @@ impl ExecutionContext {
         self.returns
     }
 
+    /// Read the signature of the function running in this context
+    /// Returns None for the top-level context
+    pub fn signature(&self) -> Option<&str> {
+        self.signature.as_deref()
+    }
+
     /// Emit an error at the current position
     pub fn emit_err(&self, error: RuntimeErrorType) -> RuntimeError {
         let e = RuntimeError {
@@ -61,3 +144,73 @@ impl ExecutionContext {
         }
     }
 }
+
+/// The constant pool is not written, since it is decoded from the bytecode again
+impl SerializeToBytes for ExecutionContext {
+    fn serialize_into_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend(self.code.len().serialize_into_bytes());
+        bytes.extend_from_slice(&self.code);
+        bytes.extend(self.pc.serialize_into_bytes());
+        bytes.extend(self.debug_profile.serialize_into_bytes());
+        bytes.push(self.returns as u8);
+        bytes.extend(self.signature.serialize_into_bytes());
+        bytes.extend(self.registers.serialize_into_bytes());
+
+        bytes
+    }
+
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
+        let code: Arc<[u8]> = bytes.read_prefixed("ExecutionContext")?.into();
+        let constants = read_constant_pool(&code)?;
+        let pc = usize::deserialize_from_bytes(bytes)?;
+        let debug_profile = Option::<DebugProfile>::deserialize_from_bytes(bytes)?;
+        let returns = u8::deserialize_from_bytes(bytes)?;
+        let returns = ValueType::from_u8(returns).ok_or_else(|| {
+            ByteDecodeError::MalformedData(
+                "ExecutionContext".to_string(),
+                "Invalid return type".to_string(),
+            )
+        })?;
+        let signature = Option::<String>::deserialize_from_bytes(bytes)?;
+        let registers = Vec::<ValueSource>::deserialize_from_bytes(bytes)?;
+
+        Ok(Self {
+            code,
+            constants,
+            pc,
+            debug_profile,
+            returns,
+            signature,
+            registers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{vm::VirtualMachine, Lavendeux};
+
+    #[test]
+    fn test_context_pool() {
+        let mut vm = VirtualMachine::new();
+        let (profile, bytecode) = Lavendeux::new()
+            .compile("f(n) = n < 1 ? 'done' : f(n - 1)\ng(x) = x * 2\nf(100) + g(2)")
+            .unwrap();
+        let result = vm.run(bytecode.clone(), Some(profile.clone())).unwrap();
+        assert_eq!(result.to_string(), "done4");
+
+        // Finished calls leave their contexts behind for the next run
+        let pooled = vm.pooled_contexts();
+        assert!(pooled > 1);
+        let result = vm.run(bytecode, Some(profile)).unwrap();
+        assert_eq!(result.to_string(), "done4");
+        assert_eq!(vm.pooled_contexts(), pooled);
+
+        // Errors inside a call still name the right function after recycling
+        let (profile, bytecode) = Lavendeux::new().compile("h(x) = x / 0\nh(1)").unwrap();
+        let error = vm.run(bytecode, Some(profile)).unwrap_err();
+        assert!(error.to_string().contains("h(x)"), "{error}");
+    }
+}
@@ -5,8 +5,247 @@ use crate::value::StdFunctionSet;
 const STDLIB: &'static [u8] = include_bytes!("../../stdlib/stdlib.lbc");
 
 pub fn load_stdlib(mem: &mut MemoryManager) {
-    match StdFunctionSet::deserialize_from_bytes(&mut STDLIB.iter().copied()) {
+    match StdFunctionSet::from_bytes(STDLIB) {
         Ok(set) => set.into_mem(mem),
         Err(e) => eprintln!("Failed to load stdlib: {}", e),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{value::Value, Lavendeux};
+
+    #[test]
+    fn test_concat_and_extend() {
+        let mut lav = Lavendeux::new();
+        let result = lav.run("concat(['a', 'b'])").unwrap();
+        assert_eq!(result, Value::string("ab".to_string()));
+
+        let result = lav.run("join(['a', 'b'], ', ')").unwrap();
+        assert_eq!(result, Value::string("a, b".to_string()));
+
+        let result = lav.run("extend([1, 2], [3])").unwrap();
+        assert_eq!(result, lav.run("[1, 2, 3]").unwrap());
+    }
+
+    #[test]
+    fn test_calculus() {
+        let mut lav = Lavendeux::new();
+        let checks = [
+            "round(integrate('x ** 2', 0, 3)['value'], 6) == 9",
+            "round(integrate(sin, 0, pi)['value'], 6) == 2",
+            "integrate('x', 0, 1)['error'] < 0.00000001",
+            "round(derivative('x ** 3', 2)['value'], 6) == 12",
+            "round(derivative(cos, 0)['value'], 6) == 0",
+            "round(bisect('x ** 2 - 4', 0, 5), 8) == 2",
+            "round(bisect(cos, 0, 3), 8) == round(pi / 2, 8)",
+        ];
+        for check in checks {
+            assert_eq!(lav.run(check).unwrap(), Value::boolean(true), "{check}");
+        }
+
+        assert!(lav.run("bisect('x ** 2 + 1', 0, 5)").is_err());
+    }
+
+    #[test]
+    fn test_solve() {
+        let mut lav = Lavendeux::new();
+        let checks = [
+            "round(solve('x ** 2 - 4', 'x'), 8) == 2",
+            "round(solve('x ** 2 - 4', -1), 8) == -2",
+            "round(solve('y ** 3 - 8', 'y'), 8) == 2",
+            "round(solve(cos, 1), 8) == round(pi / 2, 8)",
+            "round(bisect('x - 2', 0, 4), 8) == 2",
+        ];
+        for check in checks {
+            assert_eq!(lav.run(check).unwrap(), Value::boolean(true), "{check}");
+        }
+
+        // No real root, or too few iterations to reach one
+        for source in [
+            "solve('x ** 2 + 1', 0.5)",
+            "solve('x ** 2 - 4', 100, 0.0000000001, 2)",
+        ] {
+            let error = lav.run(source).unwrap_err();
+            assert!(error.to_string().contains("Did not converge"), "{source}");
+        }
+
+        // A flat guess gives Newton's method nowhere to go
+        let error = lav.run("solve('x ** 2 - 4', 0)").unwrap_err();
+        assert!(error.to_string().contains("Found no slope"), "{error}");
+
+        // Bounds that do not bracket a root
+        let error = lav.run("bisect('x ** 2 - 4', 3, 5)").unwrap_err();
+        assert!(error.to_string().contains("opposite signs"), "{error}");
+
+        let error = lav
+            .run("bisect('x ** 2 - 4', 0, 5, 0.0000000001, 3)")
+            .unwrap_err();
+        assert!(error.to_string().contains("Did not converge"), "{error}");
+    }
+
+    #[test]
+    fn test_special_functions() {
+        let mut lav = Lavendeux::new();
+        let checks = [
+            "round(gamma(5), 8) == 24",
+            "round(gamma(1), 8) == 1",
+            "round(gamma(0.5) ** 2, 8) == round(pi, 8)",
+            "round(gamma(-0.5), 8) == round(-2 * sqrt(pi), 8)",
+            "erf(0) == 0",
+            "erf(-1) == -erf(1)",
+            "round(erf(3), 6) == 0.999978",
+            "factorial(0) == 1",
+            "factorial(20) == 2432902008176640000",
+            "nCr(0, 0) == 1",
+            "nCr(5, -1) == 0",
+            "nCr(60, 30) == 118264581564861424",
+            "nPr(5, 0) == 1",
+            "gcd(0, 0) == 0",
+            "gcd(-12, -18) == 6",
+            "lcm(-4, 6) == 12",
+            "is_prime(0) == false",
+            "is_prime(-7) == false",
+            "is_prime(2) && is_prime(1000000007)",
+            "next_prime(0) == 2",
+            "fib(0) == 0",
+            "fib(1) == 1",
+        ];
+        for check in checks {
+            assert_eq!(lav.run(check).unwrap(), Value::boolean(true), "{check}");
+        }
+
+        // Poles, and results too large to represent, are errors
+        for source in [
+            "gamma(0)",
+            "gamma(-1)",
+            "gamma(-2)",
+            "factorial(40)",
+            "fib(200)",
+        ] {
+            let error = lav.run(source).unwrap_err();
+            assert_eq!(error.code(), "E0500", "{source}");
+        }
+
+        // As are negative inputs that have no meaning
+        for source in ["factorial(-1)", "fib(-1)"] {
+            let error = lav.run(source).unwrap_err();
+            assert!(
+                error.to_string().contains("must not be negative"),
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_finance() {
+        let mut lav = Lavendeux::new();
+        let checks = [
+            // Reference values
+            "round_with(fv(0.05, 10, 0, 1000), 2, 'half_even') == 1628.89",
+            "round_with(fv(0.05, 10, 100, 0), 2, 'half_even') == 1257.79",
+            "round_with(pv(0.05, 10, 100, 0), 2, 'half_even') == 772.17",
+            "round_with(pmt(0.01, 12, 1000), 2, 'half_even') == 88.85",
+            "round_with(pmt(0.05 / 12, 360, 200000), 2, 'half_even') == 1073.64",
+            "round(compound_interest(1000, 0.05, 10), 2) == 628.89",
+            "round(npv(0.1, [-1000, 500, 500, 500]), 2) == 243.43",
+            "round(irr([-1000, 500, 500, 500]), 4) == 0.2338",
+            "round(irr([-100, 110]), 8) == 0.1",
+            // A zero rate reduces to simple sums
+            "fv(0, 10, 100, 50) == 1050",
+            "pv(0, 10, 100, 50) == 1050",
+            "pmt(0, 12, 1200) == 100",
+            "compound_interest(1000, 0, 10) == 0",
+            "npv(0, [-1000, 500, 500, 500]) == 500",
+            "round(irr([-100, 100]), 8) == 0",
+            // Rounding modes
+            "round_with(2.345, 2, 'half_up') == 2.35",
+            "round_with(2.345, 2, 'half_even') == 2.34",
+            "round_with(-2.5, 0, 'half_up') == -3",
+        ];
+        for check in checks {
+            assert_eq!(lav.run(check).unwrap(), Value::boolean(true), "{check}");
+        }
+
+        // Schedules pay the principal off exactly
+        lav.run("flat = amortization(1200, 0, 12, 'half_even')")
+            .unwrap();
+        lav.run("loan = amortization(200000, 0.05 / 12, 360, 'half_even')")
+            .unwrap();
+        let checks = [
+            "flat.len() == 12",
+            "flat[0]['interest'] == 0 && flat[0]['principal'] == 100",
+            "flat[11]['balance'] == 0",
+            "loan[0]['interest'] == 833.33",
+            "loan[359]['balance'] == 0",
+        ];
+        for check in checks {
+            assert_eq!(lav.run(check).unwrap(), Value::boolean(true), "{check}");
+        }
+
+        // Cash flows with no sign change have no rate of return
+        let error = lav.run("irr([100, 100])").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Could not find a rate of return"),
+            "{error}"
+        );
+
+        let error = lav.run("round_with(2.5, 0, 'nearest')").unwrap_err();
+        assert_eq!(error.code(), "E0421");
+    }
+
+    #[test]
+    fn test_colors() {
+        let mut lav = Lavendeux::new();
+        for (source, expected) in [
+            ("color_to_hex(parse_color('#F80'))", "#ff8800"),
+            ("color_to_hex([300, 127.5, -1])", "#ff8000"),
+            ("rgb_to_hsl('#3366cc')['h'] == 220", "true"),
+            ("color_to_hex(hsl_to_rgb(220, 0.6, 0.5))", "#3366cc"),
+            ("color_to_hex(blend('#ff0000', '#0000ff'))", "#800080"),
+        ] {
+            assert_eq!(lav.run(source).unwrap().to_string(), expected, "{source}");
+        }
+
+        // Every field is a decimal, so results can be compared with object literals
+        let checks = [
+            "rgb_to_hsl('#ff0000') == {'h': 0.0, 's': 1.0, 'l': 0.5}",
+            "rgb_to_hsl('#3366cc') == {'h': 220.0, 's': 0.6, 'l': 0.5}",
+            "rgb_to_hsl('#808080') == {'h': 0.0, 's': 0.0, 'l': 0.502}",
+            "rgb_to_hsl('#000000') == {'h': 0.0, 's': 0.0, 'l': 0.0}",
+        ];
+        for check in checks {
+            assert_eq!(lav.run(check).unwrap(), Value::boolean(true), "{check}");
+        }
+
+        let output = lav.run_output("[0, 128, 255] @color").unwrap();
+        assert_eq!(output[0].to_string(), "#0080ff");
+        assert!(lav.run("parse_color('#12345')").is_err());
+        assert!(lav.run("parse_color('#ggg')").is_err());
+    }
+
+    #[test]
+    fn test_durations() {
+        let mut lav = Lavendeux::new();
+        let outputs = lav
+            .run_output("7985 @duration\n1.25 @duration\n7985250 @human_time")
+            .unwrap();
+        let outputs = outputs.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(outputs, vec!["2h 13m 5s", "1s 250ms", "2h 13m 5s 250ms"]);
+
+        // Components that are zero are left out, and nothing at all is `0s`
+        for (source, expected) in [
+            ("duration(3600.5)", "1h 500ms"),
+            ("duration(60)", "1m"),
+            ("duration(0)", "0s"),
+            ("duration(0.0004)", "0s"),
+            ("duration(-5)", "-5s"),
+            ("human_time(0)", "0s"),
+            ("human_time(86400001)", "1d 1ms"),
+        ] {
+            assert_eq!(lav.run(source).unwrap().to_string(), expected, "{source}");
+        }
+    }
+}
@@ -2,9 +2,14 @@
 //! Also provides scoping, referencing and functions
 
 use core::panic;
+use std::collections::HashMap;
 
 use super::{load_stdlib, value_source::ValueSource};
-use crate::value::{Function, Value};
+use crate::{
+    compiler::DebugProfile,
+    traits::{ByteDecodeError, ByteReader, SerializeToBytes},
+    value::{Function, Value},
+};
 
 mod slot;
 pub use slot::Slot;
@@ -19,6 +24,9 @@ pub struct MemoryManager {
     stack: Vec<Slot>,
     locks: Vec<usize>,
     frame_ptr: Vec<usize>,
+
+    // Names behind the hashes in memory, recorded from debug profiles; only used for display
+    names: HashMap<u64, String>,
 }
 impl MemoryManager {
     /// Create a new memory manager
@@ -28,6 +36,7 @@ impl MemoryManager {
             stack: Vec::new(),
             locks: Vec::new(),
             frame_ptr: Vec::new(),
+            names: HashMap::new(),
         }
     }
 
@@ -38,6 +47,7 @@ impl MemoryManager {
             stack: Vec::new(),
             locks: Vec::new(),
             frame_ptr: Vec::new(),
+            names: self.names.clone(),
         }
     }
 
@@ -45,6 +55,24 @@ impl MemoryManager {
     /// of the child
     pub fn eat_child(&mut self, child: Self) {
         self.globals = child.globals;
+        self.names = child.names;
+    }
+
+    /// Record the name behind a hash, so that dumps of memory show it instead of the hash
+    pub fn record_name(&mut self, hash: u64, name: &str) {
+        self.names.entry(hash).or_insert_with(|| name.to_string());
+    }
+
+    /// Record every name in a debug profile
+    pub fn record_names(&mut self, profile: &DebugProfile) {
+        for (hash, name) in profile.names() {
+            self.record_name(hash, name);
+        }
+    }
+
+    /// Get the recorded name behind a hash, if it is known
+    pub fn name(&self, hash: u64) -> Option<&str> {
+        self.names.get(&hash).map(String::as_str)
     }
 
     /// Load the standard library into this memory manager
@@ -113,21 +141,34 @@ impl MemoryManager {
         }
     }
 
+    /// Get the number of scopes that have been entered and not yet exited
+    pub fn scope_depth(&self) -> usize {
+        self.frame_ptr.len()
+    }
+
     /// Collects all working stack entries from the stack
     /// These are entries with a version of 0, and will be removed from the stack
     pub fn all_stack_blanks(&mut self) -> Vec<ValueSource> {
         let mut out = vec![];
-        for i in 0..self.stack.len() {
+        let mut i = 0;
+        while i < self.stack.len() {
             if self.stack[i].version() == 0 {
                 let slot = self.stack.remove(i);
                 if let Slot::Occupied { value, .. } = slot {
                     out.push(value);
                 }
+            } else {
+                i += 1;
             }
         }
         out
     }
 
+    /// Get the number of entries on the stack, including variables
+    pub fn stack_len(&self) -> usize {
+        self.stack.len()
+    }
+
     /// Peek at the top-most blank stack entry
     pub fn peek_blank(&self) -> Option<&ValueSource> {
         for i in (0..self.stack.len()).rev() {
@@ -165,7 +206,7 @@ impl MemoryManager {
                 Slot::Occupied {
                     value: ValueSource::Literal(Value::Function(func)),
                     ..
-                } => Some(func),
+                } => Some(func.as_ref()),
                 _ => None,
             })
             .collect::<Vec<_>>()
@@ -176,6 +217,18 @@ impl MemoryManager {
         &self.globals
     }
 
+    /// Get the recorded names of the variables kept between runs;
+    /// the globals, and the values at the bottom of the stack, outside of any frame
+    /// Variables whose names were never recorded are skipped
+    pub fn variable_names(&self) -> impl Iterator<Item = &str> {
+        let base = self.frame_ptr.first().copied().unwrap_or(self.stack.len());
+        let slots = self.globals.iter().chain(&self.stack[..base]);
+        slots.filter_map(|slot| match slot {
+            Slot::Occupied { name_hash, .. } => self.name(*name_hash),
+            Slot::Vacant { .. } => None,
+        })
+    }
+
     /// Write a value to the global scope
     pub fn write_global(&mut self, name_hash: u64, value: ValueSource, write_locked: bool) {
         for slot in self.globals.iter_mut().rev() {
@@ -189,6 +242,41 @@ impl MemoryManager {
             .push(Slot::new_occupied(name_hash, value, write_locked));
     }
 
+    /// Write a constant to the global scope
+    /// Constants are write-locked, but replace any existing global of the same name
+    pub fn write_constant(&mut self, name_hash: u64, value: ValueSource) {
+        let slot = Slot::new_occupied(name_hash, value, true);
+        match self
+            .globals
+            .iter_mut()
+            .rev()
+            .find(|s| s.check_name(name_hash))
+        {
+            Some(existing) => *existing = slot,
+            None => self.globals.push(slot),
+        }
+    }
+
+    /// Remove a constant from the global scope, returning its value
+    pub fn delete_constant(&mut self, name_hash: u64) -> Option<ValueSource> {
+        let slot = self
+            .globals
+            .iter_mut()
+            .rev()
+            .find(|s| s.check_name(name_hash) && s.write_locked())?;
+        slot.unlock();
+        slot.take()
+    }
+
+    /// Read a constant from the global scope
+    pub fn read_constant(&self, name_hash: u64) -> Option<&ValueSource> {
+        self.globals
+            .iter()
+            .rev()
+            .find(|s| s.check_name(name_hash) && s.write_locked())
+            .and_then(Slot::as_value)
+    }
+
     /// Write a value to the stack
     /// Returns a reference to the value
     pub fn write(&mut self, name_hash: u64, value: ValueSource) -> SlotRef {
@@ -214,6 +302,21 @@ impl MemoryManager {
         }
     }
 
+    /// Write a value to the innermost scope, hiding any variable of the same name
+    /// in outer scopes, or in the global scope
+    pub fn write_local(&mut self, name_hash: u64, value: ValueSource) {
+        let scope = self.frame_ptr.last().copied().unwrap_or(0);
+        let start = scope.max(self.last_valid_scope()).min(self.stack.len());
+        for slot in self.stack[start..].iter_mut().rev() {
+            if slot.check_name(name_hash) {
+                slot.put(value);
+                return;
+            }
+        }
+
+        self.stack.push(Slot::new_occupied(name_hash, value, false));
+    }
+
     /// Get a reference to a value in the memory manager
     pub fn read(&self, name_hash: u64) -> Option<&ValueSource> {
         // Check main stack
@@ -357,6 +460,31 @@ impl MemoryManager {
     }
 }
 
+/// Serialized with every slot, so references into memory stay valid once restored
+/// Recorded names are not serialized; they come back with the debug profiles of the restored code
+impl SerializeToBytes for MemoryManager {
+    fn serialize_into_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend(self.globals.serialize_into_bytes());
+        bytes.extend(self.stack.serialize_into_bytes());
+        bytes.extend(self.locks.serialize_into_bytes());
+        bytes.extend(self.frame_ptr.serialize_into_bytes());
+
+        bytes
+    }
+
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
+        Ok(Self {
+            globals: Vec::<Slot>::deserialize_from_bytes(bytes)?,
+            stack: Vec::<Slot>::deserialize_from_bytes(bytes)?,
+            locks: Vec::<usize>::deserialize_from_bytes(bytes)?,
+            frame_ptr: Vec::<usize>::deserialize_from_bytes(bytes)?,
+            names: HashMap::new(),
+        })
+    }
+}
+
 impl std::fmt::Display for MemoryManager {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "[Globals]")?;
@@ -365,7 +493,7 @@ impl std::fmt::Display for MemoryManager {
                 continue;
             }
 
-            writeln!(f, "{i:08X}   {slot}")?;
+            writeln!(f, "{i:08X}   {}", slot.named(self))?;
         }
 
         writeln!(f, "\n[Stack]")?;
@@ -376,7 +504,7 @@ impl std::fmt::Display for MemoryManager {
                     writeln!(f, "{i:08X} --- SCOPE LOCKED ---")?;
                 }
             }
-            writeln!(f, "{i:08X}   {slot}")?;
+            writeln!(f, "{i:08X}   {}", slot.named(self))?;
         }
 
         if self.is_frame_boundary(self.stack.len()) {
@@ -389,3 +517,45 @@ impl std::fmt::Display for MemoryManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{compiler::HashString, vm::OpCode, Lavendeux};
+
+    #[test]
+    fn test_all_stack_blanks() {
+        let mut mem = MemoryManager::new();
+        mem.push_blank(ValueSource::Literal(Value::integer(1)));
+        mem.push_blank(ValueSource::Literal(Value::integer(2)));
+        mem.write(1, ValueSource::Literal(Value::integer(3)));
+        mem.push_blank(ValueSource::Literal(Value::integer(4)));
+
+        // Neighbouring blanks are both removed, and variables are kept
+        assert_eq!(mem.all_stack_blanks().len(), 3);
+        assert_eq!(mem.stack_len(), 1);
+        assert!(mem.read(1).is_some());
+    }
+
+    #[test]
+    fn test_memory_dump_names() {
+        let mut mem = MemoryManager::new();
+        mem.write_global(
+            "total".hash_str(),
+            ValueSource::Literal(Value::integer(1)),
+            false,
+        );
+        assert!(!mem.to_string().contains("total"));
+
+        mem.record_name("total".hash_str(), "total");
+        let dump = mem.to_string();
+        assert!(dump.contains("Occupied (total v"), "{dump}");
+
+        // Names come from the debug profile of the program that wrote them
+        let mut lav = Lavendeux::new();
+        let (profile, mut bytecode) = lav.compile("total = 1").unwrap();
+        bytecode.push(OpCode::PRNTM as u8);
+        let dump = lav.execute(bytecode, Some(profile)).unwrap().to_string();
+        assert!(dump.contains("Occupied (total v"), "{dump}");
+    }
+}
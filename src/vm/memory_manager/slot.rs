@@ -1,4 +1,8 @@
-use crate::vm::value_source::ValueSource;
+use super::MemoryManager;
+use crate::{
+    traits::{ByteDecodeError, ByteReader, SerializeToBytes},
+    vm::value_source::ValueSource,
+};
 
 /// A slot in the memory manager
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -164,11 +168,38 @@ impl Slot {
             _ => {}
         }
     }
+
+    /// Unlock the slot, allowing writes
+    pub fn unlock(&mut self) {
+        if let Slot::Occupied { write_locked, .. } = self {
+            *write_locked = false;
+        }
+    }
+}
+
+/// A slot displayed with the name recorded for its hash, if there is one
+pub(crate) struct NamedSlot<'a>(&'a Slot, Option<&'a str>);
+
+impl Slot {
+    /// Display the slot using the names recorded in a memory manager
+    pub(crate) fn named<'a>(&'a self, mem: &'a MemoryManager) -> NamedSlot<'a> {
+        let name = match self {
+            Slot::Occupied { name_hash, .. } => mem.name(*name_hash),
+            _ => None,
+        };
+        NamedSlot(self, name)
+    }
 }
 
 impl std::fmt::Display for Slot {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
+        NamedSlot(self, None).fmt(f)
+    }
+}
+
+impl std::fmt::Display for NamedSlot<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
             Slot::Vacant { version } => write!(f, "Vacant (v={})", version),
             Slot::Occupied {
                 name_hash,
@@ -182,14 +213,64 @@ impl std::fmt::Display for Slot {
                     if let ValueSource::Literal(crate::value::Value::Function(func)) = value {
                         write!(f, "{}FN = {func}", if *write_locked { "#RO#" } else { "" })
                     } else {
-                        write!(
-                            f,
-                            "Occupied ({}{name_hash}v{version} = {value:?})",
-                            if *write_locked { "#RO#" } else { "" }
-                        )
+                        let ro = if *write_locked { "#RO#" } else { "" };
+                        match self.1 {
+                            Some(name) => {
+                                write!(f, "Occupied ({ro}{name} v{version} = {value:?})")
+                            }
+                            None => write!(f, "Occupied ({ro}{name_hash}v{version} = {value:?})"),
+                        }
                     }
                 }
             }
         }
     }
 }
+
+impl SerializeToBytes for Slot {
+    fn serialize_into_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        match self {
+            Slot::Vacant { version } => {
+                bytes.push(0);
+                bytes.extend(version.serialize_into_bytes());
+            }
+
+            Slot::Occupied {
+                name_hash,
+                write_locked,
+                version,
+                value,
+            } => {
+                bytes.push(1);
+                bytes.extend(name_hash.serialize_into_bytes());
+                bytes.push(write_locked as u8);
+                bytes.extend(version.serialize_into_bytes());
+                bytes.extend(value.serialize_into_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
+        match u8::deserialize_from_bytes(bytes)? {
+            0 => Ok(Slot::Vacant {
+                version: u32::deserialize_from_bytes(bytes)?,
+            }),
+
+            1 => Ok(Slot::Occupied {
+                name_hash: u64::deserialize_from_bytes(bytes)?,
+                write_locked: u8::deserialize_from_bytes(bytes)? != 0,
+                version: u32::deserialize_from_bytes(bytes)?,
+                value: ValueSource::deserialize_from_bytes(bytes)?,
+            }),
+
+            _ => Err(ByteDecodeError::MalformedData(
+                "Slot".to_string(),
+                "Invalid slot type".to_string(),
+            )),
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::vm::value_source::ValueSource;
+use crate::{
+    traits::{ByteDecodeError, ByteReader, SerializeToBytes},
+    vm::value_source::ValueSource,
+};
 
 use super::{MemoryManager, Slot};
 
@@ -133,3 +136,50 @@ impl SlotRef {
         }
     }
 }
+
+impl SerializeToBytes for SlotRef {
+    fn serialize_into_bytes(self) -> Vec<u8> {
+        let (kind, i, name_hash, version) = match self {
+            SlotRef::Stack {
+                i,
+                name_hash,
+                version,
+            } => (0, i, name_hash, version),
+            SlotRef::Global {
+                i,
+                name_hash,
+                version,
+            } => (1, i, name_hash, version),
+        };
+
+        let mut bytes = vec![kind];
+        bytes.extend(i.serialize_into_bytes());
+        bytes.extend(name_hash.serialize_into_bytes());
+        bytes.extend(version.serialize_into_bytes());
+        bytes
+    }
+
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
+        let kind = u8::deserialize_from_bytes(bytes)?;
+        let i = usize::deserialize_from_bytes(bytes)?;
+        let name_hash = u64::deserialize_from_bytes(bytes)?;
+        let version = u32::deserialize_from_bytes(bytes)?;
+
+        match kind {
+            0 => Ok(SlotRef::Stack {
+                i,
+                name_hash,
+                version,
+            }),
+            1 => Ok(SlotRef::Global {
+                i,
+                name_hash,
+                version,
+            }),
+            _ => Err(ByteDecodeError::MalformedData(
+                "SlotRef".to_string(),
+                "Invalid reference type".to_string(),
+            )),
+        }
+    }
+}
@@ -1,12 +1,22 @@
-use strum::EnumString;
+use strum::{EnumString, FromRepr};
+
+/// Version of the opcode numbering
+/// Stored in the header of compiled modules, so bytecode compiled against a different
+/// set of opcodes is refused instead of being silently misread
+///
+/// Opcode values are stable; new opcodes are given new values, and existing values are never
+/// reused. Bump this version only if an existing opcode's value or encoding changes.
+pub const OPCODE_SET_VERSION: u16 = 5;
 
 /// The set of opcodes that the VM can execute
 /// if the `--allow-syscalld` compiler flag is set, the compiler will allow the use of the `__syscalld` function
 /// which can be used to call opcodes directly from the source code
 /// See the stdlib source code for examples of how to use this function
 /// (Please do not use this function)
+///
+/// Each opcode has an explicit value, which must not change once assigned - see `OPCODE_SET_VERSION`
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, FromRepr)]
 #[rustfmt::skip]
 pub enum OpCode {
     ////////////////////////
@@ -15,50 +25,64 @@ pub enum OpCode {
     
     /// Push a value onto the stack
     /// `PUSH <TypeCode> <Value>`
-    PUSH,
+    PUSH = 0x00,
 
     /// Remove and discard the top value from the stack
     /// Consumes 1 stack value
     /// `POP``
-    POP,
+    POP = 0x01,
 
     /// Duplicate the top value on the stack
     /// Pushes 1 value onto the stack
     /// `DUP`
-    DUP,
+    DUP = 0x02,
 
     /// Swap the top two values on the stack
     /// Consumes 2 stack values
     /// `SWP`
-    SWP,
+    SWP = 0x03,
 
     ///////////////////
     /// Control flow //
     ///////////////////
+    // A `<Jump>` is an i32 offset from the position of the offset itself, to the target
+    // See `jump_target`
     
     /// Jump to a specific location in the bytecode
-    /// `JMP <u64>`
-    JMP,
+    /// `JMP <Jump>`
+    JMP = 0x04,
 
     /// Jump to a specific location in the bytecode if the top value on the stack is true
     /// Consumes 1 stack value
-    /// `JMPR <u64>`
-    JMPT,
+    /// `JMPT <Jump>`
+    JMPT = 0x05,
 
     /// Jump to a specific location in the bytecode if the top value on the stack is false
     /// Consumes 1 stack value
-    /// `JMPR <u64>`
-    JMPF,
+    /// `JMPF <Jump>`
+    JMPF = 0x06,
 
     /// Jump to a specific location in the bytecode if the top value on the stack is empty
     /// Consumes 1 stack value
-    /// `JMPE <u64>`
-    JMPE,
+    /// `JMPE <Jump>`
+    JMPE = 0x07,
 
     /// Jump to a specific location in the bytecode if the top value on the stack is not empty
     /// Consumes 1 stack value
-    /// `JMPNE <u64>`
-    JMPNE,
+    /// `JMPNE <Jump>`
+    JMPNE = 0x08,
+
+    /// Jump to a specific location in the bytecode if the top value on the stack is not nil
+    /// Consumes 1 stack value
+    /// `JMPNN <Jump>`
+    JMPNN = 0x09,
+
+    /// Jump to a location from a table, using the integer value at the top of the stack
+    /// Table entries are for consecutive integers, starting at the given minimum
+    /// Values not in the table jump to the default location
+    /// Consumes 1 stack value
+    /// `JTBL <i128 min> <u64 count> <Jump default> <Jump>*count`
+    JTBL = 0x0A,
 
     /////////////////////////
     // Memory manipulation //
@@ -67,31 +91,31 @@ pub enum OpCode {
     /// Read a value from memory by reference
     /// Pushes 1 value onto the stack
     /// `REF <Name Hash>`
-    REF,
+    REF = 0x0B,
 
     /// Check that the value at the top of the stack
     /// Has a valid value available
     /// Peek the value at the top of the stack
     /// `VREF`
-    VREF,
+    VREF = 0x0C,
 
     /// Consumes a reference and pushes the value it points to onto the stack
     /// Consumes 1 stack value [Reference]
     /// Pushes 1 value onto the stack [Value]
-    RREF,
+    RREF = 0x0D,
 
     /// Write a value to a reference
     /// If the last part of the reference is not valid, it is created
     /// Consumes 2 stack values (value, reference)
     /// Pushes 1 value onto the stack
     /// `WREF`
-    WREF,
+    WREF = 0x0E,
 
     /// Delete a value from a reference
     /// Consumes  stack value (reference)
     /// Pushes 1 value onto the stack
     /// `WREF`
-    DREF,
+    DREF = 0x0F,
 
     ////////////////////////
     // Scope manipulation //
@@ -99,15 +123,15 @@ pub enum OpCode {
 
     /// Enter a new scope
     /// `SCI`
-    SCI,
+    SCI = 0x10,
 
     /// Exit the current scope
     /// `SCO`
-    SCO,
+    SCO = 0x11,
 
     /// Lock the current scope
     /// `SCL`
-    SCL,
+    SCL = 0x12,
 
     ////////////////////////
     // Value manipulation //
@@ -117,61 +141,75 @@ pub enum OpCode {
     /// Consumes 1 stack [value]
     /// Pushes 1 value onto the stack [Type name]
     /// `TYPE`
-    TYPE,
+    TYPE = 0x13,
     
     /// Convert the top value on the stack to a type
     /// Consumes 1 stack value; [Input Value]
     /// Pushes 1 value onto the stack; [Output Value]
     /// `CAST <TypeCode>`
-    CAST,
+    CAST = 0x14,
+
+    /// Check if the top value on the stack is of a type
+    /// Consumes 1 stack value; [Input Value]
+    /// Pushes 1 value onto the stack; [Bool]
+    /// `ISA <TypeCode>`
+    ISA = 0x15,
 
     /// Remove the first element from a collection
     /// Consumes 1 stack value; [Collection]
     /// Pushes 2 values onto the stack; [Rest; First]
-    NEXT,
+    NEXT = 0x16,
 
     /// Attempt to convert the top value on the stack to the type of the second value
     /// Consumes 2 stack values [Left, Right]
     /// Pushes 2 values onto the stack [Left, CastedRight]
     /// `LCST`
-    LCST,
+    LCST = 0x17,
 
     /// Build a new array from the top `n` values on the stack
     /// Consumes `n` stack values; [Value1, Value2, ..., ValueN]
     /// Pushes 1 value onto the stack; [Array]
     /// `MKAR <n>`
-    MKAR,
+    MKAR = 0x18,
 
     /// Build a new object from the top `2n` values on the stack
     /// Consumes `2n` stack values; [Key1, Value1, Key2, Value2, ..., KeyN, ValueN]
     /// Pushes 1 value onto the stack; [Object]
     /// `MKOB <n>`
-    MKOB,
+    MKOB = 0x19,
 
     /// Attempt to create a range from the top two values on the stack
     /// Consumes 2 stack values; [Start, End]
     /// Pushes 1 value onto the stack; [Range]
     /// `MKRG`
-    MKRG,
+    MKRG = 0x1A,
 
     /// Push a value onto an array
     /// Consumes 2 stack values; [Array, Value]
     /// Pushes 1 value onto the stack
     /// `PSAR`
-    PSAR,
+    PSAR = 0x1B,
 
     /// Push a value onto an object
     /// Consumes 3 stack values; [Object, Key, Value]
     /// Pushes 1 value onto the stack
     /// `PSOB`
-    PSOB,
+    PSOB = 0x1C,
 
     /// Index into the top value on the stack
     /// If the base is a reference, the index is added to it
     /// Consumes 2 stack values (base, index)
     /// Pushes 1 value onto the stack
     /// `IDEX`
-    IDEX,
+    IDEX = 0x1D,
+
+    /// Nil-safe variant of IDEX
+    /// The base is always resolved, and the result is nil if the base is nil
+    /// or if the index does not exist in it
+    /// Consumes 2 stack values (base, index)
+    /// Pushes 1 value onto the stack
+    /// `NIDX`
+    NIDX = 0x1E,
 
     ////////////////////
     // Arithmetic ops //
@@ -181,43 +219,43 @@ pub enum OpCode {
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `ADD`
-    ADD,
+    ADD = 0x1F,
 
     /// Subtract the top two values on the stack
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `SUB`
-    SUB,
+    SUB = 0x20,
 
     /// Multiply the top two values on the stack
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `MUL`
-    MUL,
+    MUL = 0x21,
 
     /// Divide the top two values on the stack
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `DIV`
-    DIV,
+    DIV = 0x22,
 
     /// Get the remainder of the top two values on the stack
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `REM`
-    REM,
+    REM = 0x23,
 
     /// Raise the top value on the stack to the power of the second value
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `POW`
-    POW,
+    POW = 0x24,
 
     /// Negate the top value on the stack
     /// Consumes 1 stack value
     /// Pushes 1 value onto the stack
     /// `NEG`
-    NEG,
+    NEG = 0x25,
 
 
     /////////////////
@@ -228,37 +266,37 @@ pub enum OpCode {
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `AND`
-    AND,
+    AND = 0x26,
 
     /// Bitwise OR the top two values on the stack
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `OR`
-    OR,
+    OR = 0x27,
 
     /// Bitwise XOR the top two values on the stack
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `XOR`
-    XOR,
+    XOR = 0x28,
 
     /// Bitwise NOT the top value on the stack
     /// Consumes 1 stack value
     /// Pushes 1 value onto the stack
     /// `NOT`
-    NOT,
+    NOT = 0x29,
 
     /// Bitwise shift left the top two values on the stack
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `SHL`
-    SHL,
+    SHL = 0x2A,
 
     /// Bitwise shift right the top two values on the stack
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `SHR`
-    SHR,
+    SHR = 0x2B,
 
     ////////////////////
     // Comparison ops //
@@ -268,49 +306,49 @@ pub enum OpCode {
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `EQ`
-    EQ,
+    EQ = 0x2C,
 
     /// Compare the top two values on the stack for inequality
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `NE`
-    NE,
+    NE = 0x2D,
 
     /// Compare the top two values on the stack for strict equality
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `SEQ`
-    SEQ,
+    SEQ = 0x2E,
 
     /// Compare the top two values on the stack for strict inequality
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `SNE`
-    SNE,
+    SNE = 0x2F,
 
     /// Compare the top two values on the stack for less than
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `LT`
-    LT,
+    LT = 0x30,
 
     /// Compare the top two values on the stack for less than or equal
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `LE`
-    LE,
+    LE = 0x31,
 
     /// Compare the top two values on the stack for greater than
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `GT`
-    GT,
+    GT = 0x32,
 
     /// Compare the top two values on the stack for greater than or equal
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `GE`
-    GE,
+    GE = 0x33,
 
     /////////////////
     // Logical ops //
@@ -320,19 +358,19 @@ pub enum OpCode {
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `LAND`
-    LAND,
+    LAND = 0x34,
 
     /// Logical OR the top two values on the stack
     /// Consumes 2 stack values
     /// Pushes 1 value onto the stack
     /// `LOR`
-    LOR,
+    LOR = 0x35,
 
     /// Logical NOT the top value on the stack
     /// Consumes 1 stack value
     /// Pushes 1 value onto the stack
     /// `LNOT`
-    LNOT,
+    LNOT = 0x36,
 
     //////////////////
     // Matching ops //
@@ -342,25 +380,26 @@ pub enum OpCode {
     /// Consumes 2 stack values (value, pattern)
     /// Pushes 1 value onto the stack
     /// `MTCH`
-    MTCH,
+    MTCH = 0x37,
 
     /// Match the top two values on the stack (contains)
+    /// Ranges are checked in constant time, and variables are checked without being copied
     /// Consumes 2 stack values (value, pattern)
     /// Pushes 1 value onto the stack
     /// `CNTN`
-    CNTN,
+    CNTN = 0x38,
 
     /// Match the top two values on the stack (starts with)
     /// Consumes 2 stack values (value, pattern)
     /// Pushes 1 value onto the stack
     /// `STWT`
-    STWT,
+    STWT = 0x39,
 
     /// Match the top two values on the stack (ends with)
     /// Consumes 2 stack values (value, pattern)
     /// Pushes 1 value onto the stack
     /// `EDWT`
-    EDWT,
+    EDWT = 0x3A,
 
     //////////////////
     // Function ops //
@@ -369,35 +408,35 @@ pub enum OpCode {
     /// Write a new function to memory
     /// Consumes 1 stack value; [Function]
     /// `WRFN`
-    WRFN,
+    WRFN = 0x3B,
     
     /// Create a new function
     /// Pushes 1 value onto the stack; [Function]
     /// `MKFN <string: name> <u8 returns> <u64 len> [ body ]`
-    MKFN,
+    MKFN = 0x3C,
 
     /// Add a default value to a function argument
     /// Consumes 2 stack values; [Function, Default]
     /// Pushes 1 value onto the stack; [Function]
     /// `FDFT <u16: idx>`
-    FDFT,
+    FDFT = 0x3D,
 
     /// Set the signature of a function
     /// Consumes 1 stack value; [Function]
     /// Pushes 1 value onto the stack; [Function]
     /// `FSIG`
-    FSIG,
+    FSIG = 0x3E,
 
     /// Call a function
     /// Consumes `n` stack values; [Arg1, Arg2, ..., ArgN]
     /// Pushes 1 value onto the stack; [Return]
     /// `CALL <u64: name_hash> <u64: N>`
-    CALL,
+    CALL = 0x3F,
 
     /// Return from a function
     /// Consumes 1 stack value; [Return]
     /// `RET`
-    RET,
+    RET = 0x40,
 
     ///////////////
     // Debug ops //
@@ -405,17 +444,18 @@ pub enum OpCode {
     
     /// Print the state of the memory manager
     /// `PRNTM`
-    PRNTM,
+    PRNTM = 0x41,
 
-    /// Print the top value on the stack
+    /// Print the top value on the stack, to the VM's output sink
     /// Consumes 1 stack value
     /// `PRNT`
-    PRNT,
+    PRNT = 0x42,
 
     /// Throw an error and halt execution
+    /// The message is also sent to the VM's output sink
     /// Consumes 1 stack value; [Error msg]
     /// `THRW`
-    THRW,
+    THRW = 0x43,
 
     //////////////
     // Misc ops //
@@ -426,98 +466,446 @@ pub enum OpCode {
     /// Consumes 1 stack value; [Value]
     /// Pushes 1 value onto the stack; [Sorted]
     /// `SORT`
-    SORT,
+    SORT = 0x44,
 
     /// Read a file from the filesystem
-    /// Consumes 1 stack value; [Path] 
+    /// Consumes 2 stack values; [Path, Lines]
     /// Pushes 1 value onto the stack; [File]
-    /// If lines is 0, the entire file is read
+    /// If lines is 0 or less, the entire file is read
     /// Otherwise, the last `lines` lines are read
-    /// `READF <lines: u64>
-    READF,
+    /// `READF`
+    READF = 0x45,
 
     /// List all functions in memory
     /// Pushes 1 value onto the stack; [Functions]
     /// `LSTFN`
-    LSTFN,
+    LSTFN = 0x46,
 
     /// Get the length of the top value on the stack
     /// Consumes 1 stack value [Value]
     /// Pushes 1 value onto the stack [Length]
     /// `LEN`
-    LEN,
+    LEN = 0x47,
 
     /// Split a string by a pattern
     /// Consumes 2 stack values; [String, Pattern]
     /// Pushes 1 value onto the stack; [Array]
     /// `SSPLT`
-    SSPLT,
+    SSPLT = 0x48,
 
     //////////////
     // Math ops //
     //////////////
     
     /// Calculate the tangent of the top value on the stack
-    /// Expects a value in radians
-    TAN,
+    /// Expects a value in the current angle mode
+    TAN = 0x49,
 
     /// Calculate the sine of the top value on the stack
-    /// Expects a value in radians
-    SIN,
+    /// Expects a value in the current angle mode
+    SIN = 0x4A,
 
     /// Calculate the cosine of the top value on the stack
-    /// Expects a value in radians
-    COS,
+    /// Expects a value in the current angle mode
+    COS = 0x4B,
 
     /// Calculate the arctangent2 of the top value on the stack
     /// Consumes 2 stack values; [Y, X]
-    ATAN2,
+    /// Returns a value in the current angle mode
+    ATAN2 = 0x4C,
 
     /// Calculate the arctangent of the top value on the stack
-    ATAN,
+    /// Returns a value in the current angle mode
+    ATAN = 0x4D,
 
     /// Calculate the arcsine of the top value on the stack
-    ASIN,
+    /// Returns a value in the current angle mode
+    ASIN = 0x4E,
 
     /// Calculate the arccosine of the top value on the stack
-    ACOS,
+    /// Returns a value in the current angle mode
+    ACOS = 0x4F,
 
     /// Calculate the hyperbolic tangent of the top value on the stack
-    TANH,
+    TANH = 0x50,
 
     /// Calculate the hyperbolic sine of the top value on the stack
-    SINH,
+    SINH = 0x51,
 
     /// Calculate the hyperbolic cosine of the top value on the stack
-    COSH,
+    COSH = 0x52,
 
     /// Round a value to a precision
     /// Consumes 2 stack values; [Value, Precision]
-    ROUND,
+    ROUND = 0x53,
 
     /// Logarithm
     /// Consumes 2 stack values; [Value, Base]
-    LOG,
+    LOG = 0x54,
 
     /// Inverse logarithm
     /// Consumes 2 stack values; [Value, Base]
-    ILOG,
+    ILOG = 0x55,
 
     /// Root
     /// Consumes 2 stack values; [Value, Root]
-    ROOT,
+    ROOT = 0x56,
 
     /// No operation
-    NOP,
+    NOP = 0x57,
+
+    /// Check if the top value on the stack matches a user-defined structured type
+    /// Consumes 1 stack value; [Input Value]
+    /// Pushes 1 value onto the stack; [Bool]
+    /// `ISST <StructType>`
+    ISST = 0x58,
+
+    /// Check that the top value on the stack matches a user-defined structured type
+    /// Leaves the value on the stack, or fails with a list of the mismatched fields
+    /// `CSST <StructType>`
+    CSST = 0x59,
+
+    /// Write a value to a global name, such as an enum
+    /// Consumes 1 stack value; [Value]
+    /// `WRGL <u64: name_hash>`
+    WRGL = 0x5A,
+
+    /// Call a method
+    /// If the first argument is an object holding a function under the method's name, that
+    /// function is called; otherwise the function with that name is called, as with `CALL`
+    /// Consumes `n` stack values; [Object, Arg2, ..., ArgN]
+    /// Pushes 1 value onto the stack; [Return]
+    /// `CALM <string: name> <u64: N>`
+    CALM = 0x5B,
+
+    /// Concatenate arrays, used to build an array containing a spread
+    /// Consumes `n` stack values; [Array1, Array2, ..., ArrayN]
+    /// Pushes 1 value onto the stack; [Array]
+    /// `CNCT <u64: N>`
+    CNCT = 0x5C,
+
+    /// Call a function with arguments taken from an array, used for spread arguments
+    /// Consumes 1 stack value; [Args]
+    /// Pushes 1 value onto the stack; [Return]
+    /// `CALA <u64: name_hash>`
+    CALA = 0x5D,
+
+    /// Call a method with arguments taken from an array, used for spread arguments
+    /// The first argument is the object, as with `CALM`
+    /// Consumes 1 stack value; [Args]
+    /// Pushes 1 value onto the stack; [Return]
+    /// `CLMA <string: name>`
+    CLMA = 0x5E,
+
+    /// Set the angle mode used by the trigonometric opcodes; `radians`, `degrees` or `gradians`
+    /// A nil mode leaves the setting unchanged
+    /// Consumes 1 stack value; [Mode]
+    /// Pushes 1 value onto the stack; [Previous mode]
+    ANGL = 0x5F,
+
+    /// Calculate the gamma function of the top value on the stack
+    GAMMA = 0x60,
+
+    /// Calculate the error function of the top value on the stack
+    ERF = 0x61,
+
+    /// Check if the top value on the stack is a prime number
+    /// Pushes 1 value onto the stack; [Boolean]
+    PRIME = 0x62,
+
+    /// Round a value to a precision, using a rounding mode such as `half_up` or `floor`
+    /// Consumes 3 stack values; [Value, Precision, Mode]
+    RNDM = 0x63,
+
+    /// Write a value to a global name as a constant, which cannot be reassigned or deleted
+    /// Replaces any existing constant of the same name
+    /// Consumes 1 stack value; [Value]
+    /// `WRCN <u64: name_hash>`
+    WRCN = 0x64,
+
+    /// Compile an expression into a function of the named variables, such as `x ** 2 - 4`
+    /// The names can be a single string, or an array of strings
+    /// Consumes 2 stack values; [Expression, Names]
+    /// Pushes 1 value onto the stack; [Function]
+    EXFN = 0x65,
+
+    /// Raise a warning with the top value on the stack, without halting execution
+    /// Leaves the value on the stack
+    /// `WARN`
+    WARN = 0x66,
+
+    /// Push a random decimal between 0 (inclusive) and 1 (exclusive)
+    /// Recorded in, or read from, the VM's replay log
+    /// Pushes 1 value onto the stack; [Decimal]
+    RAND = 0x67,
+
+    /// Push the current time from the VM's clock, in seconds since the unix epoch
+    /// Recorded in, or read from, the VM's replay log
+    /// Pushes 1 value onto the stack; [Decimal]
+    TIME = 0x68,
+
+    ///////////////////////
+    // Superinstructions //
+    ///////////////////////
+    // Emitted by the peephole optimizer in place of a common pair of instructions
+    // Each takes the operands of the first instruction, followed by those of the second
+
+    /// `PUSH` followed by `ADD`
+    /// Consumes 1 stack value; [Left]
+    /// Pushes 1 value onto the stack; [Sum]
+    /// `PADD <TypeCode> <Value>`
+    PADD = 0x69,
+
+    /// `PUSH` followed by `SUB`
+    /// Consumes 1 stack value; [Left]
+    /// Pushes 1 value onto the stack; [Difference]
+    /// `PSUB <TypeCode> <Value>`
+    PSUB = 0x6A,
+
+    /// `REF` followed by `IDEX`; index into a value using a variable
+    /// Consumes 1 stack value; [Base]
+    /// Pushes 1 value onto the stack
+    /// `RIDX <Name Hash>`
+    RIDX = 0x6B,
+
+    /// `DUP` followed by `JMPNN`; jump if the top value on the stack is not nil, leaving it in place
+    /// `DJNN <Jump>`
+    DJNN = 0x6C,
+
+    /// `DUP` followed by `JMPNE`; jump if the top value on the stack is not empty, leaving it in place
+    /// `DJNE <Jump>`
+    DJNE = 0x6D,
+
+    ///////////////
+    // Registers //
+    ///////////////
+    // Used by the experimental register backend; see `CompilerOptions::registers`
+    // Each function call has its own file of registers, numbered from 0 to 255
+
+    /// Load a value into a register
+    /// `RLDC <u8: register> <TypeCode> <Value>`
+    RLDC = 0x6E,
+
+    /// Load the value of a variable into a register
+    /// `RLDV <u8: register> <Name Hash>`
+    RLDV = 0x6F,
+
+    /// Move the top value on the stack into a register
+    /// Consumes 1 stack value
+    /// `RPOP <u8: register>`
+    RPOP = 0x70,
+
+    /// Move the value in a register onto the stack
+    /// Pushes 1 value onto the stack
+    /// `RPSH <u8: register>`
+    RPSH = 0x71,
+
+    /// Apply a binary opcode, such as `ADD`, to two registers, writing the result to a third
+    /// The operand registers are left empty
+    /// `RBOP <u8: opcode> <u8: result> <u8: left> <u8: right>`
+    RBOP = 0x72,
+
+    /// Apply a unary opcode, such as `NEG`, to a register, writing the result to another
+    /// The operand register is left empty
+    /// `RUOP <u8: opcode> <u8: result> <u8: operand>`
+    RUOP = 0x73,
+
+    ///////////////
+    // Constants //
+    ///////////////
+    // Emitted by the optimizer, which moves literals into a pool at the start of each block
+    // of bytecode; see `ConstantPool`
+
+    /// The constant pool of a block of bytecode; only valid as its first instruction
+    /// The pool is decoded when the block is loaded, so running this instruction skips over it
+    /// `POOL <u64 count> <u64 size> <TypeCode> <Value>*count`
+    POOL = 0x74,
+
+    /// Push a value from the constant pool onto the stack
+    /// Pushes 1 value onto the stack
+    /// `PSHC <u32: index>`
+    PSHC = 0x75,
+
+    //////////
+    // Time //
+    //////////
+
+    /// Format a timestamp, in seconds since the unix epoch, as a UTC date such as `%Y-%m-%d`
+    /// Consumes 2 stack values; [Timestamp, Format]
+    /// Pushes 1 value onto the stack; [String]
+    FDATE = 0x76,
+
+    /// Format a duration in milliseconds as a human-readable string, such as `2h 13m 5s`
+    /// Consumes 1 stack value; [Milliseconds]
+    /// Pushes 1 value onto the stack; [String]
+    FDUR = 0x77,
+
+    /// Parse a human-readable duration, such as `2h 13m 5s`, into milliseconds
+    /// Consumes 1 stack value; [String]
+    /// Pushes 1 value onto the stack; [Integer]
+    PDUR = 0x78,
+
+    /////////////
+    // Network //
+    /////////////
+
+    /// Parse an IPv4 or IPv6 address into an integer
+    /// IPv6 addresses use all 128 bits of the integer, so the largest are negative
+    /// Consumes 1 stack value; [String]
+    /// Pushes 1 value onto the stack; [Integer]
+    IPINT = 0x79,
+
+    /// Format an integer as an IPv4 or IPv6 address
+    /// Consumes 2 stack values; [Integer, Version]
+    /// Pushes 1 value onto the stack; [String]
+    INTIP = 0x7A,
+
+    /// Parse a CIDR block, such as `10.0.0.0/8`
+    /// Consumes 1 stack value; [String]
+    /// Pushes 1 value onto the stack; [Object of network, broadcast, netmask, prefix, version, size]
+    CIDR = 0x7B,
+
+    ////////////
+    // Tables //
+    ////////////
+
+    /// Parse CSV text into an array of objects, keyed by the headers on the first line
+    /// Consumes 1 stack value; [String]
+    /// Pushes 1 value onto the stack; [Array]
+    CSVR = 0x7C,
+
+    /// Write rows, each an array or an object, as CSV text
+    /// Object rows are written in the order of the headers; all of their keys if there are none
+    /// Consumes 2 stack values; [Rows, Headers]
+    /// Pushes 1 value onto the stack; [String]
+    CSVW = 0x7D,
+
+    /// Convert the result of a decorator to a string
+    /// Tables and plots are drawn as text; other values are cast to strings
+    /// Consumes 1 stack value; [Value]
+    /// Pushes 1 value onto the stack; [String]
+    DFMT = 0x7E,
+
+    /////////////
+    // Strings //
+    /////////////
+
+    /// Compare the top two values on the stack for equality, ignoring the case of strings
+    /// Consumes 2 stack values
+    /// Pushes 1 value onto the stack
+    /// `IEQ`
+    IEQ = 0x7F,
+
+    /// Sort an array of strings in dictionary order, ignoring accents and case except to break ties
+    /// Values that are not strings are sorted as strings
+    /// Consumes 1 stack value; [Array]
+    /// Pushes 1 value onto the stack; [Array]
+    LSORT = 0x80,
+
+    /////////////
+    // Sorting //
+    /////////////
+
+    /// Sort an array with a function, or in natural order if it is nil; the sort is stable
+    /// A function of two values is a comparator, returning a negative number if the first goes first,
+    /// a positive number if the second does, or 0 to keep their order
+    /// A function of one value gives a key to sort each value by, in natural order
+    /// Consumes 3 stack values; [Array, Function or nil, Descending]
+    /// Pushes 1 value onto the stack; [Array]
+    SRTF = 0x81,
+
+    ////////////////
+    // Aggregates //
+    ////////////////
+
+    /// Find the smallest value in an array or range, in a single pass
+    /// Consumes 1 stack value; [Array or Range]
+    /// Pushes 1 value onto the stack
+    AMIN = 0x82,
+
+    /// Find the largest value in an array or range, in a single pass
+    /// Consumes 1 stack value; [Array or Range]
+    /// Pushes 1 value onto the stack
+    AMAX = 0x83,
+
+    /// Sum the numbers in an array or range; 0 if it is empty
+    /// Consumes 1 stack value; [Array or Range]
+    /// Pushes 1 value onto the stack; [Numeric]
+    ASUM = 0x84,
+
+    /// Multiply the numbers in an array or range; 1 if it is empty
+    /// Consumes 1 stack value; [Array or Range]
+    /// Pushes 1 value onto the stack; [Numeric]
+    APROD = 0x85,
+
+    /// Find the mean of the numbers in an array or range
+    /// Consumes 1 stack value; [Array or Range]
+    /// Pushes 1 value onto the stack; [Decimal]
+    AMEAN = 0x86,
+
+    ////////////////
+    // Membership //
+    ////////////////
+
+    /// Check if a collection sorted in natural order contains a value, using a binary search
+    /// Values that are not arrays are checked as with `CNTN`
+    /// Consumes 2 stack values; [Collection, Value]
+    /// Pushes 1 value onto the stack; [Boolean]
+    SCNTN = 0x87,
+
+    //////////////////////
+    // String operators //
+    //////////////////////
+
+    /// Remove every copy of a substring from a string, as `-` does on strings
+    /// Unlike `SUB`, works even if creative string operators are disabled
+    /// Consumes 2 stack values; [String, Substring]
+    /// Pushes 1 value onto the stack; [String]
+    SRMV = 0x88,
+
+    /// Reverse a string, as unary `-` does on strings
+    /// Unlike `NEG`, works even if creative string operators are disabled
+    /// Consumes 1 stack value; [String]
+    /// Pushes 1 value onto the stack; [String]
+    SREV = 0x89,
+
+    /// Write a value to a name in the innermost scope, as `local` does
+    /// Hides any variable of the same name in outer scopes, or in the global scope
+    /// Consumes 1 stack value; [Value]
+    /// `WRLC <u64: name_hash>`
+    WRLC = 0x8A,
 }
 
 impl OpCode {
     /// Convert a u8 to an OpCode
     pub fn from_u8(value: u8) -> Option<Self> {
-        if value <= OpCode::NOP as u8 {
-            Some(unsafe { std::mem::transmute(value) })
-        } else {
-            None
+        Self::from_repr(value)
+    }
+
+    /// Get the superinstruction that replaces a pair of instructions, if there is one
+    pub fn fuse(first: OpCode, second: OpCode) -> Option<OpCode> {
+        match (first, second) {
+            (OpCode::PUSH, OpCode::ADD) => Some(OpCode::PADD),
+            (OpCode::PUSH, OpCode::SUB) => Some(OpCode::PSUB),
+            (OpCode::REF, OpCode::IDEX) => Some(OpCode::RIDX),
+            (OpCode::DUP, OpCode::JMPNN) => Some(OpCode::DJNN),
+            (OpCode::DUP, OpCode::JMPNE) => Some(OpCode::DJNE),
+            _ => None,
         }
     }
 }
+
+/// Size in bytes of the offset used by jump instructions
+pub const JUMP_SIZE: usize = 4;
+
+/// Get the offset a jump, stored at `at`, uses to land on `target`
+/// Offsets are relative, so they can be followed without decoding, and stay valid if the code is moved
+pub fn jump_offset(at: usize, target: usize) -> i32 {
+    (target as i64 - at as i64) as i32
+}
+
+/// Get the position a jump offset, stored at `at`, lands on
+/// Returns None if it lands before the start of the code
+pub fn jump_target(at: usize, offset: i32) -> Option<usize> {
+    at.checked_add_signed(offset as isize)
+}
@@ -0,0 +1,333 @@
+//! Value operations, as performed by the VM
+//! The VM dispatches arithmetic, bitwise, comparison, logical and matching opcodes through this module,
+//! so it is the one definition of what those opcodes do
+//!
+//! These can be used to apply Lavendeux's coercion and arithmetic rules to values
+//! from a host application, without compiling or running any bytecode
+//!
+//! ```rust
+//! use lavendeux_parser::{value::Value, vm::{ops, OpCode}};
+//!
+//! // Values are coerced the same way as in the language; here the integer becomes a string
+//! let joined = ops::add(Value::integer(2), Value::string("3".to_string())).unwrap();
+//! assert_eq!(joined, Value::string("23".to_string()));
+//!
+//! // Operations can also be looked up by opcode
+//! let product = ops::binary(OpCode::MUL, Value::integer(6), Value::integer(7));
+//! assert_eq!(product.unwrap().unwrap(), Value::integer(42));
+//! ```
+use super::{collation, OpCode};
+use crate::value::{
+    CheckedArithmetic, CheckedBitwise, CheckedBoolean, CheckedMatching, Primitive, Value,
+    ValueError, ValueType,
+};
+
+// Trigonometric functions here always work in radians, whatever the VM's angle mode
+pub use super::virtual_machine::math::{
+    acos, asin, atan, atan2, cos, cosh, erf, gamma, ilog, is_prime, log, root, round, sin, sinh,
+    tan, tanh,
+};
+
+// Aggregates over an array, or a range without collecting it into an array
+pub use super::virtual_machine::aggregate::{max, mean, min, product, sum};
+
+////////////////////
+// Arithmetic ops //
+////////////////////
+
+/// Add two values; `a + b`
+pub fn add(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_add(b)
+}
+
+/// Subtract two values; `a - b`
+pub fn sub(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_sub(b)
+}
+
+/// Multiply two values; `a * b`
+pub fn mul(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_mul(b)
+}
+
+/// Divide two values; `a / b`
+pub fn div(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_div(b)
+}
+
+/// Remainder of dividing two values; `a % b`
+pub fn rem(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_rem(b)
+}
+
+/// Raise a value to a power; `a ** b`
+pub fn pow(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_pow(b)
+}
+
+/// Negate a value; `-a`
+pub fn neg(a: Value) -> Result<Value, ValueError> {
+    a.checked_neg()
+}
+
+/////////////////
+// Bitwise ops //
+/////////////////
+
+/// Bitwise and; `a & b`
+pub fn and(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_and(b)
+}
+
+/// Bitwise or; `a | b`
+pub fn or(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_or(b)
+}
+
+/// Bitwise xor; `a ^ b`
+pub fn xor(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_xor(b)
+}
+
+/// Shift left; `a << b`
+pub fn shl(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_shl(b)
+}
+
+/// Shift right; `a >> b`
+pub fn shr(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_shr(b)
+}
+
+/// Bitwise not; `~a`
+pub fn not(a: Value) -> Result<Value, ValueError> {
+    a.checked_not()
+}
+
+////////////////////
+// Comparison ops //
+////////////////////
+
+/// Equality; `a == b`
+pub fn eq(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_eq(b)
+}
+
+/// Inequality; `a != b`
+pub fn ne(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_ne(b)
+}
+
+/// Strict equality, which also compares types; `a === b`
+pub fn seq(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_seq(b)
+}
+
+/// Strict inequality, which also compares types; `a !== b`
+pub fn sne(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_sne(b)
+}
+
+/// Case-insensitive equality; `a ~= b`
+/// Strings are equal if they only differ in case; other values are compared as with `==`
+pub fn ieq(a: Value, b: Value) -> Result<Value, ValueError> {
+    match (&a, &b) {
+        (Value::Primitive(Primitive::String(x)), Value::Primitive(Primitive::String(y))) => {
+            Ok(Value::boolean(collation::eq_ignore_case(x, y)))
+        }
+        _ => a.checked_eq(b),
+    }
+}
+
+/// Less than; `a < b`
+pub fn lt(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_lt(b)
+}
+
+/// Less than or equal; `a <= b`
+pub fn le(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_le(b)
+}
+
+/// Greater than; `a > b`
+pub fn gt(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_gt(b)
+}
+
+/// Greater than or equal; `a >= b`
+pub fn ge(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_ge(b)
+}
+
+/////////////////
+// Logical ops //
+/////////////////
+
+/// Logical and; `a && b`
+pub fn logical_and(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_logical_and(b)
+}
+
+/// Logical or; `a || b`
+pub fn logical_or(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_logical_or(b)
+}
+
+/// Logical not; `!a`
+pub fn logical_not(a: Value) -> Result<Value, ValueError> {
+    a.checked_logical_not()
+}
+
+//////////////////
+// Matching ops //
+//////////////////
+
+/// Regex match; `a matches b`
+pub fn matches(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_matches(b)
+}
+
+/// Containment; `a contains b`
+pub fn contains(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_contains(b)
+}
+
+/// Prefix match; `a starts_with b`
+pub fn starts_with(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_starts_with(b)
+}
+
+/// Suffix match; `a ends_with b`
+pub fn ends_with(a: Value, b: Value) -> Result<Value, ValueError> {
+    a.checked_ends_with(b)
+}
+
+////////////////
+// String ops //
+////////////////
+
+/// Remove every copy of a substring from a string; the same as `a - b` on strings
+pub fn remove(a: Value, b: Value) -> Result<Value, ValueError> {
+    let (a, b) = (a.cast_string()?, b.cast_string()?);
+    Ok(Value::string(a.replace(&b, "")))
+}
+
+/// Reverse a string; the same as `-a` on strings
+pub fn reverse(a: Value) -> Result<Value, ValueError> {
+    let a = a.cast_string()?;
+    Ok(Value::string(a.chars().rev().collect()))
+}
+
+//////////////
+// Misc ops //
+//////////////
+
+/// Cast a value to a type; `a as <type>`
+pub fn cast(a: Value, ty: ValueType) -> Result<Value, ValueError> {
+    a.cast(ty)
+}
+
+/// Check the type of a value; `a is <type>`
+pub fn is_a(a: &Value, ty: ValueType) -> bool {
+    a.is_a(ty)
+}
+
+/// A unary value operation
+pub(super) type UnaryOp = fn(Value) -> Result<Value, ValueError>;
+
+/// A binary value operation, where the first argument is the left operand
+pub(super) type BinaryOp = fn(Value, Value) -> Result<Value, ValueError>;
+
+/// Apply a unary opcode to a value
+/// Returns `None` if the opcode is not a unary value operation
+pub fn unary(opcode: OpCode, a: Value) -> Option<Result<Value, ValueError>> {
+    unary_handler(opcode).map(|handler| handler(a))
+}
+
+/// Apply a binary opcode to two values, where `a` is the left operand
+/// Returns `None` if the opcode is not a binary value operation
+pub fn binary(opcode: OpCode, a: Value, b: Value) -> Option<Result<Value, ValueError>> {
+    binary_handler(opcode).map(|handler| handler(a, b))
+}
+
+/// Get the operation performed by a unary opcode
+pub(super) fn unary_handler(opcode: OpCode) -> Option<UnaryOp> {
+    let handler: UnaryOp = match opcode {
+        OpCode::NEG => neg,
+        OpCode::NOT => not,
+        OpCode::LNOT => logical_not,
+
+        OpCode::TAN => tan,
+        OpCode::SIN => sin,
+        OpCode::COS => cos,
+        OpCode::ATAN => atan,
+        OpCode::ASIN => asin,
+        OpCode::ACOS => acos,
+        OpCode::TANH => tanh,
+        OpCode::SINH => sinh,
+        OpCode::COSH => cosh,
+        OpCode::GAMMA => gamma,
+        OpCode::ERF => erf,
+        OpCode::PRIME => is_prime,
+
+        OpCode::AMIN => min,
+        OpCode::AMAX => max,
+        OpCode::ASUM => sum,
+        OpCode::APROD => product,
+        OpCode::AMEAN => mean,
+
+        OpCode::SREV => reverse,
+
+        _ => return None,
+    };
+
+    Some(handler)
+}
+
+/// Get the operation performed by a binary opcode
+pub(super) fn binary_handler(opcode: OpCode) -> Option<BinaryOp> {
+    let handler: BinaryOp = match opcode {
+        OpCode::ADD => add,
+        OpCode::SUB => sub,
+        OpCode::MUL => mul,
+        OpCode::DIV => div,
+        OpCode::REM => rem,
+        OpCode::POW => pow,
+
+        OpCode::AND => and,
+        OpCode::OR => or,
+        OpCode::XOR => xor,
+        OpCode::SHL => shl,
+        OpCode::SHR => shr,
+
+        OpCode::EQ => eq,
+        OpCode::NE => ne,
+        OpCode::SEQ => seq,
+        OpCode::SNE => sne,
+        OpCode::IEQ => ieq,
+        OpCode::LT => lt,
+        OpCode::LE => le,
+        OpCode::GT => gt,
+        OpCode::GE => ge,
+
+        OpCode::LAND => logical_and,
+        OpCode::LOR => logical_or,
+
+        OpCode::MTCH => matches,
+        OpCode::CNTN => contains,
+        OpCode::STWT => starts_with,
+        OpCode::EDWT => ends_with,
+
+        OpCode::ATAN2 => atan2,
+        OpCode::ROUND => round,
+        OpCode::LOG => log,
+        OpCode::ILOG => ilog,
+        OpCode::ROOT => root,
+
+        OpCode::SRMV => remove,
+
+        _ => return None,
+    };
+
+    Some(handler)
+}
@@ -0,0 +1,86 @@
+use std::{cell::RefCell, io::Write, rc::Rc};
+
+/// Receives the messages a program writes while it runs
+/// Set with `VirtualMachine::set_stdout`; the default is `StdoutSink`
+///
+/// Closures taking a `&str` are sinks that receive only printed values
+pub trait OutputSink {
+    /// Called for each value printed with `PRNT`, such as by `echo`
+    fn print(&mut self, text: &str);
+
+    /// Called for each message raised with `WARN`, such as by `warn`
+    fn warn(&mut self, text: &str) {
+        eprintln!("warning: {text}");
+    }
+
+    /// Called with the message of each error raised with `THRW`, before the program halts
+    /// The message is also part of the returned error, so nothing is done by default
+    fn throw(&mut self, _text: &str) {}
+}
+
+impl std::fmt::Debug for dyn OutputSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "OutputSink")
+    }
+}
+
+impl<F: FnMut(&str)> OutputSink for F {
+    fn print(&mut self, text: &str) {
+        self(text)
+    }
+}
+
+/// Writes printed values to stdout, and warnings to stderr
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink;
+impl OutputSink for StdoutSink {
+    fn print(&mut self, text: &str) {
+        println!("{text}");
+    }
+}
+
+/// Writes printed values, warnings and thrown errors, one per line, to any writer
+/// Such as a file, or a `Vec<u8>`
+#[derive(Debug, Clone, Default)]
+pub struct WriteSink<W: Write>(pub W);
+impl<W: Write> OutputSink for WriteSink<W> {
+    fn print(&mut self, text: &str) {
+        writeln!(self.0, "{text}").ok();
+    }
+
+    fn warn(&mut self, text: &str) {
+        writeln!(self.0, "warning: {text}").ok();
+    }
+
+    fn throw(&mut self, text: &str) {
+        writeln!(self.0, "error: {text}").ok();
+    }
+}
+
+/// Output written by a program while it runs, kept instead of being printed
+/// Share it with the VM as an `Rc<RefCell<CapturedOutput>>` to read it back afterwards
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapturedOutput {
+    /// Values printed with `PRNT`, such as by `echo`, in order
+    pub printed: Vec<String>,
+
+    /// Messages raised with `WARN`, such as by `warn`, in order
+    pub warnings: Vec<String>,
+
+    /// The message of the error raised with `THRW`, if the program threw one
+    pub thrown: Option<String>,
+}
+
+impl OutputSink for Rc<RefCell<CapturedOutput>> {
+    fn print(&mut self, text: &str) {
+        self.borrow_mut().printed.push(text.to_string());
+    }
+
+    fn warn(&mut self, text: &str) {
+        self.borrow_mut().warnings.push(text.to_string());
+    }
+
+    fn throw(&mut self, text: &str) {
+        self.borrow_mut().thrown = Some(text.to_string());
+    }
+}
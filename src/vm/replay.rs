@@ -0,0 +1,80 @@
+use super::error::RuntimeErrorType;
+use crate::{
+    traits::{ByteDecodeError, ByteReader, SerializeToBytes},
+    value::Value,
+};
+
+/// Magic bytes at the start of a serialized replay log
+const REPLAY_MAGIC: [u8; 4] = *b"LVR\0";
+
+/// The nondeterministic inputs a program received, such as random numbers and the time, in order
+/// Replaying the log runs the same program again with identical results
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayLog {
+    /// The value of each input, in the order the program asked for them
+    pub inputs: Vec<Value>,
+}
+
+impl SerializeToBytes for ReplayLog {
+    fn serialize_into_bytes(self) -> Vec<u8> {
+        let mut bytes = REPLAY_MAGIC.to_vec();
+        bytes.extend(self.inputs.serialize_into_bytes());
+        bytes
+    }
+
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
+        let magic = bytes.take(REPLAY_MAGIC.len()).collect::<Vec<_>>();
+        if magic != REPLAY_MAGIC {
+            return Err(ByteDecodeError::MalformedData(
+                "ReplayLog".to_string(),
+                "Missing replay log header".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            inputs: Vec::<Value>::deserialize_from_bytes(bytes)?,
+        })
+    }
+}
+
+/// What the VM does with nondeterministic inputs
+#[derive(Debug, Clone, Default)]
+pub(super) enum ReplayState {
+    /// Inputs are generated, and not kept
+    #[default]
+    Off,
+
+    /// Inputs are generated, and added to the log
+    Recording(ReplayLog),
+
+    /// Inputs are read from the log, starting at `next`
+    Replaying { log: ReplayLog, next: usize },
+}
+
+impl ReplayState {
+    /// Get the next input; generating it, or reading it from the log when replaying
+    pub fn next_input(
+        &mut self,
+        generate: impl FnOnce() -> Result<Value, RuntimeErrorType>,
+    ) -> Result<Value, RuntimeErrorType> {
+        match self {
+            Self::Off => generate(),
+
+            Self::Recording(log) => {
+                let value = generate()?;
+                log.inputs.push(value.clone());
+                Ok(value)
+            }
+
+            Self::Replaying { log, next } => {
+                let value = log
+                    .inputs
+                    .get(*next)
+                    .cloned()
+                    .ok_or(RuntimeErrorType::ReplayExhausted)?;
+                *next += 1;
+                Ok(value)
+            }
+        }
+    }
+}
@@ -0,0 +1,605 @@
+//! A harness for testing opcodes, by running short sequences of them against a fresh VM
+//! Sequences are written in the assembly format read by `compiler::assembler`
+use std::{cell::RefCell, rc::Rc};
+
+use super::{
+    error::{RuntimeError, RuntimeErrorType},
+    memory_manager::MemoryManager,
+    CapturedOutput, OpCode, TraceEntry, VirtualMachine,
+};
+use crate::{
+    compiler::{assembler::assemble, Compiler, CompilerOptions, DebugProfile, HashString},
+    lexer::{Lexer, Stack},
+    parser::build_ast,
+    value::{Value, ValueError},
+};
+
+/// Everything left behind by running a sequence
+pub struct Outcome {
+    /// The values left on the stack, bottom first, or the error that halted the sequence
+    pub result: Result<Vec<Value>, RuntimeError>,
+
+    /// Each opcode executed, in order, including those in called functions
+    pub executed: Vec<OpCode>,
+
+    /// Values printed, warnings raised and errors thrown by the sequence
+    pub output: CapturedOutput,
+
+    mem: MemoryManager,
+}
+
+impl Outcome {
+    /// The values left on the stack; panics if the sequence failed
+    pub fn stack(&self) -> &[Value] {
+        match &self.result {
+            Ok(stack) => stack,
+            Err(e) => panic!("Sequence failed: {e}"),
+        }
+    }
+
+    /// The error that halted the sequence; panics if it succeeded
+    pub fn error(&self) -> &RuntimeErrorType {
+        match &self.result {
+            Ok(stack) => panic!("Sequence succeeded, leaving {stack:?}"),
+            Err(e) => &e.error,
+        }
+    }
+
+    /// Read a variable, global or constant from memory
+    pub fn read(&self, name: &str) -> Option<Value> {
+        let hash = name.hash_str();
+        let value = self
+            .mem
+            .read(hash)
+            .or_else(|| self.mem.read_constant(hash))?;
+        value.clone().into_value(&self.mem).ok()
+    }
+}
+
+/// Assemble and run a sequence against a fresh memory manager, without the stdlib
+pub fn run(asm: &str) -> Outcome {
+    let bytecode = assemble(asm).expect("Sequence should assemble");
+    execute(bytecode, None, MemoryManager::new())
+}
+
+/// Assemble and run a sequence with the stdlib loaded, so it can call functions like `abs`
+pub fn run_with_stdlib(asm: &str) -> Outcome {
+    let bytecode = assemble(asm).expect("Sequence should assemble");
+    execute(bytecode, None, stdlib())
+}
+
+/// Compile source code without optimizations, and run it with the stdlib loaded
+/// For opcodes that cannot be assembled, such as `MKFN`
+/// `__syscalld` is allowed
+pub fn run_source(source: &str) -> Outcome {
+    let (profile, bytecode) = compile(source);
+    execute(bytecode, Some(profile), stdlib())
+}
+
+/// Compile source code without optimizations, for running on a VM set up by the caller
+/// `__syscalld` is allowed
+pub fn compile(source: &str) -> (DebugProfile, Vec<u8>) {
+    let tokens = Lexer::new(source).all_tokens().expect("Source should lex");
+    let ast = build_ast(Stack::new(tokens), Default::default()).expect("Source should parse");
+
+    let mut compiler = Compiler::new(
+        source,
+        CompilerOptions {
+            allow_syscalld: true,
+            ..Default::default()
+        },
+    );
+    ast.compile(&mut compiler).expect("Source should compile");
+    compiler.decompose()
+}
+
+/// A fresh memory manager, with the stdlib loaded
+fn stdlib() -> MemoryManager {
+    let mut mem = MemoryManager::new();
+    mem.load_stdlib();
+    mem
+}
+
+/// Run bytecode to completion, recording each opcode executed
+fn execute(bytecode: Vec<u8>, profile: Option<DebugProfile>, mem: MemoryManager) -> Outcome {
+    let executed = Rc::new(RefCell::new(vec![]));
+    let output = Rc::new(RefCell::new(CapturedOutput::default()));
+
+    let mut vm = VirtualMachine::with_mem(mem);
+    let log = executed.clone();
+    vm.set_tracer(Some(Box::new(move |entry: &TraceEntry| {
+        log.borrow_mut().push(entry.opcode)
+    })));
+    vm.set_stdout(Box::new(output.clone()));
+    vm.set_file_access(true);
+
+    vm.load(bytecode, profile);
+    let mut result = Ok(());
+    while vm.is_running() {
+        result = vm.next();
+        if result.is_err() {
+            break;
+        }
+    }
+
+    let mut mem = vm.destroy();
+    let result = result.map(|()| {
+        mem.all_stack_blanks()
+            .into_iter()
+            .map(|value| value.into_value(&mem))
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Stack values should resolve")
+    });
+
+    Outcome {
+        result,
+        executed: executed.take(),
+        output: output.take(),
+        mem,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// What a sequence should leave behind
+    enum Expect {
+        /// The stack, bottom first, as each value is displayed
+        Stack(Vec<&'static str>),
+
+        /// An error matching the predicate
+        Error(fn(&RuntimeErrorType) -> bool),
+    }
+
+    struct Case {
+        source: String,
+        runner: fn(&str) -> Outcome,
+        expect: Expect,
+    }
+
+    fn asm(source: &str, expect: Expect) -> Case {
+        Case {
+            source: source.to_string(),
+            runner: run,
+            expect,
+        }
+    }
+
+    fn asm_stdlib(source: &str, expect: Expect) -> Case {
+        Case {
+            source: source.to_string(),
+            runner: run_with_stdlib,
+            expect,
+        }
+    }
+
+    fn lav(source: &str, expect: Expect) -> Case {
+        Case {
+            source: source.to_string(),
+            runner: run_source,
+            expect,
+        }
+    }
+
+    /// Apply an opcode to the given operands
+    fn op(opcode: &str, operands: &[&str], expect: &'static str) -> Case {
+        let pushes = operands.iter().map(|v| format!("PUSH {v}\n"));
+        let source = pushes.collect::<String>() + opcode;
+        asm(&source, Expect::Stack(vec![expect]))
+    }
+
+    fn cases() -> Vec<Case> {
+        use Expect::*;
+
+        let file = std::env::temp_dir().join("lavendeux_test_harness_readf.txt");
+        std::fs::write(&file, "one\ntwo\nthree").unwrap();
+        let file = file.to_string_lossy().to_string();
+
+        vec![
+            // Stack manipulation
+            asm("PUSH 1\nPUSH 2\nPOP", Stack(vec!["1"])),
+            asm("PUSH 1\nDUP", Stack(vec!["1", "1"])),
+            asm("PUSH 1\nPUSH 2\nSWP", Stack(vec!["2", "1"])),
+            // Control flow
+            asm("PUSH 1\nJMP end\nPUSH 2\nend:", Stack(vec!["1"])),
+            asm("PUSH true\nJMPT end\nPUSH 2\nend:", Stack(vec![])),
+            asm("PUSH true\nJMPF end\nPUSH 2\nend:", Stack(vec!["2"])),
+            asm("PUSH ``\nJMPE end\nPUSH 2\nend:", Stack(vec![])),
+            asm("PUSH `a`\nJMPNE end\nPUSH 2\nend:", Stack(vec![])),
+            asm("PUSH ``\nJMPNE end\nPUSH 2\nend:", Stack(vec!["2"])),
+            asm("PUSH nil\nJMPNN end\nPUSH 2\nend:", Stack(vec!["2"])),
+            asm("PUSH 3\ntop:\nPUSH 1\nSUB\nDUP\nJMPT top", Stack(vec!["0"])),
+            asm(
+                "PUSH 1\nJTBL 0 other [zero, one]\n\
+                zero:\nPUSH `zero`\nJMP end\n\
+                one:\nPUSH `one`\nJMP end\n\
+                other:\nPUSH `other`\nend:",
+                Stack(vec!["one"]),
+            ),
+            asm(
+                "PUSH 5\nJTBL 0 other [zero]\nzero:\nPUSH `zero`\nJMP end\nother:\nPUSH `other`\nend:",
+                Stack(vec!["other"]),
+            ),
+            // Constants
+            asm("PSHC `a`\nPSHC 5\nPSHC `a`", Stack(vec!["a", "5", "a"])),
+            asm("PSHC 1\nJMP end\nPSHC 2\nend:", Stack(vec!["1"])),
+            asm("POOL 0\nPUSH 1", Stack(vec!["1"])),
+            // Memory manipulation
+            asm("PUSH 5\nREF x\nWREF\nPOP\nREF x\nRREF", Stack(vec!["5"])),
+            asm("PUSH 5\nREF x\nWREF\nPOP\nREF x\nVREF\nRREF", Stack(vec!["5"])),
+            asm("REF x\nVREF", Error(|e| matches!(e, RuntimeErrorType::HashNotFound(..)))),
+            asm(
+                "PUSH 5\nREF x\nWREF\nPOP\nREF x\nDREF\nREF x\nVREF",
+                Error(|e| matches!(e, RuntimeErrorType::HashNotFound(..))),
+            ),
+            asm("PUSH 5\nWRGL g\nREF g\nRREF", Stack(vec!["5"])),
+            asm("PUSH 5\nWRCN c\nREF c\nRREF", Stack(vec!["5"])),
+            // Scope manipulation
+            asm(
+                "SCI\nPUSH 5\nREF x\nWREF\nPOP\nSCO\nREF x\nVREF",
+                Error(|e| matches!(e, RuntimeErrorType::HashNotFound(..))),
+            ),
+            asm(
+                "PUSH 5\nREF x\nWREF\nPOP\nSCI\nSCL\nREF x\nVREF",
+                Error(|e| matches!(e, RuntimeErrorType::HashNotFound(..))),
+            ),
+            asm(
+                "PUSH 5\nREF x\nWREF\nPOP\nSCI\nPUSH 6\nWRLC x\nSCO\nREF x\nVREF",
+                Stack(vec!["5"]),
+            ),
+            asm("PUSH 5\nWRGL x\nPUSH 6\nWRLC x\nREF x\nVREF", Stack(vec!["6"])),
+            // Value manipulation
+            asm("PUSH 1\nTYPE", Stack(vec!["int"])),
+            asm("PUSH true\nCAST int", Stack(vec!["1"])),
+            asm("PUSH 1\nISA int", Stack(vec!["true"])),
+            asm("PUSH 1\nPUSH `x`\nMKOB 1\nISST Point{x: int}", Stack(vec!["true"])),
+            asm("PUSH 1\nPUSH `x`\nMKOB 1\nCSST Point{x: int}", Stack(vec!["{`x`: 1}"])),
+            asm(
+                "PUSH 1\nCSST Point{x: int}",
+                Error(|e| matches!(e, RuntimeErrorType::StructMismatch(_, _))),
+            ),
+            asm("PUSH `abc`\nNEXT", Stack(vec!["bc", "a"])),
+            asm("MKAR 0\nNEXT", Error(|e| matches!(e, RuntimeErrorType::IteratorEmpty))),
+            asm("PUSH 1\nPUSH true\nLCST", Stack(vec!["1", "1"])),
+            // Collections
+            asm("PUSH 1\nPUSH 2\nMKAR 2", Stack(vec!["[2, 1]"])),
+            asm("PUSH 1\nMKAR 1\nPUSH 2\nMKAR 1\nCNCT 2", Stack(vec!["[2, 1]"])),
+            asm("PUSH 1\nPUSH `a`\nMKOB 1", Stack(vec!["{`a`: 1}"])),
+            asm("PUSH 3\nPUSH 1\nMKRG", Stack(vec!["1..3"])),
+            asm("PUSH `c`\nPUSH `a`\nMKRG", Stack(vec!["[`a`, `b`, `c`]"])),
+            asm("MKAR 0\nPUSH 1\nPSAR", Stack(vec!["[1]"])),
+            asm("MKOB 0\nPUSH `a`\nPUSH 1\nPSOB", Stack(vec!["{`a`: 1}"])),
+            asm("PUSH 1\nPUSH 2\nMKAR 2\nPUSH 0\nIDEX", Stack(vec!["2"])),
+            asm("PUSH nil\nPUSH 0\nNIDX", Stack(vec!["nil"])),
+            asm("MKOB 0\nPUSH `a`\nNIDX", Stack(vec!["nil"])),
+            asm(
+                "PUSH 5\nPUSH `a`\nNIDX",
+                Error(|e| matches!(e, RuntimeErrorType::IndexingBaseType)),
+            ),
+            // Arithmetic
+            op("ADD", &["2", "3"], "5"),
+            op("SUB", &["2", "3"], "-1"),
+            op("MUL", &["2", "3"], "6"),
+            op("DIV", &["6", "3"], "2"),
+            op("REM", &["7", "3"], "1"),
+            op("POW", &["2", "3"], "8"),
+            op("NEG", &["2"], "-2"),
+            asm(
+                "PUSH 1\nPUSH 0\nDIV",
+                Error(|e| matches!(e, RuntimeErrorType::Value(ValueError::DivideByZero))),
+            ),
+            asm(
+                "PUSH 1.5\nPUSH 0.0\nREM",
+                Error(|e| matches!(e, RuntimeErrorType::Value(ValueError::DivideByZero))),
+            ),
+            // Bitwise
+            op("AND", &["6", "3"], "2"),
+            op("OR", &["6", "3"], "7"),
+            op("XOR", &["6", "3"], "5"),
+            op("NOT", &["0"], "-1"),
+            op("SHL", &["1", "3"], "8"),
+            op("SHR", &["8", "3"], "1"),
+            // Comparison
+            op("EQ", &["1", "1"], "true"),
+            op("NE", &["1", "1"], "false"),
+            op("SEQ", &["1", "`1`"], "false"),
+            op("SNE", &["1", "`1`"], "true"),
+            op("LT", &["1", "2"], "true"),
+            op("LE", &["2", "2"], "true"),
+            op("GT", &["1", "2"], "false"),
+            op("GE", &["1", "2"], "false"),
+            // Logical
+            op("LAND", &["true", "false"], "false"),
+            op("LOR", &["true", "false"], "true"),
+            op("LNOT", &["true"], "false"),
+            // Matching
+            op("MTCH", &["`abc`", "`a.c`"], "true"),
+            op("CNTN", &["`abc`", "`b`"], "true"),
+            op("SRMV", &["`banana`", "`an`"], "ba"),
+            op("SREV", &["`abc`"], "cba"),
+            asm(
+                "PUSH 2\nPUSH 1\nMKAR 2\nREF a\nWREF\nPOP\nREF a\nPUSH 2\nCNTN",
+                Stack(vec!["true"]),
+            ),
+            asm("PUSH 3\nPUSH 1\nMKRG\nPUSH 2.5\nCNTN", Stack(vec!["false"])),
+            op("STWT", &["`abc`", "`a`"], "true"),
+            op("EDWT", &["`abc`", "`a`"], "false"),
+            // Functions
+            lav("f(x = 1 + 2) = x * 2\nf()", Stack(vec!["6"])),
+            lav("[3, 1, 2].len()", Stack(vec!["3"])),
+            lav("f(a, b) = a - b\nf(...[3, 1])", Stack(vec!["2"])),
+            lav("f(a, b) = a - b\n3.f(...[1])", Stack(vec!["2"])),
+            lav(
+                "f = __syscalld(EXFN, 'x * 2', 'x')\nf(4)",
+                Stack(vec!["__expression(x)", "8"]),
+            ),
+            asm_stdlib("PUSH -2\nCALL abs 1", Stack(vec!["2"])),
+            asm("PUSH 1\nCALL nope 1", Error(|e| matches!(e, RuntimeErrorType::HashNotFound(..)))),
+            // Output
+            asm("PRNTM\nTYPE", Stack(vec!["string"])),
+            asm("PUSH 1\nPRNT", Stack(vec!["1"])),
+            asm("PUSH 1\nWARN", Stack(vec!["1"])),
+            asm(
+                "PUSH `oops`\nTHRW",
+                Error(|e| matches!(e, RuntimeErrorType::Custom(msg) if msg == "oops")),
+            ),
+            // Misc
+            asm("PUSH 2\nPUSH 1\nPUSH 3\nMKAR 3\nSORT", Stack(vec!["[1, 2, 3]"])),
+            asm(&format!("PUSH `{file}`\nPUSH 0\nREADF"), Stack(vec!["one\ntwo\nthree"])),
+            asm(&format!("PUSH `{file}`\nPUSH 2\nREADF"), Stack(vec!["two\nthree"])),
+            asm(
+                "PUSH `no/such/file`\nPUSH 0\nREADF",
+                Error(|e| matches!(e, RuntimeErrorType::ReadFile(_, _))),
+            ),
+            asm_stdlib("LSTFN\nLEN\nPUSH 0\nGT", Stack(vec!["true"])),
+            asm("PUSH `abc`\nLEN", Stack(vec!["3"])),
+            asm("PUSH `a,b`\nPUSH `,`\nSSPLT", Stack(vec!["[`a`, `b`]"])),
+            asm("NOP", Stack(vec![])),
+            // Math
+            op("TAN", &["0"], "0"),
+            op("SIN", &["0"], "0"),
+            op("COS", &["0"], "1"),
+            op("ATAN2", &["0", "1"], "0"),
+            op("ATAN", &["0"], "0"),
+            op("ASIN", &["0"], "0"),
+            op("ACOS", &["1"], "0"),
+            op("TANH", &["0"], "0"),
+            op("SINH", &["0"], "0"),
+            op("COSH", &["0"], "1"),
+            op("ROUND", &["1.26", "1"], "1.3"),
+            op("LOG", &["100", "10"], "2"),
+            op("ILOG", &["100", "10"], "2"),
+            op("ROOT", &["27", "3"], "3"),
+            asm("PUSH 1\nGAMMA\nPUSH 1\nSUB\nPUSH 0.000001\nLT", Stack(vec!["true"])),
+            op("ERF", &["0"], "0"),
+            op("PRIME", &["7"], "true"),
+            op("RNDM", &["1.25", "1", "`floor`"], "1.2"),
+            asm(
+                "PUSH 1\nPUSH 1\nPUSH `sideways`\nRNDM",
+                Error(|e| matches!(e, RuntimeErrorType::InvalidRoundingMode(_))),
+            ),
+            asm("PUSH `degrees`\nANGL\nPUSH 90\nSIN", Stack(vec!["radians", "1"])),
+            asm(
+                "PUSH `sideways`\nANGL",
+                Error(|e| matches!(e, RuntimeErrorType::InvalidAngleMode(_))),
+            ),
+            // Nondeterministic inputs
+            asm("RAND\nTYPE", Stack(vec!["float"])),
+            asm("TIME\nPUSH 0\nGT", Stack(vec!["true"])),
+            // Time
+            asm(
+                "PUSH 951782400\nPUSH `%a %d %b %Y, %H:%M:%S`\nFDATE",
+                Stack(vec!["Tue 29 Feb 2000, 00:00:00"]),
+            ),
+            asm("PUSH -1\nPUSH `%Y-%m-%d %H:%M`\nFDATE", Stack(vec!["1969-12-31 23:59"])),
+            asm(
+                "PUSH 0\nPUSH `%Q`\nFDATE",
+                Error(|e| matches!(e, RuntimeErrorType::InvalidDateFormat(_))),
+            ),
+            asm("PUSH 7985250\nFDUR", Stack(vec!["2h 13m 5s 250ms"])),
+            asm("PUSH 0\nFDUR", Stack(vec!["0s"])),
+            asm("PUSH -90061001\nFDUR", Stack(vec!["-1d 1h 1m 1s 1ms"])),
+            asm("PUSH `2h 13m 5s`\nPDUR", Stack(vec!["7985000"])),
+            asm("PUSH `1.5d 250ms`\nPDUR", Stack(vec!["129600250"])),
+            asm("PUSH `-1m30s`\nPDUR", Stack(vec!["-90000"])),
+            asm(
+                "PUSH `5 minutes`\nPDUR",
+                Error(|e| matches!(e, RuntimeErrorType::InvalidDuration(_))),
+            ),
+            // Network
+            asm("PUSH `10.0.0.1`\nIPINT", Stack(vec!["167772161"])),
+            asm("PUSH `ffff::`\nIPINT\nPUSH 0\nLT", Stack(vec!["true"])),
+            asm(
+                "PUSH `10.0.0.256`\nIPINT",
+                Error(|e| matches!(e, RuntimeErrorType::InvalidIpAddress(_))),
+            ),
+            asm("PUSH 167772161\nPUSH 4\nINTIP", Stack(vec!["10.0.0.1"])),
+            asm(
+                "PUSH -1\nPUSH 6\nINTIP",
+                Stack(vec!["ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"]),
+            ),
+            asm(
+                "PUSH -1\nPUSH 4\nINTIP",
+                Error(|e| matches!(e, RuntimeErrorType::InvalidIpAddress(_))),
+            ),
+            asm(
+                "PUSH `192.168.1.77/26`\nCIDR\nDUP\nPUSH `network`\nIDEX\nSWP\nPUSH `size`\nIDEX",
+                Stack(vec!["192.168.1.64", "64"]),
+            ),
+            asm(
+                "PUSH `10.0.0.0/33`\nCIDR",
+                Error(|e| matches!(e, RuntimeErrorType::InvalidCidr(_))),
+            ),
+            // Tables
+            asm("PUSH `a,b`\nCSVR\nLEN", Stack(vec!["0"])),
+            asm(
+                "PUSH `a,\"b`\nCSVR",
+                Error(|e| matches!(e, RuntimeErrorType::InvalidCsv(_))),
+            ),
+            asm(
+                "PUSH `x, \"y\"`\nPUSH 1\nMKAR 2\nMKAR 1\nPUSH `n`\nMKAR 1\nCSVW",
+                Stack(vec!["n\n1,\"x, \"\"y\"\"\""]),
+            ),
+            asm(
+                "PUSH 1\nMKAR 1\nMKAR 0\nCSVW",
+                Error(|e| matches!(e, RuntimeErrorType::InvalidTable)),
+            ),
+            asm("PUSH 1.5\nDFMT", Stack(vec!["1.5"])),
+            // Strings
+            asm("PUSH `Hello`\nPUSH `hELLO`\nIEQ", Stack(vec!["true"])),
+            asm("PUSH 1\nPUSH 1.0\nIEQ", Stack(vec!["true"])),
+            asm(
+                "PUSH `f`\nPUSH `E`\nPUSH `é`\nPUSH `e`\nMKAR 4\nLSORT",
+                Stack(vec!["[`e`, `E`, `é`, `f`]"]),
+            ),
+            // Sorting
+            asm(
+                "PUSH 2\nPUSH 3\nPUSH 1\nMKAR 3\nPUSH nil\nPUSH true\nSRTF",
+                Stack(vec!["[3, 2, 1]"]),
+            ),
+            // Aggregates
+            asm("PUSH 3\nPUSH 1\nMKRG\nAMIN", Stack(vec!["1"])),
+            asm("PUSH 3\nPUSH 1\nMKRG\nAMAX", Stack(vec!["2"])),
+            asm("PUSH 2\nPUSH 1.5\nMKAR 2\nASUM", Stack(vec!["3.5"])),
+            asm("PUSH 2\nPUSH 3\nMKAR 2\nAPROD", Stack(vec!["6"])),
+            asm("PUSH 2\nPUSH 1\nMKAR 2\nAMEAN", Stack(vec!["1.5"])),
+            asm("MKAR 0\nASUM", Stack(vec!["0"])),
+            asm(
+                "MKAR 0\nAMAX",
+                Error(|e| {
+                    matches!(
+                        e,
+                        RuntimeErrorType::Value(ValueError::EmptyCollection(_))
+                    )
+                }),
+            ),
+            // Membership
+            asm("PUSH 5\nPUSH 3\nPUSH 1\nMKAR 3\nPUSH 3\nSCNTN", Stack(vec!["true"])),
+            asm("PUSH 5\nPUSH 3\nPUSH 1\nMKAR 3\nPUSH 4\nSCNTN", Stack(vec!["false"])),
+            // Superinstructions
+            asm("PUSH 2\nPADD 3", Stack(vec!["5"])),
+            asm("PUSH 2\nPSUB 3", Stack(vec!["-1"])),
+            asm(
+                "PUSH 1\nREF i\nWREF\nPOP\nPUSH 1\nPUSH 2\nMKAR 2\nRIDX i",
+                Stack(vec!["1"]),
+            ),
+            asm("PUSH 1\nDJNN end\nPUSH 2\nend:", Stack(vec!["1"])),
+            asm("PUSH nil\nDJNN end\nPUSH 2\nend:", Stack(vec!["nil", "2"])),
+            asm("PUSH `a`\nDJNE end\nPUSH 2\nend:", Stack(vec!["a"])),
+            asm("PUSH ``\nDJNE end\nPUSH 2\nend:", Stack(vec!["", "2"])),
+            // Registers
+            asm("RLDC r0 2\nRLDC r1 3\nRBOP SUB r0 r0 r1\nRPSH r0", Stack(vec!["-1"])),
+            asm("RLDC r3 2\nRUOP NEG r0 r3\nRPSH r0\nRPSH r3", Stack(vec!["-2", "nil"])),
+            asm("PUSH 5\nREF i\nWREF\nPOP\nRLDV r0 i\nRPSH r0", Stack(vec!["5"])),
+            asm("PUSH 1\nPUSH 2\nRPOP r0\nRPSH r0", Stack(vec!["1", "2"])),
+            asm(
+                "RLDC r0 1\nRBOP NOP r0 r0 r0",
+                Error(|e| matches!(e, RuntimeErrorType::InvalidOpcode(_))),
+            ),
+            asm(
+                "RLDV r0 missing\nRUOP NEG r0 r0",
+                Error(|e| matches!(e, RuntimeErrorType::HashNotFound(..))),
+            ),
+            // Memory budget
+            lav(
+                "(0..1000000000) as array",
+                Error(|e| matches!(e, RuntimeErrorType::MemoryBudgetExceeded { .. })),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_opcodes() {
+        let mut executed = HashSet::new();
+        let mut failures = vec![];
+        for case in cases() {
+            let outcome = (case.runner)(&case.source);
+            let failure = match (&outcome.result, case.expect) {
+                (Ok(stack), Expect::Stack(expected)) => {
+                    let stack = stack.iter().map(ToString::to_string).collect::<Vec<_>>();
+                    (stack != expected).then(|| format!("Expected {expected:?}, found {stack:?}"))
+                }
+                (Err(e), Expect::Error(predicate)) => {
+                    (!predicate(&e.error)).then(|| format!("Unexpected error {e}"))
+                }
+                (Ok(stack), Expect::Error(_)) => Some(format!("Expected an error, found {stack:?}")),
+                (Err(e), Expect::Stack(_)) => Some(format!("Unexpected error {e}")),
+            };
+            if let Some(failure) = failure {
+                failures.push(format!("{failure}\nin:\n{}", case.source));
+            }
+            executed.extend(outcome.executed.iter().map(|opcode| *opcode as u8));
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+
+        let missing = (0..=u8::MAX)
+            .filter_map(OpCode::from_repr)
+            .filter(|opcode| !executed.contains(&(*opcode as u8)))
+            .collect::<Vec<_>>();
+        assert!(missing.is_empty(), "No test executes {missing:?}");
+    }
+
+    #[test]
+    fn test_outcome() {
+        let outcome = run("PUSH 1\nPUSH 2\nADD");
+        assert_eq!(outcome.stack(), &[Value::integer(3)]);
+        assert_eq!(outcome.executed, vec![OpCode::PUSH, OpCode::PUSH, OpCode::ADD]);
+
+        let outcome = run("PUSH `oops`\nTHRW");
+        assert!(matches!(outcome.error(), RuntimeErrorType::Custom(msg) if msg == "oops"));
+    }
+
+    #[test]
+    fn test_outcome_memory() {
+        let outcome = run("PUSH 5\nREF x\nWREF\nPOP\nPUSH 6\nWRGL g\nPUSH 7\nWRCN c");
+        assert_eq!(outcome.read("x"), Some(Value::integer(5)));
+        assert_eq!(outcome.read("g"), Some(Value::integer(6)));
+        assert_eq!(outcome.read("c"), Some(Value::integer(7)));
+        assert_eq!(outcome.read("y"), None);
+
+        let outcome = run("PUSH `hi`\nPRNT\nWARN\nTHRW");
+        assert_eq!(outcome.output.printed, vec!["hi"]);
+        assert_eq!(outcome.output.warnings, vec!["hi"]);
+        assert_eq!(outcome.output.thrown.as_deref(), Some("hi"));
+    }
+
+    #[test]
+    fn test_memory_budget() {
+        let (profile, bytecode) = compile("(0..1000) as array");
+        let mut vm = VirtualMachine::new();
+        vm.set_memory_budget(1000);
+
+        let error = vm.run(bytecode, Some(profile)).unwrap_err();
+        assert!(
+            matches!(
+                error.error,
+                RuntimeErrorType::MemoryBudgetExceeded { requested, budget: 1000 } if requested > 1000
+            ),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_memory_budget_reset() {
+        let (profile, bytecode) = compile("(0..1000) as array");
+        let mut vm = VirtualMachine::new();
+        vm.set_memory_budget(1000);
+
+        // The budget is kept when the VM is reset
+        vm.reset();
+        assert_eq!(vm.memory_budget(), 1000);
+        assert!(vm.run(bytecode.clone(), Some(profile.clone())).is_err());
+
+        // Failed runs hold nothing against the budget, so a smaller collection still fits
+        let (small_profile, small_bytecode) = compile("(0..10) as array");
+        let value = vm.run(small_bytecode, Some(small_profile)).unwrap();
+        assert_eq!(value.to_string(), "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]");
+
+        // A new VM starts with the default budget
+        let mut vm = VirtualMachine::new();
+        assert!(vm.memory_budget() > 1000);
+        assert!(vm.run(bytecode, Some(profile)).is_ok());
+    }
+}
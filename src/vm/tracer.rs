@@ -0,0 +1,82 @@
+use super::OpCode;
+use crate::lexer::Token;
+use std::io::Write;
+
+/// One executed instruction, as recorded by an execution trace
+#[derive(Debug, Clone)]
+pub struct TraceEntry {
+    /// The position of the instruction in its bytecode
+    pub pc: usize,
+
+    /// The instruction that was executed
+    pub opcode: OpCode,
+
+    /// How many function calls deep the instruction is; 0 for the top level
+    pub call_depth: usize,
+
+    /// The number of values on the VM's stack, before the instruction ran
+    pub stack_depth: usize,
+
+    /// The signature of the function the instruction is in, or None for the top level
+    pub function: Option<String>,
+
+    /// The source code the instruction was compiled from, if debug information is available
+    pub token: Option<Token<'static>>,
+}
+
+impl std::fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{:04X} {:<6} stack={}",
+            "  ".repeat(self.call_depth),
+            self.pc,
+            format!("{:?}", self.opcode),
+            self.stack_depth
+        )?;
+
+        if let Some(token) = &self.token {
+            let filename = token.filename().unwrap_or("input");
+            let (line, col) = token.source_map().line_col(token.span().start);
+            let slice = token.slice().lines().next().unwrap_or_default().trim();
+            write!(f, " @ {filename}:{line}:{col} `{slice}`")?;
+        }
+
+        if let Some(function) = &self.function {
+            write!(f, " in {function}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Receives each instruction the VM executes, for debugging scripts
+/// Set with `VirtualMachine::set_tracer`
+///
+/// Closures taking a `&TraceEntry` are tracers
+pub trait Tracer {
+    /// Called before each instruction is executed
+    fn trace(&mut self, entry: &TraceEntry);
+}
+
+impl std::fmt::Debug for dyn Tracer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Tracer")
+    }
+}
+
+impl<F: FnMut(&TraceEntry)> Tracer for F {
+    fn trace(&mut self, entry: &TraceEntry) {
+        self(entry)
+    }
+}
+
+/// Writes each instruction, one per line, to any writer
+/// Such as a file, or stderr
+#[derive(Debug, Clone, Default)]
+pub struct WriteTracer<W: Write>(pub W);
+impl<W: Write> Tracer for WriteTracer<W> {
+    fn trace(&mut self, entry: &TraceEntry) {
+        writeln!(self.0, "{entry}").ok();
+    }
+}
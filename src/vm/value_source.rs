@@ -1,6 +1,9 @@
 //! Value sources are used to represent the source of a value
 //! This can be a literal value, or a reference to a value in memory
-use crate::value::{IndexingExt, Value, ValueError, ValueIndexResult, ValueType};
+use crate::{
+    traits::{ByteDecodeError, ByteReader, SerializeToBytes},
+    value::{IndexingExt, Value, ValueError, ValueIndexResult, ValueType},
+};
 
 use super::{
     error::RuntimeErrorType,
@@ -40,7 +43,7 @@ impl ValueSource {
     pub fn value<'mem>(
         &'mem self,
         mem: &'mem MemoryManager,
-    ) -> Result<ValueIndexResult<'_>, RuntimeErrorType> {
+    ) -> Result<ValueIndexResult<'mem>, RuntimeErrorType> {
         match self {
             Self::Literal(value) => Ok(ValueIndexResult::Immutable(&value)),
             Self::Reference(reference) => reference.value(mem),
@@ -51,7 +54,7 @@ impl ValueSource {
     pub fn value_mut<'mem>(
         &'mem mut self,
         mem: &'mem mut MemoryManager,
-    ) -> Result<ValueIndexResult<'_>, RuntimeErrorType> {
+    ) -> Result<ValueIndexResult<'mem>, RuntimeErrorType> {
         match self {
             Self::Literal(value) => Ok(ValueIndexResult::Mutable(value)),
             Self::Reference(reference) => reference.value_mut(mem),
@@ -115,11 +118,11 @@ impl ValueReference {
     pub fn value<'mem>(
         &'mem self,
         mem: &'mem MemoryManager,
-    ) -> Result<ValueIndexResult<'_>, RuntimeErrorType> {
+    ) -> Result<ValueIndexResult<'mem>, RuntimeErrorType> {
         match self {
             Self::Unresolved(name_hash) => match mem.read(*name_hash) {
                 Some(value) => value.value(mem),
-                None => Err(RuntimeErrorType::HashNotFound),
+                None => Err(RuntimeErrorType::HashNotFound(*name_hash, None)),
             },
 
             Self::Resolved(slotref, idxpath) => {
@@ -145,8 +148,8 @@ impl ValueReference {
                         }
                     }
 
-                    value = next
-                        .ref_index(next.clone())
+                    value = value
+                        .into_ref_index(next.clone())
                         .map_err(RuntimeErrorType::Value)?;
                 }
 
@@ -156,13 +159,13 @@ impl ValueReference {
     }
 
     pub fn value_mut<'mem>(
-        &'mem self,
+        &self,
         mem: &'mem mut MemoryManager,
-    ) -> Result<ValueIndexResult<'_>, RuntimeErrorType> {
+    ) -> Result<ValueIndexResult<'mem>, RuntimeErrorType> {
         match self {
             Self::Unresolved(name_hash) => match mem.get_ref(*name_hash) {
                 Some(slotref) => Self::resolve(&slotref, mem),
-                None => Err(RuntimeErrorType::HashNotFound),
+                None => Err(RuntimeErrorType::HashNotFound(*name_hash, None)),
             },
 
             Self::Resolved(slotref, idxpath) => {
@@ -186,7 +189,7 @@ impl ValueReference {
                     }
 
                     value = value
-                        .ref_index(next.clone())
+                        .into_ref_index(next.clone())
                         .map_err(RuntimeErrorType::Value)?;
                 }
 
@@ -201,7 +204,7 @@ impl ValueReference {
         match self {
             Self::Unresolved(name_hash) => match mem.read(name_hash) {
                 Some(value) => Ok(value.clone().into_value(mem)?),
-                None => Err(RuntimeErrorType::HashNotFound),
+                None => Err(RuntimeErrorType::HashNotFound(name_hash, None)),
             },
 
             Self::Resolved(slotref, idxpath) => {
@@ -224,7 +227,7 @@ impl ValueReference {
         match self {
             Self::Unresolved(name_hash) => match mem.delete(name_hash) {
                 Some(value) => Ok(value.into_value(mem)?),
-                None => Err(RuntimeErrorType::HashNotFound),
+                None => Err(RuntimeErrorType::HashNotFound(name_hash, None)),
             },
 
             Self::Resolved(slotref, mut idxpath) => match idxpath.pop() {
@@ -249,7 +252,7 @@ impl ValueReference {
                         }
 
                         base = base
-                            .ref_index(next.clone())
+                            .into_ref_index(next.clone())
                             .map_err(RuntimeErrorType::Value)?;
                     }
                     base.delete_index(index).map_err(RuntimeErrorType::Value)
@@ -279,7 +282,7 @@ impl ValueReference {
                     let mut base = Self::resolve(&slotref, mem)?;
                     for idx in &idxpath[..idxpath.len() - 1] {
                         base = base
-                            .mut_index(idx.clone())
+                            .into_mut_index(idx.clone())
                             .map_err(RuntimeErrorType::Value)?;
                     }
                     base.set_index(last_index.clone(), value)
@@ -303,7 +306,7 @@ impl ValueReference {
         match self {
             Self::Unresolved(name_hash) => match mem.get_ref(name_hash) {
                 Some(slot) => Ok(Self::Resolved(slot, vec![])),
-                None => Err(RuntimeErrorType::HashNotFound),
+                None => Err(RuntimeErrorType::HashNotFound(name_hash, None)),
             },
             _ => Ok(self),
         }
@@ -330,3 +333,61 @@ impl ValueReference {
         }
     }
 }
+
+impl SerializeToBytes for ValueSource {
+    fn serialize_into_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        match self {
+            Self::Literal(value) => {
+                bytes.push(0);
+                bytes.extend(value.serialize_into_bytes());
+            }
+
+            Self::Reference(ValueReference::Unresolved(name_hash)) => {
+                bytes.push(1);
+                bytes.extend(name_hash.serialize_into_bytes());
+            }
+
+            Self::Reference(ValueReference::Resolved(slotref, idxpath)) => {
+                bytes.push(2);
+                bytes.extend(slotref.serialize_into_bytes());
+                bytes.extend(idxpath.serialize_into_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    fn deserialize_from_bytes(bytes: &mut ByteReader<'_>) -> Result<Self, ByteDecodeError> {
+        match u8::deserialize_from_bytes(bytes)? {
+            0 => Ok(Self::Literal(Value::deserialize_from_bytes(bytes)?)),
+            1 => Ok(Self::unresolved(u64::deserialize_from_bytes(bytes)?)),
+            2 => {
+                let slotref = SlotRef::deserialize_from_bytes(bytes)?;
+                let idxpath = Vec::<Value>::deserialize_from_bytes(bytes)?;
+                Ok(Self::resolved(slotref, idxpath))
+            }
+
+            _ => Err(ByteDecodeError::MalformedData(
+                "ValueSource".to_string(),
+                "Invalid value source type".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{value::Value, Lavendeux};
+
+    #[test]
+    fn test_indexed_reference() {
+        let mut lav = Lavendeux::new();
+        lav.run("w = [-4, {'b': [5, -6]}]").unwrap();
+        for check in ["abs(w[0]) == 4", "abs(w[1].b[1]) == 6"] {
+            assert_eq!(lav.run(check).unwrap(), Value::boolean(true), "{check}");
+        }
+        assert!(lav.run("abs(w[0][0])").is_err());
+    }
+}
@@ -1,19 +1,35 @@
 use super::{
+    angle_mode::AngleMode,
+    clock::{self, Clock, SystemClock},
+    collation,
+    constant_pool::{read_constant_pool, ConstantPool},
+    currency::CurrencyConverter,
     error::{RuntimeError, RuntimeErrorType},
     execution_context::ExecutionContext,
     memory_manager::MemoryManager,
-    opcodes::OpCode,
+    opcodes::{OpCode, OPCODE_SET_VERSION},
+    ops,
+    output_sink::{OutputSink, StdoutSink},
+    replay::{ReplayLog, ReplayState},
+    tracer::{TraceEntry, Tracer},
+    value_source::ValueSource,
 };
 use crate::{
-    compiler::DebugProfile,
+    compiler::{DebugProfile, HashString},
+    run_output::{PlotData, TableData},
+    traits::{ByteDecodeError, ByteReader, IntoOwned, SafeVecAlloc, SerializeToBytes},
     value::{
-        CheckedArithmetic, CheckedBitwise, CheckedBoolean, CheckedMatching, Primitive, Value,
-        ValueType,
+        self, CollisionPolicy, DivisionByZero, IntegerOverflow, LibraryError, Number, NumberSymbol,
+        Primitive, StdFunctionSet, StructType, Value, ValueError, ValueType,
     },
 };
+use std::{collections::HashMap, sync::Arc};
 
 // syscall helpers
-mod math;
+pub(super) mod aggregate;
+mod csv;
+pub(super) mod math;
+mod network;
 
 mod alu;
 mod collections;
@@ -22,6 +38,7 @@ mod functions;
 mod index;
 mod io;
 mod references;
+mod registers;
 mod stack;
 
 use alu::ALUExt;
@@ -31,27 +48,393 @@ use functions::FunctionExt;
 use index::IndexExt;
 use io::IOExt;
 use references::RefExt;
+use registers::RegisterExt;
 use stack::StackExt;
 
+/// Magic bytes at the start of a VM snapshot
+/// The last byte is the version of the snapshot format, bumped when the layout of a context changes
+const SNAPSHOT_MAGIC: [u8; 4] = *b"LVS\x01";
+
+/// The default limit on nested function calls
+const DEFAULT_MAX_CALL_DEPTH: usize = 10_000;
+
+/// The number of distinct functions named in a call depth error
+const MAX_CHAIN_LEN: usize = 8;
+
+/// The number of finished contexts kept for reuse by later calls
+const CONTEXT_POOL_SIZE: usize = 64;
+
+/// The default limit on the size of a single collection, in bytes
+const DEFAULT_MEMORY_BUDGET: usize = 512 * 1024 * 1024;
+
+/// Restores the previous value settings of the thread when a run ends; see `apply_value_settings`
+struct ValueSettings {
+    strict_booleans: bool,
+    division_by_zero: DivisionByZero,
+    integer_overflow: IntegerOverflow,
+}
+
+impl Drop for ValueSettings {
+    fn drop(&mut self) {
+        value::set_strict_booleans(self.strict_booleans);
+        value::set_division_by_zero(self.division_by_zero);
+        value::set_integer_overflow(self.integer_overflow);
+    }
+}
+
 /// The execution context for the Lavendeux VM.
 /// This is the actual VM that runs the bytecode.
 /// In practice you should access this through the `Lavendeux` struct.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct VirtualMachine {
     mem: MemoryManager,
     last_opcode: OpCode,
+    angle_mode: AngleMode,
+    stdout: Box<dyn OutputSink>,
+    replay: ReplayState,
+    tracer: Option<Box<dyn Tracer>>,
+    currency_converter: Option<Box<dyn CurrencyConverter>>,
+    creative_string_ops: bool,
+    strict_booleans: bool,
+    file_access: bool,
+    division_by_zero: DivisionByZero,
+    integer_overflow: IntegerOverflow,
+    clock: Box<dyn Clock>,
+    max_call_depth: usize,
+    memory_budget: usize,
 
     context: Vec<ExecutionContext>,
+    context_pool: Vec<ExecutionContext>,
 }
 
 impl VirtualMachine {
     /// Creates a new execution context with the given bytecode and debug profile.
     /// Uses the specified memory manager.
+    #[doc(hidden)]
     pub fn with_mem(mem: MemoryManager) -> Self {
         Self {
             mem,
             last_opcode: OpCode::NOP,
+            angle_mode: AngleMode::default(),
+            stdout: Box::new(StdoutSink),
+            replay: ReplayState::Off,
+            tracer: None,
+            currency_converter: None,
+            creative_string_ops: true,
+            strict_booleans: false,
+            file_access: false,
+            division_by_zero: DivisionByZero::default(),
+            integer_overflow: IntegerOverflow::default(),
+            clock: Box::new(SystemClock),
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            memory_budget: DEFAULT_MEMORY_BUDGET,
             context: vec![],
+            context_pool: Vec::with_capacity(CONTEXT_POOL_SIZE),
+        }
+    }
+
+    /// Get the unit used for angles by the trigonometric functions
+    pub fn angle_mode(&self) -> AngleMode {
+        self.angle_mode
+    }
+
+    /// Set the unit used for angles by the trigonometric functions
+    /// This setting is kept when the VM is reset
+    pub fn set_angle_mode(&mut self, mode: AngleMode) {
+        self.angle_mode = mode;
+    }
+
+    /// Get the maximum number of nested function calls
+    pub fn max_call_depth(&self) -> usize {
+        self.max_call_depth
+    }
+
+    /// Set the maximum number of nested function calls; the default is 10,000
+    /// Calls beyond the limit fail with `RuntimeErrorType::CallDepthExceeded`, instead of exhausting memory
+    /// This setting is kept when the VM is reset
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.max_call_depth = depth;
+    }
+
+    /// Get the largest collection a program may build, in bytes
+    pub fn memory_budget(&self) -> usize {
+        self.memory_budget
+    }
+
+    /// Set the largest collection a program may build, in bytes; the default is 512MiB
+    /// This covers arrays, objects, and ranges expanded into either, such as by `0..1000000000 as array`
+    /// Larger collections fail with `RuntimeErrorType::MemoryBudgetExceeded`
+    /// This setting is kept when the VM is reset
+    pub fn set_memory_budget(&mut self, bytes: usize) {
+        self.memory_budget = bytes;
+    }
+
+    /// Allocate a collection with room for `len` items, if it fits in the memory budget
+    #[allow(clippy::result_large_err)]
+    fn alloc_collection<T: SafeVecAlloc>(&self, len: usize) -> Result<T, RuntimeError> {
+        self.check_budget::<T>(len)?;
+        T::safe_alloc(len).map_err(|e| self.emit_err(RuntimeErrorType::MemoryAllocation(e)))
+    }
+
+    /// Fail if a collection of `len` items would not fit in the memory budget
+    #[allow(clippy::result_large_err)]
+    fn check_budget<T: SafeVecAlloc>(&self, len: usize) -> Result<(), RuntimeError> {
+        let requested = len.saturating_mul(T::item_size());
+        if requested > self.memory_budget {
+            return Err(self.emit_err(RuntimeErrorType::MemoryBudgetExceeded {
+                requested,
+                budget: self.memory_budget,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Fail if converting a value to the given type would expand a range past the memory budget
+    #[allow(clippy::result_large_err)]
+    fn check_range_cast(&self, value: &Value, target: ValueType) -> Result<(), RuntimeError> {
+        if let Value::Range(range) = value {
+            let len = range.end.saturating_sub(range.start).max(0);
+            let len = usize::try_from(len).unwrap_or(usize::MAX);
+            match target {
+                ValueType::Array => self.check_budget::<Vec<Value>>(len)?,
+                ValueType::Object => self.check_budget::<HashMap<Primitive, Value>>(len)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Describe the functions being called, innermost first, ending with the one about to be called
+    /// Repeated calls to the same function, as in recursion, are counted instead of listed
+    fn call_chain(&self, calling: &str) -> String {
+        let frames = std::iter::once(calling).chain(
+            self.context
+                .iter()
+                .rev()
+                .map(|context| context.signature().unwrap_or("<script>")),
+        );
+
+        let mut chain: Vec<(&str, usize)> = vec![];
+        for frame in frames {
+            match chain.last_mut() {
+                Some((name, count)) if *name == frame => *count += 1,
+                _ => chain.push((frame, 1)),
+            }
+        }
+
+        let mut names = chain
+            .iter()
+            .take(MAX_CHAIN_LEN)
+            .map(|(name, count)| match count {
+                1 => name.to_string(),
+                _ => format!("{name} (x{count})"),
+            })
+            .collect::<Vec<_>>();
+        if chain.len() > MAX_CHAIN_LEN {
+            names.push("...".to_string());
+        }
+        names.join(" <- ")
+    }
+
+    /// Set where printed values, warnings and thrown errors are sent
+    /// Returns the previous sink, so it can be restored later
+    /// This setting is kept when the VM is reset
+    pub fn set_stdout(&mut self, sink: Box<dyn OutputSink>) -> Box<dyn OutputSink> {
+        std::mem::replace(&mut self.stdout, sink)
+    }
+
+    /// Set a tracer to receive each instruction before it is executed, or None to stop tracing
+    /// Instructions compiled with debug information include the source code they came from
+    /// Returns the previous tracer
+    pub fn set_tracer(&mut self, tracer: Option<Box<dyn Tracer>>) -> Option<Box<dyn Tracer>> {
+        std::mem::replace(&mut self.tracer, tracer)
+    }
+
+    /// Set a converter for arithmetic that mixes currencies, or None to make that an error
+    /// Returns the previous converter
+    /// This setting is kept when the VM is reset
+    pub fn set_currency_converter(
+        &mut self,
+        converter: Option<Box<dyn CurrencyConverter>>,
+    ) -> Option<Box<dyn CurrencyConverter>> {
+        std::mem::replace(&mut self.currency_converter, converter)
+    }
+
+    /// Check if `-` can be used on strings; see `set_creative_string_ops`
+    pub fn creative_string_ops(&self) -> bool {
+        self.creative_string_ops
+    }
+
+    /// Set whether `-` can be used on strings, where `a - b` removes every copy of `b` from `a`,
+    /// and `-a` reverses `a`; enabled by default
+    /// When disabled, these are errors, and scripts use `remove` and `reverse` instead
+    /// This setting is kept when the VM is reset
+    pub fn set_creative_string_ops(&mut self, enabled: bool) {
+        self.creative_string_ops = enabled;
+    }
+
+    /// Check if arithmetic on booleans is an error; see `set_strict_booleans`
+    pub fn strict_booleans(&self) -> bool {
+        self.strict_booleans
+    }
+
+    /// Set whether arithmetic on booleans, such as `true + true`, is an error; disabled by default
+    /// When disabled, `+` and `-` act as XOR on booleans, and `*`, `/`, `%` and `**` act as AND
+    /// This setting is kept when the VM is reset
+    pub fn set_strict_booleans(&mut self, strict: bool) {
+        self.strict_booleans = strict;
+    }
+
+    /// Check if scripts can read files from the host; see `set_file_access`
+    pub fn file_access(&self) -> bool {
+        self.file_access
+    }
+
+    /// Set whether scripts can read files from the host, with `read_file`; disabled by default
+    /// When disabled, reading a file is an error
+    /// This setting is kept when the VM is reset
+    pub fn set_file_access(&mut self, enabled: bool) {
+        self.file_access = enabled;
+    }
+
+    /// Get what dividing by zero results in; see `set_division_by_zero`
+    pub fn division_by_zero(&self) -> DivisionByZero {
+        self.division_by_zero
+    }
+
+    /// Set what dividing by zero, with `/` or `%`, results in; an error by default
+    /// This setting is kept when the VM is reset
+    pub fn set_division_by_zero(&mut self, policy: DivisionByZero) {
+        self.division_by_zero = policy;
+    }
+
+    /// Get what integer overflow results in; see `set_integer_overflow`
+    pub fn integer_overflow(&self) -> IntegerOverflow {
+        self.integer_overflow
+    }
+
+    /// Set what integer arithmetic that overflows results in; an error by default
+    /// This setting is kept when the VM is reset
+    pub fn set_integer_overflow(&mut self, policy: IntegerOverflow) {
+        self.integer_overflow = policy;
+    }
+
+    /// Send a warning to the output sink, as `warn` does in scripts
+    pub fn warn(&mut self, text: &str) {
+        self.stdout.warn(text);
+    }
+
+    /// Set the clock used for the current time, such as a `FixedClock` for reproducible runs
+    /// Returns the previous clock
+    /// This setting is kept when the VM is reset
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) -> Box<dyn Clock> {
+        std::mem::replace(&mut self.clock, clock)
+    }
+
+    /// Convert a value into the currency with the given symbol, using the currency converter
+    /// Returns None if the value is not an amount, or the converter has no rate for it
+    fn convert_currency(&mut self, value: &Value, to: &NumberSymbol) -> Option<Value> {
+        match value {
+            Value::Primitive(Primitive::Decimal(amount)) => self
+                .currency_converter
+                .as_mut()?
+                .convert(amount, to)
+                .map(|n| Value::decimal(n.with_symbol(Some(to.clone())))),
+            _ => None,
+        }
+    }
+
+    /// Build the trace entry for the instruction at the current position
+    fn trace_entry(&self, opcode: OpCode) -> TraceEntry {
+        let context = self.context();
+        TraceEntry {
+            pc: context.pc(),
+            opcode,
+            call_depth: self.context.len() - 1,
+            stack_depth: self.mem.stack_len(),
+            function: context.signature().map(str::to_string),
+            token: context
+                .debug_profile()
+                .and_then(|profile| profile.current_token(context.pc()))
+                .map(IntoOwned::into_owned),
+        }
+    }
+
+    /// Start recording nondeterministic inputs, such as random numbers and the time
+    /// Replaces any log being recorded or replayed; collect the log with `take_replay_log`
+    pub fn record_replay(&mut self) {
+        self.replay = ReplayState::Recording(ReplayLog::default());
+    }
+
+    /// Start replaying a recorded log, so programs receive the same inputs as when it was recorded
+    /// Programs that ask for more inputs than were recorded fail with `ReplayExhausted`
+    pub fn replay(&mut self, log: ReplayLog) {
+        self.replay = ReplayState::Replaying { log, next: 0 };
+    }
+
+    /// Stop recording or replaying, and return the log
+    /// Returns None if neither was started
+    pub fn take_replay_log(&mut self) -> Option<ReplayLog> {
+        match std::mem::take(&mut self.replay) {
+            ReplayState::Off => None,
+            ReplayState::Recording(log) | ReplayState::Replaying { log, .. } => Some(log),
+        }
+    }
+
+    /// Push a nondeterministic input; generated, or read from the replay log
+    fn push_input(&mut self, generate: impl FnOnce() -> f64) -> Result<(), RuntimeError> {
+        let value = self
+            .replay
+            .next_input(|| {
+                let value = Number::from_f64(generate()).map_err(RuntimeErrorType::Value)?;
+                Ok(Value::decimal(value))
+            })
+            .map_err(|e| self.emit_err(e))?;
+        self.push_value(value);
+        Ok(())
+    }
+
+    /// Define a constant, available to every program run by this VM
+    /// Replaces any existing constant with the same name
+    pub fn define_constant(&mut self, name: &str, value: Value) {
+        self.mem
+            .write_constant(name.hash_str(), ValueSource::Literal(value));
+    }
+
+    /// Remove a constant by name, returning its value
+    pub fn remove_constant(&mut self, name: &str) -> Option<Value> {
+        match self.mem.delete_constant(name.hash_str())? {
+            ValueSource::Literal(value) => Some(value),
+            ValueSource::Reference(_) => None,
+        }
+    }
+
+    /// Add a set of compiled functions, such as a module loaded with `StdFunctionSet::map_file`
+    /// The functions are available to every program run by this VM, and replace any global of the same name
+    pub fn load_functions(&mut self, functions: StdFunctionSet) {
+        functions.into_mem(&mut self.mem);
+    }
+
+    /// Add a set of compiled functions, resolving collisions with existing globals according to the policy
+    pub fn load_functions_with(
+        &mut self,
+        functions: StdFunctionSet,
+        policy: CollisionPolicy,
+    ) -> Result<(), LibraryError> {
+        functions.into_mem_with(&mut self.mem, policy)
+    }
+
+    /// Get the names of the variables kept between runs, such as those assigned by earlier runs
+    /// Only names recorded from a debug profile are known
+    pub fn variable_names(&self) -> impl Iterator<Item = &str> {
+        self.mem.variable_names()
+    }
+
+    /// Get the value of a constant by name
+    pub fn constant(&self, name: &str) -> Option<Value> {
+        match self.mem.read_constant(name.hash_str())? {
+            ValueSource::Literal(value) => Some(value.clone()),
+            ValueSource::Reference(_) => None,
         }
     }
 
@@ -64,7 +447,9 @@ impl VirtualMachine {
     /// Clears the stack and resets the VM to its initial state.
     pub fn reset(&mut self) {
         self.last_opcode = OpCode::NOP;
-        self.context.clear();
+        while !self.context.is_empty() {
+            self.pop_context();
+        }
         self.mem.reset();
     }
 
@@ -79,19 +464,45 @@ impl VirtualMachine {
     }
 
     /// Add a new function context to the stack.
+    /// Finished contexts are recycled from the pool, so calls do not allocate a new one each time
     fn push_context(
         &mut self,
-        code: Vec<u8>,
+        code: Arc<[u8]>,
+        constants: ConstantPool,
         debug_profile: Option<DebugProfile>,
         ret_type: ValueType,
+        signature: Option<&str>,
     ) {
-        self.context
-            .push(ExecutionContext::new(code, debug_profile, ret_type));
+        let context = match self.context_pool.pop() {
+            Some(mut context) => {
+                context.recycle(code, constants, debug_profile, ret_type, signature);
+                context
+            }
+            None => ExecutionContext::new(
+                code,
+                constants,
+                debug_profile,
+                ret_type,
+                signature.map(str::to_string),
+            ),
+        };
+        self.context.push(context);
     }
 
-    /// Remove the top function context from the stack.
+    /// Remove the top function context from the stack, returning it to the pool.
     fn pop_context(&mut self) {
-        self.context.pop();
+        if let Some(mut context) = self.context.pop() {
+            if self.context_pool.len() < CONTEXT_POOL_SIZE {
+                context.release();
+                self.context_pool.push(context);
+            }
+        }
+    }
+
+    /// The number of finished contexts waiting to be reused
+    #[doc(hidden)]
+    pub fn pooled_contexts(&self) -> usize {
+        self.context_pool.len()
     }
 
     /// Emit an error at the current position
@@ -113,9 +524,63 @@ impl VirtualMachine {
         bytecode: Vec<u8>,
         profile: Option<DebugProfile>,
     ) -> Result<Value, RuntimeError> {
+        self.load(bytecode, profile);
+        let _settings = self.apply_value_settings();
+        self.finish()
+    }
+
+    /// Reset the VM and prepare a program to run, without running it
+    /// Use `resume` to run it, in as many steps as needed
+    pub fn load(&mut self, bytecode: Vec<u8>, profile: Option<DebugProfile>) {
         self.reset();
-        self.push_context(bytecode, profile, ValueType::All);
-        while self.context().pc() < self.context().code().len() {
+        if let Some(profile) = &profile {
+            self.mem.record_names(profile);
+        }
+
+        let code: Arc<[u8]> = bytecode.into();
+        let constants = read_constant_pool(&code).unwrap_or_default();
+        self.push_context(code, constants, profile, ValueType::All, None);
+    }
+
+    /// Returns true if a program is loaded and has instructions left to run
+    pub fn is_running(&self) -> bool {
+        self.context
+            .last()
+            .is_some_and(|context| context.pc() < context.code().len())
+    }
+
+    /// Run at most `max_instructions` instructions of the loaded program
+    /// Returns the result if the program finished, or None if it was paused
+    /// A paused program can be snapshotted, or resumed again
+    pub fn resume(&mut self, max_instructions: usize) -> Result<Option<Value>, RuntimeError> {
+        let _settings = self.apply_value_settings();
+        for _ in 0..max_instructions {
+            if !self.is_running() {
+                break;
+            }
+            self.next()?;
+        }
+
+        if self.is_running() {
+            Ok(None)
+        } else {
+            self.finish().map(Some)
+        }
+    }
+
+    /// Apply the settings used by value operations on this thread, such as `strict_booleans`
+    /// The previous settings are restored when the returned guard is dropped
+    fn apply_value_settings(&self) -> ValueSettings {
+        ValueSettings {
+            strict_booleans: value::set_strict_booleans(self.strict_booleans),
+            division_by_zero: value::set_division_by_zero(self.division_by_zero),
+            integer_overflow: value::set_integer_overflow(self.integer_overflow),
+        }
+    }
+
+    /// Run the loaded program until it finishes, and collect the result
+    fn finish(&mut self) -> Result<Value, RuntimeError> {
+        while self.is_running() {
             self.next()?;
         }
 
@@ -133,19 +598,93 @@ impl VirtualMachine {
     }
 
     /// Consumes the execution context and returns the memory manager.
+    #[doc(hidden)]
     pub fn destroy(self) -> MemoryManager {
         self.mem
     }
 
+    /// Serialize the state of the VM into bytes, including any paused program
+    /// The state can be restored later, or in another process, with `restore`
+    /// The output sink is not included
+    ///
+    /// Example:
+    /// ```rust
+    /// # use lavendeux_parser::{value::Value, vm::VirtualMachine, Error, Lavendeux};
+    /// # fn main() -> Result<(), Error> {
+    /// let (profile, bytecode) = Lavendeux::new().compile("for i in 0..100 do i * 2")?;
+    /// let mut vm = VirtualMachine::new();
+    /// vm.load(bytecode, Some(profile));
+    /// assert_eq!(vm.resume(10)?, None);
+    ///
+    /// let mut other = VirtualMachine::new();
+    /// other.restore(&vm.snapshot()).expect("Invalid snapshot");
+    /// let result = other.resume(usize::MAX)?.expect("Program did not finish");
+    /// assert_eq!(result.cast_array().unwrap().len(), 100);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn snapshot(&self) -> Vec<u8> {
+        let mut bytes = SNAPSHOT_MAGIC.to_vec();
+        bytes.extend(OPCODE_SET_VERSION.serialize_into_bytes());
+        bytes.extend(self.angle_mode.to_string().serialize_into_bytes());
+        bytes.push(self.last_opcode as u8);
+        bytes.extend(self.context.clone().serialize_into_bytes());
+        bytes.extend(self.mem.clone().serialize_into_bytes());
+        bytes
+    }
+
+    /// Replace the state of the VM with one serialized by `snapshot`
+    /// A program that was paused when the snapshot was taken can be continued with `resume`
+    /// The VM is left unchanged if the snapshot is invalid
+    pub fn restore(&mut self, snapshot: &[u8]) -> Result<(), ByteDecodeError> {
+        let bytes = &mut ByteReader::new(snapshot);
+        let malformed = |msg: String| ByteDecodeError::MalformedData("Snapshot".to_string(), msg);
+
+        let magic = bytes.read_slice(SNAPSHOT_MAGIC.len(), "Snapshot").ok();
+        if magic != Some(&SNAPSHOT_MAGIC[..]) {
+            return Err(malformed("Missing snapshot header".to_string()));
+        }
+
+        let version = u16::deserialize_from_bytes(bytes)?;
+        if version != OPCODE_SET_VERSION {
+            return Err(malformed(format!(
+                "Snapshot uses opcode set v{version}, but this version expects v{OPCODE_SET_VERSION}"
+            )));
+        }
+
+        let angle_mode = String::deserialize_from_bytes(bytes)?;
+        let angle_mode = AngleMode::from_name(&angle_mode)
+            .ok_or_else(|| malformed(format!("Invalid angle mode `{angle_mode}`")))?;
+
+        let last_opcode = u8::deserialize_from_bytes(bytes)?;
+        let last_opcode = OpCode::from_u8(last_opcode)
+            .ok_or_else(|| malformed(format!("Invalid opcode 0x{last_opcode:02X}")))?;
+
+        let context = Vec::<ExecutionContext>::deserialize_from_bytes(bytes)?;
+        let mem = MemoryManager::deserialize_from_bytes(bytes)?;
+
+        self.angle_mode = angle_mode;
+        self.last_opcode = last_opcode;
+        self.context = context;
+        self.mem = mem;
+        Ok(())
+    }
+
     /// Run the next instruction in the current context.
     pub fn next(&mut self) -> Result<(), RuntimeError> {
+        if self.tracer.is_some() {
+            let opcode = self.context().code().get(self.context().pc()).copied();
+            if let Some(opcode) = opcode.and_then(OpCode::from_u8) {
+                let entry = self.trace_entry(opcode);
+                if let Some(tracer) = &mut self.tracer {
+                    tracer.trace(&entry);
+                }
+            }
+        }
+
         let opcode = self.read_opcode()?;
 
-        #[cfg(feature = "debug_compiler_internal_vm")]
-        {
-            print!("{:?}@{:02X} ", opcode, self.context().pc());
-            println!("{}", self.mem);
-        }
+        trace_event!(target: "lavendeux::vm", pc = self.context().pc(), ?opcode);
 
         self.last_opcode = opcode;
         match opcode {
@@ -165,6 +704,8 @@ impl VirtualMachine {
             OpCode::JMPF => self.op_jump_if_false()?,
             OpCode::JMPE => self.op_jump_if_empty()?,
             OpCode::JMPNE => self.op_jump_if_not_empty()?,
+            OpCode::JMPNN => self.op_jump_if_not_nil()?,
+            OpCode::JTBL => self.op_jump_table()?,
 
             /////////////////////////
             // Memory manipulation //
@@ -193,11 +734,34 @@ impl VirtualMachine {
             OpCode::CAST => {
                 let type_name = self.read_type()?;
                 let value = self.pop_value()?;
+                self.check_range_cast(&value, type_name)?;
                 let value = value
                     .cast(type_name)
                     .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
                 self.push_value(value);
             }
+            OpCode::ISA => {
+                let type_name = self.read_type()?;
+                let value = self.pop_value()?;
+                self.push_value(Value::boolean(value.is_a(type_name)));
+            }
+            OpCode::ISST => {
+                let struct_type = self.decode_next::<StructType>()?;
+                let value = self.pop_value()?;
+                self.push_value(Value::boolean(struct_type.matches(&value)));
+            }
+            OpCode::CSST => {
+                let struct_type = self.decode_next::<StructType>()?;
+                let value = self.pop_value()?;
+                let mismatches = struct_type.mismatches(&value);
+                if !mismatches.is_empty() {
+                    return Err(self.emit_err(RuntimeErrorType::StructMismatch(
+                        struct_type.name,
+                        mismatches.join("\n= "),
+                    )));
+                }
+                self.push_value(value);
+            }
             OpCode::NEXT => {
                 let value = self.pop_value()?;
                 let (first, rest) = match value {
@@ -250,6 +814,7 @@ impl VirtualMachine {
                 let left = self.pop_value()?;
 
                 let lefttype = left.type_of();
+                self.check_range_cast(&right, lefttype)?;
                 let right = right
                     .cast(lefttype)
                     .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
@@ -259,6 +824,7 @@ impl VirtualMachine {
             }
 
             OpCode::MKAR => self.op_make_array()?,
+            OpCode::CNCT => self.op_concat_arrays()?,
             OpCode::MKOB => self.op_make_object()?,
             OpCode::MKRG => self.op_make_range()?,
 
@@ -266,64 +832,38 @@ impl VirtualMachine {
             OpCode::PSOB => self.op_push_object()?,
 
             OpCode::IDEX => self.index_into()?,
-
-            ////////////////////
-            // Arithmetic ops //
-            ////////////////////
-            OpCode::ADD => self.op_binary(Value::checked_add)?,
-            OpCode::SUB => self.op_binary(Value::checked_sub)?,
-            OpCode::MUL => self.op_binary(Value::checked_mul)?,
-            OpCode::DIV => self.op_binary(Value::checked_div)?,
-            OpCode::REM => self.op_binary(Value::checked_rem)?,
-            OpCode::POW => self.op_binary(Value::checked_pow)?,
-            OpCode::NEG => self.op_unary(Value::checked_neg)?,
-
-            /////////////////
-            // Bitwise ops //
-            /////////////////
-            OpCode::AND => self.op_binary(Value::checked_and)?,
-            OpCode::OR => self.op_binary(Value::checked_or)?,
-            OpCode::XOR => self.op_binary(Value::checked_xor)?,
-            OpCode::SHL => self.op_binary(Value::checked_shl)?,
-            OpCode::SHR => self.op_binary(Value::checked_shr)?,
-            OpCode::NOT => self.op_unary(Value::checked_not)?,
-
-            ////////////////////
-            // Comparison ops //
-            ////////////////////
-            OpCode::EQ => self.op_binary(Value::checked_eq)?,
-            OpCode::NE => self.op_binary(Value::checked_ne)?,
-            OpCode::SEQ => self.op_binary(Value::checked_seq)?,
-            OpCode::SNE => self.op_binary(Value::checked_sne)?,
-            OpCode::LT => self.op_binary(Value::checked_lt)?,
-            OpCode::LE => self.op_binary(Value::checked_le)?,
-            OpCode::GT => self.op_binary(Value::checked_gt)?,
-            OpCode::GE => self.op_binary(Value::checked_ge)?,
-
-            /////////////////
-            // Logical ops //
-            /////////////////
-            OpCode::LAND => self.op_binary(Value::checked_logical_and)?,
-            OpCode::LOR => self.op_binary(Value::checked_logical_or)?,
-            OpCode::LNOT => self.op_unary(Value::checked_logical_not)?,
-
-            //////////////////
-            // Matching ops //
-            //////////////////
-            OpCode::MTCH => self.op_binary(Value::checked_matches)?,
-            OpCode::CNTN => self.op_binary(Value::checked_contains)?,
-            OpCode::STWT => self.op_binary(Value::checked_starts_with)?,
-            OpCode::EDWT => self.op_binary(Value::checked_ends_with)?,
+            OpCode::NIDX => self.index_into_nil_safe()?,
 
             //////////////////
             // Function ops //
             //////////////////
             OpCode::WRFN => self.alloc_fn()?,
+            OpCode::WRGL => {
+                let name_hash = self.read_u64()?;
+                let value = self.pop_value()?;
+                self.mem
+                    .write_global(name_hash, ValueSource::Literal(value), false);
+            }
+            OpCode::WRCN => {
+                let name_hash = self.read_u64()?;
+                let value = self.pop_value()?;
+                self.mem
+                    .write_constant(name_hash, ValueSource::Literal(value));
+            }
+            OpCode::WRLC => {
+                let name_hash = self.read_u64()?;
+                let value = self.pop_value()?;
+                self.mem.write_local(name_hash, ValueSource::Literal(value));
+            }
             OpCode::MKFN => self.make_fn()?,
             OpCode::FDFT => self.push_default_fn_arg()?,
             OpCode::FSIG => self.push_fn_signature()?,
+            OpCode::EXFN => self.make_expression_fn()?,
 
             OpCode::CALL => self.call_fn()?,
+            OpCode::CALM => self.call_method()?,
+            OpCode::CALA => self.call_fn_spread()?,
+            OpCode::CLMA => self.call_method_spread()?,
             OpCode::RET => self.ret_fn()?,
 
             ///////////////
@@ -337,12 +877,19 @@ impl VirtualMachine {
 
             OpCode::PRNT => {
                 let value = self.pop_value()?;
-                println!("{value:?}");
+                self.stdout.print(&value.to_string());
+                self.push_value(value);
+            }
+
+            OpCode::WARN => {
+                let value = self.pop_value()?;
+                self.stdout.warn(&value.to_string());
                 self.push_value(value);
             }
 
             OpCode::THRW => {
                 let msg = self.pop_value()?.to_string();
+                self.stdout.throw(&msg);
                 return Err(self.emit_err(RuntimeErrorType::Custom(msg)));
             }
 
@@ -355,14 +902,55 @@ impl VirtualMachine {
                 self.push_value(value);
             }
 
-            OpCode::READF => todo!(),
+            OpCode::SRTF => self.op_sort_with()?,
+
+            OpCode::CNTN => self.op_contains(false)?,
+            OpCode::SCNTN => self.op_contains(true)?,
+
+            OpCode::LSORT => {
+                let values = self
+                    .pop_value()?
+                    .cast_array()
+                    .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+                let mut values = values
+                    .into_iter()
+                    .map(|value| (value.to_string(), value))
+                    .collect::<Vec<_>>();
+                values.sort_by(|(a, _), (b, _)| collation::compare(a, b));
+                self.push_value(Value::Array(values.into_iter().map(|(_, v)| v).collect()));
+            }
+
+            OpCode::READF => {
+                let lines = self.pop_value()?;
+                let lines = lines
+                    .cast_integer()
+                    .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+                let lines = usize::try_from(lines).unwrap_or(0);
+                let path = self.pop_value()?.to_string();
+                if !self.file_access {
+                    return Err(self.emit_err(RuntimeErrorType::FileAccessDisabled(path)));
+                }
+
+                let contents = std::fs::read_to_string(&path).map_err(|e| {
+                    self.emit_err(RuntimeErrorType::ReadFile(path.clone(), e.to_string()))
+                })?;
+
+                let contents = match lines {
+                    0 => contents,
+                    _ => {
+                        let all = contents.lines().collect::<Vec<_>>();
+                        all[all.len().saturating_sub(lines)..].join("\n")
+                    }
+                };
+                self.push_value(Value::string(contents));
+            }
 
             OpCode::LSTFN => {
                 let functions = self.mem.all_functions();
                 let values = functions
                     .into_iter()
                     .cloned()
-                    .map(Value::Function)
+                    .map(Value::function)
                     .collect();
                 self.push_value(Value::Array(values));
             }
@@ -397,27 +985,263 @@ impl VirtualMachine {
             ////////////////
             // Trigonomic //
             ////////////////
-            OpCode::ATAN2 => self.op_binary(math::atan2)?,
-            OpCode::TAN => self.op_unary(math::tan)?,
-            OpCode::SIN => self.op_unary(math::sin)?,
-            OpCode::COS => self.op_unary(math::cos)?,
+            OpCode::ATAN2 => {
+                let mode = self.angle_mode;
+                self.op_binary(|y, x| math::angle_from_radians(math::atan2(y, x)?, mode))?
+            }
+            OpCode::TAN => {
+                let mode = self.angle_mode;
+                self.op_unary(|v| math::tan(math::angle_to_radians(v, mode)?))?
+            }
+            OpCode::SIN => {
+                let mode = self.angle_mode;
+                self.op_unary(|v| math::sin(math::angle_to_radians(v, mode)?))?
+            }
+            OpCode::COS => {
+                let mode = self.angle_mode;
+                self.op_unary(|v| math::cos(math::angle_to_radians(v, mode)?))?
+            }
 
-            OpCode::ATAN => self.op_unary(math::atan)?,
-            OpCode::ASIN => self.op_unary(math::asin)?,
-            OpCode::ACOS => self.op_unary(math::acos)?,
+            OpCode::ATAN => {
+                let mode = self.angle_mode;
+                self.op_unary(|v| math::angle_from_radians(math::atan(v)?, mode))?
+            }
+            OpCode::ASIN => {
+                let mode = self.angle_mode;
+                self.op_unary(|v| math::angle_from_radians(math::asin(v)?, mode))?
+            }
+            OpCode::ACOS => {
+                let mode = self.angle_mode;
+                self.op_unary(|v| math::angle_from_radians(math::acos(v)?, mode))?
+            }
 
-            OpCode::TANH => self.op_unary(math::tanh)?,
-            OpCode::SINH => self.op_unary(math::sinh)?,
-            OpCode::COSH => self.op_unary(math::cosh)?,
+            OpCode::ANGL => {
+                let previous = self.angle_mode;
+                match self.pop_value()? {
+                    Value::Primitive(Primitive::Nil) => {}
+                    mode => {
+                        let mode = mode.to_string();
+                        self.angle_mode = AngleMode::from_name(&mode).ok_or_else(|| {
+                            self.emit_err(RuntimeErrorType::InvalidAngleMode(mode))
+                        })?;
+                    }
+                }
+                self.push_value(Value::string(previous.to_string()));
+            }
 
-            OpCode::ROUND => self.op_binary(math::round)?,
+            OpCode::RNDM => {
+                let mode = self.pop_value()?.to_string();
+                let mode = math::rounding_mode(&mode)
+                    .ok_or_else(|| self.emit_err(RuntimeErrorType::InvalidRoundingMode(mode)))?;
+                self.op_binary(|v, precision| math::round_with(v, precision, mode))?
+            }
 
-            OpCode::LOG => self.op_binary(math::log)?,
-            OpCode::ILOG => self.op_binary(math::ilog)?,
+            OpCode::RAND => self.push_input(rand::random::<f64>)?,
+            OpCode::TIME => {
+                let clock = &mut self.clock;
+                let value = self
+                    .replay
+                    .next_input(|| {
+                        let value = Number::from_f64(clock.now().as_secs_f64())
+                            .map_err(RuntimeErrorType::Value)?;
+                        Ok(Value::decimal(value))
+                    })
+                    .map_err(|e| self.emit_err(e))?;
+                self.push_value(value);
+            }
 
-            OpCode::ROOT => self.op_binary(math::root)?,
+            OpCode::FDATE => {
+                let format = self.pop_value()?.to_string();
+                let timestamp = self
+                    .pop_value()?
+                    .cast_integer()
+                    .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+                let timestamp = i64::try_from(timestamp).map_err(|_| {
+                    self.emit_err(RuntimeErrorType::Value(ValueError::ArithmeticOverflow))
+                })?;
+                let date = clock::format_date(timestamp, &format)
+                    .ok_or_else(|| self.emit_err(RuntimeErrorType::InvalidDateFormat(format)))?;
+                self.push_value(Value::string(date));
+            }
+
+            OpCode::FDUR => {
+                let milliseconds = self
+                    .pop_value()?
+                    .cast_integer()
+                    .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+                self.push_value(Value::string(clock::format_duration(milliseconds)));
+            }
+
+            OpCode::PDUR => {
+                let input = self.pop_value()?.to_string();
+                let milliseconds = clock::parse_duration(&input)
+                    .ok_or_else(|| self.emit_err(RuntimeErrorType::InvalidDuration(input)))?;
+                self.push_value(Value::integer(milliseconds));
+            }
+
+            OpCode::IPINT => {
+                let input = self.pop_value()?.to_string();
+                let n = network::ip_to_int(&input)
+                    .ok_or_else(|| self.emit_err(RuntimeErrorType::InvalidIpAddress(input)))?;
+                self.push_value(Value::integer(n));
+            }
+
+            OpCode::INTIP => {
+                let version = self
+                    .pop_value()?
+                    .cast_integer()
+                    .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+                let n = self
+                    .pop_value()?
+                    .cast_integer()
+                    .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+                let ip = network::int_to_ip(n, version).ok_or_else(|| {
+                    let address = format!("IPv{version} {n}");
+                    self.emit_err(RuntimeErrorType::InvalidIpAddress(address))
+                })?;
+                self.push_value(Value::string(ip));
+            }
+
+            OpCode::CIDR => {
+                let input = self.pop_value()?.to_string();
+                let block = network::cidr_block(&input)
+                    .ok_or_else(|| self.emit_err(RuntimeErrorType::InvalidCidr(input)))?;
+                self.push_value(block);
+            }
+
+            OpCode::CSVR => {
+                let input = self.pop_value()?.to_string();
+                let rows = csv::parse(&input)
+                    .map_err(|e| self.emit_err(RuntimeErrorType::InvalidCsv(e)))?;
+                self.push_value(rows);
+            }
+
+            OpCode::CSVW => {
+                let headers = self
+                    .pop_value()?
+                    .cast_array()
+                    .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+                let headers = headers.iter().map(ToString::to_string).collect();
+                let rows = self
+                    .pop_value()?
+                    .cast_array()
+                    .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+                let table = TableData::from_rows(&rows, headers)
+                    .ok_or_else(|| self.emit_err(RuntimeErrorType::InvalidTable))?;
+                self.push_value(Value::string(csv::write(&table)));
+            }
+
+            OpCode::DFMT => {
+                let value = self.pop_value()?;
+                if let Some(table) = TableData::from_value(&value) {
+                    self.push_value(Value::string(table.to_string()));
+                } else if let Some(plot) = PlotData::from_value(&value) {
+                    self.push_value(Value::string(plot.to_string()));
+                } else {
+                    self.check_range_cast(&value, ValueType::String)?;
+                    let value = value
+                        .cast(ValueType::String)
+                        .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+                    self.push_value(value);
+                }
+            }
 
             OpCode::NOP => {}
+
+            ///////////////////////
+            // Superinstructions //
+            ///////////////////////
+            // Each runs the pair of instructions it replaces, in a single dispatch
+            OpCode::PADD => {
+                self.op_push()?;
+                self.op_binary(ops::add)?
+            }
+            OpCode::PSUB => {
+                self.op_push()?;
+                self.op_binary(ops::sub)?
+            }
+            OpCode::RIDX => {
+                self.read_reference()?;
+                self.index_into()?
+            }
+            OpCode::DJNN => self.op_dup_jump_if_not_nil()?,
+            OpCode::DJNE => self.op_dup_jump_if_not_empty()?,
+
+            ///////////////
+            // Registers //
+            ///////////////
+            OpCode::RLDC => self.op_load_constant()?,
+            OpCode::RLDV => self.op_load_variable()?,
+            OpCode::RPOP => self.op_pop_register()?,
+            OpCode::RPSH => self.op_push_register()?,
+            OpCode::RBOP => self.op_register_binary()?,
+            OpCode::RUOP => self.op_register_unary()?,
+
+            ///////////////
+            // Constants //
+            ///////////////
+            OpCode::POOL => self.op_skip_pool()?,
+            OpCode::PSHC => self.op_push_constant()?,
+
+            ///////////////
+            // Value ops //
+            ///////////////
+            // Arithmetic, bitwise, comparison, logical and matching ops are defined once, in `vm::ops`
+            OpCode::ADD => self.op_binary(ops::add)?,
+            OpCode::SUB => self.op_binary(ops::sub)?,
+            OpCode::MUL => self.op_binary(ops::mul)?,
+            OpCode::DIV => self.op_binary(ops::div)?,
+            OpCode::REM => self.op_binary(ops::rem)?,
+            OpCode::POW => self.op_binary(ops::pow)?,
+
+            OpCode::AND => self.op_binary(ops::and)?,
+            OpCode::OR => self.op_binary(ops::or)?,
+            OpCode::XOR => self.op_binary(ops::xor)?,
+            OpCode::SHL => self.op_binary(ops::shl)?,
+            OpCode::SHR => self.op_binary(ops::shr)?,
+
+            OpCode::EQ => self.op_binary(ops::eq)?,
+            OpCode::NE => self.op_binary(ops::ne)?,
+            OpCode::SEQ => self.op_binary(ops::seq)?,
+            OpCode::SNE => self.op_binary(ops::sne)?,
+            OpCode::IEQ => self.op_binary(ops::ieq)?,
+            OpCode::LT => self.op_binary(ops::lt)?,
+            OpCode::LE => self.op_binary(ops::le)?,
+            OpCode::GT => self.op_binary(ops::gt)?,
+            OpCode::GE => self.op_binary(ops::ge)?,
+
+            OpCode::LAND => self.op_binary(ops::logical_and)?,
+            OpCode::LOR => self.op_binary(ops::logical_or)?,
+
+            OpCode::MTCH => self.op_binary(ops::matches)?,
+            OpCode::STWT => self.op_binary(ops::starts_with)?,
+            OpCode::EDWT => self.op_binary(ops::ends_with)?,
+
+            OpCode::ROUND => self.op_binary(ops::round)?,
+            OpCode::LOG => self.op_binary(ops::log)?,
+            OpCode::ILOG => self.op_binary(ops::ilog)?,
+            OpCode::ROOT => self.op_binary(ops::root)?,
+
+            OpCode::SRMV => self.op_binary(ops::remove)?,
+
+            OpCode::NEG => self.op_unary(ops::neg)?,
+            OpCode::NOT => self.op_unary(ops::not)?,
+            OpCode::LNOT => self.op_unary(ops::logical_not)?,
+
+            OpCode::TANH => self.op_unary(ops::tanh)?,
+            OpCode::SINH => self.op_unary(ops::sinh)?,
+            OpCode::COSH => self.op_unary(ops::cosh)?,
+            OpCode::GAMMA => self.op_unary(ops::gamma)?,
+            OpCode::ERF => self.op_unary(ops::erf)?,
+            OpCode::PRIME => self.op_unary(ops::is_prime)?,
+
+            OpCode::AMIN => self.op_unary(ops::min)?,
+            OpCode::AMAX => self.op_unary(ops::max)?,
+            OpCode::ASUM => self.op_unary(ops::sum)?,
+            OpCode::APROD => self.op_unary(ops::product)?,
+            OpCode::AMEAN => self.op_unary(ops::mean)?,
+
+            OpCode::SREV => self.op_unary(ops::reverse)?,
         }
 
         Ok(())
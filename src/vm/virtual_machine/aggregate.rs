@@ -0,0 +1,147 @@
+use crate::value::{CheckedArithmetic, CheckedBoolean, Snippet, Value, ValueError, ValueType};
+
+/// The values of an array, or the integers of a range, without collecting the range into an array
+enum Values {
+    Array(std::vec::IntoIter<Value>),
+    Range(std::ops::Range<i128>),
+}
+
+impl Values {
+    fn new(input: Value) -> Result<Self, ValueError> {
+        match input {
+            Value::Array(a) => Ok(Self::Array(a.into_iter())),
+            Value::Range(r) => Ok(Self::Range(r)),
+            other => Err(ValueError::TypeConversion(
+                other.type_of(),
+                ValueType::Collection,
+                Snippet::of(&[&other]),
+            )),
+        }
+    }
+}
+
+impl Iterator for Values {
+    type Item = Value;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Array(a) => a.next(),
+            Self::Range(r) => r.next().map(Value::integer),
+        }
+    }
+}
+
+/// Check that a value can be used in arithmetic aggregates
+fn numeric(value: Value) -> Result<Value, ValueError> {
+    if value.is_a(ValueType::Numeric) {
+        Ok(value)
+    } else {
+        Err(ValueError::TypeConversion(
+            value.type_of(),
+            ValueType::Numeric,
+            Snippet::of(&[&value]),
+        ))
+    }
+}
+
+/// Find the value for which `better(value, best)` holds against every other value
+/// The first of several equal values is kept, unchanged; values are compared with the usual coercion rules
+fn extreme(
+    input: Value,
+    name: &'static str,
+    better: fn(&Value, &Value) -> Result<bool, ValueError>,
+) -> Result<Value, ValueError> {
+    let mut values = Values::new(input)?;
+    let mut best = values.next().ok_or(ValueError::EmptyCollection(name))?;
+    for value in values {
+        if better(&value, &best)? {
+            best = value;
+        }
+    }
+    Ok(best)
+}
+
+/// The smallest value of an array or range
+pub fn min(input: Value) -> Result<Value, ValueError> {
+    extreme(input, "minimum", |a, b| {
+        a.clone().checked_lt(b.clone())?.cast_boolean()
+    })
+}
+
+/// The largest value of an array or range
+pub fn max(input: Value) -> Result<Value, ValueError> {
+    extreme(input, "maximum", |a, b| {
+        a.clone().checked_gt(b.clone())?.cast_boolean()
+    })
+}
+
+/// Fold the numeric values of an array or range, starting from `identity`
+/// Returns the result, and the number of values
+fn fold(
+    input: Value,
+    identity: i128,
+    op: fn(Value, Value) -> Result<Value, ValueError>,
+) -> Result<(Value, usize), ValueError> {
+    let mut result = Value::integer(identity);
+    let mut count = 0;
+    for value in Values::new(input)? {
+        result = op(result, numeric(value)?)?;
+        count += 1;
+    }
+    Ok((result, count))
+}
+
+/// The sum of an array or range; 0 if it is empty
+/// The result is an integer unless any value is a decimal
+pub fn sum(input: Value) -> Result<Value, ValueError> {
+    Ok(fold(input, 0, |a, b| a.checked_add(b))?.0)
+}
+
+/// The product of an array or range; 1 if it is empty
+/// The result is an integer unless any value is a decimal
+pub fn product(input: Value) -> Result<Value, ValueError> {
+    Ok(fold(input, 1, |a, b| a.checked_mul(b))?.0)
+}
+
+/// The mean of an array or range, always as a decimal
+pub fn mean(input: Value) -> Result<Value, ValueError> {
+    let (sum, count) = fold(input, 0, |a, b| a.checked_add(b))?;
+    if count == 0 {
+        return Err(ValueError::EmptyCollection("mean"));
+    }
+
+    let sum_type = sum.type_of();
+    let sum = sum.as_decimal().ok_or(ValueError::TypeConversion(
+        sum_type,
+        ValueType::Decimal,
+        Snippet::default(),
+    ))?;
+    sum.checked_div(Value::integer(count as i128))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_aggregates() {
+        let mut lav = Lavendeux::new();
+        for (source, expected) in [
+            ("sum([1, 2, 3])", "6"),
+            ("sum([1, 2.5])", "3.5"),
+            ("sum(0..1000001)", "500000500000"),
+            ("product(1..6)", "120"),
+            ("mean([1, 2])", "1.5"),
+            ("min(['b', 'a', 'c'])", "a"),
+            ("max(0..10)", "9"),
+            ("sum([])", "0"),
+            ("product([])", "1"),
+        ] {
+            assert_eq!(lav.run(source).unwrap().to_string(), expected, "{source}");
+        }
+
+        // Empty inputs have no minimum, maximum or mean, and only numbers can be summed
+        assert!(lav.run("min([])").is_err());
+        assert!(lav.run("mean(0..0)").is_err());
+        assert!(lav.run("sum(['a'])").is_err());
+    }
+}
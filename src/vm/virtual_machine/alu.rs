@@ -1,11 +1,21 @@
 use crate::{
-    value::Value,
-    vm::error::{RuntimeError, RuntimeErrorType},
+    value::{Primitive, Value, ValueError},
+    vm::{
+        error::{RuntimeError, RuntimeErrorType},
+        OpCode,
+    },
 };
 
 use super::StackExt;
 
 pub trait ALUExt {
+    /// Check that strings are only used with `-` if creative string operators are enabled
+    fn check_string_operator(
+        &self,
+        opcode: OpCode,
+        operands: &[&Value],
+    ) -> Result<(), RuntimeError>;
+
     /// Perform a unary value operation on the top of the stack.
     /// The result is pushed back onto the stack.
     fn op_unary<F>(&mut self, handler: F) -> Result<(), RuntimeError>
@@ -17,15 +27,49 @@ pub trait ALUExt {
     fn op_binary<F>(&mut self, handler: F) -> Result<(), RuntimeError>
     where
         F: Fn(Value, Value) -> Result<Value, crate::value::ValueError>;
+
+    /// Apply a binary value operation to a left and right operand.
+    /// Shared by the stack and register forms of each operation.
+    #[allow(clippy::result_large_err)]
+    fn apply_binary<F>(
+        &mut self,
+        handler: F,
+        left: Value,
+        right: Value,
+    ) -> Result<Value, RuntimeError>
+    where
+        F: Fn(Value, Value) -> Result<Value, crate::value::ValueError>;
 }
 
 impl ALUExt for super::VirtualMachine {
+    #[inline(always)]
+    fn check_string_operator(
+        &self,
+        opcode: OpCode,
+        operands: &[&Value],
+    ) -> Result<(), RuntimeError> {
+        let function = match opcode {
+            OpCode::SUB | OpCode::PSUB => "remove",
+            OpCode::NEG => "reverse",
+            _ => return Ok(()),
+        };
+
+        let string = operands
+            .iter()
+            .any(|v| matches!(v, Value::Primitive(Primitive::String(_))));
+        match string && !self.creative_string_ops {
+            true => Err(self.emit_err(RuntimeErrorType::CreativeStringOperator(function))),
+            false => Ok(()),
+        }
+    }
+
     #[inline(always)]
     fn op_unary<F>(&mut self, handler: F) -> Result<(), RuntimeError>
     where
         F: Fn(Value) -> Result<Value, crate::value::ValueError>,
     {
         let a = self.pop_value()?;
+        self.check_string_operator(self.last_opcode, &[&a])?;
         self.push_value(handler(a).map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?);
         Ok(())
     }
@@ -37,7 +81,65 @@ impl ALUExt for super::VirtualMachine {
     {
         let a = self.pop_value()?;
         let b = self.pop_value()?;
-        self.push_value(handler(b, a).map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?);
+        self.check_string_operator(self.last_opcode, &[&a, &b])?;
+        let result = self.apply_binary(handler, b, a)?;
+        self.push_value(result);
         Ok(())
     }
+
+    #[inline(always)]
+    fn apply_binary<F>(&mut self, handler: F, b: Value, a: Value) -> Result<Value, RuntimeError>
+    where
+        F: Fn(Value, Value) -> Result<Value, crate::value::ValueError>,
+    {
+        // Mixing a range with a collection expands the range
+        self.check_range_cast(&a, b.type_of())?;
+        self.check_range_cast(&b, a.type_of())?;
+
+        // Mixed currencies are only compared or combined if a converter is set
+        let result = match self.currency_converter.is_some() {
+            false => handler(b, a),
+            true => match handler(b.clone(), a.clone()) {
+                Err(ValueError::CurrencyMismatch(symbol, other)) => {
+                    match self.convert_currency(&a, &symbol) {
+                        Some(a) => handler(b, a),
+                        None => Err(ValueError::CurrencyMismatch(symbol, other)),
+                    }
+                }
+                result => result,
+            },
+        };
+
+        result.map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_creative_string_ops() {
+        let mut lav = Lavendeux::new();
+        assert_eq!(lav.run("remove('banana', 'an')").unwrap().to_string(), "ba");
+        assert_eq!(lav.run("reverse('abc')").unwrap().to_string(), "cba");
+
+        // Enabled by default, with a warning when used on a string literal
+        let report = lav.run_report("'banana' - 'an'").unwrap();
+        assert_eq!(report.outputs[0].to_string(), "ba");
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("remove("));
+        let report = lav.run_report("f(s) = -s\nf(1) + f(2)").unwrap();
+        assert!(report.warnings.is_empty());
+
+        // Disabled, `-` is an error on strings, but the named functions still work
+        lav.set_creative_string_ops(false);
+        lav.run("s = 'abc'").unwrap();
+        assert!(lav.run("s - 'b'").is_err());
+        assert!(lav.run("-s").is_err());
+        assert_eq!(lav.run("-(2 - 3)").unwrap().to_string(), "1");
+        assert_eq!(lav.run("remove(s, 'b')").unwrap().to_string(), "ac");
+        assert_eq!(lav.run("reverse(s)").unwrap().to_string(), "cba");
+        assert!(lav.run("s -= 'b'").is_err());
+    }
 }
@@ -1,28 +1,43 @@
-use std::collections::HashMap;
+use std::{cmp::Ordering, collections::HashMap};
 
 use crate::{
-    traits::SafeVecAlloc,
-    value::{Value, ValueType},
-    vm::error::{RuntimeError, RuntimeErrorType},
+    value::{Function, Value, ValueType},
+    vm::{
+        error::{RuntimeError, RuntimeErrorType},
+        ops,
+    },
 };
 
-use super::{IOExt, StackExt};
+use super::{FunctionExt, IOExt, StackExt};
 
 pub trait CollectionExt {
     fn op_make_array(&mut self) -> Result<(), RuntimeError>;
+    #[allow(clippy::result_large_err)]
+    fn op_concat_arrays(&mut self) -> Result<(), RuntimeError>;
     fn op_make_object(&mut self) -> Result<(), RuntimeError>;
     fn op_make_range(&mut self) -> Result<(), RuntimeError>;
 
     fn op_push_array(&mut self) -> Result<(), RuntimeError>;
     fn op_push_object(&mut self) -> Result<(), RuntimeError>;
+
+    fn op_contains(&mut self, sorted: bool) -> Result<(), RuntimeError>;
+
+    #[allow(clippy::result_large_err)]
+    fn op_sort_with(&mut self) -> Result<(), RuntimeError>;
+    #[allow(clippy::result_large_err)]
+    fn compare_with(
+        &mut self,
+        comparator: &Function,
+        a: &Value,
+        b: &Value,
+    ) -> Result<Ordering, RuntimeError>;
 }
 
 impl CollectionExt for super::VirtualMachine {
     #[inline(always)]
     fn op_make_array(&mut self) -> Result<(), RuntimeError> {
         let n = self.read_u64()? as usize;
-        let mut values =
-            Vec::safe_alloc(n).map_err(|e| self.emit_err(RuntimeErrorType::MemoryAllocation(e)))?;
+        let mut values: Vec<_> = self.alloc_collection(n)?;
         for _ in 0..n {
             let value = self.pop_value()?;
             values.push(value);
@@ -31,11 +46,35 @@ impl CollectionExt for super::VirtualMachine {
         Ok(())
     }
 
+    #[inline(always)]
+    fn op_concat_arrays(&mut self) -> Result<(), RuntimeError> {
+        let n = self.read_u64()? as usize;
+        let mut arrays = vec![];
+        for _ in 0..n {
+            match self.pop_value()? {
+                Value::Array(array) => arrays.push(array),
+                _ => {
+                    return Err(self.emit_err(RuntimeErrorType::BadType(
+                        self.last_opcode,
+                        ValueType::Array,
+                    )))
+                }
+            }
+        }
+
+        let len = arrays.iter().map(Vec::len).sum();
+        let mut values: Vec<_> = self.alloc_collection(len)?;
+        for array in arrays {
+            values.extend(array);
+        }
+        self.push_value(Value::Array(values));
+        Ok(())
+    }
+
     #[inline(always)]
     fn op_make_object(&mut self) -> Result<(), RuntimeError> {
         let n = self.read_u64()? as usize;
-        let mut values = HashMap::safe_alloc(n)
-            .map_err(|e| self.emit_err(RuntimeErrorType::MemoryAllocation(e)))?;
+        let mut values: HashMap<_, _> = self.alloc_collection(n)?;
         for _ in 0..n {
             let key = self.pop_value()?;
             let key = key
@@ -82,6 +121,8 @@ impl CollectionExt for super::VirtualMachine {
                 if start.len() == 1 && end.len() == 1 {
                     let start = start.chars().next().unwrap();
                     let end = end.chars().next().unwrap();
+                    let len = (start as usize).abs_diff(end as usize) + 1;
+                    self.check_budget::<Vec<Value>>(len)?;
                     let crange = if start > end {
                         (end..=start)
                             .map(|c| Value::string(c.to_string()))
@@ -106,6 +147,7 @@ impl CollectionExt for super::VirtualMachine {
         let value = self.pop_value()?;
 
         let array = self.pop_value()?;
+        self.check_range_cast(&array, ValueType::Array)?;
 
         let mut array = array
             .cast_array()
@@ -126,6 +168,7 @@ impl CollectionExt for super::VirtualMachine {
             .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
 
         let object = self.pop_value()?;
+        self.check_range_cast(&object, ValueType::Object)?;
 
         let mut object = object
             .cast_object()
@@ -135,4 +178,177 @@ impl CollectionExt for super::VirtualMachine {
 
         Ok(())
     }
+
+    /// Variables are checked where they are stored, rather than being copied onto the stack first
+    fn op_contains(&mut self, sorted: bool) -> Result<(), RuntimeError> {
+        let value = self.pop_value()?;
+        let collection = self.pop()?;
+        let collection = collection.value(&self.mem).map_err(|e| self.emit_err(e))?;
+
+        let result = match sorted {
+            true => collection.value().contains_sorted(&value),
+            false => collection.value().contains(&value),
+        }
+        .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+
+        self.push_value(Value::boolean(result));
+        Ok(())
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn op_sort_with(&mut self) -> Result<(), RuntimeError> {
+        let descending = self
+            .pop_value()?
+            .cast_boolean()
+            .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+        let function = match self.pop_value()? {
+            Value::Function(function) => Some(*function),
+            _ => None,
+        };
+        let array = self.pop_value()?;
+        self.check_range_cast(&array, ValueType::Array)?;
+        let mut array = array
+            .cast_array()
+            .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+
+        let direction = |ordering: Ordering| match descending {
+            true => ordering.reverse(),
+            false => ordering,
+        };
+
+        let sorted = match function {
+            // A key function; values are sorted by the keys, which are found once for each value
+            Some(key) if key.expects.len() == 1 => {
+                let mut keyed = vec![];
+                for value in array {
+                    keyed.push((self.call_value(key.clone(), vec![value.clone()])?, value));
+                }
+                keyed.sort_by(|(a, _), (b, _)| direction(a.cmp(b)));
+                keyed.into_iter().map(|(_, value)| value).collect()
+            }
+
+            // A comparator, which may fail, or not be consistent, so `sort_by` cannot be used
+            Some(comparator) => merge_sort(array, &mut |a, b| {
+                self.compare_with(&comparator, a, b).map(direction)
+            })?,
+
+            None => {
+                array.sort_by(|a, b| direction(a.cmp(b)));
+                array
+            }
+        };
+
+        self.push_value(Value::Array(sorted));
+        Ok(())
+    }
+
+    /// Call a comparator with two values; it returns a negative number if `a` goes first,
+    /// a positive number if `b` goes first, or 0 if they are equal
+    fn compare_with(
+        &mut self,
+        comparator: &Function,
+        a: &Value,
+        b: &Value,
+    ) -> Result<Ordering, RuntimeError> {
+        let result = self.call_value(comparator.clone(), vec![a.clone(), b.clone()])?;
+        let less = ops::lt(result.clone(), Value::integer(0)).and_then(Value::cast_boolean);
+        let greater = ops::gt(result, Value::integer(0)).and_then(Value::cast_boolean);
+        let ordering = match (less, greater) {
+            (Ok(true), _) => Ordering::Less,
+            (_, Ok(true)) => Ordering::Greater,
+            (Ok(false), Ok(false)) => Ordering::Equal,
+            (Err(e), _) | (_, Err(e)) => return Err(self.emit_err(RuntimeErrorType::Value(e))),
+        };
+        Ok(ordering)
+    }
+}
+
+/// A stable merge sort, with a comparison that can fail
+fn merge_sort<E>(
+    mut values: Vec<Value>,
+    compare: &mut impl FnMut(&Value, &Value) -> Result<Ordering, E>,
+) -> Result<Vec<Value>, E> {
+    if values.len() <= 1 {
+        return Ok(values);
+    }
+
+    let right = values.split_off(values.len() / 2);
+    let left = merge_sort(values, compare)?;
+    let right = merge_sort(right, compare)?;
+
+    // Equal values are taken from the left first, so they keep their order
+    let mut sorted = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        let next = match compare(a, b)? {
+            Ordering::Greater => right.next(),
+            _ => left.next(),
+        };
+        sorted.extend(next);
+    }
+    sorted.extend(left);
+    sorted.extend(right);
+    Ok(sorted)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_sort_by() {
+        let mut lav = Lavendeux::new();
+        for (source, expected) in [
+            (
+                "sort_by([[1, 'a'], [0, 'b'], [1, 'c']], 'a[0] - b[0]')",
+                "[[0, `b`], [1, `a`], [1, `c`]]",
+            ),
+            (
+                "sort_by([[1, 'a'], [0, 'b'], [1, 'c']], 'a[0] - b[0]', true)",
+                "[[1, `a`], [1, `c`], [0, `b`]]",
+            ),
+            (
+                "sort_key(['bb', 'a', 'cc', 'd'], 'x.len()', true)",
+                "[`bb`, `cc`, `a`, `d`]",
+            ),
+            ("sort([1, 3, 2], true)", "[3, 2, 1]"),
+        ] {
+            assert_eq!(lav.run(source).unwrap().to_string(), expected, "{source}");
+        }
+
+        // Comparators can be named functions; an error stops the sort
+        lav.run("desc(a, b) = b - a").unwrap();
+        let result = lav.run("sort_by([1, 3, 2], desc)").unwrap();
+        assert_eq!(result.to_string(), "[3, 2, 1]");
+        assert!(lav.run("sort_by([1, 2], 'throw(\"no\")')").is_err());
+        assert_eq!(
+            lav.run("sort_by([2, 1], 'a - b')").unwrap().to_string(),
+            "[1, 2]"
+        );
+    }
+
+    #[test]
+    fn test_membership() {
+        let mut lav = Lavendeux::new();
+        for (source, expected) in [
+            ("(0..10) contains 5", "true"),
+            ("(0..10) contains 5.0", "true"),
+            ("(0..10) contains 5.5", "false"),
+            ("(0..10) contains (2..4)", "true"),
+            ("(0..10) contains (8..12)", "false"),
+            ("(0..1000000000000) contains 999999999999", "true"),
+            ("contains_sorted([1, 3, 5, 7], 5)", "true"),
+            ("contains_sorted([1, 3, 5, 7], 4)", "false"),
+            ("contains_sorted(['a', 'c'], 'c')", "true"),
+        ] {
+            assert_eq!(lav.run(source).unwrap().to_string(), expected, "{source}");
+        }
+
+        // Sets are built once, then checked by key
+        lav.run("s = to_set(['a', 'b', 3])").unwrap();
+        assert_eq!(lav.run("s contains 3").unwrap().to_string(), "true");
+        assert_eq!(lav.run("s contains 'c'").unwrap().to_string(), "false");
+        assert!(lav.run("to_set([[1]])").is_err());
+    }
 }
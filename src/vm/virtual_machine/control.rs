@@ -1,5 +1,5 @@
 use crate::{
-    value::{Value, ValueError},
+    value::{CheckedBoolean, Primitive, Value, ValueError},
     vm::error::{RuntimeError, RuntimeErrorType},
 };
 
@@ -10,11 +10,21 @@ pub trait ControlExt {
     fn jump_if<F>(&mut self, f: F) -> Result<(), RuntimeError>
     where
         F: Fn(Value) -> Result<bool, ValueError>;
+    #[allow(clippy::result_large_err)]
+    fn peek_jump_if<F>(&mut self, f: F) -> Result<(), RuntimeError>
+    where
+        F: Fn(&Value) -> bool;
 
     fn op_jump_if_false(&mut self) -> Result<(), RuntimeError>;
     fn op_jump_if_true(&mut self) -> Result<(), RuntimeError>;
     fn op_jump_if_empty(&mut self) -> Result<(), RuntimeError>;
     fn op_jump_if_not_empty(&mut self) -> Result<(), RuntimeError>;
+    fn op_jump_if_not_nil(&mut self) -> Result<(), RuntimeError>;
+    fn op_jump_table(&mut self) -> Result<(), RuntimeError>;
+    #[allow(clippy::result_large_err)]
+    fn op_dup_jump_if_not_nil(&mut self) -> Result<(), RuntimeError>;
+    #[allow(clippy::result_large_err)]
+    fn op_dup_jump_if_not_empty(&mut self) -> Result<(), RuntimeError>;
 
     fn jump_to(&mut self, pos: usize) -> Result<(), RuntimeError>;
 }
@@ -22,18 +32,35 @@ pub trait ControlExt {
 impl ControlExt for super::VirtualMachine {
     #[inline(always)]
     fn jump(&mut self) -> Result<(), RuntimeError> {
-        let pos = self.read_u64()?;
-        self.jump_to(pos as usize)
+        let pos = self.read_jump()?;
+        self.jump_to(pos)
     }
 
     fn jump_if<F>(&mut self, f: F) -> Result<(), RuntimeError>
     where
         F: Fn(Value) -> Result<bool, ValueError>,
     {
-        let pos = self.read_u64()?;
+        let pos = self.read_jump()?;
         let value = self.pop_value()?;
         if f(value).map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))? {
-            self.jump_to(pos as usize)
+            self.jump_to(pos)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Like `jump_if`, but the value is left on the stack, as if it had been duplicated first
+    fn peek_jump_if<F>(&mut self, f: F) -> Result<(), RuntimeError>
+    where
+        F: Fn(&Value) -> bool,
+    {
+        let pos = self.read_jump()?;
+        let jump = match self.mem.peek_blank() {
+            Some(value) => value.value(&self.mem).map(|v| f(v.value())),
+            None => return Err(self.emit_err(RuntimeErrorType::StackEmpty(self.last_opcode))),
+        };
+        if jump.map_err(|e| self.emit_err(e))? {
+            self.jump_to(pos)
         } else {
             Ok(())
         }
@@ -54,11 +81,68 @@ impl ControlExt for super::VirtualMachine {
         self.jump_if(|v| Ok(v.len() > 0))
     }
 
+    #[inline(always)]
+    fn op_jump_if_not_nil(&mut self) -> Result<(), RuntimeError> {
+        self.jump_if(|v| Ok(!v.is_nil()))
+    }
+
+    fn op_jump_table(&mut self) -> Result<(), RuntimeError> {
+        let min = self.decode_next::<i128>()?;
+        let count = self.read_u64()?;
+        let default = self.read_jump()?;
+        let mut table = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            table.push(self.read_jump()?);
+        }
+
+        let value = self.pop_value()?;
+        let target = match value {
+            Value::Primitive(Primitive::Integer(i)) => i
+                .checked_sub(min)
+                .and_then(|i| usize::try_from(i).ok())
+                .and_then(|i| table.get(i).copied()),
+
+            // Other types fall back to equality checks, to match a comparison chain
+            value => {
+                let mut target = None;
+                for (i, pos) in table.iter().enumerate() {
+                    if *pos == default {
+                        continue;
+                    }
+
+                    let key = Value::integer(min + i as i128);
+                    let matched = value
+                        .clone()
+                        .checked_eq(key)
+                        .and_then(|v| v.cast_boolean())
+                        .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+                    if matched {
+                        target = Some(*pos);
+                        break;
+                    }
+                }
+                target
+            }
+        };
+
+        self.jump_to(target.unwrap_or(default))
+    }
+
     #[inline(always)]
     fn op_jump_if_empty(&mut self) -> Result<(), RuntimeError> {
         self.jump_if(|v| Ok(v.len() == 0))
     }
 
+    #[inline(always)]
+    fn op_dup_jump_if_not_nil(&mut self) -> Result<(), RuntimeError> {
+        self.peek_jump_if(|v| !v.is_nil())
+    }
+
+    #[inline(always)]
+    fn op_dup_jump_if_not_empty(&mut self) -> Result<(), RuntimeError> {
+        self.peek_jump_if(|v| v.len() > 0)
+    }
+
     fn jump_to(&mut self, pos: usize) -> Result<(), RuntimeError> {
         self.context_mut().set_pc(pos);
         Ok(())
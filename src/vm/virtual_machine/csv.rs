@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use crate::{
+    run_output::TableData,
+    value::{Primitive, Value},
+};
+
+/// Split CSV text into records of fields
+/// Fields can be quoted, to contain commas, newlines or doubled `""` quotes
+/// Blank lines are skipped; returns the line of the quote if one is never closed
+fn records(input: &str) -> Result<Vec<Vec<String>>, usize> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut quote_line = 0;
+    let mut line = 1;
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => {
+                quoted = true;
+                quote_line = line;
+            }
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n', false) => {
+                line += 1;
+                record.push(std::mem::take(&mut field));
+                if record.len() > 1 || !record[0].is_empty() {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            ('\n', true) => {
+                line += 1;
+                field.push(c);
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(quote_line);
+    }
+    if !record.is_empty() || !field.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Parse CSV text into an array of objects, keyed by the headers on the first line
+/// Values are left as strings; rows with fewer fields than the headers are filled with blanks
+/// Returns a description of the problem if the text is not valid CSV
+pub fn parse(input: &str) -> Result<Value, String> {
+    let records = records(input).map_err(|line| format!("quote on line {line} is never closed"))?;
+    let mut records = records.into_iter();
+    let headers = records.next().unwrap_or_default();
+
+    let mut rows = vec![];
+    for (i, record) in records.enumerate() {
+        if record.len() > headers.len() {
+            return Err(format!(
+                "row {} has {} fields, but there are {} headers",
+                i + 1,
+                record.len(),
+                headers.len()
+            ));
+        }
+
+        let mut fields = record.into_iter();
+        let row = headers
+            .iter()
+            .map(|header| {
+                let field = fields.next().unwrap_or_default();
+                (Primitive::String(header.clone()), Value::string(field))
+            })
+            .collect::<HashMap<_, _>>();
+        rows.push(Value::Object(row));
+    }
+
+    Ok(Value::Array(rows))
+}
+
+/// Quote a field if it contains a comma, quote or newline
+fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Write a table as CSV text, with a line of headers first if it has any
+pub fn write(table: &TableData) -> String {
+    let mut lines = vec![];
+    if !table.headers.is_empty() {
+        let headers = table.headers.iter().map(|h| field(h)).collect::<Vec<_>>();
+        lines.push(headers.join(","));
+    }
+    for row in &table.rows {
+        let fields = row
+            .iter()
+            .map(|value| match value {
+                Value::Primitive(Primitive::Nil) => String::new(),
+                value => field(&value.to_string()),
+            })
+            .collect::<Vec<_>>();
+        lines.push(fields.join(","));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_tables() {
+        let mut lav = Lavendeux::new();
+        let source = "rows = csv_parse('item,qty\\r\\npen,2\\r\\n\"ink, \"\"blue\"\"\",10\\r\\n')
+            rows[1]['item']
+            csv_write(rows)";
+        let outputs = lav.run_output(source).unwrap();
+        assert_eq!(outputs[1].to_string(), "ink, \"blue\"");
+        assert_eq!(
+            outputs[2].to_string(),
+            "item,qty\npen,2\n\"ink, \"\"blue\"\"\",10"
+        );
+
+        // Decorated tables are drawn with aligned columns
+        let outputs = lav
+            .run_output("[{'item': 'pen', 'qty': 2}, {'item': 'ink', 'qty': 10}] @table")
+            .unwrap();
+        assert_eq!(
+            outputs[0].to_string(),
+            [
+                "+------+-----+",
+                "| item | qty |",
+                "+------+-----+",
+                "| pen  |   2 |",
+                "| ink  |  10 |",
+                "+------+-----+",
+            ]
+            .join("\n")
+        );
+
+        // Tables are still artifacts when not decorated
+        let report = lav.run_report("table([[1, 2]], ['a', 'b'])").unwrap();
+        assert_eq!(report.artifacts().count(), 1);
+
+        assert!(lav.run("csv_parse('a\\n\"b')").is_err());
+        assert!(lav.run("csv_write([1, 2])").is_err());
+    }
+}
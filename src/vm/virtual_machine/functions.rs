@@ -1,7 +1,11 @@
-use crate::traits::SafeVecAlloc;
-use crate::value::ValueType;
+use crate::compiler::{Compiler, CompilerOptions, HashString};
+use crate::lexer::{Lexer, Stack};
+use crate::parser::{build_ast, ParserOptions};
+use crate::traits::{SafeVecAlloc, SerializeToBytes};
+use crate::value::{Primitive, ValueType};
 use crate::vm::memory_manager::MemoryManager;
 use crate::vm::value_source::ValueSource;
+use crate::vm::OpCode;
 use crate::{
     value::{Function, Value},
     vm::error::{RuntimeError, RuntimeErrorType},
@@ -12,12 +16,40 @@ use super::{IOExt, StackExt};
 pub trait FunctionExt {
     fn alloc_fn(&mut self) -> Result<(), RuntimeError>;
     fn make_fn(&mut self) -> Result<(), RuntimeError>;
+    fn make_expression_fn(&mut self) -> Result<(), RuntimeError>;
     fn push_default_fn_arg(&mut self) -> Result<(), RuntimeError>;
     fn push_fn_signature(&mut self) -> Result<(), RuntimeError>;
 
     fn call_fn(&mut self) -> Result<(), RuntimeError>;
+    #[allow(clippy::result_large_err)]
+    fn call_method(&mut self) -> Result<(), RuntimeError>;
+    #[allow(clippy::result_large_err)]
+    fn call_fn_spread(&mut self) -> Result<(), RuntimeError>;
+    #[allow(clippy::result_large_err)]
+    fn call_method_spread(&mut self) -> Result<(), RuntimeError>;
     fn ret_fn(&mut self) -> Result<(), RuntimeError>;
 
+    #[allow(clippy::result_large_err)]
+    fn resolve_fn(&mut self, name_hash: u64) -> Result<Function, RuntimeError>;
+    #[allow(clippy::result_large_err)]
+    fn resolve_method(
+        &mut self,
+        name: &str,
+        provided: &[ValueSource],
+    ) -> Result<Function, RuntimeError>;
+    #[allow(clippy::result_large_err)]
+    fn pop_fn_args(&mut self, n_args: usize) -> Result<Vec<ValueSource>, RuntimeError>;
+    #[allow(clippy::result_large_err)]
+    fn pop_fn_arg_array(&mut self) -> Result<Vec<ValueSource>, RuntimeError>;
+    #[allow(clippy::result_large_err)]
+    fn invoke_fn(
+        &mut self,
+        function: Function,
+        provided: Vec<ValueSource>,
+    ) -> Result<(), RuntimeError>;
+    #[allow(clippy::result_large_err)]
+    fn call_value(&mut self, function: Function, args: Vec<Value>) -> Result<Value, RuntimeError>;
+
     fn pop_function(&mut self) -> Result<Function, RuntimeError>;
 }
 
@@ -26,7 +58,7 @@ impl FunctionExt for super::VirtualMachine {
     fn pop_function(&mut self) -> Result<Function, RuntimeError> {
         let function = self.pop_value()?;
         match function {
-            Value::Function(function) => Ok(function),
+            Value::Function(function) => Ok(*function),
             _ => Err(self.emit_err(RuntimeErrorType::BadType(
                 self.last_opcode,
                 ValueType::Function,
@@ -38,9 +70,13 @@ impl FunctionExt for super::VirtualMachine {
     fn alloc_fn(&mut self) -> Result<(), RuntimeError> {
         let function = self.pop_function()?;
         let name_hash = function.name_hash;
+        if let Some(profile) = &function.debug {
+            self.mem.record_names(profile);
+        }
+
         self.mem.write_global(
             name_hash,
-            ValueSource::Literal(Value::Function(function)),
+            ValueSource::Literal(Value::function(function)),
             false,
         );
         Ok(())
@@ -49,11 +85,24 @@ impl FunctionExt for super::VirtualMachine {
     #[inline(always)]
     fn make_fn(&mut self) -> Result<(), RuntimeError> {
         let _version = self.next_byte()?;
-        let function = self.decode_with_iterator::<Function>()?;
-        self.push_value(Value::Function(function));
+        let function = self.decode_next::<Function>()?;
+        self.push_value(Value::function(function));
         Ok(())
     }
 
+    fn make_expression_fn(&mut self) -> Result<(), RuntimeError> {
+        let names = match self.pop_value()? {
+            Value::Array(names) => names.iter().map(|n| n.to_string()).collect::<Vec<_>>(),
+            name => vec![name.to_string()],
+        };
+        let expression = self.pop_value()?.to_string();
+
+        let function = compile_expression(&expression, &names)
+            .map_err(|e| self.emit_err(RuntimeErrorType::InvalidExpression(e)))?;
+        self.push_value(Value::function(function));
+        self.push_fn_signature()
+    }
+
     #[inline(always)]
     fn push_default_fn_arg(&mut self) -> Result<(), RuntimeError> {
         let default = self.pop_value()?;
@@ -63,7 +112,7 @@ impl FunctionExt for super::VirtualMachine {
         if let Some(arg) = function.expects.get_mut(i) {
             arg.default = Some(default);
         }
-        self.push_value(Value::Function(function));
+        self.push_value(Value::function(function));
         Ok(())
     }
 
@@ -98,7 +147,7 @@ impl FunctionExt for super::VirtualMachine {
         };
 
         function.docs.signature = format!("{name}({args}){returns}");
-        self.push_value(Value::Function(function));
+        self.push_value(Value::function(function));
         Ok(())
     }
 
@@ -107,17 +156,73 @@ impl FunctionExt for super::VirtualMachine {
         let name_hash = self.read_u64()?;
         let n_args = self.read_u64()? as usize;
 
+        let function = self.resolve_fn(name_hash)?;
+        let provided = self.pop_fn_args(n_args)?;
+        self.invoke_fn(function, provided)
+    }
+
+    #[inline(always)]
+    fn call_method(&mut self) -> Result<(), RuntimeError> {
+        let name = self.decode_next::<String>()?;
+        let n_args = self.read_u64()? as usize;
+        let provided = self.pop_fn_args(n_args)?;
+
+        let function = self.resolve_method(&name, &provided)?;
+        self.invoke_fn(function, provided)
+    }
+
+    #[inline(always)]
+    fn call_fn_spread(&mut self) -> Result<(), RuntimeError> {
+        let name_hash = self.read_u64()?;
+
+        let function = self.resolve_fn(name_hash)?;
+        let provided = self.pop_fn_arg_array()?;
+        self.invoke_fn(function, provided)
+    }
+
+    #[inline(always)]
+    fn call_method_spread(&mut self) -> Result<(), RuntimeError> {
+        let name = self.decode_next::<String>()?;
+        let provided = self.pop_fn_arg_array()?;
+
+        let function = self.resolve_method(&name, &provided)?;
+        self.invoke_fn(function, provided)
+    }
+
+    fn resolve_method(
+        &mut self,
+        name: &str,
+        provided: &[ValueSource],
+    ) -> Result<Function, RuntimeError> {
+        // A function stored in the object under the method's name takes precedence
+        if let Some(base) = provided.first() {
+            let base = match base.value(&self.mem) {
+                Ok(base) => base,
+                Err(e) => return Err(self.emit_err(e)),
+            };
+            if let Value::Object(object) = base.value() {
+                if let Some(Value::Function(f)) = object.get(&Primitive::String(name.to_string())) {
+                    return Ok(f.as_ref().clone());
+                }
+            }
+        }
+
+        self.resolve_fn(name.hash_str())
+    }
+
+    fn resolve_fn(&mut self, name_hash: u64) -> Result<Function, RuntimeError> {
         let function = ValueSource::unresolved(name_hash);
         let function = function
             .into_value(&self.mem)
             .map_err(|e| self.emit_err(e))?;
 
-        let function = match function {
-            Value::Function(f) => f,
-            _ => return Err(self.emit_err(RuntimeErrorType::UndefinedFunction)),
+        match function {
+            Value::Function(f) => Ok(*f),
+            _ => Err(self.emit_err(RuntimeErrorType::UndefinedFunction)),
         }
-        .clone();
+    }
 
+    fn pop_fn_args(&mut self, n_args: usize) -> Result<Vec<ValueSource>, RuntimeError> {
         // Resolve argument values
         let mut provided = vec![];
         for _ in 0..n_args {
@@ -130,6 +235,34 @@ impl FunctionExt for super::VirtualMachine {
             provided.push(next);
         }
         provided.reverse();
+        Ok(provided)
+    }
+
+    fn pop_fn_arg_array(&mut self) -> Result<Vec<ValueSource>, RuntimeError> {
+        // Arguments built from a spread are values, never references
+        match self.pop_value()? {
+            Value::Array(values) => Ok(values.into_iter().map(ValueSource::Literal).collect()),
+            _ => Err(self.emit_err(RuntimeErrorType::BadType(
+                self.last_opcode,
+                ValueType::Array,
+            ))),
+        }
+    }
+
+    fn invoke_fn(
+        &mut self,
+        function: Function,
+        provided: Vec<ValueSource>,
+    ) -> Result<(), RuntimeError> {
+        // The first context is the script itself, not a function call
+        // The error names the call chain itself, in place of a note for each of the frames
+        if self.context.len() > self.max_call_depth {
+            return Err(self.context().emit_err(RuntimeErrorType::CallDepthExceeded {
+                limit: self.max_call_depth,
+                chain: self.call_chain(&function.docs.signature),
+            }));
+        }
+
         let arguments =
             resolve_arguments(&function, provided, &self.mem).map_err(|e| self.emit_err(e))?;
 
@@ -141,16 +274,43 @@ impl FunctionExt for super::VirtualMachine {
         }
 
         // Create a new context level for the function to run in
-        self.push_context(function.body, function.debug, function.returns);
+        let (code, constants) = function.body.shared();
+        self.push_context(
+            code,
+            constants,
+            function.debug,
+            function.returns,
+            Some(&function.docs.signature),
+        );
 
         Ok(())
     }
 
+    /// Call a function, and run it to completion before returning its result
+    /// Used by instructions that call back into the program, such as `SRTF`
+    fn call_value(&mut self, function: Function, args: Vec<Value>) -> Result<Value, RuntimeError> {
+        let depth = self.context.len();
+        let provided = args.into_iter().map(ValueSource::Literal).collect();
+        self.invoke_fn(function, provided)?;
+        while self.context.len() > depth {
+            self.next()?;
+        }
+        self.pop_value()
+    }
+
     fn ret_fn(&mut self) -> Result<(), RuntimeError> {
+        // The declared return type is a conversion; it is an error if the value cannot be converted
         let returns = self.pop_value()?;
-        let returns = returns
-            .cast(self.context().return_type())
-            .map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+        let provided = returns.type_of();
+        let expected = self.context().return_type();
+        self.check_range_cast(&returns, expected)?;
+        let returns = returns.cast(expected).map_err(|_| {
+            self.emit_err(RuntimeErrorType::IncorrectReturnType {
+                signature: self.context().signature().unwrap_or_default().to_string(),
+                expected,
+                provided,
+            })
+        })?;
         self.push_value(returns);
         self.mem.scope_out_to_lock();
         let returns = self.pop_value()?;
@@ -230,3 +390,109 @@ fn resolve_arguments(
 
     Ok(arguments)
 }
+
+/// Compile an expression into a function of the given variables
+/// Returns the error message if the expression is invalid
+fn compile_expression(expression: &str, names: &[String]) -> Result<Function, String> {
+    let source = format!("__expression({}) = {expression}", names.join(", "));
+    let tokens = Lexer::new(&source)
+        .all_tokens()
+        .map_err(|e| e.to_string())?;
+    let ast = build_ast(Stack::new(tokens), ParserOptions::default()).map_err(|e| e.to_string())?;
+
+    let mut compiler = Compiler::new(&source, CompilerOptions::default());
+    ast.compile(&mut compiler).map_err(|e| e.to_string())?;
+    let (_, bytecode) = compiler.decompose();
+
+    // A function definition starts with `MKFN <version> <function>`
+    match bytecode.as_slice() {
+        [op, _version, function @ ..] if *op == OpCode::MKFN as u8 => {
+            Function::from_bytes(function).map_err(|e| e.to_string())
+        }
+        _ => Err(format!("`{expression}` is not a valid expression")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        value::ValueType,
+        vm::error::{RuntimeError, RuntimeErrorType},
+        Error, Lavendeux,
+    };
+
+    #[test]
+    fn test_return_type() {
+        let mut lav = Lavendeux::new();
+
+        // The returned value is converted to the declared type
+        let value = lav.run("half(x): int = x / 2\nhalf(5)").unwrap();
+        assert_eq!(value.to_string(), "2");
+
+        // A value that cannot be converted is an error naming the function
+        let error = lav.run("name(x): int = 'n' + x\nname(1)").unwrap_err();
+        assert!(
+            matches!(
+                &error,
+                Error::Runtime(RuntimeError {
+                    error: RuntimeErrorType::IncorrectReturnType {
+                        signature,
+                        expected: ValueType::Integer,
+                        provided: ValueType::String,
+                    },
+                    ..
+                }) if signature.starts_with("name(x)")
+            ),
+            "{error:?}"
+        );
+
+        // Without a declared type, anything can be returned
+        assert!(lav.run("name(x) = 'n' + x\nname(1)").is_ok());
+    }
+
+    #[test]
+    fn test_call_depth() {
+        let mut lav = Lavendeux::new();
+
+        // Unbounded recursion stops at the limit, instead of overflowing the stack
+        let error = lav
+            .run("forever(n) = forever(n + 1)\nforever(0)")
+            .unwrap_err();
+        assert_eq!(error.code(), "E0418");
+        assert!(
+            matches!(
+                &error,
+                Error::Runtime(RuntimeError {
+                    error: RuntimeErrorType::CallDepthExceeded { limit: 10_000, chain },
+                    ..
+                }) if chain.starts_with("forever(n) (x10001) <- <script>")
+            ),
+            "{error:?}"
+        );
+
+        // The VM is still usable afterwards
+        assert_eq!(lav.run("1 + 1").unwrap().to_string(), "2");
+
+        // The limit counts nested calls, and can be changed
+        lav.set_max_call_depth(10);
+        lav.run("count(n) = if n == 0 then 0 else 1 + count(n - 1)")
+            .unwrap();
+        assert_eq!(lav.run("count(9)").unwrap().to_string(), "9");
+        let error = lav.run("count(10)").unwrap_err();
+        assert!(
+            matches!(
+                &error,
+                Error::Runtime(RuntimeError {
+                    error: RuntimeErrorType::CallDepthExceeded { limit: 10, .. },
+                    ..
+                })
+            ),
+            "{error:?}"
+        );
+
+        // Mutual recursion names each of the functions involved
+        lav.run("ping(n) = pong(n)\npong(n) = ping(n)").unwrap();
+        let error = lav.run("ping(0)").unwrap_err();
+        assert!(error.to_string().contains("pong(n) <- ping(n)"), "{error}");
+    }
+}
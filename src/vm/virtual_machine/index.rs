@@ -1,7 +1,7 @@
 use std::{collections::HashMap, ops::Range};
 
 use crate::{
-    value::{Primitive, Value},
+    value::{Primitive, Value, ValueError},
     vm::{
         error::{RuntimeError, RuntimeErrorType},
         value_source::{ValueReference, ValueSource},
@@ -14,6 +14,7 @@ pub type Map = HashMap<Primitive, Value>;
 
 pub trait IndexExt {
     fn index_into(&mut self) -> Result<(), RuntimeError>;
+    fn index_into_nil_safe(&mut self) -> Result<(), RuntimeError>;
 }
 
 impl IndexExt for super::VirtualMachine {
@@ -28,10 +29,9 @@ impl IndexExt for super::VirtualMachine {
         // Now we branch on whether the base is a reference
         match base {
             ValueSource::Reference(ValueReference::Unresolved(name_hash)) => {
-                let slot = self
-                    .mem
-                    .get_ref(name_hash)
-                    .ok_or_else(|| self.emit_err(RuntimeErrorType::HashNotFound))?;
+                let slot = self.mem.get_ref(name_hash).ok_or_else(|| {
+                    self.emit_err(RuntimeErrorType::HashNotFound(name_hash, None))
+                })?;
                 base = ValueSource::resolved(slot, vec![index]);
                 self.push(base);
             }
@@ -58,6 +58,22 @@ impl IndexExt for super::VirtualMachine {
                 });
             }
 
+            ValueSource::Literal(Value::Primitive(Primitive::Bytes(bytes))) => {
+                // If the base is bytes, we're getting a byte, or a slice of bytes
+                self.push_value(match index {
+                    Value::Range(range) => {
+                        idx_bytes_by_range(bytes, range).map_err(|e| self.emit_err(e))?
+                    }
+                    Value::Array(array) => {
+                        idx_bytes_by_arr(bytes, array).map_err(|e| self.emit_err(e))?
+                    }
+                    Value::Primitive(p) => {
+                        idx_bytes_by_val(bytes, p).map_err(|e| self.emit_err(e))?
+                    }
+                    _ => return Err(self.emit_err(RuntimeErrorType::IndexingType)),
+                });
+            }
+
             ValueSource::Literal(Value::Range(range)) => {
                 // If the base is a range, we're getting a set of values
                 match index {
@@ -124,6 +140,39 @@ impl IndexExt for super::VirtualMachine {
 
         Ok(())
     }
+
+    #[inline(always)]
+    fn index_into_nil_safe(&mut self) -> Result<(), RuntimeError> {
+        // Unlike IDEX, the base is resolved right away
+        // Since we need to know if the index exists to decide on nil
+        let index = self.pop_value()?;
+        let base = self.pop_value()?;
+        if base.is_nil() {
+            self.push_value(Value::nil());
+            return Ok(());
+        }
+
+        // Only a missing index gives nil; indexing into the wrong type is still an error
+        self.push_value(base);
+        self.push_value(index);
+        match self.index_into() {
+            Err(e) if is_missing_index(&e.error) => self.push_value(Value::nil()),
+            result => result?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Check if an indexing error means the index does not exist in the base
+#[inline(always)]
+fn is_missing_index(error: &RuntimeErrorType) -> bool {
+    matches!(
+        error,
+        RuntimeErrorType::IndexingValue
+            | RuntimeErrorType::Value(ValueError::KeyNotFound(_))
+            | RuntimeErrorType::Value(ValueError::IndexOutOfBounds(_, _))
+    )
 }
 
 #[inline(always)]
@@ -171,6 +220,50 @@ fn idx_string_by_range(base: String, index: Range<i128>) -> Result<Value, Runtim
     Ok(Value::string(s))
 }
 
+#[inline(always)]
+fn idx_bytes_by_val(base: Vec<u8>, index: Primitive) -> Result<Value, RuntimeErrorType> {
+    match index {
+        Primitive::Integer(mut i) => {
+            if i < 0 {
+                i += base.len() as i128;
+            }
+
+            let b = base
+                .get(i as usize)
+                .ok_or(RuntimeErrorType::IndexingValue)?;
+            Ok(Value::integer(*b as i128))
+        }
+        _ => Err(RuntimeErrorType::IndexingType),
+    }
+}
+#[inline(always)]
+fn idx_bytes_by_arr(base: Vec<u8>, index: Vec<Value>) -> Result<Value, RuntimeErrorType> {
+    let indices = index
+        .into_iter()
+        .map(|v| v.cast_integer().map_err(RuntimeErrorType::Value))
+        .collect::<Result<Vec<_>, _>>()?;
+    let bytes = indices
+        .iter()
+        .map(|i| {
+            let i = if *i < 0 { base.len() as i128 + i } else { *i };
+            base.get(i as usize)
+                .copied()
+                .ok_or(RuntimeErrorType::IndexingValue)
+        })
+        .collect::<Result<Vec<_>, RuntimeErrorType>>()?;
+    Ok(Value::bytes(bytes))
+}
+#[inline(always)]
+fn idx_bytes_by_range(base: Vec<u8>, index: Range<i128>) -> Result<Value, RuntimeErrorType> {
+    let bytes = base
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| index.contains(&(*i as i128)))
+        .map(|(_, b)| b)
+        .collect();
+    Ok(Value::bytes(bytes))
+}
+
 #[inline(always)]
 fn idx_range_by_val(base: Range<i128>, index: Primitive) -> Result<Value, RuntimeErrorType> {
     match index {
@@ -1,9 +1,9 @@
 use crate::{
-    traits::SerializeToBytes,
+    traits::{ByteReader, SerializeToBytes},
     value::{Primitive, Value, ValueType},
     vm::{
         error::{RuntimeError, RuntimeErrorType},
-        opcodes::OpCode,
+        opcodes::{jump_target, OpCode, JUMP_SIZE},
     },
 };
 
@@ -17,8 +17,9 @@ pub trait IOExt {
     fn read_u64(&mut self) -> Result<u64, RuntimeError>;
     fn read_u16(&mut self) -> Result<u16, RuntimeError>;
     fn read_i32(&mut self) -> Result<i32, RuntimeError>;
+    fn read_jump(&mut self) -> Result<usize, RuntimeError>;
 
-    fn decode_with_iterator<T>(&mut self) -> Result<T, RuntimeError>
+    fn decode_next<T>(&mut self) -> Result<T, RuntimeError>
     where
         T: SerializeToBytes;
 }
@@ -59,37 +60,48 @@ impl IOExt for super::VirtualMachine {
 
     #[inline(always)]
     fn read_value(&mut self) -> Result<Value, RuntimeError> {
-        let value = self.decode_with_iterator::<Primitive>()?;
+        let value = self.decode_next::<Primitive>()?;
         let value = Value::Primitive(value);
         Ok(value)
     }
 
     #[inline(always)]
     fn read_u64(&mut self) -> Result<u64, RuntimeError> {
-        self.decode_with_iterator::<u64>()
+        self.decode_next::<u64>()
     }
 
     #[inline(always)]
     fn read_u16(&mut self) -> Result<u16, RuntimeError> {
-        self.decode_with_iterator::<u16>()
+        self.decode_next::<u16>()
     }
 
     #[inline(always)]
     fn read_i32(&mut self) -> Result<i32, RuntimeError> {
-        self.decode_with_iterator::<i32>()
+        self.decode_next::<i32>()
     }
 
+    /// Read a jump offset, and return the position it lands on
+    /// Offsets are read straight from the code, since jumps are on the hot path
     #[inline(always)]
-    fn decode_with_iterator<T>(&mut self) -> Result<T, RuntimeError>
+    fn read_jump(&mut self) -> Result<usize, RuntimeError> {
+        let at = self.context().pc();
+        let mut offset = [0; JUMP_SIZE];
+        offset.copy_from_slice(self.next_bytes(JUMP_SIZE)?);
+        jump_target(at, i32::from_be_bytes(offset))
+            .ok_or_else(|| self.emit_err(RuntimeErrorType::InvalidJump(self.last_opcode)))
+    }
+
+    #[inline(always)]
+    fn decode_next<T>(&mut self) -> Result<T, RuntimeError>
     where
         T: SerializeToBytes,
     {
         let pc = self.context().pc();
-        let mut iter = self.context().code().iter().copied().skip(pc);
-        let len = iter.len();
-        let result = T::deserialize_from_bytes(&mut iter)
+        let code = self.context().code();
+        let mut reader = ByteReader::new(code.get(pc..).unwrap_or_default());
+        let result = T::deserialize_from_bytes(&mut reader)
             .map_err(|e| self.emit_err(RuntimeErrorType::Decode(self.last_opcode, e)))?;
-        let len = len - iter.len();
+        let len = reader.offset();
 
         if len > 0 {
             self.context_mut().set_pc(pc + len);
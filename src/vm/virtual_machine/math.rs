@@ -1,4 +1,29 @@
-use crate::value::{Number, Primitive, Value, ValueError};
+use fpdec::RoundingMode;
+
+use crate::{
+    value::{Number, Primitive, Value, ValueError},
+    vm::AngleMode,
+};
+
+/// Convert an angle in the given angle mode to radians, for use with `sin`, `cos` and `tan`
+pub fn angle_to_radians(input: Value, mode: AngleMode) -> Result<Value, ValueError> {
+    if mode == AngleMode::Radians {
+        return Ok(input);
+    }
+
+    let v = mode.to_radians(input.cast_decimal()?.into_f64());
+    Ok(Value::Primitive(Primitive::Decimal(Number::from_f64(v)?)))
+}
+
+/// Convert an angle in radians, as returned by `asin`, `acos`, `atan` and `atan2`, to the given angle mode
+pub fn angle_from_radians(input: Value, mode: AngleMode) -> Result<Value, ValueError> {
+    if mode == AngleMode::Radians {
+        return Ok(input);
+    }
+
+    let v = mode.from_radians(input.cast_decimal()?.into_f64());
+    Ok(Value::Primitive(Primitive::Decimal(Number::from_f64(v)?)))
+}
 
 /// Calculate the tangent of the top value on the stack
 /// Expects a value in radians
@@ -75,6 +100,7 @@ pub fn cosh(input: Value) -> Result<Value, ValueError> {
     Ok(Value::Primitive(Primitive::Decimal(Number::from_f64(v)?)))
 }
 
+/// Round a value to the given number of decimal places
 pub fn round(input: Value, precision: Value) -> Result<Value, ValueError> {
     let v = input.cast_decimal()?;
     let precision = precision.cast_integer()?;
@@ -83,6 +109,31 @@ pub fn round(input: Value, precision: Value) -> Result<Value, ValueError> {
     Ok(Value::Primitive(Primitive::Decimal(v)))
 }
 
+/// Parse the name of a rounding mode, such as `half_up` or `floor`
+/// Returns None if the name is not recognized
+pub fn rounding_mode(name: &str) -> Option<RoundingMode> {
+    match name.to_lowercase().as_str() {
+        "half_even" => Some(RoundingMode::RoundHalfEven),
+        "half_up" => Some(RoundingMode::RoundHalfUp),
+        "half_down" => Some(RoundingMode::RoundHalfDown),
+        "up" => Some(RoundingMode::RoundUp),
+        "down" => Some(RoundingMode::RoundDown),
+        "ceiling" => Some(RoundingMode::RoundCeiling),
+        "floor" => Some(RoundingMode::RoundFloor),
+        _ => None,
+    }
+}
+
+/// Round a value to a precision, using the given rounding mode
+pub fn round_with(input: Value, precision: Value, mode: RoundingMode) -> Result<Value, ValueError> {
+    let v = input.cast_decimal()?;
+    let precision = precision.cast_integer()?;
+
+    let v = v.round_with(precision as i8, mode)?;
+    Ok(Value::Primitive(Primitive::Decimal(v)))
+}
+
+/// Calculate the logarithm of a value in the given base
 pub fn log(input: Value, base: Value) -> Result<Value, ValueError> {
     let v = input.cast_decimal()?.into_f64();
     let base = base.cast_decimal()?.into_f64();
@@ -90,6 +141,7 @@ pub fn log(input: Value, base: Value) -> Result<Value, ValueError> {
     Ok(Value::Primitive(Primitive::Decimal(Number::from_f64(v)?)))
 }
 
+/// Calculate the integer logarithm of a value in the given base, rounded down
 pub fn ilog(input: Value, base: Value) -> Result<Value, ValueError> {
     let v = input.cast_integer()?;
     let base = base.cast_integer()?;
@@ -97,9 +149,160 @@ pub fn ilog(input: Value, base: Value) -> Result<Value, ValueError> {
     Ok(Value::Primitive(Primitive::Integer(v as i128)))
 }
 
+/// Calculate the nth root of a value
 pub fn root(input: Value, n: Value) -> Result<Value, ValueError> {
     let v = input.cast_decimal()?.into_f64();
     let n = n.cast_decimal()?.into_f64();
-    let v = n.powf(1.0 / v);
+    let v = v.powf(1.0 / n);
     Ok(Value::Primitive(Primitive::Decimal(Number::from_f64(v)?)))
 }
+
+/// Calculate the gamma function of the top value on the stack
+/// Uses the Lanczos approximation, with the reflection formula below 0.5
+pub fn gamma(input: Value) -> Result<Value, ValueError> {
+    let v = input.cast_decimal()?.into_f64();
+    Ok(Value::Primitive(Primitive::Decimal(Number::from_f64(
+        gamma_f64(v),
+    )?)))
+}
+
+fn gamma_f64(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    // Undefined at 0 and the negative integers
+    if x <= 0.0 && x.fract() == 0.0 {
+        return f64::NAN;
+    }
+
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return pi / ((pi * x).sin() * gamma_f64(1.0 - x));
+    }
+
+    let x = x - 1.0;
+    let t = x + G + 0.5;
+    let sum = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64));
+
+    (2.0 * std::f64::consts::PI).sqrt() * t.powf(x + 0.5) * (-t).exp() * sum
+}
+
+/// Calculate the error function of the top value on the stack
+/// Uses the Taylor series for small values, and a continued fraction for the tails
+pub fn erf(input: Value) -> Result<Value, ValueError> {
+    let v = input.cast_decimal()?.into_f64();
+    Ok(Value::Primitive(Primitive::Decimal(Number::from_f64(
+        erf_f64(v),
+    )?)))
+}
+
+fn erf_f64(x: f64) -> f64 {
+    if x < 0.0 {
+        return -erf_f64(-x);
+    }
+
+    if x < 3.0 {
+        let mut term = x;
+        let mut sum = x;
+        for n in 1..100 {
+            term *= -x * x / n as f64;
+            let next = term / (2 * n + 1) as f64;
+            sum += next;
+            if next.abs() < 1e-17 * sum.abs() {
+                break;
+            }
+        }
+        sum * 2.0 / std::f64::consts::PI.sqrt()
+    } else {
+        // erfc(x) = exp(-x^2) / sqrt(pi) * 1 / (x + (1/2) / (x + 1 / (x + (3/2) / (x + ...))))
+        let fraction = (1..60).rev().fold(x, |acc, k| x + (k as f64 / 2.0) / acc);
+        1.0 - (-x * x).exp() / (std::f64::consts::PI.sqrt() * fraction)
+    }
+}
+
+/// Check if the top value on the stack is a prime number
+/// Uses a Miller-Rabin test, which is deterministic for all values below 3.3e24
+pub fn is_prime(input: Value) -> Result<Value, ValueError> {
+    let n = input.cast_integer()?;
+    let prime = n > 1 && is_prime_u128(n as u128);
+    Ok(Value::Primitive(Primitive::Boolean(prime)))
+}
+
+fn is_prime_u128(n: u128) -> bool {
+    const BASES: [u128; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    for p in BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    let mut d = n - 1;
+    let mut s = 0;
+    while d.is_multiple_of(2) {
+        d /= 2;
+        s += 1;
+    }
+
+    'bases: for a in BASES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+
+    true
+}
+
+fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    if let Some(v) = a.checked_mul(b) {
+        return v % m;
+    }
+
+    // Double-and-add, so that intermediate values never overflow
+    let (mut a, mut b, mut result) = (a % m, b % m, 0u128);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = if result >= m - a {
+                result - (m - a)
+            } else {
+                result + a
+            };
+        }
+        a = if a >= m - a { a - (m - a) } else { a + a };
+        b >>= 1;
+    }
+    result
+}
+
+fn pow_mod(base: u128, exp: u128, m: u128) -> u128 {
+    let (mut base, mut exp, mut result) = (base % m, exp, 1u128);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
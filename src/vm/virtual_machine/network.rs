@@ -0,0 +1,99 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use crate::value::{Primitive, Value};
+
+/// Parse an IPv4 or IPv6 address into an integer
+/// IPv6 addresses use all 128 bits, so those above `7fff::` are negative, but still work with bitwise operators
+pub fn ip_to_int(input: &str) -> Option<i128> {
+    match input.trim().parse::<IpAddr>().ok()? {
+        IpAddr::V4(ip) => Some(u32::from(ip) as i128),
+        IpAddr::V6(ip) => Some(u128::from(ip) as i128),
+    }
+}
+
+/// Format an integer as an IPv4 or IPv6 address, such as `192.168.0.1`
+/// Returns None if the version is not 4 or 6, or the integer does not fit in an IPv4 address
+pub fn int_to_ip(n: i128, version: i128) -> Option<String> {
+    match version {
+        4 => Some(Ipv4Addr::from(u32::try_from(n).ok()?).to_string()),
+        6 => Some(Ipv6Addr::from(n as u128).to_string()),
+        _ => None,
+    }
+}
+
+/// Parse a CIDR block, such as `10.0.0.0/8`, into an object describing the subnet
+/// The address does not need to be the start of the block; `10.1.2.3/8` is the same subnet
+pub fn cidr_block(input: &str) -> Option<Value> {
+    let (address, prefix) = input.trim().split_once('/')?;
+    let address = address.trim().parse::<IpAddr>().ok()?;
+    let prefix = prefix.trim().parse::<u32>().ok()?;
+
+    let (version, bits, address) = match address {
+        IpAddr::V4(ip) => (4, 32, u32::from(ip) as u128),
+        IpAddr::V6(ip) => (6, 128, u128::from(ip)),
+    };
+    if prefix > bits {
+        return None;
+    }
+
+    // Mask of the bits of the address that are inside the block
+    let all = u128::MAX >> (128 - bits);
+    let host_mask = all.checked_shr(prefix).unwrap_or(0) & all;
+    let netmask = all & !host_mask;
+    let network = address & netmask;
+    let broadcast = network | host_mask;
+
+    let ip = |n: u128| match version {
+        4 => Value::string(Ipv4Addr::from(n as u32).to_string()),
+        _ => Value::string(Ipv6Addr::from(n).to_string()),
+    };
+
+    // The largest IPv6 blocks have too many addresses to count in an integer
+    let size = match i128::try_from(host_mask)
+        .ok()
+        .and_then(|n| n.checked_add(1))
+    {
+        Some(size) => Value::integer(size),
+        None => Value::nil(),
+    };
+
+    let fields = [
+        ("network", ip(network)),
+        ("broadcast", ip(broadcast)),
+        ("netmask", ip(netmask)),
+        ("prefix", Value::integer(prefix as i128)),
+        ("version", Value::integer(version)),
+        ("size", size),
+    ];
+    Some(Value::Object(
+        fields
+            .into_iter()
+            .map(|(key, value)| (Primitive::String(key.to_string()), value))
+            .collect::<HashMap<_, _>>(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Lavendeux;
+
+    #[test]
+    fn test_network() {
+        let mut lav = Lavendeux::new();
+        for (source, expected) in [
+            ("int_to_ip(ip_to_int('10.0.0.1') + 255)", "10.0.1.0"),
+            ("subnet('172.16.5.4/12')['broadcast']", "172.31.255.255"),
+            ("cidr_contains('172.16.0.0/12', '172.20.1.1')", "true"),
+            ("cidr_contains('172.16.0.0/12', '172.32.1.1')", "false"),
+            ("cidr_contains('fc00::/7', 'fd12::1')", "true"),
+            ("cidr_contains('fc00::/7', '10.0.0.1')", "false"),
+            ("prefix_to_netmask(20)", "255.255.240.0"),
+        ] {
+            assert_eq!(lav.run(source).unwrap().to_string(), expected, "{source}");
+        }
+        assert!(lav.run("ip_version('localhost')").is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{
+    value::Value,
+    vm::{
+        error::{RuntimeError, RuntimeErrorType},
+        ops,
+        value_source::ValueSource,
+    },
+};
+
+use super::{ALUExt, IOExt, StackExt};
+
+#[allow(clippy::result_large_err)]
+pub trait RegisterExt {
+    fn op_load_constant(&mut self) -> Result<(), RuntimeError>;
+    fn op_load_variable(&mut self) -> Result<(), RuntimeError>;
+    fn op_pop_register(&mut self) -> Result<(), RuntimeError>;
+    fn op_push_register(&mut self) -> Result<(), RuntimeError>;
+
+    fn op_register_binary(&mut self) -> Result<(), RuntimeError>;
+    fn op_register_unary(&mut self) -> Result<(), RuntimeError>;
+}
+
+impl RegisterExt for super::VirtualMachine {
+    #[inline(always)]
+    fn op_load_constant(&mut self) -> Result<(), RuntimeError> {
+        let register = self.next_byte()?;
+        let value = self.read_value()?;
+        self.context_mut()
+            .set_register(register, ValueSource::Literal(value));
+        Ok(())
+    }
+
+    /// Variables are resolved when the register is used, as with `REF`
+    #[inline(always)]
+    fn op_load_variable(&mut self) -> Result<(), RuntimeError> {
+        let register = self.next_byte()?;
+        let name_hash = self.read_u64()?;
+        self.context_mut()
+            .set_register(register, ValueSource::unresolved(name_hash));
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn op_pop_register(&mut self) -> Result<(), RuntimeError> {
+        let register = self.next_byte()?;
+        let value = self.pop()?;
+        self.context_mut().set_register(register, value);
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn op_push_register(&mut self) -> Result<(), RuntimeError> {
+        let register = self.next_byte()?;
+        let value = self.context_mut().take_register(register);
+        self.push(value);
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn op_register_binary(&mut self) -> Result<(), RuntimeError> {
+        let opcode = self.read_opcode()?;
+        let handler = ops::binary_handler(opcode)
+            .ok_or_else(|| self.emit_err(RuntimeErrorType::InvalidOpcode(opcode as u8)))?;
+        let dst = self.next_byte()?;
+        let left = self.next_byte()?;
+        let right = self.next_byte()?;
+
+        let left = self.take_register_value(left)?;
+        let right = self.take_register_value(right)?;
+        self.check_string_operator(opcode, &[&left, &right])?;
+        let result = self.apply_binary(handler, left, right)?;
+
+        self.context_mut()
+            .set_register(dst, ValueSource::Literal(result));
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn op_register_unary(&mut self) -> Result<(), RuntimeError> {
+        let opcode = self.read_opcode()?;
+        let handler = ops::unary_handler(opcode)
+            .ok_or_else(|| self.emit_err(RuntimeErrorType::InvalidOpcode(opcode as u8)))?;
+        let dst = self.next_byte()?;
+        let operand = self.next_byte()?;
+
+        let operand = self.take_register_value(operand)?;
+        self.check_string_operator(opcode, &[&operand])?;
+        let result = handler(operand).map_err(|e| self.emit_err(RuntimeErrorType::Value(e)))?;
+
+        self.context_mut()
+            .set_register(dst, ValueSource::Literal(result));
+        Ok(())
+    }
+}
+
+impl super::VirtualMachine {
+    /// Take the value out of a register, resolving it if it refers to a variable
+    #[inline(always)]
+    #[allow(clippy::result_large_err)]
+    fn take_register_value(&mut self, register: u8) -> Result<Value, RuntimeError> {
+        self.context_mut()
+            .take_register(register)
+            .into_value(&self.mem)
+            .map_err(|e| self.emit_err(e))
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
     vm::{
         error::{RuntimeError, RuntimeErrorType},
         value_source::ValueSource,
+        CONSTANT_INDEX_SIZE,
     },
 };
 
@@ -16,6 +17,8 @@ pub trait StackExt {
     fn pop(&mut self) -> Result<ValueSource, RuntimeError>;
 
     fn op_push(&mut self) -> Result<(), RuntimeError>;
+    fn op_push_constant(&mut self) -> Result<(), RuntimeError>;
+    fn op_skip_pool(&mut self) -> Result<(), RuntimeError>;
     fn op_pop(&mut self) -> Result<(), RuntimeError>;
     fn swap(&mut self) -> Result<(), RuntimeError>;
     fn dup(&mut self) -> Result<(), RuntimeError>;
@@ -53,6 +56,30 @@ impl StackExt for super::VirtualMachine {
         Ok(())
     }
 
+    /// The index is read straight from the code, and the constant was decoded with the pool
+    #[inline(always)]
+    fn op_push_constant(&mut self) -> Result<(), RuntimeError> {
+        let mut index = [0; CONSTANT_INDEX_SIZE];
+        index.copy_from_slice(self.next_bytes(CONSTANT_INDEX_SIZE)?);
+        let index = u32::from_be_bytes(index) as usize;
+
+        let value = self
+            .context()
+            .constant(index)
+            .cloned()
+            .ok_or_else(|| self.emit_err(RuntimeErrorType::InvalidConstant(index)))?;
+        self.push_value(value);
+        Ok(())
+    }
+
+    /// The pool was decoded when the code was loaded, so it is skipped without reading it
+    fn op_skip_pool(&mut self) -> Result<(), RuntimeError> {
+        let _count = self.read_u64()?;
+        let size = self.read_u64()?;
+        self.next_bytes(size as usize)?;
+        Ok(())
+    }
+
     #[inline(always)]
     fn op_pop(&mut self) -> Result<(), RuntimeError> {
         self.pop()?;
@@ -0,0 +1,339 @@
+//! Golden-file tests for the scripts in `example_scripts/`
+//! Each script is lexed, parsed, compiled and run, and the output of every stage is compared
+//! against the files checked in under `tests/golden/`:
+//! - `.tokens` - one line per token; line, rule and text
+//! - `.ast` - the tree of node kinds, and the source each node covers
+//! - `.asm` - the disassembly of the compiled program
+//! - `.value` - the value the script returns, and anything it prints
+//!
+//! A stage that fails records its error instead, and later stages are not run
+//! Only the stages listed in `EXPECTED_FAILURES` may fail
+//!
+//! After an intended change in output, review the differences and update the files with:
+//! `cargo test --test golden -- --bless`
+use lavendeux_parser::{
+    compiler::asm_transcoder::ASMTranscoder,
+    lexer::{Lexer, Stack, Token},
+    parser::{build_ast, Node, Visitor},
+    value::Value,
+    vm::CapturedOutput,
+    Error, Lavendeux,
+};
+use std::{
+    cell::RefCell,
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+const SCRIPT_DIR: &str = "example_scripts";
+const GOLDEN_DIR: &str = "tests/golden";
+
+/// Longest excerpt of source shown for an AST node
+const MAX_EXCERPT_LEN: usize = 40;
+
+/// Stages that fail, as (script, stage), because the script was written for an older version of the language
+/// Their golden files still record the error; a listed stage that starts passing must be removed from here
+const EXPECTED_FAILURES: &[(&str, &str)] = &[
+    // `$` is not a token
+    ("self_test", "tokens"),
+    // Trailing commas in object literals
+    ("gpt", "ast"),
+    ("emoji", "ast"),
+    // Includes `stdlib/array`
+    ("stdlib", "asm"),
+    // Empty index, as in `chunks[]`
+    ("stdlib/array", "ast"),
+    // `match` as a switch on a value
+    ("stdlib/bitwise", "ast"),
+    // Float suffix, as in `1.0f`
+    ("stdlib/system", "ast"),
+    // `assert_eq` and `from_json` are not in the standard library
+    ("stdlib/math", "value"),
+    ("stdlib/string", "value"),
+    ("zarbans_grotto", "value"),
+];
+
+fn main() {
+    let bless = std::env::args().any(|arg| arg == "--bless");
+
+    let mut scripts = vec![];
+    find_scripts(Path::new(SCRIPT_DIR), &mut scripts);
+    scripts.sort();
+
+    let mut failures = vec![];
+    let mut expected_files = vec![];
+    for script in &scripts {
+        let source = std::fs::read_to_string(script).expect("Could not read script");
+        let relative = script.strip_prefix(SCRIPT_DIR).unwrap().with_extension("");
+        let name = relative.to_string_lossy().replace('\\', "/");
+        let name = name.trim_start_matches('/');
+
+        for (extension, actual) in run_stages(&source) {
+            // Golden files may be checked out with either line ending
+            let actual = actual.replace("\r\n", "\n");
+
+            let failed = actual.starts_with("error:") || actual.starts_with("panic:");
+            match (failed, EXPECTED_FAILURES.contains(&(name, extension))) {
+                (true, false) => failures.push(format!("{name}.{extension}: unexpected failure")),
+                (false, true) => failures.push(format!(
+                    "{name}.{extension}: now passes; remove it from EXPECTED_FAILURES"
+                )),
+                _ => {}
+            }
+
+            let path = Path::new(GOLDEN_DIR).join(&relative).with_extension(extension);
+            if bless {
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, &actual).expect("Could not write golden file");
+            } else if let Some(failure) = compare(&path, &actual) {
+                failures.push(failure);
+            }
+            expected_files.push(path);
+        }
+    }
+
+    // Files left behind by a script or stage that no longer exists
+    let mut golden_files = vec![];
+    find_files(Path::new(GOLDEN_DIR), &mut golden_files);
+    for path in golden_files {
+        if expected_files.contains(&path) {
+            continue;
+        } else if bless {
+            std::fs::remove_file(&path).expect("Could not remove golden file");
+        } else {
+            failures.push(format!("{}: no longer produced by any script", path.display()));
+        }
+    }
+
+    if bless && !failures.is_empty() {
+        // Blessing cannot fix a stage that fails when it should not, or the reverse
+        eprintln!("{}\n", failures.join("\n\n"));
+        std::process::exit(1);
+    } else if bless {
+        println!("golden: blessed {} files", expected_files.len());
+    } else if failures.is_empty() {
+        println!("golden: {} scripts ok", scripts.len());
+    } else {
+        eprintln!("{}\n", failures.join("\n\n"));
+        eprintln!("golden: {} files differ", failures.len());
+        eprintln!("If the changes are intended, run `cargo test --test golden -- --bless`");
+        std::process::exit(1);
+    }
+}
+
+/// Lex, parse, compile and run a script, rendering the output of each stage
+#[allow(clippy::result_large_err)]
+fn run_stages(source: &str) -> Vec<(&'static str, String)> {
+    let mut outputs = vec![];
+
+    let tokens = stage(
+        &mut outputs,
+        "tokens",
+        || Ok(Lexer::new(source).all_tokens()?),
+        |tokens| render_tokens(tokens),
+    );
+    let Some(tokens) = tokens else {
+        return outputs;
+    };
+
+    let ast = stage(
+        &mut outputs,
+        "ast",
+        || Ok(build_ast(Stack::new(tokens), Default::default())?),
+        render_ast,
+    );
+    if ast.is_none() {
+        return outputs;
+    }
+
+    let output = Rc::new(RefCell::new(CapturedOutput::default()));
+    let mut lav = Lavendeux::new();
+    lav.set_stdout(Box::new(output.clone()));
+
+    let program = stage(
+        &mut outputs,
+        "asm",
+        || lav.compile(source),
+        |(_, bytecode)| ASMTranscoder::new(bytecode, None).disassemble_as_string(),
+    );
+    let Some((profile, bytecode)) = program else {
+        return outputs;
+    };
+
+    let value = stage(
+        &mut outputs,
+        "value",
+        || lav.execute(bytecode, Some(profile)),
+        render_value,
+    );
+
+    // Printed output is part of the result, even if the script failed
+    let printed = &output.borrow().printed;
+    if !printed.is_empty() {
+        let (_, text) = outputs.last_mut().unwrap();
+        text.push_str("\n\nprinted:\n");
+        text.push_str(&printed.join("\n"));
+    }
+    drop(value);
+
+    outputs
+}
+
+/// Run one stage, recording its rendered output, or the error or panic that stopped it
+/// Returns the stage's result if later stages can run
+fn stage<T>(
+    outputs: &mut Vec<(&'static str, String)>,
+    extension: &'static str,
+    run: impl FnOnce() -> Result<T, Error>,
+    render: impl FnOnce(&T) -> String,
+) -> Option<T> {
+    // Panics are recorded like errors, so keep the default hook from printing them
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let result = std::panic::catch_unwind(AssertUnwindSafe(run));
+    std::panic::set_hook(hook);
+
+    let (text, result) = match result {
+        Ok(Ok(result)) => (render(&result), Some(result)),
+        Ok(Err(e)) => (format!("error: {e}"), None),
+        Err(panic) => {
+            let message = match panic.downcast_ref::<String>() {
+                Some(message) => message.as_str(),
+                None => panic.downcast_ref::<&str>().copied().unwrap_or("?"),
+            };
+            (format!("panic: {message}"), None)
+        }
+    };
+
+    outputs.push((extension, text));
+    result
+}
+
+/// Compare a stage's output against its golden file, describing the first difference
+fn compare(path: &Path, actual: &str) -> Option<String> {
+    let expected = match std::fs::read_to_string(path) {
+        Ok(expected) => expected.replace("\r\n", "\n"),
+        Err(_) => return Some(format!("{}: missing", path.display())),
+    };
+    if expected == actual {
+        return None;
+    }
+
+    let mut expected_lines = expected.split('\n');
+    let mut actual_lines = actual.split('\n');
+    for line in 1.. {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(e), Some(a)) if e == a => continue,
+            (e, a) => {
+                let describe = |line: Option<&str>| match line {
+                    Some(line) => format!("{line:?}"),
+                    None => "<end of file>".to_string(),
+                };
+                return Some(format!(
+                    "{}:{line}: differs\n  expected: {}\n  found:    {}",
+                    path.display(),
+                    describe(e),
+                    describe(a),
+                ));
+            }
+        }
+    }
+    unreachable!()
+}
+
+fn render_tokens(tokens: &[Token<'_>]) -> String {
+    tokens
+        .iter()
+        .map(|token| {
+            let text = token.slice().replace('\r', "\\r").replace('\n', "\\n");
+            format!("{} {:?} {}", token.line(), token.rule(), text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_ast(ast: &Node<'_>) -> String {
+    struct Shape {
+        depth: usize,
+        lines: Vec<String>,
+    }
+
+    impl<'source> Visitor<'source> for Shape {
+        fn enter(&mut self, node: &Node<'source>) -> bool {
+            let slice = node.token().slice();
+            let mut excerpt = slice.lines().next().unwrap_or_default().trim().to_string();
+            if excerpt.len() > MAX_EXCERPT_LEN || excerpt.len() < slice.trim().len() {
+                excerpt = excerpt.chars().take(MAX_EXCERPT_LEN).collect::<String>() + "...";
+            }
+
+            let indent = "  ".repeat(self.depth);
+            self.lines.push(format!("{indent}{} `{excerpt}`", node.name()));
+            self.depth += 1;
+            true
+        }
+
+        fn exit(&mut self, _: &Node<'source>) {
+            self.depth -= 1;
+        }
+    }
+
+    let mut shape = Shape {
+        depth: 0,
+        lines: vec![],
+    };
+    ast.walk(&mut shape);
+    shape.lines.join("\n")
+}
+
+/// Values are displayed as usual, except that object keys are sorted so the output is stable
+fn render_value(value: &Value) -> String {
+    match value {
+        Value::Primitive(primitive) => primitive.to_string(),
+        _ => render_nested(value),
+    }
+}
+
+/// Values inside a collection, where strings are quoted
+fn render_nested(value: &Value) -> String {
+    match value {
+        Value::Array(values) => {
+            let values = values.iter().map(render_nested).collect::<Vec<_>>();
+            format!("[{}]", values.join(", "))
+        }
+        Value::Object(object) => {
+            let mut entries = object.iter().collect::<Vec<_>>();
+            entries.sort_by_key(|(key, _)| *key);
+            let entries = entries
+                .into_iter()
+                .map(|(key, value)| format!("{key:?}: {}", render_nested(value)))
+                .collect::<Vec<_>>();
+            format!("{{{}}}", entries.join(", "))
+        }
+        value => format!("{value:?}"),
+    }
+}
+
+fn find_scripts(dir: &Path, scripts: &mut Vec<PathBuf>) {
+    let mut files = vec![];
+    find_files(dir, &mut files);
+    scripts.extend(
+        files
+            .into_iter()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "lav")),
+    );
+}
+
+fn find_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+error: ./example_scripts/stdlib/array.lav:175
+|         if __len(chunks[]
+|                         ^
+= Syntax error: Unexpected Symbol(`]`), expected one of:
+= Symbol(`(, {, [`), Keyword(`if`, `for`, `switch`, `return`, `continue`, `break`, `del`), Operator(`-`, `~`, `!`), `identifier`, `literal value`, `linebreak`
+= In include:
+Line 20
+| include('./example_scripts/stdlib/array.lav')
+| ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
\ No newline at end of file
@@ -0,0 +1,559 @@
+  POOL 0000000D
+  MKFN FN_arbitrary_arbitrary
+  FSIG
+  WRFN
+  PSHC 1
+  NEG
+  SCI
+  REF VAR_kangaroo_kangaroo
+  WREF
+  POP
+  REF VAR_kangaroo_kangaroo
+  PSHC 0
+  LT
+  JMPF JUMP_cabbage_umbrella
+  REF VAR_kangaroo_kangaroo
+  NEG
+  JMP JUMP_jellybean_grapefruit
+JUMP_cabbage_umbrella:
+  REF VAR_kangaroo_kangaroo
+JUMP_jellybean_grapefruit:
+  RREF
+  SCO
+  PSHC 1
+  CALL #6FB70545F51DC00E 2
+  PSHC 1
+  SCI
+  REF VAR_rhubarb_lumberjack
+  WREF
+  POP
+  REF VAR_rhubarb_lumberjack
+  PSHC 0
+  LT
+  JMPF JUMP_quarantine_hedgehog
+  REF VAR_rhubarb_lumberjack
+  NEG
+  JMP JUMP_dolphin_jellybean
+JUMP_quarantine_hedgehog:
+  REF VAR_rhubarb_lumberjack
+JUMP_dolphin_jellybean:
+  RREF
+  SCO
+  PSHC 1
+  CALL #6FB70545F51DC00E 2
+  MKFN FN_grapefruit_bananas
+  FSIG
+  WRFN
+  PSHC 1.1
+  CALL #B9F979CF0EE3E99B 1
+  PSHC 2
+  CALL #6FB70545F51DC00E 2
+  PSHC 1.9
+  CALL #B9F979CF0EE3E99B 1
+  PSHC 2
+  CALL #6FB70545F51DC00E 2
+  PSHC 1.1
+  NEG
+  CALL #B9F979CF0EE3E99B 1
+  PSHC 1
+  NEG
+  CALL #6FB70545F51DC00E 2
+  PSHC 1.9
+  NEG
+  CALL #B9F979CF0EE3E99B 1
+  PSHC 1
+  NEG
+  CALL #6FB70545F51DC00E 2
+  MKFN FN_octopus_cabbage
+  FSIG
+  WRFN
+  PSHC 1.1
+  CALL #7C662F3B0CDDA44D 1
+  PSHC 1
+  CALL #6FB70545F51DC00E 2
+  PSHC 1.9
+  CALL #7C662F3B0CDDA44D 1
+  PSHC 1
+  CALL #6FB70545F51DC00E 2
+  PSHC 1.1
+  NEG
+  CALL #7C662F3B0CDDA44D 1
+  PSHC 2
+  NEG
+  CALL #6FB70545F51DC00E 2
+  PSHC 1.9
+  NEG
+  CALL #7C662F3B0CDDA44D 1
+  PSHC 2
+  NEG
+  CALL #6FB70545F51DC00E 2
+  MKFN FN_bananas_dolphin
+  FSIG
+  WRFN
+  PSHC 3
+  PSHC 2
+  PSHC 1
+  MKAR 00000003
+  CALL #83589DC85DCDC62E 1
+  PSHC 3
+  CALL #6FB70545F51DC00E 2
+  PSHC 1
+  PSHC 2
+  PSHC 3
+  MKAR 00000003
+  CALL #83589DC85DCDC62E 1
+  PSHC 3
+  CALL #6FB70545F51DC00E 2
+  MKFN FN_hedgehog_pointbreak
+  FSIG
+  WRFN
+  PSHC 3
+  PSHC 2
+  PSHC 1
+  MKAR 00000003
+  CALL #2E238FAC4C74088D 1
+  PSHC 1
+  CALL #6FB70545F51DC00E 2
+  PSHC 1
+  PSHC 2
+  PSHC 3
+  MKAR 00000003
+  CALL #2E238FAC4C74088D 1
+  PSHC 1
+  CALL #6FB70545F51DC00E 2
+  MKFN FN_penguin_alabaster
+  FSIG
+  WRFN
+  PSHC 1.123
+  PSHC 2
+  CALL #A9581637E97BA5E1 2
+  PSHC 1.12
+  CALL #6FB70545F51DC00E 2
+  PSHC 1.125
+  PSHC 2
+  CALL #A9581637E97BA5E1 2
+  PSHC 1.13
+  CALL #6FB70545F51DC00E 2
+  PSHC 1.123
+  PSHC 0
+  CALL #A9581637E97BA5E1 2
+  PSHC 1.0
+  CALL #6FB70545F51DC00E 2
+  PSHC 1.5
+  PSHC 0
+  CALL #A9581637E97BA5E1 2
+  PSHC 2.0
+  CALL #6FB70545F51DC00E 2
+; fn arbitrary_arbitrary
+  POOL 00000001
+; n < 0 ? -n : n
+; n < 0
+; n
+  REF VAR_pointbreak_marmalade
+; 0
+  PSHC 0
+  LT
+  JMPF JUMP_arbitrary_arbitrary
+; -n
+; n
+  REF VAR_pointbreak_marmalade
+  NEG
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+; n
+  REF VAR_pointbreak_marmalade
+JUMP_grapefruit_bananas:
+  RET
+; fn grapefruit_bananas
+  POOL 00000002
+; n % 1 == 0 ? n : n < 0 ? n - n % 1 : n - n % 1 + 1) as int
+; n % 1 == 0 ? n : n < 0 ? n - n % 1 : n - n % 1 + 1
+; n % 1 == 0 ? n : n < 0
+; n % 1 == 0
+; n % 1
+; n
+  REF VAR_pointbreak_marmalade
+; 1
+  PSHC 1
+  REM
+; 0
+  PSHC 0
+  EQ
+  JMPF JUMP_arbitrary_arbitrary
+; n
+  REF VAR_pointbreak_marmalade
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+; n < 0
+; n
+  REF VAR_pointbreak_marmalade
+; 0
+  PSHC 0
+  LT
+JUMP_grapefruit_bananas:
+  JMPF JUMP_octopus_cabbage
+; n - n % 1
+; n
+  REF VAR_pointbreak_marmalade
+; n % 1
+; n
+  REF VAR_pointbreak_marmalade
+; 1
+  PSHC 1
+  REM
+  SUB
+  JMP JUMP_bananas_dolphin
+JUMP_octopus_cabbage:
+; n - n % 1 + 1
+; n - n % 1
+; n
+  REF VAR_pointbreak_marmalade
+; n % 1
+; n
+  REF VAR_pointbreak_marmalade
+; 1
+  PSHC 1
+  REM
+  SUB
+; 1
+  PADD 1
+JUMP_bananas_dolphin:
+  CAST Integer
+  RET
+; fn octopus_cabbage
+  POOL 00000002
+; n % 1 == 0 ? n : n < 0 ? n - n % 1 - 1 : n - n % 1) as int
+; n % 1 == 0 ? n : n < 0 ? n - n % 1 - 1 : n - n % 1
+; n % 1 == 0 ? n : n < 0
+; n % 1 == 0
+; n % 1
+; n
+  REF VAR_pointbreak_marmalade
+; 1
+  PSHC 1
+  REM
+; 0
+  PSHC 0
+  EQ
+  JMPF JUMP_arbitrary_arbitrary
+; n
+  REF VAR_pointbreak_marmalade
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+; n < 0
+; n
+  REF VAR_pointbreak_marmalade
+; 0
+  PSHC 0
+  LT
+JUMP_grapefruit_bananas:
+  JMPF JUMP_octopus_cabbage
+; n - n % 1 - 1
+; n - n % 1
+; n
+  REF VAR_pointbreak_marmalade
+; n % 1
+; n
+  REF VAR_pointbreak_marmalade
+; 1
+  PSHC 1
+  REM
+  SUB
+; 1
+  PSUB 1
+  JMP JUMP_bananas_dolphin
+JUMP_octopus_cabbage:
+; n - n % 1
+; n
+  REF VAR_pointbreak_marmalade
+; n % 1
+; n
+  REF VAR_pointbreak_marmalade
+; 1
+  PSHC 1
+  REM
+  SUB
+JUMP_bananas_dolphin:
+  CAST Integer
+  RET
+; fn bananas_dolphin
+  POOL 00000001
+; {
+;     v = a[0]
+;     for i in a do {
+;         v = if i > v then {
+;             i
+;         } else {
+;             v
+;         }
+;     }
+;     v
+; }
+; v = a[0]
+; a[0]
+; a
+  REF VAR_lumberjack_noodle
+; 0
+  PSHC 0
+  IDEX
+; v
+  REF VAR_salamander_octopus
+  WREF
+  POP
+; for i in a do {
+;         v = if i > v then {
+;             i
+;         } else {
+;             v
+;         }
+;     }
+  SCI
+  MKAR 00000000
+; a
+  REF VAR_lumberjack_noodle
+  SWP
+JUMP_hedgehog_pointbreak:
+  SWP
+  DJNE JUMP_arbitrary_arbitrary
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+  NEXT
+  REF VAR_alabaster_penguin
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;         v = if i > v then {
+;             i
+;         } else {
+;             v
+;         }
+;     }
+; v = if i > v then {
+;             i
+;         } else {
+;             v
+;         }
+; if i > v then {
+;             i
+;         } else {
+;             v
+;         }
+; i > v
+; i
+  REF VAR_alabaster_penguin
+; v
+  REF VAR_salamander_octopus
+  GT
+  JMPF JUMP_octopus_cabbage
+; {
+;             i
+;         }
+; i
+  REF VAR_alabaster_penguin
+  RREF
+  JMP JUMP_bananas_dolphin
+JUMP_octopus_cabbage:
+; {
+;             v
+;         }
+; v
+  REF VAR_salamander_octopus
+  RREF
+JUMP_bananas_dolphin:
+; v
+  REF VAR_salamander_octopus
+  WREF
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_hedgehog_pointbreak
+  POP
+  JMP JUMP_penguin_alabaster
+  PSAR
+JUMP_penguin_alabaster:
+  SWP
+JUMP_grapefruit_bananas:
+  POP
+  SCO
+  POP
+; v
+  REF VAR_salamander_octopus
+  RREF
+  RET
+; fn hedgehog_pointbreak
+  POOL 00000001
+; {
+;     v = a[0]
+;     for i in a do {
+;         v = if i < v then {
+;             i
+;         } else {
+;             v
+;         }
+;     }
+;     v
+; }
+; v = a[0]
+; a[0]
+; a
+  REF VAR_lumberjack_noodle
+; 0
+  PSHC 0
+  IDEX
+; v
+  REF VAR_salamander_octopus
+  WREF
+  POP
+; for i in a do {
+;         v = if i < v then {
+;             i
+;         } else {
+;             v
+;         }
+;     }
+  SCI
+  MKAR 00000000
+; a
+  REF VAR_lumberjack_noodle
+  SWP
+JUMP_hedgehog_pointbreak:
+  SWP
+  DJNE JUMP_arbitrary_arbitrary
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+  NEXT
+  REF VAR_alabaster_penguin
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;         v = if i < v then {
+;             i
+;         } else {
+;             v
+;         }
+;     }
+; v = if i < v then {
+;             i
+;         } else {
+;             v
+;         }
+; if i < v then {
+;             i
+;         } else {
+;             v
+;         }
+; i < v
+; i
+  REF VAR_alabaster_penguin
+; v
+  REF VAR_salamander_octopus
+  LT
+  JMPF JUMP_octopus_cabbage
+; {
+;             i
+;         }
+; i
+  REF VAR_alabaster_penguin
+  RREF
+  JMP JUMP_bananas_dolphin
+JUMP_octopus_cabbage:
+; {
+;             v
+;         }
+; v
+  REF VAR_salamander_octopus
+  RREF
+JUMP_bananas_dolphin:
+; v
+  REF VAR_salamander_octopus
+  WREF
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_hedgehog_pointbreak
+  POP
+  JMP JUMP_penguin_alabaster
+  PSAR
+JUMP_penguin_alabaster:
+  SWP
+JUMP_grapefruit_bananas:
+  POP
+  SCO
+  POP
+; v
+  REF VAR_salamander_octopus
+  RREF
+  RET
+; fn penguin_alabaster
+  POOL 00000003
+; {
+;     m = 10 ** p
+;     _n = n * m
+;     _n = _n % 1 >= 0.5 ? __ceil(_n) : __floor(_n)
+;     (_n as float) / m
+; }
+; m = 10 ** p
+; 10 ** p
+; 10
+  PSHC 10
+; p
+  REF VAR_marmalade_quarantine
+  POW
+; m
+  REF VAR_tangerine_rhubarb
+  WREF
+  POP
+; _n = n * m
+; n * m
+; n
+  REF VAR_pointbreak_marmalade
+; m
+  REF VAR_tangerine_rhubarb
+  MUL
+; _n
+  REF VAR_umbrella_salamander
+  WREF
+  POP
+; _n = _n % 1 >= 0.5 ? __ceil(_n) : __floor(_n)
+;     (_n as float) / m
+; _n % 1 >= 0.5 ? __ceil(_n) : __floor(_n)
+;     (_n as float) / m
+; _n % 1 >= 0.5
+; _n % 1
+; _n
+  REF VAR_umbrella_salamander
+; 1
+  PSHC 1
+  REM
+; 0.5
+  PSHC 0.5
+  GE
+  JMPF JUMP_arbitrary_arbitrary
+; __ceil(_n)
+; _n
+  REF VAR_umbrella_salamander
+  CALL #B9F979CF0EE3E99B 1
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+; __floor(_n)
+;     (_n as float) / m
+; __floor(_n)
+;     (_n as float)
+; _n as float
+; _n
+  REF VAR_umbrella_salamander
+  CAST Decimal
+  CALL #77873D30FCAD73C1 1
+; m
+  REF VAR_tangerine_rhubarb
+  DIV
+JUMP_grapefruit_bananas:
+; _n
+  REF VAR_umbrella_salamander
+  WREF
+  RREF
+  RET
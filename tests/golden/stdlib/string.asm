@@ -0,0 +1,792 @@
+  POOL 0000000B
+  MKFN FN_arbitrary_arbitrary
+  FSIG
+  WRFN
+  PSHC `World!`
+  PSHC `, `
+  PSHC `Hello`
+  MKAR 00000003
+  CALL #45A8A94D4BBFE756 1
+  PSHC `Hello, World!`
+  CALL #6FB70545F51DC00E 2
+  MKFN FN_grapefruit_bananas
+  FSIG
+  WRFN
+  PSHC `Hello, World!`
+  CALL #22B3AB747A1DCC8 1
+  PSHC `hello, world!`
+  CALL #6FB70545F51DC00E 2
+  PSHC `HELLO, WORLD!`
+  CALL #22B3AB747A1DCC8 1
+  PSHC `hello, world!`
+  CALL #6FB70545F51DC00E 2
+  MKFN FN_octopus_cabbage
+  FSIG
+  WRFN
+  PSHC `a`
+  PSHC 5
+  CALL #D5D04924E14A9E3 2
+  PSHC `aaaaa`
+  CALL #6FB70545F51DC00E 2
+  MKFN FN_bananas_dolphin
+  FSIG
+  WRFN
+  PSHC `{"a": 1, "b": [1, 2, 3]}`
+  CALL #7DDFB1D06E392499 1
+  PSHC `{
+  "a": 1,
+  "b": [
+    1,
+    2,
+    3
+  ]
+}`
+  CALL #6FB70545F51DC00E 2
+  MKFN FN_hedgehog_pointbreak
+  FSIG
+  WRFN
+  PSHC `Hello, World!`
+  CALL #6FB4F81647DA97EC 1
+  PSHC `HELLO, WORLD!`
+  CALL #6FB70545F51DC00E 2
+  PSHC `hello, world!`
+  CALL #6FB4F81647DA97EC 1
+  PSHC `HELLO, WORLD!`
+  CALL #6FB70545F51DC00E 2
+; fn arbitrary_arbitrary
+  POOL 00000001
+; {
+;     out = ''
+;     for s in a do {
+;         out += s
+;     }
+;     out
+; }
+; out = ''
+; ''
+  PSHC ``
+; out
+  REF VAR_penguin_alabaster
+  WREF
+  POP
+; for s in a do {
+;         out += s
+;     }
+  SCI
+  MKAR 00000000
+; a
+  REF VAR_cabbage_umbrella
+  SWP
+JUMP_octopus_cabbage:
+  SWP
+  DJNE JUMP_arbitrary_arbitrary
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+  NEXT
+  REF VAR_jellybean_grapefruit
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;         out += s
+;     }
+; out += s
+; out
+  REF VAR_penguin_alabaster
+  DUP
+; s
+  REF VAR_jellybean_grapefruit
+  LCST
+  ADD
+  SWP
+  WREF
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_octopus_cabbage
+  POP
+  JMP JUMP_bananas_dolphin
+  PSAR
+JUMP_bananas_dolphin:
+  SWP
+JUMP_grapefruit_bananas:
+  POP
+  SCO
+  POP
+; out
+  REF VAR_penguin_alabaster
+  RREF
+  RET
+; fn grapefruit_bananas
+  POOL 00000002
+; {
+;     __concat(for c in s.chars() do {
+;         if ('A'..'Z') contains c then {
+;             chr(ord(c) + 32)
+;         } else {
+;             c
+;         }
+;     })
+; }
+; __concat(for c in s.chars() do {
+;         if ('A'..'Z') contains c then {
+;             chr(ord(c) + 32)
+;         } else {
+;             c
+;         }
+;     })
+; for c in s.chars() do {
+;         if ('A'..'Z') contains c then {
+;             chr(ord(c) + 32)
+;         } else {
+;             c
+;         }
+;     }
+  SCI
+  MKAR 00000000
+; s.chars()
+; s
+  REF VAR_jellybean_grapefruit
+  CALM chars 1
+  SWP
+JUMP_hedgehog_pointbreak:
+  SWP
+  DJNE JUMP_arbitrary_arbitrary
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+  NEXT
+  REF VAR_quarantine_hedgehog
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;         if ('A'..'Z') contains c then {
+;             chr(ord(c) + 32)
+;         } else {
+;             c
+;         }
+;     }
+; if ('A'..'Z') contains c then {
+;             chr(ord(c) + 32)
+;         } else {
+;             c
+;         }
+; 'A'..'Z') contains c
+; 'A'..'Z'
+; 'A'
+  PSHC `A`
+; 'Z'
+  PSHC `Z`
+  MKRG
+; c
+  REF VAR_quarantine_hedgehog
+  CNTN
+  JMPF JUMP_octopus_cabbage
+; {
+;             chr(ord(c) + 32)
+;         }
+; chr(ord(c) + 32)
+; ord(c) + 32
+; ord(c)
+; c
+  REF VAR_quarantine_hedgehog
+  CALL #AD12338F1FEFB50E 1
+; 32
+  PADD 32
+  CALL #CD9305626A154461 1
+  RREF
+  JMP JUMP_bananas_dolphin
+JUMP_octopus_cabbage:
+; {
+;             c
+;         }
+; c
+  REF VAR_quarantine_hedgehog
+  RREF
+JUMP_bananas_dolphin:
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_hedgehog_pointbreak
+  POP
+  JMP JUMP_penguin_alabaster
+  PSAR
+JUMP_penguin_alabaster:
+  SWP
+JUMP_grapefruit_bananas:
+  POP
+  SCO
+  CALL #45A8A94D4BBFE756 1
+  RREF
+  RET
+; fn octopus_cabbage
+  POOL 00000002
+; {
+;     if n == 0 then {
+;         ''
+;     } else {
+;         __concat(for _ in 0..(n-1) {
+;             s
+;         })
+;     }
+; }
+; if n == 0 then {
+;         ''
+;     } else {
+;         __concat(for _ in 0..(n-1) {
+;             s
+;         })
+;     }
+; n == 0
+; n
+  REF VAR_dolphin_jellybean
+; 0
+  PSHC 0
+  EQ
+  JMPF JUMP_arbitrary_arbitrary
+; {
+;         ''
+;     }
+; ''
+  PSHC ``
+  RREF
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+; {
+;         __concat(for _ in 0..(n-1) {
+;             s
+;         })
+;     }
+; __concat(for _ in 0..(n-1) {
+;             s
+;         })
+; for _ in 0..(n-1) {
+;             s
+;         }
+  SCI
+  MKAR 00000000
+; 0..(n-1
+; 0
+  PSHC 0
+; n-1
+; n
+  REF VAR_dolphin_jellybean
+; 1
+  PSUB 1
+  MKRG
+  SWP
+JUMP_hedgehog_pointbreak:
+  SWP
+  DJNE JUMP_octopus_cabbage
+  JMP JUMP_bananas_dolphin
+JUMP_octopus_cabbage:
+  NEXT
+  REF VAR_kangaroo_kangaroo
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;             s
+;         }
+; s
+  REF VAR_jellybean_grapefruit
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_hedgehog_pointbreak
+  POP
+  JMP JUMP_penguin_alabaster
+  PSAR
+JUMP_penguin_alabaster:
+  SWP
+JUMP_bananas_dolphin:
+  POP
+  SCO
+  CALL #45A8A94D4BBFE756 1
+  RREF
+JUMP_grapefruit_bananas:
+  RREF
+  RET
+; fn bananas_dolphin
+  POOL 0000000D
+; {
+;     indent = 0
+;     pretty = ""
+;     for c in s.chars() do {
+;         if c == '{' || c == '[' then {
+;             indent += 1
+;             pretty += c + "\n" + __repeat('  ', indent)
+;         } else if c == '}' || c == ']' then {
+;             indent -= 1
+;             pretty += "\n" + __repeat('  ', indent) + c
+;         } else if c == ',' then {
+;             pretty += c + "\n" + __repeat('  ', indent)
+;         } else if c == ' ' || c == '\t' then {
+;             // ignore whitespace
+;             continue
+;         } else if c == ':' then {
+;             pretty += c + " "
+;         } else {
+;             pretty += c
+;         }
+;     }
+; 
+;     pretty
+; }
+; indent = 0
+; 0
+  PSHC 0
+; indent
+  REF VAR_rhubarb_lumberjack
+  WREF
+  POP
+; pretty = ""
+; ""
+  PSHC ``
+; pretty
+  REF VAR_pointbreak_marmalade
+  WREF
+  POP
+; for c in s.chars() do {
+;         if c == '{' || c == '[' then {
+;             indent += 1
+;             pretty += c + "\n" + __repeat('  ', indent)
+;         } else if c == '}' || c == ']' then {
+;             indent -= 1
+;             pretty += "\n" + __repeat('  ', indent) + c
+;         } else if c == ',' then {
+;             pretty += c + "\n" + __repeat('  ', indent)
+;         } else if c == ' ' || c == '\t' then {
+;             // ignore whitespace
+;             continue
+;         } else if c == ':' then {
+;             pretty += c + " "
+;         } else {
+;             pretty += c
+;         }
+;     }
+  SCI
+  MKAR 00000000
+; s.chars()
+; s
+  REF VAR_jellybean_grapefruit
+  CALM chars 1
+  SWP
+JUMP_jellybean_grapefruit:
+  SWP
+  DJNE JUMP_arbitrary_arbitrary
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+  NEXT
+  REF VAR_quarantine_hedgehog
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;         if c == '{' || c == '[' then {
+;             indent += 1
+;             pretty += c + "\n" + __repeat('  ', indent)
+;         } else if c == '}' || c == ']' then {
+;             indent -= 1
+;             pretty += "\n" + __repeat('  ', indent) + c
+;         } else if c == ',' then {
+;             pretty += c + "\n" + __repeat('  ', indent)
+;         } else if c == ' ' || c == '\t' then {
+;             // ignore whitespace
+;             continue
+;         } else if c == ':' then {
+;             pretty += c + " "
+;         } else {
+;             pretty += c
+;         }
+;     }
+; if c == '{' || c == '[' then {
+;             indent += 1
+;             pretty += c + "\n" + __repeat('  ', indent)
+;         } else if c == '}' || c == ']' then {
+;             indent -= 1
+;             pretty += "\n" + __repeat('  ', indent) + c
+;         } else if c == ',' then {
+;             pretty += c + "\n" + __repeat('  ', indent)
+;         } else if c == ' ' || c == '\t' then {
+;             // ignore whitespace
+;             continue
+;         } else if c == ':' then {
+;             pretty += c + " "
+;         } else {
+;             pretty += c
+;         }
+; c == '{' || c == '['
+; c == '{'
+; c
+  REF VAR_quarantine_hedgehog
+; '{'
+  PSHC `{`
+  EQ
+; c == '['
+; c
+  REF VAR_quarantine_hedgehog
+; '['
+  PSHC `[`
+  EQ
+  LOR
+  JMPF JUMP_octopus_cabbage
+; {
+;             indent += 1
+;             pretty += c + "\n" + __repeat('  ', indent)
+;         }
+; indent += 1
+; indent
+  REF VAR_rhubarb_lumberjack
+  DUP
+; 1
+  PSHC 1
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; pretty += c + "\n" + __repeat('  ', indent)
+; pretty
+  REF VAR_pointbreak_marmalade
+  DUP
+; c + "\n" + __repeat('  ', indent)
+; c + "\n"
+; c
+  REF VAR_quarantine_hedgehog
+; "\n"
+  PADD `
+`
+; __repeat('  ', indent)
+; '  '
+  PSHC `  `
+; indent
+  REF VAR_rhubarb_lumberjack
+  CALL #D5D04924E14A9E3 2
+  ADD
+  LCST
+  ADD
+  SWP
+  WREF
+  RREF
+  JMP JUMP_bananas_dolphin
+JUMP_octopus_cabbage:
+; if c == '}' || c == ']' then {
+;             indent -= 1
+;             pretty += "\n" + __repeat('  ', indent) + c
+;         } else if c == ',' then {
+;             pretty += c + "\n" + __repeat('  ', indent)
+;         } else if c == ' ' || c == '\t' then {
+;             // ignore whitespace
+;             continue
+;         } else if c == ':' then {
+;             pretty += c + " "
+;         } else {
+;             pretty += c
+;         }
+; c == '}' || c == ']'
+; c == '}'
+; c
+  REF VAR_quarantine_hedgehog
+; '}'
+  PSHC `}`
+  EQ
+; c == ']'
+; c
+  REF VAR_quarantine_hedgehog
+; ']'
+  PSHC `]`
+  EQ
+  LOR
+  JMPF JUMP_hedgehog_pointbreak
+; {
+;             indent -= 1
+;             pretty += "\n" + __repeat('  ', indent) + c
+;         }
+; indent -= 1
+; indent
+  REF VAR_rhubarb_lumberjack
+  DUP
+; 1
+  PSHC 1
+  LCST
+  SUB
+  SWP
+  WREF
+  POP
+; pretty += "\n" + __repeat('  ', indent) + c
+; pretty
+  REF VAR_pointbreak_marmalade
+  DUP
+; "\n" + __repeat('  ', indent) + c
+; "\n" + __repeat('  ', indent)
+; "\n"
+  PSHC `
+`
+; __repeat('  ', indent)
+; '  '
+  PSHC `  `
+; indent
+  REF VAR_rhubarb_lumberjack
+  CALL #D5D04924E14A9E3 2
+  ADD
+; c
+  REF VAR_quarantine_hedgehog
+  ADD
+  LCST
+  ADD
+  SWP
+  WREF
+  RREF
+  JMP JUMP_bananas_dolphin
+JUMP_hedgehog_pointbreak:
+; if c == ',' then {
+;             pretty += c + "\n" + __repeat('  ', indent)
+;         } else if c == ' ' || c == '\t' then {
+;             // ignore whitespace
+;             continue
+;         } else if c == ':' then {
+;             pretty += c + " "
+;         } else {
+;             pretty += c
+;         }
+; c == ','
+; c
+  REF VAR_quarantine_hedgehog
+; ','
+  PSHC `,`
+  EQ
+  JMPF JUMP_penguin_alabaster
+; {
+;             pretty += c + "\n" + __repeat('  ', indent)
+;         }
+; pretty += c + "\n" + __repeat('  ', indent)
+; pretty
+  REF VAR_pointbreak_marmalade
+  DUP
+; c + "\n" + __repeat('  ', indent)
+; c + "\n"
+; c
+  REF VAR_quarantine_hedgehog
+; "\n"
+  PADD `
+`
+; __repeat('  ', indent)
+; '  '
+  PSHC `  `
+; indent
+  REF VAR_rhubarb_lumberjack
+  CALL #D5D04924E14A9E3 2
+  ADD
+  LCST
+  ADD
+  SWP
+  WREF
+  RREF
+  JMP JUMP_bananas_dolphin
+JUMP_penguin_alabaster:
+; if c == ' ' || c == '\t' then {
+;             // ignore whitespace
+;             continue
+;         } else if c == ':' then {
+;             pretty += c + " "
+;         } else {
+;             pretty += c
+;         }
+; c == ' ' || c == '\t'
+; c == ' '
+; c
+  REF VAR_quarantine_hedgehog
+; ' '
+  PSHC ` `
+  EQ
+; c == '\t'
+; c
+  REF VAR_quarantine_hedgehog
+; '\t'
+  PSHC `	`
+  EQ
+  LOR
+  JMPF JUMP_cabbage_umbrella
+; {
+;             // ignore whitespace
+;             continue
+;         }
+; continue
+  PUSH nil
+  SCO
+  POP
+  JMP JUMP_jellybean_grapefruit
+  RREF
+  JMP JUMP_bananas_dolphin
+JUMP_cabbage_umbrella:
+; if c == ':' then {
+;             pretty += c + " "
+;         } else {
+;             pretty += c
+;         }
+; c == ':'
+; c
+  REF VAR_quarantine_hedgehog
+; ':'
+  PSHC `:`
+  EQ
+  JMPF JUMP_quarantine_hedgehog
+; {
+;             pretty += c + " "
+;         }
+; pretty += c + " "
+; pretty
+  REF VAR_pointbreak_marmalade
+  DUP
+; c + " "
+; c
+  REF VAR_quarantine_hedgehog
+; " "
+  PADD ` `
+  LCST
+  ADD
+  SWP
+  WREF
+  RREF
+  JMP JUMP_bananas_dolphin
+JUMP_quarantine_hedgehog:
+; {
+;             pretty += c
+;         }
+; pretty += c
+; pretty
+  REF VAR_pointbreak_marmalade
+  DUP
+; c
+  REF VAR_quarantine_hedgehog
+  LCST
+  ADD
+  SWP
+  WREF
+  RREF
+JUMP_bananas_dolphin:
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_jellybean_grapefruit
+  POP
+  JMP JUMP_dolphin_jellybean
+  PSAR
+JUMP_dolphin_jellybean:
+  SWP
+JUMP_grapefruit_bananas:
+  POP
+  SCO
+  POP
+; pretty
+  REF VAR_pointbreak_marmalade
+  RREF
+  RET
+; fn hedgehog_pointbreak
+  POOL 00000002
+; {
+;     __concat(for c in s.chars() do {
+;         if ('a'..'z') contains c then {
+;             chr(ord(c) - 32)
+;         } else {
+;             c
+;         }
+;     })
+; }
+; __concat(for c in s.chars() do {
+;         if ('a'..'z') contains c then {
+;             chr(ord(c) - 32)
+;         } else {
+;             c
+;         }
+;     })
+; for c in s.chars() do {
+;         if ('a'..'z') contains c then {
+;             chr(ord(c) - 32)
+;         } else {
+;             c
+;         }
+;     }
+  SCI
+  MKAR 00000000
+; s.chars()
+; s
+  REF VAR_jellybean_grapefruit
+  CALM chars 1
+  SWP
+JUMP_hedgehog_pointbreak:
+  SWP
+  DJNE JUMP_arbitrary_arbitrary
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+  NEXT
+  REF VAR_quarantine_hedgehog
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;         if ('a'..'z') contains c then {
+;             chr(ord(c) - 32)
+;         } else {
+;             c
+;         }
+;     }
+; if ('a'..'z') contains c then {
+;             chr(ord(c) - 32)
+;         } else {
+;             c
+;         }
+; 'a'..'z') contains c
+; 'a'..'z'
+; 'a'
+  PSHC `a`
+; 'z'
+  PSHC `z`
+  MKRG
+; c
+  REF VAR_quarantine_hedgehog
+  CNTN
+  JMPF JUMP_octopus_cabbage
+; {
+;             chr(ord(c) - 32)
+;         }
+; chr(ord(c) - 32)
+; ord(c) - 32
+; ord(c)
+; c
+  REF VAR_quarantine_hedgehog
+  CALL #AD12338F1FEFB50E 1
+; 32
+  PSUB 32
+  CALL #CD9305626A154461 1
+  RREF
+  JMP JUMP_bananas_dolphin
+JUMP_octopus_cabbage:
+; {
+;             c
+;         }
+; c
+  REF VAR_quarantine_hedgehog
+  RREF
+JUMP_bananas_dolphin:
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_hedgehog_pointbreak
+  POP
+  JMP JUMP_penguin_alabaster
+  PSAR
+JUMP_penguin_alabaster:
+  SWP
+JUMP_grapefruit_bananas:
+  POP
+  SCO
+  CALL #45A8A94D4BBFE756 1
+  RREF
+  RET
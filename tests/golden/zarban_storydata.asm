@@ -0,0 +1,3470 @@
+  POOL 000001E2
+  PUSH false
+  PSHC `hidden`
+  PSHC 2
+  PSHC `value`
+  PSHC 3
+  PSHC `maximum`
+  MKOB 00000003
+  PSHC `stamina`
+  PUSH true
+  PSHC `hidden`
+  PSHC 0
+  PSHC `value`
+  PSHC 2
+  PSHC `maximum`
+  MKOB 00000003
+  PSHC `alcoholism`
+  MKOB 00000002
+  PSHC `status`
+  PSHC `Vampire hunter's sword`
+  PSHC `description`
+  PUSH false
+  PSHC `equipped`
+  MKAR 00000000
+  PSHC `effects`
+  MKOB 00000003
+  PSHC `hunter_sword`
+  PSHC `Vampire hunter's armour`
+  PSHC `description`
+  PUSH false
+  PSHC `equipped`
+  MKAR 00000000
+  PSHC `effects`
+  MKOB 00000003
+  PSHC `hunter_armor`
+  PSHC `Rusty shortsword`
+  PSHC `description`
+  PUSH false
+  PSHC `equipped`
+  MKAR 00000000
+  PSHC `effects`
+  MKOB 00000003
+  PSHC `rusty_sword`
+  PSHC `Soldier's leather cuirass`
+  PSHC `description`
+  PUSH false
+  PSHC `equipped`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `add`
+  PSHC `operation`
+  PSHC 2
+  PSHC `value`
+  MKOB 00000004
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `add_max`
+  PSHC `operation`
+  PSHC 2
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000002
+  PSHC `effects`
+  MKOB 00000003
+  PSHC `old_armor`
+  PSHC `Moonsbane`
+  PSHC `description`
+  PUSH false
+  PSHC `equipped`
+  MKAR 00000000
+  PSHC `effects`
+  MKOB 00000003
+  PSHC `magic_sword`
+  PSHC `Edwin Rothsten's armour`
+  PSHC `description`
+  PUSH false
+  PSHC `equipped`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `add`
+  PSHC `operation`
+  PSHC 5
+  PSHC `value`
+  MKOB 00000004
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `add_max`
+  PSHC `operation`
+  PSHC 5
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000002
+  PSHC `effects`
+  MKOB 00000003
+  PSHC `ancient_armor`
+  PSHC `Key to the vampire hunter's tomb`
+  PSHC `description`
+  PUSH false
+  PSHC `equipped`
+  MKAR 00000000
+  PSHC `effects`
+  MKOB 00000003
+  PSHC `hunter_tomb_key`
+  PSHC `Flower's for Dave's family grave`
+  PSHC `description`
+  PUSH false
+  PSHC `equipped`
+  MKAR 00000000
+  PSHC `effects`
+  MKOB 00000003
+  PSHC `dave_flowers`
+  MKOB 00000008
+  PSHC `inventory`
+  PSHC `Spoke to Dave in the tavern, making him go back to his home`
+  PSHC `description`
+  MKOB 00000001
+  PSHC `made_dave_go_home`
+  PSHC `Was rude to Dave, who will no longer help you`
+  PSHC `description`
+  MKOB 00000001
+  PSHC `made_dave_sad`
+  PSHC `Learnt about the church a day's walk down the road from the village`
+  PSHC `description`
+  MKOB 00000001
+  PSHC `learnt_about_priest`
+  PSHC `Accepted bread from the baker`
+  PSHC `description`
+  MKOB 00000001
+  PSHC `accepted_bread`
+  PSHC `Snooped on the teacher's shack`
+  PSHC `description`
+  MKOB 00000001
+  PSHC `snooped_on_teacher`
+  PSHC `Teacher found out about your snooping`
+  PSHC `description`
+  MKOB 00000001
+  PSHC `insulted_teacher`
+  PSHC `Impressed the priest with your kindness`
+  PSHC `description`
+  MKOB 00000001
+  PSHC `impressed_gaylen`
+  PSHC `Learnt about the famous hunter (and his sword)`
+  PSHC `description`
+  MKOB 00000001
+  PSHC `learnt_about_hunter`
+  PSHC `Injured Zarban's legs`
+  PSHC `description`
+  MKOB 00000001
+  PSHC `injured_zarban`
+  MKOB 00000009
+  PSHC `choices`
+  PSHC `Chapter 1: The Grotto`
+  PSHC `title`
+  PSHC `The foul stench of magic fills your nostrils as you prepare to enter the grotto proper.`
+  PSHC `You approach the foreboding cavern to which you have stalked your prey, the shapeshifting vampire Zarban.`
+  PSHC `Drenched from the rain, and exhausted from having hunted all through the night, you have finally cornered the beast which you have been hired to dispatch.`
+  MKAR 00000003
+  PSHC `text`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `hunter_armor`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `hunter_sword`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `add`
+  PSHC `operation`
+  PSHC 2
+  PSHC `value`
+  MKOB 00000004
+  PSHC `status`
+  PSHC `type`
+  PSHC `all`
+  PSHC `target`
+  PSHC `add`
+  PSHC `operation`
+  PSHC 99
+  NEG
+  PSHC `value`
+  MKOB 00000004
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `all`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  PSHC `choices`
+  PSHC `type`
+  PSHC `all`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000006
+  PSHC `effects`
+  PSHC `Enter the grotto`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave2`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `intro_cave1`
+  PSHC `Chapter 1: The Grotto`
+  PSHC `title`
+  PSHC `It can only be the mighty vampire Zarban himself.`
+  PSHC `In the darkness before you, deep within the cave looms a vile shadow, dripping with evil.`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `I don't care about vampires, let's go to the tavern`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave3_tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Draw your magic sword and approach the shadow`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave3_brave`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `intro_cave2`
+  PSHC `Chapter 1: The Grotto`
+  PSHC `title`
+  PSHC `You collapse to the ground, unconscious as the mighty Zarban scurries away into the night, cackling annoyingly.`
+  PSHC `As you prepare yourself and draw your enchanted blade, you are knocked out from behind by a large rock to the head.`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `...`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave4`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `intro_cave3_brave`
+  PSHC `Chapter 1: The Grotto`
+  PSHC `title`
+  PSHC `You collapse to the ground, unconscious as the mighty Zarban scurries away into the night, cackling annoyingly.`
+  PSHC `As you turn around to give up your promising career as a mediocre vampire hunter for hire, you are knocked out from behind by a large rock to the head.`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `...`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave4`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `intro_cave3_tavern`
+  PSHC `Chapter 1: The Grotto`
+  PSHC `title`
+  PSHC `Before you lies a single set of footprints, leading away from the grotto.`
+  PSHC ``
+  PSHC `You gather what little you can salvage, and turn to leave the grotto.`
+  PSHC `You awaken sometime later, to find your magic blade and mint-condition vintage vampire hunting armour reduced to worthless scrap before you.`
+  MKAR 00000004
+  PSHC `text`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `all`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `I don't care about footprints, let's go to the tavern`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave5_tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Follow the footprints`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave5_brave`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `intro_cave4`
+  PSHC `Chapter 1: The Grotto`
+  PSHC `title`
+  PSHC `You are exhausted from searching through the night, and could use a pick-me-up.`
+  PSHC `Likely having replaced one of the sleepy village's unsuspecting peasants.`
+  PSHC `One thing you can be sure of, however; you still sense Zarban's evil aura - you are sure he is still hiding out somewhere in this very town`
+  PSHC ``
+  PSHC `The footprints lead into the village, but too many footprints coming and going make it impossible to tell what happened next.`
+  PSHC `You follow the footprints to a nearby village, and arrive just as dawn breaks.`
+  MKAR 00000006
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Look around`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `intro_cave5_brave`
+  PSHC `Chapter 1: The Grotto`
+  PSHC `title`
+  PSHC `Likely having replaced one of the sleepy village's unsuspecting peasants.`
+  PSHC `As you approach the village, you once again sense Zarban's evil aura - you are sure he is hiding out somewhere in this very town`
+  PSHC ``
+  PSHC `You walk to a tavern in a nearby village, and arrive just as dawn breaks.`
+  MKAR 00000004
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Enter the tavern`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_enter`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `intro_cave5_tavern`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `Looking around you see several people of interest milling about the small village bar.`
+  PSHC `You enter the tavern.`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Leave the tavern`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `eq`
+  PSHC `operation`
+  PSHC 0
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_ending_stamina`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Take a seat at the bar`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_enter`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `GAME OVER! Try again?`
+  PSHC ``
+  PSHC `Zarban would later go on to burn down 27 orphanages in your name.`
+  PSHC `Having clearly decided to give up vampire hunting for a promising new career in alcoholism, you decide to let Zarban live.`
+  PSHC `You take your familiar seat back at the bar, and order another round.`
+  MKAR 00000005
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_ending_alcoholic`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `You take a seat on a ramshackle stool at the tavern bar.`
+  MKAR 00000001
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Leave the tavern`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `eq`
+  PSHC `operation`
+  PSHC 0
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_ending_stamina`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Approach the old farmer drinking alone at the bar`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_farmer`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Approach the suspicious hooded figure lurking in the corner`
+  PSHC `prompt`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `made_dave_go_home`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `tavern_lurker_sad`
+  PSHC `target`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `made_dave_sad`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  MKOB 00000002
+  PSHC `tavern_lurker`
+  PSHC `target`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `made_dave_sad`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Speak to the bartender`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_bartender`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000004
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Back for another already? What can I get'cha, stranger?"`
+  PSHC `You approach the tired-looking bartender, intending to vigorously question the overworked customer-service employee.`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `I could use a refreshing drink`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_bartender_shots`
+  PSHC `status`
+  PSHC `type`
+  PSHC `alcoholism`
+  PSHC `target`
+  PSHC 1
+  PSHC `value`
+  PSHC `eq`
+  PSHC `operation`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `tavern_ending_alcoholic`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask if anyone in town sells weapons and armour`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_bartender_blacksmith`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask if any strangers have come through town recently`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_bartender_strangers`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000004
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_bartender`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Ye could always ask Gaylen, the priest 'round' these parts - his shack'll be a good halfday's walk from here, north of the village."`
+  PSHC `"Besides yourself, m'lord? Just one, earlier this very morn'! He'll be long gone by now though, headed out in something of a hurry."`
+  PSHC ``
+  PSHC `You ask the bartender about other strangers that have passed through town.`
+  MKAR 00000004
+  PSHC `text`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `learnt_about_priest`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask the bartender more questions`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_bartender`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_bartender_strangers`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `The bartender laughs at his attempted joke, as he points to a hooded figure drinking alone in the corner of the bar.`
+  PSHC `"That said, Dave over yonder has been known to fix a rake in his time, if you catch my meanin'"`
+  PSHC `"Well, you're not likely to find a big fancy smithy in our little village, m'lord"`
+  PSHC ``
+  PSHC `You ask the bartender where you might acquire new weapons and armour.`
+  MKAR 00000005
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask the bartender more questions`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_bartender`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_bartender_blacksmith`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `Your stamina has been restored`
+  PSHC `You ask the bartender for a glass of his strongest brew, and down it in one mighty gulp.`
+  MKAR 00000002
+  PSHC `text`
+  PSHC `status`
+  PSHC `type`
+  PSHC `alcoholism`
+  PSHC `target`
+  PSHC `add`
+  PSHC `operation`
+  PSHC 1
+  PSHC `value`
+  MKOB 00000004
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `add`
+  PSHC `operation`
+  PSHC 99
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000002
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask the bartender more questions`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_bartender`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_bartender_shots`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"What do ye' want, stranger. I ain't in no mood fer conversin' with the likes of you today."`
+  PSHC `You approach the hooded stranger, clearly an imposing figure. The hulking goliath of a man looks up at you with a scowl on his face.`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask if he's seen anything unusual`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_lurker_unusual`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask where you might be able to acquire some new weapons and armour`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_lurker_smithy`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `I KNOW YOU'RE THE VAMPIRE, YOU MONSTER! PREPARE TO DIE!`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_lurker_sad`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000004
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_lurker`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Meet me at my house just outside the village. Could use a hand with something, then I think I can help ye."`
+  PSHC `"Well I haven't the skills to make such a thing, if that's what yer asking, but I was a soldier in me youth."`
+  MKAR 00000002
+  PSHC `text`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `made_dave_go_home`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_lurker_smithy`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Wouldn't know nothin' about that, stranger. Leave me be."`
+  MKAR 00000001
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask him more questions`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_lurker`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_lurker_unusual`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `The mighty Zarban would never debase himself so. This is clearly not he.`
+  PSHC ``
+  PSHC `"Just leave me alone! You're really mean... G... Go away..."`
+  PSHC `The large muscular man before you begins to cry uncontrollably, tears streaming down his bearded visage.`
+  MKAR 00000004
+  PSHC `text`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `made_dave_sad`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_lurker_sad`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `An old man sits at the bar next to you, his skin wrinkled from years of hard labour under the sun.`
+  MKAR 00000001
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask if he's seen anything unusual`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_farmer_unusual`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask if anyone in town sells weapons and armour`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_farmer_blacksmith`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Make small talk`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_farmer_daughter`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask him how a farmer has time to drink in broad daylight`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_farmer_sun`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000005
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_farmer`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Never much cared for the sun is all. Do most of me work by night these days. What business is it of yours, anyway?"`
+  MKAR 00000001
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Continue talking to the farmer`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_farmer`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_farmer_sun`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Unusual? Two strangers in one night is unusual. It's also annoying. Can I get back to me drink now?"`
+  MKAR 00000001
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  PSHC `status`
+  PSHC `type`
+  PSHC `alcoholism`
+  PSHC `target`
+  PSHC 2
+  PSHC `value`
+  PSHC `lt`
+  PSHC `operation`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Continue talking to the farmer`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_farmer`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_farmer_unusual`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Me daughter's been awful moody lately, but tis the norm for the young'uns these days."`
+  PSHC `"Not much of interest happens round these parts, stranger."`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Continue talking to the farmer`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_farmer`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_farmer_daughter`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `The farmer laughs heartily, clearly pleased with his joke.`
+  PSHC `"He's been known to fix a rake in his time, if you catch my meanin'"`
+  PSHC `"You could try Dave, round back. He's the hooded feller with crippling emotional issues."`
+  MKAR 00000003
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go back to your stool`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Continue talking to the farmer`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_farmer`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `tavern_farmer_blacksmith`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `GAME OVER! Try again?`
+  PSHC ``
+  PSHC `Unfortunately, by then Zarban is long gone, and the trail cold. He will later go on to become the CEO of Nestle.`
+  PSHC `Exhausted and thirsty, you collapse to the ground. The villagers find you, and bring you to the inn to recover.`
+  MKAR 00000004
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_ending_stamina`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `You stand on the main road crossing the village. A nearby sign identifies the town as Rothsten.`
+  MKAR 00000001
+  PSHC `text`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `add`
+  PSHC `operation`
+  PSHC 1
+  NEG
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Leave the village and head to the priest's cottage`
+  PSHC `prompt`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `learnt_about_priest`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `church_bear`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `eq`
+  PSHC `operation`
+  PSHC 0
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_ending_stamina`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Search the tavern`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `tavern_enter`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Search the mill`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_mill`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `eq`
+  PSHC `operation`
+  PSHC 0
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_ending_stamina`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Search the school`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_outside`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `eq`
+  PSHC `operation`
+  PSHC 0
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_ending_stamina`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Search Dave's house`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_dave`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `made_dave_sad`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_dave_not_home`
+  PSHC `target`
+  MKOB 00000002
+  PSHC `choices`
+  PSHC `type`
+  PSHC `made_dave_go_home`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_dave_not_home`
+  PSHC `target`
+  MKOB 00000002
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `eq`
+  PSHC `operation`
+  PSHC 0
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_ending_stamina`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000004
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Search the farm`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `eq`
+  PSHC `operation`
+  PSHC 0
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_ending_stamina`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000006
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"A vampire hunter in our little corner of the world? What could possibly bring you here?" She asks you.`
+  PSHC `She does not seem surprised to see you, not so much as looking up from her work.`
+  PSHC `Based on her attire - and the visibility of her assets - you wonder if her clothes were the victim of a shrinking spell.`
+  PSHC `You arrive at a well-maintained farmhouse. You walk into the house, and see a young woman crushing herbs with a mortar & pestle.`
+  MKAR 00000004
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Leave the farm`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Look around the humble farmhouse`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm_search`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Speak to the young woman`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm_amelie`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000003
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_farm`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Need a potion? Or a salve?" The young woman asks`
+  MKAR 00000001
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Stop speaking to the woman`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask if she's seen anything unusual`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm_amelie_unusual`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask her about herself`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm_amelie_self`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask her about her family`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm_amelie_family`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask her for a potion of vampire detection`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm_amelie_potion`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000005
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_farm_amelie`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"I have a potion to cure rheumatism, if that interests you, or the best salve for warts this side of the continent!"`
+  PSHC `The woman looks at you confused; "Is that a real potion? I'm afraid I wouldn't know how to make this." The young woman replies`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Ask her something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm_amelie`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_farm_amelie_potion`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Honey, having a stranger on our farm is the most unusual thing that's happened in recent memory."`
+  PSHC `"Unusual?" The young woman laughs`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Ask her something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm_amelie`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_farm_amelie_unusual`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"My father doesn't like to talk about it, and there's not much left of the family, but it's a proud history to bear."`
+  PSHC `"In the days of the Aremeic Order, our family laid claim to the thrones of half the Order's colonial territories on the East continent!"`
+  PSHC `"My father is Arnoulf, of House Brolette - yes those Brolettes."`
+  PSHC `"Our family? It's an interesting enough tale, I suppose." The young woman begins`
+  MKAR 00000004
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Ask her something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm_amelie`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_farm_amelie_family`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Ever try foraging for mushrooms in 3 layers of frill and fuss? You'd never catch me in some silly long dress."`
+  PSHC `"I provide these simpletons with medicines and salves, but nobody can see past my choice of clothes. Nobody would bat an eye in the cities."`
+  PSHC `"Me? I am Amelie of House Brolette; I'm the village herbalist, not that my skills net me much respect in this hole of a village."`
+  MKAR 00000003
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Ask her something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm_amelie`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_farm_amelie_self`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `Supplies for alchemical brewing are strewn about.`
+  PSHC `In the kitchen, various herbs and mushrooms hang from the ceiling.`
+  PSHC `In the corner are a pair of small beds, one of which clearly hasn't seem much use of late.`
+  PSHC `You take a good look around the room.`
+  MKAR 00000004
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Leave the farm`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Speak to the young woman`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_farm_amelie`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_farm_search`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `No candles burn within, and the door is locked tight.`
+  PSHC ``
+  PSHC `Vines and weeds grow rampant across the property, and the windows sit greased and dusty.`
+  PSHC `You arrive at Dave's home at the edge of the village, a once beautiful home, but clearly neglected of late.`
+  MKAR 00000004
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Leave Dave's house`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_dave_not_home`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `All around him sits dusty disused brushes, paints and canvases`
+  PSHC `The door sits open, so you walk inside. Dave sits alone on a dusty old wooden chair.`
+  PSHC ``
+  PSHC `Vines and weeds grow rampant across the property, and the windows sit greased and dusty.`
+  PSHC `You arrive at Dave's home at the edge of the village, a once beautiful home, but clearly neglected of late.`
+  MKAR 00000005
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Leave Dave's house`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Talk to Dave`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_dave_dave`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_dave`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"My wife, god rest 'er soul didn't approve of wanton violence."`
+  PSHC `"Not usually in the habit of armin' strangers though, so ye'd best have a good cause."`
+  PSHC `"So, ye needed a weapon, ey? I do have me gear from the war that I'd be happy enough to never see again."`
+  MKAR 00000003
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Leave Dave's house`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Tell him to give you the gear or you'll take it by force`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_dave_dave_threaten`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Tell him you are avenging the death of a loved one`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_dave_dave_lie`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Tell him you are hunting a vampire hiding amongst the villagers`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_dave_dave_honest`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000004
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_dave_dave`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `But it may grant some protection, at the very least.`
+  PSHC `The rusted old blade would never be able to strike a killing blow against such a powerful vampire;`
+  PSHC ``
+  PSHC `"Ye'll see it marked by the clan name Audemars."`
+  PSHC `"All I ask is that you lay these here flowers at my family grave. The walk to the temple is too much for this old soldier."`
+  PSHC `"I believe you, stranger. My sword and armour are yours. They ain't much but I hope they help you."`
+  PSHC `"There's something in the air today; as if the sky itself were thick with dread."`
+  PSHC `Dave sits in silence for an awkwardly long time.`
+  MKAR 00000008
+  PSHC `text`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `dave_flowers`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `old_armor`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `rusty_sword`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  PSHC `choices`
+  PSHC `type`
+  PSHC `made_dave_sad`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000004
+  PSHC `effects`
+  PSHC `Leave Dave's house`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_dave_dave_honest`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"In the meantime, I'm afraid ye'll need to leave."`
+  PSHC `"Sorry stranger, I cannae in good conscience lend creed to vengeance. I hope you find a way to see another path."`
+  PSHC `Dave slowly shakes his head.`
+  MKAR 00000003
+  PSHC `text`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `made_dave_sad`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Leave Dave's house`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_dave_dave_lie`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `You awaken outside the house, with the door now securely locked.`
+  PSHC `Without any warning, the giant of a man hits you on the side of the head with one mighty punch.`
+  MKAR 00000002
+  PSHC `text`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `made_dave_sad`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Leave Dave's house`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_dave_dave_threaten`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Ah, we heard there were strangers about!" says the man; "Something ye' knead? Some bread for the road perhaps?"`
+  PSHC ``
+  PSHC `Inside are a man, expertly kneading a large ball of dough, and a woman on a wooden rocking chair repairing a tunic.`
+  PSHC `The stone brick building is small, but impressive - clearly much older than the wooden structures you've seen thus far.`
+  PSHC `You arrive at the small village mill, and step inside`
+  MKAR 00000005
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Leave the mill`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask if they've seen anything unusual`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_mill_unusual`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask about their family`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_mill_family`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask if they have garlic bread instead`
+  PSHC `prompt`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `accepted_bread`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_mill_garlic`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Take the man up on his offer of bread`
+  PSHC `prompt`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `accepted_bread`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_mill_bread`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000005
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_mill`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"We've 3 little 'uns - Dylan is 8, and the twins are fresh out the oven."`
+  PSHC `"We're the Fouchers sir - Nils and Silvia. Our family's been in these parts since the colonial days of the Order."`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Ask something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_mill`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_mill_family`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"If it's adventure ye' seek, you've picked the wrong village I'm afraid."`
+  PSHC `"Not a thing of interest has happened in these here parts in many a year, sir."`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Ask something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_mill`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_mill_unusual`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `The man shakes his head; "Nay, stranger. I've a terrible allergy to garlic, so none of that 'ere."`
+  PSHC `You narrow your eyes in suspicion as the question leaves your lips`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Ask something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_mill`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_mill_garlic`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `The family then looks on in horror as you devour the entire loaf in one go like some kind of feral beast`
+  PSHC `The baker hands you a fresh loaf of bread, hot out of the oven,`
+  MKAR 00000002
+  PSHC `text`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `add`
+  PSHC `operation`
+  PSHC 99
+  PSHC `value`
+  MKOB 00000004
+  PSHC `choices`
+  PSHC `type`
+  PSHC `accepted_bread`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `effects`
+  PSHC `Ask something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_mill`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_mill_bread`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `A gaggle of annoying-looking children mill about uselessly outside the school.`
+  PSHC `You arrive outside the small schoolhouse. A humble shack sits next to it, likely a home for the teacher and her family.`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Leave the school`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Harass the annoying children`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_children`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Search the schoolhouse`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_inside`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Search the shack`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_shack`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000004
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_school_outside`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `On her head sits the biggest hat you have ever seen. This sunhat has a wingspan that could make a dragon jealous.`
+  PSHC `A woman - the teacher, presumably - is sat at the far side of the room.`
+  PSHC `You walk into the schoolhouse.`
+  MKAR 00000003
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Look somewhere else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_outside`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask about her gigantic hat`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_hat`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `insulted_teacher`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_school_items`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask about the strange items in her home`
+  PSHC `prompt`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `snooped_on_teacher`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_school_items`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask if she's seen anything unusual`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_unusual`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `insulted_teacher`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_school_items`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask her about herself`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_teacher`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `insulted_teacher`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `village_school_items`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000005
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_school_inside`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Leave my classroom this instant!"`
+  PSHC `"What kind of creepy imbecile looks in a young woman's windows at her private things?"`
+  MKAR 00000002
+  PSHC `text`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `insulted_teacher`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Ask something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_inside`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_school_items`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Besides the usual nonsense from that horrible Brolette girl? Not in a long time."`
+  MKAR 00000001
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Ask something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_inside`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_school_unusual`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"Milton is outside with his friends - mercifully he was too young to remember it."`
+  PSHC `"My family? nothing special, I'm afraid. Lost my eldest and my husband two winters back to red fever."`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Ask something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_inside`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_school_teacher`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `"I could buy it from that Brolette girl, but I'd rather not have to interact with that one."`
+  PSHC `"I use the hat to protect myself, along with a salve I make at home!"`
+  PSHC `"I've a skin condition, always have - the sun; my skin burns so easily"`
+  PSHC `The young woman laughs as she grips her cap`
+  MKAR 00000004
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Ask something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_inside`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_school_hat`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `You can see strange objects strewn about - a dagger, various herbs, an ornate pendant covered in runes, and a pouch of white powder`
+  PSHC `The shack is locked but you look in through the window.`
+  MKAR 00000002
+  PSHC `text`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `snooped_on_teacher`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Look somewhere else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_outside`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_school_shack`
+  PSHC `Chapter 2: Footprints`
+  PSHC `title`
+  PSHC `One child begins to try, while another kicks you in the groin and runs away giggling.`
+  PSHC `You walk up to the children and kick sand at them like a complete arsehole.`
+  MKAR 00000002
+  PSHC `text`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `add`
+  PSHC `operation`
+  PSHC 1
+  NEG
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Look somewhere else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `village_school_outside`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `village_school_children`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `You hear a mighty roar from behind you.`
+  PSHC ``
+  PSHC `As you enter the forest, the air around you grows cold and the sun itself begins to dim.`
+  PSHC `You head down the wooded path towards the temple.`
+  MKAR 00000004
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Run away from the creature`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_bear_coward`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Turn around and face your foe`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_bear_unarmed`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `rusty_sword`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `church_bear_armed`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_bear`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `"Greetings, stranger. How may I be of service?"`
+  PSHC `The priest approaches you:`
+  PSHC `Nearby you can see a graveyard, with a massive ornate tomb at its center.`
+  PSHC `You arrive at the temple late in the afternoon, and see the priest standing outside the ornate stone building.`
+  PSHC `You bravely run away, as fast as you can into the woods, towards the temple, and Gaylen the town priest.`
+  MKAR 00000005
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Speak to the priest`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_gaylen`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_bear_coward`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `GAME OVER! Try again?`
+  PSHC ``
+  PSHC `And that's terrible.`
+  PSHC `That's as many as four tens.`
+  PSHC `But by then, when no one was looking, Zarban took forty cakes.`
+  PSHC `It will be daybreak before you are found by the villagers, and after numerous surgeries and expensive physiotherapy you regain the ability to walk`
+  PSHC ``
+  PSHC `You start to run away, but the mighty beast - no doubt the shapeshifter himself - rips you open with a mighty swipe of his paw`
+  PSHC `You instinctively reach for your blade, but of course, you don't have one.`
+  PSHC `You turn to face your foe and come face to crotch with a 12ft tall monster of a bear.`
+  MKAR 0000000A
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_bear_unarmed`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `You know in your heart that can only be Zarban the undead shapeshifter himself.`
+  PSHC `The mighty beast looms over you, menace in his eyes. The beast drips with magic and malice;`
+  PSHC `You instinctively reach for your blade, and draw the corroded shortsword you were given.`
+  PSHC `You turn to face your foe and come face to crotch with a 12ft tall monster of a bear.`
+  MKAR 00000004
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Slash at the beast's legs`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_bear_armed_legs`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Go for the beast's head`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_bear_armed_stupid`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Run away very very quickly`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_bear_armed_coward`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000003
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_bear_armed`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `GAME OVER! Try again?`
+  PSHC ``
+  PSHC `But by then, Zarban is long gone. He would later go on to start a mediocre reaction channel on TikTok.`
+  PSHC `It will be daybreak before you are found by the villagers, and after numerous surgeries and expensive physiotherapy you regain the ability to walk`
+  PSHC ``
+  PSHC `You start to run away, but the mighty beast rips your back open with a mighty swipe of his paw`
+  MKAR 00000006
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_bear_armed_coward`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `GAME OVER! Try again?`
+  PSHC ``
+  PSHC `Zarban would later go on to become a successful corporate attorney.`
+  PSHC `While you leave your flank fully exposed, Zarban disembowels you with ease.`
+  PSHC `The common steel of the blade cannot deal a finishing blow against such a powerful magical foe, however, and the metal passes right through the beast.`
+  PSHC `You stab directly upwards into the beast's head, aiming to finish this now and here.`
+  MKAR 00000006
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_bear_armed_stupid`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `It will take days for him to heal from such a blow.`
+  PSHC `You slash at the beast's legs, causing him to roar in pain. You can't kill him with this sword, but even if he takes on another form,`
+  MKAR 00000002
+  PSHC `text`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `injured_zarban`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Go for the beast's head`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_bear_armed_stupid`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Escape while the beast reels from the blow`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_bear_armed_escape`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_bear_armed_legs`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `"Greetings, stranger. How may I be of service?"`
+  PSHC `The priest approaches you:`
+  PSHC `Nearby you can see a graveyard, with a massive ornate tomb at its center.`
+  PSHC `You arrive at the temple late in the afternoon, and see the priest standing outside the ornate stone building.`
+  PSHC `With the beast's legs injured, you take your chance to escape into the woods, towards the temple, and Gaylen the town priest.`
+  MKAR 00000005
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `There is nothing for me here, let's return to town`
+  PSHC `prompt`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `rusty_sword`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_arrival`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Speak to the priest`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_gaylen`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_bear_armed_escape`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `"Yes, stranger, how can I help you?"`
+  MKAR 00000001
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go to the graveyard`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_graveyard`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask the priest about Dave's family grave`
+  PSHC `prompt`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `dave_flowers`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `church_gaylen_impressed`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask about the temple`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_gaylen_temple`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask for help against the evil vampire`
+  PSHC `prompt`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `ancient_armor`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `church_gaylen_fail`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `impressed_gaylen`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `church_gaylen_success`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000004
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_gaylen`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `"May the ancient vampire hunter's armour protect you. And take this key, you'll find Moonsbane in the hunter's tomb."`
+  PSHC `"As you are a friend to the village, and an honourable soul, I grant you the protection of the great Edwin Rothsten."`
+  PSHC `The priest stays silent for a time, appearing to pray to himself. Finally he speaks;`
+  MKAR 00000003
+  PSHC `text`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `learnt_about_hunter`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `old_armor`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `hunter_tomb_key`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `ancient_armor`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000004
+  PSHC `effects`
+  PSHC `Go to the graveyard`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_graveyard`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask the priest something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_gaylen`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_gaylen_success`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `"Sadly I have only your word, so I cannot help you more than that. Good luck to you."`
+  PSHC `"I will say a prayer of blessing for you stranger."`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go to the graveyard`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_graveyard`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask the priest something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_gaylen`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_gaylen_fail`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `The priest points to the ornate tomb in the centre of the graveyard - you think to yourself that it might be prudent to 'borrow' Moonsbane.`
+  PSHC ``
+  PSHC `"The legend himself is entombed here, where himself and his enchanted blade Moonsbane can watch over us."`
+  PSHC `"It was personally commissioned by the famed vampire hunter, and knight of the order Edwin Rothsten."`
+  PSHC `"This temple was founded over 700 years ago during the time of the Aremeic Order."`
+  MKAR 00000005
+  PSHC `text`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `learnt_about_hunter`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Go to the graveyard`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_graveyard`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask the priest something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_gaylen`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_gaylen_temple`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `"Ah! A friend of lord Audemars is a friend of mine. I will bless these and place them at his family grave for you."`
+  PSHC `You show the flowers to the priest, who smiles warmly at you.`
+  MKAR 00000002
+  PSHC `text`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `impressed_gaylen`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `dave_flowers`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `effects`
+  PSHC `Go to the graveyard`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_graveyard`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Ask the priest something else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `church_gaylen`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_gaylen_impressed`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `The family grave of the Audemars clan sits nearby.`
+  PSHC `You arrive at the humble graveyard. In its center lies an ornate tomb marked 'Edwin Rothsten, Hunter of the Aremeic Order'`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Return to the village, and confront Zarban`
+  PSHC `prompt`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `rusty_sword`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_arrival`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Retrieve the sword from the hunter's tomb`
+  PSHC `prompt`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `learnt_about_hunter`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `church_graveyard_breakin`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `hunter_tomb_key`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `church_graveyard_key`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Place the flower's on Dave Audemars' family grave`
+  PSHC `prompt`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `dave_flowers`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `church_graveyard_flowers`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000003
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_graveyard`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `You feel content, your promise fulfilled.`
+  PSHC `You place the flowers at the foot of the family gravestone, and say a short prayer.`
+  MKAR 00000002
+  PSHC `text`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `dave_flowers`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Return to the village, and confront Zarban`
+  PSHC `prompt`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `rusty_sword`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_arrival`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Retrieve the sword from the hunter's tomb`
+  PSHC `prompt`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `learnt_about_hunter`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `church_graveyard_breakin`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `hunter_tomb_key`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `church_graveyard_key`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_graveyard_flowers`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `You pick up the ancient runic sword and feel its holy power flow through you - Zarban's end is at hand!`
+  PSHC `You walk up to the tomb, and use a the key to open the ornate lock.`
+  MKAR 00000002
+  PSHC `text`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `hunter_tomb_key`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `rusty_sword`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `magic_sword`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000003
+  PSHC `effects`
+  PSHC `Return to the village, and confront Zarban`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_arrival`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_graveyard_key`
+  PSHC `Chapter 3: The Hunter, Hunted`
+  PSHC `title`
+  PSHC `You'll probably return it when you are finished. Maybe.`
+  PSHC `You pick up the ancient runic sword and equip it - Zarban's end is at hand!`
+  PSHC `You walk up to the tomb, and use a nearby rock to smash open the ornate lock.`
+  MKAR 00000003
+  PSHC `text`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `rusty_sword`
+  PSHC `target`
+  PUSH false
+  PSHC `value`
+  MKOB 00000003
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `magic_sword`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000002
+  PSHC `effects`
+  PSHC `Return to the village, and confront Zarban`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_arrival`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `church_graveyard_breakin`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `But which villager has been replaced by the vile sorcerer?`
+  PSHC `You arrive back in the village at midnight. The stench of evil still hangs in the air - it is time to confront Zarban.`
+  MKAR 00000002
+  PSHC `text`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `add`
+  PSHC `operation`
+  PSHC 1
+  NEG
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `effects`
+  PSHC `Go to the schoolhouse`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_school`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `eq`
+  PSHC `operation`
+  PSHC 0
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_end_stamina`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Go to Dave's house`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_dave`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `eq`
+  PSHC `operation`
+  PSHC 0
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_end_stamina`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Go to the farm`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_farm`
+  PSHC `choices`
+  PSHC `type`
+  PSHC `injured_zarban`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_farm_scar`
+  PSHC `target`
+  MKOB 00000002
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `eq`
+  PSHC `operation`
+  PSHC 0
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_end_stamina`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000003
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Go to the tavern`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_tavern`
+  PSHC `status`
+  PSHC `type`
+  PSHC `stamina`
+  PSHC `target`
+  PSHC `eq`
+  PSHC `operation`
+  PSHC 0
+  PSHC `value`
+  MKOB 00000004
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_end_stamina`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000002
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000004
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_arrival`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `GAME OVER! Try again?`
+  PSHC ``
+  PSHC `Unfortunately, by then Zarban is long gone, and the trail cold. He will later go on to form a knitting circle that only makes ugly sweaters for puppies.`
+  PSHC `Exhausted and thirsty, you collapse to the ground. The villagers find you, and bring you to the inn to recover.`
+  MKAR 00000004
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_end_stamina`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `You enter the tavern. The bartender stands at his usual spot, and Dave drinks alone in the corner.`
+  MKAR 00000001
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go somewhere else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_arrival`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Confront Dave`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_dave`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Confront the bartender`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_bartender`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000003
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_tavern`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `GAME OVER! Try again?`
+  PSHC ``
+  PSHC `Zarban would later escape, and go on to form a boy band that only sings Nickelback covers.`
+  PSHC `The innocent man falling to the ground before you is the last thing you ever see as a drunken, angry Dave breaks your neck from behind.`
+  PSHC `As your sword plunges into the man's chest, and you see the light leave his eyes, you know in your heart you've chosen wrong.`
+  PSHC `You approach the bartender, who smiles warmly on your approach. The smile fades as you draw your blade.`
+  MKAR 00000006
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_bartender`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `GAME OVER! Try again?`
+  PSHC ``
+  PSHC `Zarban would later escape, and go on to become the world's most successful spam email marketer.`
+  PSHC `A bottle breaks over your head from behind, and you fall to the ground. You'll later awaken just in time for your hanging`
+  PSHC `As your blade pierces his heart and Dave Audemars dies before you, you know in your heart you've chosen wrong.`
+  PSHC `A single tear falls down his cheek as he mutters to himself "I'm coming home, Amy."`
+  PSHC `Dave meets your gaze as you approach him, the old soldier recognizing the look in your eyes.`
+  MKAR 00000007
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_dave`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `The white frills of Amelie's long dress reflect the dim light of the moon`
+  PSHC `You arrive at the farm, and find Arnoulf and his daughter working the field by moonlight.`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go somewhere else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_arrival`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Confront the farmer's daughter`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `magic_sword`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_farm_amelie_noarmor`
+  PSHC `target`
+  MKOB 00000002
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `ancient_armor`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_farm_amelie_good`
+  PSHC `target`
+  MKOB 00000002
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `rusty_sword`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_farm_amelie_nosword`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000003
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Confront the old farmer`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_farm_arnoulf`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000003
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_farm_scar`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `You arrive at the farm, and find Arnoulf and his daughter working the field by moonlight.`
+  MKAR 00000001
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go somewhere else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_arrival`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Confront the farmer's daughter`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `magic_sword`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_farm_amelie_noarmor`
+  PSHC `target`
+  MKOB 00000002
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `ancient_armor`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_farm_amelie_good`
+  PSHC `target`
+  MKOB 00000002
+  PSHC `inventory`
+  PSHC `type`
+  PSHC `rusty_sword`
+  PSHC `target`
+  PUSH true
+  PSHC `value`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `conditions`
+  PSHC `jaccuse_farm_amelie_nosword`
+  PSHC `target`
+  MKOB 00000002
+  MKAR 00000003
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Confront the old farmer`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_farm_arnoulf`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000003
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_farm`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `GAME OVER! Try again?`
+  PSHC ``
+  PSHC `Zarban would later go on to invent a new type of toothpaste that causes cavities instead of preventing them.`
+  PSHC `As you both bleed out on the ground, you know you have chosen incorrectly.`
+  PSHC `You manage to get one good slash in on the farmer's neck... just as his pitchfork pierces your lungs.`
+  PSHC `The farmer scowls as he raises his pitchfork to confront you.`
+  PSHC `You approach the farmer as he toils under the moonlight, and draw your blade.`
+  MKAR 00000007
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_farm_arnoulf`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `The End! Try again?`
+  PSHC ``
+  PSHC `Renewed by his bloodbath, Zarban escapes into the world, more powerful than ever before.`
+  PSHC `Zarban, revealed and unleashed, will draw strength from the blood of the village by massacring the entire populate of the tiny hamlet.`
+  PSHC `As the old farmer tries in vain to help you, your neck is ripped open by his mighty jaws, and you bleed out onto the moonlit field.`
+  PSHC `You hack and slash away to no avail, as the common steel of your rusted blade cannot kill the vampire.`
+  PSHC `She dons an evil grin and her features twist and distort, as the evil shapeshifter assumes the form of a massive bear!`
+  PSHC `You approach the young woman and draw your sword.`
+  MKAR 00000008
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_farm_amelie_nosword`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `The End! Try again?`
+  PSHC ``
+  PSHC `The village will remember you as a brave hero.`
+  PSHC `You see the evil creature turn to dust before you. You may die, but at least the world is safe from this creature.`
+  PSHC `As you fall to the ground, the air sucked out of your lungs, and slowly choke to death on your own blood,`
+  PSHC `You stab the vile creature through it's dark heart, but in his dying breath, the evil sorcerer utters a final curse.`
+  PSHC `She dons an evil grin and her features twist and distort, as the evil shapeshifter assumes the form of a massive bear!`
+  PSHC `You approach the young woman and draw your sword.`
+  MKAR 00000008
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_farm_amelie_noarmor`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `Congratulations! You are victorious!`
+  PSHC ``
+  PSHC `The creature contorts and screeches as it is reduced to dust and ash - the vile one has been vanquished!`
+  PSHC `Your enchanted armour glows bright with ancient runic magic as the vampire's curse is nullified.`
+  PSHC `You stab the vile creature through it's dark heart, but in his dying breath, the evil sorcerer utters a final curse.`
+  PSHC `She dons an evil grin and her features twist and distort, as the evil shapeshifter assumes the form of a massive bear!`
+  PSHC `You approach the young woman and draw your sword.`
+  MKAR 00000007
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_farm_amelie_good`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `No candles burn within, and the door is locked tight.`
+  PSHC ``
+  PSHC `Vines and weeds grow rampant across the property, and the windows sit greased and dusty.`
+  PSHC `You arrive at Dave's home at the edge of the village, a once beautiful home, but clearly neglected of late.`
+  MKAR 00000004
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go somewhere else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_arrival`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_davehouse`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `The teacher and her 6-year-old son scream as you run inside.`
+  PSHC `You kick down the door of the teacher's humble shack and burst in, sword drawn.`
+  MKAR 00000002
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go somewhere else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_arrival`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Confront the son`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_school_teacher_child`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Confront the teacher`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_school_teacher`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000003
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_school`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `The End! Try again?`
+  PSHC ``
+  PSHC `You spend the rest of your days in hiding, as Zarban goes on to invent a new type of coffee that tastes like burnt popcorn and expired milk.`
+  PSHC `You run off as the young boy cries and vows to avenge his mother.`
+  PSHC `She dies instantly, since she was a schoolteacher - not a vampire.`
+  PSHC `You expertly swing your blade, and take the young woman's head clean off!`
+  MKAR 00000006
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_school_teacher`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `The End! Try again?`
+  PSHC ``
+  PSHC `You spend the rest of your days in hiding, as Zarban goes on to open a bakery that only sells cakes made with vegetables instead of sugar.`
+  PSHC `You run off as the young boy cries and vows to avenge his mother.`
+  PSHC `The young woman bleeds out in seconds, and the small cut on the boy's arm reveals the pure red blood of a human child.`
+  PSHC `You expertly swing your blade at the young child, but the mother blocks your blade with her body.`
+  MKAR 00000006
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_school_teacher_child`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `A bandage is wrapped around the baker's hand, clearly a fresh wound.`
+  PSHC `They look up in confused horror at the unexpected home invasion.`
+  PSHC `You burst into the stone building and charge to the far side of the room, where the family sits around a fireplace.`
+  MKAR 00000003
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `Go somewhere else`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_arrival`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Confront the twins`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_mill_babies`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Confront the 8 year old boy`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_mill_boy`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Confront the seamstress`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_mill_seamstress`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  PSHC `Confront the baker`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `jaccuse_mill_baker`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000005
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_mill`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `The End! Try again?`
+  PSHC ``
+  PSHC `You will be sentenced to life in prison without parole, from where you will be blissfully unaware of all the puppies Zarban would later eat.`
+  PSHC `As his guts spill all over the floor of the mill, the grieving family holds you down until the authorities arrive.`
+  PSHC `You draw your blade and eviscerate the baker and father.`
+  MKAR 00000005
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_mill_baker`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `The End! Try again?`
+  PSHC ``
+  PSHC `Zarban would later go on to traffic in baby spines for fun and profit.`
+  PSHC `As she falls to the ground and you realize the gravity of your mistake, a cast iron pan to the forehead puts you out of everyone's misery.`
+  PSHC `You draw your blade and stab the young mother of 3 through the eye.`
+  MKAR 00000005
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_mill_seamstress`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `The End! Try again?`
+  PSHC ``
+  PSHC `Zarban would later go on to rethink his life and become a nun. Just kidding he eats children now.`
+  PSHC `The pure red blood of a human tells you that a mistake has been made as his mother pierces your brain with a fork through the eye.`
+  PSHC `The boy uses his arms to shield himself, and survives - barely - your blow.`
+  PSHC `You draw your sword and slash at the young boy's throat.`
+  MKAR 00000006
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_mill_boy`
+  PSHC `Chapter 4: J'Accuse!`
+  PSHC `title`
+  PSHC `The End! Try again?`
+  PSHC ``
+  PSHC `Zarban would later go on to invest heavily into dogecoin.`
+  PSHC `The close-call has scarred you, and you no longer wish to hunt vampires.`
+  PSHC `You stop yourself from murdering two babies at the last second and decide to go to the tavern to rethink your life.`
+  PSHC `You draw your sword and approach the sleeping newborn babies.`
+  MKAR 00000006
+  PSHC `text`
+  MKAR 00000000
+  PSHC `effects`
+  PSHC `New game`
+  PSHC `prompt`
+  MKAR 00000000
+  PSHC `conditions`
+  PSHC `intro_cave1`
+  MKAR 00000001
+  PSHC `results`
+  MKOB 00000003
+  MKAR 00000001
+  PSHC `options`
+  MKOB 00000004
+  PSHC `jaccuse_mill_babies`
+  MKOB 00000058
+  PSHC `chapters`
+  MKOB 00000004
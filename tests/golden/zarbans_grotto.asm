@@ -0,0 +1,3442 @@
+  POOL 00000002
+  MKFN FN_arbitrary_arbitrary
+  FSIG
+  WRFN
+  MKFN FN_grapefruit_bananas
+  FSIG
+  WRFN
+  MKFN FN_octopus_cabbage
+  FSIG
+  WRFN
+  MKFN FN_bananas_dolphin
+  FSIG
+  WRFN
+  MKFN FN_hedgehog_pointbreak
+  FSIG
+  WRFN
+  MKFN FN_penguin_alabaster
+  FSIG
+  WRFN
+  MKFN FN_cabbage_umbrella
+  FSIG
+  WRFN
+  MKFN FN_jellybean_grapefruit
+  FSIG
+  WRFN
+  MKFN FN_quarantine_hedgehog
+  FSIG
+  WRFN
+  MKFN FN_dolphin_jellybean
+  FSIG
+  WRFN
+  MKFN FN_rhubarb_lumberjack
+  FSIG
+  WRFN
+  MKFN FN_rhubarb_lumberjack
+  FSIG
+  WRFN
+  MKFN FN_pointbreak_marmalade
+  FSIG
+  WRFN
+  PSHC `__zarban_storydata`
+  PSHC `{
+        "status":{
+            "stamina":{"hidden":false,"value":2,"maximum":3},
+            "alcoholism":{"hidden":true,"value":0,"maximum":2}
+        },
+        
+        "inventory":{
+            "hunter_sword":{"description":"Vampire hunter's sword","equipped":false,"effects":[]},
+            "hunter_armor":{"description":"Vampire hunter's armour","equipped":false,"effects":[]},
+            "rusty_sword":{"description":"Rusty shortsword","equipped":false,"effects":[]},
+            "old_armor":{"description":"Soldier's leather cuirass","equipped":false,"effects":[
+                {"type":"status","target":"stamina","operation":"add_max","value":2},
+                {"type":"status","target":"stamina","operation":"add","value":2}]
+            },
+            "magic_sword":{"description":"Moonsbane","equipped":false,"effects":[]},
+            "ancient_armor":{"description":"Edwin Rothsten's armour","equipped":false,"effects":[
+                {"type":"status","target":"stamina","operation":"add_max","value":5},
+                {"type":"status","target":"stamina","operation":"add","value":5}]
+            },
+            "hunter_tomb_key":{"description":"Key to the vampire hunter's tomb","equipped":false,"effects":[]},
+            "dave_flowers":{"description":"Flower's for Dave's family grave","equipped":false,"effects":[]}
+        },
+        
+        "choices":{
+            "made_dave_go_home":{"description":"Spoke to Dave in the tavern, making him go back to his home"},
+            "made_dave_sad":{"description":"Was rude to Dave, who will no longer help you"},
+            "learnt_about_priest":{"description":"Learnt about the church a day's walk down the road from the village"},
+            "accepted_bread":{"description":"Accepted bread from the baker"},
+            "snooped_on_teacher":{"description":"Snooped on the teacher's shack"},
+            "insulted_teacher":{"description":"Teacher found out about your snooping"},
+            "impressed_gaylen":{"description":"Impressed the priest with your kindness"},
+            "learnt_about_hunter":{"description":"Learnt about the famous hunter (and his sword)"},
+            "injured_zarban":{"description":"Injured Zarban's legs"}
+        },
+        
+        "chapters":{
+            "intro_cave1":{"title": "Chapter 1: The Grotto","text":["Drenched from the rain, and exhausted from having hunted all through the night, you have finally cornered the beast which you have been hired to dispatch.","You approach the foreboding cavern to which you have stalked your prey, the shapeshifting vampire Zarban.","The foul stench of magic fills your nostrils as you prepare to enter the grotto proper."],"effects":[{"type":"choices","target":"all","value":false},{"type":"inventory","target":"all","value":false},{"type":"status","target":"all","operation":"add","value":-99},{"type":"status","target":"stamina","operation":"add","value":2},{"type":"inventory","target":"hunter_sword","value":true},{"type":"inventory","target":"hunter_armor","value":true}],"options":[{"prompt":"Enter the grotto","conditions":[],"results":["intro_cave2"]}]},
+            "intro_cave2":{"title": "Chapter 1: The Grotto","text":["In the darkness before you, deep within the cave looms a vile shadow, dripping with evil.","It can only be the mighty vampire Zarban himself."],"effects":[],"options":[{"prompt":"Draw your magic sword and approach the shadow","conditions":[],"results":["intro_cave3_brave"]},{"prompt":"I don't care about vampires, let's go to the tavern","conditions":[],"results":["intro_cave3_tavern"]}]},
+            "intro_cave3_brave":{"title": "Chapter 1: The Grotto","text":["As you prepare yourself and draw your enchanted blade, you are knocked out from behind by a large rock to the head.","You collapse to the ground, unconscious as the mighty Zarban scurries away into the night, cackling annoyingly."],"effects":[],"options":[{"prompt":"...","conditions":[],"results":["intro_cave4"]}]},
+            "intro_cave3_tavern":{"title": "Chapter 1: The Grotto","text":["As you turn around to give up your promising career as a mediocre vampire hunter for hire, you are knocked out from behind by a large rock to the head.","You collapse to the ground, unconscious as the mighty Zarban scurries away into the night, cackling annoyingly."],"effects":[],"options":[{"prompt":"...","conditions":[],"results":["intro_cave4"]}]},
+            "intro_cave4":{"title": "Chapter 1: The Grotto","text":["You awaken sometime later, to find your magic blade and mint-condition vintage vampire hunting armour reduced to worthless scrap before you.","You gather what little you can salvage, and turn to leave the grotto.","","Before you lies a single set of footprints, leading away from the grotto."],"effects":[{"type":"inventory","target":"all","value":false}],"options":[{"prompt":"Follow the footprints","conditions":[],"results":["intro_cave5_brave"]},{"prompt":"I don't care about footprints, let's go to the tavern","conditions":[],"results":["intro_cave5_tavern"]}]},
+            "intro_cave5_brave":{"title": "Chapter 1: The Grotto","text":["You follow the footprints to a nearby village, and arrive just as dawn breaks.","The footprints lead into the village, but too many footprints coming and going make it impossible to tell what happened next.","","One thing you can be sure of, however; you still sense Zarban's evil aura - you are sure he is still hiding out somewhere in this very town","Likely having replaced one of the sleepy village's unsuspecting peasants.","You are exhausted from searching through the night, and could use a pick-me-up."],"effects":[],"options":[{"prompt":"Look around","conditions":[],"results":["village"]}]},
+            "intro_cave5_tavern":{"title": "Chapter 1: The Grotto","text":["You walk to a tavern in a nearby village, and arrive just as dawn breaks.","","As you approach the village, you once again sense Zarban's evil aura - you are sure he is hiding out somewhere in this very town","Likely having replaced one of the sleepy village's unsuspecting peasants."],"effects":[],"options":[{"prompt":"Enter the tavern","conditions":[],"results":["tavern_enter"]}]},
+            
+            "tavern_enter":{"title": "Chapter 2: Footprints","text":["You enter the tavern.","Looking around you see several people of interest milling about the small village bar."],"effects":[],"options":[{"prompt":"Take a seat at the bar","conditions":[],"results":["tavern"]},{"prompt":"Leave the tavern","conditions":[],"results":[{"conditions":[{"type":"status","target":"stamina","operation":"eq","value":0}],"target":"village_ending_stamina"},"village"]}]},
+            "tavern_ending_alcoholic":{"title": "Chapter 2: Footprints","text":["You take your familiar seat back at the bar, and order another round.","Having clearly decided to give up vampire hunting for a promising new career in alcoholism, you decide to let Zarban live.","Zarban would later go on to burn down 27 orphanages in your name.","","GAME OVER! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "tavern":{"title": "Chapter 2: Footprints","text":["You take a seat on a ramshackle stool at the tavern bar."],"effects":[],"options":[{"prompt":"Speak to the bartender","conditions":[],"results":["tavern_bartender"]},{"prompt":"Approach the suspicious hooded figure lurking in the corner","conditions":[{"type":"choices","target":"made_dave_go_home","value":false}],"results":[{"target":"tavern_lurker","conditions":[{"type":"choices","target":"made_dave_sad","value":false}]},{"target":"tavern_lurker_sad","conditions":[{"type":"choices","target":"made_dave_sad","value":true}]}]},{"prompt":"Approach the old farmer drinking alone at the bar","conditions":[],"results":["tavern_farmer"]},{"prompt":"Leave the tavern","conditions":[],"results":[{"conditions":[{"type":"status","target":"stamina","operation":"eq","value":0}],"target":"village_ending_stamina"},"village"]}]},
+            "tavern_bartender":{"title": "Chapter 2: Footprints","text":["You approach the tired-looking bartender, intending to vigorously question the overworked customer-service employee.","\"Back for another already? What can I get'cha, stranger?\""],"effects":[],"options":[{"prompt":"Ask if any strangers have come through town recently","conditions":[],"results":["tavern_bartender_strangers"]},{"prompt":"Ask if anyone in town sells weapons and armour","conditions":[],"results":["tavern_bartender_blacksmith"]},{"prompt":"I could use a refreshing drink","conditions":[],"results":[{"conditions":[{"type":"status","target":"alcoholism","value":1,"operation":"eq"}],"target":"tavern_ending_alcoholic"},
+            "tavern_bartender_shots"]},{"prompt":"Go back to your stool","conditions":[],"results":["tavern"]}]},"tavern_bartender_strangers":{"title": "Chapter 2: Footprints","text":["You ask the bartender about other strangers that have passed through town.","","\"Besides yourself, m'lord? Just one, earlier this very morn'! He'll be long gone by now though, headed out in something of a hurry.\"","\"Ye could always ask Gaylen, the priest 'round' these parts - his shack'll be a good halfday's walk from here, north of the village.\""],"effects":[{"type":"choices","target":"learnt_about_priest","value":true}],"options":[{"prompt":"Ask the bartender more questions","conditions":[],"results":["tavern_bartender"]},{"prompt":"Go back to your stool","conditions":[],"results":["tavern"]}]},
+            "tavern_bartender_blacksmith":{"title": "Chapter 2: Footprints","text":["You ask the bartender where you might acquire new weapons and armour.","","\"Well, you're not likely to find a big fancy smithy in our little village, m'lord\"","\"That said, Dave over yonder has been known to fix a rake in his time, if you catch my meanin'\"","The bartender laughs at his attempted joke, as he points to a hooded figure drinking alone in the corner of the bar."],"effects":[],"options":[{"prompt":"Ask the bartender more questions","conditions":[],"results":["tavern_bartender"]},{"prompt":"Go back to your stool","conditions":[],"results":["tavern"]}]},
+            "tavern_bartender_shots":{"title": "Chapter 2: Footprints","text":["You ask the bartender for a glass of his strongest brew, and down it in one mighty gulp.","Your stamina has been restored"],"effects":[{"type":"status","target":"stamina","operation":"add","value":99},{"type":"status","target":"alcoholism","operation":"add","value":1}],"options":[{"prompt":"Ask the bartender more questions","conditions":[],"results":["tavern_bartender"]},{"prompt":"Go back to your stool","conditions":[],"results":["tavern"]}]},
+            "tavern_lurker":{"title": "Chapter 2: Footprints","text":["You approach the hooded stranger, clearly an imposing figure. The hulking goliath of a man looks up at you with a scowl on his face.","\"What do ye' want, stranger. I ain't in no mood fer conversin' with the likes of you today.\""],"effects":[],"options":[{"prompt":"I KNOW YOU'RE THE VAMPIRE, YOU MONSTER! PREPARE TO DIE!","conditions":[],"results":["tavern_lurker_sad"]},{"prompt":"Ask where you might be able to acquire some new weapons and armour","conditions":[],"results":["tavern_lurker_smithy"]},{"prompt":"Ask if he's seen anything unusual","conditions":[],"results":["tavern_lurker_unusual"]},{"prompt":"Go back to your stool","conditions":[],"results":["tavern"]}]},
+            "tavern_lurker_smithy":{"title": "Chapter 2: Footprints","text":["\"Well I haven't the skills to make such a thing, if that's what yer asking, but I was a soldier in me youth.\"","\"Meet me at my house just outside the village. Could use a hand with something, then I think I can help ye.\""],"effects":[{"type":"choices","target":"made_dave_go_home","value":true}],"options":[{"prompt":"Go back to your stool","conditions":[],"results":["tavern"]}]},
+            "tavern_lurker_unusual":{"title": "Chapter 2: Footprints","text":["\"Wouldn't know nothin' about that, stranger. Leave me be.\""],"effects":[],"options":[{"prompt":"Ask him more questions","conditions":[],"results":["tavern_lurker"]},{"prompt":"Go back to your stool","conditions":[],"results":["tavern"]}]},
+            "tavern_lurker_sad":{"title": "Chapter 2: Footprints","text":["The large muscular man before you begins to cry uncontrollably, tears streaming down his bearded visage.","\"Just leave me alone! You're really mean... G... Go away...\"","","The mighty Zarban would never debase himself so. This is clearly not he."],"effects":[{"type":"choices","target":"made_dave_sad","value":true}],"options":[{"prompt":"Go back to your stool","conditions":[],"results":["tavern"]}]},"tavern_farmer":{"title": "Chapter 2: Footprints","text":["An old man sits at the bar next to you, his skin wrinkled from years of hard labour under the sun."],"effects":[],"options":[{"prompt":"Ask him how a farmer has time to drink in broad daylight","conditions":[],"results":["tavern_farmer_sun"]},{"prompt":"Make small talk","conditions":[],"results":["tavern_farmer_daughter"]},{"prompt":"Ask if anyone in town sells weapons and armour","conditions":[],"results":["tavern_farmer_blacksmith"]},{"prompt":"Ask if he's seen anything unusual","conditions":[],"results":["tavern_farmer_unusual"]},{"prompt":"Go back to your stool","conditions":[],"results":["tavern"]}]},
+            "tavern_farmer_sun":{"title": "Chapter 2: Footprints","text":["\"Never much cared for the sun is all. Do most of me work by night these days. What business is it of yours, anyway?\""],"effects":[],"options":[{"prompt":"Continue talking to the farmer","conditions":[],"results":["tavern_farmer"]},{"prompt":"Go back to your stool","conditions":[],"results":["tavern"]}]},
+            "tavern_farmer_unusual":{"title": "Chapter 2: Footprints","text":["\"Unusual? Two strangers in one night is unusual. It's also annoying. Can I get back to me drink now?\""],"effects":[],"options":[{"prompt":"Continue talking to the farmer","conditions":[],"results":["tavern_farmer"]},{"prompt":"Go back to your stool","conditions":[{"type":"status","target":"alcoholism","value":2,"operation":"lt"}],"results":["tavern"]}]},
+            "tavern_farmer_daughter":{"title": "Chapter 2: Footprints","text":["\"Not much of interest happens round these parts, stranger.\"","\"Me daughter's been awful moody lately, but tis the norm for the young'uns these days.\""],"effects":[],"options":[{"prompt":"Continue talking to the farmer","conditions":[],"results":["tavern_farmer"]},{"prompt":"Go back to your stool","conditions":[],"results":["tavern"]}]},
+            "tavern_farmer_blacksmith":{"title": "Chapter 2: Footprints","text":["\"You could try Dave, round back. He's the hooded feller with crippling emotional issues.\"","\"He's been known to fix a rake in his time, if you catch my meanin'\"","The farmer laughs heartily, clearly pleased with his joke."],"effects":[],"options":[{"prompt":"Continue talking to the farmer","conditions":[],"results":["tavern_farmer"]},{"prompt":"Go back to your stool","conditions":[],"results":["tavern"]}]},
+            
+            "village_ending_stamina":{"title": "Chapter 2: Footprints","text":["Exhausted and thirsty, you collapse to the ground. The villagers find you, and bring you to the inn to recover.","Unfortunately, by then Zarban is long gone, and the trail cold. He will later go on to become the CEO of Nestle.","","GAME OVER! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "village":{"title": "Chapter 2: Footprints","text":["You stand on the main road crossing the village. A nearby sign identifies the town as Rothsten."],"effects":[{"type":"status","target":"stamina","operation":"add","value":-1}],"options":[{"prompt":"Search the farm","conditions":[],"results":[{"conditions":[{"type":"status","target":"stamina","operation":"eq","value":0}],"target":"village_ending_stamina"},"village_farm"]},{"prompt":"Search Dave's house","conditions":[],"results":[{"conditions":[{"type":"status","target":"stamina","operation":"eq","value":0}],"target":"village_ending_stamina"},{"conditions":[{"type":"choices","target":"made_dave_go_home","value":false}],"target":"village_dave_not_home"},{"conditions":[{"type":"choices","target":"made_dave_sad","value":true}],"target":"village_dave_not_home"},"village_dave"]},{"prompt":"Search the school","conditions":[],"results":[{"conditions":[{"type":"status","target":"stamina","operation":"eq","value":0}],"target":"village_ending_stamina"},"village_school_outside"]},{"prompt":"Search the mill","conditions":[],"results":[{"conditions":[{"type":"status","target":"stamina","operation":"eq","value":0}],"target":"village_ending_stamina"},"village_mill"]},{"prompt":"Search the tavern","conditions":[],"results":["tavern_enter"]},{"prompt":"Leave the village and head to the priest's cottage","conditions":[{"type":"choices","target":"learnt_about_priest","value":true}],"results":[{"conditions":[{"type":"status","target":"stamina","operation":"eq","value":0}],"target":"village_ending_stamina"},"church_bear"]}]},
+            
+            "village_farm":{"title": "Chapter 2: Footprints","text":["You arrive at a well-maintained farmhouse. You walk into the house, and see a young woman crushing herbs with a mortar & pestle.","Based on her attire - and the visibility of her assets - you wonder if her clothes were the victim of a shrinking spell.","She does not seem surprised to see you, not so much as looking up from her work.","\"A vampire hunter in our little corner of the world? What could possibly bring you here?\" She asks you."],"effects":[],"options":[{"prompt":"Speak to the young woman","conditions":[],"results":["village_farm_amelie"]},{"prompt":"Look around the humble farmhouse","conditions":[],"results":["village_farm_search"]},{"prompt":"Leave the farm","conditions":[],"results":["village"]}]},
+            "village_farm_amelie":{"title": "Chapter 2: Footprints","text":["\"Need a potion? Or a salve?\" The young woman asks"],"effects":[],"options":[{"prompt":"Ask her for a potion of vampire detection","conditions":[],"results":["village_farm_amelie_potion"]},{"prompt":"Ask her about her family","conditions":[],"results":["village_farm_amelie_family"]},{"prompt":"Ask her about herself","conditions":[],"results":["village_farm_amelie_self"]},{"prompt":"Ask if she's seen anything unusual","conditions":[],"results":["village_farm_amelie_unusual"]},{"prompt":"Stop speaking to the woman","conditions":[],"results":["village_farm"]}]},
+            "village_farm_amelie_potion":{"title": "Chapter 2: Footprints","text":["The woman looks at you confused; \"Is that a real potion? I'm afraid I wouldn't know how to make this.\" The young woman replies","\"I have a potion to cure rheumatism, if that interests you, or the best salve for warts this side of the continent!\""],"effects":[],"options":[{"prompt":"Ask her something else","conditions":[],"results":["village_farm_amelie"]}]},
+            "village_farm_amelie_unusual":{"title": "Chapter 2: Footprints","text":["\"Unusual?\" The young woman laughs","\"Honey, having a stranger on our farm is the most unusual thing that's happened in recent memory.\""],"effects":[],"options":[{"prompt":"Ask her something else","conditions":[],"results":["village_farm_amelie"]}]},
+            "village_farm_amelie_family":{"title": "Chapter 2: Footprints","text":["\"Our family? It's an interesting enough tale, I suppose.\" The young woman begins","\"My father is Arnoulf, of House Brolette - yes those Brolettes.\"","\"In the days of the Aremeic Order, our family laid claim to the thrones of half the Order's colonial territories on the East continent!\"","\"My father doesn't like to talk about it, and there's not much left of the family, but it's a proud history to bear.\""],"effects":[],"options":[{"prompt":"Ask her something else","conditions":[],"results":["village_farm_amelie"]}]},
+            "village_farm_amelie_self":{"title": "Chapter 2: Footprints","text":["\"Me? I am Amelie of House Brolette; I'm the village herbalist, not that my skills net me much respect in this hole of a village.\"","\"I provide these simpletons with medicines and salves, but nobody can see past my choice of clothes. Nobody would bat an eye in the cities.\"","\"Ever try foraging for mushrooms in 3 layers of frill and fuss? You'd never catch me in some silly long dress.\""],"effects":[],"options":[{"prompt":"Ask her something else","conditions":[],"results":["village_farm_amelie"]}]},
+            "village_farm_search":{"title": "Chapter 2: Footprints","text":["You take a good look around the room.","In the corner are a pair of small beds, one of which clearly hasn't seem much use of late.","In the kitchen, various herbs and mushrooms hang from the ceiling.","Supplies for alchemical brewing are strewn about."],"effects":[],"options":[{"prompt":"Speak to the young woman","conditions":[],"results":["village_farm_amelie"]},{"prompt":"Leave the farm","conditions":[],"results":["village"]}]},
+            
+            "village_dave_not_home":{"title": "Chapter 2: Footprints","text":["You arrive at Dave's home at the edge of the village, a once beautiful home, but clearly neglected of late.","Vines and weeds grow rampant across the property, and the windows sit greased and dusty.","","No candles burn within, and the door is locked tight."],"effects":[],"options":[{"prompt":"Leave Dave's house","conditions":[],"results":["village"]}]},
+            "village_dave":{"title": "Chapter 2: Footprints","text":["You arrive at Dave's home at the edge of the village, a once beautiful home, but clearly neglected of late.","Vines and weeds grow rampant across the property, and the windows sit greased and dusty.","","The door sits open, so you walk inside. Dave sits alone on a dusty old wooden chair.","All around him sits dusty disused brushes, paints and canvases"],"effects":[],"options":[{"prompt":"Talk to Dave","conditions":[],"results":["village_dave_dave"]},{"prompt":"Leave Dave's house","conditions":[],"results":["village"]}]},
+            "village_dave_dave":{"title": "Chapter 2: Footprints","text":["\"So, ye needed a weapon, ey? I do have me gear from the war that I'd be happy enough to never see again.\"","\"Not usually in the habit of armin' strangers though, so ye'd best have a good cause.\"","\"My wife, god rest 'er soul didn't approve of wanton violence.\""],"effects":[],"options":[{"prompt":"Tell him you are hunting a vampire hiding amongst the villagers","conditions":[],"results":["village_dave_dave_honest"]},{"prompt":"Tell him you are avenging the death of a loved one","conditions":[],"results":["village_dave_dave_lie"]},{"prompt":"Tell him to give you the gear or you'll take it by force","conditions":[],"results":["village_dave_dave_threaten"]},{"prompt":"Leave Dave's house","conditions":[],"results":["village"]}]},
+            "village_dave_dave_honest":{"title": "Chapter 2: Footprints","text":["Dave sits in silence for an awkwardly long time.","\"There's something in the air today; as if the sky itself were thick with dread.\"","\"I believe you, stranger. My sword and armour are yours. They ain't much but I hope they help you.\"","\"All I ask is that you lay these here flowers at my family grave. The walk to the temple is too much for this old soldier.\"","\"Ye'll see it marked by the clan name Audemars.\"","","The rusted old blade would never be able to strike a killing blow against such a powerful vampire;","But it may grant some protection, at the very least."],"effects":[{"type":"choices","target":"made_dave_sad","value":true},{"type":"inventory","target":"rusty_sword","value":true},{"type":"inventory","target":"old_armor","value":true},{"type":"inventory","target":"dave_flowers","value":true}],"options":[{"prompt":"Leave Dave's house","conditions":[],"results":["village"]}]},
+            "village_dave_dave_lie":{"title": "Chapter 2: Footprints","text":["Dave slowly shakes his head.","\"Sorry stranger, I cannae in good conscience lend creed to vengeance. I hope you find a way to see another path.\"","\"In the meantime, I'm afraid ye'll need to leave.\""],"effects":[{"type":"choices","target":"made_dave_sad","value":true}],"options":[{"prompt":"Leave Dave's house","conditions":[],"results":["village"]}]},
+            "village_dave_dave_threaten":{"title": "Chapter 2: Footprints","text":["Without any warning, the giant of a man hits you on the side of the head with one mighty punch.","You awaken outside the house, with the door now securely locked."],"effects":[{"type":"choices","target":"made_dave_sad","value":true}],"options":[{"prompt":"Leave Dave's house","conditions":[],"results":["village"]}]},
+            
+            "village_mill":{"title": "Chapter 2: Footprints","text":["You arrive at the small village mill, and step inside","The stone brick building is small, but impressive - clearly much older than the wooden structures you've seen thus far.","Inside are a man, expertly kneading a large ball of dough, and a woman on a wooden rocking chair repairing a tunic.","","\"Ah, we heard there were strangers about!\" says the man; \"Something ye' knead? Some bread for the road perhaps?\""],"effects":[],"options":[{"prompt":"Take the man up on his offer of bread","conditions":[{"type":"choices","target":"accepted_bread","value":false}],"results":["village_mill_bread"]},{"prompt":"Ask if they have garlic bread instead","conditions":[{"type":"choices","target":"accepted_bread","value":false}],"results":["village_mill_garlic"]},{"prompt":"Ask about their family","conditions":[],"results":["village_mill_family"]},{"prompt":"Ask if they've seen anything unusual","conditions":[],"results":["village_mill_unusual"]},{"prompt":"Leave the mill","conditions":[],"results":["village"]}]},
+            "village_mill_family":{"title": "Chapter 2: Footprints","text":["\"We're the Fouchers sir - Nils and Silvia. Our family's been in these parts since the colonial days of the Order.\"","\"We've 3 little 'uns - Dylan is 8, and the twins are fresh out the oven.\""],"effects":[],"options":[{"prompt":"Ask something else","conditions":[],"results":["village_mill"]}]},
+            "village_mill_unusual":{"title": "Chapter 2: Footprints","text":["\"Not a thing of interest has happened in these here parts in many a year, sir.\"","\"If it's adventure ye' seek, you've picked the wrong village I'm afraid.\""],"effects":[],"options":[{"prompt":"Ask something else","conditions":[],"results":["village_mill"]}]},
+            "village_mill_garlic":{"title": "Chapter 2: Footprints","text":["You narrow your eyes in suspicion as the question leaves your lips","The man shakes his head; \"Nay, stranger. I've a terrible allergy to garlic, so none of that 'ere.\""],"effects":[],"options":[{"prompt":"Ask something else","conditions":[],"results":["village_mill"]}]},
+            "village_mill_bread":{"title": "Chapter 2: Footprints","text":["The baker hands you a fresh loaf of bread, hot out of the oven,","The family then looks on in horror as you devour the entire loaf in one go like some kind of feral beast"],"effects":[{"type":"choices","target":"accepted_bread","value":true},{"type":"status","target":"stamina","operation":"add","value":99}],"options":[{"prompt":"Ask something else","conditions":[],"results":["village_mill"]}]},
+            
+            "village_school_outside":{"title": "Chapter 2: Footprints","text":["You arrive outside the small schoolhouse. A humble shack sits next to it, likely a home for the teacher and her family.","A gaggle of annoying-looking children mill about uselessly outside the school."],"effects":[],"options":[{"prompt":"Search the shack","conditions":[],"results":["village_school_shack"]},{"prompt":"Search the schoolhouse","conditions":[],"results":["village_school_inside"]},{"prompt":"Harass the annoying children","conditions":[],"results":["village_school_children"]},{"prompt":"Leave the school","conditions":[],"results":["village"]}]},
+            "village_school_inside":{"title": "Chapter 2: Footprints","text":["You walk into the schoolhouse.","A woman - the teacher, presumably - is sat at the far side of the room.","On her head sits the biggest hat you have ever seen. This sunhat has a wingspan that could make a dragon jealous."],"effects":[],"options":[{"prompt":"Ask her about herself","conditions":[],"results":[{"conditions":[{"type":"choices","target":"insulted_teacher","value":true}],"target":"village_school_items"},"village_school_teacher"]},{"prompt":"Ask if she's seen anything unusual","conditions":[],"results":[{"conditions":[{"type":"choices","target":"insulted_teacher","value":true}],"target":"village_school_items"},"village_school_unusual"]},{"prompt":"Ask about the strange items in her home","conditions":[{"type":"choices","target":"snooped_on_teacher","value":true}],"results":["village_school_items"]},{"prompt":"Ask about her gigantic hat","conditions":[],"results":[{"conditions":[{"type":"choices","target":"insulted_teacher","value":true}],"target":"village_school_items"},"village_school_hat"]},{"prompt":"Look somewhere else","conditions":[],"results":["village_school_outside"]}]},
+            "village_school_items":{"title": "Chapter 2: Footprints","text":["\"What kind of creepy imbecile looks in a young woman's windows at her private things?\"","\"Leave my classroom this instant!\""],"effects":[{"type":"choices","target":"insulted_teacher","value":true}],"options":[{"prompt":"Ask something else","conditions":[],"results":["village_school_inside"]}]},
+            "village_school_unusual":{"title": "Chapter 2: Footprints","text":["\"Besides the usual nonsense from that horrible Brolette girl? Not in a long time.\""],"effects":[],"options":[{"prompt":"Ask something else","conditions":[],"results":["village_school_inside"]}]},
+            "village_school_teacher":{"title": "Chapter 2: Footprints","text":["\"My family? nothing special, I'm afraid. Lost my eldest and my husband two winters back to red fever.\"","\"Milton is outside with his friends - mercifully he was too young to remember it.\""],"effects":[],"options":[{"prompt":"Ask something else","conditions":[],"results":["village_school_inside"]}]},
+            "village_school_hat":{"title": "Chapter 2: Footprints","text":["The young woman laughs as she grips her cap","\"I've a skin condition, always have - the sun; my skin burns so easily\"","\"I use the hat to protect myself, along with a salve I make at home!\"","\"I could buy it from that Brolette girl, but I'd rather not have to interact with that one.\""],"effects":[],"options":[{"prompt":"Ask something else","conditions":[],"results":["village_school_inside"]}]},
+            "village_school_shack":{"title": "Chapter 2: Footprints","text":["The shack is locked but you look in through the window.","You can see strange objects strewn about - a dagger, various herbs, an ornate pendant covered in runes, and a pouch of white powder"],"effects":[{"type":"choices","target":"snooped_on_teacher","value":true}],"options":[{"prompt":"Look somewhere else","conditions":[],"results":["village_school_outside"]}]},
+            "village_school_children":{"title": "Chapter 2: Footprints","text":["You walk up to the children and kick sand at them like a complete arsehole.","One child begins to try, while another kicks you in the groin and runs away giggling."],"effects":[{"type":"status","target":"stamina","operation":"add","value":-1}],"options":[{"prompt":"Look somewhere else","conditions":[],"results":["village_school_outside"]}]},
+            
+            "church_bear":{"title": "Chapter 3: The Hunter, Hunted","text":["You head down the wooded path towards the temple.","As you enter the forest, the air around you grows cold and the sun itself begins to dim.","","You hear a mighty roar from behind you."],"effects":[],"options":[{"prompt":"Turn around and face your foe","conditions":[],"results":[{"conditions":[{"type":"inventory","target":"rusty_sword","value":true}],"target":"church_bear_armed"},"church_bear_unarmed"]},{"prompt":"Run away from the creature","conditions":[],"results":["church_bear_coward"]}]},
+            "church_bear_coward":{"title": "Chapter 3: The Hunter, Hunted","text":["You bravely run away, as fast as you can into the woods, towards the temple, and Gaylen the town priest.","You arrive at the temple late in the afternoon, and see the priest standing outside the ornate stone building.","Nearby you can see a graveyard, with a massive ornate tomb at its center.","The priest approaches you:","\"Greetings, stranger. How may I be of service?\""],"effects":[],"options":[{"prompt":"Speak to the priest","conditions":[],"results":["church_gaylen"]}]},
+            "church_bear_unarmed":{"title": "Chapter 3: The Hunter, Hunted","text":["You turn to face your foe and come face to crotch with a 12ft tall monster of a bear.","You instinctively reach for your blade, but of course, you don't have one.","You start to run away, but the mighty beast - no doubt the shapeshifter himself - rips you open with a mighty swipe of his paw","","It will be daybreak before you are found by the villagers, and after numerous surgeries and expensive physiotherapy you regain the ability to walk","But by then, when no one was looking, Zarban took forty cakes.","That's as many as four tens.","And that's terrible.","","GAME OVER! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "church_bear_armed":{"title": "Chapter 3: The Hunter, Hunted","text":["You turn to face your foe and come face to crotch with a 12ft tall monster of a bear.","You instinctively reach for your blade, and draw the corroded shortsword you were given.","The mighty beast looms over you, menace in his eyes. The beast drips with magic and malice;","You know in your heart that can only be Zarban the undead shapeshifter himself."],"effects":[],"options":[{"prompt":"Run away very very quickly","conditions":[],"results":["church_bear_armed_coward"]},{"prompt":"Go for the beast's head","conditions":[],"results":["church_bear_armed_stupid"]},{"prompt":"Slash at the beast's legs","conditions":[],"results":["church_bear_armed_legs"]}]},
+            "church_bear_armed_coward":{"title": "Chapter 3: The Hunter, Hunted","text":["You start to run away, but the mighty beast rips your back open with a mighty swipe of his paw","","It will be daybreak before you are found by the villagers, and after numerous surgeries and expensive physiotherapy you regain the ability to walk","But by then, Zarban is long gone. He would later go on to start a mediocre reaction channel on TikTok.","","GAME OVER! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "church_bear_armed_stupid":{"title": "Chapter 3: The Hunter, Hunted","text":["You stab directly upwards into the beast's head, aiming to finish this now and here.","The common steel of the blade cannot deal a finishing blow against such a powerful magical foe, however, and the metal passes right through the beast.","While you leave your flank fully exposed, Zarban disembowels you with ease.","Zarban would later go on to become a successful corporate attorney.","","GAME OVER! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "church_bear_armed_legs":{"title": "Chapter 3: The Hunter, Hunted","text":["You slash at the beast's legs, causing him to roar in pain. You can't kill him with this sword, but even if he takes on another form,","It will take days for him to heal from such a blow."],"effects":[{"type":"choices","target":"injured_zarban","value":true}],"options":[{"prompt":"Escape while the beast reels from the blow","conditions":[],"results":["church_bear_armed_escape"]},{"prompt":"Go for the beast's head","conditions":[],"results":["church_bear_armed_stupid"]}]},
+            "church_bear_armed_escape":{"title": "Chapter 3: The Hunter, Hunted","text":["With the beast's legs injured, you take your chance to escape into the woods, towards the temple, and Gaylen the town priest.","You arrive at the temple late in the afternoon, and see the priest standing outside the ornate stone building.","Nearby you can see a graveyard, with a massive ornate tomb at its center.","The priest approaches you:","\"Greetings, stranger. How may I be of service?\""],"effects":[],"options":[{"prompt":"Speak to the priest","conditions":[],"results":["church_gaylen"]},{"prompt":"There is nothing for me here, let's return to town","conditions":[{"type":"inventory","target":"rusty_sword","value":true}],"results":["jaccuse_arrival"]}]},
+            
+            "church_gaylen":{"title": "Chapter 3: The Hunter, Hunted","text":["\"Yes, stranger, how can I help you?\""],"effects":[],"options":[{"prompt":"Ask for help against the evil vampire","conditions":[{"type":"inventory","target":"ancient_armor","value":false}],"results":[{"conditions":[{"type":"choices","target":"impressed_gaylen","value":true}],"target":"church_gaylen_success"},"church_gaylen_fail"]},{"prompt":"Ask about the temple","conditions":[],"results":["church_gaylen_temple"]},{"prompt":"Ask the priest about Dave's family grave","conditions":[{"type":"inventory","target":"dave_flowers","value":true}],"results":["church_gaylen_impressed"]},{"prompt":"Go to the graveyard","conditions":[],"results":["church_graveyard"]}]},
+            "church_gaylen_success":{"title": "Chapter 3: The Hunter, Hunted","text":["The priest stays silent for a time, appearing to pray to himself. Finally he speaks;","\"As you are a friend to the village, and an honourable soul, I grant you the protection of the great Edwin Rothsten.\"","\"May the ancient vampire hunter's armour protect you. And take this key, you'll find Moonsbane in the hunter's tomb.\""],"effects":[{"type":"inventory","target":"ancient_armor","value":true},{"type":"inventory","target":"hunter_tomb_key","value":true},{"type":"inventory","target":"old_armor","value":false},{"type":"choices","target":"learnt_about_hunter","value":true}],"options":[{"prompt":"Ask the priest something else","conditions":[],"results":["church_gaylen"]},{"prompt":"Go to the graveyard","conditions":[],"results":["church_graveyard"]}]},
+            "church_gaylen_fail":{"title": "Chapter 3: The Hunter, Hunted","text":["\"I will say a prayer of blessing for you stranger.\"","\"Sadly I have only your word, so I cannot help you more than that. Good luck to you.\""],"effects":[],"options":[{"prompt":"Ask the priest something else","conditions":[],"results":["church_gaylen"]},{"prompt":"Go to the graveyard","conditions":[],"results":["church_graveyard"]}]},
+            "church_gaylen_temple":{"title": "Chapter 3: The Hunter, Hunted","text":["\"This temple was founded over 700 years ago during the time of the Aremeic Order.\"","\"It was personally commissioned by the famed vampire hunter, and knight of the order Edwin Rothsten.\"","\"The legend himself is entombed here, where himself and his enchanted blade Moonsbane can watch over us.\"","","The priest points to the ornate tomb in the centre of the graveyard - you think to yourself that it might be prudent to 'borrow' Moonsbane."],"effects":[{"type":"choices","target":"learnt_about_hunter","value":true}],"options":[{"prompt":"Ask the priest something else","conditions":[],"results":["church_gaylen"]},{"prompt":"Go to the graveyard","conditions":[],"results":["church_graveyard"]}]},
+            "church_gaylen_impressed":{"title": "Chapter 3: The Hunter, Hunted","text":["You show the flowers to the priest, who smiles warmly at you.","\"Ah! A friend of lord Audemars is a friend of mine. I will bless these and place them at his family grave for you.\""],"effects":[{"type":"inventory","target":"dave_flowers","value":false},{"type":"choices","target":"impressed_gaylen","value":true}],"options":[{"prompt":"Ask the priest something else","conditions":[],"results":["church_gaylen"]},{"prompt":"Go to the graveyard","conditions":[],"results":["church_graveyard"]}]},
+            
+            "church_graveyard":{"title": "Chapter 3: The Hunter, Hunted","text":["You arrive at the humble graveyard. In its center lies an ornate tomb marked 'Edwin Rothsten, Hunter of the Aremeic Order'","The family grave of the Audemars clan sits nearby."],"effects":[],"options":[{"prompt":"Place the flower's on Dave Audemars' family grave","conditions":[{"type":"inventory","target":"dave_flowers","value":true}],"results":["church_graveyard_flowers"]},{"prompt":"Retrieve the sword from the hunter's tomb","conditions":[{"type":"choices","target":"learnt_about_hunter","value":true}],"results":[{"conditions":[{"type":"inventory","target":"hunter_tomb_key","value":true}],"target":"church_graveyard_key"},"church_graveyard_breakin"]},{"prompt":"Return to the village, and confront Zarban","conditions":[{"type":"inventory","target":"rusty_sword","value":true}],"results":["jaccuse_arrival"]}]},
+            "church_graveyard_flowers":{"title": "Chapter 3: The Hunter, Hunted","text":["You place the flowers at the foot of the family gravestone, and say a short prayer.","You feel content, your promise fulfilled."],"effects":[{"type":"inventory","target":"dave_flowers","value":false}],"options":[{"prompt":"Retrieve the sword from the hunter's tomb","conditions":[{"type":"choices","target":"learnt_about_hunter","value":true}],"results":[{"conditions":[{"type":"inventory","target":"hunter_tomb_key","value":true}],"target":"church_graveyard_key"},"church_graveyard_breakin"]},{"prompt":"Return to the village, and confront Zarban","conditions":[{"type":"inventory","target":"rusty_sword","value":true}],"results":["jaccuse_arrival"]}]},
+            "church_graveyard_key":{"title": "Chapter 3: The Hunter, Hunted","text":["You walk up to the tomb, and use a the key to open the ornate lock.","You pick up the ancient runic sword and feel its holy power flow through you - Zarban's end is at hand!"],"effects":[{"type":"inventory","target":"magic_sword","value":true},{"type":"inventory","target":"rusty_sword","value":false},{"type":"inventory","target":"hunter_tomb_key","value":false}],"options":[{"prompt":"Return to the village, and confront Zarban","conditions":[],"results":["jaccuse_arrival"]}]},
+            "church_graveyard_breakin":{"title": "Chapter 3: The Hunter, Hunted","text":["You walk up to the tomb, and use a nearby rock to smash open the ornate lock.","You pick up the ancient runic sword and equip it - Zarban's end is at hand!","You'll probably return it when you are finished. Maybe."],"effects":[{"type":"inventory","target":"magic_sword","value":true},{"type":"inventory","target":"rusty_sword","value":false}],"options":[{"prompt":"Return to the village, and confront Zarban","conditions":[],"results":["jaccuse_arrival"]}]},
+            
+            "jaccuse_arrival":{"title": "Chapter 4: J'Accuse!","text":["You arrive back in the village at midnight. The stench of evil still hangs in the air - it is time to confront Zarban.","But which villager has been replaced by the vile sorcerer?"],"effects":[{"type":"status","target":"stamina","operation":"add","value":-1}],"options":[{"prompt":"Go to the tavern","conditions":[],"results":[{"conditions":[{"type":"status","target":"stamina","operation":"eq","value":0}],"target":"jaccuse_end_stamina"},"jaccuse_tavern"]},{"prompt":"Go to the farm","conditions":[],"results":[{"conditions":[{"type":"status","target":"stamina","operation":"eq","value":0}],"target":"jaccuse_end_stamina"},{"conditions":[{"type":"choices","target":"injured_zarban","value":true}],"target":"jaccuse_farm_scar"},"jaccuse_farm"]},{"prompt":"Go to Dave's house","conditions":[],"results":[{"conditions":[{"type":"status","target":"stamina","operation":"eq","value":0}],"target":"jaccuse_end_stamina"},"jaccuse_dave"]},{"prompt":"Go to the schoolhouse","conditions":[],"results":[{"conditions":[{"type":"status","target":"stamina","operation":"eq","value":0}],"target":"jaccuse_end_stamina"},"jaccuse_school"]}]},
+            "jaccuse_end_stamina":{"title": "Chapter 4: J'Accuse!","text":["Exhausted and thirsty, you collapse to the ground. The villagers find you, and bring you to the inn to recover.","Unfortunately, by then Zarban is long gone, and the trail cold. He will later go on to form a knitting circle that only makes ugly sweaters for puppies.","","GAME OVER! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+
+            "jaccuse_tavern":{"title": "Chapter 4: J'Accuse!","text":["You enter the tavern. The bartender stands at his usual spot, and Dave drinks alone in the corner."],"effects":[],"options":[{"prompt":"Confront the bartender","conditions":[],"results":["jaccuse_bartender"]},{"prompt":"Confront Dave","conditions":[],"results":["jaccuse_dave"]},{"prompt":"Go somewhere else","conditions":[],"results":["jaccuse_arrival"]}]},
+            "jaccuse_bartender":{"title": "Chapter 4: J'Accuse!","text":["You approach the bartender, who smiles warmly on your approach. The smile fades as you draw your blade.","As your sword plunges into the man's chest, and you see the light leave his eyes, you know in your heart you've chosen wrong.","The innocent man falling to the ground before you is the last thing you ever see as a drunken, angry Dave breaks your neck from behind.","Zarban would later escape, and go on to form a boy band that only sings Nickelback covers.","","GAME OVER! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "jaccuse_dave":{"title": "Chapter 4: J'Accuse!","text":["Dave meets your gaze as you approach him, the old soldier recognizing the look in your eyes.","A single tear falls down his cheek as he mutters to himself \"I'm coming home, Amy.\"","As your blade pierces his heart and Dave Audemars dies before you, you know in your heart you've chosen wrong.","A bottle breaks over your head from behind, and you fall to the ground. You'll later awaken just in time for your hanging","Zarban would later escape, and go on to become the world's most successful spam email marketer.","","GAME OVER! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+
+            "jaccuse_farm_scar":{"title": "Chapter 4: J'Accuse!","text":["You arrive at the farm, and find Arnoulf and his daughter working the field by moonlight.","The white frills of Amelie's long dress reflect the dim light of the moon"],"effects":[],"options":[{"prompt":"Confront the old farmer","conditions":[],"results":["jaccuse_farm_arnoulf"]},{"prompt":"Confront the farmer's daughter","conditions":[],"results":[{"conditions":[{"type":"inventory","target":"rusty_sword","value":true}],"target":"jaccuse_farm_amelie_nosword"},{"conditions":[{"type":"inventory","target":"ancient_armor","value":true}],"target":"jaccuse_farm_amelie_good"},{"conditions":[{"type":"inventory","target":"magic_sword","value":true}],"target":"jaccuse_farm_amelie_noarmor"}]},{"prompt":"Go somewhere else","conditions":[],"results":["jaccuse_arrival"]}]},
+            "jaccuse_farm":{"title": "Chapter 4: J'Accuse!","text":["You arrive at the farm, and find Arnoulf and his daughter working the field by moonlight."],"effects":[],"options":[{"prompt":"Confront the old farmer","conditions":[],"results":["jaccuse_farm_arnoulf"]},{"prompt":"Confront the farmer's daughter","conditions":[],"results":[{"conditions":[{"type":"inventory","target":"rusty_sword","value":true}],"target":"jaccuse_farm_amelie_nosword"},{"conditions":[{"type":"inventory","target":"ancient_armor","value":true}],"target":"jaccuse_farm_amelie_good"},{"conditions":[{"type":"inventory","target":"magic_sword","value":true}],"target":"jaccuse_farm_amelie_noarmor"}]},{"prompt":"Go somewhere else","conditions":[],"results":["jaccuse_arrival"]}]},
+            "jaccuse_farm_arnoulf":{"title": "Chapter 4: J'Accuse!","text":["You approach the farmer as he toils under the moonlight, and draw your blade.","The farmer scowls as he raises his pitchfork to confront you.","You manage to get one good slash in on the farmer's neck... just as his pitchfork pierces your lungs.","As you both bleed out on the ground, you know you have chosen incorrectly.","Zarban would later go on to invent a new type of toothpaste that causes cavities instead of preventing them.","","GAME OVER! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "jaccuse_farm_amelie_nosword":{"title": "Chapter 4: J'Accuse!","text":["You approach the young woman and draw your sword.","She dons an evil grin and her features twist and distort, as the evil shapeshifter assumes the form of a massive bear!","You hack and slash away to no avail, as the common steel of your rusted blade cannot kill the vampire.","As the old farmer tries in vain to help you, your neck is ripped open by his mighty jaws, and you bleed out onto the moonlit field.","Zarban, revealed and unleashed, will draw strength from the blood of the village by massacring the entire populate of the tiny hamlet.","Renewed by his bloodbath, Zarban escapes into the world, more powerful than ever before.","","The End! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "jaccuse_farm_amelie_noarmor":{"title": "Chapter 4: J'Accuse!","text":["You approach the young woman and draw your sword.","She dons an evil grin and her features twist and distort, as the evil shapeshifter assumes the form of a massive bear!","You stab the vile creature through it's dark heart, but in his dying breath, the evil sorcerer utters a final curse.","As you fall to the ground, the air sucked out of your lungs, and slowly choke to death on your own blood,","You see the evil creature turn to dust before you. You may die, but at least the world is safe from this creature.","The village will remember you as a brave hero.","","The End! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "jaccuse_farm_amelie_good":{"title": "Chapter 4: J'Accuse!","text":["You approach the young woman and draw your sword.","She dons an evil grin and her features twist and distort, as the evil shapeshifter assumes the form of a massive bear!","You stab the vile creature through it's dark heart, but in his dying breath, the evil sorcerer utters a final curse.","Your enchanted armour glows bright with ancient runic magic as the vampire's curse is nullified.","The creature contorts and screeches as it is reduced to dust and ash - the vile one has been vanquished!","","Congratulations! You are victorious!"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+
+            "jaccuse_davehouse":{"title": "Chapter 4: J'Accuse!","text":["You arrive at Dave's home at the edge of the village, a once beautiful home, but clearly neglected of late.","Vines and weeds grow rampant across the property, and the windows sit greased and dusty.","","No candles burn within, and the door is locked tight."],"effects":[],"options":[{"prompt":"Go somewhere else","conditions":[],"results":["jaccuse_arrival"]}]},
+
+            "jaccuse_school":{"title": "Chapter 4: J'Accuse!","text":["You kick down the door of the teacher's humble shack and burst in, sword drawn.","The teacher and her 6-year-old son scream as you run inside."],"effects":[],"options":[{"prompt":"Confront the teacher","conditions":[],"results":["jaccuse_school_teacher"]},{"prompt":"Confront the son","conditions":[],"results":["jaccuse_school_teacher_child"]},{"prompt":"Go somewhere else","conditions":[],"results":["jaccuse_arrival"]}]},
+            "jaccuse_school_teacher":{"title": "Chapter 4: J'Accuse!","text":["You expertly swing your blade, and take the young woman's head clean off!","She dies instantly, since she was a schoolteacher - not a vampire.","You run off as the young boy cries and vows to avenge his mother.","You spend the rest of your days in hiding, as Zarban goes on to invent a new type of coffee that tastes like burnt popcorn and expired milk.","","The End! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "jaccuse_school_teacher_child":{"title": "Chapter 4: J'Accuse!","text":["You expertly swing your blade at the young child, but the mother blocks your blade with her body.","The young woman bleeds out in seconds, and the small cut on the boy's arm reveals the pure red blood of a human child.","You run off as the young boy cries and vows to avenge his mother.","You spend the rest of your days in hiding, as Zarban goes on to open a bakery that only sells cakes made with vegetables instead of sugar.","","The End! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+
+            "jaccuse_mill":{"title": "Chapter 4: J'Accuse!","text":["You burst into the stone building and charge to the far side of the room, where the family sits around a fireplace.","They look up in confused horror at the unexpected home invasion.","A bandage is wrapped around the baker's hand, clearly a fresh wound."],"effects":[],"options":[{"prompt":"Confront the baker","conditions":[],"results":["jaccuse_mill_baker"]},{"prompt":"Confront the seamstress","conditions":[],"results":["jaccuse_mill_seamstress"]},{"prompt":"Confront the 8 year old boy","conditions":[],"results":["jaccuse_mill_boy"]},{"prompt":"Confront the twins","conditions":[],"results":["jaccuse_mill_babies"]},{"prompt":"Go somewhere else","conditions":[],"results":["jaccuse_arrival"]}]},
+            "jaccuse_mill_baker":{"title": "Chapter 4: J'Accuse!","text":["You draw your blade and eviscerate the baker and father.","As his guts spill all over the floor of the mill, the grieving family holds you down until the authorities arrive.","You will be sentenced to life in prison without parole, from where you will be blissfully unaware of all the puppies Zarban would later eat.","","The End! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "jaccuse_mill_seamstress":{"title": "Chapter 4: J'Accuse!","text":["You draw your blade and stab the young mother of 3 through the eye.","As she falls to the ground and you realize the gravity of your mistake, a cast iron pan to the forehead puts you out of everyone's misery.","Zarban would later go on to traffic in baby spines for fun and profit.","","The End! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "jaccuse_mill_boy":{"title": "Chapter 4: J'Accuse!","text":["You draw your sword and slash at the young boy's throat.","The boy uses his arms to shield himself, and survives - barely - your blow.","The pure red blood of a human tells you that a mistake has been made as his mother pierces your brain with a fork through the eye.","Zarban would later go on to rethink his life and become a nun. Just kidding he eats children now.","","The End! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]},
+            "jaccuse_mill_babies":{"title": "Chapter 4: J'Accuse!","text":["You draw your sword and approach the sleeping newborn babies.","You stop yourself from murdering two babies at the last second and decide to go to the tavern to rethink your life.","The close-call has scarred you, and you no longer wish to hunt vampires.","Zarban would later go on to invest heavily into dogecoin.","","The End! Try again?"],"effects":[],"options":[{"prompt":"New game","conditions":[],"results":["intro_cave1"]}]}
+        }
+    }`
+  CALL #2C7C6CFC6E694760 1
+  CALL #818B07AC0019FEA7 2
+; fn arbitrary_arbitrary
+  POOL 0000000F
+; {
+;     save = global('__zarban_savedata')
+;     switch condition['type'] {
+;         // Check a status condition
+;         "status" => switch condition['operation'] {
+;             "lt" => save['status'][condition['target']]['value'] < condition['value'],
+;             "lte" => save['status'][condition['target']]['value'] <= condition['value'],
+;             "eq" => save['status'][condition['target']]['value'] == condition['value'],
+;             "neq" => save['status'][condition['target']]['value'] != condition['value'],
+;             "gte" => save['status'][condition['target']]['value'] >= condition['value'],
+;             "gt" => save['status'][condition['target']]['value'] > condition['value'],
+;             _ => false
+;         },
+; 
+;         // Check an inventory condition
+;         "inventory" => save['inventory'][condition['target']]['equipped'] == condition['value'],
+; 
+;         // Check a choices condition
+;         "choices" => {
+;             v = global('__zarban_savedata')['choices'] contains condition['target']
+;             condition['value'] == v
+;         },
+; 
+;         // Invalid condition type
+;         _ => false
+;     }
+; }
+; save = global('__zarban_savedata')
+; global('__zarban_savedata')
+; '__zarban_savedata'
+  PSHC `__zarban_savedata`
+  CALL #90B4D2FF6ACB88AF 1
+; save
+  REF VAR_lumberjack_noodle
+  WREF
+  POP
+; switch condition['type'] {
+;         // Check a status condition
+;         "status" => switch condition['operation'] {
+;             "lt" => save['status'][condition['target']]['value'] < condition['value'],
+;             "lte" => save['status'][condition['target']]['value'] <= condition['value'],
+;             "eq" => save['status'][condition['target']]['value'] == condition['value'],
+;             "neq" => save['status'][condition['target']]['value'] != condition['value'],
+;             "gte" => save['status'][condition['target']]['value'] >= condition['value'],
+;             "gt" => save['status'][condition['target']]['value'] > condition['value'],
+;             _ => false
+;         },
+; 
+;         // Check an inventory condition
+;         "inventory" => save['inventory'][condition['target']]['equipped'] == condition['value'],
+; 
+;         // Check a choices condition
+;         "choices" => {
+;             v = global('__zarban_savedata')['choices'] contains condition['target']
+;             condition['value'] == v
+;         },
+; 
+;         // Invalid condition type
+;         _ => false
+;     }
+; condition['type']
+; condition
+  REF VAR_salamander_octopus
+; 'type'
+  PSHC `type`
+  IDEX
+  DUP
+; "status"
+  PSHC `status`
+  EQ
+  JMPT JUMP_arbitrary_arbitrary
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+  POP
+; switch condition['operation'] {
+;             "lt" => save['status'][condition['target']]['value'] < condition['value'],
+;             "lte" => save['status'][condition['target']]['value'] <= condition['value'],
+;             "eq" => save['status'][condition['target']]['value'] == condition['value'],
+;             "neq" => save['status'][condition['target']]['value'] != condition['value'],
+;             "gte" => save['status'][condition['target']]['value'] >= condition['value'],
+;             "gt" => save['status'][condition['target']]['value'] > condition['value'],
+;             _ => false
+;         }
+; condition['operation']
+; condition
+  REF VAR_salamander_octopus
+; 'operation'
+  PSHC `operation`
+  IDEX
+  DUP
+; "lt"
+  PSHC `lt`
+  EQ
+  JMPT JUMP_octopus_cabbage
+  JMP JUMP_bananas_dolphin
+JUMP_octopus_cabbage:
+  POP
+; save['status'][condition['target']]['value'] < condition['value']
+; save['status'][condition['target']]['value']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; condition['target']
+; condition
+  REF VAR_salamander_octopus
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'value'
+  PSHC `value`
+  IDEX
+; condition['value']
+; condition
+  REF VAR_salamander_octopus
+; 'value'
+  PSHC `value`
+  IDEX
+  LT
+  JMP JUMP_hedgehog_pointbreak
+JUMP_bananas_dolphin:
+  DUP
+; "lte"
+  PSHC `lte`
+  EQ
+  JMPT JUMP_penguin_alabaster
+  JMP JUMP_cabbage_umbrella
+JUMP_penguin_alabaster:
+  POP
+; save['status'][condition['target']]['value'] <= condition['value']
+; save['status'][condition['target']]['value']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; condition['target']
+; condition
+  REF VAR_salamander_octopus
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'value'
+  PSHC `value`
+  IDEX
+; condition['value']
+; condition
+  REF VAR_salamander_octopus
+; 'value'
+  PSHC `value`
+  IDEX
+  LE
+  JMP JUMP_hedgehog_pointbreak
+JUMP_cabbage_umbrella:
+  DUP
+; "eq"
+  PSHC `eq`
+  EQ
+  JMPT JUMP_jellybean_grapefruit
+  JMP JUMP_quarantine_hedgehog
+JUMP_jellybean_grapefruit:
+  POP
+; save['status'][condition['target']]['value'] == condition['value']
+; save['status'][condition['target']]['value']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; condition['target']
+; condition
+  REF VAR_salamander_octopus
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'value'
+  PSHC `value`
+  IDEX
+; condition['value']
+; condition
+  REF VAR_salamander_octopus
+; 'value'
+  PSHC `value`
+  IDEX
+  EQ
+  JMP JUMP_hedgehog_pointbreak
+JUMP_quarantine_hedgehog:
+  DUP
+; "neq"
+  PSHC `neq`
+  EQ
+  JMPT JUMP_dolphin_jellybean
+  JMP JUMP_kangaroo_kangaroo
+JUMP_dolphin_jellybean:
+  POP
+; save['status'][condition['target']]['value'] != condition['value']
+; save['status'][condition['target']]['value']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; condition['target']
+; condition
+  REF VAR_salamander_octopus
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'value'
+  PSHC `value`
+  IDEX
+; condition['value']
+; condition
+  REF VAR_salamander_octopus
+; 'value'
+  PSHC `value`
+  IDEX
+  NE
+  JMP JUMP_hedgehog_pointbreak
+JUMP_kangaroo_kangaroo:
+  DUP
+; "gte"
+  PSHC `gte`
+  EQ
+  JMPT JUMP_rhubarb_lumberjack
+  JMP JUMP_pointbreak_marmalade
+JUMP_rhubarb_lumberjack:
+  POP
+; save['status'][condition['target']]['value'] >= condition['value']
+; save['status'][condition['target']]['value']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; condition['target']
+; condition
+  REF VAR_salamander_octopus
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'value'
+  PSHC `value`
+  IDEX
+; condition['value']
+; condition
+  REF VAR_salamander_octopus
+; 'value'
+  PSHC `value`
+  IDEX
+  GE
+  JMP JUMP_hedgehog_pointbreak
+JUMP_pointbreak_marmalade:
+  DUP
+; "gt"
+  PSHC `gt`
+  EQ
+  JMPT JUMP_lumberjack_noodle
+  JMP JUMP_salamander_octopus
+JUMP_lumberjack_noodle:
+  POP
+; save['status'][condition['target']]['value'] > condition['value']
+; save['status'][condition['target']]['value']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; condition['target']
+; condition
+  REF VAR_salamander_octopus
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'value'
+  PSHC `value`
+  IDEX
+; condition['value']
+; condition
+  REF VAR_salamander_octopus
+; 'value'
+  PSHC `value`
+  IDEX
+  GT
+  JMP JUMP_hedgehog_pointbreak
+JUMP_salamander_octopus:
+  POP
+; false
+  PUSH false
+JUMP_hedgehog_pointbreak:
+  JMP JUMP_alabaster_penguin
+JUMP_grapefruit_bananas:
+  DUP
+; "inventory"
+  PSHC `inventory`
+  EQ
+  JMPT JUMP_marmalade_quarantine
+  JMP JUMP_tangerine_rhubarb
+JUMP_marmalade_quarantine:
+  POP
+; save['inventory'][condition['target']]['equipped'] == condition['value']
+; save['inventory'][condition['target']]['equipped']
+; save
+  REF VAR_lumberjack_noodle
+; 'inventory'
+  PSHC `inventory`
+  IDEX
+; condition['target']
+; condition
+  REF VAR_salamander_octopus
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'equipped'
+  PSHC `equipped`
+  IDEX
+; condition['value']
+; condition
+  REF VAR_salamander_octopus
+; 'value'
+  PSHC `value`
+  IDEX
+  EQ
+  JMP JUMP_alabaster_penguin
+JUMP_tangerine_rhubarb:
+  DUP
+; "choices"
+  PSHC `choices`
+  EQ
+  JMPT JUMP_umbrella_salamander
+  JMP JUMP_noodle_tangerine
+JUMP_umbrella_salamander:
+  POP
+; {
+;             v = global('__zarban_savedata')['choices'] contains condition['target']
+;             condition['value'] == v
+;         }
+; v = global('__zarban_savedata')['choices'] contains condition['target']
+; global('__zarban_savedata')['choices'] contains condition['target']
+; global('__zarban_savedata')['choices']
+; global('__zarban_savedata')
+; '__zarban_savedata'
+  PSHC `__zarban_savedata`
+  CALL #90B4D2FF6ACB88AF 1
+; 'choices'
+  PSHC `choices`
+  IDEX
+; condition['target']
+; condition
+  REF VAR_salamander_octopus
+; 'target'
+  PSHC `target`
+  IDEX
+  CNTN
+; v
+  REF VAR_alabaster_penguin
+  WREF
+  POP
+; condition['value'] == v
+; condition['value']
+; condition
+  REF VAR_salamander_octopus
+; 'value'
+  PSHC `value`
+  IDEX
+; v
+  REF VAR_alabaster_penguin
+  EQ
+  RREF
+  JMP JUMP_alabaster_penguin
+JUMP_noodle_tangerine:
+  POP
+; false
+  PUSH false
+JUMP_alabaster_penguin:
+  RREF
+  RET
+; fn grapefruit_bananas
+  POOL 00000011
+; {
+;     save = global('__zarban_savedata')
+;     switch effect['type'] {
+;         // Apply a status effect
+;         "status" => {
+;             if effect['target'] == 'all' {
+;                 // Global effect - apply to all statuses
+;                 for status in save['status'] {
+;                     __zarban_apply_effect(effect + {'target': status})
+;                 }
+;                 return nil
+;             } else {
+;                 // Apply the effect to a specific status
+;                 switch effect['operation'] {
+;                     "set" => save['status'][effect['target']]['value'] = effect['value'],
+;                     "add" => save['status'][effect['target']]['value'] += effect['value'],
+;                     "add_max" => save['status'][effect['target']]['maximum'] += effect['value'],
+; 
+;                     _ => '' // Invalid operation - do nothing
+;                 }
+; 
+;                 // Ensure the value is within bounds
+;                 value = save['status'][effect['target']]['value']
+;                 maximum = save['status'][effect['target']]['maximum']
+;                 save['status'][effect['target']]['value'] = min([value, maximum])
+;                 save['status'][effect['target']]['value'] = max([value, 0])
+;             }
+;         },
+; 
+;         // Apply an inventory effect
+;         "inventory" => {
+;             if effect['target'] == 'all' {
+;                 // Global effect - apply to all inventory items
+;                 for item in save['inventory'] {
+;                     __zarban_apply_effect(effect + {'target': item})
+;                 }
+;                 return nil
+;             }
+;             else {
+;                 // Apply the effect to a specific inventory item
+;                 save['inventory'][effect['target']]['equipped'] = effect['value']
+;             }
+;         },
+; 
+;         // Apply a choices effect
+;         "choices" => {
+;             if effect['target'] == 'all' {
+;                 // Global effect - apply to all choices
+;                 for choice in global('__zarban_storydata')['choices'] {
+;                     __zarban_apply_effect(effect + {'target': choice})
+;                 }
+;                 return nil
+;             } else {
+;                 // Apply the effect to a specific choice
+;                 if effect['value'] {
+;                     save['choices'] += effect['target']
+;                 } else {
+;                     save['choices'] -= effect['target']
+;                 }
+;             }
+;         },
+; 
+;         // Invalid effect type
+;         _ => nil
+;     }
+; 
+;     // Save the updated data
+;     assign_global('__zarban_savedata', save)
+;     ; // Avoid returning anything, as this function is only used for side effects
+; }
+; save = global('__zarban_savedata')
+; global('__zarban_savedata')
+; '__zarban_savedata'
+  PSHC `__zarban_savedata`
+  CALL #90B4D2FF6ACB88AF 1
+; save
+  REF VAR_lumberjack_noodle
+  WREF
+  POP
+; switch effect['type'] {
+;         // Apply a status effect
+;         "status" => {
+;             if effect['target'] == 'all' {
+;                 // Global effect - apply to all statuses
+;                 for status in save['status'] {
+;                     __zarban_apply_effect(effect + {'target': status})
+;                 }
+;                 return nil
+;             } else {
+;                 // Apply the effect to a specific status
+;                 switch effect['operation'] {
+;                     "set" => save['status'][effect['target']]['value'] = effect['value'],
+;                     "add" => save['status'][effect['target']]['value'] += effect['value'],
+;                     "add_max" => save['status'][effect['target']]['maximum'] += effect['value'],
+; 
+;                     _ => '' // Invalid operation - do nothing
+;                 }
+; 
+;                 // Ensure the value is within bounds
+;                 value = save['status'][effect['target']]['value']
+;                 maximum = save['status'][effect['target']]['maximum']
+;                 save['status'][effect['target']]['value'] = min([value, maximum])
+;                 save['status'][effect['target']]['value'] = max([value, 0])
+;             }
+;         },
+; 
+;         // Apply an inventory effect
+;         "inventory" => {
+;             if effect['target'] == 'all' {
+;                 // Global effect - apply to all inventory items
+;                 for item in save['inventory'] {
+;                     __zarban_apply_effect(effect + {'target': item})
+;                 }
+;                 return nil
+;             }
+;             else {
+;                 // Apply the effect to a specific inventory item
+;                 save['inventory'][effect['target']]['equipped'] = effect['value']
+;             }
+;         },
+; 
+;         // Apply a choices effect
+;         "choices" => {
+;             if effect['target'] == 'all' {
+;                 // Global effect - apply to all choices
+;                 for choice in global('__zarban_storydata')['choices'] {
+;                     __zarban_apply_effect(effect + {'target': choice})
+;                 }
+;                 return nil
+;             } else {
+;                 // Apply the effect to a specific choice
+;                 if effect['value'] {
+;                     save['choices'] += effect['target']
+;                 } else {
+;                     save['choices'] -= effect['target']
+;                 }
+;             }
+;         },
+; 
+;         // Invalid effect type
+;         _ => nil
+;     }
+; effect['type']
+; effect
+  REF VAR_marmalade_quarantine
+; 'type'
+  PSHC `type`
+  IDEX
+  DUP
+; "status"
+  PSHC `status`
+  EQ
+  JMPT JUMP_arbitrary_arbitrary
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+  POP
+; {
+;             if effect['target'] == 'all' {
+;                 // Global effect - apply to all statuses
+;                 for status in save['status'] {
+;                     __zarban_apply_effect(effect + {'target': status})
+;                 }
+;                 return nil
+;             } else {
+;                 // Apply the effect to a specific status
+;                 switch effect['operation'] {
+;                     "set" => save['status'][effect['target']]['value'] = effect['value'],
+;                     "add" => save['status'][effect['target']]['value'] += effect['value'],
+;                     "add_max" => save['status'][effect['target']]['maximum'] += effect['value'],
+; 
+;                     _ => '' // Invalid operation - do nothing
+;                 }
+; 
+;                 // Ensure the value is within bounds
+;                 value = save['status'][effect['target']]['value']
+;                 maximum = save['status'][effect['target']]['maximum']
+;                 save['status'][effect['target']]['value'] = min([value, maximum])
+;                 save['status'][effect['target']]['value'] = max([value, 0])
+;             }
+;         }
+; if effect['target'] == 'all' {
+;                 // Global effect - apply to all statuses
+;                 for status in save['status'] {
+;                     __zarban_apply_effect(effect + {'target': status})
+;                 }
+;                 return nil
+;             } else {
+;                 // Apply the effect to a specific status
+;                 switch effect['operation'] {
+;                     "set" => save['status'][effect['target']]['value'] = effect['value'],
+;                     "add" => save['status'][effect['target']]['value'] += effect['value'],
+;                     "add_max" => save['status'][effect['target']]['maximum'] += effect['value'],
+; 
+;                     _ => '' // Invalid operation - do nothing
+;                 }
+; 
+;                 // Ensure the value is within bounds
+;                 value = save['status'][effect['target']]['value']
+;                 maximum = save['status'][effect['target']]['maximum']
+;                 save['status'][effect['target']]['value'] = min([value, maximum])
+;                 save['status'][effect['target']]['value'] = max([value, 0])
+;             }
+; effect['target'] == 'all'
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+; 'all'
+  PSHC `all`
+  EQ
+  JMPF JUMP_octopus_cabbage
+; {
+;                 // Global effect - apply to all statuses
+;                 for status in save['status'] {
+;                     __zarban_apply_effect(effect + {'target': status})
+;                 }
+;                 return nil
+;             }
+; for status in save['status'] {
+;                     __zarban_apply_effect(effect + {'target': status})
+;                 }
+  SCI
+  MKAR 00000000
+; save['status']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+  SWP
+JUMP_penguin_alabaster:
+  SWP
+  DJNE JUMP_bananas_dolphin
+  JMP JUMP_hedgehog_pointbreak
+JUMP_bananas_dolphin:
+  NEXT
+  REF VAR_tangerine_rhubarb
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;                     __zarban_apply_effect(effect + {'target': status})
+;                 }
+; __zarban_apply_effect(effect + {'target': status})
+; effect + {'target': status}
+; effect
+  REF VAR_marmalade_quarantine
+; {'target': status}
+; status
+  REF VAR_tangerine_rhubarb
+; 'target'
+  PSHC `target`
+  MKOB 00000001
+  ADD
+  CALL #4EE797E226C21812 1
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_penguin_alabaster
+  POP
+  JMP JUMP_cabbage_umbrella
+  PSAR
+JUMP_cabbage_umbrella:
+  SWP
+JUMP_hedgehog_pointbreak:
+  POP
+  SCO
+  POP
+; return nil
+; nil
+  PUSH nil
+  RET
+  RREF
+  JMP JUMP_jellybean_grapefruit
+JUMP_octopus_cabbage:
+; {
+;                 // Apply the effect to a specific status
+;                 switch effect['operation'] {
+;                     "set" => save['status'][effect['target']]['value'] = effect['value'],
+;                     "add" => save['status'][effect['target']]['value'] += effect['value'],
+;                     "add_max" => save['status'][effect['target']]['maximum'] += effect['value'],
+; 
+;                     _ => '' // Invalid operation - do nothing
+;                 }
+; 
+;                 // Ensure the value is within bounds
+;                 value = save['status'][effect['target']]['value']
+;                 maximum = save['status'][effect['target']]['maximum']
+;                 save['status'][effect['target']]['value'] = min([value, maximum])
+;                 save['status'][effect['target']]['value'] = max([value, 0])
+;             }
+; switch effect['operation'] {
+;                     "set" => save['status'][effect['target']]['value'] = effect['value'],
+;                     "add" => save['status'][effect['target']]['value'] += effect['value'],
+;                     "add_max" => save['status'][effect['target']]['maximum'] += effect['value'],
+; 
+;                     _ => '' // Invalid operation - do nothing
+;                 }
+; effect['operation']
+; effect
+  REF VAR_marmalade_quarantine
+; 'operation'
+  PSHC `operation`
+  IDEX
+  DUP
+; "set"
+  PSHC `set`
+  EQ
+  JMPT JUMP_quarantine_hedgehog
+  JMP JUMP_dolphin_jellybean
+JUMP_quarantine_hedgehog:
+  POP
+; save['status'][effect['target']]['value'] = effect['value']
+; effect['value']
+; effect
+  REF VAR_marmalade_quarantine
+; 'value'
+  PSHC `value`
+  IDEX
+; save['status'][effect['target']]['value']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'value'
+  PSHC `value`
+  IDEX
+  WREF
+  JMP JUMP_kangaroo_kangaroo
+JUMP_dolphin_jellybean:
+  DUP
+; "add"
+  PSHC `add`
+  EQ
+  JMPT JUMP_rhubarb_lumberjack
+  JMP JUMP_pointbreak_marmalade
+JUMP_rhubarb_lumberjack:
+  POP
+; save['status'][effect['target']]['value'] += effect['value']
+; save['status'][effect['target']]['value']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'value'
+  PSHC `value`
+  IDEX
+  DUP
+; effect['value']
+; effect
+  REF VAR_marmalade_quarantine
+; 'value'
+  PSHC `value`
+  IDEX
+  LCST
+  ADD
+  SWP
+  WREF
+  JMP JUMP_kangaroo_kangaroo
+JUMP_pointbreak_marmalade:
+  DUP
+; "add_max"
+  PSHC `add_max`
+  EQ
+  JMPT JUMP_lumberjack_noodle
+  JMP JUMP_salamander_octopus
+JUMP_lumberjack_noodle:
+  POP
+; save['status'][effect['target']]['maximum'] += effect['value']
+; save['status'][effect['target']]['maximum']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'maximum'
+  PSHC `maximum`
+  IDEX
+  DUP
+; effect['value']
+; effect
+  REF VAR_marmalade_quarantine
+; 'value'
+  PSHC `value`
+  IDEX
+  LCST
+  ADD
+  SWP
+  WREF
+  JMP JUMP_kangaroo_kangaroo
+JUMP_salamander_octopus:
+  POP
+; ''
+  PSHC ``
+JUMP_kangaroo_kangaroo:
+  POP
+; value = save['status'][effect['target']]['value']
+; save['status'][effect['target']]['value']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'value'
+  PSHC `value`
+  IDEX
+; value
+  REF VAR_umbrella_salamander
+  WREF
+  POP
+; maximum = save['status'][effect['target']]['maximum']
+; save['status'][effect['target']]['maximum']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'maximum'
+  PSHC `maximum`
+  IDEX
+; maximum
+  REF VAR_noodle_tangerine
+  WREF
+  POP
+; save['status'][effect['target']]['value'] = min([value, maximum])
+; min([value, maximum])
+; [value, maximum]
+; maximum
+  REF VAR_noodle_tangerine
+; value
+  REF VAR_umbrella_salamander
+  MKAR 00000002
+  CALL #96EC731ED4DCB222 1
+; save['status'][effect['target']]['value']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'value'
+  PSHC `value`
+  IDEX
+  WREF
+  POP
+; save['status'][effect['target']]['value'] = max([value, 0])
+; max([value, 0])
+; [value, 0]
+; 0
+  PSHC 0
+; value
+  REF VAR_umbrella_salamander
+  MKAR 00000002
+  CALL #8960430CFD85939F 1
+; save['status'][effect['target']]['value']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'value'
+  PSHC `value`
+  IDEX
+  WREF
+  RREF
+JUMP_jellybean_grapefruit:
+  RREF
+  JMP JUMP_alabaster_penguin
+JUMP_grapefruit_bananas:
+  DUP
+; "inventory"
+  PSHC `inventory`
+  EQ
+  JMPT JUMP_marmalade_quarantine
+  JMP JUMP_tangerine_rhubarb
+JUMP_marmalade_quarantine:
+  POP
+; {
+;             if effect['target'] == 'all' {
+;                 // Global effect - apply to all inventory items
+;                 for item in save['inventory'] {
+;                     __zarban_apply_effect(effect + {'target': item})
+;                 }
+;                 return nil
+;             }
+;             else {
+;                 // Apply the effect to a specific inventory item
+;                 save['inventory'][effect['target']]['equipped'] = effect['value']
+;             }
+;         }
+; if effect['target'] == 'all' {
+;                 // Global effect - apply to all inventory items
+;                 for item in save['inventory'] {
+;                     __zarban_apply_effect(effect + {'target': item})
+;                 }
+;                 return nil
+;             }
+;             else {
+;                 // Apply the effect to a specific inventory item
+;                 save['inventory'][effect['target']]['equipped'] = effect['value']
+;             }
+; effect['target'] == 'all'
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+; 'all'
+  PSHC `all`
+  EQ
+  JMPF JUMP_umbrella_salamander
+; {
+;                 // Global effect - apply to all inventory items
+;                 for item in save['inventory'] {
+;                     __zarban_apply_effect(effect + {'target': item})
+;                 }
+;                 return nil
+;             }
+; for item in save['inventory'] {
+;                     __zarban_apply_effect(effect + {'target': item})
+;                 }
+  SCI
+  MKAR 00000000
+; save['inventory']
+; save
+  REF VAR_lumberjack_noodle
+; 'inventory'
+  PSHC `inventory`
+  IDEX
+  SWP
+JUMP_grapefruit_arbitrary_dolphin:
+  SWP
+  DJNE JUMP_noodle_tangerine
+  JMP JUMP_arbitrary_arbitrary_cabbage
+JUMP_noodle_tangerine:
+  NEXT
+  REF VAR_arbitrary_arbitrary_cabbage
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;                     __zarban_apply_effect(effect + {'target': item})
+;                 }
+; __zarban_apply_effect(effect + {'target': item})
+; effect + {'target': item}
+; effect
+  REF VAR_marmalade_quarantine
+; {'target': item}
+; item
+  REF VAR_arbitrary_arbitrary_cabbage
+; 'target'
+  PSHC `target`
+  MKOB 00000001
+  ADD
+  CALL #4EE797E226C21812 1
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_grapefruit_arbitrary_dolphin
+  POP
+  JMP JUMP_octopus_arbitrary_pointbreak
+  PSAR
+JUMP_octopus_arbitrary_pointbreak:
+  SWP
+JUMP_arbitrary_arbitrary_cabbage:
+  POP
+  SCO
+  POP
+; return nil
+; nil
+  PUSH nil
+  RET
+  RREF
+  JMP JUMP_bananas_arbitrary_alabaster
+JUMP_umbrella_salamander:
+; {
+;                 // Apply the effect to a specific inventory item
+;                 save['inventory'][effect['target']]['equipped'] = effect['value']
+;             }
+; save['inventory'][effect['target']]['equipped'] = effect['value']
+; effect['value']
+; effect
+  REF VAR_marmalade_quarantine
+; 'value'
+  PSHC `value`
+  IDEX
+; save['inventory'][effect['target']]['equipped']
+; save
+  REF VAR_lumberjack_noodle
+; 'inventory'
+  PSHC `inventory`
+  IDEX
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+  IDEX
+; 'equipped'
+  PSHC `equipped`
+  IDEX
+  WREF
+  RREF
+JUMP_bananas_arbitrary_alabaster:
+  RREF
+  JMP JUMP_alabaster_penguin
+JUMP_tangerine_rhubarb:
+  DUP
+; "choices"
+  PSHC `choices`
+  EQ
+  JMPT JUMP_hedgehog_arbitrary_umbrella
+  JMP JUMP_penguin_arbitrary_grapefruit
+JUMP_hedgehog_arbitrary_umbrella:
+  POP
+; {
+;             if effect['target'] == 'all' {
+;                 // Global effect - apply to all choices
+;                 for choice in global('__zarban_storydata')['choices'] {
+;                     __zarban_apply_effect(effect + {'target': choice})
+;                 }
+;                 return nil
+;             } else {
+;                 // Apply the effect to a specific choice
+;                 if effect['value'] {
+;                     save['choices'] += effect['target']
+;                 } else {
+;                     save['choices'] -= effect['target']
+;                 }
+;             }
+;         }
+; if effect['target'] == 'all' {
+;                 // Global effect - apply to all choices
+;                 for choice in global('__zarban_storydata')['choices'] {
+;                     __zarban_apply_effect(effect + {'target': choice})
+;                 }
+;                 return nil
+;             } else {
+;                 // Apply the effect to a specific choice
+;                 if effect['value'] {
+;                     save['choices'] += effect['target']
+;                 } else {
+;                     save['choices'] -= effect['target']
+;                 }
+;             }
+; effect['target'] == 'all'
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+; 'all'
+  PSHC `all`
+  EQ
+  JMPF JUMP_cabbage_arbitrary_hedgehog
+; {
+;                 // Global effect - apply to all choices
+;                 for choice in global('__zarban_storydata')['choices'] {
+;                     __zarban_apply_effect(effect + {'target': choice})
+;                 }
+;                 return nil
+;             }
+; for choice in global('__zarban_storydata')['choices'] {
+;                     __zarban_apply_effect(effect + {'target': choice})
+;                 }
+  SCI
+  MKAR 00000000
+; global('__zarban_storydata')['choices']
+; global('__zarban_storydata')
+; '__zarban_storydata'
+  PSHC `__zarban_storydata`
+  CALL #90B4D2FF6ACB88AF 1
+; 'choices'
+  PSHC `choices`
+  IDEX
+  SWP
+JUMP_dolphin_arbitrary_lumberjack:
+  SWP
+  DJNE JUMP_jellybean_arbitrary_jellybean
+  JMP JUMP_quarantine_arbitrary_kangaroo
+JUMP_jellybean_arbitrary_jellybean:
+  NEXT
+  REF VAR_grapefruit_arbitrary_dolphin
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;                     __zarban_apply_effect(effect + {'target': choice})
+;                 }
+; __zarban_apply_effect(effect + {'target': choice})
+; effect + {'target': choice}
+; effect
+  REF VAR_marmalade_quarantine
+; {'target': choice}
+; choice
+  REF VAR_grapefruit_arbitrary_dolphin
+; 'target'
+  PSHC `target`
+  MKOB 00000001
+  ADD
+  CALL #4EE797E226C21812 1
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_dolphin_arbitrary_lumberjack
+  POP
+  JMP JUMP_kangaroo_arbitrary_marmalade
+  PSAR
+JUMP_kangaroo_arbitrary_marmalade:
+  SWP
+JUMP_quarantine_arbitrary_kangaroo:
+  POP
+  SCO
+  POP
+; return nil
+; nil
+  PUSH nil
+  RET
+  RREF
+  JMP JUMP_rhubarb_arbitrary_noodle
+JUMP_cabbage_arbitrary_hedgehog:
+; {
+;                 // Apply the effect to a specific choice
+;                 if effect['value'] {
+;                     save['choices'] += effect['target']
+;                 } else {
+;                     save['choices'] -= effect['target']
+;                 }
+;             }
+; if effect['value'] {
+;                     save['choices'] += effect['target']
+;                 } else {
+;                     save['choices'] -= effect['target']
+;                 }
+; effect['value']
+; effect
+  REF VAR_marmalade_quarantine
+; 'value'
+  PSHC `value`
+  IDEX
+  JMPF JUMP_pointbreak_arbitrary_octopus
+; {
+;                     save['choices'] += effect['target']
+;                 }
+; save['choices'] += effect['target']
+; save['choices']
+; save
+  REF VAR_lumberjack_noodle
+; 'choices'
+  PSHC `choices`
+  IDEX
+  DUP
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+  LCST
+  ADD
+  SWP
+  WREF
+  RREF
+  JMP JUMP_lumberjack_arbitrary_penguin
+JUMP_pointbreak_arbitrary_octopus:
+; {
+;                     save['choices'] -= effect['target']
+;                 }
+; save['choices'] -= effect['target']
+; save['choices']
+; save
+  REF VAR_lumberjack_noodle
+; 'choices'
+  PSHC `choices`
+  IDEX
+  DUP
+; effect['target']
+; effect
+  REF VAR_marmalade_quarantine
+; 'target'
+  PSHC `target`
+  IDEX
+  LCST
+  SUB
+  SWP
+  WREF
+  RREF
+JUMP_lumberjack_arbitrary_penguin:
+  RREF
+JUMP_rhubarb_arbitrary_noodle:
+  RREF
+  JMP JUMP_alabaster_penguin
+JUMP_penguin_arbitrary_grapefruit:
+  POP
+; nil
+  PUSH nil
+JUMP_alabaster_penguin:
+  POP
+; assign_global('__zarban_savedata', save)
+; '__zarban_savedata'
+  PSHC `__zarban_savedata`
+; save
+  REF VAR_lumberjack_noodle
+  CALL #818B07AC0019FEA7 2
+  RREF
+  RET
+; fn octopus_cabbage
+  POOL 00000006
+; {
+;     save = global('__zarban_savedata')
+;     options = global('__zarban_storydata')['chapters'][save['chapter']]['options']
+; 
+;     // This loop acts as a filter - it will return only the options that pass all conditions
+;     for option in options {
+;         // Check all conditions
+;         ok_conditions = for c in option['conditions'] do __zarban_check_condition(c);
+;         if ok_conditions.all() {
+;             option
+;         } else {
+;             continue
+;         }
+;     }
+; }
+; save = global('__zarban_savedata')
+; global('__zarban_savedata')
+; '__zarban_savedata'
+  PSHC `__zarban_savedata`
+  CALL #90B4D2FF6ACB88AF 1
+; save
+  REF VAR_lumberjack_noodle
+  WREF
+  POP
+; options = global('__zarban_storydata')['chapters'][save['chapter']]['options']
+; global('__zarban_storydata')['chapters'][save['chapter']]['options']
+; global('__zarban_storydata')
+; '__zarban_storydata'
+  PSHC `__zarban_storydata`
+  CALL #90B4D2FF6ACB88AF 1
+; 'chapters'
+  PSHC `chapters`
+  IDEX
+; save['chapter']
+; save
+  REF VAR_lumberjack_noodle
+; 'chapter'
+  PSHC `chapter`
+  IDEX
+  IDEX
+; 'options'
+  PSHC `options`
+  IDEX
+; options
+  REF VAR_octopus_arbitrary_pointbreak
+  WREF
+  POP
+; for option in options {
+;         // Check all conditions
+;         ok_conditions = for c in option['conditions'] do __zarban_check_condition(c);
+;         if ok_conditions.all() {
+;             option
+;         } else {
+;             continue
+;         }
+;     }
+  SCI
+  MKAR 00000000
+; options
+  REF VAR_octopus_arbitrary_pointbreak
+  SWP
+JUMP_quarantine_hedgehog:
+  SWP
+  DJNE JUMP_arbitrary_arbitrary
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+  NEXT
+  REF VAR_bananas_arbitrary_alabaster
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;         // Check all conditions
+;         ok_conditions = for c in option['conditions'] do __zarban_check_condition(c);
+;         if ok_conditions.all() {
+;             option
+;         } else {
+;             continue
+;         }
+;     }
+; ok_conditions = for c in option['conditions'] do __zarban_check_condition(c)
+; for c in option['conditions'] do __zarban_check_condition(c)
+  SCI
+  MKAR 00000000
+; option['conditions']
+; option
+  REF VAR_bananas_arbitrary_alabaster
+; 'conditions'
+  PSHC `conditions`
+  IDEX
+  SWP
+JUMP_hedgehog_pointbreak:
+  SWP
+  DJNE JUMP_octopus_cabbage
+  JMP JUMP_bananas_dolphin
+JUMP_octopus_cabbage:
+  NEXT
+  REF VAR_hedgehog_arbitrary_umbrella
+  WREF
+  POP
+  SWP
+  SCI
+; __zarban_check_condition(c)
+; c
+  REF VAR_hedgehog_arbitrary_umbrella
+  CALL #91B1045A644D718B 1
+  SCO
+  PSAR
+  JMP JUMP_hedgehog_pointbreak
+  POP
+  JMP JUMP_penguin_alabaster
+  PSAR
+JUMP_penguin_alabaster:
+  SWP
+JUMP_bananas_dolphin:
+  POP
+  SCO
+; ok_conditions
+  REF VAR_penguin_arbitrary_grapefruit
+  WREF
+  POP
+; if ok_conditions.all() {
+;             option
+;         } else {
+;             continue
+;         }
+; ok_conditions.all()
+; ok_conditions
+  REF VAR_penguin_arbitrary_grapefruit
+  CALM all 1
+  JMPF JUMP_cabbage_umbrella
+; {
+;             option
+;         }
+; option
+  REF VAR_bananas_arbitrary_alabaster
+  RREF
+  JMP JUMP_jellybean_grapefruit
+JUMP_cabbage_umbrella:
+; {
+;             continue
+;         }
+; continue
+  PUSH nil
+  SCO
+  POP
+  JMP JUMP_quarantine_hedgehog
+  RREF
+JUMP_jellybean_grapefruit:
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_quarantine_hedgehog
+  POP
+  JMP JUMP_dolphin_jellybean
+  PSAR
+JUMP_dolphin_jellybean:
+  SWP
+JUMP_grapefruit_bananas:
+  POP
+  SCO
+  RREF
+  RET
+; fn bananas_dolphin
+  POOL 00000005
+; {
+;     data = global('__zarban_storydata')
+;     save = global('__zarban_savedata')
+; 
+;     target = data['chapters'][chapter]
+;     save['chapter'] = chapter
+;     assign_global('__zarban_savedata', save)
+; 
+;     // Apply chapter effects
+;     for effect in target['effects'] {
+;         __zarban_apply_effect(effect)
+;     }
+; 
+; }
+; data = global('__zarban_storydata')
+; global('__zarban_storydata')
+; '__zarban_storydata'
+  PSHC `__zarban_storydata`
+  CALL #90B4D2FF6ACB88AF 1
+; data
+  REF VAR_cabbage_arbitrary_hedgehog
+  WREF
+  POP
+; save = global('__zarban_savedata')
+; global('__zarban_savedata')
+; '__zarban_savedata'
+  PSHC `__zarban_savedata`
+  CALL #90B4D2FF6ACB88AF 1
+; save
+  REF VAR_lumberjack_noodle
+  WREF
+  POP
+; target = data['chapters'][chapter]
+; data['chapters'][chapter]
+; data
+  REF VAR_cabbage_arbitrary_hedgehog
+; 'chapters'
+  PSHC `chapters`
+  IDEX
+; chapter
+  RIDX VAR_jellybean_arbitrary_jellybean
+; target
+  REF VAR_quarantine_arbitrary_kangaroo
+  WREF
+  POP
+; save['chapter'] = chapter
+; chapter
+  REF VAR_jellybean_arbitrary_jellybean
+; save['chapter']
+; save
+  REF VAR_lumberjack_noodle
+; 'chapter'
+  PSHC `chapter`
+  IDEX
+  WREF
+  POP
+; assign_global('__zarban_savedata', save)
+; '__zarban_savedata'
+  PSHC `__zarban_savedata`
+; save
+  REF VAR_lumberjack_noodle
+  CALL #818B07AC0019FEA7 2
+  POP
+; for effect in target['effects'] {
+;         __zarban_apply_effect(effect)
+;     }
+  SCI
+  MKAR 00000000
+; target['effects']
+; target
+  REF VAR_quarantine_arbitrary_kangaroo
+; 'effects'
+  PSHC `effects`
+  IDEX
+  SWP
+JUMP_octopus_cabbage:
+  SWP
+  DJNE JUMP_arbitrary_arbitrary
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+  NEXT
+  REF VAR_marmalade_quarantine
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;         __zarban_apply_effect(effect)
+;     }
+; __zarban_apply_effect(effect)
+; effect
+  REF VAR_marmalade_quarantine
+  CALL #4EE797E226C21812 1
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_octopus_cabbage
+  POP
+  JMP JUMP_bananas_dolphin
+  PSAR
+JUMP_bananas_dolphin:
+  SWP
+JUMP_grapefruit_bananas:
+  POP
+  SCO
+  RREF
+  RET
+; fn hedgehog_pointbreak
+  POOL 00000012
+; {
+;     data = global('__zarban_storydata')
+;     save = global('__zarban_savedata')
+;     status = save['status']
+;     inventory = save['inventory']
+;     out = []
+; 
+;     // Status conditions
+;     for status_name in save['status'] {
+;         status = save['status'][status_name]
+;         if status['hidden'] { continue } else {
+;             out += format("{}: {}/{}", [status_name, status['value'], status['maximum']])
+;         }
+;     }
+; 
+;     out += ''
+; 
+;     // Inventory
+;     out += 'Equipment:'
+;     n_equipped = 0
+;     for item in save['inventory'] {
+;         item = save['inventory'][item]
+;         if item['equipped'] {
+;             n_equipped += 1
+;             out += format("- {}", [item['description']])
+;         } else { continue }
+;     }
+;     out += if n_equipped == 0 {
+;         "- None"
+;     } else {
+;         ""
+;     }
+;     
+;     out += ''
+; 
+;     // Choices
+;     if len(save['choices']) > 0 {
+;         out += ''
+;         out += "Choices:"
+;         for choice in save['choices'] {
+;             out += format("- {}", [data['choices'][choice]['description']])
+;         }
+;     } else nil
+; 
+;     out
+; }
+; data = global('__zarban_storydata')
+; global('__zarban_storydata')
+; '__zarban_storydata'
+  PSHC `__zarban_storydata`
+  CALL #90B4D2FF6ACB88AF 1
+; data
+  REF VAR_cabbage_arbitrary_hedgehog
+  WREF
+  POP
+; save = global('__zarban_savedata')
+; global('__zarban_savedata')
+; '__zarban_savedata'
+  PSHC `__zarban_savedata`
+  CALL #90B4D2FF6ACB88AF 1
+; save
+  REF VAR_lumberjack_noodle
+  WREF
+  POP
+; status = save['status']
+; save['status']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; status
+  REF VAR_tangerine_rhubarb
+  WREF
+  POP
+; inventory = save['inventory']
+; save['inventory']
+; save
+  REF VAR_lumberjack_noodle
+; 'inventory'
+  PSHC `inventory`
+  IDEX
+; inventory
+  REF VAR_dolphin_arbitrary_lumberjack
+  WREF
+  POP
+; out = []
+; []
+  MKAR 00000000
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  WREF
+  POP
+; for status_name in save['status'] {
+;         status = save['status'][status_name]
+;         if status['hidden'] { continue } else {
+;             out += format("{}: {}/{}", [status_name, status['value'], status['maximum']])
+;         }
+;     }
+  SCI
+  MKAR 00000000
+; save['status']
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+  SWP
+JUMP_bananas_dolphin:
+  SWP
+  DJNE JUMP_arbitrary_arbitrary
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+  NEXT
+  REF VAR_rhubarb_arbitrary_noodle
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;         status = save['status'][status_name]
+;         if status['hidden'] { continue } else {
+;             out += format("{}: {}/{}", [status_name, status['value'], status['maximum']])
+;         }
+;     }
+; status = save['status'][status_name]
+; save['status'][status_name]
+; save
+  REF VAR_lumberjack_noodle
+; 'status'
+  PSHC `status`
+  IDEX
+; status_name
+  RIDX VAR_rhubarb_arbitrary_noodle
+; status
+  REF VAR_tangerine_rhubarb
+  WREF
+  POP
+; if status['hidden'] { continue } else {
+;             out += format("{}: {}/{}", [status_name, status['value'], status['maximum']])
+;         }
+; status['hidden']
+; status
+  REF VAR_tangerine_rhubarb
+; 'hidden'
+  PSHC `hidden`
+  IDEX
+  JMPF JUMP_octopus_cabbage
+; { continue }
+; continue
+  PUSH nil
+  SCO
+  POP
+  JMP JUMP_bananas_dolphin
+  RREF
+  JMP JUMP_hedgehog_pointbreak
+JUMP_octopus_cabbage:
+; {
+;             out += format("{}: {}/{}", [status_name, status['value'], status['maximum']])
+;         }
+; out += format("{}: {}/{}", [status_name, status['value'], status['maximum']])
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; format("{}: {}/{}", [status_name, status['value'], status['maximum']])
+; "{}: {}/{}"
+  PSHC `{}: {}/{}`
+; [status_name, status['value'], status['maximum']]
+; status['maximum']
+; status
+  REF VAR_tangerine_rhubarb
+; 'maximum'
+  PSHC `maximum`
+  IDEX
+; status['value']
+; status
+  REF VAR_tangerine_rhubarb
+; 'value'
+  PSHC `value`
+  IDEX
+; status_name
+  REF VAR_rhubarb_arbitrary_noodle
+  MKAR 00000003
+  CALL #D9468344D3651243 2
+  LCST
+  ADD
+  SWP
+  WREF
+  RREF
+JUMP_hedgehog_pointbreak:
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_bananas_dolphin
+  POP
+  JMP JUMP_penguin_alabaster
+  PSAR
+JUMP_penguin_alabaster:
+  SWP
+JUMP_grapefruit_bananas:
+  POP
+  SCO
+  POP
+; out += ''
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; ''
+  PSHC ``
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; out += 'Equipment:'
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; 'Equipment:'
+  PSHC `Equipment:`
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; n_equipped = 0
+; 0
+  PSHC 0
+; n_equipped
+  REF VAR_pointbreak_arbitrary_octopus
+  WREF
+  POP
+; for item in save['inventory'] {
+;         item = save['inventory'][item]
+;         if item['equipped'] {
+;             n_equipped += 1
+;             out += format("- {}", [item['description']])
+;         } else { continue }
+;     }
+  SCI
+  MKAR 00000000
+; save['inventory']
+; save
+  REF VAR_lumberjack_noodle
+; 'inventory'
+  PSHC `inventory`
+  IDEX
+  SWP
+JUMP_kangaroo_kangaroo:
+  SWP
+  DJNE JUMP_cabbage_umbrella
+  JMP JUMP_jellybean_grapefruit
+JUMP_cabbage_umbrella:
+  NEXT
+  REF VAR_arbitrary_arbitrary_cabbage
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;         item = save['inventory'][item]
+;         if item['equipped'] {
+;             n_equipped += 1
+;             out += format("- {}", [item['description']])
+;         } else { continue }
+;     }
+; item = save['inventory'][item]
+; save['inventory'][item]
+; save
+  REF VAR_lumberjack_noodle
+; 'inventory'
+  PSHC `inventory`
+  IDEX
+; item
+  RIDX VAR_arbitrary_arbitrary_cabbage
+; item
+  REF VAR_arbitrary_arbitrary_cabbage
+  WREF
+  POP
+; if item['equipped'] {
+;             n_equipped += 1
+;             out += format("- {}", [item['description']])
+;         } else { continue }
+; item['equipped']
+; item
+  REF VAR_arbitrary_arbitrary_cabbage
+; 'equipped'
+  PSHC `equipped`
+  IDEX
+  JMPF JUMP_quarantine_hedgehog
+; {
+;             n_equipped += 1
+;             out += format("- {}", [item['description']])
+;         }
+; n_equipped += 1
+; n_equipped
+  REF VAR_pointbreak_arbitrary_octopus
+  DUP
+; 1
+  PSHC 1
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; out += format("- {}", [item['description']])
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; format("- {}", [item['description']])
+; "- {}"
+  PSHC `- {}`
+; [item['description']]
+; item['description']
+; item
+  REF VAR_arbitrary_arbitrary_cabbage
+; 'description'
+  PSHC `description`
+  IDEX
+  MKAR 00000001
+  CALL #D9468344D3651243 2
+  LCST
+  ADD
+  SWP
+  WREF
+  RREF
+  JMP JUMP_dolphin_jellybean
+JUMP_quarantine_hedgehog:
+; { continue }
+; continue
+  PUSH nil
+  SCO
+  POP
+  JMP JUMP_kangaroo_kangaroo
+  RREF
+JUMP_dolphin_jellybean:
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_kangaroo_kangaroo
+  POP
+  JMP JUMP_rhubarb_lumberjack
+  PSAR
+JUMP_rhubarb_lumberjack:
+  SWP
+JUMP_jellybean_grapefruit:
+  POP
+  SCO
+  POP
+; out += if n_equipped == 0 {
+;         "- None"
+;     } else {
+;         ""
+;     }
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; if n_equipped == 0 {
+;         "- None"
+;     } else {
+;         ""
+;     }
+; n_equipped == 0
+; n_equipped
+  REF VAR_pointbreak_arbitrary_octopus
+; 0
+  PSHC 0
+  EQ
+  JMPF JUMP_pointbreak_marmalade
+; {
+;         "- None"
+;     }
+; "- None"
+  PSHC `- None`
+  RREF
+  JMP JUMP_lumberjack_noodle
+JUMP_pointbreak_marmalade:
+; {
+;         ""
+;     }
+; ""
+  PSHC ``
+  RREF
+JUMP_lumberjack_noodle:
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; out += ''
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; ''
+  PSHC ``
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; if len(save['choices']) > 0 {
+;         out += ''
+;         out += "Choices:"
+;         for choice in save['choices'] {
+;             out += format("- {}", [data['choices'][choice]['description']])
+;         }
+;     } else nil
+; len(save['choices']) > 0
+; len(save['choices'])
+; save['choices']
+; save
+  REF VAR_lumberjack_noodle
+; 'choices'
+  PSHC `choices`
+  IDEX
+  CALL #FC1400FACF92C78 1
+; 0
+  PSHC 0
+  GT
+  JMPF JUMP_salamander_octopus
+; {
+;         out += ''
+;         out += "Choices:"
+;         for choice in save['choices'] {
+;             out += format("- {}", [data['choices'][choice]['description']])
+;         }
+;     }
+; out += ''
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; ''
+  PSHC ``
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; out += "Choices:"
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; "Choices:"
+  PSHC `Choices:`
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; for choice in save['choices'] {
+;             out += format("- {}", [data['choices'][choice]['description']])
+;         }
+  SCI
+  MKAR 00000000
+; save['choices']
+; save
+  REF VAR_lumberjack_noodle
+; 'choices'
+  PSHC `choices`
+  IDEX
+  SWP
+JUMP_tangerine_rhubarb:
+  SWP
+  DJNE JUMP_alabaster_penguin
+  JMP JUMP_marmalade_quarantine
+JUMP_alabaster_penguin:
+  NEXT
+  REF VAR_grapefruit_arbitrary_dolphin
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;             out += format("- {}", [data['choices'][choice]['description']])
+;         }
+; out += format("- {}", [data['choices'][choice]['description']])
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; format("- {}", [data['choices'][choice]['description']])
+; "- {}"
+  PSHC `- {}`
+; [data['choices'][choice]['description']]
+; data['choices'][choice]['description']
+; data
+  REF VAR_cabbage_arbitrary_hedgehog
+; 'choices'
+  PSHC `choices`
+  IDEX
+; choice
+  RIDX VAR_grapefruit_arbitrary_dolphin
+; 'description'
+  PSHC `description`
+  IDEX
+  MKAR 00000001
+  CALL #D9468344D3651243 2
+  LCST
+  ADD
+  SWP
+  WREF
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_tangerine_rhubarb
+  POP
+  JMP JUMP_umbrella_salamander
+  PSAR
+JUMP_umbrella_salamander:
+  SWP
+JUMP_marmalade_quarantine:
+  POP
+  SCO
+  RREF
+  JMP JUMP_noodle_tangerine
+JUMP_salamander_octopus:
+; nil
+  PUSH nil
+JUMP_noodle_tangerine:
+  POP
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  RREF
+  RET
+; fn penguin_alabaster
+  POOL 0000000B
+; {
+;     data = global('__zarban_storydata')
+;     save = global('__zarban_savedata')
+;     chapter = data['chapters'][save['chapter']]
+; 
+;     out = []
+; 
+;     // Chapter text
+;     out += chapter['text']
+;     out += ''
+;     out += __zarban_fmt_player()
+;     out += ''
+;     out += if with_error {
+;         "Invalid selection. What do you do?"
+;     } else {
+;         "What do you do?"
+;     }
+; 
+;     // Available choices
+;     choices = __zarban_get_choices()
+;     for i in 0..(len(choices)-1) {
+;         choice = choices[i]
+;         out += format("{}- {}", [i+1, choice['prompt']])
+;     }
+;     
+;     out
+; }
+; data = global('__zarban_storydata')
+; global('__zarban_storydata')
+; '__zarban_storydata'
+  PSHC `__zarban_storydata`
+  CALL #90B4D2FF6ACB88AF 1
+; data
+  REF VAR_cabbage_arbitrary_hedgehog
+  WREF
+  POP
+; save = global('__zarban_savedata')
+; global('__zarban_savedata')
+; '__zarban_savedata'
+  PSHC `__zarban_savedata`
+  CALL #90B4D2FF6ACB88AF 1
+; save
+  REF VAR_lumberjack_noodle
+  WREF
+  POP
+; chapter = data['chapters'][save['chapter']]
+; data['chapters'][save['chapter']]
+; data
+  REF VAR_cabbage_arbitrary_hedgehog
+; 'chapters'
+  PSHC `chapters`
+  IDEX
+; save['chapter']
+; save
+  REF VAR_lumberjack_noodle
+; 'chapter'
+  PSHC `chapter`
+  IDEX
+  IDEX
+; chapter
+  REF VAR_jellybean_arbitrary_jellybean
+  WREF
+  POP
+; out = []
+; []
+  MKAR 00000000
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  WREF
+  POP
+; out += chapter['text']
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; chapter['text']
+; chapter
+  REF VAR_jellybean_arbitrary_jellybean
+; 'text'
+  PSHC `text`
+  IDEX
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; out += ''
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; ''
+  PSHC ``
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; out += __zarban_fmt_player()
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; __zarban_fmt_player()
+  CALL #4992768D53844B64 0
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; out += ''
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; ''
+  PSHC ``
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; out += if with_error {
+;         "Invalid selection. What do you do?"
+;     } else {
+;         "What do you do?"
+;     }
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; if with_error {
+;         "Invalid selection. What do you do?"
+;     } else {
+;         "What do you do?"
+;     }
+; with_error
+  REF VAR_lumberjack_arbitrary_penguin
+  JMPF JUMP_arbitrary_arbitrary
+; {
+;         "Invalid selection. What do you do?"
+;     }
+; "Invalid selection. What do you do?"
+  PSHC `Invalid selection. What do you do?`
+  RREF
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+; {
+;         "What do you do?"
+;     }
+; "What do you do?"
+  PSHC `What do you do?`
+  RREF
+JUMP_grapefruit_bananas:
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; choices = __zarban_get_choices()
+; __zarban_get_choices()
+  CALL #ED222DA408C41CAD 0
+; choices
+  REF VAR_salamander_arbitrary_quarantine
+  WREF
+  POP
+; for i in 0..(len(choices)-1) {
+;         choice = choices[i]
+;         out += format("{}- {}", [i+1, choice['prompt']])
+;     }
+  SCI
+  MKAR 00000000
+; 0..(len(choices)-1
+; 0
+  PSHC 0
+; len(choices)-1
+; len(choices)
+; choices
+  REF VAR_salamander_arbitrary_quarantine
+  CALL #FC1400FACF92C78 1
+; 1
+  PSUB 1
+  MKRG
+  SWP
+JUMP_hedgehog_pointbreak:
+  SWP
+  DJNE JUMP_octopus_cabbage
+  JMP JUMP_bananas_dolphin
+JUMP_octopus_cabbage:
+  NEXT
+  REF VAR_alabaster_arbitrary_rhubarb
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;         choice = choices[i]
+;         out += format("{}- {}", [i+1, choice['prompt']])
+;     }
+; choice = choices[i]
+; choices[i]
+; choices
+  REF VAR_salamander_arbitrary_quarantine
+; i
+  RIDX VAR_alabaster_arbitrary_rhubarb
+; choice
+  REF VAR_grapefruit_arbitrary_dolphin
+  WREF
+  POP
+; out += format("{}- {}", [i+1, choice['prompt']])
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; format("{}- {}", [i+1, choice['prompt']])
+; "{}- {}"
+  PSHC `{}- {}`
+; [i+1, choice['prompt']]
+; choice['prompt']
+; choice
+  REF VAR_grapefruit_arbitrary_dolphin
+; 'prompt'
+  PSHC `prompt`
+  IDEX
+; i+1
+; i
+  REF VAR_alabaster_arbitrary_rhubarb
+; 1
+  PADD 1
+  MKAR 00000002
+  CALL #D9468344D3651243 2
+  LCST
+  ADD
+  SWP
+  WREF
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_hedgehog_pointbreak
+  POP
+  JMP JUMP_penguin_alabaster
+  PSAR
+JUMP_penguin_alabaster:
+  SWP
+JUMP_bananas_dolphin:
+  POP
+  SCO
+  POP
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  RREF
+  RET
+; fn cabbage_umbrella
+  POOL 00000006
+; {
+;     // Grab the length of the longest line/title
+;     max_len = max(
+;         (for l in lines do len(l)) + len(title)
+;     )
+; 
+;     out = []
+; 
+;     // Header portion
+;     out += format("╔{}╗", ['═'.repeat(max_len+2)])
+;     out += format("║ {} ║", [title.pad_right(max_len)])
+;     out += format("╠{}╣", ['═'.repeat(max_len+2)])
+; 
+;     // Body portion
+;     for line in lines {
+;         out += format("║ {} ║", [line.pad_right(max_len)])
+;     }
+; 
+;     // Footer portion
+;     out += format("╚{}╝", ['═'.repeat(max_len+2)])
+; 
+;     out.join('\n')
+; }
+; max_len = max(
+;         (for l in lines do len(l)) + len(title)
+;     )
+; max(
+;         (for l in lines do len(l)) + len(title)
+;     )
+; for l in lines do len(l)) + len(title)
+; for l in lines do len(l)
+  SCI
+  MKAR 00000000
+; lines
+  REF VAR_marmalade_arbitrary_salamander
+  SWP
+JUMP_octopus_cabbage:
+  SWP
+  DJNE JUMP_arbitrary_arbitrary
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+  NEXT
+  REF VAR_tangerine_arbitrary_tangerine
+  WREF
+  POP
+  SWP
+  SCI
+; len(l)
+; l
+  REF VAR_tangerine_arbitrary_tangerine
+  CALL #FC1400FACF92C78 1
+  SCO
+  PSAR
+  JMP JUMP_octopus_cabbage
+  POP
+  JMP JUMP_bananas_dolphin
+  PSAR
+JUMP_bananas_dolphin:
+  SWP
+JUMP_grapefruit_bananas:
+  POP
+  SCO
+; len(title)
+; title
+  REF VAR_umbrella_arbitrary_bananas
+  CALL #FC1400FACF92C78 1
+  ADD
+  CALL #8960430CFD85939F 1
+; max_len
+  REF VAR_noodle_arbitrary_cabbage
+  WREF
+  POP
+; out = []
+; []
+  MKAR 00000000
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  WREF
+  POP
+; out += format("╔{}╗", ['═'.repeat(max_len+2)])
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; format("╔{}╗", ['═'.repeat(max_len+2)])
+; "╔{}╗"
+  PSHC `╔{}╗`
+; ['═'.repeat(max_len+2)]
+; '═'.repeat(max_len+2)
+; '═'
+  PSHC `═`
+; max_len+2
+; max_len
+  REF VAR_noodle_arbitrary_cabbage
+; 2
+  PADD 2
+  CALM repeat 2
+  MKAR 00000001
+  CALL #D9468344D3651243 2
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; out += format("║ {} ║", [title.pad_right(max_len)])
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; format("║ {} ║", [title.pad_right(max_len)])
+; "║ {} ║"
+  PSHC `║ {} ║`
+; [title.pad_right(max_len)]
+; title.pad_right(max_len)
+; title
+  REF VAR_umbrella_arbitrary_bananas
+; max_len
+  REF VAR_noodle_arbitrary_cabbage
+  CALM pad_right 2
+  MKAR 00000001
+  CALL #D9468344D3651243 2
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; out += format("╠{}╣", ['═'.repeat(max_len+2)])
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; format("╠{}╣", ['═'.repeat(max_len+2)])
+; "╠{}╣"
+  PSHC `╠{}╣`
+; ['═'.repeat(max_len+2)]
+; '═'.repeat(max_len+2)
+; '═'
+  PSHC `═`
+; max_len+2
+; max_len
+  REF VAR_noodle_arbitrary_cabbage
+; 2
+  PADD 2
+  CALM repeat 2
+  MKAR 00000001
+  CALL #D9468344D3651243 2
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; for line in lines {
+;         out += format("║ {} ║", [line.pad_right(max_len)])
+;     }
+  SCI
+  MKAR 00000000
+; lines
+  REF VAR_marmalade_arbitrary_salamander
+  SWP
+JUMP_cabbage_umbrella:
+  SWP
+  DJNE JUMP_hedgehog_pointbreak
+  JMP JUMP_penguin_alabaster
+JUMP_hedgehog_pointbreak:
+  NEXT
+  REF VAR_arbitrary_bananas_dolphin
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;         out += format("║ {} ║", [line.pad_right(max_len)])
+;     }
+; out += format("║ {} ║", [line.pad_right(max_len)])
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; format("║ {} ║", [line.pad_right(max_len)])
+; "║ {} ║"
+  PSHC `║ {} ║`
+; [line.pad_right(max_len)]
+; line.pad_right(max_len)
+; line
+  REF VAR_arbitrary_bananas_dolphin
+; max_len
+  REF VAR_noodle_arbitrary_cabbage
+  CALM pad_right 2
+  MKAR 00000001
+  CALL #D9468344D3651243 2
+  LCST
+  ADD
+  SWP
+  WREF
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_cabbage_umbrella
+  POP
+  JMP JUMP_jellybean_grapefruit
+  PSAR
+JUMP_jellybean_grapefruit:
+  SWP
+JUMP_penguin_alabaster:
+  POP
+  SCO
+  POP
+; out += format("╚{}╝", ['═'.repeat(max_len+2)])
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+  DUP
+; format("╚{}╝", ['═'.repeat(max_len+2)])
+; "╚{}╝"
+  PSHC `╚{}╝`
+; ['═'.repeat(max_len+2)]
+; '═'.repeat(max_len+2)
+; '═'
+  PSHC `═`
+; max_len+2
+; max_len
+  REF VAR_noodle_arbitrary_cabbage
+; 2
+  PADD 2
+  CALM repeat 2
+  MKAR 00000001
+  CALL #D9468344D3651243 2
+  LCST
+  ADD
+  SWP
+  WREF
+  POP
+; out.join('\n')
+; out
+  REF VAR_kangaroo_arbitrary_marmalade
+; '\n'
+  PSHC `
+`
+  CALM join 2
+  RREF
+  RET
+; fn jellybean_grapefruit
+  POOL 00000007
+; {
+;     data = global('__zarban_storydata')
+;     save = global('__zarban_savedata')
+; 
+;     lines = __zarban_fmt_chapter(with_error) + [
+;         '', '[Use `zarban(<choice>)` or `<choice> @zarban` to make a selection!]'
+;     ]
+;     title = data['chapters'][save['chapter']]['title']
+; 
+;     __zarban_cool_box(title, lines)
+; }
+; data = global('__zarban_storydata')
+; global('__zarban_storydata')
+; '__zarban_storydata'
+  PSHC `__zarban_storydata`
+  CALL #90B4D2FF6ACB88AF 1
+; data
+  REF VAR_cabbage_arbitrary_hedgehog
+  WREF
+  POP
+; save = global('__zarban_savedata')
+; global('__zarban_savedata')
+; '__zarban_savedata'
+  PSHC `__zarban_savedata`
+  CALL #90B4D2FF6ACB88AF 1
+; save
+  REF VAR_lumberjack_noodle
+  WREF
+  POP
+; lines = __zarban_fmt_chapter(with_error) + [
+;         '', '[Use `zarban(<choice>)` or `<choice> @zarban` to make a selection!]'
+;     ]
+; __zarban_fmt_chapter(with_error) + [
+;         '', '[Use `zarban(<choice>)` or `<choice> @zarban` to make a selection!]'
+;     ]
+; __zarban_fmt_chapter(with_error)
+; with_error
+  REF VAR_lumberjack_arbitrary_penguin
+  CALL #F127EB0C495160E9 1
+; [
+;         '', '[Use `zarban(<choice>)` or `<choice> @zarban` to make a selection!]'
+;     ]
+; '[Use `zarban(<choice>)` or `<choice> @zarban` to make a selection!]'
+  PSHC `[Use `zarban(<choice>)` or `<choice> @zarban` to make a selection!]`
+; ''
+  PSHC ``
+  MKAR 00000002
+  ADD
+; lines
+  REF VAR_marmalade_arbitrary_salamander
+  WREF
+  POP
+; title = data['chapters'][save['chapter']]['title']
+; data['chapters'][save['chapter']]['title']
+; data
+  REF VAR_cabbage_arbitrary_hedgehog
+; 'chapters'
+  PSHC `chapters`
+  IDEX
+; save['chapter']
+; save
+  REF VAR_lumberjack_noodle
+; 'chapter'
+  PSHC `chapter`
+  IDEX
+  IDEX
+; 'title'
+  PSHC `title`
+  IDEX
+; title
+  REF VAR_umbrella_arbitrary_bananas
+  WREF
+  POP
+; __zarban_cool_box(title, lines)
+; title
+  REF VAR_umbrella_arbitrary_bananas
+; lines
+  REF VAR_marmalade_arbitrary_salamander
+  CALL #B045C9E059F491CF 2
+  RREF
+  RET
+; fn quarantine_hedgehog
+  POOL 00000006
+; {
+;     data = global('__zarban_storydata')
+;     assign_global('__zarban_savedata', {
+;         "choices": [],
+;         "status": data["status"],
+;         "inventory": data["inventory"]
+;     })
+; 
+;     __zarban_goto_chapter("intro_cave1")
+; }
+; data = global('__zarban_storydata')
+; global('__zarban_storydata')
+; '__zarban_storydata'
+  PSHC `__zarban_storydata`
+  CALL #90B4D2FF6ACB88AF 1
+; data
+  REF VAR_cabbage_arbitrary_hedgehog
+  WREF
+  POP
+; assign_global('__zarban_savedata', {
+;         "choices": [],
+;         "status": data["status"],
+;         "inventory": data["inventory"]
+;     })
+; '__zarban_savedata'
+  PSHC `__zarban_savedata`
+; {
+;         "choices": [],
+;         "status": data["status"],
+;         "inventory": data["inventory"]
+;     }
+; []
+  MKAR 00000000
+; "choices"
+  PSHC `choices`
+; data["status"]
+; data
+  REF VAR_cabbage_arbitrary_hedgehog
+; "status"
+  PSHC `status`
+  IDEX
+; "status"
+  PSHC `status`
+; data["inventory"]
+; data
+  REF VAR_cabbage_arbitrary_hedgehog
+; "inventory"
+  PSHC `inventory`
+  IDEX
+; "inventory"
+  PSHC `inventory`
+  MKOB 00000003
+  CALL #818B07AC0019FEA7 2
+  POP
+; __zarban_goto_chapter("intro_cave1")
+; "intro_cave1"
+  PSHC `intro_cave1`
+  CALL #2CF5C46E9FA0223 1
+  RREF
+  RET
+; fn dolphin_jellybean
+; {
+;     __zarban_savedata_reset()
+;     __zarban_draw_ui(false)
+; }
+; __zarban_savedata_reset()
+  CALL #CE0302A22022D87D 0
+  POP
+; __zarban_draw_ui(false)
+; false
+  PUSH false
+  CALL #73A46F29C6A95BE 1
+  RREF
+  RET
+; fn rhubarb_lumberjack
+  POOL 00000005
+; {
+;     save = global('__zarban_savedata')
+;     choices = __zarban_get_choices()
+;     if choice < 1 || choice > len(choices) {
+;         // Selection was out of range
+;         return __zarban_draw_ui(true)
+;     } else {
+;         choice = choices[choice-1]
+; 
+;         // Go through possible results of the choice
+;         for result in choice['results'] {
+;             if result is string {
+;                 // No conditions, just go to the next chapter
+;                 __zarban_goto_chapter(result)
+;                 return __zarban_draw_ui(false)
+;             } else {
+;                 // Check conditions
+;                 if (for condition in result['conditions'] do !__zarban_check_condition(condition)).any() {
+;                     continue
+;                 } else {
+;                     __zarban_goto_chapter(result['target'])
+;                     return __zarban_draw_ui(false)
+;                 }
+;             }
+;         }
+;         
+;         // fallback
+;         __zarban_draw_ui(true)
+;     }
+; }
+; save = global('__zarban_savedata')
+; global('__zarban_savedata')
+; '__zarban_savedata'
+  PSHC `__zarban_savedata`
+  CALL #90B4D2FF6ACB88AF 1
+; save
+  REF VAR_lumberjack_noodle
+  WREF
+  POP
+; choices = __zarban_get_choices()
+; __zarban_get_choices()
+  CALL #ED222DA408C41CAD 0
+; choices
+  REF VAR_salamander_arbitrary_quarantine
+  WREF
+  POP
+; if choice < 1 || choice > len(choices) {
+;         // Selection was out of range
+;         return __zarban_draw_ui(true)
+;     } else {
+;         choice = choices[choice-1]
+; 
+;         // Go through possible results of the choice
+;         for result in choice['results'] {
+;             if result is string {
+;                 // No conditions, just go to the next chapter
+;                 __zarban_goto_chapter(result)
+;                 return __zarban_draw_ui(false)
+;             } else {
+;                 // Check conditions
+;                 if (for condition in result['conditions'] do !__zarban_check_condition(condition)).any() {
+;                     continue
+;                 } else {
+;                     __zarban_goto_chapter(result['target'])
+;                     return __zarban_draw_ui(false)
+;                 }
+;             }
+;         }
+;         
+;         // fallback
+;         __zarban_draw_ui(true)
+;     }
+; choice < 1 || choice > len(choices)
+; choice < 1
+; choice
+  REF VAR_grapefruit_arbitrary_dolphin
+; 1
+  PSHC 1
+  LT
+; choice > len(choices)
+; choice
+  REF VAR_grapefruit_arbitrary_dolphin
+; len(choices)
+; choices
+  REF VAR_salamander_arbitrary_quarantine
+  CALL #FC1400FACF92C78 1
+  GT
+  LOR
+  JMPF JUMP_arbitrary_arbitrary
+; {
+;         // Selection was out of range
+;         return __zarban_draw_ui(true)
+;     }
+; return __zarban_draw_ui(true)
+; __zarban_draw_ui(true)
+; true
+  PUSH true
+  CALL #73A46F29C6A95BE 1
+  RET
+  RREF
+  JMP JUMP_grapefruit_bananas
+JUMP_arbitrary_arbitrary:
+; {
+;         choice = choices[choice-1]
+; 
+;         // Go through possible results of the choice
+;         for result in choice['results'] {
+;             if result is string {
+;                 // No conditions, just go to the next chapter
+;                 __zarban_goto_chapter(result)
+;                 return __zarban_draw_ui(false)
+;             } else {
+;                 // Check conditions
+;                 if (for condition in result['conditions'] do !__zarban_check_condition(condition)).any() {
+;                     continue
+;                 } else {
+;                     __zarban_goto_chapter(result['target'])
+;                     return __zarban_draw_ui(false)
+;                 }
+;             }
+;         }
+;         
+;         // fallback
+;         __zarban_draw_ui(true)
+;     }
+; choice = choices[choice-1]
+; choices[choice-1]
+; choices
+  REF VAR_salamander_arbitrary_quarantine
+; choice-1
+; choice
+  REF VAR_grapefruit_arbitrary_dolphin
+; 1
+  PSUB 1
+  IDEX
+; choice
+  REF VAR_grapefruit_arbitrary_dolphin
+  WREF
+  POP
+; for result in choice['results'] {
+;             if result is string {
+;                 // No conditions, just go to the next chapter
+;                 __zarban_goto_chapter(result)
+;                 return __zarban_draw_ui(false)
+;             } else {
+;                 // Check conditions
+;                 if (for condition in result['conditions'] do !__zarban_check_condition(condition)).any() {
+;                     continue
+;                 } else {
+;                     __zarban_goto_chapter(result['target'])
+;                     return __zarban_draw_ui(false)
+;                 }
+;             }
+;         }
+  SCI
+  MKAR 00000000
+; choice['results']
+; choice
+  REF VAR_grapefruit_arbitrary_dolphin
+; 'results'
+  PSHC `results`
+  IDEX
+  SWP
+JUMP_rhubarb_lumberjack:
+  SWP
+  DJNE JUMP_octopus_cabbage
+  JMP JUMP_bananas_dolphin
+JUMP_octopus_cabbage:
+  NEXT
+  REF VAR_grapefruit_bananas_pointbreak
+  WREF
+  POP
+  SWP
+  SCI
+; {
+;             if result is string {
+;                 // No conditions, just go to the next chapter
+;                 __zarban_goto_chapter(result)
+;                 return __zarban_draw_ui(false)
+;             } else {
+;                 // Check conditions
+;                 if (for condition in result['conditions'] do !__zarban_check_condition(condition)).any() {
+;                     continue
+;                 } else {
+;                     __zarban_goto_chapter(result['target'])
+;                     return __zarban_draw_ui(false)
+;                 }
+;             }
+;         }
+; if result is string {
+;                 // No conditions, just go to the next chapter
+;                 __zarban_goto_chapter(result)
+;                 return __zarban_draw_ui(false)
+;             } else {
+;                 // Check conditions
+;                 if (for condition in result['conditions'] do !__zarban_check_condition(condition)).any() {
+;                     continue
+;                 } else {
+;                     __zarban_goto_chapter(result['target'])
+;                     return __zarban_draw_ui(false)
+;                 }
+;             }
+; result is string
+; result
+  REF VAR_grapefruit_bananas_pointbreak
+  ISA String
+  JMPF JUMP_hedgehog_pointbreak
+; {
+;                 // No conditions, just go to the next chapter
+;                 __zarban_goto_chapter(result)
+;                 return __zarban_draw_ui(false)
+;             }
+; __zarban_goto_chapter(result)
+; result
+  REF VAR_grapefruit_bananas_pointbreak
+  CALL #2CF5C46E9FA0223 1
+  POP
+; return __zarban_draw_ui(false)
+; __zarban_draw_ui(false)
+; false
+  PUSH false
+  CALL #73A46F29C6A95BE 1
+  RET
+  RREF
+  JMP JUMP_penguin_alabaster
+JUMP_hedgehog_pointbreak:
+; {
+;                 // Check conditions
+;                 if (for condition in result['conditions'] do !__zarban_check_condition(condition)).any() {
+;                     continue
+;                 } else {
+;                     __zarban_goto_chapter(result['target'])
+;                     return __zarban_draw_ui(false)
+;                 }
+;             }
+; if (for condition in result['conditions'] do !__zarban_check_condition(condition)).any() {
+;                     continue
+;                 } else {
+;                     __zarban_goto_chapter(result['target'])
+;                     return __zarban_draw_ui(false)
+;                 }
+; for condition in result['conditions'] do !__zarban_check_condition(condition)).any()
+; for condition in result['conditions'] do !__zarban_check_condition(condition)
+  SCI
+  MKAR 00000000
+; result['conditions']
+; result
+  REF VAR_grapefruit_bananas_pointbreak
+; 'conditions'
+  PSHC `conditions`
+  IDEX
+  SWP
+JUMP_quarantine_hedgehog:
+  SWP
+  DJNE JUMP_cabbage_umbrella
+  JMP JUMP_jellybean_grapefruit
+JUMP_cabbage_umbrella:
+  NEXT
+  REF VAR_salamander_octopus
+  WREF
+  POP
+  SWP
+  SCI
+; !__zarban_check_condition(condition)
+; __zarban_check_condition(condition)
+; condition
+  REF VAR_salamander_octopus
+  CALL #91B1045A644D718B 1
+  LNOT
+  SCO
+  PSAR
+  JMP JUMP_quarantine_hedgehog
+  POP
+  JMP JUMP_dolphin_jellybean
+  PSAR
+JUMP_dolphin_jellybean:
+  SWP
+JUMP_jellybean_grapefruit:
+  POP
+  SCO
+  CALM any 1
+  JMPF JUMP_kangaroo_kangaroo
+; {
+;                     continue
+;                 }
+; continue
+  PUSH nil
+  SCO
+  POP
+  JMP JUMP_rhubarb_lumberjack
+  RREF
+  JMP JUMP_pointbreak_marmalade
+JUMP_kangaroo_kangaroo:
+; {
+;                     __zarban_goto_chapter(result['target'])
+;                     return __zarban_draw_ui(false)
+;                 }
+; __zarban_goto_chapter(result['target'])
+; result['target']
+; result
+  REF VAR_grapefruit_bananas_pointbreak
+; 'target'
+  PSHC `target`
+  IDEX
+  CALL #2CF5C46E9FA0223 1
+  POP
+; return __zarban_draw_ui(false)
+; __zarban_draw_ui(false)
+; false
+  PUSH false
+  CALL #73A46F29C6A95BE 1
+  RET
+  RREF
+JUMP_pointbreak_marmalade:
+  RREF
+JUMP_penguin_alabaster:
+  RREF
+  SCO
+  PSAR
+  JMP JUMP_rhubarb_lumberjack
+  POP
+  JMP JUMP_lumberjack_noodle
+  PSAR
+JUMP_lumberjack_noodle:
+  SWP
+JUMP_bananas_dolphin:
+  POP
+  SCO
+  POP
+; __zarban_draw_ui(true)
+; true
+  PUSH true
+  CALL #73A46F29C6A95BE 1
+  RREF
+JUMP_grapefruit_bananas:
+  RREF
+  RET
+; fn rhubarb_lumberjack
+; zarban(choice)
+; choice
+  REF VAR_grapefruit_arbitrary_dolphin
+  CALL #53AE7F092855252B 1
+  RET
+; fn pointbreak_marmalade
+  POOL 00000005
+; {
+;     play_zarban()
+;     1@zarban; 1@zarban; 1@zarban; 2@zarban; 1@zarban // Enter the bar
+;     1@zarban; 1@zarban; 2@zarban; 2@zarban; 2@zarban; 2@zarban // Send dave home
+;     1@zarban; 1@zarban; 1@zarban; 2@zarban; 1@zarban; 3@zarban
+; }
+; play_zarban()
+  CALL #DF855A8BDA4EE639 0
+  POP
+; 1@zarban
+; 1
+  PSHC 1
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 1@zarban
+; 1
+  PSHC 1
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 1@zarban
+; 1
+  PSHC 1
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 2@zarban
+; 2
+  PSHC 2
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 1@zarban
+; 1
+  PSHC 1
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 1@zarban
+; 1
+  PSHC 1
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 1@zarban
+; 1
+  PSHC 1
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 2@zarban
+; 2
+  PSHC 2
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 2@zarban
+; 2
+  PSHC 2
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 2@zarban
+; 2
+  PSHC 2
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 2@zarban
+; 2
+  PSHC 2
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 1@zarban
+; 1
+  PSHC 1
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 1@zarban
+; 1
+  PSHC 1
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 1@zarban
+; 1
+  PSHC 1
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 2@zarban
+; 2
+  PSHC 2
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 1@zarban
+; 1
+  PSHC 1
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  POP
+; 3@zarban
+; 3
+  PSHC 3
+  DUP
+  CALL #53AE7F092855252B 1
+  DFMT
+  SWP
+  PSHC `__decorated`
+  SWP
+  PSHC `value`
+  MKOB 00000002
+  RREF
+  RET